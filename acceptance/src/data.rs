@@ -1,5 +1,7 @@
 use std::{path::Path, sync::Arc};
 
+use url::Url;
+
 use delta_kernel::arrow::array::{Array, RecordBatch};
 use delta_kernel::arrow::compute::{
     concat_batches, filter_record_batch, lexsort_to_indices, take, SortColumn,
@@ -18,8 +20,14 @@ use object_store::{local::LocalFileSystem, ObjectStore};
 use crate::{TestCaseInfo, TestResult};
 
 pub async fn read_golden(path: &Path, _version: Option<&str>) -> DeltaResult<RecordBatch> {
-    let expected_root = path.join("expected").join("latest").join("table_content");
-    let store = Arc::new(LocalFileSystem::new_with_prefix(&expected_root)?);
+    read_latest_content(&path.join("expected")).await
+}
+
+/// Reads the expected contents of the latest version of a table from the golden data at
+/// `expected_root`.
+async fn read_latest_content(expected_root: &Path) -> DeltaResult<RecordBatch> {
+    let content_root = expected_root.join("latest").join("table_content");
+    let store = Arc::new(LocalFileSystem::new_with_prefix(&content_root)?);
     let files: Vec<_> = store.list(None).try_collect().await?;
     let mut batches = vec![];
     let mut schema = None;
@@ -114,8 +122,17 @@ pub async fn assert_scan_metadata(
     engine: Arc<dyn Engine>,
     test_case: &TestCaseInfo,
 ) -> TestResult<()> {
-    let table_root = test_case.table_root()?;
-    let snapshot = Snapshot::builder_for(table_root).build(engine.as_ref())?;
+    verify_data(engine, &test_case.table_root()?, &test_case.expected_root()).await
+}
+
+/// Asserts that a full scan of the latest version of the table at `table_root` with `engine`
+/// returns the rows recorded in the golden data at `expected_root`, in any order.
+pub async fn verify_data(
+    engine: Arc<dyn Engine>,
+    table_root: &Url,
+    expected_root: &Path,
+) -> TestResult<()> {
+    let snapshot = Snapshot::builder_for(table_root.clone()).build(engine.as_ref())?;
     let scan = snapshot.scan_builder().build()?;
     let mut schema = None;
     let batches: Vec<RecordBatch> = scan
@@ -141,7 +158,7 @@ pub async fn assert_scan_metadata(
         .try_collect()?;
     let all_data = concat_batches(&schema.unwrap(), batches.iter()).map_err(Error::from)?;
     let all_data = sort_record_batch(all_data)?;
-    let golden = read_golden(test_case.root_dir(), None).await?;
+    let golden = read_latest_content(expected_root).await?;
     let golden = sort_record_batch(golden)?;
 
     assert_columns_match(all_data.columns(), golden.columns());
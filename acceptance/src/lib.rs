@@ -1,5 +1,43 @@
 //! Helpers to validate Engine implementations
+//!
+//! These are the helpers the kernel's own acceptance tests use to check that the kernel, with the
+//! default engine, reads the tables of the [Delta Acceptance Testing (DAT)] suite correctly.
+//! Implementors of an [`Engine`] can use [`verify_table`] to run the same checks against their own
+//! handlers, for the DAT tables or any other golden table.
+//!
+//! [Delta Acceptance Testing (DAT)]: https://github.com/delta-incubator/dat
+
+use std::path::Path;
+use std::sync::Arc;
+
+use delta_kernel::Engine;
+use url::Url;
 
 pub mod data;
 pub mod meta;
 pub use meta::*;
+
+/// Verifies that `engine` reads the table at `table_root` as recorded in the golden data at
+/// `expected`, by asserting that:
+/// - the version, protocol and table properties of the latest version of the table, and of every
+///   other version the golden data lists, match the golden data (see [`verify_metadata`])
+/// - a full scan of the latest version returns the rows of the golden data (see
+///   [`data::verify_data`])
+///
+/// The golden data must be laid out like the `expected` directory of a DAT test case:
+/// `latest/table_version_metadata.json` and `latest/table_content/*.parquet` describe the latest
+/// version, and `<dir>/table_version_metadata.json` any other version.
+///
+/// # Panics
+///
+/// Panics if the table read by `engine` doesn't match the golden data. Returns an error if the
+/// golden data is invalid, or if the kernel fails to read the table.
+pub async fn verify_table(
+    engine: Arc<dyn Engine>,
+    table_root: &Url,
+    expected: impl AsRef<Path>,
+) -> TestResult<()> {
+    let expected = expected.as_ref();
+    verify_metadata(engine.as_ref(), table_root, expected).await?;
+    data::verify_data(engine, table_root, expected).await
+}
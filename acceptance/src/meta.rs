@@ -45,76 +45,90 @@ impl TestCaseInfo {
         &self.root_dir
     }
 
-    async fn versions(&self) -> TestResult<(TableVersionMetaData, Vec<TableVersionMetaData>)> {
-        let expected_root = self.root_dir.join("expected");
-        let store = LocalFileSystem::new_with_prefix(&expected_root).unwrap();
-
-        let files = store.list(None).try_collect::<Vec<_>>().await.unwrap();
-
-        let raw_cases = files.into_iter().filter(|meta| {
-            meta.location.filename() == Some("table_version_metadata.json")
-                && !meta
-                    .location
-                    .prefix_matches(&object_store::path::Path::from("latest"))
-        });
-
-        let mut cases = Vec::new();
-        for case in raw_cases {
-            let case_file = expected_root.join(case.location.as_ref());
-            let file = File::open(case_file).map_err(|_| AssertionError::InvalidTestCase)?;
-            let info: TableVersionMetaData =
-                serde_json::from_reader(file).map_err(|_| AssertionError::InvalidTestCase)?;
-            cases.push(info);
-        }
-
-        let case_file = expected_root.join("latest/table_version_metadata.json");
-        let file = File::open(case_file).map_err(|_| AssertionError::InvalidTestCase)?;
-        let latest: TableVersionMetaData =
-            serde_json::from_reader(file).map_err(|_| AssertionError::InvalidTestCase)?;
+    /// Root path of the golden data this test case's Delta table is verified against.
+    pub fn expected_root(&self) -> PathBuf {
+        self.root_dir.join("expected")
+    }
 
-        Ok((latest, cases))
+    pub async fn assert_metadata(&self, engine: Arc<dyn Engine>) -> TestResult<()> {
+        verify_metadata(engine.as_ref(), &self.table_root()?, &self.expected_root()).await
     }
+}
 
-    fn assert_snapshot_meta(
-        &self,
-        case: &TableVersionMetaData,
-        snapshot: &Snapshot,
-    ) -> TestResult<()> {
-        assert_eq!(snapshot.version(), case.version);
-
-        // assert correct metadata is read
-        let metadata = snapshot.metadata();
-        let protocol = snapshot.protocol();
-        let tvm = TableVersionMetaData {
-            version: snapshot.version(),
-            properties: metadata
-                .configuration()
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect(),
-            min_reader_version: protocol.min_reader_version() as u32,
-            min_writer_version: protocol.min_writer_version() as u32,
-        };
-        assert_eq!(&tvm, case);
-        Ok(())
+/// Reads the expected metadata of the latest version, and of every other version the golden data
+/// at `expected_root` lists.
+async fn expected_versions(
+    expected_root: &Path,
+) -> TestResult<(TableVersionMetaData, Vec<TableVersionMetaData>)> {
+    let store = LocalFileSystem::new_with_prefix(expected_root).unwrap();
+
+    let files = store.list(None).try_collect::<Vec<_>>().await.unwrap();
+
+    let raw_cases = files.into_iter().filter(|meta| {
+        meta.location.filename() == Some("table_version_metadata.json")
+            && !meta
+                .location
+                .prefix_matches(&object_store::path::Path::from("latest"))
+    });
+
+    let mut cases = Vec::new();
+    for case in raw_cases {
+        let case_file = expected_root.join(case.location.as_ref());
+        let file = File::open(case_file).map_err(|_| AssertionError::InvalidTestCase)?;
+        let info: TableVersionMetaData =
+            serde_json::from_reader(file).map_err(|_| AssertionError::InvalidTestCase)?;
+        cases.push(info);
     }
 
-    pub async fn assert_metadata(&self, engine: Arc<dyn Engine>) -> TestResult<()> {
-        let engine = engine.as_ref();
-        let (latest, versions) = self.versions().await?;
+    let case_file = expected_root.join("latest/table_version_metadata.json");
+    let file = File::open(case_file).map_err(|_| AssertionError::InvalidTestCase)?;
+    let latest: TableVersionMetaData =
+        serde_json::from_reader(file).map_err(|_| AssertionError::InvalidTestCase)?;
 
-        let snapshot = Snapshot::builder_for(self.table_root()?).build(engine)?;
-        self.assert_snapshot_meta(&latest, &snapshot)?;
+    Ok((latest, cases))
+}
 
-        for table_version in versions {
-            let snapshot = Snapshot::builder_for(self.table_root()?)
-                .at_version(table_version.version)
-                .build(engine)?;
-            self.assert_snapshot_meta(&table_version, &snapshot)?;
-        }
+fn assert_snapshot_meta(case: &TableVersionMetaData, snapshot: &Snapshot) -> TestResult<()> {
+    assert_eq!(snapshot.version(), case.version);
+
+    // assert correct metadata is read
+    let metadata = snapshot.metadata();
+    let protocol = snapshot.protocol();
+    let tvm = TableVersionMetaData {
+        version: snapshot.version(),
+        properties: metadata
+            .configuration()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+        min_reader_version: protocol.min_reader_version() as u32,
+        min_writer_version: protocol.min_writer_version() as u32,
+    };
+    assert_eq!(&tvm, case);
+    Ok(())
+}
 
-        Ok(())
+/// Asserts that `engine` reads the version, protocol and table properties of the table at
+/// `table_root` as recorded in the golden data at `expected_root`. This is checked for the latest
+/// version of the table, and for every other version the golden data lists.
+pub async fn verify_metadata(
+    engine: &dyn Engine,
+    table_root: &Url,
+    expected_root: &Path,
+) -> TestResult<()> {
+    let (latest, versions) = expected_versions(expected_root).await?;
+
+    let snapshot = Snapshot::builder_for(table_root.clone()).build(engine)?;
+    assert_snapshot_meta(&latest, &snapshot)?;
+
+    for table_version in versions {
+        let snapshot = Snapshot::builder_for(table_root.clone())
+            .at_version(table_version.version)
+            .build(engine)?;
+        assert_snapshot_meta(&table_version, &snapshot)?;
     }
+
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
@@ -145,7 +159,7 @@ mod tests {
     async fn test_read_test_case() {
         let path = PathBuf::from("./tests/dat/out/reader_tests/generated/with_schema_change");
         let case = read_dat_case(path).unwrap();
-        let versions = case.versions().await.unwrap();
+        let versions = expected_versions(&case.expected_root()).await.unwrap();
         println!("{versions:?}")
     }
 }
@@ -36,8 +36,7 @@ fn reader_test(path: &Path) -> datatest_stable::Result<()> {
                 .unwrap(),
             );
 
-            case.assert_metadata(engine.clone()).await.unwrap();
-            acceptance::data::assert_scan_metadata(engine.clone(), &case)
+            acceptance::verify_table(engine, &table_root, case.expected_root())
                 .await
                 .unwrap();
         });
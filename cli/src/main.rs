@@ -0,0 +1,423 @@
+//! `delta-kernel`: a command line tool for inspecting Delta tables, built on the `delta_kernel`
+//! crate (with its `internal-api` feature, for `try_parse_uri`) and its default engine.
+use std::collections::HashMap;
+use std::process::ExitCode;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat};
+use clap::{Args, Parser, Subcommand};
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use delta_kernel::expressions::{column_name, ColumnName};
+use delta_kernel::scan::state::{DvInfo, Stats};
+use delta_kernel::schema::{DataType, StructField, StructType};
+use delta_kernel::{
+    DeltaResult, Engine, Error, ExpressionRef, FileMeta, Predicate, Snapshot, Version,
+};
+use url::Url;
+
+#[derive(Parser)]
+#[command(name = "delta-kernel", author, version, about, long_about = None)]
+#[command(propagate_version = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+
+    #[command(flatten)]
+    location_args: LocationArgs,
+}
+
+#[derive(Args)]
+struct LocationArgs {
+    /// Path to the table
+    path: String,
+
+    /// Region to specify to the cloud access store (only applies to S3)
+    #[arg(long)]
+    region: Option<String>,
+
+    /// Specify that the table is "public" (i.e. no cloud credentials are needed). This is required
+    /// for things like s3 public buckets, otherwise the kernel will try and authenticate by talking
+    /// to the aws metadata server, which will fail unless you're on an ec2 instance.
+    #[arg(long)]
+    public: bool,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Show the table's schema
+    Schema,
+    /// Show the commits of the table, newest first
+    History {
+        /// Only show the LIMIT most recent commits
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+    /// Show the data files of the table
+    Files {
+        /// Only show the files that may contain rows matching this SQL predicate, e.g. "id > 10"
+        #[arg(short, long)]
+        predicate: Option<String>,
+    },
+    /// Show the table's protocol versions and features
+    Protocol,
+    /// Show where a checkpoint of the latest version would be written
+    Checkpoint {
+        /// Write the checkpoint
+        #[arg(long)]
+        write: bool,
+    },
+    /// Show the files that are no longer referenced by the table, which VACUUM would delete
+    Vacuum {
+        /// Delete the files
+        #[arg(long)]
+        delete: bool,
+
+        /// Override the table's deleted file retention duration, in hours
+        #[arg(long)]
+        retention_hours: Option<u64>,
+
+        /// Allow a retention duration shorter than the table's, which can delete files that
+        /// readers of older table versions or running transactions still need
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+    match try_main() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Get an engine configured to read the table at `url` and `LocationArgs`
+fn get_engine(
+    url: &Url,
+    args: &LocationArgs,
+) -> DeltaResult<DefaultEngine<TokioBackgroundExecutor>> {
+    let mut options = if let Some(ref region) = args.region {
+        HashMap::from([("region", region.clone())])
+    } else {
+        HashMap::new()
+    };
+    if args.public {
+        options.insert("skip_signature", "true".to_string());
+    }
+    DefaultEngine::try_new(url, options, Arc::new(TokioBackgroundExecutor::new()))
+}
+
+fn try_main() -> DeltaResult<()> {
+    let cli = Cli::parse();
+
+    let url = delta_kernel::try_parse_uri(&cli.location_args.path)?;
+    let engine = get_engine(&url, &cli.location_args)?;
+    let snapshot = Snapshot::builder_for(url).build(&engine)?;
+
+    match cli.command {
+        Commands::Schema => {
+            for field in snapshot.schema().fields() {
+                print_field(field, 0);
+            }
+        }
+        Commands::History { limit } => {
+            let commits = read_history(&engine, snapshot.table_root(), snapshot.version(), limit)?;
+            for commit in &commits {
+                let timestamp = DateTime::from_timestamp_millis(commit.timestamp)
+                    .map(|ts| ts.to_rfc3339_opts(SecondsFormat::Millis, true))
+                    .unwrap_or_else(|| commit.timestamp.to_string());
+                let operation = commit.operation.as_deref().unwrap_or("[unknown]");
+                println!("{}\t{timestamp}\t{operation}", commit.version);
+            }
+        }
+        Commands::Files { predicate } => {
+            let predicate = predicate
+                .map(|sql| Predicate::parse_sql_with_schema(&sql, &snapshot.schema()))
+                .transpose()?
+                .map(Arc::new);
+            let scan = snapshot
+                .scan_builder()
+                .with_predicate(predicate)
+                .with_stats(true)
+                .build()?;
+            for scan_metadata in scan.scan_metadata(&engine)? {
+                scan_metadata?.visit_scan_files((), print_scan_file)?;
+            }
+        }
+        Commands::Protocol => {
+            let protocol = snapshot.protocol();
+            println!("Min reader version:\t{}", protocol.min_reader_version());
+            println!("Min writer version:\t{}", protocol.min_writer_version());
+            let features = |names: Option<Vec<String>>| {
+                names.map_or_else(|| "[none]".to_string(), |names| names.join(", "))
+            };
+            println!(
+                "Reader features:\t{}",
+                features(protocol.reader_feature_names())
+            );
+            println!(
+                "Writer features:\t{}",
+                features(protocol.writer_feature_names())
+            );
+        }
+        Commands::Checkpoint { write } => {
+            let version = snapshot.version();
+            let writer = snapshot.checkpoint()?;
+            if write {
+                let file = engine.write_checkpoint(writer)?;
+                println!(
+                    "Wrote checkpoint of version {version} to {} ({} bytes)",
+                    file.location, file.size
+                );
+            } else {
+                println!(
+                    "Checkpoint of version {version} would be written to {}",
+                    writer.checkpoint_path()?
+                );
+            }
+        }
+        Commands::Vacuum {
+            delete,
+            retention_hours,
+            force,
+        } => {
+            let table_retention = snapshot
+                .table_properties()
+                .deleted_file_retention_duration_or_default();
+            let mut vacuum = snapshot.vacuum();
+            if let Some(hours) = retention_hours {
+                let retention = vacuum_retention(hours, table_retention, force)?;
+                vacuum = vacuum.with_retention_duration(retention);
+            }
+            let plan = vacuum.plan(&engine)?;
+            for file in plan.files() {
+                println!("{}", file.location);
+            }
+            let (count, size) = (plan.files().len(), plan.size_in_bytes());
+            if delete {
+                plan.execute(&engine)?;
+                println!("Deleted {count} files ({size} bytes)");
+            } else {
+                println!("Would delete {count} files ({size} bytes). Pass --delete to delete them");
+            }
+        }
+    };
+    Ok(())
+}
+
+/// The retention duration of `hours` to vacuum with, unless it is shorter than the table's
+/// `table_retention` and the vacuum isn't forced.
+fn vacuum_retention(hours: u64, table_retention: Duration, force: bool) -> DeltaResult<Duration> {
+    let retention = Duration::from_secs(hours.saturating_mul(60 * 60));
+    if retention < table_retention && !force {
+        return Err(Error::generic(format!(
+            "The retention duration of {hours} hours is shorter than the table's deleted file \
+             retention duration of {} hours, which can delete files that are still needed. Pass \
+             --force to vacuum anyway",
+            table_retention.as_secs() / (60 * 60)
+        )));
+    }
+    Ok(retention)
+}
+
+/// Print `field` as `name: type`, expanding the fields of (top-level) struct columns.
+fn print_field(field: &StructField, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let nullable = if field.is_nullable() { "" } else { " not null" };
+    match field.data_type() {
+        DataType::Struct(st) => {
+            println!("{indent}{}: struct{nullable}", field.name());
+            for child in st.fields() {
+                print_field(child, depth + 1);
+            }
+        }
+        data_type => println!("{indent}{}: {data_type}{nullable}", field.name()),
+    }
+}
+
+// This is the callback that will be called for each valid scan row
+fn print_scan_file(
+    _: &mut (),
+    path: &str,
+    size: i64,
+    stats: Option<Stats>,
+    dv_info: DvInfo,
+    _transform: Option<ExpressionRef>,
+    partition_values: HashMap<String, String>,
+) {
+    let num_records = stats.map_or_else(|| "[unknown]".to_string(), |s| s.num_records.to_string());
+    println!(
+        "{path}\n  \
+              Size (bytes):\t{size}\n  \
+              Num Records:\t{num_records}\n  \
+              Has DV?:\t{}\n  \
+              Part Vals:\t{partition_values:?}",
+        dv_info.has_vector()
+    );
+}
+
+/// A commit of the table, as shown by `history`.
+#[derive(Debug, PartialEq)]
+struct Commit {
+    version: Version,
+    /// The commit timestamp in milliseconds since the unix epoch. This is the `commitInfo`
+    /// timestamp if the commit has one, and the modification time of the commit file otherwise.
+    timestamp: i64,
+    operation: Option<String>,
+}
+
+/// Parses the version of a published commit file name, i.e. `<20 digit version>.json`.
+fn commit_version(file_name: &str) -> Option<Version> {
+    let version = file_name.strip_suffix(".json")?;
+    if version.len() != 20 || !version.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    version.parse().ok()
+}
+
+/// Reads the commits of the table at `table_root` up to and including `version`, newest first. If a
+/// `limit` is given, only the `limit` newest commit files are read.
+fn read_history(
+    engine: &dyn Engine,
+    table_root: &Url,
+    version: Version,
+    limit: Option<usize>,
+) -> DeltaResult<Vec<Commit>> {
+    let log_root = table_root
+        .join("_delta_log/")
+        .map_err(|e| Error::generic(format!("Invalid table root {table_root}: {e}")))?;
+    let mut commit_files: Vec<(Version, FileMeta)> = vec![];
+    for file in engine.storage_handler().list_from(&log_root)? {
+        let file = file?;
+        let file_name = file
+            .location
+            .path_segments()
+            .and_then(|mut s| s.next_back());
+        if let Some(commit_version) = file_name.and_then(commit_version) {
+            if commit_version <= version {
+                commit_files.push((commit_version, file));
+            }
+        }
+    }
+    commit_files.sort_by_key(|(version, _)| std::cmp::Reverse(*version));
+    if let Some(limit) = limit {
+        commit_files.truncate(limit);
+    }
+
+    let schema = Arc::new(StructType::try_new([StructField::nullable(
+        "commitInfo",
+        StructType::try_new([
+            StructField::nullable("timestamp", DataType::LONG),
+            StructField::nullable("operation", DataType::STRING),
+        ])?,
+    )])?);
+    let mut commits = Vec::with_capacity(commit_files.len());
+    for (version, file) in commit_files {
+        let mut visitor = CommitInfoVisitor::default();
+        let data = engine.json_handler().read_json_files(
+            std::slice::from_ref(&file),
+            schema.clone(),
+            None,
+        )?;
+        for batch in data {
+            visitor.visit_rows_of(batch?.as_ref())?;
+        }
+        commits.push(Commit {
+            version,
+            timestamp: visitor.timestamp.unwrap_or(file.last_modified),
+            operation: visitor.operation,
+        });
+    }
+    Ok(commits)
+}
+
+/// Extracts the timestamp and operation of the `commitInfo` action of a commit.
+#[derive(Default)]
+struct CommitInfoVisitor {
+    timestamp: Option<i64>,
+    operation: Option<String>,
+}
+
+impl RowVisitor for CommitInfoVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<(Vec<ColumnName>, Vec<DataType>)> = LazyLock::new(|| {
+            (
+                vec![
+                    column_name!("commitInfo.timestamp"),
+                    column_name!("commitInfo.operation"),
+                ],
+                vec![DataType::LONG, DataType::STRING],
+            )
+        });
+        (&NAMES_AND_TYPES.0, &NAMES_AND_TYPES.1)
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        for i in 0..row_count {
+            let timestamp: Option<i64> = getters[0].get_opt(i, "commitInfo.timestamp")?;
+            let operation: Option<String> = getters[1].get_opt(i, "commitInfo.operation")?;
+            if timestamp.is_some() || operation.is_some() {
+                self.timestamp = timestamp;
+                self.operation = operation;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_version() {
+        assert_eq!(commit_version("00000000000000000000.json"), Some(0));
+        assert_eq!(commit_version("00000000000000000012.json"), Some(12));
+        assert_eq!(
+            commit_version("00000000000000000012.checkpoint.parquet"),
+            None
+        );
+        assert_eq!(commit_version("00000000000000000012.crc"), None);
+        assert_eq!(commit_version("0012.json"), None);
+        assert_eq!(commit_version("_last_checkpoint"), None);
+    }
+
+    #[test]
+    fn test_vacuum_retention() {
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        assert_eq!(vacuum_retention(168, week, false).unwrap(), week);
+        assert_eq!(
+            vacuum_retention(200, week, false).unwrap(),
+            Duration::from_secs(200 * 60 * 60)
+        );
+        let err = vacuum_retention(0, week, false).unwrap_err();
+        assert!(err.to_string().contains("Pass --force"), "{err}");
+        assert_eq!(vacuum_retention(0, week, true).unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_history() {
+        let path = std::fs::canonicalize("../kernel/tests/data/table-with-dv-small/").unwrap();
+        let url = Url::from_directory_path(path).unwrap();
+        let engine = DefaultEngine::try_new(
+            &url,
+            HashMap::<String, String>::new(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )
+        .unwrap();
+        let commits = read_history(&engine, &url, 1, None).unwrap();
+        let versions: Vec<_> = commits.iter().map(|c| c.version).collect();
+        assert_eq!(versions, [1, 0]);
+        assert!(commits.iter().all(|c| c.operation.is_some()));
+
+        let commits = read_history(&engine, &url, 1, Some(1)).unwrap();
+        let versions: Vec<_> = commits.iter().map(|c| c.version).collect();
+        assert_eq!(versions, [1]);
+    }
+}
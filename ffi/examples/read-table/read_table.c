@@ -335,6 +335,17 @@ int main(int argc, char* argv[])
 
   print_diag("All done reading table data\n");
 
+  ScanReport report = scan_report(scan);
+  print_diag(
+    "Scan report: %" PRIu64 " files seen, %" PRIu64 " pruned by partition, %" PRIu64
+    " pruned by stats, %" PRIu64 " planned (%" PRIu64 " bytes), planning took %" PRIu64 " ns\n",
+    report.files_seen,
+    report.files_pruned_by_partition,
+    report.files_pruned_by_stats,
+    report.files_planned,
+    report.bytes_planned,
+    report.planning_duration_ns);
+
 #ifdef PRINT_ARROW_DATA
   print_arrow_context(context.arrow_context);
   free_arrow_context(context.arrow_context);
@@ -26,12 +26,15 @@ enum OpType {
   Add,
   Minus,
   Divide,
+  Modulo,
   Multiply,
   LessThan,
   GreaterThan,
   Equal,
   Distinct,
   In,
+  Like,
+  ILike,
 };
 enum LitType {
   Integer,
@@ -193,11 +196,14 @@ DEFINE_BINOP(visit_expr_add, Add)
 DEFINE_BINOP(visit_expr_minus, Minus)
 DEFINE_BINOP(visit_expr_multiply, Multiply)
 DEFINE_BINOP(visit_expr_divide, Divide)
+DEFINE_BINOP(visit_expr_modulo, Modulo)
 DEFINE_BINOP(visit_expr_lt, LessThan)
 DEFINE_BINOP(visit_expr_gt, GreaterThan)
 DEFINE_BINOP(visit_expr_eq, Equal)
 DEFINE_BINOP(visit_expr_distinct, Distinct)
 DEFINE_BINOP(visit_expr_in, In)
+DEFINE_BINOP(visit_expr_like, Like)
+DEFINE_BINOP(visit_expr_ilike, ILike)
 #undef DEFINE_BINOP
 
 /*************************************************************
@@ -479,10 +485,13 @@ ExpressionItemList construct_expression(SharedExpression* expression) {
     .visit_eq = visit_expr_eq,
     .visit_distinct = visit_expr_distinct,
     .visit_in = visit_expr_in,
+    .visit_like = visit_expr_like,
+    .visit_ilike = visit_expr_ilike,
     .visit_add = visit_expr_add,
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
     .visit_divide = visit_expr_divide,
+    .visit_modulo = visit_expr_modulo,
     .visit_column = visit_expr_column,
     .visit_struct_expr = visit_expr_struct_expr,
     .visit_transform_expr = visit_transform_expr,
@@ -527,10 +536,13 @@ ExpressionItemList construct_predicate(SharedPredicate* predicate) {
     .visit_eq = visit_expr_eq,
     .visit_distinct = visit_expr_distinct,
     .visit_in = visit_expr_in,
+    .visit_like = visit_expr_like,
+    .visit_ilike = visit_expr_ilike,
     .visit_add = visit_expr_add,
     .visit_minus = visit_expr_minus,
     .visit_multiply = visit_expr_multiply,
     .visit_divide = visit_expr_divide,
+    .visit_modulo = visit_expr_modulo,
     .visit_column = visit_expr_column,
     .visit_struct_expr = visit_expr_struct_expr,
     .visit_opaque_pred = visit_opaque_pred,
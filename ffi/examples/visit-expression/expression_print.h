@@ -45,6 +45,10 @@ void print_tree_helper(ExpressionItem ref, int depth) {
           printf("Multiply\n");
           break;
         };
+        case Modulo: {
+          printf("Modulo\n");
+          break;
+        };
         case LessThan: {
           printf("LessThan\n");
           break;
@@ -64,6 +68,12 @@ void print_tree_helper(ExpressionItem ref, int depth) {
         case Distinct:
           printf("Distinct\n");
           break;
+        case Like:
+          printf("Like\n");
+          break;
+        case ILike:
+          printf("ILike\n");
+          break;
       }
       print_expression_item_list(op->exprs, depth + 1);
       break;
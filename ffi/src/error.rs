@@ -67,6 +67,12 @@ pub enum KernelError {
     LiteralExpressionTransformError = 40,
     CheckpointWriteError = 41,
     SchemaError = 42,
+    InvariantViolationError = 43,
+    #[cfg(feature = "default-engine-base")]
+    ObjectStoreRetriesExhaustedError = 44,
+    NotADeltaTableError = 45,
+    ChecksumMismatchError = 46,
+    CommitConflictError = 47,
 }
 
 impl From<Error> for KernelError {
@@ -86,6 +92,8 @@ impl From<Error> for KernelError {
             #[cfg(feature = "default-engine-base")]
             Error::ObjectStore(_) => KernelError::ObjectStoreError,
             #[cfg(feature = "default-engine-base")]
+            Error::ObjectStoreRetriesExhausted(_) => KernelError::ObjectStoreRetriesExhaustedError,
+            #[cfg(feature = "default-engine-base")]
             Error::ObjectStorePath(_) => KernelError::ObjectStorePathError,
             #[cfg(feature = "default-engine-base")]
             Error::Reqwest(_) => KernelError::ReqwestError,
@@ -107,6 +115,7 @@ impl From<Error> for KernelError {
             Error::ParseIntError(_) => KernelError::ParseIntError,
             Error::InvalidColumnMappingMode(_) => KernelError::InvalidColumnMappingModeError,
             Error::InvalidTableLocation(_) => KernelError::InvalidTableLocationError,
+            Error::NotADeltaTable(_) => KernelError::NotADeltaTableError,
             Error::InvalidDecimal(_) => KernelError::InvalidDecimalError,
             Error::InvalidStructData(_) => KernelError::InvalidStructDataError,
             Error::InternalError(_) => KernelError::InternalError,
@@ -114,6 +123,7 @@ impl From<Error> for KernelError {
                 source,
                 backtrace: _,
             } => Self::from(*source),
+            Error::WithContext { source, .. } => Self::from(*source),
             Error::InvalidExpressionEvaluation(_) => KernelError::InvalidExpression,
             Error::InvalidLogPath(_) => KernelError::InvalidLogPath,
             Error::FileAlreadyExists(_) => KernelError::FileAlreadyExists,
@@ -128,6 +138,9 @@ impl From<Error> for KernelError {
                 KernelError::LiteralExpressionTransformError
             }
             Error::Schema(_) => KernelError::SchemaError,
+            Error::InvariantViolation(_) => KernelError::InvariantViolationError,
+            Error::ChecksumMismatch(_) => KernelError::ChecksumMismatchError,
+            Error::CommitConflict(_) => KernelError::CommitConflictError,
             _ => KernelError::UnknownError,
         }
     }
@@ -7,7 +7,8 @@ use crate::{handle::Handle, kernel_string_slice, KernelStringSlice};
 
 use delta_kernel::expressions::{
     ArrayData, BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp,
-    ColumnName, Expression, ExpressionRef, JunctionPredicate, JunctionPredicateOp, MapData,
+    CaseExpression, CastExpression, CastOverflowPolicy, ColumnName, ElementAccessor,
+    ElementExpression, Expression, ExpressionRef, JunctionPredicate, JunctionPredicateOp, MapData,
     OpaqueExpression, OpaqueExpressionOpRef, OpaquePredicate, OpaquePredicateOpRef, Predicate,
     Scalar, StructData, Transform, UnaryExpression, UnaryExpressionOp, UnaryPredicate,
     UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
@@ -153,6 +154,14 @@ pub struct EngineExpressionVisitor {
     /// Visits the `In` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_in: VisitBinaryFn,
+    /// Visits the `Like` binary operator belonging to the list identified by `sibling_list_id`.
+    /// The operands (value and backslash-escaped pattern) will be in a _two_ item list identified
+    /// by `child_list_id`
+    pub visit_like: VisitBinaryFn,
+    /// Visits the `ILike` binary operator belonging to the list identified by `sibling_list_id`.
+    /// The operands (value and backslash-escaped pattern) will be in a _two_ item list identified
+    /// by `child_list_id`
+    pub visit_ilike: VisitBinaryFn,
     /// Visits the `Add` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_add: VisitBinaryFn,
@@ -165,9 +174,51 @@ pub struct EngineExpressionVisitor {
     /// Visits the `Divide` binary operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a _two_ item list identified by `child_list_id`
     pub visit_divide: VisitBinaryFn,
+    /// Visits the `Modulo` binary operator belonging to the list identified by `sibling_list_id`.
+    /// The operands will be in a _two_ item list identified by `child_list_id`
+    pub visit_modulo: VisitBinaryFn,
     /// Visits the `Coalesce` variadic operator belonging to the list identified by `sibling_list_id`.
     /// The operands will be in a list identified by `child_list_id`
     pub visit_coalesce: VisitVariadicFn,
+    /// Visits the `NullIf` variadic operator belonging to the list identified by `sibling_list_id`.
+    /// The operands will be in a _two_ item list identified by `child_list_id`
+    pub visit_null_if: VisitVariadicFn,
+    /// Visits a `Case` expression belonging to the list identified by `sibling_list_id`. The list
+    /// identified by `child_list_id` contains the WHEN predicate and THEN expression of each branch,
+    /// in order, followed by the ELSE expression if present. An odd-length list thus has an ELSE.
+    pub visit_case: VisitVariadicFn,
+    /// Visits a `Cast` expression belonging to the list identified by `sibling_list_id`. The input
+    /// expression is in a _one_ item list identified by `child_list_id`. The target type is given by
+    /// its SQL-like name (e.g. `long` or `decimal(10,2)`), and `null_on_overflow` tells whether
+    /// values that do not fit the target type become NULL (rather than an error).
+    pub visit_cast: extern "C" fn(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        child_list_id: usize,
+        target_type: KernelStringSlice,
+        null_on_overflow: bool,
+    ),
+    /// Visits a map value access (`map[key]`) belonging to the list identified by
+    /// `sibling_list_id`. The map and the key (a literal) will be in a _two_ item list identified
+    /// by `child_list_id`.
+    pub visit_map_value: VisitBinaryFn,
+    /// Visits an array element access (`array[index]`, zero-based) belonging to the list
+    /// identified by `sibling_list_id`. The array will be in a _one_ item list identified by
+    /// `child_list_id`.
+    pub visit_array_element: extern "C" fn(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        child_list_id: usize,
+        index: usize,
+    ),
+    /// Visits a struct field access by (zero-based) ordinal belonging to the list identified by
+    /// `sibling_list_id`. The struct will be in a _one_ item list identified by `child_list_id`.
+    pub visit_struct_field: extern "C" fn(
+        data: *mut c_void,
+        sibling_list_id: usize,
+        child_list_id: usize,
+        ordinal: usize,
+    ),
     /// Visits the `column` belonging to the list identified by `sibling_list_id`.
     pub visit_column:
         extern "C" fn(data: *mut c_void, sibling_list_id: usize, name: KernelStringSlice),
@@ -612,6 +663,7 @@ fn visit_expression_impl(
                 BinaryExpressionOp::Minus => visitor.visit_minus,
                 BinaryExpressionOp::Multiply => visitor.visit_multiply,
                 BinaryExpressionOp::Divide => visitor.visit_divide,
+                BinaryExpressionOp::Modulo => visitor.visit_modulo,
             };
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
@@ -622,9 +674,72 @@ fn visit_expression_impl(
             }
             let visit_fn = match op {
                 VariadicExpressionOp::Coalesce => visitor.visit_coalesce,
+                VariadicExpressionOp::NullIf => visitor.visit_null_if,
             };
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
+        Expression::Case(CaseExpression {
+            branches,
+            else_expr,
+        }) => {
+            let len = 2 * branches.len() + usize::from(else_expr.is_some());
+            let child_list_id = call!(visitor, make_field_list, len);
+            for (when, then) in branches {
+                visit_predicate_impl(visitor, when, child_list_id);
+                visit_expression_impl(visitor, then, child_list_id);
+            }
+            if let Some(else_expr) = else_expr {
+                visit_expression_impl(visitor, else_expr, child_list_id);
+            }
+            call!(visitor, visit_case, sibling_list_id, child_list_id);
+        }
+        Expression::Cast(CastExpression {
+            expr,
+            to,
+            on_overflow,
+        }) => {
+            let child_list_id = call!(visitor, make_field_list, 1);
+            visit_expression_impl(visitor, expr, child_list_id);
+            let target_type = to.to_string();
+            call!(
+                visitor,
+                visit_cast,
+                sibling_list_id,
+                child_list_id,
+                kernel_string_slice!(target_type),
+                *on_overflow == CastOverflowPolicy::Null
+            );
+        }
+        Expression::Element(ElementExpression { expr, accessor }) => match accessor {
+            ElementAccessor::MapValue(key) => {
+                let child_list_id = call!(visitor, make_field_list, 2);
+                visit_expression_impl(visitor, expr, child_list_id);
+                visit_expression_scalar(visitor, key, child_list_id);
+                call!(visitor, visit_map_value, sibling_list_id, child_list_id);
+            }
+            ElementAccessor::ArrayElement(index) => {
+                let child_list_id = call!(visitor, make_field_list, 1);
+                visit_expression_impl(visitor, expr, child_list_id);
+                call!(
+                    visitor,
+                    visit_array_element,
+                    sibling_list_id,
+                    child_list_id,
+                    *index
+                );
+            }
+            ElementAccessor::StructField(ordinal) => {
+                let child_list_id = call!(visitor, make_field_list, 1);
+                visit_expression_impl(visitor, expr, child_list_id);
+                call!(
+                    visitor,
+                    visit_struct_field,
+                    sibling_list_id,
+                    child_list_id,
+                    *ordinal
+                );
+            }
+        },
         Expression::Opaque(OpaqueExpression { op, exprs }) => {
             visit_expression_opaque(visitor, op, exprs, sibling_list_id)
         }
@@ -662,6 +777,8 @@ fn visit_predicate_impl(
                 BinaryPredicateOp::Equal => visitor.visit_eq,
                 BinaryPredicateOp::Distinct => visitor.visit_distinct,
                 BinaryPredicateOp::In => visitor.visit_in,
+                BinaryPredicateOp::Like => visitor.visit_like,
+                BinaryPredicateOp::ILike => visitor.visit_ilike,
             };
             visit_fn(visitor.data, sibling_list_id, child_list_id);
         }
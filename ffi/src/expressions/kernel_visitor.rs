@@ -135,6 +135,15 @@ pub extern "C" fn visit_expression_divide(
     visit_expression_binary(state, BinaryExpressionOp::Divide, a, b)
 }
 
+#[no_mangle]
+pub extern "C" fn visit_expression_modulo(
+    state: &mut KernelExpressionVisitorState,
+    a: usize,
+    b: usize,
+) -> usize {
+    visit_expression_binary(state, BinaryExpressionOp::Modulo, a, b)
+}
+
 #[no_mangle]
 pub extern "C" fn visit_predicate_lt(
     state: &mut KernelExpressionVisitorState,
@@ -331,3 +340,23 @@ pub extern "C" fn visit_expression_literal_date(
 ) -> usize {
     wrap_expression(state, Expression::literal(Scalar::Date(value)))
 }
+
+/// visit a timestamp literal expression 'value' (i64 representing microseconds since unix epoch,
+/// adjusted to UTC)
+#[no_mangle]
+pub extern "C" fn visit_expression_literal_timestamp(
+    state: &mut KernelExpressionVisitorState,
+    value: i64,
+) -> usize {
+    wrap_expression(state, Expression::literal(Scalar::Timestamp(value)))
+}
+
+/// visit a timestamp_ntz literal expression 'value' (i64 representing microseconds since unix
+/// epoch, with no timezone)
+#[no_mangle]
+pub extern "C" fn visit_expression_literal_timestamp_ntz(
+    state: &mut KernelExpressionVisitorState,
+    value: i64,
+) -> usize {
+    wrap_expression(state, Expression::literal(Scalar::TimestampNtz(value)))
+}
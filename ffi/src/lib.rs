@@ -865,7 +865,7 @@ mod tests {
         // Test getting non-existent snapshot
         let snapshot_at_non_existent_version =
             unsafe { snapshot_at_version(kernel_string_slice!(path), engine.shallow_copy(), 1) };
-        assert_extern_result_error_with_message(snapshot_at_non_existent_version, KernelError::GenericError, "Generic delta kernel error: LogSegment end version 0 not the same as the specified end version 1");
+        assert_extern_result_error_with_message(snapshot_at_non_existent_version, KernelError::GenericError, "Generic delta kernel error: LogSegment end version 0 not the same as the specified end version 1 (table: memory:///, version: 1)");
 
         let table_root = unsafe { snapshot_table_root(snapshot1.shallow_copy(), allocate_str) };
         assert!(table_root.is_some());
@@ -74,7 +74,37 @@ pub unsafe extern "C" fn selection_vector_from_scan_metadata(
 fn selection_vector_from_scan_metadata_impl(
     scan_metadata: &ScanMetadata,
 ) -> DeltaResult<KernelBoolSlice> {
-    Ok(scan_metadata.scan_files.selection_vector.clone().into())
+    Ok(scan_metadata.scan_files.selection_vector.to_vec().into())
+}
+
+/// The selection vector of a [`SharedScanMetadata`] as a bitmap, borrowed from kernel without
+/// copying it. Row `i` is selected if bit `i % 8` (least significant first) of byte `i / 8` is set,
+/// which is the layout of Arrow boolean buffers. The bits past `len` rows are unspecified.
+///
+/// The bitmap is only valid until the scan metadata it was obtained from is freed, and must not be
+/// freed by the engine.
+#[repr(C)]
+pub struct KernelSelectionBitmap {
+    pub bits: *const u8,
+    pub len: usize,
+}
+
+/// Get the selection vector of a [`SharedScanMetadata`] as a bitmap, without copying it. Unlike
+/// [`selection_vector_from_scan_metadata`], the result borrows from the scan metadata and is not
+/// freed by the engine.
+///
+/// # Safety
+/// Engine is responsible for providing a valid scan metadata handle
+#[no_mangle]
+pub unsafe extern "C" fn selection_bitmap_from_scan_metadata(
+    scan_metadata: Handle<SharedScanMetadata>,
+) -> KernelSelectionBitmap {
+    let scan_metadata = unsafe { scan_metadata.as_ref() };
+    let selection_vector = &scan_metadata.scan_files.selection_vector;
+    KernelSelectionBitmap {
+        bits: selection_vector.as_bytes().as_ptr(),
+        len: selection_vector.len(),
+    }
 }
 
 /// Drops a scan.
@@ -153,6 +183,62 @@ pub unsafe extern "C" fn scan_physical_schema(scan: Handle<SharedScan>) -> Handl
     scan.physical_schema().clone().into()
 }
 
+/// A report of the work a scan has performed so far, e.g. for engines to include in the output of
+/// `EXPLAIN ANALYZE`. Durations are in nanoseconds. See [`scan_report`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanReport {
+    /// The number of add actions read from the log, before any pruning or reconciliation.
+    pub files_seen: u64,
+    /// The number of add actions skipped by their partition values.
+    pub files_pruned_by_partition: u64,
+    /// The number of add actions skipped by their statistics.
+    pub files_pruned_by_stats: u64,
+    /// The number of files selected for the scan to read.
+    pub files_planned: u64,
+    /// The total size in bytes of the files selected for the scan to read.
+    pub bytes_planned: u64,
+    /// The number of deletion vectors the kernel loaded to execute the scan. Deletion vectors the
+    /// engine loads with [`selection_vector_from_dv`] or [`row_indexes_from_dv`] are not counted.
+    pub deletion_vectors_loaded: u64,
+    /// The time spent replaying the log to produce scan metadata.
+    pub planning_duration_ns: u64,
+    /// The time spent loading deletion vectors to execute the scan.
+    pub deletion_vector_duration_ns: u64,
+    /// The time spent reading data files to execute the scan.
+    pub read_duration_ns: u64,
+}
+
+impl From<delta_kernel::scan::report::ScanReport> for ScanReport {
+    fn from(report: delta_kernel::scan::report::ScanReport) -> Self {
+        let nanos =
+            |duration: std::time::Duration| u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        Self {
+            files_seen: report.files_seen,
+            files_pruned_by_partition: report.files_pruned_by_partition,
+            files_pruned_by_stats: report.files_pruned_by_stats,
+            files_planned: report.files_planned,
+            bytes_planned: report.bytes_planned,
+            deletion_vectors_loaded: report.deletion_vectors_loaded,
+            planning_duration_ns: nanos(report.planning_duration),
+            deletion_vector_duration_ns: nanos(report.deletion_vector_duration),
+            read_duration_ns: nanos(report.read_duration),
+        }
+    }
+}
+
+/// Get a [`ScanReport`] of the work the scan has performed so far. The report covers all scan
+/// metadata iterators of the scan (see [`scan_metadata_iter_init`]), so engines should get it once
+/// they have consumed them.
+///
+/// # Safety
+/// Engine is responsible for providing a valid `SharedScan` handle
+#[no_mangle]
+pub unsafe extern "C" fn scan_report(scan: Handle<SharedScan>) -> ScanReport {
+    let scan = unsafe { scan.as_ref() };
+    scan.report().into()
+}
+
 // Intentionally opaque to the engine.
 //
 // TODO: This approach liberates the engine from having to worry about mutual exclusion, but that
@@ -2,8 +2,12 @@ use std::os::raw::c_void;
 
 use crate::handle::Handle;
 use crate::scan::CStringMap;
-use crate::{kernel_string_slice, KernelStringSlice, SharedSchema};
-use delta_kernel::schema::{ArrayType, DataType, MapType, PrimitiveType, StructType};
+use crate::{
+    kernel_string_slice, ExternResult, IntoExternResult, KernelStringSlice, SharedExternEngine,
+    SharedSchema,
+};
+use delta_kernel::schema::{ArrayType, DataType, MapType, PrimitiveType, SchemaLimits, StructType};
+use delta_kernel::DeltaResult;
 
 /// The `EngineSchemaVisitor` defines a visitor system to allow engines to build their own
 /// representation of a schema from a particular schema within kernel.
@@ -219,6 +223,33 @@ pub unsafe extern "C" fn visit_schema(
     visit_schema_impl(schema, visitor)
 }
 
+/// Like [`visit_schema`], but first checks that the schema does not exceed the default
+/// [`SchemaLimits`] and has no field names that differ only by case. Engines that build their own
+/// schema representation recursively should prefer this method for schemas from untrusted sources.
+/// Returns an error without visiting anything if the checks fail.
+///
+/// # Safety
+///
+/// Caller is responsible for passing a valid schema handle, schema visitor, and engine handle.
+#[no_mangle]
+pub unsafe extern "C" fn visit_schema_checked(
+    schema: Handle<SharedSchema>,
+    visitor: &mut EngineSchemaVisitor,
+    engine: Handle<SharedExternEngine>,
+) -> ExternResult<usize> {
+    let schema = unsafe { schema.as_ref() };
+    let engine = unsafe { engine.as_ref() };
+    visit_schema_checked_impl(schema, visitor).into_extern_result(&engine)
+}
+
+fn visit_schema_checked_impl(
+    schema: &StructType,
+    visitor: &mut EngineSchemaVisitor,
+) -> DeltaResult<usize> {
+    schema.validate_limits(&SchemaLimits::DEFAULT)?;
+    Ok(visit_schema_impl(schema, visitor))
+}
+
 fn visit_schema_impl(schema: &StructType, visitor: &mut EngineSchemaVisitor) -> usize {
     // Visit all the fields of a struct and return the list of children
     fn visit_struct_fields(visitor: &EngineSchemaVisitor, s: &StructType) -> usize {
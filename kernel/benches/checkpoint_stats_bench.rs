@@ -0,0 +1,141 @@
+//! Benchmark for scan planning with data skipping over a checkpoint, comparing checkpoints that only
+//! carry JSON `add.stats` against checkpoints that also carry struct-typed `add.stats_parsed`
+//! (written when the table enables `delta.checkpoint.writeStatsAsStruct`).
+//!
+//! You can run this benchmark with `cargo bench --bench checkpoint_stats_bench`.
+//!
+//! To compare your changes vs. latest main, you can:
+//! ```bash
+//! # checkout baseline branch (upstream/main) and save as baseline
+//! git checkout main # or upstream/main, another branch, etc.
+//! cargo bench --bench checkpoint_stats_bench -- --save-baseline main
+//!
+//! # switch back to your changes, and compare against baseline
+//! git checkout your-branch
+//! cargo bench --bench checkpoint_stats_bench -- --baseline main
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
+use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::expressions::{column_expr, Expression as Expr, Predicate as Pred};
+use delta_kernel::snapshot::Snapshot;
+use delta_kernel::try_parse_uri;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::json;
+use tempfile::TempDir;
+use url::Url;
+
+const NUM_FILES: usize = 50_000;
+const NUM_COLUMNS: usize = 10;
+const SAMPLE_SIZE: usize = 20;
+
+/// Creates a table with [`NUM_FILES`] add actions (each with stats for [`NUM_COLUMNS`] integer
+/// columns) and checkpoints it with the default engine.
+fn setup(
+    write_stats_as_struct: bool,
+) -> (TempDir, Url, Arc<DefaultEngine<TokioBackgroundExecutor>>) {
+    let tempdir = tempfile::tempdir().expect("Failed to create temp dir");
+    let log_dir = tempdir.path().join("_delta_log");
+    fs::create_dir(&log_dir).expect("Failed to create _delta_log");
+
+    let fields: Vec<_> = (0..NUM_COLUMNS)
+        .map(|i| json!({"name": format!("c{i}"), "type": "integer", "nullable": true, "metadata": {}}))
+        .collect();
+    let schema = json!({"type": "struct", "fields": fields});
+    let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+    let metadata = json!({"metaData": {
+        "id": "checkpoint-stats-bench",
+        "format": {"provider": "parquet", "options": {}},
+        "schemaString": schema.to_string(),
+        "partitionColumns": [],
+        "configuration": {
+            "delta.checkpoint.writeStatsAsStruct": write_stats_as_struct.to_string(),
+        },
+        "createdTime": 0,
+    }});
+    let mut commit = vec![protocol.to_string(), metadata.to_string()];
+    commit.extend((0..NUM_FILES).map(|i| {
+        let values: serde_json::Map<_, _> = (0..NUM_COLUMNS)
+            .map(|c| (format!("c{c}"), json!(i * 10 + c)))
+            .collect();
+        let null_counts: serde_json::Map<_, _> = (0..NUM_COLUMNS)
+            .map(|c| (format!("c{c}"), json!(0)))
+            .collect();
+        let stats = json!({
+            "numRecords": 10,
+            "minValues": values,
+            "maxValues": values,
+            "nullCount": null_counts,
+        });
+        json!({"add": {
+            "path": format!("part-{i:05}.parquet"),
+            "partitionValues": {},
+            "size": 1024,
+            "modificationTime": 0,
+            "dataChange": true,
+            "stats": stats.to_string(),
+        }})
+        .to_string()
+    }));
+    fs::write(log_dir.join("00000000000000000000.json"), commit.join("\n"))
+        .expect("Failed to write commit");
+
+    let url = try_parse_uri(tempdir.path().to_str().unwrap()).expect("Failed to parse table path");
+    let executor = Arc::new(TokioBackgroundExecutor::new());
+    let engine = DefaultEngine::try_new(&url, HashMap::<String, String>::new(), executor)
+        .expect("Failed to create engine");
+    let snapshot = Snapshot::builder_for(url.clone())
+        .build(&engine)
+        .expect("Failed to create snapshot");
+    let writer = snapshot
+        .checkpoint()
+        .expect("Failed to create checkpoint writer");
+    engine
+        .write_checkpoint(writer)
+        .expect("Failed to write checkpoint");
+
+    (tempdir, url, Arc::new(engine))
+}
+
+fn scan_metadata_with_predicate_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("checkpoint_scan_metadata_with_predicate");
+    group.sample_size(SAMPLE_SIZE);
+    for write_stats_as_struct in [false, true] {
+        let (_tempdir, url, engine) = setup(write_stats_as_struct);
+        let snapshot = Snapshot::builder_for(url)
+            .build(engine.as_ref())
+            .expect("Failed to create snapshot");
+        // keeps ~1% of the files
+        let predicate = Arc::new(Pred::lt(
+            column_expr!("c0"),
+            Expr::literal((NUM_FILES / 10) as i32),
+        ));
+        let id = BenchmarkId::new("write_stats_as_struct", write_stats_as_struct);
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                let scan = snapshot
+                    .clone() // arc
+                    .scan_builder()
+                    .with_predicate(predicate.clone())
+                    .build()
+                    .expect("Failed to build scan");
+                let metadata_iter = scan
+                    .scan_metadata(engine.as_ref())
+                    .expect("Failed to get scan metadata");
+                // kernel scans are lazy, we must consume iterator to do the work we want to test
+                for result in metadata_iter {
+                    result.expect("Failed to process scan metadata");
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scan_metadata_with_predicate_benchmark);
+criterion_main!(benches);
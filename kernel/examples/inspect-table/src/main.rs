@@ -70,7 +70,7 @@ enum Action {
 }
 
 static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-    LazyLock::new(|| get_log_schema().leaves(None));
+    LazyLock::new(|| get_log_schema().column_names_and_types(None));
 
 struct LogVisitor {
     actions: Vec<(Action, usize)>,
@@ -195,7 +195,7 @@ fn try_main() -> DeltaResult<()> {
             println!("{:#?}", snapshot.schema());
         }
         Commands::ScanMetadata => {
-            let scan = ScanBuilder::new(snapshot).build()?;
+            let scan = ScanBuilder::new(snapshot).with_stats(true).build()?;
             let scan_metadata_iter = scan.scan_metadata(&engine)?;
             for res in scan_metadata_iter {
                 let scan_metadata = res?;
@@ -30,9 +30,11 @@
 //!   4. Produces a [`ActionReconciliationBatch`] result which includes both the filtered data and counts of
 //!      actions selected
 //!
-use crate::engine_data::{FilteredEngineData, GetData, RowVisitor, TypedGetData as _};
+use crate::engine_data::{
+    FilteredEngineData, GetData, PrimitiveColumn, RowVisitor, TypedGetData as _,
+};
 use crate::log_replay::{
-    ActionsBatch, FileActionDeduplicator, FileActionKey, HasSelectionVector, LogReplayProcessor,
+    ActionsBatch, FileActionDeduplicator, HasSelectionVector, LogReplayProcessor, SeenFileKeys,
 };
 use crate::scan::data_skipping::DataSkippingFilter;
 use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType};
@@ -47,7 +49,7 @@ use std::sync::LazyLock;
 pub(crate) struct ActionReconciliationProcessor {
     /// Tracks file actions that have been seen during log replay to avoid duplicates.
     /// Contains (data file path, dv_unique_id) pairs as `FileActionKey` instances.
-    seen_file_keys: HashSet<FileActionKey>,
+    seen_file_keys: SeenFileKeys,
     /// Indicates whether a protocol action has been seen in the log.
     seen_protocol: bool,
     /// Indicates whether a metadata action has been seen in the log.
@@ -116,7 +118,7 @@ impl LogReplayProcessor for ActionReconciliationProcessor {
 
         let filtered_data = FilteredEngineData {
             data: actions,
-            selection_vector: visitor.selection_vector,
+            selection_vector: visitor.selection_vector.into(),
         };
 
         Ok(ActionReconciliationBatch {
@@ -230,7 +232,7 @@ impl ActionReconciliationVisitor<'_> {
 
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new<'seen>(
-        seen_file_keys: &'seen mut HashSet<FileActionKey>,
+        seen_file_keys: &'seen mut SeenFileKeys,
         is_log_batch: bool,
         selection_vector: Vec<bool>,
         minimum_file_retention_timestamp: i64,
@@ -269,7 +271,11 @@ impl ActionReconciliationVisitor<'_> {
     /// - If deletion_timestamp <= minimum_file_retention_timestamp: Expired (exclude)
     /// - If deletion_timestamp > minimum_file_retention_timestamp: Valid (include)
     /// - If deletion_timestamp is missing: Defaults to 0, treated as expired (exclude)
-    fn is_expired_tombstone<'a>(&self, i: usize, getter: &'a dyn GetData<'a>) -> DeltaResult<bool> {
+    fn is_expired_tombstone<'a, G: GetData<'a> + ?Sized>(
+        &self,
+        i: usize,
+        getter: &'a G,
+    ) -> DeltaResult<bool> {
         // Ideally this should never be zero, but we are following the same behavior as Delta
         // Spark and the Java Kernel.
         // Note: When remove.deletion_timestamp is not present (defaulting to 0), the remove action
@@ -289,10 +295,10 @@ impl ActionReconciliationVisitor<'_> {
     ///
     /// Note: This function handles both add and remove actions, applying deduplication logic and
     /// tombstone expiration rules as needed.
-    fn check_file_action<'a>(
+    fn check_file_action<'a, G: GetData<'a> + ?Sized>(
         &mut self,
         i: usize,
-        getters: &[&'a dyn GetData<'a>],
+        getters: &[&'a G],
     ) -> DeltaResult<Option<bool>> {
         // Extract the file action and handle errors immediately
         let Some((file_key, is_add)) = self.deduplicator.extract_file_action(i, getters, false)?
@@ -319,10 +325,10 @@ impl ActionReconciliationVisitor<'_> {
     /// Returns `Ok(Some(false))` if the row contains a protocol action but it's suppressed (duplicate).
     /// Returns `Ok(None)` if the row doesn't contain a protocol action (continue checking other action types).
     /// Returns `Err(...)` if there was an error processing the action.
-    fn check_protocol_action<'a>(
+    fn check_protocol_action<'a, G: GetData<'a> + ?Sized>(
         &mut self,
         i: usize,
-        getter: &'a dyn GetData<'a>,
+        getter: &'a G,
     ) -> DeltaResult<Option<bool>> {
         // minReaderVersion is a required field, so we check for its presence to determine if this is a protocol action.
         // Only return the first (newest) protocol action we see, ignoring other types
@@ -339,10 +345,10 @@ impl ActionReconciliationVisitor<'_> {
     /// Returns `Ok(Some(false))` if the row contains a metadata action but it's suppressed (duplicate).
     /// Returns `Ok(None)` if the row doesn't contain a metadata action (continue checking other action types).
     /// Returns `Err(...)` if there was an error processing the action.
-    fn check_metadata_action<'a>(
+    fn check_metadata_action<'a, G: GetData<'a> + ?Sized>(
         &mut self,
         i: usize,
-        getter: &'a dyn GetData<'a>,
+        getter: &'a G,
     ) -> DeltaResult<Option<bool>> {
         // id is a required field, so we check for its presence to determine if this is a metadata action.
         // Only return the first (newest) metadata action we see, ignoring other types
@@ -359,10 +365,10 @@ impl ActionReconciliationVisitor<'_> {
     /// Returns `Ok(Some(false))` if the row contains a txn action but it's suppressed (duplicate/expired).
     /// Returns `Ok(None)` if the row doesn't contain a txn action (continue checking other action types).
     /// Returns `Err(...)` if there was an error processing the action.
-    fn check_txn_action<'a>(
+    fn check_txn_action<'a, G: GetData<'a> + ?Sized>(
         &mut self,
         i: usize,
-        getter: &[&'a dyn GetData<'a>],
+        getter: &[&'a G],
     ) -> DeltaResult<Option<bool>> {
         // Check for txn field
         let Some(app_id) = getter[11].get_str(i, "txn.appId")? else {
@@ -401,10 +407,30 @@ impl ActionReconciliationVisitor<'_> {
     /// Returns `Ok(true)` if the row should be included.
     /// Returns `Ok(false)` if the row should be skipped.
     /// Returns `Err(...)` if any validation or extraction failed.
-    pub(crate) fn is_valid_action<'a>(
+    /// Selects the valid actions of a batch of `row_count` rows, read through `getters`.
+    fn visit_getters<'a, G: GetData<'a> + ?Sized>(
+        &mut self,
+        row_count: usize,
+        getters: &[&'a G],
+    ) -> DeltaResult<()> {
+        require!(
+            getters.len() == 13,
+            Error::InternalError(format!(
+                "Wrong number of visitor getters: {}",
+                getters.len()
+            ))
+        );
+
+        for i in 0..row_count {
+            self.selection_vector[i] = self.is_valid_action(i, getters)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_valid_action<'a, G: GetData<'a> + ?Sized>(
         &mut self,
         i: usize,
-        getters: &[&'a dyn GetData<'a>],
+        getters: &[&'a G],
     ) -> DeltaResult<bool> {
         // Check each action type in sequence, short-circuiting when an action is found
         let is_valid = if let Some(result) = self.check_file_action(i, getters)? {
@@ -462,18 +488,18 @@ impl RowVisitor for ActionReconciliationVisitor<'_> {
     }
 
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
-        require!(
-            getters.len() == 13,
-            Error::InternalError(format!(
-                "Wrong number of visitor getters: {}",
-                getters.len()
-            ))
-        );
+        self.visit_getters(row_count, getters)
+    }
 
-        for i in 0..row_count {
-            self.selection_vector[i] = self.is_valid_action(i, getters)?;
-        }
-        Ok(())
+    fn visit_primitive<'a>(
+        &mut self,
+        row_count: usize,
+        columns: &'a [PrimitiveColumn<'a>],
+    ) -> DeltaResult<()> {
+        // All the columns this visitor needs are primitive, so checkpoint and commit batches
+        // normally take this path and read their columns without dynamic dispatch.
+        let getters: Vec<_> = columns.iter().collect();
+        self.visit_getters(row_count, &getters)
     }
 }
 
@@ -514,7 +540,7 @@ mod tests {
     #[test]
     fn test_action_reconciliation_visitor() -> DeltaResult<()> {
         let data = action_batch();
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -570,7 +596,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -601,7 +627,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -640,7 +666,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -659,6 +685,8 @@ mod tests {
         assert_eq!(visitor.selection_vector, expected);
         assert_eq!(visitor.actions_count, 3);
         assert_eq!(visitor.add_actions_count, 1);
+        // The three actions are distinct file actions of the same file
+        assert_eq!(seen_file_keys.len(), 3);
 
         Ok(())
     }
@@ -673,7 +701,7 @@ mod tests {
         let batch = parse_json_batch(json_strings);
 
         // Pre-populate with txn app1
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         seen_txns.insert("app1".to_string());
 
@@ -713,7 +741,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -868,7 +896,7 @@ mod tests {
         .into();
         let batch = parse_json_batch(json_strings);
 
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = ActionReconciliationVisitor::new(
             &mut seen_file_keys,
@@ -1021,7 +1049,7 @@ mod tests {
 
     /// Helper function to create a standard action reconciliation visitor for error testing
     fn create_test_visitor<'a>(
-        seen_file_keys: &'a mut HashSet<FileActionKey>,
+        seen_file_keys: &'a mut SeenFileKeys,
         seen_txns: &'a mut HashSet<String>,
         txn_expiration_timestamp: Option<i64>,
     ) -> ActionReconciliationVisitor<'a> {
@@ -1057,7 +1085,7 @@ mod tests {
     #[test]
     fn test_action_reconciliation_visitor_validation_and_type_errors() {
         // Test 1: Wrong getter count validation
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, None);
         let getter = MockErrorGetData::default();
@@ -1083,7 +1111,7 @@ mod tests {
         ];
 
         for (getter_index, field_name, error_type, expected_error_text) in test_cases {
-            let mut seen_file_keys = HashSet::new();
+            let mut seen_file_keys = SeenFileKeys::default();
             let mut seen_txns = HashSet::new();
             let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, None);
             let getters = create_getters_with_error_at_index(getter_index, field_name, error_type);
@@ -1101,7 +1129,7 @@ mod tests {
     #[test]
     fn test_action_reconciliation_visitor_complex_field_errors() {
         // Test txn.lastUpdated with retention enabled
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, Some(1000));
         let defaults = (0..11)
@@ -1122,7 +1150,7 @@ mod tests {
             .contains("lastUpdated is not of type i64"));
 
         // Test remove.deletionTimestamp
-        let mut seen_file_keys = HashSet::new();
+        let mut seen_file_keys = SeenFileKeys::default();
         let mut seen_txns = HashSet::new();
         let mut visitor = create_test_visitor(&mut seen_file_keys, &mut seen_txns, None);
         let defaults = (0..4)
@@ -18,20 +18,11 @@
 //! - **Transaction Retention**: Calculates when expired app ids can be cleaned up
 use std::time::Duration;
 
-use crate::table_properties::TableProperties;
+use crate::table_properties::{TableProperties, DEFAULT_DELETED_FILE_RETENTION_DURATION};
 use crate::{DeltaResult, Error};
 
 pub(crate) mod log_replay;
 
-const SECONDS_PER_MINUTE: u64 = 60;
-const MINUTES_PER_HOUR: u64 = 60;
-const HOURS_PER_DAY: u64 = 24;
-
-/// The default retention period for deleted files in seconds.
-/// This is set to 7 days, which is the default in delta-spark.
-pub(crate) const DEFAULT_RETENTION_SECS: u64 =
-    7 * HOURS_PER_DAY * MINUTES_PER_HOUR * SECONDS_PER_MINUTE;
-
 /// Provides common functionality for calculating file retention timestamps
 /// and transaction expiration timestamps.
 pub(crate) trait RetentionCalculator {
@@ -94,8 +85,7 @@ pub(crate) fn deleted_file_retention_timestamp_with_time(
     now_duration: Duration,
 ) -> DeltaResult<i64> {
     // Use provided retention duration or default (7 days)
-    let retention_duration =
-        retention_duration.unwrap_or_else(|| Duration::from_secs(DEFAULT_RETENTION_SECS));
+    let retention_duration = retention_duration.unwrap_or(DEFAULT_DELETED_FILE_RETENTION_DURATION);
 
     // Convert to milliseconds for remove action deletion_timestamp comparison
     let now_ms = i64::try_from(now_duration.as_millis())
@@ -108,8 +108,8 @@ impl RowVisitor for CrcProtocolMetadataVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
             // annoyingly, the 'metadata' in CRC is under the name 'metadata', not 'metaData'
-            let mut cols = Metadata::to_schema().leaves("metadata");
-            cols.extend(Protocol::to_schema().leaves(PROTOCOL_NAME));
+            let mut cols = Metadata::to_schema().column_names_and_types("metadata");
+            cols.extend(Protocol::to_schema().column_names_and_types(PROTOCOL_NAME));
             cols
         });
         NAMES_AND_TYPES.as_ref()
@@ -1,7 +1,8 @@
 //! Code relating to parsing and using deletion vectors
 
-use std::io::{Cursor, Read};
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
 
 use bytes::Bytes;
 use roaring::RoaringTreemap;
@@ -9,8 +10,17 @@ use url::Url;
 
 use delta_kernel_derive::ToSchema;
 
-use crate::utils::require;
-use crate::{DeltaResult, Error, StorageHandler};
+use crate::utils::{enter_span, require};
+use crate::{DeltaResult, Engine, Error, StorageHandler};
+
+/// The magic number of a deletion vector bitmap serialized in the portable RoaringBitmap format.
+const PORTABLE_ROARING_BITMAP_MAGIC: u32 = 1681511377;
+/// The magic number of a deletion vector bitmap serialized in the native RoaringBitmap format.
+const NATIVE_ROARING_BITMAP_MAGIC: u32 = 1681511376;
+/// The format version of deletion vector files, stored as their first byte.
+const DELETION_VECTOR_FILE_FORMAT_VERSION: u8 = 1;
+/// The length of a z85-encoded uuid.
+const ENCODED_UUID_LENGTH: usize = 20;
 
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
 #[cfg_attr(test, derive(serde::Serialize), serde(rename_all = "camelCase"))]
@@ -65,10 +75,12 @@ impl DeletionVectorDescriptor {
             "u" => {
                 let path_len = self.path_or_inline_dv.len();
                 require!(
-                    path_len >= 20,
-                    Error::DeletionVector(format!("Invalid length {path_len}, must be >= 20"))
+                    path_len >= ENCODED_UUID_LENGTH,
+                    Error::DeletionVector(format!(
+                        "Invalid length {path_len}, must be >= {ENCODED_UUID_LENGTH}"
+                    ))
                 );
-                let prefix_len = path_len - 20;
+                let prefix_len = path_len - ENCODED_UUID_LENGTH;
                 let decoded = z85::decode(&self.path_or_inline_dv[prefix_len..])
                     .map_err(|_| Error::deletion_vector("Failed to decode DV uuid"))?;
                 let uuid = uuid::Uuid::from_slice(&decoded)
@@ -97,81 +109,146 @@ impl DeletionVectorDescriptor {
     }
 
     /// Read a dv in stored form into a [`RoaringTreemap`]
+    ///
+    /// To read many deletion vectors, use [`DeletionVectorDescriptor::read_all`], which fetches
+    /// them together.
+    pub fn read(
+        &self,
+        storage: Arc<dyn StorageHandler>,
+        parent: &Url,
+    ) -> DeltaResult<RoaringTreemap> {
+        Self::read_all(storage.as_ref(), parent, &[self], None)?
+            .pop()
+            .ok_or(Error::missing_data("No deletion vector data"))
+    }
+
+    /// Read the given deletion vectors, in order. Deletion vectors stored in files are fetched
+    /// with a single call to [`StorageHandler::read_files`], so engines may fetch them
+    /// concurrently. Deletion vectors stored in the same file are fetched as a single byte range
+    /// of the file.
+    ///
+    /// If a `cache` is given, deletion vectors found in the cache are not fetched again, and the
+    /// fetched ones are added to the cache.
     // A few notes:
     //  - dvs write integers in BOTH big and little endian format. The magic and dv itself are
     //  little, while the version, size, and checksum are big
     //  - dvs can potentially indicate the size in the delta log, and _also_ in the file. If both
     //  are present, we assert they are the same
-    pub fn read(
-        &self,
-        storage: Arc<dyn StorageHandler>,
+    pub fn read_all(
+        storage: &dyn StorageHandler,
         parent: &Url,
-    ) -> DeltaResult<RoaringTreemap> {
-        match self.absolute_path(parent)? {
-            None => {
-                let byte_slice = z85::decode(&self.path_or_inline_dv)
-                    .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
-                let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
-                match magic {
-                    1681511377 => RoaringTreemap::deserialize_from(&byte_slice[4..])
-                        .map_err(|err| Error::DeletionVector(err.to_string())),
-                    1681511376 => {
-                        todo!("Don't support native serialization in inline bitmaps yet");
-                    }
-                    _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
-                }
+        descriptors: &[&DeletionVectorDescriptor],
+        cache: Option<&DeletionVectorCache>,
+    ) -> DeltaResult<Vec<RoaringTreemap>> {
+        enter_span!(INFO, "read_deletion_vectors", table = %parent, count = descriptors.len());
+        let mut treemaps: Vec<Option<RoaringTreemap>> = vec![None; descriptors.len()];
+        // the deletion vectors to fetch, grouped by the file they are stored in
+        let mut files: Vec<(Url, Vec<usize>)> = vec![];
+        let mut file_indexes: HashMap<Url, usize> = HashMap::new();
+        for (i, descriptor) in descriptors.iter().enumerate() {
+            let Some(path) = descriptor.absolute_path(parent)? else {
+                treemaps[i] = Some(descriptor.read_inline()?);
+                continue;
+            };
+            let offset = descriptor.file_offset()?;
+            if let Some(treemap) = cache.and_then(|cache| cache.get(&path, offset)) {
+                treemaps[i] = Some(treemap.as_ref().clone());
+                continue;
             }
-            Some(path) => {
-                let offset = self.offset;
-                let size_in_bytes = self.size_in_bytes;
+            let file_index = *file_indexes.entry(path.clone()).or_insert_with(|| {
+                files.push((path, vec![]));
+                files.len() - 1
+            });
+            files[file_index].1.push(i);
+        }
 
-                let dv_data = storage
-                    .read_files(vec![(path, None)])?
-                    .next()
-                    .ok_or(Error::missing_data("No deletion vector data"))??;
+        // fetch the byte range spanning all deletion vectors of each file. if it starts right after
+        // the format version, include that to check it.
+        let mut slices = Vec::with_capacity(files.len());
+        for (path, indexes) in &files {
+            let mut range = descriptors[indexes[0]].file_range()?;
+            for i in &indexes[1..] {
+                let dv_range = descriptors[*i].file_range()?;
+                range = range.start.min(dv_range.start)..range.end.max(dv_range.end);
+            }
+            if range.start == 1 {
+                range.start = 0;
+            }
+            slices.push((path.clone(), Some(range)));
+        }
+        let starts: Vec<_> = slices
+            .iter()
+            .map(|(_, range)| range.as_ref().map_or(0, |range| range.start))
+            .collect();
+        let file_data = storage.read_files(slices)?;
 
-                let mut cursor = Cursor::new(dv_data);
-                let mut version_buf = [0; 1];
-                cursor
-                    .read(&mut version_buf)
-                    .map_err(|err| Error::DeletionVector(err.to_string()))?;
-                let version = u8::from_be_bytes(version_buf);
+        for (((path, indexes), start), data) in files.into_iter().zip(starts).zip(file_data) {
+            let data = data?;
+            if start == 0 {
+                let version = data.first().copied().unwrap_or_default();
                 require!(
-                    version == 1,
+                    version == DELETION_VECTOR_FILE_FORMAT_VERSION,
                     Error::DeletionVector(format!("Invalid version: {version}"))
                 );
-
-                if let Some(offset) = offset {
-                    cursor.set_position(offset as u64);
+            }
+            for i in indexes {
+                let descriptor = descriptors[i];
+                let offset = descriptor.file_offset()?;
+                let dv_data = usize::try_from(offset - start)
+                    .ok()
+                    .and_then(|position| data.get(position..))
+                    .ok_or_else(|| Error::deletion_vector("Deletion vector data is truncated"))?;
+                let treemap = deserialize_stored(dv_data, descriptor.size_in_bytes)?;
+                if let Some(cache) = cache {
+                    cache.insert(path.clone(), offset, Arc::new(treemap.clone()));
                 }
-                let dv_size = read_u32(&mut cursor, Endian::Big)?;
-                require!(
-                    dv_size == size_in_bytes as u32,
-                    Error::DeletionVector(format!(
-                        "DV size mismatch. Log indicates {size_in_bytes}, file says: {dv_size}"
-                    ))
-                );
-                let magic = read_u32(&mut cursor, Endian::Little)?;
-                require!(
-                    magic == 1681511377,
-                    Error::DeletionVector(format!("Invalid magic: {magic}"))
-                );
-
-                // get the Bytes back out and limit it to dv_size
-                let position = cursor.position();
-                let mut bytes = cursor.into_inner();
-                let truncate_pos = position + dv_size as u64;
-                assert!(
-                    truncate_pos <= usize::MAX as u64,
-                    "Can't truncate as truncate_pos is > usize::MAX"
-                );
-                bytes.truncate(truncate_pos as usize);
-                let mut cursor = Cursor::new(bytes);
-                cursor.set_position(position);
-                RoaringTreemap::deserialize_from(cursor)
-                    .map_err(|err| Error::DeletionVector(err.to_string()))
+                treemaps[i] = Some(treemap);
             }
         }
+        treemaps
+            .into_iter()
+            .map(|treemap| treemap.ok_or(Error::missing_data("No deletion vector data")))
+            .collect()
+    }
+
+    /// Read a deletion vector stored inline in the log.
+    fn read_inline(&self) -> DeltaResult<RoaringTreemap> {
+        let byte_slice = z85::decode(&self.path_or_inline_dv)
+            .map_err(|_| Error::deletion_vector("Failed to decode DV"))?;
+        require!(
+            byte_slice.len() >= 4,
+            Error::deletion_vector("Deletion vector data is truncated")
+        );
+        let magic = slice_to_u32(&byte_slice[0..4], Endian::Little)?;
+        match magic {
+            PORTABLE_ROARING_BITMAP_MAGIC => RoaringTreemap::deserialize_from(&byte_slice[4..])
+                .map_err(|err| Error::DeletionVector(err.to_string())),
+            NATIVE_ROARING_BITMAP_MAGIC => Err(Error::unsupported(
+                "Native serialization of inline deletion vectors is not supported",
+            )),
+            _ => Err(Error::DeletionVector(format!("Invalid magic {magic}"))),
+        }
+    }
+
+    /// The offset of a deletion vector stored in a file. Without an offset, the deletion vector
+    /// directly follows the format version of the file.
+    fn file_offset(&self) -> DeltaResult<u64> {
+        let offset = self.offset.unwrap_or(1);
+        u64::try_from(offset)
+            .map_err(|_| Error::DeletionVector(format!("Invalid offset: {offset}")))
+    }
+
+    /// The byte range of a deletion vector stored in a file: its size, followed by the serialized
+    /// bitmap.
+    fn file_range(&self) -> DeltaResult<Range<u64>> {
+        let start = self.file_offset()?;
+        let size = u64::try_from(self.size_in_bytes)
+            .map_err(|_| Error::DeletionVector(format!("Invalid size: {}", self.size_in_bytes)))?;
+        let end = start
+            .checked_add(4)
+            .and_then(|end| end.checked_add(size))
+            .ok_or_else(|| Error::DeletionVector(format!("Invalid size: {size}")))?;
+        Ok(start..end)
     }
 
     /// Materialize the row indexes of the deletion vector as a `Vec<u64>` in which each element
@@ -183,6 +260,362 @@ impl DeletionVectorDescriptor {
     ) -> DeltaResult<Vec<u64>> {
         Ok(self.read(storage, parent)?.into_iter().collect())
     }
+
+    /// Read the serialized bitmap of the deletion vector, without deserializing it. This allows
+    /// engines to load deletion vectors into their own bitmap implementations, see
+    /// [`DeletionVectorBitmap`].
+    pub fn read_bitmap_bytes(
+        &self,
+        engine: &dyn Engine,
+        parent: &Url,
+    ) -> DeltaResult<DeletionVectorBitmap> {
+        // the serialized bitmap, preceded by its magic number
+        let data = match self.absolute_path(parent)? {
+            None => Bytes::from(
+                z85::decode(&self.path_or_inline_dv)
+                    .map_err(|_| Error::deletion_vector("Failed to decode DV"))?,
+            ),
+            Some(path) => {
+                // if the deletion vector starts right after the format version, include that to
+                // check it.
+                let mut range = self.file_range()?;
+                let check_version = range.start == 1;
+                if check_version {
+                    range.start = 0;
+                }
+                let mut data = engine
+                    .storage_handler()
+                    .read_files(vec![(path, Some(range))])?
+                    .next()
+                    .ok_or(Error::missing_data("No deletion vector data"))??;
+                if check_version {
+                    let version = data.first().copied().unwrap_or_default();
+                    require!(
+                        version == DELETION_VECTOR_FILE_FORMAT_VERSION,
+                        Error::DeletionVector(format!("Invalid version: {version}"))
+                    );
+                    data = data.slice(1..);
+                }
+                require!(
+                    data.len() >= 4,
+                    Error::deletion_vector("Deletion vector data is truncated")
+                );
+                let dv_size = slice_to_u32(&data[0..4], Endian::Big)?;
+                require!(
+                    dv_size == self.size_in_bytes as u32,
+                    Error::DeletionVector(format!(
+                        "DV size mismatch. Log indicates {}, file says: {dv_size}",
+                        self.size_in_bytes
+                    ))
+                );
+                data.slice(4..)
+            }
+        };
+        // inline deletion vectors may be padded beyond their size
+        let size = usize::try_from(self.size_in_bytes)
+            .ok()
+            .filter(|size| (4..=data.len()).contains(size))
+            .ok_or_else(|| Error::deletion_vector("Deletion vector data is truncated"))?;
+        let magic = slice_to_u32(&data[0..4], Endian::Little)?;
+        require!(
+            magic == PORTABLE_ROARING_BITMAP_MAGIC,
+            Error::DeletionVector(format!("Invalid magic: {magic}"))
+        );
+        let cardinality = u64::try_from(self.cardinality).map_err(|_| {
+            Error::DeletionVector(format!("Invalid cardinality: {}", self.cardinality))
+        })?;
+        Ok(DeletionVectorBitmap {
+            bytes: data.slice(4..size),
+            cardinality,
+        })
+    }
+}
+
+/// The serialized bitmap of a deletion vector, as read by
+/// [`DeletionVectorDescriptor::read_bitmap_bytes`].
+///
+/// The bitmap is in the standard portable serialization of 64-bit roaring bitmaps, which e.g.
+/// `RoaringTreemap::deserialize_from` of the `roaring` crate and `Roaring64Map::readSafe` of
+/// CRoaring read. Each set bit is the index of a deleted row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletionVectorBitmap {
+    /// The serialized bitmap, without the magic number that precedes it in Delta tables.
+    pub bytes: Bytes,
+    /// The number of rows the deletion vector removes, i.e. the number of set bits.
+    pub cardinality: u64,
+}
+
+/// The default size budget of a [`DeletionVectorCache`], in bytes.
+pub const DEFAULT_DV_CACHE_SIZE: usize = 64 * 1024 * 1024;
+
+/// A cache of deletion vectors read from deletion vector files, keyed by the file and the offset
+/// of the deletion vector in it. Share a cache between scans of a table to avoid fetching and
+/// decoding the same deletion vectors again, see [`ScanBuilder::with_deletion_vector_cache`].
+///
+/// The cache holds deletion vectors up to a budget of their total serialized size, evicting the
+/// least recently used ones beyond that. Inline deletion vectors are not cached.
+///
+/// [`ScanBuilder::with_deletion_vector_cache`]: crate::scan::ScanBuilder::with_deletion_vector_cache
+#[derive(Debug)]
+pub struct DeletionVectorCache {
+    max_size: usize,
+    state: Mutex<DeletionVectorCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct DeletionVectorCacheState {
+    entries: HashMap<(Url, u64), CachedDeletionVector>,
+    // the keys of the entries, ordered by when they were last used
+    last_used: BTreeMap<u64, (Url, u64)>,
+    clock: u64,
+    size: usize,
+}
+
+#[derive(Debug)]
+struct CachedDeletionVector {
+    treemap: Arc<RoaringTreemap>,
+    size: usize,
+    last_used: u64,
+}
+
+impl Default for DeletionVectorCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DV_CACHE_SIZE)
+    }
+}
+
+impl DeletionVectorCache {
+    /// Create a cache that holds deletion vectors of up to `max_size` bytes in total.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            state: Mutex::new(DeletionVectorCacheState::default()),
+        }
+    }
+
+    /// The number of cached deletion vectors.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total serialized size of the cached deletion vectors, in bytes.
+    pub fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    fn get(&self, path: &Url, offset: u64) -> Option<Arc<RoaringTreemap>> {
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        let key = (path.clone(), offset);
+        let entry = state.entries.get_mut(&key)?;
+        let previous = std::mem::replace(&mut entry.last_used, clock);
+        let treemap = entry.treemap.clone();
+        state.last_used.remove(&previous);
+        state.last_used.insert(clock, key);
+        Some(treemap)
+    }
+
+    fn insert(&self, path: Url, offset: u64, treemap: Arc<RoaringTreemap>) {
+        let size = treemap.serialized_size();
+        if size > self.max_size {
+            return;
+        }
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        let key = (path, offset);
+        let entry = CachedDeletionVector {
+            treemap,
+            size,
+            last_used: clock,
+        };
+        if let Some(previous) = state.entries.insert(key.clone(), entry) {
+            state.last_used.remove(&previous.last_used);
+            state.size -= previous.size;
+        }
+        state.last_used.insert(clock, key);
+        state.size += size;
+        while state.size > self.max_size {
+            let Some((_, key)) = state.last_used.pop_first() else {
+                break;
+            };
+            if let Some(evicted) = state.entries.remove(&key) {
+                state.size -= evicted.size;
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DeletionVectorCacheState> {
+        // The cache is always left in a consistent state, so a poisoned lock is still usable
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The default maximum size in bytes of a deletion vector stored inline in the log. See
+/// [`DeletionVectorWriter::with_max_inline_size`].
+pub const DEFAULT_MAX_INLINE_DV_SIZE: usize = 128;
+/// The default length of the random prefix of deletion vector files. See
+/// [`DeletionVectorWriter::with_random_prefix_length`].
+pub const DEFAULT_DV_RANDOM_PREFIX_LENGTH: usize = 2;
+
+/// Serializes deletion vectors for writing. Small deletion vectors are stored inline in the log
+/// (storage type `i`), larger ones in a file relative to the table root, whose path is encoded as
+/// a random prefix followed by the z85-encoded uuid of the file (storage type `u`). This matches
+/// the deletion vectors written by other Delta writers.
+///
+/// Note that this only serializes the deletion vector: engines must write
+/// [`SerializedDeletionVector::file`] (if any) before committing the descriptor.
+#[derive(Debug, Clone)]
+pub struct DeletionVectorWriter {
+    table_root: Url,
+    max_inline_size: usize,
+    random_prefix_length: usize,
+}
+
+/// A deletion vector serialized by [`DeletionVectorWriter::serialize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializedDeletionVector {
+    /// The descriptor to reference the deletion vector by, e.g. in an add action.
+    pub descriptor: DeletionVectorDescriptor,
+    /// The file to write, or `None` if the deletion vector is stored inline.
+    pub file: Option<DeletionVectorFile>,
+}
+
+/// A deletion vector file to be written by the engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeletionVectorFile {
+    /// The absolute location of the file.
+    pub location: Url,
+    /// The contents of the file.
+    pub data: Bytes,
+}
+
+impl DeletionVectorWriter {
+    /// Create a writer for deletion vectors of the table at `table_root`.
+    pub fn new(table_root: Url) -> Self {
+        Self {
+            table_root,
+            max_inline_size: DEFAULT_MAX_INLINE_DV_SIZE,
+            random_prefix_length: DEFAULT_DV_RANDOM_PREFIX_LENGTH,
+        }
+    }
+
+    /// Set the maximum size in bytes of a serialized deletion vector to store inline in the log.
+    /// Larger deletion vectors are stored in files. Set this to 0 to never store deletion vectors
+    /// inline.
+    pub fn with_max_inline_size(mut self, max_inline_size: usize) -> Self {
+        self.max_inline_size = max_inline_size;
+        self
+    }
+
+    /// Set the length of the random directory prefix of deletion vector files, which spreads the
+    /// files across object store key prefixes. Set this to 0 to write the files into the table
+    /// root.
+    pub fn with_random_prefix_length(mut self, random_prefix_length: usize) -> Self {
+        self.random_prefix_length = random_prefix_length;
+        self
+    }
+
+    /// Serialize a deletion vector marking the row indexes in `deleted_rows` as deleted.
+    pub fn serialize(
+        &self,
+        deleted_rows: &RoaringTreemap,
+    ) -> DeltaResult<SerializedDeletionVector> {
+        let bitmap = serialize_bitmap(deleted_rows)?;
+        let size_in_bytes = i32::try_from(bitmap.len())
+            .map_err(|_| Error::deletion_vector("Deletion vector is too large"))?;
+        let cardinality = i64::try_from(deleted_rows.len())
+            .map_err(|_| Error::deletion_vector("Deletion vector cardinality is too large"))?;
+
+        if bitmap.len() <= self.max_inline_size {
+            return Ok(SerializedDeletionVector {
+                descriptor: DeletionVectorDescriptor {
+                    storage_type: "i".to_string(),
+                    path_or_inline_dv: z85_encode_padded(bitmap),
+                    offset: None,
+                    size_in_bytes,
+                    cardinality,
+                },
+                file: None,
+            });
+        }
+
+        // a deletion vector file contains the format version, followed by the size, data and
+        // checksum of each deletion vector. we write a single deletion vector per file.
+        let checksum = crc32(&bitmap);
+        let mut data = Vec::with_capacity(bitmap.len() + 9);
+        data.push(DELETION_VECTOR_FILE_FORMAT_VERSION);
+        data.extend_from_slice(&(size_in_bytes as u32).to_be_bytes());
+        data.extend_from_slice(&bitmap);
+        data.extend_from_slice(&checksum.to_be_bytes());
+
+        let prefix = random_prefix(self.random_prefix_length);
+        let uuid = uuid::Uuid::new_v4();
+        let descriptor = DeletionVectorDescriptor {
+            storage_type: "u".to_string(),
+            path_or_inline_dv: format!("{prefix}{}", z85::encode(uuid.as_bytes())),
+            offset: Some(1),
+            size_in_bytes,
+            cardinality,
+        };
+        let location = descriptor.absolute_path(&self.table_root)?.ok_or_else(|| {
+            Error::internal_error("Deletion vector stored as 'u' must have a path")
+        })?;
+        Ok(SerializedDeletionVector {
+            descriptor,
+            file: Some(DeletionVectorFile {
+                location,
+                data: data.into(),
+            }),
+        })
+    }
+}
+
+/// Serialize a treemap in the portable RoaringBitmap format, preceded by its magic number.
+fn serialize_bitmap(treemap: &RoaringTreemap) -> DeltaResult<Vec<u8>> {
+    let mut bitmap = Vec::with_capacity(treemap.serialized_size() + 4);
+    bitmap.extend_from_slice(&PORTABLE_ROARING_BITMAP_MAGIC.to_le_bytes());
+    treemap
+        .serialize_into(&mut bitmap)
+        .map_err(|err| Error::DeletionVector(err.to_string()))?;
+    Ok(bitmap)
+}
+
+/// z85 encodes only multiples of 4 bytes, so pad the data with zeros. Readers ignore the padding,
+/// since the serialized bitmap encodes its own length.
+fn z85_encode_padded(mut data: Vec<u8>) -> String {
+    data.resize(data.len().next_multiple_of(4), 0);
+    z85::encode(data)
+}
+
+/// A random alphanumeric string of the given length.
+fn random_prefix(length: usize) -> String {
+    const ALPHANUMERIC: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    std::iter::repeat_with(|| uuid::Uuid::new_v4().into_bytes())
+        .flatten()
+        .take(length)
+        .map(|byte| ALPHANUMERIC[byte as usize % ALPHANUMERIC.len()] as char)
+        .collect()
+}
+
+/// The CRC-32 (IEEE) checksum of `data`, which deletion vector files store after each deletion
+/// vector.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
 }
 
 enum Endian {
@@ -190,16 +623,28 @@ enum Endian {
     Little,
 }
 
-/// small helper to read a big or little endian u32 from a cursor
-fn read_u32(cursor: &mut Cursor<Bytes>, endian: Endian) -> DeltaResult<u32> {
-    let mut buf = [0; 4];
-    cursor
-        .read(&mut buf)
-        .map_err(|err| Error::DeletionVector(err.to_string()))?;
-    match endian {
-        Endian::Big => Ok(u32::from_be_bytes(buf)),
-        Endian::Little => Ok(u32::from_le_bytes(buf)),
-    }
+/// Deserialize a deletion vector stored in a file from `data`, which starts at the size of the
+/// deletion vector.
+fn deserialize_stored(data: &[u8], size_in_bytes: i32) -> DeltaResult<RoaringTreemap> {
+    require!(
+        data.len() >= 8,
+        Error::deletion_vector("Deletion vector data is truncated")
+    );
+    let dv_size = slice_to_u32(&data[0..4], Endian::Big)?;
+    require!(
+        dv_size == size_in_bytes as u32,
+        Error::DeletionVector(format!(
+            "DV size mismatch. Log indicates {size_in_bytes}, file says: {dv_size}"
+        ))
+    );
+    let magic = slice_to_u32(&data[4..8], Endian::Little)?;
+    require!(
+        magic == PORTABLE_ROARING_BITMAP_MAGIC,
+        Error::DeletionVector(format!("Invalid magic: {magic}"))
+    );
+    let end = data.len().min(4 + dv_size as usize);
+    RoaringTreemap::deserialize_from(&data[8..end])
+        .map_err(|err| Error::DeletionVector(err.to_string()))
 }
 
 /// decode a slice into a u32
@@ -380,6 +825,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_invalid_inline_read() {
+        let storage = SyncEngine::new().storage_handler();
+        let parent = Url::parse("http://not.used").unwrap();
+
+        // shorter than the magic number
+        let truncated = DeletionVectorDescriptor {
+            path_or_inline_dv: String::new(),
+            ..dv_inline()
+        };
+        let err = truncated.read(storage.clone(), &parent).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Deletion vector data is truncated"));
+
+        let native = DeletionVectorDescriptor {
+            path_or_inline_dv: z85::encode(NATIVE_ROARING_BITMAP_MAGIC.to_le_bytes()),
+            ..dv_inline()
+        };
+        let err = native.read(storage, &parent).unwrap_err();
+        assert!(matches!(err, Error::Unsupported(_)), "{err}");
+    }
+
     #[test]
     fn test_deletion_vector_read() {
         let path =
@@ -396,6 +864,30 @@ mod tests {
         assert_eq!(found, expected)
     }
 
+    #[test]
+    fn test_read_bitmap_bytes() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/")).unwrap();
+        let parent = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        for dv in [dv_example(), dv_inline()] {
+            let bitmap = dv.read_bitmap_bytes(&engine, &parent).unwrap();
+            let expected = dv.read(engine.storage_handler(), &parent).unwrap();
+            assert_eq!(bitmap.cardinality, expected.len());
+            assert_eq!(bitmap.bytes.len(), expected.serialized_size());
+            let treemap = RoaringTreemap::deserialize_from(bitmap.bytes.as_ref()).unwrap();
+            assert_eq!(treemap, expected);
+        }
+
+        let bad_size = DeletionVectorDescriptor {
+            size_in_bytes: 40,
+            ..dv_example()
+        };
+        let err = bad_size.read_bitmap_bytes(&engine, &parent).unwrap_err();
+        assert!(err.to_string().contains("DV size mismatch"));
+    }
+
     // this test is ignored by default as it's expensive to allocate such big vecs full of `true`. you can run it via:
     // cargo test actions::deletion_vector::tests::test_dv_to_bools -- --ignored
     #[test]
@@ -442,6 +934,86 @@ mod tests {
         assert_eq!(bools, expected);
     }
 
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_write_inline_dv() {
+        let deleted_rows = RoaringTreemap::from_iter([3, 4, 7, 11, 18, 29]);
+        let writer = DeletionVectorWriter::new(Url::parse("s3://mytable/").unwrap());
+        let serialized = writer.serialize(&deleted_rows).unwrap();
+        assert_eq!(serialized.file, None);
+        // matches the inline deletion vector written by Spark
+        assert_eq!(serialized.descriptor, dv_inline());
+
+        let sync_engine = SyncEngine::new();
+        let storage = sync_engine.storage_handler();
+        let parent = Url::parse("http://not.used").unwrap();
+        let tree_map = serialized.descriptor.read(storage, &parent).unwrap();
+        assert_eq!(tree_map, deleted_rows);
+    }
+
+    #[test]
+    fn test_write_dv_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let deleted_rows = RoaringTreemap::from_iter((0..1000).map(|i| i * 3));
+        let writer = DeletionVectorWriter::new(table_root.clone()).with_random_prefix_length(3);
+        let serialized = writer.serialize(&deleted_rows).unwrap();
+
+        let descriptor = &serialized.descriptor;
+        assert_eq!(descriptor.storage_type, "u");
+        assert_eq!(descriptor.path_or_inline_dv.len(), 23);
+        assert_eq!(descriptor.offset, Some(1));
+        assert_eq!(descriptor.cardinality, 1000);
+        let file = serialized.file.unwrap();
+        let prefix = &descriptor.path_or_inline_dv[..3];
+        assert!(prefix.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert!(file
+            .location
+            .as_str()
+            .starts_with(&format!("{table_root}{prefix}/deletion_vector_")));
+        assert_eq!(
+            file.data.len(),
+            descriptor.size_in_bytes as usize + 9,
+            "version, size and checksum take 9 bytes"
+        );
+
+        let path = file.location.to_file_path().unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, &file.data).unwrap();
+        let sync_engine = SyncEngine::new();
+        let storage = sync_engine.storage_handler();
+        let tree_map = descriptor.read(storage, &table_root).unwrap();
+        assert_eq!(tree_map, deleted_rows);
+    }
+
+    #[test]
+    fn test_write_dv_size_threshold() {
+        let table_root = Url::parse("s3://mytable/").unwrap();
+        let deleted_rows = RoaringTreemap::from_iter([1, 2, 3]);
+        let serialized = DeletionVectorWriter::new(table_root.clone())
+            .with_max_inline_size(0)
+            .with_random_prefix_length(0)
+            .serialize(&deleted_rows)
+            .unwrap();
+        assert_eq!(serialized.descriptor.storage_type, "u");
+        assert_eq!(serialized.descriptor.path_or_inline_dv.len(), 20);
+        let location = serialized.file.unwrap().location;
+        assert!(location
+            .as_str()
+            .starts_with("s3://mytable/deletion_vector_"));
+
+        let large = RoaringTreemap::from_iter((0..10_000).map(|i| i * 2));
+        let serialized = DeletionVectorWriter::new(table_root)
+            .serialize(&large)
+            .unwrap();
+        assert_eq!(serialized.descriptor.storage_type, "u");
+    }
+
     #[test]
     fn test_dv_row_indexes() {
         let example = dv_inline();
@@ -453,4 +1025,108 @@ mod tests {
         assert_eq!(row_idx.len(), 6);
         assert_eq!(&row_idx, &[3, 4, 7, 11, 18, 29]);
     }
+
+    /// Write a deletion vector file containing the given deletion vectors, and return their
+    /// descriptors.
+    fn write_packed_dvs(
+        path: &std::path::Path,
+        dvs: &[RoaringTreemap],
+    ) -> Vec<DeletionVectorDescriptor> {
+        let mut data = vec![DELETION_VECTOR_FILE_FORMAT_VERSION];
+        let mut descriptors = vec![];
+        for dv in dvs {
+            let bitmap = serialize_bitmap(dv).unwrap();
+            descriptors.push(DeletionVectorDescriptor {
+                storage_type: "p".to_string(),
+                path_or_inline_dv: Url::from_file_path(path).unwrap().to_string(),
+                offset: Some(data.len() as i32),
+                size_in_bytes: bitmap.len() as i32,
+                cardinality: dv.len() as i64,
+            });
+            data.extend_from_slice(&(bitmap.len() as u32).to_be_bytes());
+            data.extend_from_slice(&bitmap);
+            data.extend_from_slice(&crc32(&bitmap).to_be_bytes());
+        }
+        std::fs::write(path, data).unwrap();
+        descriptors
+    }
+
+    #[test]
+    fn test_read_packed_dvs() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let dvs = [
+            RoaringTreemap::from_iter([1, 5, 7]),
+            RoaringTreemap::from_iter((0..1000).map(|i| i * 2)),
+            RoaringTreemap::from_iter([3]),
+        ];
+        let packed = write_packed_dvs(&dir.path().join("packed.bin"), &dvs);
+        let single = write_packed_dvs(&dir.path().join("single.bin"), &dvs[2..]);
+        let storage = SyncEngine::new().storage_handler();
+
+        // deletion vectors from different files, inline ones, and duplicates are returned in order
+        let descriptors = [&packed[2], &single[0], &dv_inline(), &packed[0], &packed[2]];
+        let treemaps =
+            DeletionVectorDescriptor::read_all(storage.as_ref(), &table_root, &descriptors, None)
+                .unwrap();
+        let expected = [
+            &dvs[2],
+            &dvs[2],
+            &dv_inline().read(storage.clone(), &table_root).unwrap(),
+            &dvs[0],
+            &dvs[2],
+        ];
+        assert_eq!(treemaps.iter().collect::<Vec<_>>(), expected);
+
+        // a deletion vector in the middle of a file
+        let treemap = packed[1].read(storage, &table_root).unwrap();
+        assert_eq!(treemap, dvs[1]);
+    }
+
+    #[test]
+    fn test_read_dvs_with_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(dir.path()).unwrap();
+        let dvs = [
+            RoaringTreemap::from_iter([1, 5, 7]),
+            RoaringTreemap::from_iter([2, 4]),
+        ];
+        let path = dir.path().join("packed.bin");
+        let descriptors = write_packed_dvs(&path, &dvs);
+        let descriptors: Vec<_> = descriptors.iter().collect();
+        let storage = SyncEngine::new().storage_handler();
+
+        let cache = DeletionVectorCache::default();
+        let read = |cache: Option<&DeletionVectorCache>| {
+            DeletionVectorDescriptor::read_all(storage.as_ref(), &table_root, &descriptors, cache)
+        };
+        assert_eq!(read(Some(&cache)).unwrap(), dvs);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.size(),
+            dvs[0].serialized_size() + dvs[1].serialized_size()
+        );
+
+        // cached deletion vectors are not read again
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(read(Some(&cache)).unwrap(), dvs);
+        assert!(read(None).is_err());
+
+        // the least recently used deletion vector is evicted when the cache is full
+        let cache =
+            DeletionVectorCache::new(dvs[0].serialized_size().max(dvs[1].serialized_size()));
+        let path = dir.path().join("packed.bin");
+        write_packed_dvs(&path, &dvs);
+        read(Some(&cache)).unwrap();
+        assert_eq!(cache.len(), 1);
+        std::fs::remove_file(path).unwrap();
+        let treemaps = DeletionVectorDescriptor::read_all(
+            storage.as_ref(),
+            &table_root,
+            &descriptors[1..],
+            Some(&cache),
+        )
+        .unwrap();
+        assert_eq!(treemaps, dvs[1..]);
+    }
 }
@@ -1,8 +1,7 @@
 //! This module includes support for reading DomainMetadata from the log. NB: it is similar to the
 //! set_transaction module which reads SetTransaction actions from the log.
 //!
-//! For now, this module only exposes the ability to read a single domain at once from the log. In
-//! the future this should allow for reading all domains from the log at once.
+//! This module exposes the ability to read either a single domain or all domains from the log.
 
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
@@ -35,6 +34,15 @@ pub(crate) fn domain_metadata_configuration(
         .map(|domain_metadata| domain_metadata.configuration))
 }
 
+/// Read the latest domain metadata of all domains, excluding removed domains. Like
+/// [`domain_metadata_configuration`], this includes 'internal' (delta.*) domains.
+pub(crate) fn all_domain_metadatas(
+    log_segment: &LogSegment,
+    engine: &dyn Engine,
+) -> DeltaResult<DomainMetadataMap> {
+    scan_domain_metadatas(log_segment, None, engine)
+}
+
 /// Scan the entire log for all domain metadata actions but terminate early if a specific domain
 /// is provided. Note that this returns the latest domain metadata for each domain, accounting for
 /// tombstones (removed=true) - that is, removed domain metadatas will _never_ be returned.
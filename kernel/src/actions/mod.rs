@@ -1,7 +1,7 @@
 //! Provides parsing and manipulation of the various actions defined in the [Delta
 //! specification](https://github.com/delta-io/delta/blob/master/PROTOCOL.md)
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::str::FromStr;
@@ -13,7 +13,8 @@ use crate::schema::{
     ArrayType, DataType, MapType, SchemaRef, StructField, StructType, ToSchema as _,
 };
 use crate::table_features::{
-    ReaderFeature, WriterFeature, SUPPORTED_READER_FEATURES, SUPPORTED_WRITER_FEATURES,
+    ReaderFeature, TableFeature, WriterFeature, SUPPORTED_READER_FEATURES,
+    SUPPORTED_WRITER_FEATURES,
 };
 use crate::table_properties::TableProperties;
 use crate::utils::require;
@@ -89,6 +90,27 @@ static LOG_ADD_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     )]))
 });
 
+static LOG_REMOVE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new_unchecked([StructField::nullable(
+        REMOVE_NAME,
+        Remove::to_schema(),
+    )]))
+});
+
+static LOG_METADATA_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new_unchecked([StructField::nullable(
+        METADATA_NAME,
+        Metadata::to_schema(),
+    )]))
+});
+
+static LOG_PROTOCOL_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new_unchecked([StructField::nullable(
+        PROTOCOL_NAME,
+        Protocol::to_schema(),
+    )]))
+});
+
 static LOG_COMMIT_INFO_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new_unchecked([StructField::nullable(
         COMMIT_INFO_NAME,
@@ -120,6 +142,18 @@ pub(crate) fn get_log_add_schema() -> &'static SchemaRef {
     &LOG_ADD_SCHEMA
 }
 
+pub(crate) fn get_log_remove_schema() -> &'static SchemaRef {
+    &LOG_REMOVE_SCHEMA
+}
+
+pub(crate) fn get_log_metadata_schema() -> &'static SchemaRef {
+    &LOG_METADATA_SCHEMA
+}
+
+pub(crate) fn get_log_protocol_schema() -> &'static SchemaRef {
+    &LOG_PROTOCOL_SCHEMA
+}
+
 pub(crate) fn get_log_commit_info_schema() -> &'static SchemaRef {
     &LOG_COMMIT_INFO_SCHEMA
 }
@@ -329,10 +363,9 @@ impl IntoEngineData for Metadata {
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, ToSchema, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-#[internal_api]
 // TODO move to another module so that we disallow constructing this struct without using the
 // try_new function.
-pub(crate) struct Protocol {
+pub struct Protocol {
     /// The minimum version of the Delta read protocol that a client must implement
     /// in order to correctly read this table
     min_reader_version: i32,
@@ -410,17 +443,29 @@ impl Protocol {
     }
 
     /// This protocol's minimum reader version
-    #[internal_api]
-    pub(crate) fn min_reader_version(&self) -> i32 {
+    pub fn min_reader_version(&self) -> i32 {
         self.min_reader_version
     }
 
     /// This protocol's minimum writer version
-    #[internal_api]
-    pub(crate) fn min_writer_version(&self) -> i32 {
+    pub fn min_writer_version(&self) -> i32 {
         self.min_writer_version
     }
 
+    /// The names of this protocol's reader features, as they appear in the Delta log, or `None` if
+    /// the protocol doesn't use table features for readers.
+    pub fn reader_feature_names(&self) -> Option<Vec<String>> {
+        let features = self.reader_features.as_ref()?;
+        Some(features.iter().map(ToString::to_string).collect())
+    }
+
+    /// The names of this protocol's writer features, as they appear in the Delta log, or `None` if
+    /// the protocol doesn't use table features for writers.
+    pub fn writer_feature_names(&self) -> Option<Vec<String>> {
+        let features = self.writer_features.as_ref()?;
+        Some(features.iter().map(ToString::to_string).collect())
+    }
+
     /// Get the reader features for the protocol
     #[internal_api]
     pub(crate) fn reader_features(&self) -> Option<&[ReaderFeature]> {
@@ -433,6 +478,143 @@ impl Protocol {
         self.writer_features.as_deref()
     }
 
+    /// Returns a copy of this protocol that additionally supports the given table features. The
+    /// result always uses writer features (i.e. `minWriterVersion = 7`), and reader features
+    /// (i.e. `minReaderVersion = 3`) if the table already uses them or if any of the given reader
+    /// features isn't implied by the current reader version. The features implied by legacy
+    /// protocol versions are carried over.
+    ///
+    /// Note that reader-writer features must be given both as reader and as writer feature.
+    pub(crate) fn with_features(
+        &self,
+        reader_features: impl IntoIterator<Item = ReaderFeature>,
+        writer_features: impl IntoIterator<Item = WriterFeature>,
+    ) -> DeltaResult<Protocol> {
+        fn extend<T: PartialEq>(features: &mut Vec<T>, new_features: impl IntoIterator<Item = T>) {
+            for feature in new_features {
+                if !features.contains(&feature) {
+                    features.push(feature);
+                }
+            }
+        }
+
+        let mut new_reader_features = self.effective_reader_features();
+        let implied_reader_features = new_reader_features.len();
+        extend(&mut new_reader_features, reader_features);
+        let (min_reader_version, new_reader_features) = if self.min_reader_version == 3
+            || new_reader_features.len() > implied_reader_features
+        {
+            (3, Some(new_reader_features))
+        } else {
+            (self.min_reader_version, None)
+        };
+
+        let mut new_writer_features = self.effective_writer_features();
+        extend(&mut new_writer_features, writer_features);
+
+        Protocol::try_new(
+            min_reader_version,
+            7,
+            new_reader_features,
+            Some(new_writer_features),
+        )
+    }
+
+    /// Returns a copy of this protocol that additionally supports the given table features, along
+    /// with the features they require (e.g. `domainMetadata` for `rowTracking`). Reader-writer
+    /// features are added both as reader and as writer feature. If this protocol already supports
+    /// all of them, it is returned unchanged; otherwise the result uses table features, as
+    /// described in [`Self::with_features`].
+    #[internal_api]
+    pub(crate) fn upgrade_with_features(&self, features: &[TableFeature]) -> DeltaResult<Protocol> {
+        let mut features = features.to_vec();
+        let mut i = 0;
+        while i < features.len() {
+            for required in features[i].required_features() {
+                if !features.contains(&required) {
+                    features.push(required);
+                }
+            }
+            i += 1;
+        }
+
+        let reader_features = self.effective_reader_features();
+        let writer_features = self.effective_writer_features();
+        let missing_features: Vec<_> = features
+            .into_iter()
+            .filter(|feature| {
+                !writer_features.contains(feature.writer_feature())
+                    || feature
+                        .reader_feature()
+                        .is_some_and(|feature| !reader_features.contains(&feature))
+            })
+            .collect();
+        if missing_features.is_empty() {
+            return Ok(self.clone());
+        }
+        self.with_features(
+            missing_features
+                .iter()
+                .filter_map(TableFeature::reader_feature),
+            missing_features
+                .iter()
+                .map(|feature| feature.writer_feature().clone()),
+        )
+    }
+
+    /// Returns the equivalent protocol with the lowest legacy reader and writer versions (i.e.
+    /// without table features), which older clients may be able to read and write. This is only
+    /// possible if the features of this protocol are exactly those implied by some legacy
+    /// versions, since legacy versions cannot express arbitrary sets of features. Returns an error
+    /// otherwise.
+    #[internal_api]
+    pub(crate) fn try_downgrade(&self) -> DeltaResult<Protocol> {
+        fn same_features<T: Eq + Hash>(a: &[T], b: &[T]) -> bool {
+            a.iter().collect::<HashSet<_>>() == b.iter().collect::<HashSet<_>>()
+        }
+
+        let reader_features = self.effective_reader_features();
+        let writer_features = self.effective_writer_features();
+        let min_reader_version = (1..=2)
+            .find(|version| same_features(&legacy_reader_features(*version), &reader_features))
+            .ok_or_else(|| {
+                Error::unsupported(format!(
+                    "Cannot downgrade protocol: reader features [{}] are not implied by any legacy reader version",
+                    reader_features.iter().join(", ")
+                ))
+            })?;
+        let min_writer_version = (1..=6)
+            .find(|version| same_features(&legacy_writer_features(*version), &writer_features))
+            .ok_or_else(|| {
+                Error::unsupported(format!(
+                    "Cannot downgrade protocol: writer features [{}] are not implied by any legacy writer version",
+                    writer_features.iter().join(", ")
+                ))
+            })?;
+        Protocol::try_new(
+            min_reader_version,
+            min_writer_version,
+            None::<Vec<String>>,
+            None::<Vec<String>>,
+        )
+    }
+
+    /// The reader features of this protocol, including those implied by a legacy reader version.
+    pub(crate) fn effective_reader_features(&self) -> Vec<ReaderFeature> {
+        match &self.reader_features {
+            Some(features) if self.min_reader_version == 3 => features.clone(),
+            _ => legacy_reader_features(self.min_reader_version),
+        }
+    }
+
+    /// The writer features of this protocol, including those implied by a legacy writer version.
+    pub(crate) fn effective_writer_features(&self) -> Vec<WriterFeature> {
+        match &self.writer_features {
+            Some(features) if self.min_writer_version == 7 => features.clone(),
+            _ => legacy_writer_features(self.min_writer_version),
+        }
+    }
+
     /// True if this protocol has the requested reader feature
     pub(crate) fn has_reader_feature(&self, feature: &ReaderFeature) -> bool {
         self.reader_features()
@@ -531,6 +713,31 @@ impl Protocol {
     }
 }
 
+/// The reader features implied by a legacy (i.e. < 3) reader version.
+fn legacy_reader_features(min_reader_version: i32) -> Vec<ReaderFeature> {
+    match min_reader_version {
+        2 => vec![ReaderFeature::ColumnMapping],
+        _ => vec![],
+    }
+}
+
+/// The writer features implied by a legacy (i.e. < 7) writer version.
+fn legacy_writer_features(min_writer_version: i32) -> Vec<WriterFeature> {
+    [
+        (2, WriterFeature::AppendOnly),
+        (2, WriterFeature::Invariants),
+        (3, WriterFeature::CheckConstraints),
+        (4, WriterFeature::ChangeDataFeed),
+        (4, WriterFeature::GeneratedColumns),
+        (5, WriterFeature::ColumnMapping),
+        (6, WriterFeature::IdentityColumns),
+    ]
+    .into_iter()
+    .filter(|(version, _)| min_writer_version >= *version)
+    .map(|(_, feature)| feature)
+    .collect()
+}
+
 // TODO: implement Scalar::From<HashMap<K, V>> so we can derive IntoEngineData using a macro (issue#1083)
 impl IntoEngineData for Protocol {
     fn into_engine_data(
@@ -743,6 +950,67 @@ impl Add {
     }
 }
 
+/// Convert an optional string map into a map scalar (or a null scalar of the map type).
+fn string_map_to_scalar(
+    map: Option<HashMap<String, String>>,
+    value_contains_null: bool,
+) -> DeltaResult<Scalar> {
+    let map_type = MapType::new(DataType::STRING, DataType::STRING, value_contains_null);
+    match map {
+        Some(map) => Ok(MapData::try_new(map_type, map)?.into()),
+        None => Ok(Scalar::Null(map_type.into())),
+    }
+}
+
+/// Convert an optional deletion vector descriptor into the scalars of its (leaf) fields.
+fn deletion_vector_to_scalars(dv: Option<DeletionVectorDescriptor>) -> [Scalar; 5] {
+    match dv {
+        Some(dv) => [
+            dv.storage_type.into(),
+            dv.path_or_inline_dv.into(),
+            dv.offset.into(),
+            dv.size_in_bytes.into(),
+            dv.cardinality.into(),
+        ],
+        None => [
+            Scalar::Null(DataType::STRING),
+            Scalar::Null(DataType::STRING),
+            Scalar::Null(DataType::INTEGER),
+            Scalar::Null(DataType::INTEGER),
+            Scalar::Null(DataType::LONG),
+        ],
+    }
+}
+
+// TODO: derive IntoEngineData instead (see issue #1083)
+impl IntoEngineData for Add {
+    fn into_engine_data(
+        self,
+        schema: SchemaRef,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let values: Vec<Scalar> = [
+            self.path.into(),
+            string_map_to_scalar(Some(self.partition_values), true)?,
+            self.size.into(),
+            self.modification_time.into(),
+            self.data_change.into(),
+            self.stats.into(),
+            string_map_to_scalar(self.tags, false)?,
+        ]
+        .into_iter()
+        .chain(deletion_vector_to_scalars(self.deletion_vector))
+        .chain([
+            self.base_row_id.into(),
+            self.default_row_commit_version.into(),
+            self.clustering_provider.into(),
+        ])
+        .collect();
+
+        engine.evaluation_handler().create_one(schema, &values)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
 #[internal_api]
 #[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
@@ -793,6 +1061,34 @@ pub(crate) struct Remove {
     pub(crate) default_row_commit_version: Option<i64>,
 }
 
+// TODO: derive IntoEngineData instead (see issue #1083)
+impl IntoEngineData for Remove {
+    fn into_engine_data(
+        self,
+        schema: SchemaRef,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let values: Vec<Scalar> = [
+            self.path.into(),
+            self.deletion_timestamp.into(),
+            self.data_change.into(),
+            self.extended_file_metadata.into(),
+            string_map_to_scalar(self.partition_values, false)?,
+            self.size.into(),
+            string_map_to_scalar(self.tags, false)?,
+        ]
+        .into_iter()
+        .chain(deletion_vector_to_scalars(self.deletion_vector))
+        .chain([
+            self.base_row_id.into(),
+            self.default_row_commit_version.into(),
+        ])
+        .collect();
+
+        engine.evaluation_handler().create_one(schema, &values)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, ToSchema)]
 #[internal_api]
 #[cfg_attr(test, derive(Serialize, Default), serde(rename_all = "camelCase"))]
@@ -939,6 +1235,16 @@ impl DomainMetadata {
         }
     }
 
+    /// Create a new DomainMetadata tombstone, which removes the domain. Per the Delta protocol, the
+    /// `configuration` should be the latest configuration of the removed domain.
+    pub(crate) fn remove(domain: String, configuration: String) -> Self {
+        Self {
+            domain,
+            configuration,
+            removed: true,
+        }
+    }
+
     // returns true if the domain metadata is an system-controlled domain (all domains that start
     // with "delta.")
     #[allow(unused)]
@@ -949,6 +1255,10 @@ impl DomainMetadata {
     pub(crate) fn domain(&self) -> &str {
         &self.domain
     }
+
+    pub(crate) fn is_removed(&self) -> bool {
+        self.removed
+    }
 }
 
 #[cfg(test)]
@@ -1370,12 +1680,12 @@ mod tests {
             3,
             7,
             Some([ReaderFeature::Unknown("unsupported reader".to_string())]),
-            Some([WriterFeature::IdentityColumns]),
+            Some([WriterFeature::CheckConstraints]),
         )
         .unwrap();
         assert_result_error_with_message(
             protocol.ensure_write_supported(),
-            r#"Unsupported: Unknown WriterFeatures: "identityColumns". Supported WriterFeatures: "appendOnly", "deletionVectors", "domainMetadata", "invariants", "rowTracking", "timestampNtz", "variantType", "variantType-preview", "variantShredding-preview""#,
+            r#"Unsupported: Unknown WriterFeatures: "checkConstraints". Supported WriterFeatures: "allowColumnDefaults", "appendOnly", "changeDataFeed", "columnMapping", "deletionVectors", "domainMetadata", "generatedColumns", "icebergCompatV2", "identityColumns", "inCommitTimestamp", "invariants", "rowTracking", "timestampNtz", "vacuumProtocolCheck", "variantType", "variantType-preview", "variantShredding-preview""#,
         );
 
         // Unknown writer features should cause an error
@@ -1388,7 +1698,206 @@ mod tests {
         .unwrap();
         assert_result_error_with_message(
             protocol.ensure_write_supported(),
-            r#"Unsupported: Unknown WriterFeatures: "unsupported writer". Supported WriterFeatures: "appendOnly", "deletionVectors", "domainMetadata", "invariants", "rowTracking", "timestampNtz", "variantType", "variantType-preview", "variantShredding-preview""#,
+            r#"Unsupported: Unknown WriterFeatures: "unsupported writer". Supported WriterFeatures: "allowColumnDefaults", "appendOnly", "changeDataFeed", "columnMapping", "deletionVectors", "domainMetadata", "generatedColumns", "icebergCompatV2", "identityColumns", "inCommitTimestamp", "invariants", "rowTracking", "timestampNtz", "vacuumProtocolCheck", "variantType", "variantType-preview", "variantShredding-preview""#,
+        );
+    }
+
+    #[test]
+    fn test_protocol_feature_names() {
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        assert!(protocol.reader_feature_names().is_none());
+        assert!(protocol.writer_feature_names().is_none());
+
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some(["deletionVectors", "futureReaderFeature"]),
+            Some(["deletionVectors", "variantType-preview"]),
+        )
+        .unwrap();
+        assert_eq!(
+            protocol.reader_feature_names().unwrap(),
+            ["deletionVectors", "futureReaderFeature"]
+        );
+        assert_eq!(
+            protocol.writer_feature_names().unwrap(),
+            ["deletionVectors", "variantType-preview"]
+        );
+    }
+
+    #[test]
+    fn test_protocol_with_features() {
+        // legacy protocol: implied features are carried over, reader version is kept if possible
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let upgraded = protocol
+            .with_features([], [WriterFeature::DomainMetadata])
+            .unwrap();
+        assert_eq!(upgraded.min_reader_version(), 1);
+        assert_eq!(upgraded.min_writer_version(), 7);
+        assert_eq!(upgraded.reader_features(), None);
+        assert_eq!(
+            upgraded.writer_features(),
+            Some(
+                [
+                    WriterFeature::AppendOnly,
+                    WriterFeature::Invariants,
+                    WriterFeature::DomainMetadata
+                ]
+                .as_slice()
+            )
+        );
+
+        // reader-writer features bump the reader version
+        let upgraded = protocol
+            .with_features(
+                [ReaderFeature::ColumnMapping],
+                [WriterFeature::ColumnMapping],
+            )
+            .unwrap();
+        assert_eq!(upgraded.min_reader_version(), 3);
+        assert_eq!(
+            upgraded.reader_features(),
+            Some([ReaderFeature::ColumnMapping].as_slice())
+        );
+        assert!(upgraded.has_writer_feature(&WriterFeature::ColumnMapping));
+
+        // table features protocol: existing features are kept and not duplicated
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors]),
+            Some([
+                WriterFeature::DeletionVectors,
+                WriterFeature::DomainMetadata,
+            ]),
+        )
+        .unwrap();
+        let upgraded = protocol
+            .with_features([], [WriterFeature::DomainMetadata])
+            .unwrap();
+        assert_eq!(upgraded, protocol);
+    }
+
+    #[test]
+    fn test_protocol_upgrade_with_features() {
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+
+        // features implied by the legacy versions don't change the protocol
+        let upgraded = protocol
+            .upgrade_with_features(&[WriterFeature::AppendOnly.into()])
+            .unwrap();
+        assert_eq!(upgraded, protocol);
+
+        // reader-writer features are added as reader and writer features
+        let upgraded = protocol
+            .upgrade_with_features(&[WriterFeature::DeletionVectors.into()])
+            .unwrap();
+        assert_eq!(upgraded.min_reader_version(), 3);
+        assert_eq!(upgraded.min_writer_version(), 7);
+        assert_eq!(
+            upgraded.reader_features(),
+            Some([ReaderFeature::DeletionVectors].as_slice())
+        );
+        assert_eq!(
+            upgraded.writer_features(),
+            Some(
+                [
+                    WriterFeature::AppendOnly,
+                    WriterFeature::Invariants,
+                    WriterFeature::DeletionVectors
+                ]
+                .as_slice()
+            )
+        );
+
+        // required features are added as well
+        let upgraded = protocol
+            .upgrade_with_features(&[WriterFeature::RowTracking.into()])
+            .unwrap();
+        assert_eq!(upgraded.min_reader_version(), 1);
+        assert!(upgraded.has_writer_feature(&WriterFeature::RowTracking));
+        assert!(upgraded.has_writer_feature(&WriterFeature::DomainMetadata));
+        upgraded.ensure_write_supported().unwrap();
+
+        // a legacy reader version implying the reader feature is kept
+        let protocol = Protocol::try_new(2, 5, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let upgraded = protocol
+            .upgrade_with_features(&[
+                WriterFeature::ColumnMapping.into(),
+                WriterFeature::DomainMetadata.into(),
+            ])
+            .unwrap();
+        assert_eq!(upgraded.min_reader_version(), 2);
+        assert_eq!(upgraded.min_writer_version(), 7);
+        assert!(upgraded.has_writer_feature(&WriterFeature::ColumnMapping));
+        assert!(upgraded.has_writer_feature(&WriterFeature::DomainMetadata));
+    }
+
+    #[test]
+    fn test_protocol_try_downgrade() {
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::ColumnMapping]),
+            Some([
+                WriterFeature::AppendOnly,
+                WriterFeature::Invariants,
+                WriterFeature::CheckConstraints,
+                WriterFeature::ChangeDataFeed,
+                WriterFeature::GeneratedColumns,
+                WriterFeature::ColumnMapping,
+            ]),
+        )
+        .unwrap();
+        let downgraded = protocol.try_downgrade().unwrap();
+        assert_eq!(
+            downgraded,
+            Protocol::try_new(2, 5, None::<Vec<String>>, None::<Vec<String>>).unwrap()
+        );
+
+        let protocol = Protocol::try_new(
+            1,
+            7,
+            None::<Vec<String>>,
+            Some([WriterFeature::Invariants, WriterFeature::AppendOnly]),
+        )
+        .unwrap();
+        let downgraded = protocol.try_downgrade().unwrap();
+        assert_eq!(downgraded.min_reader_version(), 1);
+        assert_eq!(downgraded.min_writer_version(), 2);
+
+        // upgrading and downgrading legacy features roundtrips
+        let protocol = Protocol::try_new(1, 1, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let downgraded = protocol
+            .with_features([], [])
+            .and_then(|protocol| protocol.try_downgrade())
+            .unwrap();
+        assert_eq!(downgraded, protocol);
+
+        // legacy versions would imply features the table doesn't support
+        let protocol = Protocol::try_new(
+            1,
+            7,
+            None::<Vec<String>>,
+            Some([WriterFeature::ChangeDataFeed]),
+        )
+        .unwrap();
+        assert_result_error_with_message(
+            protocol.try_downgrade(),
+            "Cannot downgrade protocol: writer features [changeDataFeed] are not implied by any legacy writer version",
+        );
+
+        // table features without legacy versions
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors]),
+            Some([WriterFeature::DeletionVectors]),
+        )
+        .unwrap();
+        assert_result_error_with_message(
+            protocol.try_downgrade(),
+            "Cannot downgrade protocol: reader features [deletionVectors] are not implied by any legacy reader version",
         );
     }
 
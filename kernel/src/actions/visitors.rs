@@ -6,7 +6,7 @@ use std::sync::{Arc, LazyLock};
 
 use delta_kernel_derive::internal_api;
 
-use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::engine_data::{GetData, PrimitiveColumn, RowVisitor, TypedGetData as _};
 use crate::schema::{column_name, ColumnName, ColumnNamesAndTypes, DataType, Schema, StructField};
 use crate::utils::require;
 use crate::{DeltaResult, Error};
@@ -24,7 +24,7 @@ pub(crate) struct MetadataVisitor {
 impl RowVisitor for MetadataVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| Metadata::to_schema().leaves(METADATA_NAME));
+            LazyLock::new(|| Metadata::to_schema().column_names_and_types(METADATA_NAME));
         NAMES_AND_TYPES.as_ref()
     }
 
@@ -52,6 +52,24 @@ impl RowVisitor for SelectionVectorVisitor {
         NAMES_AND_TYPES.as_ref()
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        self.visit_getters(row_count, getters)
+    }
+    fn visit_primitive<'a>(
+        &mut self,
+        row_count: usize,
+        columns: &'a [PrimitiveColumn<'a>],
+    ) -> DeltaResult<()> {
+        let getters: Vec<_> = columns.iter().collect();
+        self.visit_getters(row_count, &getters)
+    }
+}
+
+impl SelectionVectorVisitor {
+    fn visit_getters<'a, G: GetData<'a> + ?Sized>(
+        &mut self,
+        row_count: usize,
+        getters: &[&'a G],
+    ) -> DeltaResult<()> {
         require!(
             getters.len() == 1,
             Error::InternalError(format!(
@@ -59,6 +77,7 @@ impl RowVisitor for SelectionVectorVisitor {
                 getters.len()
             ))
         );
+        self.selection_vector.reserve(row_count);
         for i in 0..row_count {
             self.selection_vector
                 .push(getters[0].get(i, "selectionvector.output")?);
@@ -76,7 +95,7 @@ pub(crate) struct ProtocolVisitor {
 impl RowVisitor for ProtocolVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| Protocol::to_schema().leaves(PROTOCOL_NAME));
+            LazyLock::new(|| Protocol::to_schema().column_names_and_types(PROTOCOL_NAME));
         NAMES_AND_TYPES.as_ref()
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
@@ -99,7 +118,7 @@ pub(crate) struct AddVisitor {
 
 impl AddVisitor {
     #[internal_api]
-    fn visit_add<'a>(
+    pub(crate) fn visit_add<'a>(
         row_index: usize,
         path: String,
         getters: &[&'a dyn GetData<'a>],
@@ -143,7 +162,7 @@ impl AddVisitor {
     }
     pub(crate) fn names_and_types() -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| Add::to_schema().leaves(ADD_NAME));
+            LazyLock::new(|| Add::to_schema().column_names_and_types(ADD_NAME));
         NAMES_AND_TYPES.as_ref()
     }
 }
@@ -218,7 +237,7 @@ impl RemoveVisitor {
     }
     pub(crate) fn names_and_types() -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| Remove::to_schema().leaves(REMOVE_NAME));
+            LazyLock::new(|| Remove::to_schema().column_names_and_types(REMOVE_NAME));
         NAMES_AND_TYPES.as_ref()
     }
 }
@@ -265,7 +284,7 @@ impl CdcVisitor {
 impl RowVisitor for CdcVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| Cdc::to_schema().leaves(CDC_NAME));
+            LazyLock::new(|| Cdc::to_schema().column_names_and_types(CDC_NAME));
         NAMES_AND_TYPES.as_ref()
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
@@ -341,8 +360,9 @@ impl SetTransactionVisitor {
 
 impl RowVisitor for SetTransactionVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
-        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| SetTransaction::to_schema().leaves(SET_TRANSACTION_NAME));
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            SetTransaction::to_schema().column_names_and_types(SET_TRANSACTION_NAME)
+        });
         NAMES_AND_TYPES.as_ref()
     }
 
@@ -399,7 +419,7 @@ impl SidecarVisitor {
 impl RowVisitor for SidecarVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| Sidecar::to_schema().leaves(SIDECAR_NAME));
+            LazyLock::new(|| Sidecar::to_schema().column_names_and_types(SIDECAR_NAME));
         NAMES_AND_TYPES.as_ref()
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
@@ -478,8 +498,9 @@ impl DomainMetadataVisitor {
 
 impl RowVisitor for DomainMetadataVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
-        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| DomainMetadata::to_schema().leaves(DOMAIN_METADATA_NAME));
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            DomainMetadata::to_schema().column_names_and_types(DOMAIN_METADATA_NAME)
+        });
         NAMES_AND_TYPES.as_ref()
     }
 
@@ -618,14 +639,12 @@ pub(crate) fn visit_protocol_at<'a>(
 ///
 /// Only the a single row of the engine data is checked (the first row). This is because in-commit
 /// timestamps requires that the CommitInfo containing the ICT be the first action in the log.
-#[allow(unused)]
 #[derive(Default)]
 pub(crate) struct InCommitTimestampVisitor {
     pub(crate) in_commit_timestamp: Option<i64>,
 }
 
 impl InCommitTimestampVisitor {
-    #[allow(unused)]
     /// Get the schema that the visitor expects the data to have.
     pub(crate) fn schema() -> Arc<Schema> {
         static SCHEMA: LazyLock<Arc<Schema>> = LazyLock::new(|| {
@@ -823,7 +842,7 @@ mod tests {
         };
         let expected = vec![add1, add2, add3];
         assert_eq!(add_visitor.adds.len(), expected.len());
-        for (add, expected) in add_visitor.adds.into_iter().zip(expected.into_iter()) {
+        for (add, expected) in add_visitor.adds.into_iter().zip(expected) {
             assert_eq!(add, expected);
         }
     }
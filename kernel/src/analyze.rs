@@ -0,0 +1,411 @@
+//! This module implements computing the statistics of the data files of a table, i.e. ANALYZE
+//! (`COMPUTE STATISTICS`).
+//!
+//! The entry point for this API is [`Snapshot::analyze`]. Analyzing a table reads the data files
+//! whose statistics are missing or stale, computes their statistics, and commits a new version
+//! that replaces their `add` actions with ones that carry the new statistics. A file's statistics
+//! are stale if they don't include the null count of each of the table's stats columns (see
+//! [`WriteContext::stats_columns_schema`]), e.g. because the file was written by a writer that
+//! doesn't collect statistics, or before `delta.dataSkippingStatsColumns` was changed.
+//!
+//! The statistics are collected like the default engine does when writing data files: the number
+//! of records, and the null count, minimum and maximum value of each stats column. The new `add`
+//! actions are committed with `dataChange = false`, since the data of the table doesn't change.
+//! Files with deletion vectors are skipped, since their statistics must only cover the rows that
+//! aren't deleted.
+//!
+//! ```no_run
+//! # use delta_kernel::{DeltaResult, Engine, Snapshot};
+//! # fn analyze(engine: &dyn Engine) -> DeltaResult<()> {
+//! let url = delta_kernel::try_parse_uri("./tests/data/basic_partitioned")?;
+//! let snapshot = Snapshot::builder_for(url).build(engine)?;
+//! if snapshot.analyze().commit(engine)?.is_none() {
+//!     println!("All files already have statistics");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Note that the data files are read with the [`ParquetHandler`] of the engine, which must return
+//! [`ArrowEngineData`].
+//!
+//! [`Snapshot::analyze`]: crate::Snapshot::analyze
+//! [`WriteContext::stats_columns_schema`]: crate::transaction::WriteContext::stats_columns_schema
+//! [`ParquetHandler`]: crate::ParquetHandler
+//! [`ArrowEngineData`]: crate::engine::arrow_data::ArrowEngineData
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serde_json::Value;
+
+use crate::actions::Add;
+use crate::arrow::array::{AsArray, RecordBatch};
+use crate::arrow::compute::concat_batches;
+use crate::arrow::datatypes::Schema as ArrowSchema;
+use crate::engine::arrow_conversion::TryIntoArrow as _;
+use crate::engine::arrow_data::extract_record_batch;
+use crate::engine::arrow_expression::evaluate_expression::to_json;
+use crate::engine::arrow_stats::{collect_column_stats, file_stats};
+use crate::schema::{SchemaRef, StructType};
+use crate::snapshot::SnapshotRef;
+use crate::transaction::CommitResult;
+use crate::{DeltaResult, Engine, Error, FileMeta};
+
+/// Computes the statistics of the data files of a table. Created with [`Snapshot::analyze`].
+///
+/// [`Snapshot::analyze`]: crate::Snapshot::analyze
+#[derive(Debug)]
+pub struct Analyze {
+    snapshot: SnapshotRef,
+    recompute_all: bool,
+    engine_info: Option<String>,
+}
+
+impl Analyze {
+    pub(crate) fn new(snapshot: SnapshotRef) -> Self {
+        Self {
+            snapshot,
+            recompute_all: false,
+            engine_info: None,
+        }
+    }
+
+    /// Recompute the statistics of all files (without deletion vectors), not just of those whose
+    /// statistics are missing or stale.
+    pub fn with_recompute_all(mut self, recompute_all: bool) -> Self {
+        self.recompute_all = recompute_all;
+        self
+    }
+
+    /// Set the engine info field of the commit info action of the analyze commit.
+    pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
+        self.engine_info = Some(engine_info.into());
+        self
+    }
+
+    /// Compute the statistics of the files that need them, and commit a new table version with
+    /// the updated `add` actions. Returns `None` (without committing) if no file needs new
+    /// statistics. Like [`Transaction::commit`], this returns a [`CommitResult::Conflict`] if
+    /// another writer committed the next table version first.
+    ///
+    /// [`Transaction::commit`]: crate::transaction::Transaction::commit
+    pub fn commit(self, engine: &dyn Engine) -> DeltaResult<Option<CommitResult>> {
+        let table_configuration = self.snapshot.table_configuration();
+        table_configuration.ensure_write_supported()?;
+        let stats_columns = table_configuration.stats_columns_schema();
+
+        let mut files: Vec<Add> = crate::restore::live_files(&self.snapshot, engine)?
+            .into_values()
+            .filter(|add| add.deletion_vector.is_none())
+            .filter(|add| self.recompute_all || !has_stats(add, stats_columns.as_deref()))
+            .sorted_by(|a, b| a.path.cmp(&b.path))
+            .collect();
+        if files.is_empty() {
+            return Ok(None);
+        }
+        for add in &mut files {
+            let stats = self.compute_stats(engine, add, stats_columns.as_ref())?;
+            add.stats = Some(stats);
+        }
+
+        let mut transaction = self
+            .snapshot
+            .clone()
+            .transaction()?
+            .with_operation("COMPUTE STATS".to_string())
+            .with_stats_updates(files);
+        if let Some(engine_info) = self.engine_info {
+            transaction = transaction.with_engine_info(engine_info);
+        }
+        transaction.commit(engine).map(Some)
+    }
+
+    /// The physical schema of the non-partition columns of the table.
+    fn data_schema(&self) -> DeltaResult<SchemaRef> {
+        let table_configuration = self.snapshot.table_configuration();
+        let partition_columns = table_configuration.metadata().partition_columns();
+        let fields = table_configuration
+            .schema()
+            .fields()
+            .filter(|field| !partition_columns.contains(field.name()))
+            .map(|field| field.make_physical(table_configuration.column_mapping_mode()))
+            .collect_vec();
+        Ok(Arc::new(StructType::try_new(fields)?))
+    }
+
+    /// Read the stats columns of the data file of `add`, and return its statistics as JSON.
+    fn compute_stats(
+        &self,
+        engine: &dyn Engine,
+        add: &Add,
+        stats_columns: Option<&SchemaRef>,
+    ) -> DeltaResult<String> {
+        let location = self.snapshot.table_root().join(&add.path)?;
+        let size = add
+            .size
+            .try_into()
+            .map_err(|_| Error::generic(format!("Invalid size of data file {location}")))?;
+        let file = FileMeta::new(location, add.modification_time, size);
+        // only the number of records is needed if the table has no stats columns, which is counted
+        // from the data columns of the file
+        let read_schema = match stats_columns {
+            Some(stats_columns) => stats_columns.clone(),
+            None => self.data_schema()?,
+        };
+        let batches: Vec<RecordBatch> = engine
+            .parquet_handler()
+            .read_parquet_files(&[file], read_schema.clone(), None)?
+            .map(|data| extract_record_batch(data?.as_ref()).cloned())
+            .try_collect()?;
+        let arrow_schema: ArrowSchema = read_schema.as_ref().try_into_arrow()?;
+        let batch = concat_batches(&Arc::new(arrow_schema), &batches)?;
+
+        let column_stats = stats_columns
+            .map(|stats_columns| collect_column_stats(&batch, stats_columns))
+            .transpose()?;
+        let stats = file_stats(batch.num_rows(), column_stats.as_ref())?;
+        let json = to_json(&stats)?;
+        Ok(json.as_string::<i32>().value(0).to_string())
+    }
+}
+
+/// Whether `add` has statistics that include the number of records and the null count of each of
+/// the `stats_columns`. Min and max values aren't checked, since they are legitimately missing
+/// for columns whose values are all null, or of types without min and max values.
+fn has_stats(add: &Add, stats_columns: Option<&StructType>) -> bool {
+    let Some(stats) = add.stats.as_deref() else {
+        return false;
+    };
+    let Ok(stats) = serde_json::from_str::<Value>(stats) else {
+        return false;
+    };
+    if stats.get("numRecords").is_none() {
+        return false;
+    }
+    let Some(stats_columns) = stats_columns else {
+        return true;
+    };
+    stats_columns.leaves().all(|(column, _, _)| {
+        column
+            .iter()
+            .try_fold(&stats["nullCount"], |stats, name| stats.get(name))
+            .is_some()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::json;
+    use url::Url;
+
+    use crate::arrow::array::{ArrayRef, Int32Array, StringArray};
+    use crate::arrow::datatypes::{DataType, Field};
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::parquet::arrow::ArrowWriter;
+    use crate::Snapshot;
+
+    use super::*;
+
+    fn commit_path(version: u64) -> Path {
+        Path::from(format!("test_table/_delta_log/{version:020}.json"))
+    }
+
+    fn metadata_action(config: Value) -> Value {
+        let schema_string = json!({
+            "type": "struct",
+            "fields": [
+                {"name": "id", "type": "integer", "nullable": true, "metadata": {}},
+                {"name": "name", "type": "string", "nullable": true, "metadata": {}},
+            ]
+        });
+        json!({
+            "metaData": {
+                "id": "test",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema_string.to_string(),
+                "partitionColumns": [],
+                "configuration": config,
+                "createdTime": 0
+            }
+        })
+    }
+
+    // Creates a table with two data files without statistics, and a configuration of `config`
+    async fn setup_table(
+        config: Value,
+    ) -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let store = Arc::new(InMemory::new());
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///test_table/").unwrap();
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let mut adds = vec![];
+        for (i, (ids, names)) in [
+            (
+                vec![Some(1), Some(2), Some(3)],
+                vec![Some("a"), None, Some("c")],
+            ),
+            (vec![None, Some(7)], vec![Some("x"), Some("y")]),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(ids)) as ArrayRef,
+                    Arc::new(StringArray::from(names)) as ArrayRef,
+                ],
+            )
+            .unwrap();
+            let mut buffer = vec![];
+            let mut writer = ArrowWriter::try_new(&mut buffer, schema.clone(), None).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+            let path = format!("{i}.parquet");
+            adds.push(json!({
+                "add": {
+                    "path": path,
+                    "partitionValues": {},
+                    "size": buffer.len(),
+                    "modificationTime": 0,
+                    "dataChange": true
+                }
+            }));
+            let location = Path::from(format!("test_table/{path}"));
+            store.put(&location, buffer.into()).await.unwrap();
+        }
+
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let commit = [protocol, metadata_action(config)]
+            .into_iter()
+            .chain(adds)
+            .join("\n");
+        store.put(&commit_path(0), commit.into()).await.unwrap();
+        (store, engine, table_root)
+    }
+
+    // Reads the add actions of the commit of `version`, keyed by path
+    async fn read_adds(store: &InMemory, version: u64) -> HashMap<String, Value> {
+        let commit = store.get(&commit_path(version)).await.unwrap();
+        let commit = String::from_utf8(commit.bytes().await.unwrap().to_vec()).unwrap();
+        commit
+            .lines()
+            .filter_map(|line| {
+                serde_json::from_str::<Value>(line)
+                    .unwrap()
+                    .get("add")
+                    .cloned()
+            })
+            .map(|add| (add["path"].as_str().unwrap().to_string(), add))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_analyze() {
+        let (store, engine, table_root) = setup_table(json!({})).await;
+        let snapshot = Snapshot::builder_for(table_root.clone())
+            .build(&engine)
+            .unwrap();
+        let result = snapshot.analyze().commit(&engine).unwrap();
+        assert!(matches!(
+            result,
+            Some(CommitResult::Committed { version: 1, .. })
+        ));
+
+        let adds = read_adds(&store, 1).await;
+        assert_eq!(adds.len(), 2);
+        let stats = |path: &str| -> Value {
+            let add = &adds[path];
+            assert_eq!(add["dataChange"], json!(false));
+            serde_json::from_str(add["stats"].as_str().unwrap()).unwrap()
+        };
+        assert_eq!(
+            stats("0.parquet"),
+            json!({
+                "numRecords": 3,
+                "nullCount": {"id": 0, "name": 1},
+                "minValues": {"id": 1, "name": "a"},
+                "maxValues": {"id": 3, "name": "c"},
+            })
+        );
+        assert_eq!(
+            stats("1.parquet"),
+            json!({
+                "numRecords": 2,
+                "nullCount": {"id": 1, "name": 0},
+                "minValues": {"id": 7, "name": "x"},
+                "maxValues": {"id": 7, "name": "y"},
+            })
+        );
+
+        // all files have statistics now, unless they are recomputed
+        let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+        assert!(snapshot
+            .clone()
+            .analyze()
+            .commit(&engine)
+            .unwrap()
+            .is_none());
+        let result = snapshot
+            .analyze()
+            .with_recompute_all(true)
+            .commit(&engine)
+            .unwrap();
+        assert!(matches!(
+            result,
+            Some(CommitResult::Committed { version: 2, .. })
+        ));
+        assert_eq!(read_adds(&store, 2).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_stale_stats() {
+        let (store, engine, table_root) =
+            setup_table(json!({"delta.dataSkippingNumIndexedCols": "1"})).await;
+        let snapshot = Snapshot::builder_for(table_root.clone())
+            .build(&engine)
+            .unwrap();
+        snapshot.analyze().commit(&engine).unwrap();
+        let stats: Value = serde_json::from_str(
+            read_adds(&store, 1).await["0.parquet"]["stats"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stats["nullCount"], json!({"id": 0}));
+
+        // the stats no longer cover all stats columns once more columns are indexed
+        let metadata = metadata_action(json!({})).to_string();
+        store.put(&commit_path(2), metadata.into()).await.unwrap();
+        let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+        assert!(snapshot.analyze().commit(&engine).unwrap().is_some());
+        let stats: Value = serde_json::from_str(
+            read_adds(&store, 3).await["0.parquet"]["stats"]
+                .as_str()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stats["nullCount"], json!({"id": 0, "name": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_analyze_without_stats_columns() {
+        let (store, engine, table_root) =
+            setup_table(json!({"delta.dataSkippingNumIndexedCols": "0"})).await;
+        let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+        snapshot.analyze().commit(&engine).unwrap();
+        let adds = read_adds(&store, 1).await;
+        assert_eq!(adds["0.parquet"]["stats"], json!(r#"{"numRecords":3}"#));
+        assert_eq!(adds["1.parquet"]["stats"], json!(r#"{"numRecords":2}"#));
+    }
+}
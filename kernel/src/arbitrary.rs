@@ -0,0 +1,402 @@
+//! [`proptest`] strategies that generate schemas, scalars and expressions, for fuzzing code that
+//! consumes them, such as the visitors of an engine or the FFI schema and expression builders.
+//! Enable them with the `proptest` feature.
+//!
+//! [`DataType`], [`StructType`], [`Scalar`], [`Expression`] and [`Predicate`] implement
+//! [`Arbitrary`], bounded by [`ArbitraryParams`]. The generated values respect the invariants the
+//! kernel enforces when constructing them:
+//! - schemas pass [`StructType::try_new`] and [`StructType::validate_limits`]; in particular, no
+//!   struct has two fields whose names differ only by case
+//! - decimal types have a precision of 1 to 38 and a scale of at most their precision, and decimal
+//!   values fit their precision
+//! - struct, array and map scalars match their type, and are only null where their type allows
+//! - floating point scalars are never NaN, so that primitive scalars equal themselves
+//!
+//! Expressions and predicates are well-formed trees, but are not type checked, e.g. they may add a
+//! string to a column that doesn't exist. Variant types, opaque expressions and predicates, and
+//! transforms are never generated.
+//!
+//! ```
+//! use delta_kernel::schema::StructType;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn schema_round_trips_through_json(schema: StructType) {
+//!         let json = serde_json::to_string(&schema).unwrap();
+//!         prop_assert_eq!(serde_json::from_str::<StructType>(&json).unwrap(), schema);
+//!     }
+//! }
+//! schema_round_trips_through_json();
+//! ```
+
+use itertools::Itertools;
+use proptest::collection::vec;
+use proptest::num::{f32, f64};
+use proptest::prelude::*;
+
+use crate::expressions::{
+    ArrayData, BinaryExpressionOp, BinaryPredicateOp, ColumnName, DecimalData, Expression,
+    JunctionPredicateOp, MapData, Predicate, Scalar, StructData, UnaryExpressionOp,
+    VariadicExpressionOp,
+};
+use crate::schema::{
+    ArrayType, DataType, DecimalType, MapType, PrimitiveType, StructField, StructType,
+};
+
+/// Bounds on the shape of the values generated by the [`Arbitrary`] implementations of this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitraryParams {
+    /// The maximum nesting depth of types, scalars, expressions and predicates. Primitive types,
+    /// primitive scalars, and expressions without children are at depth 0.
+    pub max_depth: u32,
+    /// The maximum number of fields of a struct, elements of an array or map, and children of an
+    /// expression or predicate.
+    pub max_width: usize,
+}
+
+impl Default for ArbitraryParams {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_width: 4,
+        }
+    }
+}
+
+/// Generates field names, including names that need quoting in a [`ColumnName`].
+pub fn field_name() -> impl Strategy<Value = String> {
+    "[a-zA-Z_][a-zA-Z0-9_ .`]{0,11}"
+}
+
+/// Generates column names of one to three fields.
+pub fn column_name() -> impl Strategy<Value = ColumnName> {
+    vec(field_name(), 1..=3).prop_map(ColumnName::new)
+}
+
+/// Generates decimal types with a precision of 1 to 38, and a scale of at most their precision.
+pub fn decimal_type() -> impl Strategy<Value = DecimalType> {
+    (1u8..=38)
+        .prop_flat_map(|precision| (Just(precision), 0..=precision))
+        .prop_filter_map("invalid decimal type", |(precision, scale)| {
+            DecimalType::try_new(precision, scale).ok()
+        })
+}
+
+/// Generates primitive types.
+pub fn primitive_type() -> impl Strategy<Value = PrimitiveType> {
+    prop_oneof![
+        Just(PrimitiveType::String),
+        Just(PrimitiveType::Long),
+        Just(PrimitiveType::Integer),
+        Just(PrimitiveType::Short),
+        Just(PrimitiveType::Byte),
+        Just(PrimitiveType::Float),
+        Just(PrimitiveType::Double),
+        Just(PrimitiveType::Boolean),
+        Just(PrimitiveType::Binary),
+        Just(PrimitiveType::Date),
+        Just(PrimitiveType::Timestamp),
+        Just(PrimitiveType::TimestampNtz),
+        decimal_type().prop_map(PrimitiveType::Decimal),
+    ]
+}
+
+/// Generates struct types of one to `max_width` fields whose types are generated by `field_type`.
+/// Fields whose names equal the name of a previous field, ignoring case, are dropped.
+fn struct_type_of(
+    field_type: impl Strategy<Value = DataType>,
+    max_width: usize,
+) -> impl Strategy<Value = StructType> {
+    let field = (field_name(), field_type, any::<bool>())
+        .prop_map(|(name, data_type, nullable)| StructField::new(name, data_type, nullable));
+    vec(field, 1..=max_width.max(1)).prop_filter_map("invalid struct type", |fields| {
+        let fields = fields
+            .into_iter()
+            .unique_by(|field| field.name().to_lowercase());
+        StructType::try_new(fields).ok()
+    })
+}
+
+impl Arbitrary for DataType {
+    type Parameters = ArbitraryParams;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        let ArbitraryParams {
+            max_depth,
+            max_width,
+        } = params;
+        let size = max_width.saturating_pow(max_depth) as u32;
+        primitive_type()
+            .prop_map(DataType::Primitive)
+            .prop_recursive(max_depth, size, max_width as u32, move |inner| {
+                prop_oneof![
+                    (inner.clone(), any::<bool>()).prop_map(|(element_type, contains_null)| {
+                        ArrayType::new(element_type, contains_null).into()
+                    }),
+                    (primitive_type(), inner.clone(), any::<bool>()).prop_map(
+                        |(key_type, value_type, value_contains_null)| {
+                            MapType::new(key_type, value_type, value_contains_null).into()
+                        }
+                    ),
+                    struct_type_of(inner, max_width).prop_map(DataType::from),
+                ]
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for StructType {
+    type Parameters = ArbitraryParams;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        // the fields of a struct are one level deeper than the struct
+        let field_params = ArbitraryParams {
+            max_depth: params.max_depth.saturating_sub(1),
+            ..params
+        };
+        struct_type_of(any_with::<DataType>(field_params), params.max_width).boxed()
+    }
+}
+
+/// Generates non-null scalars of `data_type`.
+fn non_null_scalar_of(data_type: &DataType, max_width: usize) -> BoxedStrategy<Scalar> {
+    match data_type {
+        DataType::Primitive(primitive) => primitive_scalar_of(primitive),
+        DataType::Array(array_type) => {
+            let array_type = array_type.as_ref().clone();
+            let element = scalar_of(
+                array_type.element_type(),
+                array_type.contains_null(),
+                max_width,
+            );
+            vec(element, 0..=max_width)
+                .prop_filter_map("invalid array scalar", move |elements| {
+                    ArrayData::try_new(array_type.clone(), elements).ok()
+                })
+                .prop_map(Scalar::Array)
+                .boxed()
+        }
+        DataType::Map(map_type) => {
+            let map_type = map_type.as_ref().clone();
+            let key = non_null_scalar_of(map_type.key_type(), max_width);
+            let value = scalar_of(
+                map_type.value_type(),
+                map_type.value_contains_null,
+                max_width,
+            );
+            vec((key, value), 0..=max_width)
+                .prop_filter_map("invalid map scalar", move |pairs| {
+                    MapData::try_new(map_type.clone(), pairs).ok()
+                })
+                .prop_map(Scalar::Map)
+                .boxed()
+        }
+        DataType::Struct(struct_type) => {
+            let fields: Vec<StructField> = struct_type.fields().cloned().collect();
+            let values: Vec<_> = fields
+                .iter()
+                .map(|field| scalar_of(field.data_type(), field.is_nullable(), max_width))
+                .collect();
+            values
+                .prop_filter_map("invalid struct scalar", move |values| {
+                    StructData::try_new(fields.clone(), values).ok()
+                })
+                .prop_map(Scalar::Struct)
+                .boxed()
+        }
+        DataType::Variant(_) => Just(Scalar::Null(data_type.clone())).boxed(),
+    }
+}
+
+/// Generates scalars of `data_type`, which are null only if `nullable` is true.
+fn scalar_of(data_type: &DataType, nullable: bool, max_width: usize) -> BoxedStrategy<Scalar> {
+    let non_null = non_null_scalar_of(data_type, max_width);
+    if nullable {
+        prop_oneof![
+            1 => Just(Scalar::Null(data_type.clone())),
+            4 => non_null,
+        ]
+        .boxed()
+    } else {
+        non_null
+    }
+}
+
+fn primitive_scalar_of(primitive: &PrimitiveType) -> BoxedStrategy<Scalar> {
+    let not_nan_f32 = f32::POSITIVE | f32::NEGATIVE | f32::NORMAL | f32::SUBNORMAL | f32::ZERO;
+    let not_nan_f64 = f64::POSITIVE | f64::NEGATIVE | f64::NORMAL | f64::SUBNORMAL | f64::ZERO;
+    match primitive {
+        PrimitiveType::String => any::<String>().prop_map(Scalar::String).boxed(),
+        PrimitiveType::Long => any::<i64>().prop_map(Scalar::Long).boxed(),
+        PrimitiveType::Integer => any::<i32>().prop_map(Scalar::Integer).boxed(),
+        PrimitiveType::Short => any::<i16>().prop_map(Scalar::Short).boxed(),
+        PrimitiveType::Byte => any::<i8>().prop_map(Scalar::Byte).boxed(),
+        PrimitiveType::Float => not_nan_f32.prop_map(Scalar::Float).boxed(),
+        PrimitiveType::Double => not_nan_f64.prop_map(Scalar::Double).boxed(),
+        PrimitiveType::Boolean => any::<bool>().prop_map(Scalar::Boolean).boxed(),
+        PrimitiveType::Binary => vec(any::<u8>(), 0..16).prop_map(Scalar::Binary).boxed(),
+        PrimitiveType::Date => any::<i32>().prop_map(Scalar::Date).boxed(),
+        PrimitiveType::Timestamp => any::<i64>().prop_map(Scalar::Timestamp).boxed(),
+        PrimitiveType::TimestampNtz => any::<i64>().prop_map(Scalar::TimestampNtz).boxed(),
+        PrimitiveType::Decimal(decimal_type) => {
+            let decimal_type = *decimal_type;
+            let max = 10i128.pow(decimal_type.precision().into()) - 1;
+            (-max..=max)
+                .prop_filter_map("invalid decimal scalar", move |bits| {
+                    DecimalData::try_new(bits, decimal_type).ok()
+                })
+                .prop_map(Scalar::Decimal)
+                .boxed()
+        }
+    }
+}
+
+impl Arbitrary for Scalar {
+    type Parameters = ArbitraryParams;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        any_with::<DataType>(params)
+            .prop_flat_map(move |data_type| scalar_of(&data_type, true, params.max_width))
+            .boxed()
+    }
+}
+
+/// Generates predicates whose leaves compare, or test, expressions generated by `expr`.
+fn predicate_over(
+    expr: impl Strategy<Value = Expression> + Clone + 'static,
+    params: ArbitraryParams,
+) -> impl Strategy<Value = Predicate> {
+    let ArbitraryParams {
+        max_depth,
+        max_width,
+    } = params;
+    let comparison = prop_oneof![
+        Just(BinaryPredicateOp::LessThan),
+        Just(BinaryPredicateOp::GreaterThan),
+        Just(BinaryPredicateOp::Equal),
+        Just(BinaryPredicateOp::Distinct),
+    ];
+    let leaf = prop_oneof![
+        any::<bool>().prop_map(Predicate::literal),
+        expr.clone().prop_map(Predicate::from_expr),
+        expr.clone().prop_map(Predicate::is_null),
+        (comparison, expr.clone(), expr).prop_map(|(op, a, b)| Predicate::binary(op, a, b)),
+    ];
+    let size = max_width.saturating_pow(max_depth) as u32;
+    leaf.prop_recursive(max_depth, size, max_width as u32, move |inner| {
+        let junction = prop_oneof![
+            Just(JunctionPredicateOp::And),
+            Just(JunctionPredicateOp::Or)
+        ];
+        prop_oneof![
+            inner.clone().prop_map(Predicate::not),
+            (junction, vec(inner, 1..=max_width.max(1)))
+                .prop_map(|(op, preds)| Predicate::junction(op, preds)),
+        ]
+    })
+}
+
+impl Arbitrary for Expression {
+    type Parameters = ArbitraryParams;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        let ArbitraryParams {
+            max_depth,
+            max_width,
+        } = params;
+        // literals are primitives, or shallow nested values
+        let literal_params = ArbitraryParams {
+            max_depth: max_depth.min(1),
+            max_width,
+        };
+        let leaf = prop_oneof![
+            any_with::<Scalar>(literal_params).prop_map(Expression::Literal),
+            column_name().prop_map(Expression::Column),
+        ];
+        let size = max_width.saturating_pow(max_depth) as u32;
+        leaf.prop_recursive(max_depth, size, max_width as u32, move |inner| {
+            let children = vec(inner.clone(), 1..=max_width.max(1));
+            let arithmetic = prop_oneof![
+                Just(BinaryExpressionOp::Plus),
+                Just(BinaryExpressionOp::Minus),
+                Just(BinaryExpressionOp::Multiply),
+                Just(BinaryExpressionOp::Divide),
+                Just(BinaryExpressionOp::Modulo),
+            ];
+            let variadic = prop_oneof![
+                Just(VariadicExpressionOp::Coalesce),
+                Just(VariadicExpressionOp::NullIf),
+            ];
+            // predicates nested in an expression are shallow, so that they don't dominate it
+            let predicate_params = ArbitraryParams {
+                max_depth: 1,
+                max_width,
+            };
+            prop_oneof![
+                (arithmetic, inner.clone(), inner.clone())
+                    .prop_map(|(op, a, b)| Expression::binary(op, a, b)),
+                inner
+                    .clone()
+                    .prop_map(|expr| Expression::unary(UnaryExpressionOp::ToJson, expr)),
+                (variadic, children.clone())
+                    .prop_map(|(op, exprs)| Expression::variadic(op, exprs)),
+                children.prop_map(Expression::struct_from),
+                (inner.clone(), primitive_type()).prop_map(|(expr, to)| Expression::cast(expr, to)),
+                predicate_over(inner, predicate_params).prop_map(Expression::from_pred),
+            ]
+        })
+        .boxed()
+    }
+}
+
+impl Arbitrary for Predicate {
+    type Parameters = ArbitraryParams;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(params: Self::Parameters) -> Self::Strategy {
+        // the expressions of a predicate are one level deeper than the predicate
+        let expr_params = ArbitraryParams {
+            max_depth: params.max_depth.saturating_sub(1),
+            ..params
+        };
+        predicate_over(any_with::<Expression>(expr_params), params).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::transforms::ExpressionDepthChecker;
+    use crate::schema::SchemaLimits;
+
+    proptest! {
+        #[test]
+        fn test_arbitrary_schemas_are_valid(schema: StructType) {
+            prop_assert!(schema.validate_limits(&SchemaLimits::DEFAULT).is_ok());
+            let json = serde_json::to_string(&schema).unwrap();
+            prop_assert_eq!(serde_json::from_str::<StructType>(&json).unwrap(), schema);
+        }
+
+        #[test]
+        fn test_arbitrary_scalars_are_valid(scalar: Scalar) {
+            // the data type of a valid scalar can be used to construct a null of the same type
+            let data_type = scalar.data_type();
+            prop_assert_eq!(Scalar::Null(data_type.clone()).data_type(), data_type);
+            if let Scalar::Decimal(decimal) = &scalar {
+                let max = 10i128.pow(decimal.precision().into());
+                prop_assert!(decimal.bits().abs() < max);
+            }
+        }
+
+        #[test]
+        fn test_arbitrary_expressions_are_bounded(expr: Expression, pred: Predicate) {
+            // each level of an expression may nest a shallow predicate
+            prop_assert!(ExpressionDepthChecker::check_expr(&expr, 20) <= 12);
+            prop_assert!(ExpressionDepthChecker::check_pred(&pred, 20) <= 12);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Async counterparts of the [`Engine`] traits, for engines whose I/O is async.
+//!
+//! Reading a table through an [`Engine`] blocks the calling thread whenever the iterators its
+//! handlers return wait on I/O, so async engines end up dedicating threads to kernel calls. Instead,
+//! they can implement [`AsyncEngine`], whose handlers return [`Stream`]s, and drive the kernel with
+//! its async entry points (e.g. [`Scan::scan_metadata_async`]). The CPU-bound work of the kernel,
+//! such as evaluating expressions and parsing JSON, stays synchronous.
+//!
+//! [`SyncAdapter`] adapts an [`Engine`] or any of its handlers to these traits.
+//!
+//! [`Stream`]: futures::Stream
+//! [`Scan::scan_metadata_async`]: crate::scan::Scan::scan_metadata_async
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt as _};
+use url::Url;
+
+use crate::schema::SchemaRef;
+use crate::{
+    AsAny, DeltaResult, Engine, EngineData, Error, EvaluationHandler, FileDataReadResultIterator,
+    FileMeta, FileSlice, JsonHandler, ParquetHandler, PredicateRef, StorageHandler,
+};
+
+/// A stream of [`EngineData`] read from files, the async counterpart of
+/// [`FileDataReadResultIterator`].
+pub type FileDataReadResultStream = BoxStream<'static, DeltaResult<Box<dyn EngineData>>>;
+
+/// The async counterpart of [`StorageHandler`].
+pub trait AsyncStorageHandler: AsAny {
+    /// List the paths in the same directory that are lexicographically greater than the given
+    /// `path`, sorted by file name. See [`StorageHandler::list_from`].
+    fn list_from(&self, path: &Url) -> DeltaResult<BoxStream<'static, DeltaResult<FileMeta>>>;
+
+    /// Read data specified by the start and end offset from the file, in the order of `files`. See
+    /// [`StorageHandler::read_files`].
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<Bytes>>>;
+}
+
+/// The async counterpart of [`JsonHandler`].
+pub trait AsyncJsonHandler: AsAny {
+    /// Parse the given json strings and return the fields requested by output schema as columns
+    /// in [`EngineData`]. See [`JsonHandler::parse_json`].
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>>;
+
+    /// Read and parse the JSON format files at the given locations. The stream must return the
+    /// data in the same order as [`JsonHandler::read_json_files`] would.
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream>;
+}
+
+/// The async counterpart of [`ParquetHandler`].
+pub trait AsyncParquetHandler: AsAny {
+    /// Read and parse the parquet files at the given locations. The stream must return the data in
+    /// the same order as [`ParquetHandler::read_parquet_files`] would.
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream>;
+}
+
+/// The async counterpart of [`Engine`], for engines whose I/O is async.
+pub trait AsyncEngine: AsAny {
+    /// Get the connector provided [`EvaluationHandler`].
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler>;
+
+    /// Get the connector provided [`AsyncStorageHandler`].
+    fn storage_handler(&self) -> Arc<dyn AsyncStorageHandler>;
+
+    /// Get the connector provided [`AsyncJsonHandler`].
+    fn json_handler(&self) -> Arc<dyn AsyncJsonHandler>;
+
+    /// Get the connector provided [`AsyncParquetHandler`].
+    fn parquet_handler(&self) -> Arc<dyn AsyncParquetHandler>;
+}
+
+/// Adapts a sync [`Engine`], or any of its handlers, to the corresponding async trait.
+///
+/// The streams of the adapter are backed by the sync handlers' iterators, so polling them blocks
+/// whenever those iterators block. The iterators of [`StorageHandler`] need not be `Send`, so they
+/// are fully consumed before their stream is returned.
+#[derive(Debug)]
+pub struct SyncAdapter<T: ?Sized>(Arc<T>);
+
+impl<T: ?Sized> SyncAdapter<T> {
+    pub fn new(inner: Arc<T>) -> Self {
+        Self(inner)
+    }
+}
+
+fn into_stream(iter: FileDataReadResultIterator) -> FileDataReadResultStream {
+    stream::iter(iter).boxed()
+}
+
+impl<H: StorageHandler + ?Sized> AsyncStorageHandler for SyncAdapter<H> {
+    fn list_from(&self, path: &Url) -> DeltaResult<BoxStream<'static, DeltaResult<FileMeta>>> {
+        let files: Vec<_> = self.0.list_from(path)?.collect();
+        Ok(stream::iter(files).boxed())
+    }
+
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<Bytes>>> {
+        let data: Vec<_> = self.0.read_files(files)?.collect();
+        Ok(stream::iter(data).boxed())
+    }
+}
+
+impl<H: JsonHandler + ?Sized> AsyncJsonHandler for SyncAdapter<H> {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        self.0.parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        let batches = self.0.read_json_files(files, physical_schema, predicate)?;
+        Ok(into_stream(batches))
+    }
+}
+
+impl<H: ParquetHandler + ?Sized> AsyncParquetHandler for SyncAdapter<H> {
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        let batches = self
+            .0
+            .read_parquet_files(files, physical_schema, predicate)?;
+        Ok(into_stream(batches))
+    }
+}
+
+impl<E: Engine + ?Sized> AsyncEngine for SyncAdapter<E> {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.0.evaluation_handler()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn AsyncStorageHandler> {
+        Arc::new(SyncAdapter(self.0.storage_handler()))
+    }
+
+    fn json_handler(&self) -> Arc<dyn AsyncJsonHandler> {
+        Arc::new(SyncAdapter(self.0.json_handler()))
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn AsyncParquetHandler> {
+        Arc::new(SyncAdapter(self.0.parquet_handler()))
+    }
+}
+
+/// The CPU-bound handlers of an [`AsyncEngine`], as an [`Engine`] for the parts of log replay that
+/// are shared with sync engines. Its handlers fail any attempt to do I/O.
+pub(crate) struct ProcessingEngine {
+    evaluation_handler: Arc<dyn EvaluationHandler>,
+    json_handler: Arc<ParseOnlyJsonHandler>,
+}
+
+impl ProcessingEngine {
+    pub(crate) fn new(engine: &dyn AsyncEngine) -> Self {
+        Self {
+            evaluation_handler: engine.evaluation_handler(),
+            json_handler: Arc::new(ParseOnlyJsonHandler(engine.json_handler())),
+        }
+    }
+}
+
+fn io_unsupported(operation: &str) -> Error {
+    Error::internal_error(format!(
+        "Log replay for an async engine must not {operation} with a sync handler"
+    ))
+}
+
+impl Engine for ProcessingEngine {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.evaluation_handler.clone()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+        Arc::new(NoIoHandler)
+    }
+
+    fn json_handler(&self) -> Arc<dyn JsonHandler> {
+        self.json_handler.clone()
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+        Arc::new(NoIoHandler)
+    }
+}
+
+struct ParseOnlyJsonHandler(Arc<dyn AsyncJsonHandler>);
+
+impl JsonHandler for ParseOnlyJsonHandler {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        self.0.parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        _files: &[FileMeta],
+        _physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        Err(io_unsupported("read JSON files"))
+    }
+
+    fn write_json_file(
+        &self,
+        _path: &Url,
+        _data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+        _overwrite: bool,
+    ) -> DeltaResult<()> {
+        Err(io_unsupported("write JSON files"))
+    }
+}
+
+struct NoIoHandler;
+
+impl StorageHandler for NoIoHandler {
+    fn list_from(
+        &self,
+        _path: &Url,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>> {
+        Err(io_unsupported("list files"))
+    }
+
+    fn read_files(
+        &self,
+        _files: Vec<FileSlice>,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+        Err(io_unsupported("read files"))
+    }
+
+    fn delete(&self, _files: Vec<Url>) -> DeltaResult<()> {
+        Err(io_unsupported("delete files"))
+    }
+}
+
+impl ParquetHandler for NoIoHandler {
+    fn read_parquet_files(
+        &self,
+        _files: &[FileMeta],
+        _physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        Err(io_unsupported("read parquet files"))
+    }
+}
@@ -24,10 +24,12 @@
 //!
 //! 1. Create a [`CheckpointWriter`] using [`Snapshot::checkpoint`]
 //! 2. Get the checkpoint path from [`CheckpointWriter::checkpoint_path`]
-//! 2. Get the checkpoint data from [`CheckpointWriter::checkpoint_data`]
-//! 3. Write the data to the path in object storage (engine-specific)
-//! 4. Collect metadata ([`FileMeta`]) from the write operation
-//! 5. Pass the metadata and exhausted data iterator to [`CheckpointWriter::finalize`]
+//! 3. Get the checkpoint data from [`CheckpointWriter::checkpoint_data`]
+//! 4. Write the data to the path in object storage (engine-specific), e.g. with the engine's own
+//!    parquet writer
+//! 5. Collect metadata ([`FileMeta`]) of the written file from the write operation
+//! 6. Pass the metadata and exhausted data iterator to [`CheckpointWriter::finalize`], which writes
+//!    the `_last_checkpoint` file
 //!
 //! ```no_run
 //! # use std::sync::Arc;
@@ -124,7 +126,7 @@ static LAST_CHECKPOINT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
 });
 
 /// Schema for extracting relevant actions from log files for checkpoint creation
-static CHECKPOINT_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+pub(crate) static CHECKPOINT_ACTIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(StructType::new_unchecked([
         StructField::nullable(ADD_NAME, Add::to_schema()),
         StructField::nullable(REMOVE_NAME, Remove::to_schema()),
@@ -240,6 +242,26 @@ impl CheckpointWriter {
         )
         .map(|parsed| parsed.location)
     }
+    /// Returns the schema of the parsed file statistics to write to the checkpoint file, or `None`
+    /// if they should not be written.
+    ///
+    /// Parsed statistics are written if the table enables `delta.checkpoint.writeStatsAsStruct`.
+    /// They let readers of the checkpoint skip files without parsing the JSON `stats` of each add
+    /// action. In that case, the engine should write the `add` actions of the checkpoint data with
+    /// an additional (nullable) `stats_parsed` field after the others, holding the `add.stats` of
+    /// each action parsed as JSON with this schema (or null if `add.stats` is null).
+    pub fn stats_parsed_schema(&self) -> Option<SchemaRef> {
+        let table_configuration = self.snapshot.table_configuration();
+        if table_configuration
+            .table_properties()
+            .checkpoint_write_stats_as_struct
+            != Some(true)
+        {
+            return None;
+        }
+        table_configuration.stats_parsed_schema()
+    }
+
     /// Returns the checkpoint data to be written to the checkpoint file.
     ///
     /// This method reads the actions from the log segment and processes them
@@ -297,12 +319,14 @@ impl CheckpointWriter {
     ///
     /// # Parameters
     /// - `engine`: Implementation of [`Engine`] apis.
-    /// - `metadata`: The metadata of the written checkpoint file
+    /// - `metadata`: The metadata of the written checkpoint file. If the file was written to the
+    ///   `_delta_log` directory, its location must be the [`CheckpointWriter::checkpoint_path`]
     /// - `checkpoint_data`: The exhausted checkpoint data iterator
     ///
     /// # Returns: `Ok` if the checkpoint was successfully finalized
     // Internally, this method:
-    // 1. Validates that the checkpoint data iterator is fully exhausted
+    // 1. Validates that the checkpoint data iterator is fully exhausted, and that the checkpoint
+    //    file was not written to the log under another name than the checkpoint path
     // 2. Creates the `_last_checkpoint` data with `create_last_checkpoint_data`
     // 3. Writes the `_last_checkpoint` data to the `_last_checkpoint` file in the delta log
     pub fn finalize(
@@ -317,6 +341,18 @@ impl CheckpointWriter {
                 "The checkpoint data iterator must be fully consumed and written to storage before calling finalize"
             ));
         }
+        // A checkpoint file written to the log under any other name would be taken for a different
+        // log file (e.g. the checkpoint of another version) by readers
+        let checkpoint_path = self.checkpoint_path()?;
+        let log_root = &self.snapshot.log_segment().log_root;
+        if metadata.location.as_str().starts_with(log_root.as_str())
+            && metadata.location != checkpoint_path
+        {
+            return Err(Error::checkpoint_write(format!(
+                "The checkpoint file must be written to {checkpoint_path}, but was written to {}",
+                metadata.location
+            )));
+        }
 
         let size_in_bytes = i64::try_from(metadata.size).map_err(|e| {
             Error::CheckpointWrite(format!(
@@ -372,7 +408,7 @@ impl CheckpointWriter {
 
         let filtered_data = FilteredEngineData {
             data: checkpoint_metadata_batch,
-            selection_vector: vec![true], // Include the action in the checkpoint
+            selection_vector: vec![true].into(), // Include the action in the checkpoint
         };
 
         Ok(ActionReconciliationBatch {
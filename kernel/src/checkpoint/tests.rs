@@ -1,14 +1,16 @@
 use std::{sync::Arc, time::Duration};
 
-use crate::action_reconciliation::{
-    deleted_file_retention_timestamp_with_time, DEFAULT_RETENTION_SECS,
-};
+use crate::action_reconciliation::deleted_file_retention_timestamp_with_time;
 use crate::actions::{Add, Metadata, Protocol, Remove};
-use crate::arrow::array::{ArrayRef, StructArray};
+use crate::arrow::array::types::{Int32Type, Int64Type};
+use crate::arrow::array::{Array as _, ArrayRef, AsArray as _, StructArray};
+use crate::arrow::compute::concat_batches;
 use crate::arrow::datatypes::{DataType, Schema};
 use crate::checkpoint::create_last_checkpoint_data;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
+use crate::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use crate::schema::{DataType as KernelDataType, StructField, StructType};
 use crate::utils::test_utils::Action;
 use crate::{DeltaResult, FileMeta, Snapshot};
 
@@ -17,6 +19,7 @@ use arrow_56::{
     datatypes::Field,
 };
 
+use itertools::Itertools as _;
 use object_store::{memory::InMemory, path::Path, ObjectStore};
 use serde_json::{from_slice, json, Value};
 use test_utils::delta_path_for_version;
@@ -36,7 +39,7 @@ fn test_deleted_file_retention_timestamp() -> DeltaResult<()> {
         // None = Default retention (7 days)
         (
             None,
-            reference_time_millis - (DEFAULT_RETENTION_SECS as i64 * MILLIS_PER_SECOND),
+            reference_time_millis - (7 * 24 * 60 * 60 * MILLIS_PER_SECOND),
         ),
         // Zero retention
         (Some(Duration::from_secs(0)), reference_time_millis),
@@ -439,6 +442,52 @@ fn test_finalize_errors_if_checkpoint_data_iterator_is_not_exhausted() -> DeltaR
     Ok(())
 }
 
+#[test]
+fn test_finalize_errors_if_checkpoint_file_is_not_at_checkpoint_path() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+    write_commit_to_store(
+        &store,
+        vec![create_basic_protocol_action(), create_metadata_action()],
+        0,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let writer = snapshot.checkpoint()?;
+    let mut data_iter = writer.checkpoint_data(&engine)?;
+    data_iter.by_ref().for_each(drop);
+
+    // the engine writes the checkpoint file to the log under the name of another version
+    let wrong_path = format!(
+        "_delta_log/{}",
+        delta_path_for_version(1, "checkpoint.parquet")
+    );
+    tokio::runtime::Runtime::new()
+        .expect("create tokio runtime")
+        .block_on(async {
+            store
+                .put(&Path::from(wrong_path.as_str()), vec![0; 10].into())
+                .await
+        })?;
+    let metadata = FileMeta {
+        location: Url::parse("memory:///")?.join(&wrong_path)?,
+        last_modified: 0,
+        size: 10,
+    };
+    let err = writer
+        .finalize(&engine, &metadata, data_iter)
+        .expect_err("finalize should fail");
+    assert!(
+        err.to_string().contains("The checkpoint file must be written to memory:///_delta_log/00000000000000000000.checkpoint.parquet"),
+        "{err}"
+    );
+    // no `_last_checkpoint` file is written
+    assert!(read_last_checkpoint_file(&store).is_err());
+
+    Ok(())
+}
+
 /// Tests the `checkpoint()` API with:
 /// - A table that does supports v2Checkpoint
 /// - No version specified (latest version is used)
@@ -511,3 +560,120 @@ fn test_v2_checkpoint_supported_table() -> DeltaResult<()> {
 
     Ok(())
 }
+
+/// Tests that [`DefaultEngine::write_checkpoint`] writes the parsed stats of add actions to the
+/// checkpoint of a (V2 checkpoint) table with `delta.checkpoint.writeStatsAsStruct` enabled.
+#[test]
+fn test_write_checkpoint_with_stats_parsed() -> DeltaResult<()> {
+    let (store, _) = new_in_memory_store();
+    let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+    let metadata = Metadata {
+        id: "test-table".into(),
+        schema_string: json!({
+            "type": "struct",
+            "fields": [
+                {"name": "value", "type": "integer", "nullable": true, "metadata": {}},
+                {"name": "part", "type": "string", "nullable": true, "metadata": {}},
+                {"name": "tags", "type": {"type": "array", "elementType": "string", "containsNull": true}, "nullable": true, "metadata": {}},
+            ]
+        })
+        .to_string(),
+        partition_columns: vec!["part".into()],
+        configuration: [(
+            "delta.checkpoint.writeStatsAsStruct".to_string(),
+            "true".to_string(),
+        )]
+        .into(),
+        ..Default::default()
+    };
+    let add_with_stats = Action::Add(Add {
+        path: "file1.parquet".into(),
+        data_change: true,
+        stats: Some(
+            r#"{"numRecords":2,"minValues":{"value":1},"maxValues":{"value":5},"nullCount":{"value":0,"tags":1}}"#
+                .into(),
+        ),
+        ..Default::default()
+    });
+    write_commit_to_store(
+        &store,
+        vec![
+            create_v2_checkpoint_protocol_action(),
+            Action::Metadata(metadata),
+            add_with_stats,
+            create_add_action("file2.parquet"),
+        ],
+        0,
+    )?;
+
+    let table_root = Url::parse("memory:///")?;
+    let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+    let writer = snapshot.checkpoint()?;
+    let stats_schema = writer.stats_parsed_schema().unwrap();
+    let expected_stats_schema = StructType::new_unchecked([
+        StructField::nullable("numRecords", KernelDataType::LONG),
+        StructField::nullable(
+            "nullCount",
+            StructType::new_unchecked([StructField::nullable("value", KernelDataType::LONG)]),
+        ),
+        StructField::nullable(
+            "minValues",
+            StructType::new_unchecked([StructField::nullable("value", KernelDataType::INTEGER)]),
+        ),
+        StructField::nullable(
+            "maxValues",
+            StructType::new_unchecked([StructField::nullable("value", KernelDataType::INTEGER)]),
+        ),
+    ]);
+    assert_eq!(*stats_schema, expected_stats_schema);
+
+    let file_meta = engine.write_checkpoint(writer)?;
+    assert_eq!(
+        file_meta.location,
+        Url::parse("memory:///_delta_log/00000000000000000000.checkpoint.parquet")?
+    );
+    // protocol + metadata + 2 adds + checkpointMetadata
+    assert_last_checkpoint_contents(&store, 0, 5, 2, file_meta.size)?;
+
+    let bytes = tokio::runtime::Runtime::new()
+        .expect("create tokio runtime")
+        .block_on(async {
+            let path = Path::from("_delta_log/00000000000000000000.checkpoint.parquet");
+            store.get(&path).await?.bytes().await
+        })?;
+    let batches: Vec<_> = ParquetRecordBatchReaderBuilder::try_new(bytes)?
+        .build()?
+        .try_collect()?;
+    let checkpoint = concat_batches(&batches[0].schema(), &batches)?;
+    assert!(checkpoint.column_by_name("checkpointMetadata").is_some());
+
+    let add = checkpoint.column_by_name("add").unwrap().as_struct();
+    let paths = add.column_by_name("path").unwrap().as_string::<i32>();
+    let stats_parsed = add.column_by_name("stats_parsed").unwrap().as_struct();
+    let num_records = stats_parsed.column_by_name("numRecords").unwrap();
+    let num_records = num_records.as_primitive::<Int64Type>();
+    let min_values = stats_parsed
+        .column_by_name("minValues")
+        .unwrap()
+        .as_struct();
+    let min_values = min_values.column_by_name("value").unwrap();
+    let min_values = min_values.as_primitive::<Int32Type>();
+    let mut num_adds = 0;
+    for row in (0..checkpoint.num_rows()).filter(|&row| add.is_valid(row)) {
+        num_adds += 1;
+        match paths.value(row) {
+            "file1.parquet" => {
+                assert_eq!(num_records.value(row), 2);
+                assert_eq!(min_values.value(row), 1);
+            }
+            "file2.parquet" => assert!(stats_parsed.is_null(row)),
+            path => panic!("unexpected add action for {path}"),
+        }
+    }
+    assert_eq!(num_adds, 2);
+    // rows of other actions have no add, and so no parsed stats
+    assert_eq!(stats_parsed.null_count(), checkpoint.num_rows() - 1);
+
+    Ok(())
+}
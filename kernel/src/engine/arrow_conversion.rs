@@ -1,7 +1,16 @@
-//! Conversions from kernel schema types to arrow schema types.
+//! Conversions from kernel schema types to arrow schema types, and between kernel selection
+//! vectors and arrow boolean buffers.
+//!
+//! The [`TryFromKernel`] and [`TryFromArrow`] conversions produce the arrow types kernel reads and
+//! writes data with. Engines that need to convert a schema to arrow and back without losing any
+//! Delta-specific information should use [`schema_to_arrow_with_metadata`] and
+//! [`schema_from_arrow_with_metadata`] instead.
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use crate::arrow::array::BooleanArray;
+use crate::arrow::buffer::{BooleanBuffer, Buffer};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema,
     SchemaRef as ArrowSchemaRef, TimeUnit,
@@ -9,9 +18,12 @@ use crate::arrow::datatypes::{
 use crate::arrow::error::ArrowError;
 use itertools::Itertools;
 
+use crate::engine_data::SelectionVector;
 use crate::error::Error;
+use crate::parquet::arrow::PARQUET_FIELD_ID_META_KEY;
 use crate::schema::{
-    ArrayType, DataType, MapType, MetadataValue, PrimitiveType, StructField, StructType,
+    ArrayType, ColumnMetadataKey, DataType, MapType, MetadataValue, PrimitiveType, StructField,
+    StructType,
 };
 
 pub(crate) const LIST_ARRAY_ROOT: &str = "element";
@@ -19,6 +31,15 @@ pub(crate) const MAP_ROOT_DEFAULT: &str = "key_value";
 pub(crate) const MAP_KEY_DEFAULT: &str = "key";
 pub(crate) const MAP_VALUE_DEFAULT: &str = "value";
 
+/// The arrow field metadata key that lists (as a JSON array) the keys of all Delta field metadata
+/// values which are not strings, and were therefore JSON-encoded. See
+/// [`schema_to_arrow_with_metadata`].
+pub const JSON_METADATA_KEYS_KEY: &str = "delta.kernel.jsonMetadataKeys";
+/// The arrow field metadata key that holds the name of the field's extension type.
+pub const EXTENSION_TYPE_NAME_KEY: &str = "ARROW:extension:name";
+/// The arrow extension type name that marks a struct field as a Delta variant.
+pub const VARIANT_EXTENSION_TYPE_NAME: &str = "arrow.parquet.variant";
+
 /// Convert a kernel type into an arrow type (automatically implemented for all types that
 /// implement [`TryFromKernel`])
 pub trait TryIntoArrow<ArrowType> {
@@ -297,6 +318,228 @@ impl TryFromArrow<&ArrowDataType> for DataType {
     }
 }
 
+/// Converts a kernel schema to an arrow schema, such that [`schema_from_arrow_with_metadata`]
+/// recovers the original schema exactly. Compared to the [`TryFromKernel`] conversion:
+///  - Non-string Delta field metadata values (e.g. column mapping IDs) are JSON-encoded, and their
+///    keys are listed under [`JSON_METADATA_KEYS_KEY`] so that they are decoded with their original
+///    type.
+///  - Field IDs ([`ColumnMetadataKey::ParquetFieldId`]) are additionally exposed under
+///    [`PARQUET_FIELD_ID_META_KEY`], where arrow's parquet writer picks them up.
+///  - Variant fields (including shredded variants) are converted to their struct representation,
+///    annotated with the [`VARIANT_EXTENSION_TYPE_NAME`] extension type.
+///
+/// The nullability of fields, array elements and map values carries over as the nullability of
+/// the corresponding arrow fields.
+pub fn schema_to_arrow_with_metadata(schema: &StructType) -> Result<ArrowSchema, ArrowError> {
+    let fields: Vec<_> = schema.fields().map(field_to_arrow).try_collect()?;
+    Ok(ArrowSchema::new(fields))
+}
+
+/// Converts an arrow schema to a kernel schema, reversing [`schema_to_arrow_with_metadata`]. Arrow
+/// schemas from other sources are converted as by [`TryFromArrow`], except that field IDs found
+/// under [`PARQUET_FIELD_ID_META_KEY`] become [`ColumnMetadataKey::ParquetFieldId`] metadata, and
+/// struct fields with the [`VARIANT_EXTENSION_TYPE_NAME`] extension type become variants.
+pub fn schema_from_arrow_with_metadata(schema: &ArrowSchema) -> Result<StructType, ArrowError> {
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| field_from_arrow(field))
+        .try_collect()?;
+    StructType::try_new(fields).map_err(|e| ArrowError::from_external_error(e.into()))
+}
+
+fn field_to_arrow(field: &StructField) -> Result<ArrowField, ArrowError> {
+    let mut metadata = HashMap::new();
+    let mut json_keys = vec![];
+    for (key, value) in field.metadata() {
+        let value = match value {
+            MetadataValue::String(value) => value.clone(),
+            _ => {
+                json_keys.push(key.as_str());
+                serde_json::to_string(value).map_err(|e| ArrowError::JsonError(e.to_string()))?
+            }
+        };
+        metadata.insert(key.clone(), value);
+    }
+    if !json_keys.is_empty() {
+        json_keys.sort();
+        let json_keys =
+            serde_json::to_string(&json_keys).map_err(|e| ArrowError::JsonError(e.to_string()))?;
+        metadata.insert(JSON_METADATA_KEYS_KEY.to_string(), json_keys);
+    }
+    if let Some(field_id) = field.get_config_value(&ColumnMetadataKey::ParquetFieldId) {
+        metadata.insert(PARQUET_FIELD_ID_META_KEY.to_string(), field_id.to_string());
+    }
+    let arrow_field = type_to_arrow_field(field.name(), field.data_type(), field.is_nullable())?;
+    metadata.extend(arrow_field.metadata().clone());
+    Ok(arrow_field.with_metadata(metadata))
+}
+
+// Converts a (possibly nested) kernel type to an arrow field with the given name and nullability
+fn type_to_arrow_field(
+    name: &str,
+    data_type: &DataType,
+    nullable: bool,
+) -> Result<ArrowField, ArrowError> {
+    let (arrow_type, extension_type) = match data_type {
+        DataType::Primitive(_) => (data_type.try_into_arrow()?, None),
+        DataType::Struct(stype) => (struct_to_arrow(stype)?, None),
+        DataType::Variant(stype) => (struct_to_arrow(stype)?, Some(VARIANT_EXTENSION_TYPE_NAME)),
+        DataType::Array(atype) => {
+            let element =
+                type_to_arrow_field(LIST_ARRAY_ROOT, atype.element_type(), atype.contains_null())?;
+            (ArrowDataType::List(Arc::new(element)), None)
+        }
+        DataType::Map(mtype) => {
+            let key = type_to_arrow_field(MAP_KEY_DEFAULT, mtype.key_type(), false)?;
+            let value = type_to_arrow_field(
+                MAP_VALUE_DEFAULT,
+                mtype.value_type(),
+                mtype.value_contains_null(),
+            )?;
+            let entries = ArrowDataType::Struct(vec![key, value].into());
+            let entries = ArrowField::new(MAP_ROOT_DEFAULT, entries, false);
+            (ArrowDataType::Map(Arc::new(entries), false), None)
+        }
+    };
+    let field = ArrowField::new(name, arrow_type, nullable);
+    Ok(match extension_type {
+        Some(extension_type) => field.with_metadata(HashMap::from([(
+            EXTENSION_TYPE_NAME_KEY.to_string(),
+            extension_type.to_string(),
+        )])),
+        None => field,
+    })
+}
+
+fn struct_to_arrow(stype: &StructType) -> Result<ArrowDataType, ArrowError> {
+    let fields: Vec<_> = stype.fields().map(field_to_arrow).try_collect()?;
+    Ok(ArrowDataType::Struct(fields.into()))
+}
+
+fn field_from_arrow(field: &ArrowField) -> Result<StructField, ArrowError> {
+    let data_type = type_from_arrow_field(field)?;
+    let arrow_metadata = field.metadata();
+    let json_keys: HashSet<String> = match arrow_metadata.get(JSON_METADATA_KEYS_KEY) {
+        Some(keys) => {
+            serde_json::from_str(keys).map_err(|e| ArrowError::JsonError(e.to_string()))?
+        }
+        None => HashSet::new(),
+    };
+    let mut metadata = HashMap::new();
+    for (key, value) in arrow_metadata {
+        let is_variant_annotation = key == EXTENSION_TYPE_NAME_KEY
+            && value == VARIANT_EXTENSION_TYPE_NAME
+            && matches!(data_type, DataType::Variant(_));
+        if key == JSON_METADATA_KEYS_KEY
+            || key == PARQUET_FIELD_ID_META_KEY
+            || is_variant_annotation
+        {
+            continue;
+        }
+        let value = if json_keys.contains(key) {
+            serde_json::from_str(value).map_err(|e| ArrowError::JsonError(e.to_string()))?
+        } else {
+            MetadataValue::String(value.clone())
+        };
+        metadata.insert(key.clone(), value);
+    }
+    let field_id_key = ColumnMetadataKey::ParquetFieldId.as_ref();
+    if let Some(field_id) = arrow_metadata.get(PARQUET_FIELD_ID_META_KEY) {
+        if !metadata.contains_key(field_id_key) {
+            let field_id: i64 = field_id.parse().map_err(|_| {
+                ArrowError::SchemaError(format!(
+                    "Invalid field ID for field {}: {field_id}",
+                    field.name()
+                ))
+            })?;
+            metadata.insert(field_id_key.to_string(), field_id.into());
+        }
+    }
+    Ok(
+        StructField::new(field.name().clone(), data_type, field.is_nullable())
+            .with_metadata(metadata),
+    )
+}
+
+// Converts the type of an arrow field to a kernel type, recognizing variant annotations at any depth
+fn type_from_arrow_field(field: &ArrowField) -> Result<DataType, ArrowError> {
+    let is_variant = field
+        .metadata()
+        .get(EXTENSION_TYPE_NAME_KEY)
+        .is_some_and(|name| name == VARIANT_EXTENSION_TYPE_NAME);
+    match field.data_type() {
+        ArrowDataType::Struct(fields) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|field| field_from_arrow(field))
+                .try_collect()?;
+            let data_type = if is_variant {
+                DataType::variant_type(fields)
+            } else {
+                DataType::try_struct_type(fields)
+            };
+            data_type.map_err(|e| ArrowError::from_external_error(e.into()))
+        }
+        ArrowDataType::List(element)
+        | ArrowDataType::ListView(element)
+        | ArrowDataType::LargeList(element)
+        | ArrowDataType::LargeListView(element)
+        | ArrowDataType::FixedSizeList(element, _) => {
+            Ok(ArrayType::new(type_from_arrow_field(element)?, element.is_nullable()).into())
+        }
+        ArrowDataType::Map(entries, _) => {
+            let ArrowDataType::Struct(entry_fields) = entries.data_type() else {
+                return Err(ArrowError::SchemaError(format!(
+                    "Map field {} should contain a struct field child",
+                    field.name()
+                )));
+            };
+            let [key, value] = entry_fields.iter().collect_vec()[..] else {
+                return Err(ArrowError::SchemaError(format!(
+                    "Map field {} should contain a key and a value field",
+                    field.name()
+                )));
+            };
+            let key_type = type_from_arrow_field(key)?;
+            let value_type = type_from_arrow_field(value)?;
+            Ok(MapType::new(key_type, value_type, value.is_nullable()).into())
+        }
+        arrow_type => DataType::try_from_arrow(arrow_type),
+    }
+}
+
+/// Shares the bitmap of the selection vector, without copying it.
+impl From<SelectionVector> for BooleanBuffer {
+    fn from(selection_vector: SelectionVector) -> Self {
+        let len = selection_vector.len();
+        BooleanBuffer::new(Buffer::from(selection_vector.into_bytes()), 0, len)
+    }
+}
+
+/// Shares the bitmap of the selection vector, without copying it.
+impl From<SelectionVector> for BooleanArray {
+    fn from(selection_vector: SelectionVector) -> Self {
+        BooleanArray::new(selection_vector.into(), None)
+    }
+}
+
+/// Shares the bitmap of the buffer, without copying it unless the buffer starts in the middle of a
+/// byte.
+impl From<BooleanBuffer> for SelectionVector {
+    fn from(buffer: BooleanBuffer) -> Self {
+        struct Owner(Buffer);
+        impl AsRef<[u8]> for Owner {
+            fn as_ref(&self) -> &[u8] {
+                self.0.as_slice()
+            }
+        }
+        // the sliced buffer starts with the buffer's first bit, and holds all of its bits
+        let bits = bytes::Bytes::from_owner(Owner(buffer.sliced()));
+        SelectionVector::new_unchecked(bits, buffer.len())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,7 +549,6 @@ mod tests {
         schema::{DataType, StructField},
         DeltaResult,
     };
-    use std::collections::HashMap;
 
     #[test]
     fn test_metadata_string_conversion() -> DeltaResult<()> {
@@ -341,4 +583,101 @@ mod tests {
             .contains("Incorrect Variant Schema"));
         Ok(())
     }
+
+    #[test]
+    fn test_schema_conversion_with_metadata() -> DeltaResult<()> {
+        let variant = DataType::unshredded_variant();
+        let schema = StructType::try_new([
+            StructField::not_null("id", DataType::LONG).with_metadata([
+                (
+                    ColumnMetadataKey::ColumnMappingId.as_ref(),
+                    MetadataValue::Number(1),
+                ),
+                (
+                    ColumnMetadataKey::ParquetFieldId.as_ref(),
+                    MetadataValue::Number(1),
+                ),
+                (
+                    ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+                    "col-1".into(),
+                ),
+            ]),
+            StructField::nullable(
+                "nested",
+                StructType::try_new([
+                    StructField::nullable("v", variant.clone()),
+                    StructField::nullable("flag", DataType::BOOLEAN)
+                        .with_metadata([("comment", "true"), ("other", "x")])
+                        .add_metadata([("invariant", true)]),
+                ])?,
+            ),
+            StructField::nullable(
+                "values",
+                MapType::new(
+                    DataType::STRING,
+                    ArrayType::new(variant.clone(), false),
+                    true,
+                ),
+            ),
+        ])?;
+
+        let arrow_schema = schema_to_arrow_with_metadata(&schema)?;
+        let id = arrow_schema.field(0);
+        assert_eq!(id.metadata().get(PARQUET_FIELD_ID_META_KEY).unwrap(), "1");
+        assert_eq!(
+            id.metadata().get(JSON_METADATA_KEYS_KEY).unwrap(),
+            r#"["delta.columnMapping.id","parquet.field.id"]"#
+        );
+        let ArrowDataType::Struct(nested) = arrow_schema.field(1).data_type() else {
+            panic!("expected a struct");
+        };
+        assert_eq!(
+            nested[0].metadata().get(EXTENSION_TYPE_NAME_KEY).unwrap(),
+            VARIANT_EXTENSION_TYPE_NAME
+        );
+
+        // The round trip is lossless, unlike the plain conversion
+        assert_eq!(schema_from_arrow_with_metadata(&arrow_schema)?, schema);
+        let plain_arrow_schema = ArrowSchema::try_from_kernel(&schema)?;
+        let plain: StructType = (&plain_arrow_schema).try_into_kernel()?;
+        assert_ne!(plain, schema);
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_from_arrow_with_field_ids() -> DeltaResult<()> {
+        let arrow_schema = ArrowSchema::new(vec![ArrowField::new("a", ArrowDataType::Int32, true)
+            .with_metadata(HashMap::from([(
+                PARQUET_FIELD_ID_META_KEY.to_string(),
+                "7".to_string(),
+            )]))]);
+        let expected = StructType::try_new([StructField::nullable("a", DataType::INTEGER)
+            .with_metadata([(ColumnMetadataKey::ParquetFieldId.as_ref(), 7i64)])])?;
+        assert_eq!(schema_from_arrow_with_metadata(&arrow_schema)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_selection_vector_conversion() {
+        let selected = vec![
+            true, false, true, true, false, false, false, true, false, true,
+        ];
+        let selection_vector = SelectionVector::from(selected.clone());
+        let bits = selection_vector.as_bytes().as_ptr();
+
+        // The bitmap is shared, not copied
+        let buffer = BooleanBuffer::from(selection_vector);
+        assert_eq!(buffer.values().as_ptr(), bits);
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), selected);
+        let selection_vector = SelectionVector::from(buffer.clone());
+        assert_eq!(selection_vector.as_bytes().as_ptr(), bits);
+        assert_eq!(selection_vector, selected);
+
+        // A buffer that starts in the middle of a byte is copied
+        let selection_vector = SelectionVector::from(buffer.slice(3, 6));
+        assert_eq!(selection_vector, selected[3..9]);
+
+        let array = BooleanArray::from(SelectionVector::from(selected.clone()));
+        assert_eq!(array.values().iter().collect::<Vec<_>>(), selected);
+    }
 }
@@ -22,6 +22,20 @@ pub use crate::engine::arrow_utils::fix_nested_null_masks;
 
 /// ArrowEngineData holds an Arrow `RecordBatch`, implements `EngineData` so the kernel can extract from it.
 ///
+/// Engines built on arrow convert between `RecordBatch` and [`EngineData`] with the conversions
+/// provided here, rather than downcasting themselves:
+///
+/// - `RecordBatch` -> `ArrowEngineData`: [`ArrowEngineData::new`], or `From<RecordBatch>`
+///   (which also makes `ArrowEngineData::try_from(batch)` available).
+/// - `ArrowEngineData` -> `RecordBatch`: [`ArrowEngineData::into_record_batch`], or
+///   `From<ArrowEngineData>`.
+/// - `Box<dyn EngineData>` -> `RecordBatch`: [`ArrowEngineData::try_into_record_batch`], or
+///   `TryFrom<Box<dyn EngineData>>`, which fail with [`Error::EngineDataType`] if the data was not
+///   produced by an arrow-based engine.
+///
+/// All of these conversions move the `RecordBatch` as-is, so its schema (including schema- and
+/// field-level metadata) is preserved.
+///
 /// WARNING: Row visitors require that all leaf columns of the record batch have correctly computed
 /// NULL masks. The arrow parquet reader is known to produce incomplete NULL masks, for
 /// example. When in doubt, call [`fix_nested_null_masks`] first.
@@ -60,10 +74,37 @@ impl ArrowEngineData {
             .map_err(|_| Error::engine_data_type("ArrowEngineData"))
     }
 
+    /// Utility to get the `RecordBatch` out of a `Box<dyn EngineData>`, failing with
+    /// [`Error::EngineDataType`] if the data is not `ArrowEngineData`.
+    pub fn try_into_record_batch(engine_data: Box<dyn EngineData>) -> DeltaResult<RecordBatch> {
+        Self::try_from_engine_data(engine_data).map(|data| data.into_record_batch())
+    }
+
     /// Get a reference to the `RecordBatch` this `ArrowEngineData` is wrapping
     pub fn record_batch(&self) -> &RecordBatch {
         &self.data
     }
+
+    /// Consume this `ArrowEngineData`, returning the `RecordBatch` it is wrapping
+    pub fn into_record_batch(self) -> RecordBatch {
+        self.data
+    }
+}
+
+impl TryFrom<Box<dyn EngineData>> for ArrowEngineData {
+    type Error = Error;
+
+    fn try_from(value: Box<dyn EngineData>) -> DeltaResult<Self> {
+        ArrowEngineData::try_from_engine_data(value).map(|data| *data)
+    }
+}
+
+impl TryFrom<Box<dyn EngineData>> for RecordBatch {
+    type Error = Error;
+
+    fn try_from(value: Box<dyn EngineData>) -> DeltaResult<Self> {
+        ArrowEngineData::try_into_record_batch(value)
+    }
 }
 
 impl From<RecordBatch> for ArrowEngineData {
@@ -211,7 +252,14 @@ impl EngineData for ArrowEngineData {
                 getters.len()
             )));
         }
-        visitor.visit(self.len(), &getters)
+        let columns: Option<Vec<_>> = getters
+            .iter()
+            .map(|getter| getter.as_primitive_column())
+            .collect();
+        match columns {
+            Some(columns) => visitor.visit_primitive(self.len(), &columns),
+            None => visitor.visit(self.len(), &getters),
+        }
     }
 
     fn append_columns(
@@ -350,11 +398,13 @@ mod tests {
         DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema,
     };
     use crate::engine::sync::SyncEngine;
+    use crate::engine_data::RowVisitor;
     use crate::expressions::ArrayData;
     use crate::schema::{ArrayType, DataType, StructField, StructType};
+    use crate::schema::{ColumnName, SchemaRef};
     use crate::table_features::{ReaderFeature, WriterFeature};
     use crate::utils::test_utils::{assert_result_error_with_message, string_array_to_engine_data};
-    use crate::{DeltaResult, Engine as _, EngineData as _};
+    use crate::{DeltaResult, Engine as _, EngineData, Error};
 
     use super::{extract_record_batch, ArrowEngineData};
 
@@ -724,4 +774,180 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_visit_primitive_columns() -> DeltaResult<()> {
+        use crate::arrow::array::{BooleanArray, Int64Array};
+        use crate::engine_data::{GetData, PrimitiveColumn, TypedGetData as _};
+        use crate::schema::ColumnNamesAndTypes;
+
+        type Row = (
+            Option<bool>,
+            Option<i32>,
+            Option<i64>,
+            Option<String>,
+            Option<i32>,
+        );
+
+        #[derive(Default)]
+        struct FlatVisitor {
+            visited_primitive: bool,
+            rows: Vec<Row>,
+        }
+        impl RowVisitor for FlatVisitor {
+            fn selected_column_names_and_types(
+                &self,
+            ) -> (&'static [ColumnName], &'static [DataType]) {
+                static NAMES_AND_TYPES: std::sync::LazyLock<ColumnNamesAndTypes> =
+                    std::sync::LazyLock::new(|| {
+                        let names =
+                            ["b", "i", "l", "s", "missing"].map(|name| ColumnName::new([name]));
+                        let types = [
+                            DataType::BOOLEAN,
+                            DataType::INTEGER,
+                            DataType::LONG,
+                            DataType::STRING,
+                            DataType::INTEGER,
+                        ];
+                        (names.to_vec(), types.to_vec()).into()
+                    });
+                NAMES_AND_TYPES.as_ref()
+            }
+            fn visit<'a>(
+                &mut self,
+                row_count: usize,
+                getters: &[&'a dyn GetData<'a>],
+            ) -> DeltaResult<()> {
+                for i in 0..row_count {
+                    self.rows.push((
+                        getters[0].get_opt(i, "b")?,
+                        getters[1].get_opt(i, "i")?,
+                        getters[2].get_opt(i, "l")?,
+                        getters[3].get_opt(i, "s")?,
+                        getters[4].get_opt(i, "missing")?,
+                    ));
+                }
+                Ok(())
+            }
+            fn visit_primitive<'a>(
+                &mut self,
+                row_count: usize,
+                columns: &'a [PrimitiveColumn<'a>],
+            ) -> DeltaResult<()> {
+                self.visited_primitive = true;
+                assert!(matches!(columns[4], PrimitiveColumn::Null));
+                let getters: Vec<&'a dyn GetData<'a>> = columns.iter().map(|c| c as _).collect();
+                self.visit(row_count, &getters)
+            }
+        }
+
+        let schema = Arc::new(ArrowSchema::new(vec![
+            ArrowField::new("b", ArrowDataType::Boolean, true),
+            ArrowField::new("i", ArrowDataType::Int32, true),
+            ArrowField::new("l", ArrowDataType::Int64, true),
+            ArrowField::new("s", ArrowDataType::Utf8, true),
+            ArrowField::new("missing", ArrowDataType::Null, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(BooleanArray::from(vec![
+                    Some(false),
+                    Some(true),
+                    None,
+                    Some(false),
+                    Some(true),
+                    Some(true),
+                    Some(false),
+                    Some(true),
+                    Some(true),
+                    None,
+                ])),
+                Arc::new(Int32Array::from_iter(
+                    (0..10).map(|i| (i % 3 != 0).then_some(i)),
+                )),
+                Arc::new(Int64Array::from_iter(
+                    (0..10).map(|i| (i % 4 != 0).then_some(i * 10)),
+                )),
+                Arc::new(StringArray::from_iter(
+                    (0..10).map(|i| (i % 5 != 0).then(|| format!("ü{i}"))),
+                )),
+                Arc::new(crate::arrow::array::NullArray::new(10)),
+            ],
+        )?;
+        // Slice the batch so that the columns' bitmaps and string offsets don't start at zero
+        let data = ArrowEngineData::new(batch.slice(3, 7));
+        let mut visitor = FlatVisitor::default();
+        visitor.visit_rows_of(&data)?;
+        assert!(visitor.visited_primitive);
+        let expected: Vec<_> = (3..10)
+            .map(|i| {
+                let b = [false, true, true, false, true, true, false][i - 3];
+                (
+                    (i != 9).then_some(b),
+                    (i % 3 != 0).then_some(i as i32),
+                    (i % 4 != 0).then_some(i as i64 * 10),
+                    (i % 5 != 0).then(|| format!("ü{i}")),
+                    None,
+                )
+            })
+            .collect();
+        assert_eq!(visitor.rows, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_batch_round_trip_preserves_metadata() -> DeltaResult<()> {
+        let field = ArrowField::new("id", ArrowDataType::Int32, false)
+            .with_metadata([("delta.columnMapping.id".to_string(), "1".to_string())].into());
+        let schema = ArrowSchema::new(vec![field])
+            .with_metadata([("origin".to_string(), "test".to_string())].into());
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+        )?;
+
+        let engine_data: Box<dyn EngineData> = Box::new(ArrowEngineData::from(batch.clone()));
+        let round_tripped = RecordBatch::try_from(engine_data)?;
+        assert_eq!(round_tripped, batch);
+        assert_eq!(round_tripped.schema().metadata()["origin"], "test");
+        assert_eq!(
+            round_tripped.schema().field(0).metadata()["delta.columnMapping.id"],
+            "1"
+        );
+
+        let engine_data: Box<dyn EngineData> = Box::new(ArrowEngineData::new(batch.clone()));
+        let arrow_data = ArrowEngineData::try_from(engine_data)?;
+        assert_eq!(arrow_data.into_record_batch(), batch);
+        Ok(())
+    }
+
+    #[test]
+    fn test_record_batch_from_non_arrow_engine_data() {
+        struct NotArrow;
+        impl EngineData for NotArrow {
+            fn visit_rows(&self, _: &[ColumnName], _: &mut dyn RowVisitor) -> DeltaResult<()> {
+                Ok(())
+            }
+            fn len(&self) -> usize {
+                0
+            }
+            fn append_columns(
+                &self,
+                _: SchemaRef,
+                _: Vec<ArrayData>,
+            ) -> DeltaResult<Box<dyn EngineData>> {
+                Ok(Box::new(NotArrow))
+            }
+        }
+
+        assert_result_error_with_message(
+            ArrowEngineData::try_into_record_batch(Box::new(NotArrow)),
+            "Invalid engine data type. Could not convert to ArrowEngineData",
+        );
+        assert!(matches!(
+            RecordBatch::try_from(Box::new(NotArrow) as Box<dyn EngineData>),
+            Err(Error::EngineDataType(_))
+        ));
+    }
 }
@@ -11,9 +11,12 @@ use crate::arrow::datatypes::Schema as ArrowSchema;
 use crate::arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField};
 
 use super::super::arrow_utils::make_arrow_error;
-use crate::engine::ensure_data_types::ensure_data_types;
+use crate::arrow::compute::cast;
+use crate::engine::arrow_variant::validate_unshredded_variant;
+use crate::engine::ensure_data_types::{ensure_data_types, DataTypeCompat};
 use crate::error::{DeltaResult, Error};
-use crate::schema::{ArrayType, DataType, MapType, Schema, StructField};
+use crate::parquet::arrow::PARQUET_FIELD_ID_META_KEY;
+use crate::schema::{ArrayType, ColumnMetadataKey, DataType, MapType, Schema, StructField};
 
 // Apply a schema to an array. The array _must_ be a `StructArray`. Returns a `RecordBatch where the
 // names of fields, nullable, and metadata in the struct have been transformed to match those in
@@ -54,6 +57,17 @@ fn new_field_with_metadata(
     field
 }
 
+// Convert the metadata of a kernel field to arrow field metadata. Field IDs (as set for column
+// mapping `id` mode) are additionally exposed under the key that the parquet writer reads field IDs
+// from, so that written files carry them.
+fn arrow_field_metadata(field: &StructField) -> HashMap<String, String> {
+    let mut metadata = field.metadata_with_string_values();
+    if let Some(field_id) = metadata.get(ColumnMetadataKey::ParquetFieldId.as_ref()) {
+        metadata.insert(PARQUET_FIELD_ID_META_KEY.to_string(), field_id.clone());
+    }
+    metadata
+}
+
 // A helper that is a wrapper over `transform_field_and_col`. This will take apart the passed struct
 // and use that method to transform each column and then put the struct back together. Target types
 // and names for each column should be passed in `target_types_and_names`. The number of elements in
@@ -77,7 +91,7 @@ fn transform_struct(
                     &target_field.name,
                     transformed_col.data_type(),
                     target_field.nullable,
-                    Some(target_field.metadata_with_string_values()),
+                    Some(arrow_field_metadata(target_field)),
                 );
                 Ok((transformed_field, transformed_col))
             });
@@ -168,18 +182,21 @@ fn apply_schema_to_map(array: &dyn Array, kernel_map_type: &MapType) -> DeltaRes
     )?)
 }
 
-// apply `schema` to `array`. This handles renaming, and adjusting nullability and metadata. if the
-// actual data types don't match, this will return an error
+// apply `schema` to `array`. This handles renaming, and adjusting nullability and metadata. Data
+// types that can be safely upcast to the types of the schema (e.g. the narrower types of columns
+// whose type was widened) are cast. Variant columns are validated to be well-formed unshredded
+// variants. If the actual data types don't match otherwise, this will return an error
 pub(crate) fn apply_schema_to(array: &ArrayRef, schema: &DataType) -> DeltaResult<ArrayRef> {
     use DataType::*;
     let array: ArrayRef = match schema {
         Struct(stype) => Arc::new(apply_schema_to_struct(array, stype)?),
         Array(atype) => Arc::new(apply_schema_to_list(array, atype)?),
         Map(mtype) => Arc::new(apply_schema_to_map(array, mtype)?),
-        _ => {
-            ensure_data_types(schema, array.data_type(), true)?;
-            array.clone()
-        }
+        Variant(_) => validate_unshredded_variant(array)?,
+        _ => match ensure_data_types(schema, array.data_type(), true)? {
+            DataTypeCompat::NeedsCast(target) => cast(array, &target)?,
+            DataTypeCompat::Identical | DataTypeCompat::Nested => array.clone(),
+        },
     };
     Ok(array)
 }
@@ -190,10 +207,11 @@ mod apply_schema_validation_tests {
 
     use std::sync::Arc;
 
-    use crate::arrow::array::{Int32Array, StructArray};
+    use crate::arrow::array::{BinaryArray, Int32Array, StructArray};
     use crate::arrow::datatypes::{
         DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema,
     };
+    use crate::engine::arrow_data::unshredded_variant_arrow_type;
     use crate::schema::{DataType, StructField, StructType};
 
     #[test]
@@ -215,6 +233,74 @@ mod apply_schema_validation_tests {
         assert_eq!(result_array.num_columns(), 2, "Should have 2 columns");
     }
 
+    #[test]
+    fn test_apply_schema_upcasts_widened_types() {
+        let input_array = create_test_struct_array_2_fields();
+        let target_schema = StructType::new_unchecked([
+            StructField::new("a", DataType::LONG, false),
+            StructField::new("b", DataType::decimal(12, 2).unwrap(), false),
+        ]);
+
+        let result_array = apply_schema_to_struct(&input_array, &target_schema).unwrap();
+        assert_eq!(result_array.column(0).data_type(), &ArrowDataType::Int64);
+        assert_eq!(
+            result_array.column(1).data_type(),
+            &ArrowDataType::Decimal128(12, 2)
+        );
+
+        // narrowing casts are not allowed
+        let target_schema = StructType::new_unchecked([
+            StructField::new("a", DataType::SHORT, false),
+            StructField::new("b", DataType::INTEGER, false),
+        ]);
+        assert!(apply_schema_to_struct(&input_array, &target_schema).is_err());
+    }
+
+    #[test]
+    fn test_apply_schema_validates_variants() {
+        let variant_array = |metadata: &[u8], value: &[u8]| -> ArrayRef {
+            // the value and metadata fields are flipped and nullable
+            Arc::new(StructArray::from(vec![
+                (
+                    Arc::new(ArrowField::new("value", ArrowDataType::Binary, true)),
+                    Arc::new(BinaryArray::from(vec![value])) as ArrayRef,
+                ),
+                (
+                    Arc::new(ArrowField::new("metadata", ArrowDataType::Binary, true)),
+                    Arc::new(BinaryArray::from(vec![metadata])) as ArrayRef,
+                ),
+            ]))
+        };
+
+        // the variant int 1, in the canonical representation
+        let result = apply_schema_to(
+            &variant_array(&[0x01, 0x00, 0x00], &[0x0C, 0x01]),
+            &DataType::unshredded_variant(),
+        )
+        .unwrap();
+        assert_eq!(result.data_type(), &unshredded_variant_arrow_type());
+
+        // unsupported metadata version
+        let result = apply_schema_to(
+            &variant_array(&[0x02, 0x00, 0x00], &[0x0C, 0x01]),
+            &DataType::unshredded_variant(),
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid Variant value in row 0"));
+
+        // not a variant
+        let result = apply_schema_to(
+            &(Arc::new(Int32Array::from(vec![1])) as ArrayRef),
+            &DataType::unshredded_variant(),
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid Variant column"));
+    }
+
     // Helper functions to create test data
     fn create_test_struct_array_2_fields() -> StructArray {
         let field1 = ArrowField::new("a", ArrowDataType::Int32, false);
@@ -6,33 +6,40 @@ use itertools::Itertools;
 
 use crate::arrow::array::types::*;
 use crate::arrow::array::{
-    make_array, Array, ArrayData, ArrayRef, AsArray, BooleanArray, Datum, MutableArrayData,
-    NullBufferBuilder, RecordBatch, StringArray, StructArray,
+    make_array, new_null_array, Array, ArrayData, ArrayRef, AsArray, BooleanArray, Datum,
+    MutableArrayData, NullBufferBuilder, RecordBatch, Scalar as ArrowScalar, StringArray,
+    StructArray, UInt64Array,
 };
-use crate::arrow::buffer::OffsetBuffer;
+use crate::arrow::buffer::{NullBuffer, OffsetBuffer};
 use crate::arrow::compute::kernels::cmp::{distinct, eq, gt, gt_eq, lt, lt_eq, neq, not_distinct};
-use crate::arrow::compute::kernels::comparison::in_list_utf8;
-use crate::arrow::compute::kernels::numeric::{add, div, mul, sub};
-use crate::arrow::compute::{and_kleene, is_not_null, is_null, not, or_kleene};
+use crate::arrow::compute::kernels::comparison::{ilike, in_list_utf8, like, nilike, nlike};
+use crate::arrow::compute::kernels::nullif::nullif;
+use crate::arrow::compute::kernels::numeric::{add, div, mul, rem, sub};
+use crate::arrow::compute::kernels::zip::zip;
+use crate::arrow::compute::{
+    and_kleene, cast_with_options, is_not_null, is_null, not, or_kleene, prep_null_mask_filter,
+    take, CastOptions,
+};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, Fields as ArrowFields, IntervalUnit, TimeUnit,
 };
 use crate::arrow::error::ArrowError;
 use crate::arrow::json::writer::{make_encoder, EncoderOptions};
 use crate::arrow::json::StructMode;
-use crate::engine::arrow_conversion::TryIntoArrow;
+use crate::engine::arrow_conversion::{TryIntoArrow, TryIntoKernel};
 use crate::engine::arrow_expression::opaque::{
     ArrowOpaqueExpressionOpAdaptor, ArrowOpaquePredicateOpAdaptor,
 };
 use crate::engine::arrow_utils::prim_array_cmp;
 use crate::error::{DeltaResult, Error};
 use crate::expressions::{
-    BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, Expression,
-    ExpressionRef, JunctionPredicate, JunctionPredicateOp, OpaqueExpression, OpaquePredicate,
-    Predicate, Scalar, Transform, UnaryExpression, UnaryExpressionOp, UnaryPredicate,
-    UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
+    to_decimal_type, BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp,
+    CaseExpression, CastExpression, CastOverflowPolicy, ElementAccessor, ElementExpression,
+    Expression, ExpressionRef, JunctionPredicate, JunctionPredicateOp, OpaqueExpression,
+    OpaquePredicate, Predicate, Scalar, Transform, UnaryExpression, UnaryExpressionOp,
+    UnaryPredicate, UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
 };
-use crate::schema::{DataType, StructType};
+use crate::schema::{DataType, PrimitiveType, StructType};
 
 pub(super) trait ProvidesColumnByName {
     fn schema_fields(&self) -> &ArrowFields;
@@ -215,7 +222,6 @@ pub fn evaluate_expression(
     batch: &RecordBatch,
     result_type: Option<&DataType>,
 ) -> DeltaResult<ArrayRef> {
-    use BinaryExpressionOp::*;
     use Expression::*;
     use UnaryExpressionOp::*;
     use VariadicExpressionOp::*;
@@ -253,16 +259,7 @@ pub fn evaluate_expression(
         (Binary(BinaryExpression { op, left, right }), _) => {
             let left_arr = evaluate_expression(left.as_ref(), batch, None)?;
             let right_arr = evaluate_expression(right.as_ref(), batch, None)?;
-
-            type Operation = fn(&dyn Datum, &dyn Datum) -> Result<ArrayRef, ArrowError>;
-            let eval: Operation = match op {
-                Plus => add,
-                Minus => sub,
-                Multiply => mul,
-                Divide => div,
-            };
-
-            Ok(eval(&left_arr, &right_arr)?)
+            eval_arithmetic(*op, &left_arr, &right_arr)
         }
         (
             Variadic(VariadicExpression {
@@ -277,6 +274,35 @@ pub fn evaluate_expression(
                 .try_collect()?;
             Ok(coalesce_arrays(&arrays, result_type)?)
         }
+        (Variadic(VariadicExpression { op: NullIf, exprs }), result_type) => {
+            let [a, b] = &exprs[..] else {
+                return Err(Error::invalid_expression(format!(
+                    "NULLIF requires exactly two arguments, but got {}",
+                    exprs.len()
+                )));
+            };
+            let a = evaluate_expression(a, batch, result_type)?;
+            let b = evaluate_expression(b, batch, None)?;
+            Ok(eval_null_if(&a, &b)?)
+        }
+        (Case(case), result_type) => evaluate_case_expression(case, batch, result_type),
+        (
+            Cast(CastExpression {
+                expr,
+                to,
+                on_overflow,
+            }),
+            _,
+        ) => {
+            let input = evaluate_expression(expr, batch, None)?;
+            let to: ArrowDataType = to.try_into_arrow()?;
+            let options = CastOptions {
+                safe: *on_overflow == CastOverflowPolicy::Null,
+                ..Default::default()
+            };
+            Ok(cast_with_options(&input, &to, &options)?)
+        }
+        (Element(element), _) => evaluate_element_expression(element, batch),
         (Opaque(OpaqueExpression { op, exprs }), _) => {
             match op
                 .any_ref()
@@ -292,6 +318,155 @@ pub fn evaluate_expression(
     }
 }
 
+/// Evaluates a `CASE WHEN` expression. Every THEN (and ELSE) result is cast to the requested
+/// result type, or to the type of the first THEN result if no result type was requested. Branches
+/// are applied from last to first, so that rows matching several conditions take the first match.
+/// A NULL condition counts as not matched, and rows that match no condition take the ELSE result
+/// (or NULL if there is no ELSE).
+fn evaluate_case_expression(
+    case: &CaseExpression,
+    batch: &RecordBatch,
+    result_type: Option<&DataType>,
+) -> DeltaResult<ArrayRef> {
+    let CaseExpression {
+        branches,
+        else_expr,
+    } = case;
+    let Some((_, first_then)) = branches.first() else {
+        return Err(Error::invalid_expression(
+            "CASE expression requires at least one WHEN branch",
+        ));
+    };
+    let first_then = evaluate_expression(first_then, batch, result_type)?;
+    let output_type = match result_type {
+        Some(result_type) => result_type.try_into_arrow()?,
+        None => first_then.data_type().clone(),
+    };
+    let cast_to_output = |arr: ArrayRef| -> DeltaResult<ArrayRef> {
+        if arr.data_type() == &output_type {
+            return Ok(arr);
+        }
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        Ok(cast_with_options(&arr, &output_type, &options)?)
+    };
+
+    let mut result = match else_expr {
+        Some(else_expr) => cast_to_output(evaluate_expression(else_expr, batch, result_type)?)?,
+        None => new_null_array(&output_type, batch.num_rows()),
+    };
+    for (i, (when, then)) in branches.iter().enumerate().rev() {
+        let then = match i {
+            0 => first_then.clone(),
+            _ => evaluate_expression(then, batch, result_type)?,
+        };
+        // A NULL condition does not match, so treat it as false (masks without nulls are as-is)
+        let mut mask = evaluate_predicate(when, batch, false)?;
+        if mask.null_count() > 0 {
+            mask = prep_null_mask_filter(&mask);
+        }
+        result = zip(&mask, &cast_to_output(then)?, &result)?;
+    }
+    Ok(result)
+}
+
+/// Evaluates an element access expression. Rows whose input is NULL, whose map does not contain
+/// the key, or whose array is too short for the index produce NULL.
+fn evaluate_element_expression(
+    element: &ElementExpression,
+    batch: &RecordBatch,
+) -> DeltaResult<ArrayRef> {
+    let ElementExpression { expr, accessor } = element;
+    let input = evaluate_expression(expr, batch, None)?;
+    let wrong_type = |expected: &str| {
+        Error::generic(format!(
+            "Element access {accessor:?} expects a {expected}, but got {}",
+            input.data_type()
+        ))
+    };
+    match accessor {
+        ElementAccessor::MapValue(key) => {
+            let map = input.as_map_opt().ok_or_else(|| wrong_type("map"))?;
+            let options = CastOptions {
+                safe: false,
+                ..Default::default()
+            };
+            let key = cast_with_options(&key.to_array(1)?, map.keys().data_type(), &options)?;
+            let matches = eq(map.keys(), &ArrowScalar::new(key))?;
+            let offsets = map.value_offsets();
+            let indices: UInt64Array = (0..map.len())
+                .map(|row| {
+                    if map.is_null(row) {
+                        return None;
+                    }
+                    let mut entries = offsets[row] as usize..offsets[row + 1] as usize;
+                    let entry = entries.find(|&i| matches.is_valid(i) && matches.value(i))?;
+                    Some(entry as u64)
+                })
+                .collect();
+            Ok(take(map.values(), &indices, None)?)
+        }
+        ElementAccessor::ArrayElement(index) => {
+            let list = input
+                .as_list_opt::<i32>()
+                .ok_or_else(|| wrong_type("list"))?;
+            let offsets = list.value_offsets();
+            let indices: UInt64Array = (0..list.len())
+                .map(|row| {
+                    let (start, end) = (offsets[row] as usize, offsets[row + 1] as usize);
+                    let in_bounds = list.is_valid(row) && *index < end - start;
+                    in_bounds.then(|| (start + index) as u64)
+                })
+                .collect();
+            Ok(take(list.values(), &indices, None)?)
+        }
+        ElementAccessor::StructField(ordinal) => {
+            let data = input.as_struct_opt().ok_or_else(|| wrong_type("struct"))?;
+            let column = data.columns().get(*ordinal).ok_or_else(|| {
+                Error::generic(format!(
+                    "Struct field ordinal {ordinal} out of bounds for {}",
+                    input.data_type()
+                ))
+            })?;
+            // The field is NULL wherever the struct itself is NULL
+            let nulls = NullBuffer::union(data.nulls(), column.nulls());
+            let column = column.to_data().into_builder().nulls(nulls).build()?;
+            Ok(make_array(column))
+        }
+    }
+}
+
+/// Evaluates `NULLIF(a, b)`, nulling out each row of `a` that equals the same row of `b`. Rows
+/// where either side is NULL keep the value of `a`. If the types differ, `b` is cast to the type
+/// of `a`.
+fn eval_null_if(a: &ArrayRef, b: &ArrayRef) -> Result<ArrayRef, ArrowError> {
+    let b = match a.data_type() == b.data_type() {
+        true => b.clone(),
+        false => {
+            let options = CastOptions {
+                safe: false,
+                ..Default::default()
+            };
+            cast_with_options(b, a.data_type(), &options)?
+        }
+    };
+    nullif(a, &eq(a, &b)?)
+}
+
+/// Evaluates the IN-list `values IN (list)` with SQL semantics: a row is TRUE if its value equals
+/// any list element. Otherwise, it is NULL if its value is NULL or the list contains NULL, and FALSE
+/// if not.
+fn eval_in_list(values: &ArrayRef, list: &[Scalar]) -> DeltaResult<BooleanArray> {
+    let mut result = BooleanArray::from(vec![false; values.len()]);
+    for element in list {
+        let element = ArrowScalar::new(element.to_array(1)?);
+        result = or_kleene(&result, &eq(values, &element)?)?;
+    }
+    Ok(result)
+}
+
 /// Evaluates a (possibly inverted) kernel predicate over a record batch
 pub fn evaluate_predicate(
     predicate: &Predicate,
@@ -379,6 +554,11 @@ pub fn evaluate_predicate(
                         (Decimal256(_, _), Decimal256Type)
                     }
                 }
+                (Expression::Column(_), Expression::Literal(Scalar::Array(ad))) => {
+                    let left = evaluate_expression(left, batch, None)?;
+                    #[allow(deprecated)]
+                    eval_in_list(&left, ad.array_elements())
+                }
                 (Expression::Literal(lit), Expression::Literal(Scalar::Array(ad))) => {
                     #[allow(deprecated)]
                     let exists = ad.array_elements().contains(lit);
@@ -398,6 +578,10 @@ pub fn evaluate_predicate(
                 (Equal, true) => neq,
                 (Distinct, false) => distinct,
                 (Distinct, true) => not_distinct,
+                (Like, false) => like,
+                (Like, true) => nlike,
+                (ILike, false) => ilike,
+                (ILike, true) => nilike,
                 (In, _) => return Ok(maybe_inverted(Cow::Owned(eval_in()?))?),
             };
 
@@ -436,6 +620,103 @@ pub fn evaluate_predicate(
     }
 }
 
+/// Evaluates an arithmetic operation, coercing the operands according to
+/// [`BinaryExpressionOp::result_type`]. Arrow requires operands of the same type (except for
+/// decimals, whose result precision and scale it derives on its own), so the operands are cast to
+/// a common type first, and the result is then cast to the expected result type.
+fn eval_arithmetic(
+    op: BinaryExpressionOp,
+    left: &ArrayRef,
+    right: &ArrayRef,
+) -> DeltaResult<ArrayRef> {
+    use BinaryExpressionOp::*;
+    let left_type: DataType = left.data_type().try_into_kernel()?;
+    let right_type: DataType = right.data_type().try_into_kernel()?;
+    let result_type = op.result_type(&left_type, &right_type)?;
+    let arrow_result_type: ArrowDataType = (&result_type).try_into_arrow()?;
+
+    type Operation = fn(&dyn Datum, &dyn Datum) -> Result<ArrayRef, ArrowError>;
+    let eval: Operation = match op {
+        Plus => add,
+        Minus => sub,
+        Multiply => mul,
+        Divide => div,
+        Modulo => rem,
+    };
+    // Overflowing casts must fail rather than silently produce NULL
+    let cast_options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let cast_to = |array: &ArrayRef, data_type: &ArrowDataType| {
+        cast_with_options(array, data_type, &cast_options)
+    };
+    let result = match result_type {
+        // Arrow only adds intervals to dates, so operate on the underlying day numbers instead
+        DataType::Primitive(PrimitiveType::Date) => {
+            let days = eval(
+                &cast_to(left, &ArrowDataType::Int32)?,
+                &cast_to(right, &ArrowDataType::Int32)?,
+            )?;
+            cast_to(&days, &ArrowDataType::Date32)?
+        }
+        DataType::Primitive(PrimitiveType::Decimal(result_decimal)) => {
+            let as_decimal = |data_type: &DataType, min_scale: u8| -> DeltaResult<ArrowDataType> {
+                let decimal = match data_type {
+                    DataType::Primitive(t) => to_decimal_type(t),
+                    _ => None,
+                };
+                let decimal = decimal.ok_or_else(|| {
+                    Error::internal_error(format!("Expected a decimal operand, got {data_type}"))
+                })?;
+                // Increasing the scale also increases the precision, up to the maximum of 38
+                let scale = decimal.scale().max(min_scale);
+                let precision = (decimal.precision() + scale - decimal.scale()).min(38);
+                Ok(ArrowDataType::Decimal128(
+                    precision,
+                    scale.min(precision) as i8,
+                ))
+            };
+            // Arrow's decimal division produces 4 more digits of scale than the dividend has, so
+            // increase the scale of the dividend to produce (at least) the expected result scale.
+            let left_min_scale = match op {
+                Divide => result_decimal.scale().saturating_sub(4),
+                _ => 0,
+            };
+            let result = eval(
+                &cast_to(left, &as_decimal(&left_type, left_min_scale)?)?,
+                &cast_to(right, &as_decimal(&right_type, 0)?)?,
+            )?;
+            cast_to(&result, &arrow_result_type)?
+        }
+        _ => {
+            // Integral division produces a double, but must still fail on division by zero
+            let is_integral = |data_type: &DataType| {
+                use PrimitiveType::*;
+                matches!(
+                    data_type,
+                    DataType::Primitive(Byte | Short | Integer | Long)
+                )
+            };
+            if op == Divide && is_integral(&left_type) && is_integral(&right_type) {
+                let divisor = cast_to(right, &ArrowDataType::Int64)?;
+                if divisor
+                    .as_primitive::<Int64Type>()
+                    .iter()
+                    .any(|v| v == Some(0))
+                {
+                    return Err(ArrowError::DivideByZero.into());
+                }
+            }
+            eval(
+                &cast_to(left, &arrow_result_type)?,
+                &cast_to(right, &arrow_result_type)?,
+            )?
+        }
+    };
+    Ok(result)
+}
+
 /// Converts a StructArray to JSON-encoded strings
 pub fn to_json(input: &dyn Datum) -> Result<ArrayRef, ArrowError> {
     let (array_ref, _is_scalar) = input.get();
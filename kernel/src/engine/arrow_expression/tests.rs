@@ -1,8 +1,10 @@
-use std::ops::{Add, Div, Mul, Sub};
+use std::ops::{Add, Div, Mul, Rem, Sub};
 
+use crate::arrow::array::types::Int32Type;
 use crate::arrow::array::{
-    create_array, Array, ArrayRef, BooleanArray, GenericStringArray, Int32Array, Int32Builder,
-    ListArray, MapArray, MapBuilder, MapFieldNames, StringArray, StringBuilder, StructArray,
+    create_array, Array, ArrayRef, BooleanArray, Date32Array, Decimal128Array, Float64Array,
+    GenericStringArray, Int32Array, Int32Builder, Int64Array, Int8Array, ListArray, MapArray,
+    MapBuilder, MapFieldNames, StringArray, StringBuilder, StructArray,
 };
 use crate::arrow::buffer::{BooleanBuffer, NullBuffer, OffsetBuffer, ScalarBuffer};
 use crate::arrow::compute::kernels::cmp::{gt_eq, lt};
@@ -264,6 +266,85 @@ fn test_invalid_array_sides() {
     assert_result_error_with_message(in_result, "Invalid expression evaluation: Invalid right value for (NOT) IN comparison, left is: Column(item) right is: Column(item)");
 }
 
+#[test]
+fn test_in_list() {
+    let field = Arc::new(Field::new("x", DataType::Int32, true));
+    let schema = Schema::new([field]);
+    let values = Int32Array::from(vec![Some(1), Some(2), None, Some(4)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+    let list = |values: Vec<Scalar>| {
+        ArrayData::try_new(ArrayType::new(KernelDataType::INTEGER, true), values).unwrap()
+    };
+
+    let in_list = Pred::in_list(column_expr!("x"), list(vec![2.into(), 4.into()]));
+    let result = evaluate_predicate(&in_list, &batch, false).unwrap();
+    let expected = BooleanArray::from(vec![Some(false), Some(true), None, Some(true)]);
+    assert_eq!(result, expected);
+
+    let not_in_list = Pred::not_in_list(column_expr!("x"), list(vec![2.into(), 4.into()]));
+    let result = evaluate_predicate(&not_in_list, &batch, false).unwrap();
+    let expected = BooleanArray::from(vec![Some(true), Some(false), None, Some(false)]);
+    assert_eq!(result, expected);
+
+    // a NULL in the list makes non-matching rows NULL
+    let in_list = Pred::in_list(
+        column_expr!("x"),
+        list(vec![2.into(), Scalar::Null(KernelDataType::INTEGER)]),
+    );
+    let result = evaluate_predicate(&in_list, &batch, false).unwrap();
+    let expected = BooleanArray::from(vec![None, Some(true), None, None]);
+    assert_eq!(result, expected);
+
+    let in_list = Pred::in_list(column_expr!("x"), list(vec![]));
+    let result = evaluate_predicate(&in_list, &batch, false).unwrap();
+    assert_eq!(result, BooleanArray::from(vec![false; 4]));
+}
+
+#[test]
+fn test_like() {
+    let field = Arc::new(Field::new("s", DataType::Utf8, true));
+    let schema = Schema::new([field]);
+    let values = StringArray::from(vec![
+        Some("abc"),
+        Some("ABC"),
+        None,
+        Some("a%c"),
+        Some("xbc"),
+    ]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+
+    let cases = [
+        (
+            Pred::like(column_expr!("s"), "a%"),
+            vec![Some(true), Some(false), None, Some(true), Some(false)],
+        ),
+        (
+            Pred::not_like(column_expr!("s"), "a%"),
+            vec![Some(false), Some(true), None, Some(false), Some(true)],
+        ),
+        (
+            Pred::like(column_expr!("s"), "_bc"),
+            vec![Some(true), Some(false), None, Some(false), Some(true)],
+        ),
+        (
+            Pred::like(column_expr!("s"), r"a\%c"),
+            vec![Some(false), Some(false), None, Some(true), Some(false)],
+        ),
+        (
+            Pred::like_with_escape(column_expr!("s"), "a#%c", '#'),
+            vec![Some(false), Some(false), None, Some(true), Some(false)],
+        ),
+        (
+            Pred::ilike(column_expr!("s"), "a%"),
+            vec![Some(true), Some(true), None, Some(true), Some(false)],
+        ),
+    ];
+    for (pred, expected) in cases {
+        let result = evaluate_predicate(&pred, &batch, false).unwrap();
+        assert_eq!(result, BooleanArray::from(expected), "{pred}");
+    }
+}
+
 #[test]
 fn test_str_arrays() {
     let values = GenericStringArray::<i32>::from(vec![
@@ -358,10 +439,9 @@ fn test_binary_op_scalar() {
     let expected = Arc::new(Int32Array::from(vec![2, 4, 6]));
     assert_eq!(results.as_ref(), expected.as_ref());
 
-    // TODO handle type casting
-    let expression = column.div(Expr::literal(1));
+    let expression = column.div(Expr::literal(2));
     let results = evaluate_expression(&expression, &batch, None).unwrap();
-    let expected = Arc::new(Int32Array::from(vec![1, 2, 3]));
+    let expected = Arc::new(Float64Array::from(vec![0.5, 1.0, 1.5]));
     assert_eq!(results.as_ref(), expected.as_ref())
 }
 
@@ -396,6 +476,271 @@ fn test_binary_op() {
     assert_eq!(results.as_ref(), expected.as_ref());
 }
 
+#[test]
+fn test_binary_op_coercion() {
+    let schema = Schema::new(vec![
+        Field::new("i", DataType::Int32, true),
+        Field::new("d", DataType::Date32, true),
+        Field::new("dec", DataType::Decimal128(5, 2), true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int32Array::from(vec![Some(7), None, Some(-7)])),
+            Arc::new(Date32Array::from(vec![Some(10), Some(20), None])),
+            Arc::new(
+                Decimal128Array::from(vec![Some(150), Some(-25), None])
+                    .with_precision_and_scale(5, 2)
+                    .unwrap(),
+            ),
+        ],
+    )
+    .unwrap();
+
+    // integral operands are widened
+    let expression = column_expr!("i").add(Expr::literal(1i64));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int64Array::from(vec![Some(8), None, Some(-6)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = column_expr!("i").rem(Expr::literal(3i8));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int32Array::from(vec![Some(1), None, Some(-1)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // integral division produces a double
+    let expression = column_expr!("i").div(Expr::literal(2i16));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Float64Array::from(vec![Some(3.5), None, Some(-3.5)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = column_expr!("i").div(Expr::literal(2.0));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Float64Array::from(vec![Some(3.5), None, Some(-3.5)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // dates add and subtract days
+    let expression = column_expr!("d").sub(Expr::literal(5));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Date32Array::from(vec![Some(5), Some(15), None]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // decimal results follow the decimal precision and scale rules
+    let expression = column_expr!("dec").add(column_expr!("i"));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Decimal128Array::from(vec![Some(850), None, None])
+        .with_precision_and_scale(13, 2)
+        .unwrap();
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = column_expr!("dec").mul(Expr::literal(2));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Decimal128Array::from(vec![Some(300), Some(-50), None])
+        .with_precision_and_scale(16, 2)
+        .unwrap();
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = column_expr!("dec").div(Expr::literal(4));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Decimal128Array::from(vec![Some(3750000000000), Some(-625000000000), None])
+        .with_precision_and_scale(16, 13)
+        .unwrap();
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // overflow and integer division by zero are errors
+    let expression = column_expr!("i").mul(Expr::literal(i32::MAX));
+    assert!(evaluate_expression(&expression, &batch, None).is_err());
+    let expression = column_expr!("i").div(Expr::literal(0));
+    assert!(evaluate_expression(&expression, &batch, None).is_err());
+
+    // unsupported operand types
+    let expression = column_expr!("d").mul(Expr::literal(2));
+    assert_result_error_with_message(
+        evaluate_expression(&expression, &batch, None),
+        "Arithmetic operator * does not support operands of type date and integer",
+    );
+}
+
+#[test]
+fn test_case_expression() {
+    let schema = Schema::new(vec![Field::new("i", DataType::Int32, true)]);
+    let values = Int32Array::from(vec![Some(1), Some(5), None, Some(10)]);
+    let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(values)]).unwrap();
+
+    // the first matching branch wins, and NULL conditions fall through to ELSE
+    let expression = Expr::case(
+        [
+            (column_expr!("i").lt(Expr::literal(3)), Expr::literal(1i64)),
+            (
+                column_expr!("i").lt(Expr::literal(8)),
+                column_expr!("i").mul(Expr::literal(2i64)),
+            ),
+        ],
+        Some(Expr::literal(-1i64)),
+    );
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int64Array::from(vec![Some(1), Some(10), Some(-1), Some(-1)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // without ELSE, unmatched rows are NULL; results are cast to the requested type
+    let expression = Expr::case(
+        [(column_expr!("i").gt(Expr::literal(3)), column_expr!("i"))],
+        None,
+    );
+    let results = evaluate_expression(&expression, &batch, Some(&KernelDataType::LONG)).unwrap();
+    let expected = Int64Array::from(vec![None, Some(5), None, Some(10)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = Expr::if_then_else(
+        Pred::is_null(column_expr!("i")),
+        Expr::literal("missing"),
+        Expr::literal("present"),
+    );
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = StringArray::from(vec!["present", "present", "missing", "present"]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+}
+
+#[test]
+fn test_cast() {
+    let schema = Schema::new(vec![
+        Field::new("i", DataType::Int64, true),
+        Field::new("s", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from(vec![Some(1), Some(300), None])),
+            Arc::new(StringArray::from(vec![Some("7"), Some("x"), None])),
+        ],
+    )
+    .unwrap();
+
+    let expression = Expr::cast(column_expr!("i"), KernelDataType::DOUBLE);
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Float64Array::from(vec![Some(1.0), Some(300.0), None]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // values that do not fit the target type are errors, or NULL for TRY_CAST
+    let expression = Expr::cast(column_expr!("i"), KernelDataType::BYTE);
+    assert!(evaluate_expression(&expression, &batch, None).is_err());
+    let expression = Expr::try_cast(column_expr!("i"), KernelDataType::BYTE);
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int8Array::from(vec![Some(1), None, None]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = Expr::cast(column_expr!("s"), KernelDataType::INTEGER);
+    assert!(evaluate_expression(&expression, &batch, None).is_err());
+    let expression = Expr::try_cast(column_expr!("s"), KernelDataType::INTEGER);
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int32Array::from(vec![Some(7), None, None]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+}
+
+#[test]
+fn test_element_access() {
+    let mut tags = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+    tags.keys().append_value("env");
+    tags.values().append_value("prod");
+    tags.keys().append_value("team");
+    tags.values().append_value("a");
+    tags.append(true).unwrap();
+    tags.keys().append_value("team");
+    tags.values().append_value("b");
+    tags.append(true).unwrap();
+    tags.append(false).unwrap();
+    let ids = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1), Some(2)]),
+        Some(vec![Some(3)]),
+        None,
+    ]);
+    let s = StructArray::try_new(
+        Fields::from(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new("b", DataType::Int32, true),
+        ]),
+        vec![
+            Arc::new(StringArray::from(vec!["x", "y", "z"])),
+            Arc::new(Int32Array::from(vec![10, 20, 30])),
+        ],
+        Some(NullBuffer::from(vec![true, true, false])),
+    )
+    .unwrap();
+    let batch = RecordBatch::try_from_iter(vec![
+        ("tags", Arc::new(tags.finish()) as ArrayRef),
+        ("ids", Arc::new(ids) as ArrayRef),
+        ("s", Arc::new(s) as ArrayRef),
+    ])
+    .unwrap();
+
+    let expression = Expr::map_value(column_expr!("tags"), "env");
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = StringArray::from(vec![Some("prod"), None, None]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = Expr::array_element(column_expr!("ids"), 1);
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int32Array::from(vec![Some(2), None, None]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = Expr::struct_field(column_expr!("s"), 1);
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int32Array::from(vec![Some(10), Some(20), None]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // element access composes with predicates
+    let pred = Pred::eq(
+        Expr::map_value(column_expr!("tags"), "team"),
+        Expr::literal("b"),
+    );
+    let results = evaluate_predicate(&pred, &batch, false).unwrap();
+    assert_eq!(
+        results,
+        BooleanArray::from(vec![Some(false), Some(true), None])
+    );
+
+    let expression = Expr::array_element(column_expr!("tags"), 0);
+    assert_result_error_with_message(
+        evaluate_expression(&expression, &batch, None),
+        "expects a list",
+    );
+    let expression = Expr::struct_field(column_expr!("s"), 2);
+    assert!(evaluate_expression(&expression, &batch, None).is_err());
+}
+
+#[test]
+fn test_null_if() {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Int64, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from(vec![Some(1), Some(2), None, Some(4)])),
+            Arc::new(Int64Array::from(vec![Some(1), Some(3), Some(3), None])),
+        ],
+    )
+    .unwrap();
+
+    let expression = Expr::null_if(column_expr!("a"), column_expr!("b"));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int64Array::from(vec![None, Some(2), None, Some(4)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    // the second operand is cast to the type of the first
+    let expression = Expr::null_if(column_expr!("a"), Expr::literal(2));
+    let results = evaluate_expression(&expression, &batch, None).unwrap();
+    let expected = Int64Array::from(vec![Some(1), None, None, Some(4)]);
+    assert_eq!(results.as_ref(), &expected as &dyn Array);
+
+    let expression = Expr::variadic(VariadicExpressionOp::NullIf, [column_expr!("a")]);
+    assert_result_error_with_message(
+        evaluate_expression(&expression, &batch, None),
+        "NULLIF requires exactly two arguments, but got 1",
+    );
+}
+
 #[test]
 fn test_binary_cmp() {
     let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
@@ -3,12 +3,17 @@ use crate::arrow::array::{
     Array, BooleanArray, GenericByteArray, GenericListArray, MapArray, OffsetSizeTrait,
     PrimitiveArray,
 };
+use crate::arrow::buffer::NullBuffer;
 
 use crate::{
-    engine_data::{GetData, ListItem, MapItem},
+    engine_data::{ColumnBitmap, GetData, ListItem, MapItem, PrimitiveColumn},
     DeltaResult,
 };
 
+fn validity(nulls: Option<&NullBuffer>) -> Option<ColumnBitmap<'_>> {
+    nulls.map(|nulls| ColumnBitmap::new(nulls.validity(), nulls.offset()))
+}
+
 // actual impls (todo: could macro these)
 
 impl GetData<'_> for BooleanArray {
@@ -19,6 +24,14 @@ impl GetData<'_> for BooleanArray {
             Ok(None)
         }
     }
+
+    fn as_primitive_column(&self) -> Option<PrimitiveColumn<'_>> {
+        let values = self.values();
+        Some(PrimitiveColumn::Boolean {
+            values: ColumnBitmap::new(values.values(), values.offset()),
+            validity: validity(self.nulls()),
+        })
+    }
 }
 
 impl GetData<'_> for PrimitiveArray<Int32Type> {
@@ -29,6 +42,13 @@ impl GetData<'_> for PrimitiveArray<Int32Type> {
             Ok(None)
         }
     }
+
+    fn as_primitive_column(&self) -> Option<PrimitiveColumn<'_>> {
+        Some(PrimitiveColumn::Integer {
+            values: self.values(),
+            validity: validity(self.nulls()),
+        })
+    }
 }
 
 impl GetData<'_> for PrimitiveArray<Int64Type> {
@@ -39,6 +59,13 @@ impl GetData<'_> for PrimitiveArray<Int64Type> {
             Ok(None)
         }
     }
+
+    fn as_primitive_column(&self) -> Option<PrimitiveColumn<'_>> {
+        Some(PrimitiveColumn::Long {
+            values: self.values(),
+            validity: validity(self.nulls()),
+        })
+    }
 }
 
 impl<'a> GetData<'a> for GenericByteArray<GenericStringType<i32>> {
@@ -49,6 +76,22 @@ impl<'a> GetData<'a> for GenericByteArray<GenericStringType<i32>> {
             Ok(None)
         }
     }
+
+    fn as_primitive_column(&'a self) -> Option<PrimitiveColumn<'a>> {
+        let offsets = self.value_offsets();
+        let start = usize::try_from(*offsets.first()?).ok()?;
+        let end = usize::try_from(*offsets.last()?).ok()?;
+        let data = self.value_data().get(start..end)?;
+        // SAFETY: A string array's values are valid UTF-8 and its offsets lie on char boundaries,
+        // so the bytes from the first row's value up to the end of the last row's value are valid
+        // UTF-8 as well.
+        let data = unsafe { std::str::from_utf8_unchecked(data) };
+        Some(PrimitiveColumn::String {
+            offsets,
+            data,
+            validity: validity(self.nulls()),
+        })
+    }
 }
 
 impl<'a, OffsetSize> GetData<'a> for GenericListArray<OffsetSize>
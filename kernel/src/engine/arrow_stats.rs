@@ -0,0 +1,217 @@
+//! Collection of the file statistics of data files, see
+//! [`WriteContext::stats_columns_schema`].
+//!
+//! [`WriteContext::stats_columns_schema`]: crate::transaction::WriteContext::stats_columns_schema
+use std::sync::Arc;
+
+use crate::arrow::array::{
+    make_array, new_null_array, Array, ArrayRef, AsArray, Int64Array, RecordBatch, StructArray,
+};
+use crate::arrow::buffer::NullBuffer;
+use crate::arrow::compute::{sort_to_indices, take, SortOptions};
+use crate::arrow::datatypes::{DataType as ArrowDataType, Field, FieldRef};
+use crate::schema::{DataType, PrimitiveType, StructType};
+use crate::{DeltaResult, Error};
+
+/// The statistics of a (possibly nested) set of columns, as the fields of the `nullCount`,
+/// `minValues` and `maxValues` structs.
+#[derive(Default)]
+struct ColumnStats {
+    null_count: Vec<(FieldRef, ArrayRef)>,
+    min_values: Vec<(FieldRef, ArrayRef)>,
+    max_values: Vec<(FieldRef, ArrayRef)>,
+}
+
+/// Collects the `nullCount`, `minValues` and `maxValues` statistics of the `stats_columns` of
+/// `batch`, as the fields of a single-row struct. Min and max values are collected for all
+/// primitive columns except boolean and binary ones, like Delta Spark does.
+pub(crate) fn collect_column_stats(
+    batch: &RecordBatch,
+    stats_columns: &StructType,
+) -> DeltaResult<StructArray> {
+    let columns = |name: &str| batch.column_by_name(name);
+    let stats = collect_struct_stats(&columns, stats_columns, None)?;
+    let fields = [
+        ("nullCount", stats.null_count),
+        ("minValues", stats.min_values),
+        ("maxValues", stats.max_values),
+    ];
+    let fields = fields
+        .into_iter()
+        .filter(|(_, stats)| !stats.is_empty())
+        .map(|(name, stats)| {
+            let stats = StructArray::from(stats);
+            let field = Field::new(name, stats.data_type().clone(), true);
+            (Arc::new(field), Arc::new(stats) as ArrayRef)
+        });
+    Ok(StructArray::from(fields.collect::<Vec<_>>()))
+}
+
+/// The statistics of a file with `num_records` rows, as a single-row struct with the `numRecords`
+/// field followed by the fields of the `column_stats` (see [`collect_column_stats`]), if any.
+pub(crate) fn file_stats(
+    num_records: usize,
+    column_stats: Option<&StructArray>,
+) -> DeltaResult<StructArray> {
+    let mut fields = vec![Arc::new(Field::new(
+        "numRecords",
+        ArrowDataType::Int64,
+        true,
+    ))];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(Int64Array::from(vec![num_records as i64]))];
+    if let Some(column_stats) = column_stats {
+        fields.extend(column_stats.fields().iter().cloned());
+        columns.extend(column_stats.columns().iter().cloned());
+    }
+    Ok(StructArray::try_new_with_length(
+        fields.into(),
+        columns,
+        None,
+        1,
+    )?)
+}
+
+// Collects the stats of the `fields` of a struct, whose columns are looked up by name with
+// `columns`. Rows in `parent_nulls` are null because an ancestor struct is null.
+fn collect_struct_stats<'a>(
+    columns: &dyn Fn(&str) -> Option<&'a ArrayRef>,
+    fields: &StructType,
+    parent_nulls: Option<&NullBuffer>,
+) -> DeltaResult<ColumnStats> {
+    let mut stats = ColumnStats::default();
+    for field in fields.fields() {
+        let column = columns(field.name()).ok_or_else(|| {
+            Error::missing_column(format!("Stats column {} not found in data", field.name()))
+        })?;
+        let nulls = NullBuffer::union(parent_nulls, column.logical_nulls().as_ref());
+        match field.data_type() {
+            DataType::Struct(child_fields) => {
+                let column = column.as_struct_opt().ok_or_else(|| {
+                    Error::unexpected_column_type(format!("Expected struct for {}", field.name()))
+                })?;
+                let child_columns = |name: &str| column.column_by_name(name);
+                let child_stats =
+                    collect_struct_stats(&child_columns, child_fields, nulls.as_ref())?;
+                let as_struct = |stats: Vec<(FieldRef, ArrayRef)>| {
+                    (!stats.is_empty()).then(|| {
+                        let stats = StructArray::from(stats);
+                        let field = Field::new(field.name(), stats.data_type().clone(), true);
+                        (Arc::new(field), Arc::new(stats) as ArrayRef)
+                    })
+                };
+                stats.null_count.extend(as_struct(child_stats.null_count));
+                stats.min_values.extend(as_struct(child_stats.min_values));
+                stats.max_values.extend(as_struct(child_stats.max_values));
+            }
+            DataType::Primitive(primitive) => {
+                let null_count = nulls.as_ref().map_or(0, NullBuffer::null_count);
+                let null_count = Arc::new(Int64Array::from(vec![null_count as i64]));
+                let null_count_field =
+                    Field::new(field.name(), null_count.data_type().clone(), true);
+                stats
+                    .null_count
+                    .push((Arc::new(null_count_field), null_count));
+                if matches!(primitive, PrimitiveType::Boolean | PrimitiveType::Binary) {
+                    continue;
+                }
+                let column = match parent_nulls {
+                    // values of rows whose ancestor struct is null don't count
+                    Some(_) => make_array(column.to_data().into_builder().nulls(nulls).build()?),
+                    None => column.clone(),
+                };
+                let value_field =
+                    Arc::new(Field::new(field.name(), column.data_type().clone(), true));
+                stats
+                    .min_values
+                    .push((value_field.clone(), min_or_max(&column, false)?));
+                stats
+                    .max_values
+                    .push((value_field, min_or_max(&column, true)?));
+            }
+            // nested collections and variants are never stats columns
+            DataType::Array(_) | DataType::Map(_) | DataType::Variant(_) => {}
+        }
+    }
+    Ok(stats)
+}
+
+// The min (or max) non-null value of `column` as a single-element array, which is null if all
+// values are null.
+fn min_or_max(column: &ArrayRef, max: bool) -> DeltaResult<ArrayRef> {
+    if column.null_count() == column.len() {
+        return Ok(new_null_array(column.data_type(), 1));
+    }
+    let options = SortOptions {
+        descending: max,
+        nulls_first: false,
+    };
+    let index = sort_to_indices(column, Some(options), Some(1))?;
+    Ok(take(column, &index, None)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arrow::array::{BooleanArray, Int32Array, StringArray};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Schema};
+    use crate::schema::StructField;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    use super::*;
+
+    fn stats_json(stats: StructArray) -> String {
+        let stats = crate::engine::arrow_expression::evaluate_expression::to_json(&stats).unwrap();
+        stats.as_string::<i32>().value(0).to_string()
+    }
+
+    #[test]
+    fn test_collect_column_stats() {
+        let nested = StructArray::try_new(
+            vec![Field::new("x", ArrowDataType::Int32, true)].into(),
+            vec![Arc::new(Int32Array::from(vec![Some(7), Some(-100), None]))],
+            Some(NullBuffer::from(vec![true, false, true])),
+        )
+        .unwrap();
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("a", ArrowDataType::Int32, true),
+                Field::new("b", ArrowDataType::Utf8, true),
+                Field::new("c", ArrowDataType::Boolean, true),
+                Field::new("s", nested.data_type().clone(), true),
+                Field::new("unindexed", ArrowDataType::Int32, true),
+            ])),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(3), None, Some(1)])),
+                Arc::new(StringArray::from(vec![None::<&str>, None, None])),
+                Arc::new(BooleanArray::from(vec![true, false, true])),
+                Arc::new(nested),
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+            ],
+        )
+        .unwrap();
+        let stats_columns = StructType::new_unchecked([
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::nullable("b", DataType::STRING),
+            StructField::nullable("c", DataType::BOOLEAN),
+            StructField::nullable(
+                "s",
+                StructType::new_unchecked([StructField::nullable("x", DataType::INTEGER)]),
+            ),
+        ]);
+        let stats = collect_column_stats(&batch, &stats_columns).unwrap();
+        // the value of `s.x` in the row where `s` is null doesn't count
+        assert_eq!(
+            stats_json(stats),
+            concat!(
+                r#"{"nullCount":{"a":1,"b":3,"c":0,"s":{"x":2}},"#,
+                r#""minValues":{"a":1,"s":{"x":7}},"#,
+                r#""maxValues":{"a":3,"s":{"x":7}}}"#
+            )
+        );
+
+        let missing = StructType::new_unchecked([StructField::nullable("d", DataType::LONG)]);
+        assert_result_error_with_message(
+            collect_column_stats(&batch, &missing),
+            "Stats column d not found in data",
+        );
+    }
+}
@@ -6,28 +6,33 @@ use std::ops::Range;
 use std::sync::{Arc, OnceLock};
 
 use crate::engine::arrow_conversion::{TryFromKernel as _, TryIntoArrow as _};
+use crate::engine::arrow_variant::{is_shredded_variant, unshred_variant};
 use crate::engine::ensure_data_types::DataTypeCompat;
 use crate::schema::{ColumnMetadataKey, MetadataValue};
 use crate::{
     engine::arrow_data::ArrowEngineData,
+    engine_data::FilteredEngineData,
     schema::{DataType, MetadataColumnSpec, Schema, SchemaRef, StructField, StructType},
     utils::require,
-    DeltaResult, EngineData, Error,
+    DeltaResult, EngineData, Error, ParquetCompression, ParquetWriteOptions,
 };
 
 use crate::arrow::array::{
-    cast::AsArray, make_array, new_null_array, Array as ArrowArray, GenericListArray, MapArray,
-    OffsetSizeTrait, PrimitiveArray, RecordBatch, StringArray, StructArray,
+    cast::AsArray, make_array, new_null_array, Array as ArrowArray, BooleanArray, GenericListArray,
+    MapArray, OffsetSizeTrait, PrimitiveArray, RecordBatch, StringArray, StructArray,
 };
-use crate::arrow::buffer::NullBuffer;
-use crate::arrow::compute::concat_batches;
+use crate::arrow::buffer::{BooleanBuffer, Buffer, NullBuffer};
+use crate::arrow::compute::{concat_batches, filter_record_batch};
 use crate::arrow::datatypes::{
     DataType as ArrowDataType, Field as ArrowField, FieldRef as ArrowFieldRef,
     Fields as ArrowFields, Int64Type, Schema as ArrowSchema, SchemaRef as ArrowSchemaRef,
 };
 use crate::arrow::json::{LineDelimitedWriter, ReaderBuilder};
-use crate::parquet::arrow::PARQUET_FIELD_ID_META_KEY;
+use crate::parquet::arrow::arrow_reader::RowSelector;
+use crate::parquet::arrow::{ArrowWriter, PARQUET_FIELD_ID_META_KEY};
+use crate::parquet::basic::Compression;
 use crate::parquet::file::metadata::RowGroupMetaData;
+use crate::parquet::file::properties::WriterProperties;
 use crate::parquet::{arrow::ProjectionMask, schema::types::SchemaDescriptor};
 use delta_kernel_derive::internal_api;
 use itertools::Itertools;
@@ -104,6 +109,7 @@ pub(crate) fn make_arrow_error(s: impl Into<String>) -> Error {
 pub(crate) struct RowIndexBuilder {
     row_group_row_index_ranges: Vec<Range<i64>>,
     row_group_ordinals: Option<Vec<usize>>,
+    row_selection: Option<Vec<RowSelector>>,
 }
 
 impl RowIndexBuilder {
@@ -118,6 +124,7 @@ impl RowIndexBuilder {
         Self {
             row_group_row_index_ranges,
             row_group_ordinals: None,
+            row_selection: None,
         }
     }
 
@@ -128,6 +135,12 @@ impl RowIndexBuilder {
         // filtering is not idempotent and `with_row_groups` could be called more than once.
         self.row_group_ordinals = Some(ordinals.to_vec())
     }
+
+    /// Only produce row indexes for the rows selected by page skipping. The selection is relative
+    /// to the row groups that survived row group skipping, and must cover all of their rows.
+    pub(crate) fn select_rows(&mut self, selectors: &[RowSelector]) {
+        self.row_selection = Some(selectors.to_vec())
+    }
 }
 
 impl IntoIterator for RowIndexBuilder {
@@ -142,10 +155,39 @@ impl IntoIterator for RowIndexBuilder {
                 .collect(),
             None => self.row_group_row_index_ranges,
         };
+        let starting_offsets = match self.row_selection {
+            Some(selectors) => select_row_ranges(starting_offsets, &selectors),
+            None => starting_offsets,
+        };
         starting_offsets.into_iter().flatten()
     }
 }
 
+/// Narrows a list of row index ranges down to the rows picked out by a row selection.
+fn select_row_ranges(ranges: Vec<Range<i64>>, selectors: &[RowSelector]) -> Vec<Range<i64>> {
+    let mut ranges = ranges.into_iter();
+    let mut current = ranges.next();
+    let mut selected = vec![];
+    for selector in selectors {
+        let mut remaining = selector.row_count as i64;
+        while remaining > 0 {
+            let Some(range) = current.as_mut() else {
+                break;
+            };
+            let n = remaining.min(range.end - range.start);
+            if !selector.skip && n > 0 {
+                selected.push(range.start..range.start + n);
+            }
+            range.start += n;
+            remaining -= n;
+            if range.is_empty() {
+                current = ranges.next();
+            }
+        }
+    }
+    selected
+}
+
 /// Applies post-processing to data read from parquet files. This includes `reorder_struct_array` to
 /// ensure schema compatibility, as well as `fix_nested_null_masks` to ensure that leaf columns have
 /// accurate null masks that row visitors rely on for correctness.
@@ -278,7 +320,8 @@ pub(crate) struct ReorderIndex {
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum ReorderIndexTransform {
-    /// For a non-nested type, indicates that we need to cast to the contained type
+    /// For a non-nested type (or a list of them), indicates that we need to cast to the contained
+    /// type
     Cast(ArrowDataType),
     /// Used for struct/list/map. Potentially transform child fields using contained reordering
     Nested(Vec<ReorderIndex>),
@@ -288,6 +331,9 @@ pub(crate) enum ReorderIndexTransform {
     Missing(ArrowFieldRef),
     /// Row index column requested, compute it
     RowIndex(ArrowFieldRef),
+    /// For a shredded Variant (or a list of them), indicates that we need to reconstruct the
+    /// unshredded `STRUCT<metadata: BINARY, value: BINARY>` representation
+    UnshredVariant,
 }
 
 impl ReorderIndex {
@@ -315,14 +361,20 @@ impl ReorderIndex {
         ReorderIndex::new(index, ReorderIndexTransform::RowIndex(field))
     }
 
+    fn unshred_variant(index: usize) -> Self {
+        ReorderIndex::new(index, ReorderIndexTransform::UnshredVariant)
+    }
+
     /// Check if this reordering requires a transformation anywhere. See comment below on
     /// [`ordering_needs_transform`] to understand why this is needed.
     fn needs_transform(&self) -> bool {
         match self.transform {
-            // if we're casting, inserting null, or generating row index, we need to transform
+            // if we're casting, inserting null, generating row index, or unshredding a variant, we
+            // need to transform
             ReorderIndexTransform::Cast(_)
             | ReorderIndexTransform::Missing(_)
-            | ReorderIndexTransform::RowIndex(_) => true,
+            | ReorderIndexTransform::RowIndex(_)
+            | ReorderIndexTransform::UnshredVariant => true,
             // if our nested ordering needs a transform, we need a transform
             ReorderIndexTransform::Nested(ref children) => ordering_needs_transform(children),
             // no transform needed
@@ -350,15 +402,12 @@ fn _count_cols(dt: &ArrowDataType) -> usize {
 }
 
 /// Validate that a given field in a parquet file which is presumed to represent data of the
-/// `VARIANT` type is represented as `STRUCT<metadata: BINARY, value: BINARY>`. This is to make
-/// sure that the default engine does not try to read shredded Variants, which it currently does
-/// not support.
+/// `VARIANT` type and is not shredded is represented as `STRUCT<metadata: BINARY, value: BINARY>`.
 fn validate_parquet_variant(field: &ArrowField) -> DeltaResult<()> {
     fn variant_parquet_error(field_name: &String) -> Error {
         Error::Generic(format!(
-            "The field {field_name} presumed to be of Variant type might be \
-            shredded in the parquet file. The default engine does not support \
-            shredded reads yet."
+            "The field {field_name} presumed to be of Variant type is neither an unshredded nor \
+            a shredded Variant in the parquet file."
         ))
     }
     match field.data_type() {
@@ -411,9 +460,20 @@ fn get_indices(
             ..
         }) = kernel_field_info
         {
-            // If the field is a variant, make sure the parquet schema matches the unshredded variant
-            // representation. This is to ensure that shredded reads are not performed.
+            // If the field is a variant, make sure the parquet schema matches either the shredded
+            // or the unshredded variant representation. Shredded variants are read in full and
+            // reconstructed into the unshredded representation.
             if requested_field.data_type == DataType::unshredded_variant() {
+                if is_shredded_variant(field) {
+                    let num_cols = count_cols(field);
+                    let start = parquet_offset + parquet_index;
+                    mask_indices.extend(start..start + num_cols);
+                    // see comment below in struct match arm
+                    parquet_offset += num_cols - 1;
+                    found_fields.insert(requested_field.name());
+                    reorder_indices.push(ReorderIndex::unshred_variant(index));
+                    continue;
+                }
                 validate_parquet_variant(field)?;
             }
             match field.data_type() {
@@ -469,6 +529,22 @@ fn get_indices(
                         // the index is wrong, as it's the index from the inner schema. Adjust
                         // it to be our index
                         children.index = index;
+                        // a cast of the list elements (e.g. of a widened element type) must be
+                        // applied as a cast of the list itself
+                        if let ReorderIndexTransform::Cast(element_type) = &children.transform {
+                            let list_field = Arc::new(
+                                list_field
+                                    .as_ref()
+                                    .clone()
+                                    .with_data_type(element_type.clone()),
+                            );
+                            let list_type = match field.data_type() {
+                                ArrowDataType::LargeList(_) => ArrowDataType::LargeList(list_field),
+                                ArrowDataType::ListView(_) => ArrowDataType::ListView(list_field),
+                                _ => ArrowDataType::List(list_field),
+                            };
+                            children = ReorderIndex::cast(index, list_type);
+                        }
                         reorder_indices.push(children);
                     } else {
                         return Err(Error::unexpected_column_type(list_field.name()));
@@ -820,6 +896,16 @@ pub(crate) fn reorder_struct_array(
                         }
                     }
                 }
+                ReorderIndexTransform::UnshredVariant => {
+                    let col = unshred_variant(&input_cols[parquet_position])?;
+                    let new_field = Arc::new(
+                        input_fields[parquet_position]
+                            .as_ref()
+                            .clone()
+                            .with_data_type(col.data_type().clone()),
+                    );
+                    final_fields_cols[reorder_index.index] = Some((new_field, col));
+                }
                 ReorderIndexTransform::Identity => {
                     final_fields_cols[reorder_index.index] = Some((
                         input_fields[parquet_position].clone(), // cheap Arc clone
@@ -993,7 +1079,7 @@ pub(crate) fn parse_json(
     json_strings: Box<dyn EngineData>,
     schema: SchemaRef,
 ) -> DeltaResult<Box<dyn EngineData>> {
-    let json_strings: RecordBatch = ArrowEngineData::try_from_engine_data(json_strings)?.into();
+    let json_strings = ArrowEngineData::try_into_record_batch(json_strings)?;
     let json_strings = json_strings
         .column(0)
         .as_any()
@@ -1072,6 +1158,177 @@ pub(crate) fn to_json_bytes(
     Ok(writer.into_inner())
 }
 
+/// Serialize engine data to parquet bytes, returning them along with the number of rows written.
+/// Any [`ColumnMetadataKey::ParquetFieldId`] field metadata is written as the parquet field ID.
+pub(crate) fn to_parquet_bytes(
+    data: Box<dyn EngineData>,
+    options: &ParquetWriteOptions,
+) -> DeltaResult<(Vec<u8>, usize)> {
+    let arrow_data = ArrowEngineData::try_from_engine_data(data)?;
+    let record_batch = arrow_data.record_batch();
+    let schema = record_batch.schema();
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|f| with_parquet_field_id(f))
+        .collect();
+    let schema = ArrowSchema::new_with_metadata(fields, schema.metadata().clone());
+    // Only field metadata was added, so the new schema is a superset of the old one
+    let record_batch = record_batch.clone().with_schema(Arc::new(schema))?;
+
+    let compression = match options.compression {
+        ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+        ParquetCompression::Snappy => Compression::SNAPPY,
+        ParquetCompression::Gzip => Compression::GZIP(Default::default()),
+        ParquetCompression::Zstd => Compression::ZSTD(Default::default()),
+        ParquetCompression::Lz4 => Compression::LZ4_RAW,
+    };
+    let mut properties = WriterProperties::builder().set_compression(compression);
+    if let Some(max_row_group_size) = options.max_row_group_size {
+        properties = properties.set_max_row_group_size(max_row_group_size);
+    }
+
+    let mut buffer = vec![];
+    let mut writer =
+        ArrowWriter::try_new(&mut buffer, record_batch.schema(), Some(properties.build()))?;
+    writer.write(&record_batch)?;
+    writer.close()?; // writer must be closed to write footer
+    Ok((buffer, record_batch.num_rows()))
+}
+
+/// Recursively copies [`ColumnMetadataKey::ParquetFieldId`] field metadata to the
+/// [`PARQUET_FIELD_ID_META_KEY`] that arrow's parquet writer understands.
+fn with_parquet_field_id(field: &ArrowField) -> ArrowField {
+    let data_type = match field.data_type() {
+        ArrowDataType::Struct(fields) => {
+            ArrowDataType::Struct(fields.iter().map(|f| with_parquet_field_id(f)).collect())
+        }
+        ArrowDataType::List(f) => ArrowDataType::List(Arc::new(with_parquet_field_id(f))),
+        ArrowDataType::LargeList(f) => ArrowDataType::LargeList(Arc::new(with_parquet_field_id(f))),
+        ArrowDataType::Map(f, sorted) => {
+            ArrowDataType::Map(Arc::new(with_parquet_field_id(f)), *sorted)
+        }
+        data_type => data_type.clone(),
+    };
+    let mut metadata = field.metadata().clone();
+    if let Some(field_id) = metadata.get(ColumnMetadataKey::ParquetFieldId.as_ref()) {
+        let field_id = field_id.clone();
+        metadata
+            .entry(PARQUET_FIELD_ID_META_KEY.to_string())
+            .or_insert(field_id);
+    }
+    field
+        .clone()
+        .with_data_type(data_type)
+        .with_metadata(metadata)
+}
+
+/// Returns the rows of `data` that its selection vector selects, as a `RecordBatch`. Rows past the
+/// end of the selection vector are selected.
+#[cfg_attr(not(feature = "default-engine-base"), allow(dead_code))]
+pub(crate) fn filter_selected_rows(data: FilteredEngineData) -> DeltaResult<RecordBatch> {
+    let FilteredEngineData {
+        data,
+        selection_vector,
+    } = data;
+    let batch = ArrowEngineData::try_into_record_batch(data)?;
+    let num_rows = batch.num_rows();
+    // The bitmap of a selection vector that covers all rows is used as the filter as-is
+    let filter = if selection_vector.len() == num_rows {
+        let bits = Buffer::from(selection_vector.into_bytes());
+        BooleanArray::new(BooleanBuffer::new(bits, 0, num_rows), None)
+    } else {
+        let selected = selection_vector.iter().chain(std::iter::repeat(true));
+        selected.take(num_rows).map(Some).collect()
+    };
+    Ok(filter_record_batch(&batch, &filter)?)
+}
+
+/// Adds a `stats_parsed` field to the end of the `add` column of a batch of actions, holding the
+/// JSON `add.stats` of each row parsed with `stats_schema` (and null where `add.stats` is null).
+/// Batches without an `add` column are returned unchanged.
+#[cfg_attr(not(feature = "default-engine-base"), allow(dead_code))]
+pub(crate) fn with_parsed_stats(
+    batch: RecordBatch,
+    stats_schema: ArrowSchemaRef,
+) -> DeltaResult<RecordBatch> {
+    let Some((add_index, add_field)) = batch
+        .schema()
+        .column_with_name("add")
+        .map(|(index, field)| (index, field.clone()))
+    else {
+        return Ok(batch);
+    };
+    let add = batch
+        .column(add_index)
+        .as_struct_opt()
+        .ok_or_else(|| Error::generic("Expected add actions to be a struct"))?;
+    let stats = add
+        .column_by_name("stats")
+        .and_then(|stats| stats.as_string_opt::<i32>())
+        .ok_or_else(|| Error::generic("Expected add.stats to be a string"))?;
+    let parsed_stats = StructArray::from(parse_json_impl(stats, stats_schema)?);
+    let (stats_fields, stats_columns, _) = parsed_stats.into_parts();
+    let parsed_stats = StructArray::try_new(stats_fields, stats_columns, stats.nulls().cloned())?;
+
+    let (mut fields, mut columns, nulls) = add.clone().into_parts();
+    let stats_field = ArrowField::new("stats_parsed", parsed_stats.data_type().clone(), true);
+    fields = fields
+        .iter()
+        .cloned()
+        .chain([Arc::new(stats_field)])
+        .collect();
+    columns.push(Arc::new(parsed_stats));
+    let add = StructArray::try_new(fields, columns, nulls)?;
+
+    let schema = batch.schema();
+    let mut fields: Vec<_> = schema.fields().iter().cloned().collect();
+    fields[add_index] = Arc::new(add_field.with_data_type(add.data_type().clone()));
+    let schema = ArrowSchema::new_with_metadata(fields, schema.metadata().clone());
+    let mut columns = batch.columns().to_vec();
+    columns[add_index] = Arc::new(add);
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+/// Concatenates batches whose schemas may each contain only some of the columns, into a single
+/// batch with all of the columns (in order of first appearance). Columns missing from a batch are
+/// null for its rows.
+#[cfg_attr(not(feature = "default-engine-base"), allow(dead_code))]
+pub(crate) fn concat_with_missing_columns(batches: &[RecordBatch]) -> DeltaResult<RecordBatch> {
+    let mut fields: Vec<ArrowField> = vec![];
+    for batch in batches {
+        for field in batch.schema().fields() {
+            if !fields.iter().any(|f| f.name() == field.name()) {
+                let missing = batches
+                    .iter()
+                    .any(|batch| batch.schema().column_with_name(field.name()).is_none());
+                fields.push(
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_nullable(field.is_nullable() || missing),
+                );
+            }
+        }
+    }
+    let schema = Arc::new(ArrowSchema::new(fields));
+    let batches: Vec<_> = batches
+        .iter()
+        .map(|batch| {
+            let columns = schema
+                .fields()
+                .iter()
+                .map(|field| match batch.column_by_name(field.name()) {
+                    Some(column) => column.clone(),
+                    None => new_null_array(field.data_type(), batch.num_rows()),
+                })
+                .collect();
+            RecordBatch::try_new(schema.clone(), columns)
+        })
+        .try_collect()?;
+    Ok(concat_batches(&schema, &batches)?)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -1320,14 +1577,16 @@ mod tests {
             get_requested_indices(&requested_schema, &unshredded_parquet_schema);
         assert!(result_unshredded.is_ok());
         let result_shredded = get_requested_indices(&requested_schema, &shredded_parquet_schema);
-        assert!(matches!(result_shredded,
-            Err(e) if e.to_string().contains("The default engine does not support shredded reads")));
+        assert_eq!(
+            result_shredded.unwrap(),
+            (vec![0, 1, 2], vec![ReorderIndex::unshred_variant(0)])
+        );
         let result_incorrect = get_requested_indices(&requested_schema, &incorrect_parquet_schema);
         assert!(matches!(result_incorrect,
-            Err(e) if e.to_string().contains("The default engine does not support shredded reads")));
+            Err(e) if e.to_string().contains("is neither an unshredded nor a shredded Variant")));
         let result_scalar = get_requested_indices(&requested_schema, &scalar_parquet_schema);
         assert!(matches!(result_scalar,
-            Err(e) if e.to_string().contains("The default engine does not support shredded reads")));
+            Err(e) if e.to_string().contains("is neither an unshredded nor a shredded Variant")));
 
         // Struct of Variant
         let requested_schema = Arc::new(StructType::new_unchecked([StructField::nullable(
@@ -1348,8 +1607,16 @@ mod tests {
             get_requested_indices(&requested_schema, &unshredded_parquet_schema);
         let result_shredded = get_requested_indices(&requested_schema, &shredded_parquet_schema);
         assert!(result_unshredded.is_ok());
-        assert!(matches!(result_shredded,
-            Err(e) if e.to_string().contains("The default engine does not support shredded reads")));
+        assert_eq!(
+            result_shredded.unwrap(),
+            (
+                vec![0, 1, 2],
+                vec![ReorderIndex::nested(
+                    0,
+                    vec![ReorderIndex::unshred_variant(0)]
+                )]
+            )
+        );
         // Array of Variant
         let requested_schema = Arc::new(StructType::new_unchecked([StructField::nullable(
             "array_v",
@@ -1369,8 +1636,10 @@ mod tests {
             get_requested_indices(&requested_schema, &unshredded_parquet_schema);
         let result_shredded = get_requested_indices(&requested_schema, &shredded_parquet_schema);
         assert!(result_unshredded.is_ok());
-        assert!(matches!(result_shredded,
-            Err(e) if e.to_string().contains("The default engine does not support shredded reads")));
+        assert_eq!(
+            result_shredded.unwrap(),
+            (vec![0, 1, 2], vec![ReorderIndex::unshred_variant(0)])
+        );
 
         // Map of Variant
         let requested_schema = Arc::new(StructType::new_unchecked([StructField::nullable(
@@ -1397,8 +1666,16 @@ mod tests {
             get_requested_indices(&requested_schema, &unshredded_parquet_schema);
         let result_shredded = get_requested_indices(&requested_schema, &shredded_parquet_schema);
         assert!(result_unshredded.is_ok());
-        assert!(matches!(result_shredded,
-            Err(e) if e.to_string().contains("The default engine does not support shredded reads")));
+        assert_eq!(
+            result_shredded.unwrap(),
+            (
+                vec![0, 1, 2, 3],
+                vec![ReorderIndex::nested(
+                    0,
+                    vec![ReorderIndex::identity(0), ReorderIndex::unshred_variant(1)]
+                )]
+            )
+        );
     }
 
     #[test]
@@ -1684,6 +1961,22 @@ mod tests {
         assert_eq!(matched_fields[2].parquet_field.name(), "another_field");
     }
 
+    #[test]
+    fn test_select_row_ranges() {
+        let selectors = [
+            RowSelector::skip(3),
+            RowSelector::select(4),
+            RowSelector::skip(6),
+            RowSelector::select(2),
+        ];
+        // The selection spans row group boundaries, and the ranges need not be contiguous
+        let ranges = vec![0..5, 5..10, 20..25];
+        assert_eq!(
+            select_row_ranges(ranges, &selectors),
+            vec![3..5, 5..7, 23..25]
+        );
+    }
+
     #[test]
     fn test_ordering_needs_row_indexes() {
         // Test case 1: No row index needed
@@ -1966,6 +2259,47 @@ mod tests {
         });
     }
 
+    #[test]
+    fn widened_list_element_cast() {
+        let requested_schema = StructType::new_unchecked([StructField::nullable(
+            "list",
+            ArrayType::new(DataType::LONG, true),
+        )])
+        .into();
+        let element_field = Arc::new(ArrowField::new("element", ArrowDataType::Int32, true));
+        let parquet_schema = Arc::new(ArrowSchema::new(vec![ArrowField::new(
+            "list",
+            ArrowDataType::List(element_field.clone()),
+            true,
+        )]));
+        let (mask_indices, reorder_indices) =
+            get_requested_indices(&requested_schema, &parquet_schema).unwrap();
+        let widened_field = Arc::new(ArrowField::new("element", ArrowDataType::Int64, true));
+        assert_eq!(mask_indices, vec![0]);
+        assert_eq!(
+            reorder_indices,
+            vec![ReorderIndex::cast(0, ArrowDataType::List(widened_field))]
+        );
+
+        // the elements of the list are cast, rather than the list itself
+        let list = GenericListArray::<i32>::new(
+            element_field,
+            OffsetBuffer::new(ScalarBuffer::from(vec![0, 2, 3])),
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            None,
+        );
+        let input = StructArray::try_new(
+            parquet_schema.fields().clone(),
+            vec![Arc::new(list) as ArrowArrayRef],
+            None,
+        )
+        .unwrap();
+        let result = reorder_struct_array(input, &reorder_indices, None).unwrap();
+        let elements = result.column(0).as_list::<i32>().values();
+        assert_eq!(elements.data_type(), &ArrowDataType::Int64);
+        assert_eq!(elements.len(), 3);
+    }
+
     #[test]
     fn nested_indices_list() {
         column_mapping_cases().into_iter().for_each(|mode| {
@@ -0,0 +1,777 @@
+//! Validation of Variant columns, and reconstruction of shredded Variant columns read from
+//! parquet.
+//!
+//! A shredded Variant is stored in parquet as `STRUCT<metadata: BINARY, value: BINARY,
+//! typed_value: ...>`, where `typed_value` holds the parts of the variant that were shredded into
+//! typed columns and `value` holds the (residual) variant-encoded rest. Objects are shredded into a
+//! struct with one `STRUCT<value, typed_value>` group per shredded field, and arrays into a list of
+//! such groups. See the [parquet variant shredding spec] for details.
+//!
+//! This module reconstructs the unshredded `STRUCT<metadata: BINARY, value: BINARY>`
+//! representation from the shredded columns, by variant-encoding the typed values and merging them
+//! with the residual values. It also validates the unshredded representation of Variant columns,
+//! e.g. of data to be written.
+//!
+//! [parquet variant shredding spec]: https://github.com/apache/parquet-format/blob/master/VariantShredding.md
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::arrow::array::{
+    cast::AsArray, Array, ArrayRef, BinaryBuilder, GenericListArray, OffsetSizeTrait, StructArray,
+};
+use crate::arrow::compute::cast;
+use crate::arrow::datatypes::{
+    DataType as ArrowDataType, Date32Type, Decimal128Type, Field as ArrowField, Float32Type,
+    Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, Time64MicrosecondType, TimeUnit,
+    TimestampMicrosecondType, TimestampNanosecondType,
+};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+const METADATA: &str = "metadata";
+const VALUE: &str = "value";
+const TYPED_VALUE: &str = "typed_value";
+
+// basic types, stored in the lowest two bits of the first byte of a variant value
+const PRIMITIVE: u8 = 0;
+const SHORT_STRING: u8 = 1;
+const OBJECT: u8 = 2;
+const ARRAY: u8 = 3;
+
+// primitive type ids, stored in the upper six bits of the first byte of a primitive variant value
+const NULL: u8 = 0;
+const TRUE: u8 = 1;
+const FALSE: u8 = 2;
+const INT8: u8 = 3;
+const INT16: u8 = 4;
+const INT32: u8 = 5;
+const INT64: u8 = 6;
+const DOUBLE: u8 = 7;
+const DECIMAL4: u8 = 8;
+const DECIMAL8: u8 = 9;
+const DECIMAL16: u8 = 10;
+const DATE: u8 = 11;
+const TIMESTAMP: u8 = 12;
+const TIMESTAMP_NTZ: u8 = 13;
+const FLOAT: u8 = 14;
+const BINARY: u8 = 15;
+const STRING: u8 = 16;
+const TIME_NTZ: u8 = 17;
+const TIMESTAMP_NANOS: u8 = 18;
+const TIMESTAMP_NTZ_NANOS: u8 = 19;
+const UUID: u8 = 20;
+
+// the longest string that can be encoded as a short string
+const MAX_SHORT_STRING_LEN: usize = 63;
+
+/// Returns true if `field` is the parquet representation of a shredded Variant, i.e. a struct with
+/// a `metadata` and a `typed_value` field, and optionally a `value` field.
+pub(crate) fn is_shredded_variant(field: &ArrowField) -> bool {
+    let ArrowDataType::Struct(fields) = field.data_type() else {
+        return false;
+    };
+    let has_field = |name: &str| fields.iter().any(|f| f.name() == name);
+    has_field(METADATA)
+        && has_field(TYPED_VALUE)
+        && fields
+            .iter()
+            .all(|f| matches!(f.name().as_str(), METADATA | VALUE | TYPED_VALUE))
+}
+
+/// Reconstructs the unshredded `STRUCT<metadata: BINARY, value: BINARY>` representation of a
+/// Variant column from its shredded representation. Lists of (shredded) Variants are unshredded
+/// element-wise.
+pub(crate) fn unshred_variant(array: &ArrayRef) -> DeltaResult<ArrayRef> {
+    match array.data_type() {
+        ArrowDataType::Struct(_) => Ok(Arc::new(unshred_variant_struct(array.as_struct())?)),
+        ArrowDataType::List(_) => unshred_variant_list(array.as_list::<i32>()),
+        ArrowDataType::LargeList(_) => unshred_variant_list(array.as_list::<i64>()),
+        other => Err(Error::generic(format!(
+            "Cannot unshred a Variant stored as {other}"
+        ))),
+    }
+}
+
+fn unshred_variant_list<O: OffsetSizeTrait>(list: &GenericListArray<O>) -> DeltaResult<ArrayRef> {
+    let (field, offsets, values, nulls) = list.clone().into_parts();
+    let values = unshred_variant(&values)?;
+    let field = Arc::new(
+        field
+            .as_ref()
+            .clone()
+            .with_data_type(values.data_type().clone()),
+    );
+    Ok(Arc::new(GenericListArray::try_new(
+        field, offsets, values, nulls,
+    )?))
+}
+
+fn unshred_variant_struct(array: &StructArray) -> DeltaResult<StructArray> {
+    let metadata = array
+        .column_by_name(METADATA)
+        .ok_or_else(|| Error::generic("Shredded Variant is missing its metadata column"))?;
+    let mut values = BinaryBuilder::with_capacity(array.len(), 0);
+    for row in 0..array.len() {
+        if array.is_null(row) {
+            values.append_null();
+            continue;
+        }
+        let variant_metadata = binary_value(metadata.as_ref(), row)?
+            .map(VariantMetadata::try_new)
+            .transpose()?
+            .ok_or_else(|| Error::generic("Shredded Variant has a null metadata value"))?;
+        // a variant that is missing entirely (both value and typed_value are null) is variant null
+        let value = unshred_group(&variant_metadata, array, row)?
+            .unwrap_or_else(|| vec![(NULL << 2) | PRIMITIVE]);
+        values.append_value(value);
+    }
+    let metadata = cast(metadata, &ArrowDataType::Binary)?;
+    Ok(StructArray::try_new(
+        vec![
+            ArrowField::new(METADATA, ArrowDataType::Binary, false),
+            ArrowField::new(VALUE, ArrowDataType::Binary, false),
+        ]
+        .into(),
+        vec![metadata, Arc::new(values.finish())],
+        array.nulls().cloned(),
+    )?)
+}
+
+/// Validates that `array` is an unshredded Variant column, i.e. a struct of a `metadata` and a
+/// `value` binary column (in any order) that are non-null for every non-null variant and hold
+/// well-formed variant metadata and values. Returns the column as the canonical
+/// `STRUCT<metadata: BINARY, value: BINARY>`.
+pub(crate) fn validate_unshredded_variant(array: &ArrayRef) -> DeltaResult<ArrayRef> {
+    let invalid_variant = || {
+        Error::generic(format!(
+            "Invalid Variant column: expected STRUCT<metadata: BINARY, value: BINARY>, got {}",
+            array.data_type()
+        ))
+    };
+    let array = array.as_struct_opt().ok_or_else(invalid_variant)?;
+    require!(
+        array.column_by_name(TYPED_VALUE).is_none(),
+        Error::unsupported("Shredded Variant columns are not supported here")
+    );
+    require!(array.num_columns() == 2, invalid_variant());
+    let (Some(metadata), Some(value)) =
+        (array.column_by_name(METADATA), array.column_by_name(VALUE))
+    else {
+        return Err(invalid_variant());
+    };
+    for row in (0..array.len()).filter(|row| array.is_valid(*row)) {
+        let invalid_row =
+            |msg: String| Error::generic(format!("Invalid Variant value in row {row}: {msg}"));
+        let variant_metadata = binary_value(metadata.as_ref(), row)?
+            .ok_or_else(|| invalid_row("metadata is null".to_string()))?;
+        VariantMetadata::try_new(variant_metadata).map_err(|err| invalid_row(err.to_string()))?;
+        match binary_value(value.as_ref(), row)? {
+            Some([]) => return Err(invalid_row("value is empty".to_string())),
+            Some(_) => {}
+            None => return Err(invalid_row("value is null".to_string())),
+        }
+    }
+    Ok(Arc::new(StructArray::try_new(
+        vec![
+            ArrowField::new(METADATA, ArrowDataType::Binary, false),
+            ArrowField::new(VALUE, ArrowDataType::Binary, false),
+        ]
+        .into(),
+        vec![
+            cast(metadata, &ArrowDataType::Binary)?,
+            cast(value, &ArrowDataType::Binary)?,
+        ],
+        array.nulls().cloned(),
+    )?))
+}
+
+/// Variant-encodes the `STRUCT<value, typed_value>` group `group` at `row`. Returns `None` if the
+/// value is missing, which is only allowed for shredded object fields.
+fn unshred_group(
+    metadata: &VariantMetadata<'_>,
+    group: &StructArray,
+    row: usize,
+) -> DeltaResult<Option<Vec<u8>>> {
+    let value = match group.column_by_name(VALUE) {
+        Some(value) => binary_value(value.as_ref(), row)?,
+        None => None,
+    };
+    let typed_value = group
+        .column_by_name(TYPED_VALUE)
+        .filter(|typed_value| typed_value.is_valid(row));
+    let Some(typed_value) = typed_value else {
+        return Ok(value.map(<[u8]>::to_vec));
+    };
+
+    let mut out = vec![];
+    match typed_value.data_type() {
+        ArrowDataType::Struct(_) => {
+            // a partially shredded object stores the fields that were not shredded in `value`
+            let residual_fields = value.map(object_fields).transpose()?.unwrap_or_default();
+            let typed_value = typed_value.as_struct();
+            let mut fields = Vec::with_capacity(typed_value.num_columns() + residual_fields.len());
+            for (field, column) in typed_value.fields().iter().zip(typed_value.columns()) {
+                let column = column.as_struct_opt().ok_or_else(|| {
+                    Error::generic(format!(
+                        "Shredded Variant object field {} is not a struct",
+                        field.name()
+                    ))
+                })?;
+                if let Some(field_value) = unshred_group(metadata, column, row)? {
+                    let name = field.name().as_str();
+                    fields.push((name, metadata.find(name)?, Cow::Owned(field_value)));
+                }
+            }
+            for (id, field_value) in residual_fields {
+                fields.push((metadata.get(id)?, id, Cow::Borrowed(field_value)));
+            }
+            encode_object(fields, &mut out);
+        }
+        ArrowDataType::List(_) => {
+            unshred_array(metadata, typed_value.as_list::<i32>(), row, &mut out)?
+        }
+        ArrowDataType::LargeList(_) => {
+            unshred_array(metadata, typed_value.as_list::<i64>(), row, &mut out)?
+        }
+        _ => {
+            require!(
+                value.is_none(),
+                Error::generic("Shredded Variant has both a value and a non-object typed_value")
+            );
+            encode_primitive(typed_value.as_ref(), row, &mut out)?;
+        }
+    }
+    Ok(Some(out))
+}
+
+fn unshred_array<O: OffsetSizeTrait>(
+    metadata: &VariantMetadata<'_>,
+    typed_value: &GenericListArray<O>,
+    row: usize,
+    out: &mut Vec<u8>,
+) -> DeltaResult<()> {
+    let elements = typed_value.value(row);
+    let elements = elements
+        .as_struct_opt()
+        .ok_or_else(|| Error::generic("Shredded Variant array elements are not structs"))?;
+    let elements = (0..elements.len())
+        .map(|i| {
+            unshred_group(metadata, elements, i)?
+                .ok_or_else(|| Error::generic("Shredded Variant array element is missing"))
+        })
+        .collect::<DeltaResult<Vec<_>>>()?;
+    encode_array(elements, out);
+    Ok(())
+}
+
+/// Returns the binary value of `array` at `row`, or `None` if it is null.
+fn binary_value(array: &dyn Array, row: usize) -> DeltaResult<Option<&[u8]>> {
+    if array.is_null(row) {
+        return Ok(None);
+    }
+    let value = match array.data_type() {
+        ArrowDataType::Binary => array.as_binary::<i32>().value(row),
+        ArrowDataType::LargeBinary => array.as_binary::<i64>().value(row),
+        ArrowDataType::BinaryView => array.as_binary_view().value(row),
+        other => {
+            return Err(Error::generic(format!(
+                "Expected a binary Variant column, found {other}"
+            )))
+        }
+    };
+    Ok(Some(value))
+}
+
+/// Variant-encodes the primitive value of `array` at `row` into `out`.
+fn encode_primitive(array: &dyn Array, row: usize, out: &mut Vec<u8>) -> DeltaResult<()> {
+    let primitive_header = |type_id: u8| (type_id << 2) | PRIMITIVE;
+    match array.data_type() {
+        ArrowDataType::Boolean => {
+            let type_id = if array.as_boolean().value(row) {
+                TRUE
+            } else {
+                FALSE
+            };
+            out.push(primitive_header(type_id));
+        }
+        ArrowDataType::Int8 => {
+            out.push(primitive_header(INT8));
+            out.extend(array.as_primitive::<Int8Type>().value(row).to_le_bytes());
+        }
+        ArrowDataType::Int16 => {
+            out.push(primitive_header(INT16));
+            out.extend(array.as_primitive::<Int16Type>().value(row).to_le_bytes());
+        }
+        ArrowDataType::Int32 => {
+            out.push(primitive_header(INT32));
+            out.extend(array.as_primitive::<Int32Type>().value(row).to_le_bytes());
+        }
+        ArrowDataType::Int64 => {
+            out.push(primitive_header(INT64));
+            out.extend(array.as_primitive::<Int64Type>().value(row).to_le_bytes());
+        }
+        ArrowDataType::Float32 => {
+            out.push(primitive_header(FLOAT));
+            out.extend(array.as_primitive::<Float32Type>().value(row).to_le_bytes());
+        }
+        ArrowDataType::Float64 => {
+            out.push(primitive_header(DOUBLE));
+            out.extend(array.as_primitive::<Float64Type>().value(row).to_le_bytes());
+        }
+        &ArrowDataType::Decimal128(precision, scale) => {
+            let value = array.as_primitive::<Decimal128Type>().value(row);
+            // decimals are stored in the narrowest representation that fits their precision
+            match precision {
+                0..=9 => {
+                    out.extend([primitive_header(DECIMAL4), scale as u8]);
+                    out.extend((value as i32).to_le_bytes());
+                }
+                10..=18 => {
+                    out.extend([primitive_header(DECIMAL8), scale as u8]);
+                    out.extend((value as i64).to_le_bytes());
+                }
+                _ => {
+                    out.extend([primitive_header(DECIMAL16), scale as u8]);
+                    out.extend(value.to_le_bytes());
+                }
+            }
+        }
+        ArrowDataType::Date32 => {
+            out.push(primitive_header(DATE));
+            out.extend(array.as_primitive::<Date32Type>().value(row).to_le_bytes());
+        }
+        ArrowDataType::Timestamp(TimeUnit::Microsecond, tz) => {
+            let type_id = if tz.is_some() {
+                TIMESTAMP
+            } else {
+                TIMESTAMP_NTZ
+            };
+            out.push(primitive_header(type_id));
+            let value = array.as_primitive::<TimestampMicrosecondType>().value(row);
+            out.extend(value.to_le_bytes());
+        }
+        ArrowDataType::Timestamp(TimeUnit::Nanosecond, tz) => {
+            let type_id = if tz.is_some() {
+                TIMESTAMP_NANOS
+            } else {
+                TIMESTAMP_NTZ_NANOS
+            };
+            out.push(primitive_header(type_id));
+            let value = array.as_primitive::<TimestampNanosecondType>().value(row);
+            out.extend(value.to_le_bytes());
+        }
+        ArrowDataType::Time64(TimeUnit::Microsecond) => {
+            out.push(primitive_header(TIME_NTZ));
+            let value = array.as_primitive::<Time64MicrosecondType>().value(row);
+            out.extend(value.to_le_bytes());
+        }
+        ArrowDataType::Utf8 => encode_string(array.as_string::<i32>().value(row), out),
+        ArrowDataType::LargeUtf8 => encode_string(array.as_string::<i64>().value(row), out),
+        ArrowDataType::Utf8View => encode_string(array.as_string_view().value(row), out),
+        ArrowDataType::Binary => encode_binary(array.as_binary::<i32>().value(row), out),
+        ArrowDataType::LargeBinary => encode_binary(array.as_binary::<i64>().value(row), out),
+        ArrowDataType::BinaryView => encode_binary(array.as_binary_view().value(row), out),
+        ArrowDataType::FixedSizeBinary(16) => {
+            out.push(primitive_header(UUID));
+            out.extend(array.as_fixed_size_binary().value(row));
+        }
+        other => {
+            return Err(Error::unsupported(format!(
+                "Unsupported shredded Variant typed_value type: {other}"
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn encode_string(value: &str, out: &mut Vec<u8>) {
+    if value.len() <= MAX_SHORT_STRING_LEN {
+        out.push(((value.len() as u8) << 2) | SHORT_STRING);
+    } else {
+        out.push((STRING << 2) | PRIMITIVE);
+        out.extend((value.len() as u32).to_le_bytes());
+    }
+    out.extend(value.as_bytes());
+}
+
+fn encode_binary(value: &[u8], out: &mut Vec<u8>) {
+    out.push((BINARY << 2) | PRIMITIVE);
+    out.extend((value.len() as u32).to_le_bytes());
+    out.extend(value);
+}
+
+/// Variant-encodes an object with the given `(name, field id, encoded value)` fields into `out`.
+fn encode_object(mut fields: Vec<(&str, u32, Cow<'_, [u8]>)>, out: &mut Vec<u8>) {
+    // field ids must be ordered by the names of the fields they refer to
+    fields.sort_by_key(|(name, ..)| *name);
+    let data_size = fields.iter().map(|(.., value)| value.len()).sum();
+    let max_id = fields.iter().map(|(_, id, _)| *id).max().unwrap_or(0);
+    let id_size = int_size(max_id as usize);
+    let offset_size = int_size(data_size);
+    let is_large = fields.len() > u8::MAX as usize;
+    let header = (offset_size - 1) | ((id_size - 1) << 2) | (u8::from(is_large) << 4);
+    out.push((header << 2) | OBJECT);
+    write_uint(fields.len(), if is_large { 4 } else { 1 }, out);
+    for (_, id, _) in &fields {
+        write_uint(*id as usize, id_size, out);
+    }
+    write_offsets(
+        fields.iter().map(|(.., value)| value.len()),
+        offset_size,
+        out,
+    );
+    for (.., value) in fields {
+        out.extend(value.iter());
+    }
+}
+
+/// Variant-encodes an array with the given encoded elements into `out`.
+fn encode_array(elements: Vec<Vec<u8>>, out: &mut Vec<u8>) {
+    let data_size = elements.iter().map(Vec::len).sum();
+    let offset_size = int_size(data_size);
+    let is_large = elements.len() > u8::MAX as usize;
+    let header = (offset_size - 1) | (u8::from(is_large) << 2);
+    out.push((header << 2) | ARRAY);
+    write_uint(elements.len(), if is_large { 4 } else { 1 }, out);
+    write_offsets(elements.iter().map(Vec::len), offset_size, out);
+    out.extend(elements.into_iter().flatten());
+}
+
+fn write_offsets(lengths: impl Iterator<Item = usize>, offset_size: u8, out: &mut Vec<u8>) {
+    let mut offset = 0;
+    write_uint(offset, offset_size, out);
+    for len in lengths {
+        offset += len;
+        write_uint(offset, offset_size, out);
+    }
+}
+
+/// The number of bytes needed to store `value` as a little-endian unsigned integer
+fn int_size(value: usize) -> u8 {
+    match value {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x10000..=0xFFFFFF => 3,
+        _ => 4,
+    }
+}
+
+fn write_uint(value: usize, size: u8, out: &mut Vec<u8>) {
+    out.extend(&(value as u32).to_le_bytes()[..size as usize]);
+}
+
+fn read_uint(bytes: &[u8], pos: usize, size: usize) -> DeltaResult<usize> {
+    let bytes = bytes
+        .get(pos..pos + size)
+        .ok_or_else(|| Error::generic("Variant value is truncated"))?;
+    Ok(bytes
+        .iter()
+        .rev()
+        .fold(0, |value, byte| (value << 8) | *byte as usize))
+}
+
+/// Returns the `(field id, encoded value)` pairs of the variant-encoded object `value`
+fn object_fields(value: &[u8]) -> DeltaResult<Vec<(u32, &[u8])>> {
+    let first = *value
+        .first()
+        .ok_or_else(|| Error::generic("Variant value is empty"))?;
+    require!(
+        first & 0b11 == OBJECT,
+        Error::generic("Shredded Variant object has a residual value that is not an object")
+    );
+    let header = (first >> 2) as usize;
+    let offset_size = (header & 0b11) + 1;
+    let id_size = ((header >> 2) & 0b11) + 1;
+    let num_elements_size = if (header >> 4) & 1 == 1 { 4 } else { 1 };
+    let num_elements = read_uint(value, 1, num_elements_size)?;
+    let ids_start = 1 + num_elements_size;
+    let offsets_start = ids_start + num_elements * id_size;
+    let data_start = offsets_start + (num_elements + 1) * offset_size;
+    let offsets = (0..=num_elements)
+        .map(|i| read_uint(value, offsets_start + i * offset_size, offset_size))
+        .collect::<DeltaResult<Vec<_>>>()?;
+    (0..num_elements)
+        .map(|i| {
+            let id = read_uint(value, ids_start + i * id_size, id_size)? as u32;
+            // field values are not necessarily stored in field id order, so a field's value ends
+            // where the next value (or the object) starts
+            let start = offsets[i];
+            let end = offsets
+                .iter()
+                .copied()
+                .filter(|offset| *offset > start)
+                .min()
+                .unwrap_or(start);
+            let field_value = value
+                .get(data_start + start..data_start + end)
+                .ok_or_else(|| Error::generic("Variant value is truncated"))?;
+            Ok((id, field_value))
+        })
+        .collect()
+}
+
+/// The dictionary of field names of a variant, stored in its `metadata` column
+struct VariantMetadata<'a> {
+    bytes: &'a [u8],
+    sorted: bool,
+    offset_size: usize,
+    dictionary_size: usize,
+}
+
+impl<'a> VariantMetadata<'a> {
+    fn try_new(bytes: &'a [u8]) -> DeltaResult<Self> {
+        let header = *bytes
+            .first()
+            .ok_or_else(|| Error::generic("Variant metadata is empty"))?;
+        require!(
+            header & 0b1111 == 1,
+            Error::unsupported(format!(
+                "Unsupported Variant metadata version: {}",
+                header & 0b1111
+            ))
+        );
+        let offset_size = (header >> 6) as usize + 1;
+        Ok(Self {
+            bytes,
+            sorted: (header >> 4) & 1 == 1,
+            offset_size,
+            dictionary_size: read_uint(bytes, 1, offset_size)?,
+        })
+    }
+
+    /// Returns the field name with the given id
+    fn get(&self, id: u32) -> DeltaResult<&'a str> {
+        let id = id as usize;
+        require!(
+            id < self.dictionary_size,
+            Error::generic(format!("Variant field id {id} is not in the metadata"))
+        );
+        let offsets_start = 1 + self.offset_size;
+        let data_start = offsets_start + (self.dictionary_size + 1) * self.offset_size;
+        let start = read_uint(
+            self.bytes,
+            offsets_start + id * self.offset_size,
+            self.offset_size,
+        )?;
+        let end = read_uint(
+            self.bytes,
+            offsets_start + (id + 1) * self.offset_size,
+            self.offset_size,
+        )?;
+        let name = self
+            .bytes
+            .get(data_start + start..data_start + end)
+            .ok_or_else(|| Error::generic("Variant metadata is truncated"))?;
+        std::str::from_utf8(name).map_err(|_| Error::generic("Variant field name is not UTF-8"))
+    }
+
+    /// Returns the id of the field with the given name
+    fn find(&self, name: &str) -> DeltaResult<u32> {
+        let not_found = || {
+            Error::generic(format!(
+                "Shredded Variant field {name} is not in the Variant metadata"
+            ))
+        };
+        if self.sorted {
+            let (mut low, mut high) = (0, self.dictionary_size);
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.get(mid as u32)?.cmp(name) {
+                    std::cmp::Ordering::Less => low = mid + 1,
+                    std::cmp::Ordering::Greater => high = mid,
+                    std::cmp::Ordering::Equal => return Ok(mid as u32),
+                }
+            }
+            Err(not_found())
+        } else {
+            for id in 0..self.dictionary_size as u32 {
+                if self.get(id)? == name {
+                    return Ok(id);
+                }
+            }
+            Err(not_found())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrow::array::{BinaryArray, Int32Array, Int64Array, ListArray, StringArray};
+    use crate::arrow::buffer::{NullBuffer, OffsetBuffer};
+    use crate::arrow::datatypes::Fields;
+
+    // sorted dictionary with the field names "a" and "b"
+    const METADATA_AB: &[u8] = &[0x11, 2, 0, 1, 2, b'a', b'b'];
+
+    fn shredded(
+        metadata: Vec<&'static [u8]>,
+        value: Vec<Option<&'static [u8]>>,
+        typed_value: ArrayRef,
+        nulls: Option<NullBuffer>,
+    ) -> ArrayRef {
+        let fields = Fields::from(vec![
+            ArrowField::new(METADATA, ArrowDataType::Binary, false),
+            ArrowField::new(VALUE, ArrowDataType::Binary, true),
+            ArrowField::new(TYPED_VALUE, typed_value.data_type().clone(), true),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(BinaryArray::from(metadata)),
+            Arc::new(BinaryArray::from(value)),
+            typed_value,
+        ];
+        Arc::new(StructArray::new(fields, columns, nulls))
+    }
+
+    fn group(value: Vec<Option<&'static [u8]>>, typed_value: ArrayRef) -> StructArray {
+        let fields = Fields::from(vec![
+            ArrowField::new(VALUE, ArrowDataType::Binary, true),
+            ArrowField::new(TYPED_VALUE, typed_value.data_type().clone(), true),
+        ]);
+        StructArray::new(
+            fields,
+            vec![Arc::new(BinaryArray::from(value)), typed_value],
+            None,
+        )
+    }
+
+    fn values(unshredded: &ArrayRef) -> Vec<Option<&[u8]>> {
+        let unshredded = unshredded.as_struct();
+        assert_eq!(
+            unshredded
+                .fields()
+                .iter()
+                .map(|f| f.name())
+                .collect::<Vec<_>>(),
+            [METADATA, VALUE]
+        );
+        unshredded
+            .column_by_name(VALUE)
+            .unwrap()
+            .as_binary::<i32>()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_is_shredded_variant() {
+        let struct_field = |names: &[&str]| {
+            let fields: Vec<_> = names
+                .iter()
+                .map(|name| ArrowField::new(*name, ArrowDataType::Binary, true))
+                .collect();
+            ArrowField::new_struct("v", fields, true)
+        };
+        assert!(is_shredded_variant(&struct_field(&[
+            METADATA,
+            VALUE,
+            TYPED_VALUE
+        ])));
+        assert!(is_shredded_variant(&struct_field(&[METADATA, TYPED_VALUE])));
+        assert!(!is_shredded_variant(&struct_field(&[METADATA, VALUE])));
+        assert!(!is_shredded_variant(&struct_field(&[
+            METADATA,
+            TYPED_VALUE,
+            "other"
+        ])));
+        assert!(!is_shredded_variant(&ArrowField::new(
+            "v",
+            ArrowDataType::Binary,
+            true
+        )));
+    }
+
+    #[test]
+    fn test_unshred_primitive() {
+        let empty_metadata: &[u8] = &[0x01, 0, 0];
+        // the short string "hi"
+        let hi: &[u8] = &[0x09, b'h', b'i'];
+        let typed_value = Arc::new(Int64Array::from(vec![Some(42), None, None, None]));
+        let array = shredded(
+            vec![empty_metadata; 4],
+            vec![None, Some(hi), None, None],
+            typed_value,
+            Some(NullBuffer::from(vec![true, true, true, false])),
+        );
+        let unshredded = unshred_variant(&array).unwrap();
+        assert_eq!(
+            values(&unshredded),
+            [
+                Some([0x18, 42, 0, 0, 0, 0, 0, 0, 0].as_slice()),
+                Some(hi),
+                // missing values are variant null
+                Some([0x00].as_slice()),
+                // null variants stay null
+                None,
+            ]
+        );
+        let metadata = unshredded.as_struct().column(0).as_binary::<i32>();
+        assert_eq!(metadata.value(0), empty_metadata);
+    }
+
+    #[test]
+    fn test_unshred_object() {
+        // field `a` is shredded as an int, field `b` is stored in the residual value
+        let a = group(
+            vec![None, None],
+            Arc::new(Int32Array::from(vec![Some(1), None])),
+        );
+        let typed_value = Arc::new(StructArray::from(vec![(
+            Arc::new(ArrowField::new("a", a.data_type().clone(), true)),
+            Arc::new(a) as ArrayRef,
+        )]));
+        // the object {"b": true}
+        let residual: &[u8] = &[0x02, 1, 1, 0, 1, 0x04];
+        let array = shredded(
+            vec![METADATA_AB; 2],
+            vec![Some(residual), None],
+            typed_value,
+            None,
+        );
+        let unshredded = unshred_variant(&array).unwrap();
+        assert_eq!(
+            values(&unshredded),
+            [
+                // the object {"a": 1, "b": true}
+                Some([0x02, 2, 0, 1, 0, 5, 6, 0x14, 1, 0, 0, 0, 0x04].as_slice()),
+                // the empty object, since `a` is missing
+                Some([0x02, 0, 0].as_slice()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unshred_array() {
+        let elements = group(vec![None], Arc::new(StringArray::from(vec!["x"])));
+        let typed_value = Arc::new(ListArray::new(
+            Arc::new(ArrowField::new(
+                "element",
+                elements.data_type().clone(),
+                false,
+            )),
+            OffsetBuffer::from_lengths([1]),
+            Arc::new(elements),
+            None,
+        ));
+        let array = shredded(vec![METADATA_AB], vec![None], typed_value, None);
+        let unshredded = unshred_variant(&array).unwrap();
+        // the array ["x"]
+        assert_eq!(
+            values(&unshredded),
+            [Some([0x03, 1, 0, 2, 0x05, b'x'].as_slice())]
+        );
+    }
+
+    #[test]
+    fn test_unshred_rejects_unknown_field() {
+        let c = group(vec![None], Arc::new(Int32Array::from(vec![1])));
+        let typed_value = Arc::new(StructArray::from(vec![(
+            Arc::new(ArrowField::new("c", c.data_type().clone(), true)),
+            Arc::new(c) as ArrayRef,
+        )]));
+        let array = shredded(vec![METADATA_AB], vec![None], typed_value, None);
+        let err = unshred_variant(&array).unwrap_err();
+        assert!(err.to_string().contains("is not in the Variant metadata"));
+    }
+}
@@ -0,0 +1,498 @@
+//! Conversion between kernel expressions and schemas and their [DataFusion] counterparts, enabled
+//! by the `datafusion` feature. This allows DataFusion-based engines to evaluate kernel predicates
+//! (e.g. after kernel has used them for data skipping) and kernel-produced expressions natively,
+//! and to hand DataFusion filters to kernel.
+//!
+//! DataFusion physical expressions resolve columns against an arrow [`Schema`], so every
+//! conversion takes the schema the expressions apply to. Only expressions that have a direct
+//! physical counterpart are supported; in particular nested column references, `COALESCE`,
+//! `NULLIF`, struct construction, transforms, and opaque expressions are not, because DataFusion
+//! implements them as functions that require a function registry.
+//!
+//! [DataFusion]: https://datafusion.apache.org
+
+use std::sync::Arc;
+
+use datafusion_common::ScalarValue;
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{
+    in_list, BinaryExpr, CaseExpr, CastExpr, Column, InListExpr, IsNotNullExpr, IsNullExpr,
+    LikeExpr, Literal, NotExpr, TryCastExpr,
+};
+use datafusion_physical_expr::PhysicalExpr;
+use itertools::Itertools;
+
+use super::arrow_conversion::{TryFromArrow as _, TryFromKernel as _};
+use crate::arrow::datatypes::{DataType as ArrowDataType, Schema};
+use crate::expressions::{
+    ArrayData, BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp,
+    CaseExpression, CastExpression, CastOverflowPolicy, Expression, JunctionPredicate,
+    JunctionPredicateOp, Predicate, Scalar, UnaryPredicate, UnaryPredicateOp,
+};
+use crate::schema::{ArrayType, DataType, StructType};
+use crate::{DeltaResult, Error};
+
+/// A DataFusion physical expression.
+pub type PhysicalExprRef = Arc<dyn PhysicalExpr>;
+
+/// Converts a kernel schema to the arrow schema DataFusion uses.
+pub fn schema_to_datafusion(schema: &StructType) -> DeltaResult<Schema> {
+    Schema::try_from_kernel(schema).map_err(Error::generic_err)
+}
+
+/// Converts an arrow schema used by DataFusion to a kernel schema.
+pub fn schema_from_datafusion(schema: &Schema) -> DeltaResult<StructType> {
+    StructType::try_from_arrow(schema).map_err(Error::generic_err)
+}
+
+/// Converts a kernel expression to a DataFusion physical expression whose columns refer to
+/// `schema`.
+pub fn expression_to_datafusion(
+    expr: &Expression,
+    schema: &Schema,
+) -> DeltaResult<PhysicalExprRef> {
+    ToDataFusion { schema }.expression(expr)
+}
+
+/// Converts a kernel predicate to a (boolean) DataFusion physical expression, see
+/// [`expression_to_datafusion`].
+pub fn predicate_to_datafusion(pred: &Predicate, schema: &Schema) -> DeltaResult<PhysicalExprRef> {
+    ToDataFusion { schema }.predicate(pred)
+}
+
+/// Converts a DataFusion physical expression to a kernel expression.
+pub fn expression_from_datafusion(expr: &dyn PhysicalExpr) -> DeltaResult<Expression> {
+    let any = expr.as_any();
+    let result = if let Some(column) = any.downcast_ref::<Column>() {
+        Expression::column([column.name()])
+    } else if let Some(literal) = any.downcast_ref::<Literal>() {
+        Expression::literal(scalar_from_datafusion(literal.value())?)
+    } else if let Some(binary) = any.downcast_ref::<BinaryExpr>() {
+        let left = expression_from_datafusion(binary.left().as_ref())?;
+        let right = expression_from_datafusion(binary.right().as_ref())?;
+        let arithmetic = |op| Expression::binary(op, left.clone(), right.clone());
+        let comparison = |f: fn(Expression, Expression) -> Predicate| {
+            Expression::from_pred(f(left.clone(), right.clone()))
+        };
+        match binary.op() {
+            Operator::Plus => arithmetic(BinaryExpressionOp::Plus),
+            Operator::Minus => arithmetic(BinaryExpressionOp::Minus),
+            Operator::Multiply => arithmetic(BinaryExpressionOp::Multiply),
+            Operator::Divide => arithmetic(BinaryExpressionOp::Divide),
+            Operator::Modulo => arithmetic(BinaryExpressionOp::Modulo),
+            Operator::Eq => comparison(Predicate::eq),
+            Operator::NotEq => comparison(Predicate::ne),
+            Operator::Lt => comparison(Predicate::lt),
+            Operator::LtEq => comparison(Predicate::le),
+            Operator::Gt => comparison(Predicate::gt),
+            Operator::GtEq => comparison(Predicate::ge),
+            Operator::IsDistinctFrom => comparison(Predicate::distinct),
+            Operator::IsNotDistinctFrom => {
+                comparison(|a, b| Predicate::not(Predicate::distinct(a, b)))
+            }
+            Operator::And => {
+                comparison(|a, b| Predicate::and(Predicate::from_expr(a), Predicate::from_expr(b)))
+            }
+            Operator::Or => {
+                comparison(|a, b| Predicate::or(Predicate::from_expr(a), Predicate::from_expr(b)))
+            }
+            op => return Err(unsupported_physical(op)),
+        }
+    } else if let Some(not) = any.downcast_ref::<NotExpr>() {
+        let pred = predicate_from_datafusion(not.arg().as_ref())?;
+        Expression::from_pred(Predicate::not(pred))
+    } else if let Some(is_null) = any.downcast_ref::<IsNullExpr>() {
+        let expr = expression_from_datafusion(is_null.arg().as_ref())?;
+        Expression::from_pred(Predicate::is_null(expr))
+    } else if let Some(is_not_null) = any.downcast_ref::<IsNotNullExpr>() {
+        let expr = expression_from_datafusion(is_not_null.arg().as_ref())?;
+        Expression::from_pred(Predicate::is_not_null(expr))
+    } else if let Some(like) = any.downcast_ref::<LikeExpr>() {
+        let expr = expression_from_datafusion(like.expr().as_ref())?;
+        let Expression::Literal(Scalar::String(pattern)) =
+            expression_from_datafusion(like.pattern().as_ref())?
+        else {
+            return Err(unsupported_physical(like));
+        };
+        let pred = match like.case_insensitive() {
+            true => Predicate::ilike(expr, pattern),
+            false => Predicate::like(expr, pattern),
+        };
+        match like.negated() {
+            true => Expression::from_pred(Predicate::not(pred)),
+            false => Expression::from_pred(pred),
+        }
+    } else if let Some(list) = any.downcast_ref::<InListExpr>() {
+        let expr = expression_from_datafusion(list.expr().as_ref())?;
+        let values: Vec<_> = list
+            .list()
+            .iter()
+            .map(|value| match value.as_any().downcast_ref::<Literal>() {
+                Some(literal) => scalar_from_datafusion(literal.value()),
+                None => Err(unsupported_physical(list)),
+            })
+            .try_collect()?;
+        let element_type = values.first().ok_or_else(|| unsupported_physical(list))?;
+        let contains_null = values.iter().any(Scalar::is_null);
+        let array_type = ArrayType::new(element_type.data_type(), contains_null);
+        let values = ArrayData::try_new(array_type, values)?;
+        match list.negated() {
+            true => Expression::from_pred(Predicate::not_in_list(expr, values)),
+            false => Expression::from_pred(Predicate::in_list(expr, values)),
+        }
+    } else if let Some(case) = any.downcast_ref::<CaseExpr>() {
+        // CASE <expr> WHEN ... is not supported, only CASE WHEN <predicate> ...
+        if case.expr().is_some() {
+            return Err(unsupported_physical(case));
+        }
+        let branches: Vec<_> = case
+            .when_then_expr()
+            .iter()
+            .map(|(when, then)| {
+                Ok::<_, Error>((
+                    predicate_from_datafusion(when.as_ref())?,
+                    expression_from_datafusion(then.as_ref())?,
+                ))
+            })
+            .try_collect()?;
+        let else_expr = match case.else_expr() {
+            Some(else_expr) => Some(expression_from_datafusion(else_expr.as_ref())?),
+            None => None,
+        };
+        Expression::case(branches, else_expr)
+    } else if let Some(cast) = any.downcast_ref::<CastExpr>() {
+        let expr = expression_from_datafusion(cast.expr().as_ref())?;
+        Expression::cast(
+            expr,
+            DataType::try_from_arrow(cast.cast_type()).map_err(Error::generic_err)?,
+        )
+    } else if let Some(cast) = any.downcast_ref::<TryCastExpr>() {
+        let expr = expression_from_datafusion(cast.expr().as_ref())?;
+        Expression::try_cast(
+            expr,
+            DataType::try_from_arrow(cast.cast_type()).map_err(Error::generic_err)?,
+        )
+    } else {
+        return Err(unsupported_physical(expr));
+    };
+    Ok(result)
+}
+
+/// Converts a (boolean) DataFusion physical expression to a kernel predicate, see
+/// [`expression_from_datafusion`].
+pub fn predicate_from_datafusion(expr: &dyn PhysicalExpr) -> DeltaResult<Predicate> {
+    Ok(Predicate::from_expr(expression_from_datafusion(expr)?))
+}
+
+fn unsupported_physical(expr: impl std::fmt::Display) -> Error {
+    Error::unsupported(format!(
+        "Cannot convert DataFusion expression {expr} to kernel"
+    ))
+}
+
+fn unsupported_kernel(expr: impl std::fmt::Display) -> Error {
+    Error::unsupported(format!("Cannot convert {expr} to DataFusion"))
+}
+
+struct ToDataFusion<'a> {
+    schema: &'a Schema,
+}
+
+impl ToDataFusion<'_> {
+    fn expression(&self, expr: &Expression) -> DeltaResult<PhysicalExprRef> {
+        let result: PhysicalExprRef = match expr {
+            Expression::Literal(value) => Arc::new(Literal::new(scalar_to_datafusion(value)?)),
+            Expression::Column(name) => match name.path() {
+                [name] => Arc::new(
+                    Column::new_with_schema(name, self.schema).map_err(Error::generic_err)?,
+                ),
+                _ => return Err(unsupported_kernel(expr)),
+            },
+            Expression::Predicate(pred) => self.predicate(pred)?,
+            Expression::Binary(BinaryExpression { op, left, right }) => {
+                let op = match op {
+                    BinaryExpressionOp::Plus => Operator::Plus,
+                    BinaryExpressionOp::Minus => Operator::Minus,
+                    BinaryExpressionOp::Multiply => Operator::Multiply,
+                    BinaryExpressionOp::Divide => Operator::Divide,
+                    BinaryExpressionOp::Modulo => Operator::Modulo,
+                };
+                self.binary(left, op, right)?
+            }
+            Expression::Case(CaseExpression {
+                branches,
+                else_expr,
+            }) => {
+                let when_then = branches
+                    .iter()
+                    .map(|(when, then)| {
+                        Ok::<_, Error>((self.predicate(when)?, self.expression(then)?))
+                    })
+                    .try_collect()?;
+                let else_expr = match else_expr {
+                    Some(else_expr) => Some(self.expression(else_expr)?),
+                    None => None,
+                };
+                Arc::new(CaseExpr::try_new(None, when_then, else_expr).map_err(Error::generic_err)?)
+            }
+            Expression::Cast(CastExpression {
+                expr,
+                to,
+                on_overflow,
+            }) => {
+                let expr = self.expression(expr)?;
+                let to = ArrowDataType::try_from_kernel(to).map_err(Error::generic_err)?;
+                match on_overflow {
+                    CastOverflowPolicy::Error => Arc::new(CastExpr::new(expr, to, None)),
+                    CastOverflowPolicy::Null => Arc::new(TryCastExpr::new(expr, to)),
+                }
+            }
+            Expression::Struct(_)
+            | Expression::Transform(_)
+            | Expression::Unary(_)
+            | Expression::Variadic(_)
+            | Expression::Element(_)
+            | Expression::Opaque(_)
+            | Expression::Unknown(_) => return Err(unsupported_kernel(expr)),
+        };
+        Ok(result)
+    }
+
+    fn predicate(&self, pred: &Predicate) -> DeltaResult<PhysicalExprRef> {
+        let result: PhysicalExprRef = match pred {
+            Predicate::BooleanExpression(expr) => self.expression(expr)?,
+            Predicate::Not(pred) => Arc::new(NotExpr::new(self.predicate(pred)?)),
+            Predicate::Unary(UnaryPredicate {
+                op: UnaryPredicateOp::IsNull,
+                expr,
+            }) => Arc::new(IsNullExpr::new(self.expression(expr)?)),
+            Predicate::Binary(BinaryPredicate { op, left, right }) => match op {
+                BinaryPredicateOp::LessThan => self.binary(left, Operator::Lt, right)?,
+                BinaryPredicateOp::GreaterThan => self.binary(left, Operator::Gt, right)?,
+                BinaryPredicateOp::Equal => self.binary(left, Operator::Eq, right)?,
+                BinaryPredicateOp::Distinct => {
+                    self.binary(left, Operator::IsDistinctFrom, right)?
+                }
+                BinaryPredicateOp::Like | BinaryPredicateOp::ILike => {
+                    let case_insensitive = *op == BinaryPredicateOp::ILike;
+                    let (expr, pattern) = (self.expression(left)?, self.expression(right)?);
+                    Arc::new(LikeExpr::new(false, case_insensitive, expr, pattern))
+                }
+                BinaryPredicateOp::In => {
+                    let Expression::Literal(Scalar::Array(values)) = right.as_ref() else {
+                        return Err(unsupported_kernel(pred));
+                    };
+                    #[allow(deprecated)]
+                    let list = values
+                        .array_elements()
+                        .iter()
+                        .map(|value| {
+                            let value = scalar_to_datafusion(value)?;
+                            Ok::<_, Error>(Arc::new(Literal::new(value)) as PhysicalExprRef)
+                        })
+                        .try_collect()?;
+                    in_list(self.expression(left)?, list, &false, self.schema)
+                        .map_err(Error::generic_err)?
+                }
+            },
+            Predicate::Junction(JunctionPredicate { op, preds }) => {
+                let (op, identity) = match op {
+                    JunctionPredicateOp::And => (Operator::And, true),
+                    JunctionPredicateOp::Or => (Operator::Or, false),
+                };
+                let preds: Vec<_> = preds
+                    .iter()
+                    .map(|pred| self.predicate(pred))
+                    .try_collect()?;
+                preds
+                    .into_iter()
+                    .reduce(|a, b| Arc::new(BinaryExpr::new(a, op, b)))
+                    .unwrap_or_else(|| Arc::new(Literal::new(ScalarValue::Boolean(Some(identity)))))
+            }
+            Predicate::Opaque(_) | Predicate::Unknown(_) => return Err(unsupported_kernel(pred)),
+        };
+        Ok(result)
+    }
+
+    fn binary(
+        &self,
+        left: &Expression,
+        op: Operator,
+        right: &Expression,
+    ) -> DeltaResult<PhysicalExprRef> {
+        let (left, right) = (self.expression(left)?, self.expression(right)?);
+        Ok(Arc::new(BinaryExpr::new(left, op, right)))
+    }
+}
+
+/// Converts a kernel scalar to a DataFusion scalar value. Struct and map values are not supported.
+pub fn scalar_to_datafusion(value: &Scalar) -> DeltaResult<ScalarValue> {
+    let result = match value {
+        Scalar::Integer(v) => ScalarValue::Int32(Some(*v)),
+        Scalar::Long(v) => ScalarValue::Int64(Some(*v)),
+        Scalar::Short(v) => ScalarValue::Int16(Some(*v)),
+        Scalar::Byte(v) => ScalarValue::Int8(Some(*v)),
+        Scalar::Float(v) => ScalarValue::Float32(Some(*v)),
+        Scalar::Double(v) => ScalarValue::Float64(Some(*v)),
+        Scalar::String(s) => ScalarValue::Utf8(Some(s.clone())),
+        Scalar::Boolean(b) => ScalarValue::Boolean(Some(*b)),
+        Scalar::Timestamp(micros) => {
+            ScalarValue::TimestampMicrosecond(Some(*micros), Some("UTC".into()))
+        }
+        Scalar::TimestampNtz(micros) => ScalarValue::TimestampMicrosecond(Some(*micros), None),
+        Scalar::Date(days) => ScalarValue::Date32(Some(*days)),
+        Scalar::Binary(bytes) => ScalarValue::Binary(Some(bytes.clone())),
+        Scalar::Decimal(d) => {
+            ScalarValue::Decimal128(Some(d.bits()), d.precision(), d.scale() as i8)
+        }
+        Scalar::Null(data_type) => {
+            let data_type =
+                ArrowDataType::try_from_kernel(data_type).map_err(Error::generic_err)?;
+            ScalarValue::try_from(&data_type).map_err(Error::generic_err)?
+        }
+        Scalar::Array(data) => {
+            #[allow(deprecated)]
+            let values: Vec<_> = data
+                .array_elements()
+                .iter()
+                .map(scalar_to_datafusion)
+                .try_collect()?;
+            let element_type = ArrowDataType::try_from_kernel(data.array_type().element_type())
+                .map_err(Error::generic_err)?;
+            ScalarValue::List(ScalarValue::new_list_nullable(&values, &element_type))
+        }
+        Scalar::Struct(_) | Scalar::Map(_) => return Err(unsupported_kernel(value)),
+    };
+    Ok(result)
+}
+
+/// Converts a DataFusion scalar value to a kernel scalar. Only values of types that kernel
+/// supports can be converted.
+pub fn scalar_from_datafusion(value: &ScalarValue) -> DeltaResult<Scalar> {
+    if value.is_null() {
+        let data_type = DataType::try_from_arrow(&value.data_type()).map_err(Error::generic_err)?;
+        return Ok(Scalar::Null(data_type));
+    }
+    let result = match value {
+        ScalarValue::Int32(Some(v)) => Scalar::Integer(*v),
+        ScalarValue::Int64(Some(v)) => Scalar::Long(*v),
+        ScalarValue::Int16(Some(v)) => Scalar::Short(*v),
+        ScalarValue::Int8(Some(v)) => Scalar::Byte(*v),
+        ScalarValue::Float32(Some(v)) => Scalar::Float(*v),
+        ScalarValue::Float64(Some(v)) => Scalar::Double(*v),
+        ScalarValue::Utf8(Some(s))
+        | ScalarValue::LargeUtf8(Some(s))
+        | ScalarValue::Utf8View(Some(s)) => Scalar::String(s.clone()),
+        ScalarValue::Boolean(Some(b)) => Scalar::Boolean(*b),
+        ScalarValue::TimestampMicrosecond(Some(micros), Some(_)) => Scalar::Timestamp(*micros),
+        ScalarValue::TimestampMicrosecond(Some(micros), None) => Scalar::TimestampNtz(*micros),
+        ScalarValue::Date32(Some(days)) => Scalar::Date(*days),
+        ScalarValue::Binary(Some(bytes))
+        | ScalarValue::LargeBinary(Some(bytes))
+        | ScalarValue::BinaryView(Some(bytes)) => Scalar::Binary(bytes.clone()),
+        ScalarValue::Decimal128(Some(bits), precision, scale) => {
+            let scale = (*scale).try_into().map_err(|_| unsupported_value(value))?;
+            Scalar::decimal(*bits, *precision, scale)?
+        }
+        _ => return Err(unsupported_value(value)),
+    };
+    Ok(result)
+}
+
+fn unsupported_value(value: &ScalarValue) -> Error {
+    Error::unsupported(format!("Cannot convert DataFusion value {value} to kernel"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrow::array::{BooleanArray, Int64Array, RecordBatch, StringArray};
+    use crate::arrow::datatypes::Field;
+    use crate::expressions::test_utils::assert_null_aware_eq;
+    use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use crate::schema::StructField;
+
+    fn test_schema() -> StructType {
+        StructType::new_unchecked([
+            StructField::nullable("x", DataType::LONG),
+            StructField::nullable("flag", DataType::BOOLEAN),
+            StructField::nullable("name", DataType::STRING),
+        ])
+    }
+
+    #[test]
+    fn test_schema_roundtrip() {
+        let schema = test_schema();
+        let arrow_schema = schema_to_datafusion(&schema).unwrap();
+        assert_eq!(
+            arrow_schema.field(0),
+            &Field::new("x", ArrowDataType::Int64, true)
+        );
+        assert_eq!(schema_from_datafusion(&arrow_schema).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_predicate_roundtrip() {
+        let schema = schema_to_datafusion(&test_schema()).unwrap();
+        let values = ArrayData::try_new(ArrayType::new(DataType::LONG, false), [1i64, 2]).unwrap();
+        let preds = [
+            Pred::and(
+                Pred::gt(column_expr!("x"), Expr::literal(10i64)),
+                Pred::not(Pred::is_null(column_expr!("name"))),
+            ),
+            Pred::or(
+                column_pred!("flag"),
+                Pred::distinct(column_expr!("x"), Expr::null_literal(DataType::LONG)),
+            ),
+            Pred::in_list(column_expr!("x"), values),
+            Pred::ilike(column_expr!("name"), "a%"),
+            Pred::eq(
+                Expr::try_cast(column_expr!("x") * Expr::literal(2i64), DataType::INTEGER),
+                Expr::literal(Scalar::decimal(12345, 10, 2).unwrap()),
+            ),
+        ];
+        for pred in preds {
+            let physical = predicate_to_datafusion(&pred, &schema).unwrap();
+            let decoded = predicate_from_datafusion(physical.as_ref()).unwrap();
+            assert_null_aware_eq!(decoded, pred, "{pred}");
+        }
+    }
+
+    #[test]
+    fn test_evaluate_converted_predicate() {
+        let schema = Arc::new(schema_to_datafusion(&test_schema()).unwrap());
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), Some(20), None])),
+                Arc::new(BooleanArray::from(vec![true, false, true])),
+                Arc::new(StringArray::from(vec!["abc", "Ab", "b"])),
+            ],
+        )
+        .unwrap();
+        let pred = Pred::or(
+            Pred::gt(column_expr!("x"), Expr::literal(10i64)),
+            Pred::ilike(column_expr!("name"), "a%"),
+        );
+        let physical = predicate_to_datafusion(&pred, &schema).unwrap();
+        let result = physical.evaluate(&batch).unwrap().into_array(3).unwrap();
+        let expected = BooleanArray::from(vec![Some(true), Some(true), None]);
+        assert_eq!(
+            result.as_ref(),
+            &expected as &dyn crate::arrow::array::Array
+        );
+    }
+
+    #[test]
+    fn test_unsupported_conversions() {
+        let schema = schema_to_datafusion(&test_schema()).unwrap();
+        for expr in [
+            column_expr!("missing"),
+            column_expr!("x.nested"),
+            Expr::coalesce([column_expr!("x"), Expr::literal(0i64)]),
+            Expr::struct_from([column_expr!("x")]),
+        ] {
+            assert!(expression_to_datafusion(&expr, &schema).is_err(), "{expr}");
+        }
+    }
+}
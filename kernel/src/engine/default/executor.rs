@@ -41,6 +41,49 @@ pub trait TaskExecutor: Send + Sync + 'static {
         R: Send + 'static;
 }
 
+/// A dedicated pool of threads for CPU-heavy work of the `DefaultEngine`, such as decompressing
+/// and decoding parquet data, so that it doesn't starve the async IO tasks of the
+/// [`TaskExecutor`]. Share one pool among engines to bound the total number of decode threads.
+#[derive(Debug)]
+pub struct DecodePool {
+    pool: rayon::ThreadPool,
+}
+
+impl DecodePool {
+    /// Create a pool of `num_threads` threads. If `num_threads` is zero, the pool has one thread
+    /// per CPU.
+    pub fn try_new(num_threads: usize) -> DeltaResult<Self> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|i| format!("delta-kernel-decode-{i}"))
+            // a panicking task drops its result sender, which `run` reports as an error
+            .panic_handler(|_| {})
+            .build()
+            .map_err(|e| crate::Error::generic(format!("Failed to create decode pool: {e}")))?;
+        Ok(Self { pool })
+    }
+
+    /// The number of threads in the pool.
+    pub fn num_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Run `task` on the pool, and wait (asynchronously) for its output.
+    pub(crate) async fn run<T, R>(&self, task: T) -> DeltaResult<R>
+    where
+        T: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        self.pool.spawn(move || {
+            sender.send(task()).ok();
+        });
+        receiver
+            .await
+            .map_err(|_| crate::Error::generic("Decode task panicked"))
+    }
+}
+
 #[cfg(any(feature = "tokio", test))]
 pub mod tokio {
     use super::TaskExecutor;
@@ -152,6 +195,11 @@ pub mod tokio {
     /// A [`TaskExecutor`] that uses the tokio multi-threaded runtime. You can
     /// create one based on a handle to an existing runtime, so it can share
     /// the runtime with other parts of your application.
+    ///
+    /// [`TaskExecutor::block_on`] may be called from the runtime's own worker
+    /// threads (e.g. by kernel APIs called from an async task): the worker is
+    /// then handed off via [`tokio::task::block_in_place`] while it waits, so
+    /// the runtime's other tasks (including the awaited one) keep running.
     #[derive(Debug)]
     pub struct TokioMultiThreadExecutor {
         handle: tokio::runtime::Handle,
@@ -166,6 +214,17 @@ pub mod tokio {
             );
             Self { handle }
         }
+
+        /// Like [`TokioMultiThreadExecutor::new`], but returns an error instead
+        /// of panicking if `handle` is not a multi-threaded runtime.
+        pub fn try_new(handle: tokio::runtime::Handle) -> DeltaResult<Self> {
+            match handle.runtime_flavor() {
+                RuntimeFlavor::MultiThread => Ok(Self { handle }),
+                flavor => Err(crate::Error::generic(format!(
+                    "TokioMultiThreadExecutor requires a multi-threaded runtime, got {flavor:?}"
+                ))),
+            }
+        }
     }
 
     impl TaskExecutor for TokioMultiThreadExecutor {
@@ -191,9 +250,21 @@ pub mod tokio {
             // We throw away the handle, but it should continue on.
             self.handle.spawn(fut);
 
-            receiver
-                .recv()
-                .expect("TokioMultiThreadExecutor has crashed")
+            let wait = || {
+                receiver
+                    .recv()
+                    .expect("TokioMultiThreadExecutor has crashed")
+            };
+            // Blocking a worker thread of a multi-threaded runtime would starve its
+            // tasks (and deadlock if they include `task`), so hand the worker off first.
+            // This isn't possible on a current-thread runtime, but `task` runs on our
+            // (separate, multi-threaded) runtime then anyway.
+            match tokio::runtime::Handle::try_current() {
+                Ok(current) if current.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                    tokio::task::block_in_place(wait)
+                }
+                _ => wait(),
+            }
         }
 
         fn spawn<F>(&self, task: F)
@@ -246,5 +317,43 @@ pub mod tokio {
             let executor = TokioMultiThreadExecutor::new(tokio::runtime::Handle::current());
             test_executor(executor).await;
         }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+        async fn test_tokio_multi_thread_executor_block_on_from_worker() {
+            // block_on is called from the runtime's only worker thread, which must not prevent
+            // the task it waits for from running on that same runtime
+            let executor = TokioMultiThreadExecutor::new(tokio::runtime::Handle::current());
+            let result = tokio::spawn(async move {
+                executor.block_on(async {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    2 + 2
+                })
+            });
+            assert_eq!(result.await.unwrap(), 4);
+        }
+
+        #[tokio::test]
+        async fn test_tokio_multi_thread_executor_requires_multi_thread_runtime() {
+            let result = TokioMultiThreadExecutor::try_new(tokio::runtime::Handle::current());
+            assert!(result.is_err_and(|e| e.to_string().contains("multi-threaded runtime")));
+        }
+
+        #[tokio::test]
+        async fn test_decode_pool() {
+            use crate::engine::default::executor::DecodePool;
+
+            let pool = DecodePool::try_new(2).unwrap();
+            assert_eq!(pool.num_threads(), 2);
+            let thread_name = pool
+                .run(|| std::thread::current().name().map(str::to_string))
+                .await
+                .unwrap();
+            assert!(thread_name.unwrap().starts_with("delta-kernel-decode-"));
+
+            // a panicking task fails, but doesn't bring down the pool
+            let result = pool.run(|| -> i32 { panic!("decode failed") }).await;
+            assert!(result.is_err_and(|e| e.to_string().contains("Decode task panicked")));
+            assert_eq!(pool.run(|| 2 + 2).await.unwrap(), 4);
+        }
     }
 }
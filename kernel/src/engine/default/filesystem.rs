@@ -1,22 +1,48 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 use delta_kernel_derive::internal_api;
-use futures::stream::StreamExt;
+use futures::stream::{StreamExt, TryStreamExt};
+use futures::SinkExt as _;
 use itertools::Itertools;
 use object_store::path::Path;
-use object_store::{DynObjectStore, ObjectStore};
+use object_store::{DynObjectStore, ObjectMeta, ObjectStore};
 use url::Url;
 
 use super::UrlExt;
 use crate::engine::default::executor::TaskExecutor;
 use crate::{DeltaResult, Error, FileMeta, FileSlice, StorageHandler};
 
+// The number of listed files to buffer ahead of the consumer of a listing, i.e. one page of a
+// typical cloud store listing
+const LIST_BUFFER_SIZE: usize = 1000;
+
+// The default maximum gap between byte ranges of a file that are fetched with a single request,
+// the same as object_store's default for `get_ranges`
+const DEFAULT_COALESCE_GAP: u64 = 1024 * 1024;
+
 #[derive(Debug)]
 pub struct ObjectStoreStorageHandler<E: TaskExecutor> {
     inner: Arc<DynObjectStore>,
     task_executor: Arc<E>,
     readahead: usize,
+    coalesce_gap: u64,
+    listing_cache: Option<Arc<ListingCache>>,
+}
+
+impl<E: TaskExecutor> Clone for ObjectStoreStorageHandler<E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            task_executor: self.task_executor.clone(),
+            readahead: self.readahead,
+            coalesce_gap: self.coalesce_gap,
+            listing_cache: self.listing_cache.clone(),
+        }
+    }
 }
 
 impl<E: TaskExecutor> ObjectStoreStorageHandler<E> {
@@ -26,6 +52,8 @@ impl<E: TaskExecutor> ObjectStoreStorageHandler<E> {
             inner: store,
             task_executor,
             readahead: 10,
+            coalesce_gap: DEFAULT_COALESCE_GAP,
+            listing_cache: None,
         }
     }
 
@@ -34,6 +62,199 @@ impl<E: TaskExecutor> ObjectStoreStorageHandler<E> {
         self.readahead = readahead;
         self
     }
+
+    /// Set the maximum gap in bytes between the byte ranges of a file read by
+    /// [`StorageHandler::read_files`] for which they are fetched with a single request. Reading
+    /// the gap between the ranges is usually cheaper than an additional request, e.g. for many
+    /// small deletion vectors stored in the same file. Use 0 to only merge overlapping or adjacent
+    /// ranges. Defaults to 1 MiB.
+    pub fn with_coalesce_gap(mut self, coalesce_gap: u64) -> Self {
+        self.coalesce_gap = coalesce_gap;
+        self
+    }
+
+    /// Cache the files listed by [`StorageHandler::list_from`] for up to `ttl`, so that listing
+    /// the same directory again (e.g. to update a snapshot) only lists the files after the last
+    /// cached one, instead of all files after the requested offset.
+    ///
+    /// Within the TTL, listings may be stale: deleted files are still returned, and files that
+    /// sort before the last cached file (e.g. a checkpoint written for an earlier version) are
+    /// not returned. Listings with caching are not streamed, but collected before returning.
+    pub fn with_listing_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.listing_cache = Some(Arc::new(ListingCache::new(ttl)));
+        self
+    }
+
+    // Lists all files after `offset` (and whose paths start with `prefix`), reusing and updating
+    // the cached listing of `prefix`
+    fn list_from_cache(
+        &self,
+        cache: &ListingCache,
+        url: &Url,
+        prefix: Path,
+        offset: Path,
+        has_ordered_listing: bool,
+    ) -> DeltaResult<Vec<FileMeta>> {
+        let mut files = cache.get(&prefix, &offset);
+        let list_offset = files.last().map_or(&offset, |(path, _)| path).clone();
+        let store = self.inner.clone();
+        let list_prefix = prefix.clone();
+        let mut listed: Vec<ObjectMeta> = self.task_executor.block_on(async move {
+            store
+                .list_with_offset(Some(&list_prefix), &list_offset)
+                .try_collect()
+                .await
+        })?;
+        if !has_ordered_listing {
+            listed.sort_unstable_by(|a, b| a.location.cmp(&b.location));
+        }
+        let listed = listed
+            .into_iter()
+            .map(|meta| (meta.location.clone(), file_meta(url, &meta)))
+            .collect_vec();
+        cache.extend(prefix, offset, &listed);
+        files.extend(listed);
+        Ok(files.into_iter().map(|(_, meta)| meta).collect())
+    }
+}
+
+// Converts listed object metadata to a `FileMeta` of a file in the same store as `url`
+fn file_meta(url: &Url, meta: &ObjectMeta) -> FileMeta {
+    let mut location = url.clone();
+    location.set_path(&format!("/{}", meta.location.as_ref()));
+    FileMeta {
+        location,
+        last_modified: meta.last_modified.timestamp_millis(),
+        size: meta.size,
+    }
+}
+
+// The index of the fetch that serves a file slice, and the slice's byte range within the fetched
+// data if it doesn't need all of it
+type SliceFetch = (usize, Option<Range<usize>>);
+
+/// Plans the requests to read the given file slices: byte ranges of the same file that are at most
+/// `max_gap` bytes apart are fetched with a single request. Returns the slices to fetch, and for
+/// each of the given slices the index of the fetch that serves it, along with its byte range within
+/// the fetched data if it doesn't need all of it. Fetches are ordered by the first slice they serve.
+fn coalesce_slices(files: Vec<FileSlice>, max_gap: u64) -> (Vec<FileSlice>, Vec<SliceFetch>) {
+    // group the ranges to read from each file (pre-signed URLs may not honor ranges)
+    let mut ranges_by_file: HashMap<&Url, Vec<(usize, Range<u64>)>> = HashMap::new();
+    for (i, (url, range)) in files.iter().enumerate() {
+        if let Some(range) = range.as_ref().filter(|_| !url.is_presigned()) {
+            let ranges = ranges_by_file.entry(url).or_default();
+            ranges.push((i, range.clone()));
+        }
+    }
+
+    // merge the nearby ranges of each file, counting the slices each merged range serves
+    let mut merged: Vec<(Range<u64>, usize)> = vec![];
+    let mut merged_index = vec![None; files.len()];
+    for mut ranges in ranges_by_file.into_values() {
+        ranges.sort_by_key(|(_, range)| range.start);
+        let mut current = None;
+        for (i, range) in ranges {
+            match current
+                .filter(|&m: &usize| range.start <= merged[m].0.end.saturating_add(max_gap))
+            {
+                Some(m) => {
+                    merged[m].0.end = merged[m].0.end.max(range.end);
+                    merged[m].1 += 1;
+                }
+                None => {
+                    current = Some(merged.len());
+                    merged.push((range, 1));
+                }
+            }
+            merged_index[i] = current;
+        }
+    }
+
+    let mut fetches = vec![];
+    let mut fetch_of_merged = vec![None; merged.len()];
+    let mut plan = Vec::with_capacity(files.len());
+    for ((url, range), merged_index) in files.into_iter().zip(merged_index) {
+        let (Some(range), Some(m)) = (range.clone(), merged_index) else {
+            plan.push((fetches.len(), None));
+            fetches.push((url, range));
+            continue;
+        };
+        let (merged_range, num_slices) = &merged[m];
+        let fetch_index = *fetch_of_merged[m].get_or_insert_with(|| {
+            fetches.push((url, Some(merged_range.clone())));
+            fetches.len() - 1
+        });
+        let range = (*num_slices > 1).then(|| {
+            let start = (range.start - merged_range.start) as usize;
+            start..start + (range.end - range.start) as usize
+        });
+        plan.push((fetch_index, range));
+    }
+    (fetches, plan)
+}
+
+/// The (sorted) files listed from a directory, after some offset.
+#[derive(Debug)]
+struct CachedListing {
+    offset: Path,
+    files: Vec<(Path, FileMeta)>,
+    listed_at: Instant,
+}
+
+/// Listings of directories cached by [`ObjectStoreStorageHandler::list_from`], which are
+/// discarded once older than the TTL.
+#[derive(Debug)]
+struct ListingCache {
+    ttl: Duration,
+    listings: Mutex<HashMap<Path, CachedListing>>,
+}
+
+impl ListingCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            listings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Returns the cached files of `prefix` after `offset`, if the cache covers `offset`
+    fn get(&self, prefix: &Path, offset: &Path) -> Vec<(Path, FileMeta)> {
+        let listings = self.listings.lock().unwrap_or_else(|e| e.into_inner());
+        match listings.get(prefix) {
+            Some(listing)
+                if listing.listed_at.elapsed() < self.ttl && listing.offset <= *offset =>
+            {
+                let start = listing.files.partition_point(|(path, _)| path <= offset);
+                listing.files[start..].to_vec()
+            }
+            _ => vec![],
+        }
+    }
+
+    // Adds `files` (listed after `offset`) to the cached listing of `prefix`, or replaces the
+    // cached listing if it has expired or doesn't cover `offset`
+    fn extend(&self, prefix: Path, offset: Path, files: &[(Path, FileMeta)]) {
+        let mut listings = self.listings.lock().unwrap_or_else(|e| e.into_inner());
+        listings.retain(|_, listing| listing.listed_at.elapsed() < self.ttl);
+        match listings.get_mut(&prefix) {
+            Some(listing) if listing.offset <= offset => {
+                // skip files another listing already added concurrently
+                let last = listing.files.last().map(|(path, _)| path.clone());
+                let new_files = files
+                    .iter()
+                    .filter(|(path, _)| last.as_ref().is_none_or(|last| path > last));
+                listing.files.extend(new_files.cloned());
+            }
+            _ => {
+                let listing = CachedListing {
+                    offset,
+                    files: files.to_vec(),
+                    listed_at: Instant::now(),
+                };
+                listings.insert(prefix, listing);
+            }
+        }
+    }
 }
 
 impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
@@ -80,31 +301,28 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
         // So we just need to know if we're local and then if so, we sort the returned file list
         let has_ordered_listing = path.scheme() != "file";
 
-        // This channel will become the iterator
-        let (sender, receiver) = std::sync::mpsc::sync_channel(4_000);
+        if let Some(cache) = &self.listing_cache {
+            let files = self.list_from_cache(cache, path, prefix, offset, has_ordered_listing)?;
+            return Ok(Box::new(files.into_iter().map(Ok)));
+        }
+
+        // This channel will become the iterator. The listing only runs ahead of the consumer by
+        // the channel's buffer, so listing pages are fetched lazily, and the listing stops early
+        // once the iterator is dropped (e.g. after the last requested version).
+        let (mut sender, receiver) = futures::channel::mpsc::channel(LIST_BUFFER_SIZE);
         let url = path.clone();
         self.task_executor.spawn(async move {
             let mut stream = store.list_with_offset(Some(&prefix), &offset);
 
             while let Some(meta) = stream.next().await {
-                match meta {
-                    Ok(meta) => {
-                        let mut location = url.clone();
-                        location.set_path(&format!("/{}", meta.location.as_ref()));
-                        sender
-                            .send(Ok(FileMeta {
-                                location,
-                                last_modified: meta.last_modified.timestamp_millis(),
-                                size: meta.size,
-                            }))
-                            .ok();
-                    }
-                    Err(e) => {
-                        sender.send(Err(e.into())).ok();
-                    }
+                let meta = meta.map(|meta| file_meta(&url, &meta)).map_err(Into::into);
+                if sender.send(meta).await.is_err() {
+                    // the iterator was dropped
+                    break;
                 }
             }
         });
+        let receiver = futures::executor::block_on_stream(receiver);
 
         if !has_ordered_listing {
             // This FS doesn't return things in the order we require
@@ -121,12 +339,21 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
     /// This will return the data in the same order as the provided file slices.
     ///
     /// Multiple reads may occur in parallel, depending on the configured readahead.
-    /// See [`Self::with_readahead`].
+    /// See [`Self::with_readahead`]. Nearby byte ranges of the same file are fetched with a single
+    /// request, see [`Self::with_coalesce_gap`].
     fn read_files(
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
         let store = self.inner.clone();
+        let (fetches, plan) = coalesce_slices(files, self.coalesce_gap);
+        // the number of slices each fetch serves, to know when its data is no longer needed
+        let mut remaining = vec![0; fetches.len()];
+        for (fetch_index, _) in &plan {
+            remaining[*fetch_index] += 1;
+        }
+        let mut plan = plan.into_iter().peekable();
+        let mut fetched: Vec<DeltaResult<Bytes>> = Vec::with_capacity(fetches.len());
 
         // This channel will become the output iterator.
         // Because there will already be buffering in the stream, we set the
@@ -134,7 +361,7 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
         let (sender, receiver) = std::sync::mpsc::sync_channel(0);
 
         self.task_executor.spawn(
-            futures::stream::iter(files)
+            futures::stream::iter(fetches)
                 .map(move |(url, range)| {
                     let store = store.clone();
                     async move {
@@ -168,13 +395,61 @@ impl<E: TaskExecutor> StorageHandler for ObjectStoreStorageHandler<E> {
                 // within a synchronous method.
                 .buffered(self.readahead)
                 .for_each(move |res| {
-                    sender.send(res).ok();
+                    fetched.push(res);
+                    // return all slices (in order) whose fetches completed
+                    while let Some((fetch_index, range)) =
+                        plan.next_if(|(fetch_index, _)| *fetch_index < fetched.len())
+                    {
+                        remaining[fetch_index] -= 1;
+                        let data = if remaining[fetch_index] == 0 {
+                            std::mem::replace(&mut fetched[fetch_index], Ok(Bytes::new()))
+                        } else {
+                            match &fetched[fetch_index] {
+                                Ok(data) => Ok(data.clone()),
+                                Err(e) => Err(Error::generic(e.to_string())),
+                            }
+                        };
+                        let data = data.and_then(|data| match range {
+                            Some(range) if range.end > data.len() => Err(Error::generic(
+                                "Fetched less data than the requested byte range",
+                            )),
+                            Some(range) => Ok(data.slice(range)),
+                            None => Ok(data),
+                        });
+                        sender.send(data).ok();
+                    }
                     futures::future::ready(())
                 }),
         );
 
         Ok(Box::new(receiver.into_iter()))
     }
+
+    /// Delete the given files, up to `readahead` of them in parallel. Missing files are ignored.
+    fn delete(&self, files: Vec<Url>) -> DeltaResult<()> {
+        let store = self.inner.clone();
+        let paths: Vec<Path> = files
+            .iter()
+            .map(|url| Path::from_url_path(url.path()))
+            .try_collect()?;
+        let readahead = self.readahead;
+        self.task_executor.block_on(async move {
+            futures::stream::iter(paths)
+                .map(|path| {
+                    let store = store.clone();
+                    async move {
+                        match store.delete(&path).await {
+                            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+                            Err(e) => Err(Error::from(e)),
+                        }
+                    }
+                })
+                .buffer_unordered(readahead)
+                .try_collect::<Vec<_>>()
+                .await
+        })?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -189,6 +464,9 @@ mod tests {
     use test_utils::delta_path_for_version;
 
     use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::metrics::{
+        IoEvent, IoMetricsObjectStore, IoMetricsObserver, IoOperation,
+    };
     use crate::engine::default::DefaultEngine;
     use crate::utils::current_time_duration;
     use crate::Engine as _;
@@ -238,6 +516,104 @@ mod tests {
         assert_eq!(data[2], Bytes::from("el-da"));
     }
 
+    #[test]
+    fn test_coalesce_slices() {
+        let a = Url::parse("memory:///a").unwrap();
+        let b = Url::parse("memory:///b").unwrap();
+        let slices = vec![
+            (a.clone(), Some(100..110)),
+            (b.clone(), Some(0..10)),
+            (a.clone(), Some(0..10)),
+            (a.clone(), None),
+            (a.clone(), Some(15..20)),
+            (b.clone(), Some(50..60)),
+        ];
+        let (fetches, plan) = coalesce_slices(slices.clone(), 5);
+        assert_eq!(
+            fetches,
+            vec![
+                (a.clone(), Some(100..110)),
+                (b.clone(), Some(0..10)),
+                (a.clone(), Some(0..20)),
+                (a.clone(), None),
+                (b.clone(), Some(50..60)),
+            ]
+        );
+        assert_eq!(
+            plan,
+            vec![
+                (0, None),
+                (1, None),
+                (2, Some(0..10)),
+                (3, None),
+                (2, Some(15..20)),
+                (4, None),
+            ]
+        );
+
+        // a large enough gap merges all ranges of a file
+        let (fetches, plan) = coalesce_slices(slices, 1000);
+        assert_eq!(
+            fetches,
+            vec![
+                (a.clone(), Some(0..110)),
+                (b.clone(), Some(0..60)),
+                (a.clone(), None),
+            ]
+        );
+        assert_eq!(
+            plan,
+            vec![
+                (0, Some(100..110)),
+                (1, Some(0..10)),
+                (0, Some(0..10)),
+                (2, None),
+                (0, Some(15..20)),
+                (1, Some(50..60)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_files_coalesces_ranges() {
+        #[derive(Debug, Default)]
+        struct CountGets(std::sync::atomic::AtomicUsize);
+        impl IoMetricsObserver for CountGets {
+            fn observe(&self, event: &IoEvent) {
+                if event.operation == IoOperation::Get {
+                    self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+        }
+
+        let store = Arc::new(InMemory::new());
+        let data = Bytes::from("0123456789abcdefghij");
+        store.put(&Path::from("a"), data.into()).await.unwrap();
+        let gets = Arc::new(CountGets::default());
+        let store = Arc::new(IoMetricsObjectStore::new(store, gets.clone()));
+        let url = Url::parse("memory:///a").unwrap();
+        let slices = vec![
+            (url.clone(), Some(12..15)),
+            (url.clone(), Some(0..3)),
+            (url.clone(), Some(5..6)),
+        ];
+
+        for (coalesce_gap, expected_gets) in [(0, 3), (2, 2), (10, 1)] {
+            let executor = Arc::new(TokioBackgroundExecutor::new());
+            let storage = ObjectStoreStorageHandler::new(store.clone(), executor)
+                .with_coalesce_gap(coalesce_gap);
+            gets.0.store(0, std::sync::atomic::Ordering::Relaxed);
+            let data: Vec<Bytes> = storage
+                .read_files(slices.clone())
+                .unwrap()
+                .try_collect()
+                .unwrap();
+            assert_eq!(data, vec!["cde", "012", "5"]);
+            let num_gets = gets.0.load(std::sync::atomic::Ordering::Relaxed);
+            assert_eq!(num_gets, expected_gets, "coalesce gap {coalesce_gap}");
+        }
+    }
+
     #[tokio::test]
     async fn test_file_meta_is_correct() {
         let store = Arc::new(InMemory::new());
@@ -300,4 +676,46 @@ mod tests {
         }
         assert_eq!(len, 10, "list_from should have returned 10 files");
     }
+
+    #[tokio::test]
+    async fn test_list_from_with_cache() {
+        let store = Arc::new(InMemory::new());
+        for version in 0..3 {
+            let name = delta_path_for_version(version, "json");
+            store.put(&name, Bytes::new().into()).await.unwrap();
+        }
+        let log_root = Url::parse("memory:///_delta_log/").unwrap();
+        let list_versions = |storage: &ObjectStoreStorageHandler<_>, version: u64| {
+            let start_from = log_root.join(&format!("{version:020}")).unwrap();
+            storage
+                .list_from(&start_from)
+                .unwrap()
+                .map(|meta| {
+                    let meta = meta.unwrap();
+                    let name = meta.location.path_segments().unwrap().next_back().unwrap();
+                    name[..20].parse::<u64>().unwrap()
+                })
+                .collect_vec()
+        };
+
+        let executor = Arc::new(TokioBackgroundExecutor::new());
+        let storage = ObjectStoreStorageHandler::new(store.clone(), executor)
+            .with_listing_cache_ttl(Duration::from_secs(3600));
+        assert_eq!(list_versions(&storage, 0), vec![0, 1, 2]);
+
+        // new files after the cached ones are listed, but deleted files are still returned from
+        // the cache
+        let name = delta_path_for_version(3, "json");
+        store.put(&name, Bytes::new().into()).await.unwrap();
+        store
+            .delete(&delta_path_for_version(1, "json"))
+            .await
+            .unwrap();
+        assert_eq!(list_versions(&storage, 0), vec![0, 1, 2, 3]);
+        assert_eq!(list_versions(&storage, 2), vec![2, 3]);
+
+        // expired listings are discarded
+        let storage = storage.with_listing_cache_ttl(Duration::ZERO);
+        assert_eq!(list_versions(&storage, 0), vec![0, 2, 3]);
+    }
 }
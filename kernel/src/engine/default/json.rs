@@ -1,15 +1,16 @@
 //! Default Json handler implementation
 
-use std::io::BufReader;
+use std::io::{BufRead, BufReader};
 use std::ops::Range;
 use std::sync::{mpsc, Arc};
 use std::task::Poll;
 
 use crate::arrow::datatypes::{Schema as ArrowSchema, SchemaRef as ArrowSchemaRef};
+use crate::arrow::json::reader::Decoder;
 use crate::arrow::json::ReaderBuilder;
 use crate::arrow::record_batch::RecordBatch;
 use bytes::{Buf, Bytes};
-use futures::stream::{self, BoxStream};
+use futures::stream::{self, BoxStream, Stream};
 use futures::{ready, StreamExt, TryStreamExt};
 use object_store::path::Path;
 use object_store::{self, DynObjectStore, GetResultPayload, PutMode};
@@ -17,6 +18,8 @@ use tracing::warn;
 use url::Url;
 
 use super::executor::TaskExecutor;
+use super::log_store::put_if_absent_error;
+use crate::async_engine::{self, FileDataReadResultStream};
 use crate::engine::arrow_conversion::TryFromKernel as _;
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::parse_json as arrow_parse_json;
@@ -42,6 +45,21 @@ pub struct DefaultJsonHandler<E: TaskExecutor> {
     /// Limit the number of rows per batch. That is, for batch_size = N, then each RecordBatch
     /// yielded by the stream will have at most N rows.
     batch_size: usize,
+    /// Approximate limit on the number of bytes of JSON decoded into each RecordBatch.
+    memory_budget: Option<usize>,
+}
+
+// Not derived, because that would needlessly require `E: Clone`
+impl<E: TaskExecutor> Clone for DefaultJsonHandler<E> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            task_executor: self.task_executor.clone(),
+            buffer_size: self.buffer_size,
+            batch_size: self.batch_size,
+            memory_budget: self.memory_budget,
+        }
+    }
 }
 
 impl<E: TaskExecutor> DefaultJsonHandler<E> {
@@ -51,6 +69,7 @@ impl<E: TaskExecutor> DefaultJsonHandler<E> {
             task_executor,
             buffer_size: DEFAULT_BUFFER_SIZE,
             batch_size: DEFAULT_BATCH_SIZE,
+            memory_budget: None,
         }
     }
 
@@ -59,8 +78,10 @@ impl<E: TaskExecutor> DefaultJsonHandler<E> {
     ///
     /// Defaults to 1000.
     ///
-    /// Memory constraints can be imposed by constraining the buffer size and batch size. Note that
-    /// overall memory usage is proportional to the product of these two values.
+    /// Files are stream-decoded as their bytes arrive from the object store, so memory usage does
+    /// not grow with the size of the files. Instead, memory constraints can be imposed by
+    /// constraining the buffer size and batch size (or memory budget). Note that overall memory
+    /// usage is proportional to the product of these two values.
     /// 1. Batch size governs the size of RecordBatches yielded in each iteration of the stream
     /// 2. Buffer size governs the number of concurrent tasks (which equals the size of the buffer
     pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
@@ -81,6 +102,66 @@ impl<E: TaskExecutor> DefaultJsonHandler<E> {
         self.batch_size = batch_size;
         self
     }
+
+    /// Approximate limit on the size of each RecordBatch yielded by [Self::read_json_files()],
+    /// measured as the number of bytes of JSON decoded into it. Once the budget is used up, the
+    /// current record is finished and the batch is yielded even if it has fewer than batch size
+    /// rows. Each batch has at least one row.
+    ///
+    /// By default there is no memory budget, and only the batch size limits batches.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = Some(memory_budget);
+        self
+    }
+}
+
+impl<E: TaskExecutor> DefaultJsonHandler<E> {
+    /// A stream of the batches of `files`, which reads the files as it is polled.
+    fn json_file_stream(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        let schema = Arc::new(ArrowSchema::try_from_kernel(physical_schema.as_ref())?);
+        let file_opener = JsonOpener::new(self.batch_size, schema.clone(), self.store.clone())
+            .with_memory_budget(self.memory_budget);
+        let file_opener = Arc::new(file_opener);
+
+        // an iterator of futures that open each file
+        let files = files.to_vec();
+        let file_futures = files.into_iter().map(move |file| {
+            let file_opener = file_opener.clone();
+            async move { file_opener.open(file, None).await }
+        });
+
+        // create a stream from that iterator which buffers up to `buffer_size` futures at a time
+        let stream = stream::iter(file_futures)
+            .buffered(self.buffer_size)
+            .try_flatten()
+            .map_ok(|record_batch| -> Box<dyn EngineData> {
+                Box::new(ArrowEngineData::new(record_batch))
+            });
+        Ok(stream.boxed())
+    }
+}
+
+impl<E: TaskExecutor> async_engine::AsyncJsonHandler for DefaultJsonHandler<E> {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        arrow_parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        _predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        self.json_file_stream(files, physical_schema)
+    }
 }
 
 impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
@@ -102,29 +183,14 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
             return Ok(Box::new(std::iter::empty()));
         }
 
-        let schema = Arc::new(ArrowSchema::try_from_kernel(physical_schema.as_ref())?);
-        let file_opener = JsonOpener::new(self.batch_size, schema.clone(), self.store.clone());
-
+        let mut stream = self.json_file_stream(files, physical_schema)?;
         let (tx, rx) = mpsc::sync_channel(self.buffer_size);
-        let files = files.to_vec();
-        let buffer_size = self.buffer_size;
-
         self.task_executor.spawn(async move {
-            // an iterator of futures that open each file
-            let file_futures = files.into_iter().map(|file| file_opener.open(file, None));
-
-            // create a stream from that iterator which buffers up to `buffer_size` futures at a time
-            let mut stream = stream::iter(file_futures)
-                .buffered(buffer_size)
-                .try_flatten()
-                .map_ok(|record_batch| -> Box<dyn EngineData> {
-                    Box::new(ArrowEngineData::new(record_batch))
-                });
-
             // send each record batch over the channel
             while let Some(item) = stream.next().await {
                 if tx.send(item).is_err() {
                     warn!("read_json receiver end of channel dropped before sending completed");
+                    break;
                 }
             }
         });
@@ -151,9 +217,12 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
         let path_str = path.to_string();
         self.task_executor
             .block_on(async move { store.put_opts(&path, buffer.into(), put_mode.into()).await })
-            .map_err(|e| match e {
-                object_store::Error::AlreadyExists { .. } => Error::FileAlreadyExists(path_str),
-                e => e.into(),
+            .map_err(|e| {
+                if overwrite {
+                    e.into()
+                } else {
+                    put_if_absent_error(e, path_str)
+                }
             })?;
         Ok(())
     }
@@ -163,6 +232,7 @@ impl<E: TaskExecutor> JsonHandler for DefaultJsonHandler<E> {
 #[allow(missing_debug_implementations)]
 pub struct JsonOpener {
     batch_size: usize,
+    memory_budget: Option<usize>,
     projected_schema: ArrowSchemaRef,
     object_store: Arc<DynObjectStore>,
 }
@@ -176,10 +246,70 @@ impl JsonOpener {
     ) -> Self {
         Self {
             batch_size,
+            memory_budget: None,
             projected_schema,
             object_store,
         }
     }
+
+    /// Yield a batch once (approximately) this many bytes of JSON have been decoded into it, even
+    /// if it has fewer than `batch_size` rows.
+    pub fn with_memory_budget(mut self, memory_budget: Option<usize>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    fn decoder(&self) -> DeltaResult<BudgetedDecoder> {
+        let decoder = ReaderBuilder::new(self.projected_schema.clone())
+            .with_batch_size(self.batch_size)
+            .build_decoder()?;
+        Ok(BudgetedDecoder {
+            decoder,
+            batch_size: self.batch_size,
+            memory_budget: self.memory_budget,
+            decoded_bytes: 0,
+        })
+    }
+}
+
+/// Wraps a JSON [`Decoder`] to also limit the number of bytes decoded into each batch.
+struct BudgetedDecoder {
+    decoder: Decoder,
+    batch_size: usize,
+    memory_budget: Option<usize>,
+    /// The number of bytes decoded since the last flush
+    decoded_bytes: usize,
+}
+
+impl BudgetedDecoder {
+    /// Decode (a prefix of) `buf`, returning the number of bytes read. See [`Decoder::decode`].
+    fn decode(&mut self, buf: &[u8]) -> DeltaResult<usize> {
+        let limit = match self.memory_budget {
+            // Over budget: only finish the current record (i.e. line), so it can be flushed
+            Some(budget) if self.decoded_bytes >= budget => buf
+                .iter()
+                .position(|b| *b == b'\n')
+                .map_or(buf.len(), |newline| newline + 1),
+            Some(budget) => buf.len().min(budget - self.decoded_bytes),
+            None => buf.len(),
+        };
+        let decoded = self.decoder.decode(&buf[..limit])?;
+        self.decoded_bytes += decoded;
+        Ok(decoded)
+    }
+
+    /// Whether the batch is complete, because it is full or over budget.
+    fn is_full(&self) -> bool {
+        let over_budget = self
+            .memory_budget
+            .is_some_and(|budget| self.decoded_bytes >= budget && !self.decoder.is_empty());
+        !self.decoder.has_partial_record() && (over_budget || self.decoder.len() >= self.batch_size)
+    }
+
+    fn flush(&mut self) -> DeltaResult<Option<RecordBatch>> {
+        self.decoded_bytes = 0;
+        Ok(self.decoder.flush()?)
+    }
 }
 
 impl JsonOpener {
@@ -189,59 +319,88 @@ impl JsonOpener {
         _: Option<Range<i64>>,
     ) -> DeltaResult<BoxStream<'static, DeltaResult<RecordBatch>>> {
         let store = self.object_store.clone();
-        let schema = self.projected_schema.clone();
-        let batch_size = self.batch_size;
+        let mut decoder = self.decoder()?;
 
         let path = Path::from_url_path(file_meta.location.path())?;
-        match store.get(&path).await?.payload {
+        let stream = match store.get(&path).await?.payload {
             GetResultPayload::File(file, _) => {
-                let reader = ReaderBuilder::new(schema)
-                    .with_batch_size(batch_size)
-                    .build(BufReader::new(file))?;
-                Ok(futures::stream::iter(reader).map_err(Error::from).boxed())
-            }
-            GetResultPayload::Stream(s) => {
-                let mut decoder = ReaderBuilder::new(schema)
-                    .with_batch_size(batch_size)
-                    .build_decoder()?;
-
-                let mut input = s.map_err(Error::from);
-                let mut buffered = Bytes::new();
-
-                let s = futures::stream::poll_fn(move |cx| {
+                let mut reader = BufReader::new(file);
+                let mut next = move || {
                     loop {
-                        if buffered.is_empty() {
-                            buffered = match ready!(input.poll_next_unpin(cx)) {
-                                Some(Ok(b)) => b,
-                                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
-                                None => break,
-                            };
+                        let buf = reader.fill_buf()?;
+                        if buf.is_empty() {
+                            break;
                         }
-                        let read = buffered.len();
-
-                        // NB (from Decoder::decode docs):
-                        // Read JSON objects from `buf` (param), returning the number of bytes read
-                        //
-                        // This method returns once `batch_size` objects have been parsed since the
-                        // last call to [`Self::flush`], or `buf` is exhausted. Any remaining bytes
-                        // should be included in the next call to [`Self::decode`]
-                        let decoded = match decoder.decode(buffered.as_ref()) {
-                            Ok(decoded) => decoded,
-                            Err(e) => return Poll::Ready(Some(Err(e.into()))),
-                        };
-
-                        buffered.advance(decoded);
-                        if decoded != read {
+                        let decoded = decoder.decode(buf)?;
+                        reader.consume(decoded);
+                        if decoder.is_full() {
                             break;
                         }
                     }
+                    decoder.flush()
+                };
+                futures::stream::iter(std::iter::from_fn(move || next().transpose())).boxed()
+            }
+            GetResultPayload::Stream(s) => decode_stream(s.map_err(Error::from), decoder),
+        };
+        Ok(end_after_error(stream))
+    }
+}
 
-                    Poll::Ready(decoder.flush().map_err(Error::from).transpose())
-                });
-                Ok(s.map_err(Error::from).boxed())
+// The decoder cannot recover from an error, e.g. flushing a truncated record fails again on every
+// attempt, so the stream of a file ends after its first error.
+fn end_after_error(
+    stream: BoxStream<'static, DeltaResult<RecordBatch>>,
+) -> BoxStream<'static, DeltaResult<RecordBatch>> {
+    stream
+        .scan(false, |failed, item| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            *failed = item.is_err();
+            futures::future::ready(Some(item))
+        })
+        .boxed()
+}
+
+// Incrementally decode NDJSON from a stream of bytes (e.g. the body of an object store GET
+// request), yielding each batch as soon as it is complete. Records may span chunks of the stream,
+// and only the current chunk and batch are held in memory.
+fn decode_stream(
+    mut input: impl Stream<Item = DeltaResult<Bytes>> + Unpin + Send + 'static,
+    mut decoder: BudgetedDecoder,
+) -> BoxStream<'static, DeltaResult<RecordBatch>> {
+    let mut buffered = Bytes::new();
+    futures::stream::poll_fn(move |cx| {
+        loop {
+            if buffered.is_empty() {
+                buffered = match ready!(input.poll_next_unpin(cx)) {
+                    Some(Ok(b)) => b,
+                    Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    None => break,
+                };
+            }
+
+            // NB (from Decoder::decode docs):
+            // Read JSON objects from `buf` (param), returning the number of bytes read
+            //
+            // This method returns once `batch_size` objects have been parsed since the
+            // last call to [`Self::flush`], or `buf` is exhausted. Any remaining bytes
+            // should be included in the next call to [`Self::decode`]
+            let decoded = match decoder.decode(buffered.as_ref()) {
+                Ok(decoded) => decoded,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            };
+
+            buffered.advance(decoded);
+            if decoder.is_full() {
+                break;
             }
         }
-    }
+
+        Poll::Ready(decoder.flush().transpose())
+    })
+    .boxed()
 }
 
 #[cfg(test)]
@@ -253,7 +412,7 @@ mod tests {
 
     use crate::actions::get_log_schema;
     use crate::arrow::array::{AsArray, Int32Array, RecordBatch, StringArray};
-    use crate::arrow::datatypes::{DataType, Field, Schema as ArrowSchema};
+    use crate::arrow::datatypes::{DataType, Field, Int32Type, Schema as ArrowSchema};
     use crate::engine::arrow_data::ArrowEngineData;
     use crate::engine::default::executor::tokio::{
         TokioBackgroundExecutor, TokioMultiThreadExecutor,
@@ -562,6 +721,125 @@ mod tests {
         assert_eq!(data.len(), 2);
         assert_eq!(data[0].num_rows(), 2);
         assert_eq!(data[1].num_rows(), 2);
+
+        // a tiny memory budget yields one row (i.e. action) per batch
+        let handler = handler.with_memory_budget(1);
+        let data: Vec<RecordBatch> = handler
+            .read_json_files(files, get_log_schema().clone(), None)
+            .unwrap()
+            .map_ok(into_record_batch)
+            .try_collect()
+            .unwrap();
+
+        assert_eq!(data.len(), 4);
+        assert!(data.iter().all(|batch| batch.num_rows() == 1));
+    }
+
+    #[tokio::test]
+    async fn test_read_json_files_with_memory_budget() {
+        let store = Arc::new(InMemory::new());
+        let ndjson = (1..=5).map(|i| format!("{{\"a\":{i}}}\n")).join("");
+        store
+            .put(&Path::from("test/data.json"), ndjson.clone().into())
+            .await
+            .unwrap();
+        let location = Url::parse("memory:///test/data.json").unwrap();
+        let files = &[FileMeta::new(location, 0, ndjson.len() as u64)];
+        let schema = Arc::new(
+            Schema::try_new(vec![StructField::nullable("a", DeltaDataType::INTEGER)]).unwrap(),
+        );
+
+        // Each 8 byte record uses up most of the budget, so records are read two at a time
+        let handler = DefaultJsonHandler::new(store, Arc::new(TokioBackgroundExecutor::new()))
+            .with_memory_budget(10);
+        let batch_sizes: Vec<usize> = handler
+            .read_json_files(files, schema.clone(), None)
+            .unwrap()
+            .map_ok(|data| into_record_batch(data).num_rows())
+            .try_collect()
+            .unwrap();
+        assert_eq!(batch_sizes, vec![2, 2, 1]);
+
+        // The batch size still applies
+        let batch_sizes: Vec<usize> = handler
+            .with_batch_size(1)
+            .read_json_files(files, schema, None)
+            .unwrap()
+            .map_ok(|data| into_record_batch(data).num_rows())
+            .try_collect()
+            .unwrap();
+        assert_eq!(batch_sizes, vec![1, 1, 1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_read_truncated_json_file() {
+        let store = Arc::new(InMemory::new());
+        let ndjson = "{\"a\":1}\n{\"a\":";
+        store
+            .put(&Path::from("test/data.json"), ndjson.into())
+            .await
+            .unwrap();
+        let location = Url::parse("memory:///test/data.json").unwrap();
+        let files = &[FileMeta::new(location, 0, ndjson.len() as u64)];
+        let schema = Arc::new(
+            Schema::try_new(vec![StructField::nullable("a", DeltaDataType::INTEGER)]).unwrap(),
+        );
+
+        // the truncated record fails the file once, instead of on every attempt to flush it
+        let handler = DefaultJsonHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let results: Vec<_> = handler
+            .read_json_files(files, schema, None)
+            .unwrap()
+            .collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decode_stream_across_chunks() {
+        let ndjson = (1..=5).map(|i| format!("{{\"a\":{i}}}\n")).join("");
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "a",
+            DataType::Int32,
+            true,
+        )]));
+
+        // Feed the bytes a few at a time, so that records span multiple chunks
+        for chunk_size in [1, 3, 64] {
+            let chunks: Vec<DeltaResult<Bytes>> = ndjson
+                .as_bytes()
+                .chunks(chunk_size)
+                .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+                .collect();
+            let decoder = JsonOpener::new(2, schema.clone(), Arc::new(InMemory::new()))
+                .decoder()
+                .unwrap();
+            let batches: Vec<RecordBatch> = decode_stream(stream::iter(chunks), decoder)
+                .try_collect()
+                .await
+                .unwrap();
+
+            let batch_sizes = batches.iter().map(|b| b.num_rows()).collect_vec();
+            assert_eq!(batch_sizes, vec![2, 2, 1]);
+            let values = batches
+                .iter()
+                .flat_map(|b| b.column(0).as_primitive::<Int32Type>().values().to_vec())
+                .collect_vec();
+            assert_eq!(values, vec![1, 2, 3, 4, 5]);
+        }
+
+        // Errors from the byte stream are passed through
+        let chunks = vec![
+            Ok(Bytes::from("{\"a\":1}\n")),
+            Err(Error::generic("connection reset")),
+        ];
+        let decoder = JsonOpener::new(2, schema, Arc::new(InMemory::new()))
+            .decoder()
+            .unwrap();
+        let result: DeltaResult<Vec<RecordBatch>> = decode_stream(stream::iter(chunks), decoder)
+            .try_collect()
+            .await;
+        assert!(result.is_err_and(|e| e.to_string().contains("connection reset")));
     }
 
     #[tokio::test]
@@ -0,0 +1,241 @@
+//! Default [`LogStore`] implementations, which write commits "put-if-absent" to an object store.
+//!
+//! - [`ConditionalPutLogStore`] uses conditional writes, which S3 (`If-None-Match: *`), Azure
+//!   (`If-None-Match: *`) and GCS (`ifGenerationMatch=0`) support natively. Local file systems
+//!   emulate them by hard linking a fully written staging file, which fails if the target exists.
+//! - [`RenameLogStore`] writes the commit to a temporary file and atomically renames it, which
+//!   suits (local) file systems.
+//!
+//! Neither ever falls back to a non-atomic copy or overwrite: if the commit file already exists,
+//! the write fails with [`Error::FileAlreadyExists`], and if the store can't write atomically
+//! (e.g. S3 with `aws_conditional_put` set to `disabled`), it fails with [`Error::Unsupported`].
+//!
+//! Use [`log_store_for_url`] to get the appropriate log store for a table.
+
+use std::sync::Arc;
+
+use object_store::path::Path;
+use object_store::{DynObjectStore, PutMode};
+use url::Url;
+
+use super::executor::TaskExecutor;
+use crate::engine::arrow_utils::to_json_bytes;
+use crate::{DeltaResult, EngineData, Error, LogStore};
+
+/// Get the [`LogStore`] for the table at `table_root`: a [`RenameLogStore`] for local file systems
+/// (`file://` URLs), and a [`ConditionalPutLogStore`] otherwise.
+pub fn log_store_for_url<E: TaskExecutor>(
+    table_root: &Url,
+    store: Arc<DynObjectStore>,
+    task_executor: Arc<E>,
+) -> Arc<dyn LogStore> {
+    match table_root.scheme() {
+        "file" => Arc::new(RenameLogStore::new(store, task_executor)),
+        _ => Arc::new(ConditionalPutLogStore::new(store, task_executor)),
+    }
+}
+
+/// Convert the error of an atomic (put-if-absent) write of the file at `path` into a kernel error.
+/// Stores report an existing file either as "already exists" or as a failed precondition, and
+/// both become [`Error::FileAlreadyExists`].
+pub(crate) fn put_if_absent_error(error: object_store::Error, path: String) -> Error {
+    match error {
+        object_store::Error::AlreadyExists { .. } | object_store::Error::Precondition { .. } => {
+            Error::FileAlreadyExists(path)
+        }
+        object_store::Error::NotImplemented | object_store::Error::NotSupported { .. } => {
+            Error::unsupported(format!(
+                "Cannot write {path}: the object store does not support atomic put-if-absent \
+                 writes (for S3, set `aws_conditional_put` to `etag`)"
+            ))
+        }
+        e => e.into(),
+    }
+}
+
+/// A [`LogStore`] that writes commits with a conditional put, which only succeeds if the commit
+/// file doesn't exist yet.
+#[derive(Debug)]
+pub struct ConditionalPutLogStore<E: TaskExecutor> {
+    store: Arc<DynObjectStore>,
+    task_executor: Arc<E>,
+}
+
+impl<E: TaskExecutor> ConditionalPutLogStore<E> {
+    pub fn new(store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
+        Self {
+            store,
+            task_executor,
+        }
+    }
+}
+
+impl<E: TaskExecutor> LogStore for ConditionalPutLogStore<E> {
+    fn write_commit(
+        &self,
+        commit_path: &Url,
+        actions: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+    ) -> DeltaResult<()> {
+        let buffer = to_json_bytes(actions)?;
+        let store = self.store.clone(); // cheap Arc
+        let path = Path::from_url_path(commit_path.path())?;
+        let path_str = path.to_string();
+        self.task_executor
+            .block_on(async move {
+                store
+                    .put_opts(&path, buffer.into(), PutMode::Create.into())
+                    .await
+            })
+            .map_err(|e| put_if_absent_error(e, path_str))?;
+        Ok(())
+    }
+}
+
+/// A [`LogStore`] that writes commits to a temporary file in the Delta log first, and then
+/// renames it to the commit file unless that already exists.
+///
+/// The temporary files are hidden (their names start with `.`), so they are never mistaken for
+/// log files. If the rename fails, the temporary file is deleted on a best-effort basis.
+#[derive(Debug)]
+pub struct RenameLogStore<E: TaskExecutor> {
+    store: Arc<DynObjectStore>,
+    task_executor: Arc<E>,
+}
+
+impl<E: TaskExecutor> RenameLogStore<E> {
+    pub fn new(store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
+        Self {
+            store,
+            task_executor,
+        }
+    }
+}
+
+impl<E: TaskExecutor> LogStore for RenameLogStore<E> {
+    fn write_commit(
+        &self,
+        commit_path: &Url,
+        actions: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+    ) -> DeltaResult<()> {
+        let buffer = to_json_bytes(actions)?;
+        let store = self.store.clone(); // cheap Arc
+        let path = Path::from_url_path(commit_path.path())?;
+        let path_str = path.to_string();
+        let file_name = path
+            .filename()
+            .ok_or_else(|| Error::generic(format!("Invalid commit path: {commit_path}")))?;
+        let temp_file_name = format!(".{file_name}.{}.tmp", uuid::Uuid::new_v4());
+        let temp_path = Path::from_iter(
+            path.parts()
+                .take(path.parts().count() - 1)
+                .chain(std::iter::once(temp_file_name.as_str().into())),
+        );
+        self.task_executor
+            .block_on(async move {
+                store.put(&temp_path, buffer.into()).await?;
+                let result = store.rename_if_not_exists(&temp_path, &path).await;
+                if result.is_err() {
+                    // don't leave the temporary file behind
+                    let _ = store.delete(&temp_path).await;
+                }
+                result
+            })
+            .map_err(|e| put_if_absent_error(e, path_str))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+    use object_store::local::LocalFileSystem;
+    use object_store::memory::InMemory;
+
+    use super::*;
+    use crate::arrow::array::StringArray;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::utils::test_utils::string_array_to_engine_data;
+
+    fn test_actions(
+        values: Vec<&'static str>,
+    ) -> Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send> {
+        let data = string_array_to_engine_data(StringArray::from(values));
+        Box::new(std::iter::once(Ok(data)))
+    }
+
+    async fn assert_put_if_absent(
+        log_store: &dyn LogStore,
+        store: &DynObjectStore,
+        commit_path: &Url,
+    ) -> DeltaResult<()> {
+        log_store.write_commit(commit_path, test_actions(vec!["first"]))?;
+
+        // the second commit of the same version fails and leaves the first commit unchanged
+        let result = log_store.write_commit(commit_path, test_actions(vec!["second"]));
+        assert!(
+            matches!(result, Err(Error::FileAlreadyExists(_))),
+            "Expected FileAlreadyExists error, got: {result:?}"
+        );
+        let path = Path::from_url_path(commit_path.path())?;
+        let content = store.get(&path).await?.bytes().await?;
+        assert_eq!(content.as_ref(), b"{\"a\":\"first\"}\n");
+
+        // no temporary files are left behind
+        let log_dir = Path::from_url_path(commit_path.join(".")?.path())?;
+        let files: Vec<_> = store.list(Some(&log_dir)).try_collect().await?;
+        assert_eq!(files.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_put_if_absent_error() {
+        let source = || Box::new(std::io::Error::other("conflict")) as _;
+        let path = || "_delta_log/00000000000000000001.json".to_string();
+        let already_exists = object_store::Error::AlreadyExists {
+            path: path(),
+            source: source(),
+        };
+        let precondition = object_store::Error::Precondition {
+            path: path(),
+            source: source(),
+        };
+        for error in [already_exists, precondition] {
+            let result = put_if_absent_error(error, path());
+            assert!(matches!(result, Error::FileAlreadyExists(p) if p == path()));
+        }
+
+        let result = put_if_absent_error(object_store::Error::NotImplemented, path());
+        assert!(matches!(result, Error::Unsupported(msg) if msg.contains("aws_conditional_put")));
+
+        let generic = object_store::Error::Generic {
+            store: "test",
+            source: source(),
+        };
+        let result = put_if_absent_error(generic, path());
+        assert!(matches!(
+            result,
+            Error::ObjectStore(object_store::Error::Generic { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_conditional_put_log_store() -> DeltaResult<()> {
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let log_store =
+            ConditionalPutLogStore::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let commit_path = Url::parse("memory:///test/_delta_log/00000000000000000001.json")?;
+        assert_put_if_absent(&log_store, store.as_ref(), &commit_path).await
+    }
+
+    #[tokio::test]
+    async fn test_rename_log_store() -> DeltaResult<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let store: Arc<DynObjectStore> = Arc::new(LocalFileSystem::new());
+        let log_store =
+            RenameLogStore::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let commit_path = Url::from_directory_path(dir.path())
+            .unwrap()
+            .join("_delta_log/00000000000000000001.json")?;
+        assert_put_if_absent(&log_store, store.as_ref(), &commit_path).await
+    }
+}
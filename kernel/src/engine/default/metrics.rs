@@ -0,0 +1,496 @@
+//! Reporting of the IO the default engine performs against its object store.
+//!
+//! An [`IoMetricsObserver`] receives an [`IoEvent`] for every object store request, tagged with the
+//! [`IoPurpose`] of the file it accesses, so that operators can e.g. attribute cloud storage costs
+//! to kernel activity. Install one with [`DefaultEngine::with_io_metrics_observer`], or wrap an
+//! object store in an [`IoMetricsObjectStore`] yourself.
+//!
+//! [`DefaultEngine::with_io_metrics_observer`]: super::DefaultEngine::with_io_metrics_observer
+
+use std::ops::Range;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    DynObjectStore, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+
+/// The kind of object store request an [`IoEvent`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IoOperation {
+    /// Read (a range of) a file.
+    Get,
+    /// Read the metadata of a file.
+    Head,
+    /// Write a file, either with a single request or as a multipart upload.
+    Put,
+    /// List the files under a prefix.
+    List,
+    /// Delete a file.
+    Delete,
+    /// Copy a file.
+    Copy,
+    /// Rename (move) a file.
+    Rename,
+}
+
+/// What the file accessed by a request is used for, derived from its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IoPurpose {
+    /// Commit files, `_last_checkpoint` and any other file in the Delta log (including listing
+    /// the Delta log).
+    Log,
+    /// Checkpoint files and their sidecars.
+    Checkpoint,
+    /// Data files (and change data files) of the table.
+    Data,
+    /// Deletion vector files.
+    DeletionVector,
+}
+
+impl IoPurpose {
+    /// Classifies the file (or listing prefix) at `location`.
+    pub fn of(location: &Path) -> Self {
+        let mut parts = location.parts().map(|part| part.as_ref().to_string());
+        if parts.any(|part| part == "_delta_log") {
+            let is_sidecar = parts.next().is_some_and(|part| part == "_sidecars");
+            let is_checkpoint = location
+                .filename()
+                .is_some_and(|name| name.contains(".checkpoint"));
+            return if is_sidecar || is_checkpoint {
+                Self::Checkpoint
+            } else {
+                Self::Log
+            };
+        }
+        match location.filename() {
+            Some(name) if name.starts_with("deletion_vector_") => Self::DeletionVector,
+            _ => Self::Data,
+        }
+    }
+}
+
+/// A single object store request, reported to an [`IoMetricsObserver`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoEvent {
+    /// The kind of request.
+    pub operation: IoOperation,
+    /// What the accessed file is used for.
+    pub purpose: IoPurpose,
+    /// The accessed file, or the listed prefix (the source of a copy or rename).
+    pub location: Path,
+    /// The number of bytes read (the size of the response body of a GET) or written. Zero for
+    /// other requests.
+    pub bytes: u64,
+    /// The time until the request completed. For reads, this is the time until the response
+    /// started streaming in, and for listings, the time until the listing was fully consumed (or
+    /// dropped).
+    pub latency: Duration,
+    /// Whether the request succeeded.
+    pub success: bool,
+}
+
+/// Receives an [`IoEvent`] for every object store request the default engine makes.
+///
+/// Events are reported from the threads that perform IO, so implementations should be cheap,
+/// e.g. update atomic counters or a metrics registry.
+pub trait IoMetricsObserver: Send + Sync + std::fmt::Debug {
+    fn observe(&self, event: &IoEvent);
+}
+
+/// An [`ObjectStore`] that reports every request it forwards to the wrapped store to an
+/// [`IoMetricsObserver`].
+#[derive(Debug)]
+pub struct IoMetricsObjectStore {
+    inner: Arc<DynObjectStore>,
+    observer: RwLock<Option<Arc<dyn IoMetricsObserver>>>,
+}
+
+impl IoMetricsObjectStore {
+    /// Report all requests to `inner` to `observer`.
+    pub fn new(inner: Arc<DynObjectStore>, observer: Arc<dyn IoMetricsObserver>) -> Self {
+        Self {
+            inner,
+            observer: RwLock::new(Some(observer)),
+        }
+    }
+
+    // Wraps a store without reporting any requests until an observer is set
+    pub(crate) fn unobserved(inner: Arc<DynObjectStore>) -> Self {
+        Self {
+            inner,
+            observer: RwLock::new(None),
+        }
+    }
+
+    pub(crate) fn set_observer(&self, observer: Arc<dyn IoMetricsObserver>) {
+        *self.observer.write().unwrap_or_else(|e| e.into_inner()) = Some(observer);
+    }
+
+    fn observer(&self) -> Option<Arc<dyn IoMetricsObserver>> {
+        self.observer
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn report<T>(
+        &self,
+        operation: IoOperation,
+        location: &Path,
+        start: Instant,
+        result: &Result<T>,
+        bytes: impl FnOnce(&T) -> u64,
+    ) {
+        if let Some(observer) = self.observer() {
+            observer.observe(&IoEvent {
+                operation,
+                purpose: IoPurpose::of(location),
+                location: location.clone(),
+                bytes: result.as_ref().map_or(0, bytes),
+                latency: start.elapsed(),
+                success: result.is_ok(),
+            });
+        }
+    }
+
+    // Reports a single LIST request once the listing `stream` is exhausted or dropped
+    fn observe_list(
+        &self,
+        prefix: Option<&Path>,
+        stream: BoxStream<'static, Result<ObjectMeta>>,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        let Some(observer) = self.observer() else {
+            return stream;
+        };
+        let mut guard = ListGuard {
+            observer,
+            location: prefix.cloned().unwrap_or_default(),
+            start: Instant::now(),
+            success: true,
+        };
+        stream
+            .inspect(move |result| {
+                // capture the whole guard (not just its `success` field), so that it is dropped
+                // together with the stream
+                let guard = &mut guard;
+                guard.success &= result.is_ok();
+            })
+            .boxed()
+    }
+}
+
+impl std::fmt::Display for IoMetricsObjectStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IoMetricsObjectStore({})", self.inner)
+    }
+}
+
+#[derive(Debug)]
+struct ListGuard {
+    observer: Arc<dyn IoMetricsObserver>,
+    location: Path,
+    start: Instant,
+    success: bool,
+}
+
+impl Drop for ListGuard {
+    fn drop(&mut self) {
+        self.observer.observe(&IoEvent {
+            operation: IoOperation::List,
+            purpose: IoPurpose::of(&self.location),
+            location: self.location.clone(),
+            bytes: 0,
+            latency: self.start.elapsed(),
+            success: self.success,
+        });
+    }
+}
+
+// Reports a single PUT request with the total size of all parts once the upload completes
+#[derive(Debug)]
+struct ObservedUpload {
+    inner: Box<dyn MultipartUpload>,
+    observer: Arc<dyn IoMetricsObserver>,
+    location: Path,
+    start: Instant,
+    bytes: u64,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for ObservedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.bytes += data.content_length() as u64;
+        self.inner.put_part(data)
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let result = self.inner.complete().await;
+        self.observer.observe(&IoEvent {
+            operation: IoOperation::Put,
+            purpose: IoPurpose::of(&self.location),
+            location: self.location.clone(),
+            bytes: self.bytes,
+            latency: self.start.elapsed(),
+            success: result.is_ok(),
+        });
+        result
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for IoMetricsObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let bytes = payload.content_length() as u64;
+        let start = Instant::now();
+        let result = self.inner.put_opts(location, payload, opts).await;
+        self.report(IoOperation::Put, location, start, &result, |_| bytes);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let start = Instant::now();
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        let Some(observer) = self.observer() else {
+            return Ok(upload);
+        };
+        Ok(Box::new(ObservedUpload {
+            inner: upload,
+            observer,
+            location: location.clone(),
+            start,
+            bytes: 0,
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let operation = if options.head {
+            IoOperation::Head
+        } else {
+            IoOperation::Get
+        };
+        let start = Instant::now();
+        let result = self.inner.get_opts(location, options).await;
+        self.report(operation, location, start, &result, |r| match operation {
+            IoOperation::Get => r.range.end - r.range.start,
+            _ => 0,
+        });
+        result
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<u64>) -> Result<Bytes> {
+        let start = Instant::now();
+        let result = self.inner.get_range(location, range).await;
+        self.report(IoOperation::Get, location, start, &result, |b| {
+            b.len() as u64
+        });
+        result
+    }
+
+    // Reported as a single GET, although the wrapped store may issue a request per (coalesced)
+    // range
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        let start = Instant::now();
+        let result = self.inner.get_ranges(location, ranges).await;
+        self.report(IoOperation::Get, location, start, &result, |b| {
+            b.iter().map(|b| b.len() as u64).sum()
+        });
+        result
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let start = Instant::now();
+        let result = self.inner.head(location).await;
+        self.report(IoOperation::Head, location, start, &result, |_| 0);
+        result
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.delete(location).await;
+        self.report(IoOperation::Delete, location, start, &result, |_| 0);
+        result
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.observe_list(prefix, self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.observe_list(prefix, self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let start = Instant::now();
+        let result = self.inner.list_with_delimiter(prefix).await;
+        let location = prefix.cloned().unwrap_or_default();
+        self.report(IoOperation::List, &location, start, &result, |_| 0);
+        result
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.copy(from, to).await;
+        self.report(IoOperation::Copy, from, start, &result, |_| 0);
+        result
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.rename(from, to).await;
+        self.report(IoOperation::Rename, from, start, &result, |_| 0);
+        result
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.copy_if_not_exists(from, to).await;
+        self.report(IoOperation::Copy, from, start, &result, |_| 0);
+        result
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.rename_if_not_exists(from, to).await;
+        self.report(IoOperation::Rename, from, start, &result, |_| 0);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver(Mutex<Vec<IoEvent>>);
+
+    impl IoMetricsObserver for RecordingObserver {
+        fn observe(&self, event: &IoEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    impl RecordingObserver {
+        fn take(&self) -> Vec<(IoOperation, IoPurpose, u64, bool)> {
+            let events = std::mem::take(&mut *self.0.lock().unwrap());
+            events
+                .into_iter()
+                .map(|e| (e.operation, e.purpose, e.bytes, e.success))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_io_purpose() {
+        let purpose = |path: &str| IoPurpose::of(&Path::from(path));
+        assert_eq!(
+            purpose("t/_delta_log/00000000000000000001.json"),
+            IoPurpose::Log
+        );
+        assert_eq!(purpose("t/_delta_log/_last_checkpoint"), IoPurpose::Log);
+        assert_eq!(purpose("t/_delta_log/"), IoPurpose::Log);
+        assert_eq!(
+            purpose("t/_delta_log/00000000000000000010.checkpoint.parquet"),
+            IoPurpose::Checkpoint
+        );
+        assert_eq!(
+            purpose("t/_delta_log/00000000000000000010.checkpoint.0000000001.0000000002.parquet"),
+            IoPurpose::Checkpoint
+        );
+        assert_eq!(
+            purpose("t/_delta_log/_sidecars/3a0d65cd-4056-49b8-937b-95f9e3ee90e5.parquet"),
+            IoPurpose::Checkpoint
+        );
+        assert_eq!(purpose("t/part-00000.snappy.parquet"), IoPurpose::Data);
+        assert_eq!(purpose("t/_change_data/cdc-00000.parquet"), IoPurpose::Data);
+        assert_eq!(
+            purpose("t/ab/deletion_vector_d2c639aa-8816-431a-aaf6-d3fe2512ff61.bin"),
+            IoPurpose::DeletionVector
+        );
+    }
+
+    #[tokio::test]
+    async fn test_io_metrics_object_store() {
+        let observer = Arc::new(RecordingObserver::default());
+        let store = IoMetricsObjectStore::new(Arc::new(InMemory::new()), observer.clone());
+        let commit = Path::from("t/_delta_log/00000000000000000000.json");
+        let data = Path::from("t/part-00000.parquet");
+
+        store.put(&commit, vec![0u8; 10].into()).await.unwrap();
+        store.put(&data, vec![0u8; 100].into()).await.unwrap();
+        store.get_range(&data, 10..30).await.unwrap();
+        store.get(&commit).await.unwrap();
+        store.head(&data).await.unwrap();
+        assert!(store.get(&Path::from("t/missing.parquet")).await.is_err());
+        assert_eq!(
+            observer.take(),
+            vec![
+                (IoOperation::Put, IoPurpose::Log, 10, true),
+                (IoOperation::Put, IoPurpose::Data, 100, true),
+                (IoOperation::Get, IoPurpose::Data, 20, true),
+                (IoOperation::Get, IoPurpose::Log, 10, true),
+                (IoOperation::Head, IoPurpose::Data, 0, true),
+                (IoOperation::Get, IoPurpose::Data, 0, false),
+            ]
+        );
+
+        // a listing is reported once it is consumed
+        let listing = store.list(Some(&Path::from("t/_delta_log")));
+        assert!(observer.take().is_empty());
+        assert_eq!(listing.count().await, 1);
+        assert_eq!(
+            observer.take(),
+            vec![(IoOperation::List, IoPurpose::Log, 0, true)]
+        );
+
+        let mut upload = store.put_multipart(&data).await.unwrap();
+        upload.put_part(vec![0u8; 5].into()).await.unwrap();
+        upload.put_part(vec![0u8; 7].into()).await.unwrap();
+        upload.complete().await.unwrap();
+        assert_eq!(
+            observer.take(),
+            vec![(IoOperation::Put, IoPurpose::Data, 12, true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unobserved_object_store() {
+        let store = IoMetricsObjectStore::unobserved(Arc::new(InMemory::new()));
+        let path = Path::from("t/part-00000.parquet");
+        store.put(&path, vec![0u8; 10].into()).await.unwrap();
+
+        let observer = Arc::new(RecordingObserver::default());
+        store.set_observer(observer.clone());
+        store.get(&path).await.unwrap();
+        assert_eq!(
+            observer.take(),
+            vec![(IoOperation::Get, IoPurpose::Data, 10, true)]
+        );
+    }
+}
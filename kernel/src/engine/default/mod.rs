@@ -9,46 +9,80 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use self::storage::parse_url_opts;
-use object_store::DynObjectStore;
+use self::storage::{
+    parse_url_opts, parse_url_opts_with_retry_policy, ObjectStoreRegistry, RetryPolicy,
+};
+use itertools::Itertools as _;
+use object_store::path::Path;
+use object_store::{DynObjectStore, ObjectStore as _};
+use roaring::RoaringTreemap;
 use url::Url;
 
-use self::executor::TaskExecutor;
+use self::executor::tokio::TokioMultiThreadExecutor;
+use self::executor::{DecodePool, TaskExecutor};
 use self::filesystem::ObjectStoreStorageHandler;
 use self::json::DefaultJsonHandler;
-use self::parquet::DefaultParquetHandler;
-use super::arrow_conversion::TryFromArrow as _;
+use self::log_store::{log_store_for_url, ConditionalPutLogStore};
+use self::metrics::{IoMetricsObjectStore, IoMetricsObserver};
+use self::parquet::{CheckpointBatchCache, DefaultParquetHandler};
+use super::arrow_conversion::{TryFromArrow as _, TryFromKernel as _};
 use super::arrow_data::ArrowEngineData;
 use super::arrow_expression::ArrowEvaluationHandler;
+use super::arrow_utils::{concat_with_missing_columns, filter_selected_rows, with_parsed_stats};
+use crate::actions::deletion_vector::{
+    DeletionVectorDescriptor, DeletionVectorWriter, SerializedDeletionVector,
+};
+use crate::arrow::datatypes::Schema as ArrowSchema;
+use crate::async_engine::{self, SyncAdapter};
+use crate::checkpoint::CheckpointWriter;
 use crate::schema::Schema;
 use crate::transaction::WriteContext;
 use crate::{
-    DeltaResult, Engine, EngineData, EvaluationHandler, JsonHandler, ParquetHandler, StorageHandler,
+    DeltaResult, Engine, EngineData, EvaluationHandler, FileMeta, JsonHandler, LogStore,
+    ParquetHandler, ParquetWriteOptions, StorageHandler,
 };
 
 pub mod executor;
 pub mod file_stream;
 pub mod filesystem;
 pub mod json;
+pub mod log_store;
+pub mod metrics;
 pub mod parquet;
 pub mod storage;
 
-#[derive(Debug)]
 pub struct DefaultEngine<E: TaskExecutor> {
     object_store: Arc<DynObjectStore>,
+    // wraps `object_store` for all IO of the engine, to report it to an `IoMetricsObserver`
+    io_metrics_store: Arc<IoMetricsObjectStore>,
     storage: Arc<ObjectStoreStorageHandler<E>>,
     json: Arc<DefaultJsonHandler<E>>,
     parquet: Arc<DefaultParquetHandler<E>>,
     evaluation: Arc<ArrowEvaluationHandler>,
+    log_store: Arc<dyn LogStore>,
+}
+
+impl<E: TaskExecutor + std::fmt::Debug> std::fmt::Debug for DefaultEngine<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DefaultEngine")
+            .field("object_store", &self.object_store)
+            .field("storage", &self.storage)
+            .field("json", &self.json)
+            .field("parquet", &self.parquet)
+            .field("evaluation", &self.evaluation)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<E: TaskExecutor> DefaultEngine<E> {
-    /// Create a new [`DefaultEngine`] instance
+    /// Create a new [`DefaultEngine`] instance, which writes commits with the [`LogStore`]
+    /// appropriate for the table's storage (see [`log_store_for_url`]).
     ///
     /// # Parameters
     ///
     /// - `table_root`: The URL of the table within storage.
-    /// - `options`: key/value pairs of options to pass to the object store.
+    /// - `options`: key/value pairs of options to pass to the object store, e.g. the region,
+    ///   endpoint or credentials to use. See [`parse_url_opts`] for supported options.
     /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
     pub fn try_new<K, V>(
         table_root: &Url,
@@ -61,38 +95,201 @@ impl<E: TaskExecutor> DefaultEngine<E> {
     {
         // table root is the path of the table in the ObjectStore
         let (object_store, _table_root) = parse_url_opts(table_root, options)?;
-        Ok(Self::new(Arc::new(object_store), task_executor))
+        Ok(Self::new_for_url(
+            table_root,
+            Arc::new(object_store),
+            task_executor,
+        ))
     }
 
-    /// Create a new [`DefaultEngine`] instance
+    /// Like [`DefaultEngine::try_new`], but configures the retries and request timeouts of the
+    /// object store according to `retry_policy`. See [`parse_url_opts_with_retry_policy`].
+    ///
+    /// # Parameters
+    ///
+    /// - `table_root`: The URL of the table within storage.
+    /// - `options`: key/value pairs of options to pass to the object store.
+    /// - `retry_policy`: The retry and timeout settings to use.
+    /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
+    pub fn try_new_with_retry_policy<K, V>(
+        table_root: &Url,
+        options: impl IntoIterator<Item = (K, V)>,
+        retry_policy: &RetryPolicy,
+        task_executor: Arc<E>,
+    ) -> DeltaResult<Self>
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let (object_store, _table_root) =
+            parse_url_opts_with_retry_policy(table_root, options, retry_policy)?;
+        Ok(Self::new_for_url(
+            table_root,
+            Arc::new(object_store),
+            task_executor,
+        ))
+    }
+
+    /// Like [`DefaultEngine::try_new`], but uses the object store that `registry` has registered
+    /// for `table_root`, if any, instead of constructing a new one. This allows engines to reuse
+    /// their existing object store clients. `options` are only used when no store is registered.
+    ///
+    /// # Parameters
+    ///
+    /// - `table_root`: The URL of the table within storage.
+    /// - `registry`: The pre-built object stores to choose from.
+    /// - `options`: key/value pairs of options to pass to the object store.
+    /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
+    pub fn try_new_with_registry<K, V>(
+        table_root: &Url,
+        registry: &ObjectStoreRegistry,
+        options: impl IntoIterator<Item = (K, V)>,
+        task_executor: Arc<E>,
+    ) -> DeltaResult<Self>
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let object_store = registry.get_or_parse(table_root, options)?;
+        Ok(Self::new_for_url(table_root, object_store, task_executor))
+    }
+
+    /// Create a new [`DefaultEngine`] instance, which writes commits with a
+    /// [`ConditionalPutLogStore`].
     ///
     /// # Parameters
     ///
     /// - `object_store`: The object store to use.
     /// - `task_executor`: Used to spawn async IO tasks. See [executor::TaskExecutor].
     pub fn new(object_store: Arc<DynObjectStore>, task_executor: Arc<E>) -> Self {
+        let io_metrics_store = Arc::new(IoMetricsObjectStore::unobserved(object_store.clone()));
+        let store: Arc<DynObjectStore> = io_metrics_store.clone();
         Self {
             storage: Arc::new(ObjectStoreStorageHandler::new(
-                object_store.clone(),
+                store.clone(),
                 task_executor.clone(),
             )),
             json: Arc::new(DefaultJsonHandler::new(
-                object_store.clone(),
+                store.clone(),
                 task_executor.clone(),
             )),
             parquet: Arc::new(DefaultParquetHandler::new(
-                object_store.clone(),
-                task_executor,
+                store.clone(),
+                task_executor.clone(),
             )),
+            log_store: Arc::new(ConditionalPutLogStore::new(store, task_executor)),
             object_store,
+            io_metrics_store,
             evaluation: Arc::new(ArrowEvaluationHandler {}),
         }
     }
 
+    // Creates an engine that writes commits with the log store appropriate for `table_root`
+    fn new_for_url(
+        table_root: &Url,
+        object_store: Arc<DynObjectStore>,
+        task_executor: Arc<E>,
+    ) -> Self {
+        let engine = Self::new(object_store, task_executor.clone());
+        let log_store =
+            log_store_for_url(table_root, engine.io_metrics_store.clone(), task_executor);
+        engine.with_log_store(log_store)
+    }
+
+    /// Use the given [`LogStore`] to write commits.
+    pub fn with_log_store(mut self, log_store: Arc<dyn LogStore>) -> Self {
+        self.log_store = log_store;
+        self
+    }
+
+    /// Report every object store request of the engine (reading and writing files, listing and
+    /// committing) to `observer`, tagged with the purpose of the accessed file. See the [`metrics`]
+    /// module.
+    ///
+    /// Commits written by a [`LogStore`] set with [`DefaultEngine::with_log_store`] are only
+    /// reported if that log store writes to an [`IoMetricsObjectStore`].
+    pub fn with_io_metrics_observer(self, observer: Arc<dyn IoMetricsObserver>) -> Self {
+        self.io_metrics_store.set_observer(observer);
+        self
+    }
+
+    /// Cache directory listings (e.g. of the Delta log) for up to `ttl`, so that subsequent
+    /// snapshot updates only list the files after the cached ones. See
+    /// [`ObjectStoreStorageHandler::with_listing_cache_ttl`].
+    pub fn with_listing_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        let storage = self.storage.as_ref().clone();
+        self.storage = Arc::new(storage.with_listing_cache_ttl(ttl));
+        self
+    }
+
+    /// Fetch byte ranges of the same file (e.g. deletion vectors) that are at most `coalesce_gap`
+    /// bytes apart with a single request. See [`ObjectStoreStorageHandler::with_coalesce_gap`].
+    pub fn with_read_coalesce_gap(mut self, coalesce_gap: u64) -> Self {
+        let storage = self.storage.as_ref().clone();
+        self.storage = Arc::new(storage.with_coalesce_gap(coalesce_gap));
+        self
+    }
+
+    /// Decompress and decode parquet data on `decode_pool` instead of on the threads of the task
+    /// executor. See [`DefaultParquetHandler::with_decode_pool`].
+    pub fn with_decode_pool(mut self, decode_pool: Arc<DecodePool>) -> Self {
+        let parquet = self.parquet.as_ref().clone();
+        self.parquet = Arc::new(parquet.with_decode_pool(decode_pool));
+        self
+    }
+
+    /// Cache the decoded batches of checkpoint files in `checkpoint_cache`, which may be shared
+    /// with other engines. See [`DefaultParquetHandler::with_checkpoint_cache`].
+    pub fn with_checkpoint_cache(mut self, checkpoint_cache: Arc<CheckpointBatchCache>) -> Self {
+        let parquet = self.parquet.as_ref().clone();
+        self.parquet = Arc::new(parquet.with_checkpoint_cache(checkpoint_cache));
+        self
+    }
+
+    /// Limit the number of rows per batch read from parquet and JSON files. See
+    /// [`DefaultParquetHandler::with_batch_size`] and [`DefaultJsonHandler::with_batch_size`].
+    pub fn with_read_batch_size(mut self, batch_size: usize) -> Self {
+        let parquet = self.parquet.as_ref().clone();
+        self.parquet = Arc::new(parquet.with_batch_size(batch_size));
+        let json = self.json.as_ref().clone();
+        self.json = Arc::new(json.with_batch_size(batch_size));
+        self
+    }
+
+    /// Approximate limit on the size in bytes of each batch read from parquet and JSON files,
+    /// which shrinks batches of very wide rows below the read batch size. See
+    /// [`DefaultParquetHandler::with_memory_budget`] and
+    /// [`DefaultJsonHandler::with_memory_budget`].
+    pub fn with_read_memory_budget(mut self, memory_budget: usize) -> Self {
+        let parquet = self.parquet.as_ref().clone();
+        self.parquet = Arc::new(parquet.with_memory_budget(memory_budget));
+        let json = self.json.as_ref().clone();
+        self.json = Arc::new(json.with_memory_budget(memory_budget));
+        self
+    }
+
+    /// Read parquet data files that use parquet modular encryption, obtaining their keys from
+    /// `key_retriever` (e.g. a KMS client). See [`DefaultParquetHandler::with_key_retriever`].
+    #[cfg(feature = "parquet-encryption")]
+    pub fn with_parquet_key_retriever(
+        mut self,
+        key_retriever: Arc<dyn self::parquet::KeyRetriever>,
+    ) -> DeltaResult<Self> {
+        let parquet = self.parquet.as_ref().clone();
+        self.parquet = Arc::new(parquet.with_key_retriever(key_retriever)?);
+        Ok(self)
+    }
+
     pub fn get_object_store_for_url(&self, _url: &Url) -> Option<Arc<DynObjectStore>> {
         Some(self.object_store.clone())
     }
 
+    /// Writes logical `data` to a new parquet file of the table, returning the file's metadata to
+    /// pass to [`Transaction::add_files`]. Missing columns with defaults are filled in, and the data
+    /// is validated against the table's column invariants and generated columns first, so an error
+    /// is returned (and nothing is written) if it violates them.
+    ///
+    /// [`Transaction::add_files`]: crate::transaction::Transaction::add_files
     pub async fn write_parquet(
         &self,
         data: &ArrowEngineData,
@@ -100,24 +297,143 @@ impl<E: TaskExecutor> DefaultEngine<E> {
         partition_values: HashMap<String, String>,
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
+        let mut input_schema = Arc::new(Schema::try_from_arrow(data.record_batch().schema())?);
+        // if columns with default values are missing from the data, fill in their defaults (and
+        // NULL for any other missing columns)
+        let filled_data;
+        let data: &dyn EngineData = if write_context.schema().fields().any(|field| {
+            input_schema.field(field.name()).is_none()
+                && write_context.column_default(field.name()).is_some()
+        }) {
+            filled_data = write_context.fill_missing_columns(self, &input_schema, data)?;
+            input_schema = write_context.schema().clone();
+            filled_data.as_ref()
+        } else {
+            data
+        };
+        write_context.check_invariants(self, data)?;
+        write_context.check_generated_columns(self, data)?;
         let transform = write_context.logical_to_physical();
-        let input_schema = Schema::try_from_arrow(data.record_batch().schema())?;
-        let output_schema = write_context.schema();
-        let logical_to_physical_expr = self.evaluation_handler().new_expression_evaluator(
-            input_schema.into(),
+        let output_schema = write_context.physical_schema();
+        let logical_to_physical_expr = self.evaluation.new_expression_evaluator(
+            input_schema,
             transform.clone(),
             output_schema.clone().into(),
         );
         let physical_data = logical_to_physical_expr.evaluate(data)?;
         self.parquet
-            .write_parquet_file(
+            .write_parquet_file_with_stats(
                 write_context.target_dir(),
                 physical_data,
+                write_context.stats_columns_schema().map(AsRef::as_ref),
                 partition_values,
                 data_change,
             )
             .await
     }
+
+    /// Write change data (logical data of the table followed by the `_change_type` column) to a
+    /// change data file, and return its metadata to pass to [`Transaction::add_cdc_files`].
+    ///
+    /// [`Transaction::add_cdc_files`]: crate::transaction::Transaction::add_cdc_files
+    pub async fn write_change_data(
+        &self,
+        data: &ArrowEngineData,
+        write_context: &WriteContext,
+        partition_values: HashMap<String, String>,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let input_schema = Schema::try_from_arrow(data.record_batch().schema())?;
+        let logical_to_change_data_expr = self.evaluation.new_expression_evaluator(
+            input_schema.into(),
+            write_context.logical_to_change_data(),
+            write_context.change_data_schema().into(),
+        );
+        let change_data = logical_to_change_data_expr.evaluate(data)?;
+        self.parquet
+            .write_parquet_file(
+                &write_context.change_data_dir()?,
+                change_data,
+                partition_values,
+                false,
+            )
+            .await
+    }
+
+    /// Serialize a deletion vector marking the row indexes in `deleted_rows` as deleted with the
+    /// given `writer`, write its file unless it's stored inline, and return its descriptor.
+    pub async fn write_deletion_vector(
+        &self,
+        writer: &DeletionVectorWriter,
+        deleted_rows: &RoaringTreemap,
+    ) -> DeltaResult<DeletionVectorDescriptor> {
+        let SerializedDeletionVector { descriptor, file } = writer.serialize(deleted_rows)?;
+        if let Some(file) = file {
+            let path = Path::from_url_path(file.location.path())?;
+            self.io_metrics_store.put(&path, file.data.into()).await?;
+        }
+        Ok(descriptor)
+    }
+
+    /// Write the checkpoint of `writer` (see [`CheckpointWriter::checkpoint_data`]) as a single
+    /// parquet file at [`CheckpointWriter::checkpoint_path`], finalize it, and return the metadata
+    /// of the written file.
+    ///
+    /// If the table enables `delta.checkpoint.writeStatsAsStruct`, the add actions of the
+    /// checkpoint also include their parsed `stats_parsed` (see
+    /// [`CheckpointWriter::stats_parsed_schema`]) along with the JSON `stats`.
+    pub fn write_checkpoint(&self, writer: CheckpointWriter) -> DeltaResult<FileMeta> {
+        let path = writer.checkpoint_path()?;
+        let stats_schema = writer
+            .stats_parsed_schema()
+            .map(|schema| ArrowSchema::try_from_kernel(schema.as_ref()).map(Arc::new))
+            .transpose()?;
+        let mut checkpoint_data = writer.checkpoint_data(self)?;
+        let batches: Vec<_> = checkpoint_data
+            .by_ref()
+            .map(|data| {
+                let batch = filter_selected_rows(data?)?;
+                match stats_schema {
+                    Some(ref stats_schema) => with_parsed_stats(batch, stats_schema.clone()),
+                    None => Ok(batch),
+                }
+            })
+            .try_collect()?;
+        // the checkpoint metadata action of V2 checkpoints comes in a batch of its own schema
+        let checkpoint = concat_with_missing_columns(&batches)?;
+        let metadata = ParquetHandler::write_parquet_file(
+            self.parquet.as_ref(),
+            &path,
+            Box::new(ArrowEngineData::new(checkpoint)),
+            &ParquetWriteOptions::default(),
+        )?;
+        writer.finalize(self, &metadata.file_meta, checkpoint_data)?;
+        Ok(metadata.file_meta)
+    }
+}
+
+impl DefaultEngine<TokioMultiThreadExecutor> {
+    /// Like [`DefaultEngine::try_new`], but runs async IO tasks on the existing (multi-threaded)
+    /// Tokio runtime of `handle`, e.g. the runtime of the server embedding the kernel, instead of
+    /// on a separate runtime. The (synchronous) kernel APIs may then also be called from tasks on
+    /// that runtime. See [`TokioMultiThreadExecutor`].
+    ///
+    /// # Parameters
+    ///
+    /// - `table_root`: The URL of the table within storage.
+    /// - `options`: key/value pairs of options to pass to the object store.
+    /// - `handle`: The runtime to run async IO tasks on, e.g. [`tokio::runtime::Handle::current`].
+    pub fn try_new_with_runtime_handle<K, V>(
+        table_root: &Url,
+        options: impl IntoIterator<Item = (K, V)>,
+        handle: tokio::runtime::Handle,
+    ) -> DeltaResult<Self>
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let task_executor = Arc::new(TokioMultiThreadExecutor::try_new(handle)?);
+        Self::try_new(table_root, options, task_executor)
+    }
 }
 
 impl<E: TaskExecutor> Engine for DefaultEngine<E> {
@@ -136,6 +452,31 @@ impl<E: TaskExecutor> Engine for DefaultEngine<E> {
     fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
         self.parquet.clone()
     }
+
+    fn log_store(&self) -> Arc<dyn LogStore> {
+        self.log_store.clone()
+    }
+}
+
+/// The JSON and parquet handlers of the default engine read files without blocking when used as an
+/// [`AsyncEngine`](async_engine::AsyncEngine), so its streams must be polled within a tokio runtime. Its storage handler is
+/// adapted from the sync one (see [`SyncAdapter`]).
+impl<E: TaskExecutor> async_engine::AsyncEngine for DefaultEngine<E> {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.evaluation.clone()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn async_engine::AsyncStorageHandler> {
+        Arc::new(SyncAdapter::new(self.storage.clone()))
+    }
+
+    fn json_handler(&self) -> Arc<dyn async_engine::AsyncJsonHandler> {
+        self.json.clone()
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn async_engine::AsyncParquetHandler> {
+        self.parquet.clone()
+    }
 }
 
 trait UrlExt {
@@ -170,6 +511,7 @@ impl UrlExt for Url {
 #[cfg(test)]
 mod tests {
     use super::executor::tokio::TokioBackgroundExecutor;
+    use super::metrics::{IoEvent, IoOperation, IoPurpose};
     use super::*;
     use crate::engine::tests::test_arrow_engine;
     use object_store::local::LocalFileSystem;
@@ -183,6 +525,113 @@ mod tests {
         test_arrow_engine(&engine, &url);
     }
 
+    #[test]
+    fn test_default_engine_with_registry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let url = Url::from_directory_path(tmp.path()).unwrap();
+        let object_store: Arc<DynObjectStore> = Arc::new(LocalFileSystem::new());
+        let mut registry = ObjectStoreRegistry::new();
+        registry.register_scheme("file", object_store.clone());
+        let engine = DefaultEngine::try_new_with_registry(
+            &url,
+            &registry,
+            HashMap::<String, String>::new(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )
+        .unwrap();
+        let engine_store = engine.get_object_store_for_url(&url).unwrap();
+        assert!(Arc::ptr_eq(&engine_store, &object_store));
+        test_arrow_engine(&engine, &url);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_default_engine_with_runtime_handle() {
+        let tmp = tempfile::tempdir().unwrap();
+        let url = Url::from_directory_path(tmp.path()).unwrap();
+        let engine = DefaultEngine::try_new_with_runtime_handle(
+            &url,
+            HashMap::<String, String>::new(),
+            tokio::runtime::Handle::current(),
+        )
+        .unwrap();
+        // use the engine from a task on the runtime it runs its IO on
+        tokio::spawn(async move { test_arrow_engine(&engine, &url) })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_deletion_vector() {
+        let tmp = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(tmp.path()).unwrap();
+        let object_store = Arc::new(LocalFileSystem::new());
+        let engine = DefaultEngine::new(object_store, Arc::new(TokioBackgroundExecutor::new()));
+        let deleted_rows = RoaringTreemap::from_iter((0..1000).map(|i| i * 3));
+
+        for max_inline_size in [0, usize::MAX] {
+            let writer =
+                DeletionVectorWriter::new(table_root.clone()).with_max_inline_size(max_inline_size);
+            let descriptor = engine
+                .write_deletion_vector(&writer, &deleted_rows)
+                .await
+                .unwrap();
+            let expected_storage_type = if max_inline_size == 0 { "u" } else { "i" };
+            assert_eq!(descriptor.storage_type, expected_storage_type);
+            let read = descriptor
+                .read(engine.storage_handler(), &table_root)
+                .unwrap();
+            assert_eq!(read, deleted_rows);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_io_metrics_observer() {
+        #[derive(Debug, Default)]
+        struct Observer(std::sync::Mutex<Vec<(IoOperation, IoPurpose)>>);
+        impl IoMetricsObserver for Observer {
+            fn observe(&self, event: &IoEvent) {
+                let mut events = self.0.lock().unwrap();
+                events.push((event.operation, event.purpose));
+            }
+        }
+
+        let tmp = tempfile::tempdir().unwrap();
+        let table_root = Url::from_directory_path(tmp.path()).unwrap();
+        let observer = Arc::new(Observer::default());
+        let engine = DefaultEngine::try_new(
+            &table_root,
+            HashMap::<String, String>::new(),
+            Arc::new(TokioBackgroundExecutor::new()),
+        )
+        .unwrap()
+        .with_io_metrics_observer(observer.clone());
+
+        let writer = DeletionVectorWriter::new(table_root.clone()).with_max_inline_size(0);
+        let descriptor = engine
+            .write_deletion_vector(&writer, &RoaringTreemap::from_iter([1, 3]))
+            .await
+            .unwrap();
+        descriptor
+            .read(engine.storage_handler(), &table_root)
+            .unwrap();
+        let log_root = table_root.join("_delta_log/").unwrap();
+        let _ = engine
+            .storage_handler()
+            .list_from(&log_root)
+            .unwrap()
+            .count();
+
+        let events = observer.0.lock().unwrap();
+        assert_eq!(
+            events.as_slice(),
+            [
+                (IoOperation::Put, IoPurpose::DeletionVector),
+                (IoOperation::Get, IoPurpose::DeletionVector),
+                (IoOperation::List, IoPurpose::Log),
+            ]
+        );
+    }
+
     #[test]
     fn test_pre_signed_url() {
         let url = Url::parse("https://example.com?X-Amz-Signature=foo").unwrap();
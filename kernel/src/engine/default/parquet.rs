@@ -1,55 +1,357 @@
 //! Default Parquet handler implementation
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Range;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::arrow::array::builder::{MapBuilder, MapFieldNames, StringBuilder};
-use crate::arrow::array::{BooleanArray, Int64Array, RecordBatch, StringArray, StructArray};
-use crate::arrow::datatypes::{DataType, Field};
+use crate::arrow::array::{Array, BooleanArray, Int64Array, RecordBatch, StringArray, StructArray};
+use crate::arrow::datatypes::{Field, Schema as ArrowSchema};
 use crate::parquet::arrow::arrow_reader::{
-    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReader,
+    ParquetRecordBatchReaderBuilder,
 };
-use crate::parquet::arrow::arrow_writer::ArrowWriter;
-use crate::parquet::arrow::async_reader::{ParquetObjectReader, ParquetRecordBatchStreamBuilder};
-use futures::StreamExt;
+use crate::parquet::arrow::async_reader::{
+    ParquetObjectReader, ParquetRecordBatchStream, ParquetRecordBatchStreamBuilder,
+};
+use crate::parquet::file::metadata::ParquetMetaData;
+use crate::parquet::file::properties::ReaderProperties;
+use crate::parquet::file::reader::{FileReader as _, SerializedFileReader};
+use crate::parquet::file::serialized_reader::ReadOptionsBuilder;
+
+#[cfg(feature = "parquet-encryption")]
+use crate::parquet::encryption::decrypt::FileDecryptionProperties;
+#[cfg(feature = "parquet-encryption")]
+pub use crate::parquet::encryption::decrypt::KeyRetriever;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
 use object_store::path::Path;
 use object_store::DynObjectStore;
 use uuid::Uuid;
 
 use super::file_stream::{FileOpenFuture, FileOpener, FileStream};
 use super::UrlExt;
+use crate::async_engine::{self, FileDataReadResultStream};
 use crate::engine::arrow_conversion::TryIntoArrow as _;
-use crate::engine::arrow_data::ArrowEngineData;
+use crate::engine::arrow_data::{extract_record_batch, ArrowEngineData};
+use crate::engine::arrow_stats::{collect_column_stats, file_stats};
 use crate::engine::arrow_utils::{
     fixup_parquet_read, generate_mask, get_requested_indices, ordering_needs_row_indexes,
-    RowIndexBuilder,
+    to_parquet_bytes, RowIndexBuilder,
+};
+use crate::engine::default::executor::{DecodePool, TaskExecutor};
+use crate::engine::parquet_row_group_skipping::{
+    bloom_filter_candidates, BloomFilters, ParquetRowGroupSkipping,
 };
-use crate::engine::default::executor::TaskExecutor;
-use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
-use crate::schema::SchemaRef;
+use crate::path::ParsedLogPath;
+use crate::schema::{SchemaRef, StructType};
 use crate::transaction::add_files_schema;
 use crate::{
-    DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, ParquetHandler,
-    PredicateRef,
+    DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, FileSize,
+    ParquetFileMetadata, ParquetHandler, ParquetWriteOptions, PredicateRef,
 };
 
+const DEFAULT_BATCH_SIZE: usize = 1024;
+
 #[derive(Debug)]
 pub struct DefaultParquetHandler<E: TaskExecutor> {
     store: Arc<DynObjectStore>,
     task_executor: Arc<E>,
     readahead: usize,
+    reader_options: ArrowReaderOptions,
+    footer_cache: Arc<ParquetFooterCache>,
+    footer_prefetch: usize,
+    batch_size: usize,
+    memory_budget: Option<usize>,
+    decode_pool: Option<Arc<DecodePool>>,
+    checkpoint_cache: Option<Arc<CheckpointBatchCache>>,
+}
+
+// Not derived, because that would needlessly require `E: Clone`
+impl<E: TaskExecutor> Clone for DefaultParquetHandler<E> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            task_executor: self.task_executor.clone(),
+            readahead: self.readahead,
+            reader_options: self.reader_options.clone(),
+            footer_cache: self.footer_cache.clone(),
+            footer_prefetch: self.footer_prefetch,
+            batch_size: self.batch_size,
+            memory_budget: self.memory_budget,
+            decode_pool: self.decode_pool.clone(),
+            checkpoint_cache: self.checkpoint_cache.clone(),
+        }
+    }
+}
+
+/// A cache of decoded parquet footers, keyed by file location and size, which allows repeated
+/// reads of the same files (e.g. across scans using the same engine) to skip fetching and decoding
+/// their footers again. Once the cache is full, the least recently used footer is evicted.
+#[derive(Debug)]
+pub struct ParquetFooterCache {
+    capacity: usize,
+    inner: Mutex<FooterCacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct FooterCacheEntries {
+    // Each footer is stored along with the "time" it was last used, for LRU eviction
+    footers: HashMap<(url::Url, FileSize), (ArrowReaderMetadata, u64)>,
+    clock: u64,
+}
+
+impl ParquetFooterCache {
+    /// The number of footers cached by [`DefaultParquetHandler`] unless configured otherwise.
+    pub const DEFAULT_CAPACITY: usize = 128;
+
+    /// Create a new cache that holds at most `capacity` footers. A capacity of zero disables
+    /// caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(FooterCacheEntries::default()),
+        }
+    }
+
+    /// The number of footers currently in the cache.
+    pub fn len(&self) -> usize {
+        self.lock().footers.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, file: &FileMeta) -> Option<ArrowReaderMetadata> {
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+        let (metadata, last_used) = inner.footers.get_mut(&(file.location.clone(), file.size))?;
+        *last_used = clock;
+        Some(metadata.clone())
+    }
+
+    fn insert(&self, file: &FileMeta, metadata: ArrowReaderMetadata) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+        let key = (file.location.clone(), file.size);
+        if !inner.footers.contains_key(&key) && inner.footers.len() >= self.capacity {
+            let lru = inner
+                .footers
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(lru) = lru {
+                inner.footers.remove(&lru);
+            }
+        }
+        inner.footers.insert(key, (metadata, clock));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FooterCacheEntries> {
+        // The cache is always left in a consistent state, so a poisoned lock is still usable
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for ParquetFooterCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+/// A cache of the decoded batches of checkpoint files (and their sidecars), which allows repeated
+/// queries of a table (e.g. by different snapshots and scans using the same engine) to skip
+/// fetching and decoding its checkpoint again while the checkpoint is unchanged. See
+/// [`DefaultParquetHandler::with_checkpoint_cache`].
+///
+/// The batches of a file are cached per schema and predicate the file was read with, and keyed by
+/// the file's location and etag, so that a file that was overwritten is read again. Only files
+/// read in full are cached. The cache holds batches up to a budget of their total (in-memory) size,
+/// evicting the least recently used reads beyond that.
+#[derive(Debug)]
+pub struct CheckpointBatchCache {
+    max_size: usize,
+    state: Mutex<CheckpointBatchCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CheckpointBatchCacheState {
+    files: HashMap<url::Url, CachedCheckpointFile>,
+    // the files of the cached reads and the reads' `last_used`, ordered by when they were last used
+    last_used: BTreeMap<u64, url::Url>,
+    clock: u64,
+    size: usize,
+}
+
+#[derive(Debug)]
+struct CachedCheckpointFile {
+    etag: String,
+    reads: Vec<CachedCheckpointRead>,
+}
+
+#[derive(Debug)]
+struct CachedCheckpointRead {
+    schema: SchemaRef,
+    predicate: Option<PredicateRef>,
+    batches: Arc<[RecordBatch]>,
+    size: usize,
+    last_used: u64,
+}
+
+impl CheckpointBatchCache {
+    /// The size budget of a cache created with [`CheckpointBatchCache::default`], in bytes.
+    pub const DEFAULT_MAX_SIZE: usize = 512 * 1024 * 1024;
+
+    /// Create a cache that holds checkpoint batches of up to `max_size` bytes in total.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            state: Mutex::new(CheckpointBatchCacheState::default()),
+        }
+    }
+
+    /// The number of cached reads of checkpoint files.
+    pub fn len(&self) -> usize {
+        self.lock().last_used.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total in-memory size of the cached batches, in bytes.
+    pub fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    fn get(
+        &self,
+        location: &url::Url,
+        etag: &str,
+        schema: &SchemaRef,
+        predicate: Option<&PredicateRef>,
+    ) -> Option<Arc<[RecordBatch]>> {
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        let file = state.files.get_mut(location).filter(|f| f.etag == etag)?;
+        let read = file
+            .reads
+            .iter_mut()
+            .find(|read| read.schema == *schema && read.predicate.as_ref() == predicate)?;
+        let previous = std::mem::replace(&mut read.last_used, clock);
+        let batches = read.batches.clone();
+        state.last_used.remove(&previous);
+        state.last_used.insert(clock, location.clone());
+        Some(batches)
+    }
+
+    fn insert(
+        &self,
+        location: url::Url,
+        etag: String,
+        schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+        batches: Vec<RecordBatch>,
+    ) {
+        let size = batches.iter().map(|b| b.get_array_memory_size()).sum();
+        if size > self.max_size {
+            return;
+        }
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        // Forget the reads of an older version of the file, or a previous read with this schema
+        let mut evicted = vec![];
+        if let Some(file) = state.files.get_mut(&location) {
+            if file.etag != etag {
+                evicted = std::mem::take(&mut file.reads);
+            } else if let Some(index) = file
+                .reads
+                .iter()
+                .position(|read| read.schema == schema && read.predicate == predicate)
+            {
+                evicted.push(file.reads.swap_remove(index));
+            }
+        }
+        for read in evicted {
+            state.last_used.remove(&read.last_used);
+            state.size -= read.size;
+        }
+        let file = state
+            .files
+            .entry(location.clone())
+            .or_insert_with(|| CachedCheckpointFile {
+                etag: etag.clone(),
+                reads: vec![],
+            });
+        file.etag = etag;
+        file.reads.push(CachedCheckpointRead {
+            schema,
+            predicate,
+            batches: batches.into(),
+            size,
+            last_used: clock,
+        });
+        state.last_used.insert(clock, location);
+        state.size += size;
+        while state.size > self.max_size {
+            let Some((last_used, location)) = state.last_used.pop_first() else {
+                break;
+            };
+            let Some(file) = state.files.get_mut(&location) else {
+                continue;
+            };
+            let Some(index) = file.reads.iter().position(|r| r.last_used == last_used) else {
+                continue;
+            };
+            let read = file.reads.swap_remove(index);
+            if file.reads.is_empty() {
+                state.files.remove(&location);
+            }
+            state.size -= read.size;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CheckpointBatchCacheState> {
+        // The cache is always left in a consistent state, so a poisoned lock is still usable
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for CheckpointBatchCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_SIZE)
+    }
+}
+
+// Whether `location` is a checkpoint file or a sidecar file of a checkpoint
+fn is_checkpoint_file(location: &url::Url) -> bool {
+    let is_sidecar = location.path().contains("/_delta_log/_sidecars/");
+    is_sidecar
+        || ParsedLogPath::try_from(location.clone())
+            .is_ok_and(|path| path.is_some_and(|path| path.is_checkpoint()))
 }
 
 /// Metadata of a data file (typically a parquet file).
 ///
-/// Currently just includes the the number of records as statistics, but will expand to include
-/// more statistics and other metadata in the future.
+/// Includes the number of records and, if collected, the null counts and min and max values of
+/// its columns as statistics.
 #[derive(Debug)]
 pub struct DataFileMetadata {
     file_meta: FileMeta,
     // NB: We use usize instead of u64 since arrow uses usize for record batch sizes
     num_records: usize,
+    // the `nullCount`, `minValues` and `maxValues` stats of the file, if collected
+    column_stats: Option<StructArray>,
 }
 
 impl DataFileMetadata {
@@ -57,9 +359,16 @@ impl DataFileMetadata {
         Self {
             file_meta,
             num_records,
+            column_stats: None,
         }
     }
 
+    // Include the given column stats (see `collect_column_stats`) in the stats of the file
+    fn with_column_stats(mut self, column_stats: StructArray) -> Self {
+        self.column_stats = Some(column_stats);
+        self
+    }
+
     /// Convert DataFileMetadata into a record batch which matches the schema returned by
     /// [`add_files_schema`].
     ///
@@ -77,6 +386,7 @@ impl DataFileMetadata {
                     size,
                 },
             num_records,
+            column_stats,
         } = self;
         // create the record batch of the write metadata
         let path = Arc::new(StringArray::from(vec![location.to_string()]));
@@ -101,15 +411,20 @@ impl DataFileMetadata {
         let size = Arc::new(Int64Array::from(vec![size]));
         let data_change = Arc::new(BooleanArray::from(vec![data_change]));
         let modification_time = Arc::new(Int64Array::from(vec![*last_modified]));
-        let stats = Arc::new(StructArray::try_new_with_length(
-            vec![Field::new("numRecords", DataType::Int64, true)].into(),
-            vec![Arc::new(Int64Array::from(vec![*num_records as i64]))],
-            None,
-            1,
-        )?);
-
+        let stats = Arc::new(file_stats(*num_records, column_stats.as_ref())?);
+
+        // the stats struct has the fields of the collected column stats, in addition to the ones
+        // of `add_files_schema`
+        let schema: ArrowSchema = add_files_schema().as_ref().try_into_arrow()?;
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| match field.name().as_str() {
+                "stats" => Arc::new(Field::new("stats", stats.data_type().clone(), true)),
+                _ => field.clone(),
+            });
         Ok(Box::new(ArrowEngineData::new(RecordBatch::try_new(
-            Arc::new(add_files_schema().as_ref().try_into_arrow()?),
+            Arc::new(ArrowSchema::new(fields.collect::<Vec<_>>())),
             vec![
                 path,
                 partitions,
@@ -128,6 +443,13 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
             store,
             task_executor,
             readahead: 10,
+            reader_options: ArrowReaderOptions::new(),
+            footer_cache: Arc::new(ParquetFooterCache::default()),
+            footer_prefetch: 8,
+            batch_size: DEFAULT_BATCH_SIZE,
+            memory_budget: None,
+            decode_pool: None,
+            checkpoint_cache: None,
         }
     }
 
@@ -139,6 +461,110 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         self
     }
 
+    /// Cache parquet footers in `footer_cache`, e.g. to share it with other handlers. By default,
+    /// each handler has its own cache of [`ParquetFooterCache::DEFAULT_CAPACITY`] footers.
+    pub fn with_footer_cache(mut self, footer_cache: Arc<ParquetFooterCache>) -> Self {
+        self.footer_cache = footer_cache;
+        self
+    }
+
+    /// Max number of upcoming files whose footers are fetched concurrently (into the footer cache)
+    /// while executing [Self::read_parquet_files()]. Zero disables prefetching.
+    ///
+    /// Defaults to 8.
+    pub fn with_footer_prefetch(mut self, footer_prefetch: usize) -> Self {
+        self.footer_prefetch = footer_prefetch;
+        self
+    }
+
+    /// Limit the number of rows per batch. That is, for batch_size = N, then each RecordBatch
+    /// yielded by [Self::read_parquet_files()] will have at most N rows.
+    ///
+    /// Defaults to 1024 rows.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Approximate limit on the (uncompressed) size in bytes of each RecordBatch yielded by
+    /// [Self::read_parquet_files()]. The number of rows per batch is reduced below the batch size
+    /// as needed, based on the uncompressed size of the projected columns in each file's row
+    /// groups, so that very wide rows don't produce huge batches. Each batch has at least one row.
+    ///
+    /// By default there is no memory budget, and only the batch size limits batches.
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = Some(memory_budget);
+        self
+    }
+
+    /// Decompress and decode the data read by [Self::read_parquet_files()] on `decode_pool`,
+    /// instead of on the threads of the task executor, which then only fetch the data. This keeps
+    /// wide scans from starving other IO tasks of the executor.
+    ///
+    /// By default, data is decoded by the task executor.
+    pub fn with_decode_pool(mut self, decode_pool: Arc<DecodePool>) -> Self {
+        self.decode_pool = Some(decode_pool);
+        self
+    }
+
+    /// Cache the batches read from checkpoint files (and their sidecars) in `checkpoint_cache`, so
+    /// that later reads of an unchanged checkpoint with the same schema and predicate are served
+    /// from memory. Pass the same cache to several handlers (or engines) to share it. Checking
+    /// whether a checkpoint is unchanged costs a HEAD request per file read.
+    ///
+    /// By default, checkpoint batches are not cached.
+    pub fn with_checkpoint_cache(mut self, checkpoint_cache: Arc<CheckpointBatchCache>) -> Self {
+        self.checkpoint_cache = Some(checkpoint_cache);
+        self
+    }
+
+    // Fetch the footers of (up to `footer_prefetch` of) the given files in the background, so they
+    // are already cached by the time the files are opened. Failures are ignored here, because
+    // they will surface when the file is actually read.
+    fn prefetch_footers(&self, files: &[FileMeta], with_page_index: bool) {
+        let files: Vec<_> = files.iter().take(self.footer_prefetch).cloned().collect();
+        if files.is_empty() || self.footer_cache.capacity == 0 {
+            return;
+        }
+        let store = self.store.clone();
+        let footer_cache = self.footer_cache.clone();
+        let reader_options = self.reader_options.clone();
+        self.task_executor.spawn(async move {
+            let prefetches = files.into_iter().map(|file_meta| {
+                let store = store.clone();
+                let footer_cache = footer_cache.clone();
+                let reader_options = reader_options.clone();
+                async move {
+                    let Ok(mut reader) = object_reader(store, &file_meta).await else {
+                        return;
+                    };
+                    let _ = load_footer(
+                        &mut reader,
+                        &file_meta,
+                        reader_options,
+                        &footer_cache,
+                        with_page_index,
+                    )
+                    .await;
+                }
+            });
+            futures::future::join_all(prefetches).await;
+        });
+    }
+
+    /// Read parquet files that use parquet modular encryption, obtaining their keys from
+    /// `key_retriever`. The retriever is passed the key metadata stored in each encrypted file,
+    /// e.g. to look up or unwrap the corresponding key with a KMS client.
+    #[cfg(feature = "parquet-encryption")]
+    pub fn with_key_retriever(mut self, key_retriever: Arc<dyn KeyRetriever>) -> DeltaResult<Self> {
+        let decryption_properties =
+            FileDecryptionProperties::with_key_retriever(key_retriever).build()?;
+        self.reader_options = self
+            .reader_options
+            .with_file_decryption_properties(decryption_properties);
+        Ok(self)
+    }
+
     // Write `data` to `{path}/<uuid>.parquet` as parquet using ArrowWriter and return the parquet
     // metadata (where `<uuid>` is a generated UUIDv4).
     //
@@ -148,20 +574,16 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         &self,
         path: &url::Url,
         data: Box<dyn EngineData>,
+        stats_columns: Option<&StructType>,
     ) -> DeltaResult<DataFileMetadata> {
-        let batch: Box<_> = ArrowEngineData::try_from_engine_data(data)?;
-        let record_batch = batch.record_batch();
-        let num_records = record_batch.num_rows();
-
-        let mut buffer = vec![];
-        let mut writer = ArrowWriter::try_new(&mut buffer, record_batch.schema(), None)?;
-        writer.write(record_batch)?;
-        writer.close()?; // writer must be closed to write footer
-
-        let size: u64 = buffer
-            .len()
-            .try_into()
-            .map_err(|_| Error::generic("unable to convert usize to u64"))?;
+        let column_stats = match stats_columns {
+            Some(stats_columns) => {
+                let batch = extract_record_batch(data.as_ref())?;
+                Some(collect_column_stats(batch, stats_columns)?)
+            }
+            None => None,
+        };
+        let (buffer, num_records) = to_parquet_bytes(data, &ParquetWriteOptions::default())?;
         let name: String = format!("{}.parquet", Uuid::new_v4());
         // fail if path does not end with a trailing slash
         if !path.path().ends_with('/') {
@@ -171,21 +593,12 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         }
         let path = path.join(&name)?;
 
-        self.store
-            .put(&Path::from_url_path(path.path())?, buffer.into())
-            .await?;
-
-        let metadata = self.store.head(&Path::from_url_path(path.path())?).await?;
-        let modification_time = metadata.last_modified.timestamp_millis();
-        if size != metadata.size {
-            return Err(Error::generic(format!(
-                "Size mismatch after writing parquet file: expected {}, got {}",
-                size, metadata.size
-            )));
-        }
-
-        let file_meta = FileMeta::new(path, modification_time, size);
-        Ok(DataFileMetadata::new(file_meta, num_records))
+        let metadata = put_parquet(self.store.clone(), path, buffer, num_records).await?;
+        let metadata = DataFileMetadata::new(metadata.file_meta, num_records);
+        Ok(match column_stats {
+            Some(column_stats) => metadata.with_column_stats(column_stats),
+            None => metadata,
+        })
     }
 
     /// Write `data` to `{path}/<uuid>.parquet` as parquet using ArrowWriter and return the parquet
@@ -200,11 +613,100 @@ impl<E: TaskExecutor> DefaultParquetHandler<E> {
         partition_values: HashMap<String, String>,
         data_change: bool,
     ) -> DeltaResult<Box<dyn EngineData>> {
-        let parquet_metadata = self.write_parquet(path, data).await?;
+        let parquet_metadata = self.write_parquet(path, data, None).await?;
+        parquet_metadata.as_record_batch(&partition_values, data_change)
+    }
+
+    /// Like [`Self::write_parquet_file`], but also collects the null counts and min and max values
+    /// of the `stats_columns` of `data` (see [`WriteContext::stats_columns_schema`]) as statistics
+    /// of the written file.
+    ///
+    /// [`WriteContext::stats_columns_schema`]: crate::transaction::WriteContext::stats_columns_schema
+    pub async fn write_parquet_file_with_stats(
+        &self,
+        path: &url::Url,
+        data: Box<dyn EngineData>,
+        stats_columns: Option<&StructType>,
+        partition_values: HashMap<String, String>,
+        data_change: bool,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        let parquet_metadata = self.write_parquet(path, data, stats_columns).await?;
         parquet_metadata.as_record_batch(&partition_values, data_change)
     }
 }
 
+// PUT the encoded parquet `buffer` to `location`, followed by a HEAD to storage in order to
+// obtain metadata about the object just written.
+async fn put_parquet(
+    store: Arc<DynObjectStore>,
+    location: url::Url,
+    buffer: Vec<u8>,
+    num_records: usize,
+) -> DeltaResult<ParquetFileMetadata> {
+    let size: u64 = buffer
+        .len()
+        .try_into()
+        .map_err(|_| Error::generic("unable to convert usize to u64"))?;
+    let path = Path::from_url_path(location.path())?;
+    store.put(&path, buffer.into()).await?;
+
+    let metadata = store.head(&path).await?;
+    let modification_time = metadata.last_modified.timestamp_millis();
+    if size != metadata.size {
+        return Err(Error::generic(format!(
+            "Size mismatch after writing parquet file: expected {}, got {}",
+            size, metadata.size
+        )));
+    }
+
+    Ok(ParquetFileMetadata {
+        file_meta: FileMeta::new(location, modification_time, size),
+        num_records: num_records as u64,
+    })
+}
+
+impl<E: TaskExecutor> DefaultParquetHandler<E> {
+    /// The [`FileOpener`] to read `files` with. Every file of `files` must have the same scheme,
+    /// as this is decided by the first one:
+    /// - s3://, file://, etc. (or no scheme) -> [`ParquetOpener`] through the object store
+    /// - https:// -> assumed to be a presigned URL, read by [`PresignedUrlOpener`] without the
+    ///   object store
+    fn file_opener(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> Box<dyn FileOpener> {
+        if files
+            .first()
+            .is_some_and(|file| file.location.is_presigned())
+        {
+            Box::new(
+                PresignedUrlOpener::new(self.batch_size, physical_schema.clone(), predicate)
+                    .with_reader_options(self.reader_options.clone())
+                    .with_memory_budget(self.memory_budget)
+                    .with_decode_pool(self.decode_pool.clone()),
+            )
+        } else {
+            // The first file is opened right away, so only prefetch the footers of the others
+            self.prefetch_footers(files.get(1..).unwrap_or_default(), predicate.is_some());
+            Box::new(
+                ParquetOpener::new(
+                    self.batch_size,
+                    physical_schema.clone(),
+                    predicate,
+                    self.store.clone(),
+                )
+                .with_reader_options(self.reader_options.clone())
+                .with_footer_cache(self.footer_cache.clone())
+                .with_memory_budget(self.memory_budget)
+                .with_decode_pool(self.decode_pool.clone())
+                .with_checkpoint_cache(self.checkpoint_cache.clone()),
+            )
+        }
+    }
+}
+
 impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
     fn read_parquet_files(
         &self,
@@ -216,28 +718,7 @@ impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
             return Ok(Box::new(std::iter::empty()));
         }
 
-        // get the first FileMeta to decide how to fetch the file.
-        // NB: This means that every file in `FileMeta` _must_ have the same scheme or things will break
-        // s3://    -> aws   (ParquetOpener)
-        // nothing  -> local (ParquetOpener)
-        // https:// -> assume presigned URL (and fetch without object_store)
-        //   -> reqwest to get data
-        //   -> parse to parquet
-        // SAFETY: we did is_empty check above, this is ok.
-        let file_opener: Box<dyn FileOpener> = if files[0].location.is_presigned() {
-            Box::new(PresignedUrlOpener::new(
-                1024,
-                physical_schema.clone(),
-                predicate,
-            ))
-        } else {
-            Box::new(ParquetOpener::new(
-                1024,
-                physical_schema.clone(),
-                predicate,
-                self.store.clone(),
-            ))
-        };
+        let file_opener = self.file_opener(files, physical_schema.clone(), predicate);
         FileStream::new_async_read_iterator(
             self.task_executor.clone(),
             Arc::new(physical_schema.as_ref().try_into_arrow()?),
@@ -246,6 +727,38 @@ impl<E: TaskExecutor> ParquetHandler for DefaultParquetHandler<E> {
             self.readahead,
         )
     }
+
+    fn write_parquet_file(
+        &self,
+        location: &url::Url,
+        data: Box<dyn EngineData>,
+        options: &ParquetWriteOptions,
+    ) -> DeltaResult<ParquetFileMetadata> {
+        let (buffer, num_records) = to_parquet_bytes(data, options)?;
+        let store = self.store.clone(); // cheap Arc
+        let location = location.clone();
+        self.task_executor
+            .block_on(async move { put_parquet(store, location, buffer, num_records).await })
+    }
+}
+
+impl<E: TaskExecutor> async_engine::AsyncParquetHandler for DefaultParquetHandler<E> {
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        physical_schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultStream> {
+        if files.is_empty() {
+            return Ok(futures::stream::empty().boxed());
+        }
+
+        let file_opener = self.file_opener(files, physical_schema.clone(), predicate);
+        let schema = Arc::new(physical_schema.as_ref().try_into_arrow()?);
+        let stream = FileStream::new(files.to_vec(), schema, file_opener)?
+            .map_ok(|batch| -> Box<dyn EngineData> { Box::new(ArrowEngineData::new(batch)) });
+        Ok(stream.boxed())
+    }
 }
 
 /// Implements [`FileOpener`] for a parquet file
@@ -256,6 +769,11 @@ struct ParquetOpener {
     predicate: Option<PredicateRef>,
     limit: Option<usize>,
     store: Arc<DynObjectStore>,
+    reader_options: ArrowReaderOptions,
+    footer_cache: Arc<ParquetFooterCache>,
+    memory_budget: Option<usize>,
+    decode_pool: Option<Arc<DecodePool>>,
+    checkpoint_cache: Option<Arc<CheckpointBatchCache>>,
 }
 
 impl ParquetOpener {
@@ -271,13 +789,132 @@ impl ParquetOpener {
             predicate,
             limit: None,
             store,
+            reader_options: ArrowReaderOptions::new(),
+            footer_cache: Arc::new(ParquetFooterCache::new(0)),
+            memory_budget: None,
+            decode_pool: None,
+            checkpoint_cache: None,
+        }
+    }
+
+    /// Use the given options (e.g. decryption properties) when reading files.
+    pub(crate) fn with_reader_options(mut self, reader_options: ArrowReaderOptions) -> Self {
+        self.reader_options = reader_options;
+        self
+    }
+
+    /// Look up (and cache) file footers in the given cache.
+    pub(crate) fn with_footer_cache(mut self, footer_cache: Arc<ParquetFooterCache>) -> Self {
+        self.footer_cache = footer_cache;
+        self
+    }
+
+    /// Shrink batches as needed to (approximately) fit in the given number of bytes.
+    pub(crate) fn with_memory_budget(mut self, memory_budget: Option<usize>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Decode the fetched data on the given pool, if any.
+    pub(crate) fn with_decode_pool(mut self, decode_pool: Option<Arc<DecodePool>>) -> Self {
+        self.decode_pool = decode_pool;
+        self
+    }
+
+    /// Cache the batches of checkpoint files in the given cache, if any.
+    pub(crate) fn with_checkpoint_cache(
+        mut self,
+        checkpoint_cache: Option<Arc<CheckpointBatchCache>>,
+    ) -> Self {
+        self.checkpoint_cache = checkpoint_cache;
+        self
+    }
+}
+
+// The number of rows per batch to read, given the requested batch size and memory budget. The
+// size of a row is estimated as the uncompressed size of the projected (leaf) columns divided by
+// the number of rows, using the largest such estimate among the row groups of the file.
+fn budgeted_batch_size(
+    batch_size: usize,
+    memory_budget: Option<usize>,
+    metadata: &ParquetMetaData,
+    leaf_indices: &[usize],
+) -> usize {
+    let Some(memory_budget) = memory_budget else {
+        return batch_size;
+    };
+    let row_size = metadata
+        .row_groups()
+        .iter()
+        .filter(|row_group| row_group.num_rows() > 0)
+        .map(|row_group| {
+            let bytes: i64 = leaf_indices
+                .iter()
+                .filter_map(|&i| row_group.columns().get(i))
+                .map(|column| column.uncompressed_size())
+                .sum();
+            (bytes.max(0) as u64).div_ceil(row_group.num_rows() as u64)
+        })
+        .max()
+        .unwrap_or(0);
+    let rows = usize::try_from(row_size)
+        .ok()
+        .and_then(|row_size| memory_budget.checked_div(row_size))
+        .unwrap_or(batch_size);
+    rows.clamp(1, batch_size.max(1))
+}
+
+// Create a reader for the parquet file described by `file_meta`.
+async fn object_reader(
+    store: Arc<DynObjectStore>,
+    file_meta: &FileMeta,
+) -> DeltaResult<ParquetObjectReader> {
+    use object_store::ObjectStoreScheme;
+    let path = Path::from_url_path(file_meta.location.path())?;
+    // HACK: unfortunately, `ParquetObjectReader` under the hood does a suffix range
+    // request which isn't supported by Azure. For now we just detect if the URL is
+    // pointing to azure and if so, do a HEAD request so we can pass in file size to the
+    // reader which will cause the reader to avoid a suffix range request.
+    // see also: https://github.com/delta-io/delta-kernel-rs/issues/968
+    //
+    // TODO(#1010): Note that we don't need this at all and can actually just _always_
+    // do the `with_file_size` but need to (1) update our unit tests which often
+    // hardcode size=0 and (2) update CDF execute which also hardcodes size=0.
+    if let Ok((ObjectStoreScheme::MicrosoftAzure, _)) =
+        ObjectStoreScheme::parse(&file_meta.location)
+    {
+        // also note doing HEAD then actual GET isn't atomic, and leaves us vulnerable
+        // to file changing between the two calls.
+        let meta = store.head(&path).await?;
+        Ok(ParquetObjectReader::new(store, path).with_file_size(meta.size))
+    } else {
+        Ok(ParquetObjectReader::new(store, path))
+    }
+}
+
+// Fetch and decode the footer of the parquet file described by `file_meta`, unless it is already
+// cached. The page index is only loaded if `with_page_index` is set.
+async fn load_footer(
+    reader: &mut ParquetObjectReader,
+    file_meta: &FileMeta,
+    reader_options: ArrowReaderOptions,
+    footer_cache: &ParquetFooterCache,
+    with_page_index: bool,
+) -> DeltaResult<ArrowReaderMetadata> {
+    if let Some(metadata) = footer_cache.get(file_meta) {
+        // A footer cached without its page index must be reloaded if the page index is needed
+        if !with_page_index || metadata.metadata().column_index().is_some() {
+            return Ok(metadata);
         }
     }
+    let options = reader_options.with_page_index(with_page_index);
+    let metadata = ArrowReaderMetadata::load_async(reader, options).await?;
+    footer_cache.insert(file_meta, metadata.clone());
+    Ok(metadata)
 }
 
 impl FileOpener for ParquetOpener {
     fn open(&self, file_meta: FileMeta, _range: Option<Range<i64>>) -> DeltaResult<FileOpenFuture> {
-        let path = Path::from_url_path(file_meta.location.path())?;
         let store = self.store.clone();
 
         let batch_size = self.batch_size;
@@ -285,38 +922,54 @@ impl FileOpener for ParquetOpener {
         let table_schema = self.table_schema.clone();
         let predicate = self.predicate.clone();
         let limit = self.limit;
+        let reader_options = self.reader_options.clone();
+        let footer_cache = self.footer_cache.clone();
+        let memory_budget = self.memory_budget;
+        let decode_pool = self.decode_pool.clone();
+        // Reads with a limit are partial, so they are never cached
+        let checkpoint_cache = self
+            .checkpoint_cache
+            .clone()
+            .filter(|_| limit.is_none() && is_checkpoint_file(&file_meta.location));
 
         Ok(Box::pin(async move {
-            let mut reader = {
-                use object_store::ObjectStoreScheme;
-                // HACK: unfortunately, `ParquetObjectReader` under the hood does a suffix range
-                // request which isn't supported by Azure. For now we just detect if the URL is
-                // pointing to azure and if so, do a HEAD request so we can pass in file size to the
-                // reader which will cause the reader to avoid a suffix range request.
-                // see also: https://github.com/delta-io/delta-kernel-rs/issues/968
-                //
-                // TODO(#1010): Note that we don't need this at all and can actually just _always_
-                // do the `with_file_size` but need to (1) update our unit tests which often
-                // hardcode size=0 and (2) update CDF execute which also hardcodes size=0.
-                if let Ok((ObjectStoreScheme::MicrosoftAzure, _)) =
-                    ObjectStoreScheme::parse(&file_meta.location)
-                {
-                    // also note doing HEAD then actual GET isn't atomic, and leaves us vulnerable
-                    // to file changing between the two calls.
-                    let meta = store.head(&path).await?;
-                    ParquetObjectReader::new(store, path).with_file_size(meta.size)
-                } else {
-                    ParquetObjectReader::new(store, path)
+            let cache_fill = match checkpoint_cache {
+                Some(cache) => {
+                    let path = Path::from_url_path(file_meta.location.path())?;
+                    let etag = store.head(&path).await?.e_tag;
+                    let cached = etag.as_ref().and_then(|etag| {
+                        cache.get(&file_meta.location, etag, &table_schema, predicate.as_ref())
+                    });
+                    if let Some(batches) = cached {
+                        let batches = (0..batches.len()).map(move |i| Ok(batches[i].clone()));
+                        return Ok(futures::stream::iter(batches).boxed());
+                    }
+                    // Files without an etag can't be checked for changes, so they aren't cached
+                    etag.map(|etag| CheckpointCacheFill {
+                        cache,
+                        location: file_meta.location.clone(),
+                        etag,
+                        schema: table_schema.clone(),
+                        predicate: predicate.clone(),
+                    })
                 }
+                None => None,
             };
-
-            let metadata = ArrowReaderMetadata::load_async(&mut reader, Default::default()).await?;
+            let mut reader = object_reader(store, &file_meta).await?;
+            // The page index is only useful for page skipping, so only load it with a predicate
+            let metadata = load_footer(
+                &mut reader,
+                &file_meta,
+                reader_options,
+                &footer_cache,
+                predicate.is_some(),
+            )
+            .await?;
             let parquet_schema = metadata.schema();
             let (indices, requested_ordering) =
                 get_requested_indices(&table_schema, parquet_schema)?;
-            let options = ArrowReaderOptions::new(); //.with_page_index(enable_page_index);
             let mut builder =
-                ParquetRecordBatchStreamBuilder::new_with_options(reader, options).await?;
+                ParquetRecordBatchStreamBuilder::new_with_metadata(reader, metadata.clone());
             if let Some(mask) = generate_mask(
                 &table_schema,
                 parquet_schema,
@@ -330,25 +983,139 @@ impl FileOpener for ParquetOpener {
             let mut row_indexes = ordering_needs_row_indexes(&requested_ordering)
                 .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
 
-            // Filter row groups and row indexes if a predicate is provided
+            // Filter row groups and row indexes if a predicate is provided, consulting the bloom
+            // filters of any column the predicate compares for equality
             if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
+                let mut bloom_filters = BloomFilters::new();
+                for (row_group, column) in bloom_filter_candidates(builder.metadata(), predicate) {
+                    let bloom_filter = builder
+                        .get_row_group_column_bloom_filter(row_group, column)
+                        .await?;
+                    if let Some(bloom_filter) = bloom_filter {
+                        bloom_filters.insert((row_group, column), bloom_filter);
+                    }
+                }
+                builder = builder.with_row_group_and_bloom_filter(
+                    predicate,
+                    &bloom_filters,
+                    row_indexes.as_mut(),
+                );
             }
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
 
+            let batch_size =
+                budgeted_batch_size(batch_size, memory_budget, builder.metadata(), &indices);
             let mut row_indexes = row_indexes.map(|rb| rb.into_iter());
             let stream = builder.with_batch_size(batch_size).build()?;
+            let stream = match decode_pool {
+                Some(decode_pool) => decode_row_groups_on_pool(stream, decode_pool),
+                None => stream.map_err(Error::from).boxed(),
+            };
 
             let stream = stream.map(move |rbr| {
                 fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())
             });
-            Ok(stream.boxed())
+            match cache_fill {
+                Some(cache_fill) => Ok(cache_fill.fill_from(stream.boxed())),
+                None => Ok(stream.boxed()),
+            }
         }))
     }
 }
 
+// The entry of a [`CheckpointBatchCache`] that reading a checkpoint file fills.
+struct CheckpointCacheFill {
+    cache: Arc<CheckpointBatchCache>,
+    location: url::Url,
+    etag: String,
+    schema: SchemaRef,
+    predicate: Option<PredicateRef>,
+}
+
+impl CheckpointCacheFill {
+    // Passes through the batches of `stream`, caching them once the stream completes without
+    // error. Batches stop being collected as soon as they exceed the size budget of the cache.
+    fn fill_from(
+        self,
+        stream: BoxStream<'static, DeltaResult<RecordBatch>>,
+    ) -> BoxStream<'static, DeltaResult<RecordBatch>> {
+        let state = (stream, Some(self), vec![], 0);
+        futures::stream::unfold(
+            state,
+            |(mut stream, mut fill, mut batches, mut size)| async move {
+                let Some(result) = stream.next().await else {
+                    if let Some(fill) = fill {
+                        let CheckpointCacheFill {
+                            cache,
+                            location,
+                            etag,
+                            schema,
+                            predicate,
+                        } = fill;
+                        cache.insert(location, etag, schema, predicate, batches);
+                    }
+                    return None;
+                };
+                match (&result, &fill) {
+                    (Ok(batch), Some(cache_fill)) => {
+                        size += batch.get_array_memory_size();
+                        if size > cache_fill.cache.max_size {
+                            fill = None;
+                            batches = vec![];
+                        } else {
+                            batches.push(batch.clone());
+                        }
+                    }
+                    (Err(_), _) => fill = None,
+                    _ => {}
+                }
+                Some((result, (stream, fill, batches, size)))
+            },
+        )
+        .boxed()
+    }
+}
+
+// Decodes the batches of `reader`, whose data was already fetched, on `decode_pool`. Each batch is
+// decoded by a separate task, so a slow consumer never blocks a thread of the pool.
+fn decode_on_pool(
+    reader: ParquetRecordBatchReader,
+    decode_pool: Arc<DecodePool>,
+) -> BoxStream<'static, DeltaResult<RecordBatch>> {
+    futures::stream::try_unfold(reader, move |mut reader| {
+        let decode_pool = decode_pool.clone();
+        async move {
+            let (reader, batch) = decode_pool
+                .run(move || {
+                    let batch = reader.next();
+                    (reader, batch)
+                })
+                .await?;
+            match batch {
+                Some(batch) => Ok(Some((batch?, reader))),
+                None => Ok(None),
+            }
+        }
+    })
+    .boxed()
+}
+
+// Fetches the row groups of `stream` on the calling (IO) task, and decodes them on `decode_pool`
+fn decode_row_groups_on_pool(
+    stream: ParquetRecordBatchStream<ParquetObjectReader>,
+    decode_pool: Arc<DecodePool>,
+) -> BoxStream<'static, DeltaResult<RecordBatch>> {
+    futures::stream::try_unfold(stream, |mut stream| async move {
+        let reader = stream.next_row_group().await?;
+        Ok::<_, Error>(reader.map(|reader| (reader, stream)))
+    })
+    .map_ok(move |reader| decode_on_pool(reader, decode_pool.clone()))
+    .try_flatten()
+    .boxed()
+}
+
 /// Implements [`FileOpener`] for a opening a parquet file from a presigned URL
 struct PresignedUrlOpener {
     batch_size: usize,
@@ -356,6 +1123,9 @@ struct PresignedUrlOpener {
     limit: Option<usize>,
     table_schema: SchemaRef,
     client: reqwest::Client,
+    reader_options: ArrowReaderOptions,
+    memory_budget: Option<usize>,
+    decode_pool: Option<Arc<DecodePool>>,
 }
 
 impl PresignedUrlOpener {
@@ -370,8 +1140,29 @@ impl PresignedUrlOpener {
             predicate,
             limit: None,
             client: reqwest::Client::new(),
+            reader_options: ArrowReaderOptions::new(),
+            memory_budget: None,
+            decode_pool: None,
         }
     }
+
+    /// Use the given options (e.g. decryption properties) when reading files.
+    pub(crate) fn with_reader_options(mut self, reader_options: ArrowReaderOptions) -> Self {
+        self.reader_options = reader_options;
+        self
+    }
+
+    /// Shrink batches as needed to (approximately) fit in the given number of bytes.
+    pub(crate) fn with_memory_budget(mut self, memory_budget: Option<usize>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    /// Decode the fetched data on the given pool, if any.
+    pub(crate) fn with_decode_pool(mut self, decode_pool: Option<Arc<DecodePool>>) -> Self {
+        self.decode_pool = decode_pool;
+        self
+    }
 }
 
 impl FileOpener for PresignedUrlOpener {
@@ -381,18 +1172,21 @@ impl FileOpener for PresignedUrlOpener {
         let predicate = self.predicate.clone();
         let limit = self.limit;
         let client = self.client.clone(); // uses Arc internally according to reqwest docs
+        let reader_options = self.reader_options.clone();
+        let memory_budget = self.memory_budget;
+        let decode_pool = self.decode_pool.clone();
 
         Ok(Box::pin(async move {
             // fetch the file from the interweb
             let reader = client.get(file_meta.location).send().await?.bytes().await?;
-            let metadata = ArrowReaderMetadata::load(&reader, Default::default())?;
+            let metadata = ArrowReaderMetadata::load(&reader, reader_options.clone())?;
             let parquet_schema = metadata.schema();
             let (indices, requested_ordering) =
                 get_requested_indices(&table_schema, parquet_schema)?;
 
-            let options = ArrowReaderOptions::new();
+            let options = reader_options.with_page_index(predicate.is_some());
             let mut builder =
-                ParquetRecordBatchReaderBuilder::try_new_with_options(reader, options)?;
+                ParquetRecordBatchReaderBuilder::try_new_with_options(reader.clone(), options)?;
             if let Some(mask) = generate_mask(
                 &table_schema,
                 parquet_schema,
@@ -406,18 +1200,52 @@ impl FileOpener for PresignedUrlOpener {
             let mut row_indexes = ordering_needs_row_indexes(&requested_ordering)
                 .then(|| RowIndexBuilder::new(builder.metadata().row_groups()));
 
-            // Filter row groups and row indexes if a predicate is provided
+            // Filter row groups and row indexes if a predicate is provided, consulting the bloom
+            // filters of any column the predicate compares for equality
             if let Some(ref predicate) = predicate {
-                builder = builder.with_row_group_filter(predicate, row_indexes.as_mut());
+                let mut bloom_filters = BloomFilters::new();
+                let candidates = bloom_filter_candidates(builder.metadata(), predicate);
+                // Bloom filters are only an optimization, so skip them if the file can't be read
+                // this way (e.g. because it is encrypted)
+                let properties = ReaderProperties::builder()
+                    .set_read_bloom_filter(true)
+                    .build();
+                let options = ReadOptionsBuilder::new()
+                    .with_reader_properties(properties)
+                    .build();
+                let file_reader = (!candidates.is_empty())
+                    .then(|| SerializedFileReader::new_with_options(reader.clone(), options).ok())
+                    .flatten();
+                if let Some(file_reader) = file_reader {
+                    for (row_group, column) in candidates {
+                        let Ok(row_group_reader) = file_reader.get_row_group(row_group) else {
+                            continue;
+                        };
+                        if let Some(bloom_filter) = row_group_reader.get_column_bloom_filter(column)
+                        {
+                            bloom_filters.insert((row_group, column), bloom_filter.clone());
+                        }
+                    }
+                }
+                builder = builder.with_row_group_and_bloom_filter(
+                    predicate,
+                    &bloom_filters,
+                    row_indexes.as_mut(),
+                );
             }
             if let Some(limit) = limit {
                 builder = builder.with_limit(limit)
             }
 
+            let batch_size =
+                budgeted_batch_size(batch_size, memory_budget, builder.metadata(), &indices);
             let reader = builder.with_batch_size(batch_size).build()?;
 
             let mut row_indexes = row_indexes.map(|rb| rb.into_iter());
-            let stream = futures::stream::iter(reader);
+            let stream = match decode_pool {
+                Some(decode_pool) => decode_on_pool(reader, decode_pool),
+                None => futures::stream::iter(reader).map_err(Error::from).boxed(),
+            };
             let stream = stream.map(move |rbr| {
                 fixup_parquet_read(rbr?, &requested_ordering, row_indexes.as_mut())
             });
@@ -432,6 +1260,7 @@ mod tests {
     use std::slice;
 
     use crate::arrow::array::{Array, RecordBatch};
+    use crate::arrow::datatypes::DataType;
 
     use crate::engine::arrow_conversion::TryIntoKernel as _;
     use crate::engine::arrow_data::ArrowEngineData;
@@ -568,7 +1397,7 @@ mod tests {
         ));
 
         let write_metadata = parquet_handler
-            .write_parquet(&Url::parse("memory:///data/").unwrap(), data)
+            .write_parquet(&Url::parse("memory:///data/").unwrap(), data, None)
             .await
             .unwrap();
 
@@ -580,6 +1409,7 @@ mod tests {
                     size,
                 },
             num_records,
+            ..
         } = write_metadata;
         let expected_location = Url::parse("memory:///data/").unwrap();
 
@@ -623,6 +1453,377 @@ mod tests {
         assert_eq!(data[0].num_rows(), 3);
     }
 
+    #[test]
+    fn test_write_parquet_file_with_options() {
+        use crate::arrow::datatypes::Schema as ArrowSchema;
+        use crate::parquet::basic::Compression;
+        use crate::schema::ColumnMetadataKey;
+        use crate::ParquetCompression;
+
+        let store = Arc::new(InMemory::new());
+        let parquet_handler =
+            DefaultParquetHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+
+        // A column mapping physical column, which carries its field ID as metadata
+        let field = Field::new("col-5f422f40", DataType::Int64, true).with_metadata(
+            [(
+                ColumnMetadataKey::ParquetFieldId.as_ref().to_string(),
+                "7".to_string(),
+            )]
+            .into(),
+        );
+        let schema = Arc::new(ArrowSchema::new(vec![field]));
+        let values = Arc::new(Int64Array::from(vec![1, 2, 3, 4, 5]));
+        let data = Box::new(ArrowEngineData::new(
+            RecordBatch::try_new(schema, vec![values]).unwrap(),
+        ));
+
+        let location = Url::parse("memory:///data/file.parquet").unwrap();
+        let options = ParquetWriteOptions::default()
+            .with_compression(ParquetCompression::Zstd)
+            .with_max_row_group_size(2);
+        let ParquetFileMetadata {
+            file_meta,
+            num_records,
+        } = ParquetHandler::write_parquet_file(&parquet_handler, &location, data, &options)
+            .unwrap();
+        assert_eq!(file_meta.location, location);
+        assert_eq!(num_records, 5);
+
+        let path = Path::from_url_path(location.path()).unwrap();
+        let bytes = futures::executor::block_on(async {
+            store.get(&path).await.unwrap().bytes().await.unwrap()
+        });
+        assert_eq!(file_meta.size, bytes.len() as u64);
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(bytes).unwrap();
+        let metadata = builder.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+        for row_group in metadata.row_groups() {
+            assert!(matches!(
+                row_group.column(0).compression(),
+                Compression::ZSTD(_)
+            ));
+        }
+        let column = metadata.file_metadata().schema_descr().column(0);
+        assert_eq!(column.self_type().get_basic_info().id(), 7);
+    }
+
+    #[cfg(feature = "parquet-encryption")]
+    #[tokio::test]
+    async fn test_read_encrypted_parquet_file() {
+        use crate::parquet::arrow::ArrowWriter;
+        use crate::parquet::encryption::encrypt::FileEncryptionProperties;
+        use crate::parquet::errors::{ParquetError, Result as ParquetResult};
+        use crate::parquet::file::properties::WriterProperties;
+
+        const FOOTER_KEY: &[u8] = b"0123456789012345";
+
+        // Stands in for a KMS client, which would unwrap the key identified by the key metadata
+        struct TestKeyRetriever;
+        impl KeyRetriever for TestKeyRetriever {
+            fn retrieve_key(&self, key_metadata: &[u8]) -> ParquetResult<Vec<u8>> {
+                match key_metadata {
+                    b"footer-key" => Ok(FOOTER_KEY.to_vec()),
+                    _ => Err(ParquetError::General("unknown key".to_string())),
+                }
+            }
+        }
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int64Array::from(vec![1, 2, 3])) as Arc<dyn Array>,
+        )])
+        .unwrap();
+        let encryption_properties = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+            .with_footer_key_metadata(b"footer-key".to_vec())
+            .build()
+            .unwrap();
+        let properties = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties)
+            .build();
+        let mut buffer = vec![];
+        let mut writer =
+            ArrowWriter::try_new(&mut buffer, batch.schema(), Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let store = Arc::new(InMemory::new());
+        let size = buffer.len() as u64;
+        store
+            .put(&Path::from("data/encrypted.parquet"), buffer.into())
+            .await
+            .unwrap();
+        let location = Url::parse("memory:///data/encrypted.parquet").unwrap();
+        let files = &[FileMeta::new(location, 0, size)];
+        let physical_schema: SchemaRef = Arc::new(batch.schema().try_into_kernel().unwrap());
+        let read = |handler: &DefaultParquetHandler<TokioBackgroundExecutor>| {
+            handler
+                .read_parquet_files(files, physical_schema.clone(), None)?
+                .map(into_record_batch)
+                .try_collect::<_, Vec<_>, _>()
+        };
+
+        // Without a key retriever, the encrypted file can't be read
+        let handler = DefaultParquetHandler::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        assert!(read(&handler).is_err());
+
+        let handler = handler
+            .with_key_retriever(Arc::new(TestKeyRetriever))
+            .unwrap();
+        assert_eq!(read(&handler).unwrap(), vec![batch]);
+    }
+
+    #[tokio::test]
+    async fn test_footer_cache_shared_across_reads() {
+        use crate::parquet::arrow::ArrowWriter;
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int64Array::from(vec![1, 2, 3])) as Arc<dyn Array>,
+        )])
+        .unwrap();
+        let store = Arc::new(InMemory::new());
+        let mut files = vec![];
+        for name in ["a.parquet", "b.parquet"] {
+            let mut buffer = vec![];
+            let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+            let size = buffer.len() as u64;
+            store
+                .put(&Path::from(format!("data/{name}")), buffer.into())
+                .await
+                .unwrap();
+            let location = Url::parse(&format!("memory:///data/{name}")).unwrap();
+            files.push(FileMeta::new(location, 0, size));
+        }
+        let physical_schema: SchemaRef = Arc::new(batch.schema().try_into_kernel().unwrap());
+
+        // Two handlers share a cache that only fits one footer, without prefetching so that the
+        // cache contents are deterministic
+        let footer_cache = Arc::new(ParquetFooterCache::new(1));
+        let new_handler = || {
+            DefaultParquetHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()))
+                .with_footer_cache(footer_cache.clone())
+                .with_footer_prefetch(0)
+        };
+        let read = |handler: &DefaultParquetHandler<TokioBackgroundExecutor>, file: &FileMeta| {
+            handler
+                .read_parquet_files(slice::from_ref(file), physical_schema.clone(), None)
+                .unwrap()
+                .map(into_record_batch)
+                .try_collect::<_, Vec<_>, _>()
+                .unwrap()
+        };
+
+        assert!(footer_cache.is_empty());
+        assert_eq!(read(&new_handler(), &files[0]), vec![batch.clone()]);
+        assert_eq!(footer_cache.len(), 1);
+        assert!(footer_cache.get(&files[0]).is_some());
+
+        // Reading another file evicts the least recently used footer
+        assert_eq!(read(&new_handler(), &files[1]), vec![batch.clone()]);
+        assert_eq!(footer_cache.len(), 1);
+        assert!(footer_cache.get(&files[0]).is_none());
+        assert!(footer_cache.get(&files[1]).is_some());
+
+        // A file of a different size (e.g. rewritten in place) is not served from the cache
+        let resized = FileMeta::new(files[1].location.clone(), 0, files[1].size + 1);
+        assert!(footer_cache.get(&resized).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_batch_size_and_memory_budget() {
+        use crate::parquet::arrow::ArrowWriter;
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int64Array::from(vec![1, 2, 3])) as Arc<dyn Array>,
+        )])
+        .unwrap();
+        let mut buffer = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let size = buffer.len() as u64;
+        let store = Arc::new(InMemory::new());
+        store
+            .put(&Path::from("data/a.parquet"), buffer.into())
+            .await
+            .unwrap();
+        let files = [FileMeta::new(
+            Url::parse("memory:///data/a.parquet").unwrap(),
+            0,
+            size,
+        )];
+        let physical_schema: SchemaRef = Arc::new(batch.schema().try_into_kernel().unwrap());
+
+        let handler =
+            DefaultParquetHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let batch_sizes = |handler: DefaultParquetHandler<TokioBackgroundExecutor>| {
+            handler
+                .read_parquet_files(&files, physical_schema.clone(), None)
+                .unwrap()
+                .map(|data| into_record_batch(data).map(|batch| batch.num_rows()))
+                .try_collect::<_, Vec<_>, _>()
+                .unwrap()
+        };
+
+        assert_eq!(batch_sizes(handler.clone()), vec![3]);
+        assert_eq!(batch_sizes(handler.clone().with_batch_size(2)), vec![2, 1]);
+        // A budget smaller than a single row still reads one row per batch
+        assert_eq!(batch_sizes(handler.with_memory_budget(1)), vec![1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_read_with_decode_pool() {
+        use crate::arrow::array::AsArray as _;
+        use crate::arrow::datatypes::Int64Type;
+        use crate::parquet::arrow::ArrowWriter;
+        use crate::parquet::file::properties::WriterProperties;
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int64Array::from_iter_values(0..5)) as Arc<dyn Array>,
+        )])
+        .unwrap();
+        let mut buffer = vec![];
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(&mut buffer, batch.schema(), Some(properties)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let size = buffer.len() as u64;
+        let store = Arc::new(InMemory::new());
+        store
+            .put(&Path::from("data/a.parquet"), buffer.into())
+            .await
+            .unwrap();
+        let files = [FileMeta::new(
+            Url::parse("memory:///data/a.parquet").unwrap(),
+            0,
+            size,
+        )];
+        let physical_schema: SchemaRef = Arc::new(batch.schema().try_into_kernel().unwrap());
+
+        let decode_pool = Arc::new(DecodePool::try_new(2).unwrap());
+        let handler =
+            DefaultParquetHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()))
+                .with_decode_pool(decode_pool);
+        let batches: Vec<RecordBatch> = handler
+            .read_parquet_files(&files, physical_schema, None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+        // one batch per row group
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect_vec(),
+            vec![2, 2, 1]
+        );
+        let values = batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int64Type>().values().to_vec())
+            .collect_vec();
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_batch_cache() {
+        use crate::parquet::arrow::ArrowWriter;
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int64Array::from(vec![1, 2, 3])) as Arc<dyn Array>,
+        )])
+        .unwrap();
+        let mut buffer = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        let buffer = bytes::Bytes::from(buffer);
+        let store = Arc::new(InMemory::new());
+        let mut files = vec![];
+        for name in [
+            "_delta_log/00000000000000000001.checkpoint.parquet",
+            "a.parquet",
+        ] {
+            store
+                .put(&Path::from(format!("table/{name}")), buffer.clone().into())
+                .await
+                .unwrap();
+            let location = Url::parse(&format!("memory:///table/{name}")).unwrap();
+            files.push(FileMeta::new(location, 0, buffer.len() as u64));
+        }
+        let (checkpoint, data_file) = (&files[0], &files[1]);
+        let physical_schema: SchemaRef = Arc::new(batch.schema().try_into_kernel().unwrap());
+
+        let checkpoint_cache = Arc::new(CheckpointBatchCache::default());
+        let handler =
+            DefaultParquetHandler::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()))
+                .with_checkpoint_cache(checkpoint_cache.clone());
+        let read = |file: &FileMeta| {
+            handler
+                .read_parquet_files(slice::from_ref(file), physical_schema.clone(), None)
+                .unwrap()
+                .map(into_record_batch)
+                .try_collect::<_, Vec<_>, _>()
+                .unwrap()
+        };
+        let etag = || async {
+            let path = Path::from_url_path(checkpoint.location.path()).unwrap();
+            store.head(&path).await.unwrap().e_tag.unwrap()
+        };
+
+        // Data files are not cached
+        assert_eq!(read(data_file), vec![batch.clone()]);
+        assert!(checkpoint_cache.is_empty());
+
+        assert_eq!(read(checkpoint), vec![batch.clone()]);
+        assert_eq!(checkpoint_cache.len(), 1);
+        let size = checkpoint_cache.size();
+        assert!(size > 0);
+
+        // Later reads of the unchanged checkpoint are served from the cache
+        let cached = batch.slice(0, 1);
+        checkpoint_cache.insert(
+            checkpoint.location.clone(),
+            etag().await,
+            physical_schema.clone(),
+            None,
+            vec![cached.clone()],
+        );
+        assert_eq!(checkpoint_cache.len(), 1);
+        assert_eq!(read(checkpoint), vec![cached]);
+
+        // An overwritten checkpoint is read again, replacing its stale batches
+        store
+            .put(
+                &Path::from_url_path(checkpoint.location.path()).unwrap(),
+                buffer.into(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(read(checkpoint), vec![batch.clone()]);
+        assert_eq!(checkpoint_cache.len(), 1);
+        assert_eq!(checkpoint_cache.size(), size);
+
+        // Batches that exceed the size budget of the cache are not cached
+        let checkpoint_cache = Arc::new(CheckpointBatchCache::new(1));
+        let handler = handler.with_checkpoint_cache(checkpoint_cache.clone());
+        let batches: Vec<_> = handler
+            .read_parquet_files(slice::from_ref(checkpoint), physical_schema.clone(), None)
+            .unwrap()
+            .map(into_record_batch)
+            .try_collect()
+            .unwrap();
+        assert_eq!(batches, vec![batch]);
+        assert!(checkpoint_cache.is_empty());
+    }
+
     #[tokio::test]
     async fn test_disallow_non_trailing_slash() {
         let store = Arc::new(InMemory::new());
@@ -639,7 +1840,7 @@ mod tests {
 
         assert_result_error_with_message(
             parquet_handler
-                .write_parquet(&Url::parse("memory:///data").unwrap(), data)
+                .write_parquet(&Url::parse("memory:///data").unwrap(), data, None)
                 .await,
             "Generic delta kernel error: Path must end with a trailing slash: memory:///data",
         );
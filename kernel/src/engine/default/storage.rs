@@ -1,11 +1,19 @@
+use object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey};
+use object_store::azure::{AzureConfigKey, MicrosoftAzureBuilder};
+use object_store::gcp::{GoogleCloudStorageBuilder, GoogleConfigKey};
 use object_store::parse_url_opts as parse_url_opts_object_store;
 use object_store::path::Path;
-use object_store::{Error, ObjectStore};
+use object_store::{
+    BackoffConfig, ClientConfigKey, Error, ObjectStore, ObjectStoreScheme, RetryConfig,
+};
+use tracing::warn;
 use url::Url;
 
 use crate::Error as DeltaError;
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
 
 /// Alias for convenience
 type ClosureReturn = Result<(Box<dyn ObjectStore>, Path), Error>;
@@ -36,11 +44,53 @@ pub fn insert_url_handler(
     Ok(())
 }
 
+/// A storage option that disables request signing, to access public data anonymously. Accepts
+/// `true` or `false`, and applies to S3, Azure and GCS URLs.
+pub const ANONYMOUS_STORAGE_OPTION: &str = "anonymous";
+
 /// Parse the given URL options to produce a valid and configured [ObjectStore]
 ///
 /// This function will first attempt to use any schemes registered via [insert_url_handler],
-/// falling back to the default behavior of [object_store::parse_url_opts]
+/// falling back to the default behavior of [object_store::parse_url_opts]. In that case, the
+/// options configure the object store builder for the URL's cloud, accepting the keys of
+/// [AmazonS3ConfigKey], [AzureConfigKey], [GoogleConfigKey] and [ClientConfigKey] respectively,
+/// both with and without their cloud prefix (e.g. `region`, `endpoint`, `sas_token`, or
+/// `service_account_path`), as well as [ANONYMOUS_STORAGE_OPTION]. No settings are read from
+/// environment variables. Options that the builder does not recognize are ignored with a warning.
+///
+/// With the `hdfs` feature, `hdfs://` and `viewfs://` URLs are served by an HDFS object store,
+/// configured with the given options (e.g. `dfs.ha.namenodes.<nameservice>`), unless a handler
+/// is registered for their scheme.
 pub fn parse_url_opts<I, K, V>(url: &Url, options: I) -> Result<(Box<dyn ObjectStore>, Path), Error>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    parse_url_opts_impl(url, options, None)
+}
+
+/// Like [parse_url_opts], but configures the retries and timeouts of S3, Azure and GCS object
+/// stores according to `retry_policy`. Handlers registered via [insert_url_handler] are
+/// responsible for their own retry settings, and ignore `retry_policy`.
+pub fn parse_url_opts_with_retry_policy<I, K, V>(
+    url: &Url,
+    options: I,
+    retry_policy: &RetryPolicy,
+) -> Result<(Box<dyn ObjectStore>, Path), Error>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    parse_url_opts_impl(url, options, Some(retry_policy))
+}
+
+fn parse_url_opts_impl<I, K, V>(
+    url: &Url,
+    options: I,
+    retry_policy: Option<&RetryPolicy>,
+) -> Result<(Box<dyn ObjectStore>, Path), Error>
 where
     I: IntoIterator<Item = (K, V)>,
     K: AsRef<str>,
@@ -57,7 +107,247 @@ where
             return handler(url, options);
         }
     }
-    parse_url_opts_object_store(url, options)
+    #[cfg(feature = "hdfs")]
+    if matches!(url.scheme(), "hdfs" | "viewfs") {
+        return parse_url_opts_hdfs(url, options);
+    }
+    let mut options = translate_storage_options(url, options);
+    let Some(retry_policy) = retry_policy else {
+        return parse_url_opts_object_store(url, options);
+    };
+    if let Some(timeout) = retry_policy.request_timeout {
+        let timeout = format!("{}ms", timeout.as_millis());
+        options.insert("timeout".to_string(), timeout);
+    }
+    let retry_config = retry_policy.retry_config();
+    let (scheme, path) = ObjectStoreScheme::parse(url)?;
+    let store: Box<dyn ObjectStore> = match scheme {
+        ObjectStoreScheme::AmazonS3 => {
+            let builder = AmazonS3Builder::new().with_url(url.as_str());
+            let builder = configure(
+                builder,
+                options,
+                |builder, key: AmazonS3ConfigKey, value| builder.with_config(key, value),
+            );
+            Box::new(builder.with_retry(retry_config).build()?)
+        }
+        ObjectStoreScheme::MicrosoftAzure => {
+            let builder = MicrosoftAzureBuilder::new().with_url(url.as_str());
+            let builder = configure(builder, options, |builder, key: AzureConfigKey, value| {
+                builder.with_config(key, value)
+            });
+            Box::new(builder.with_retry(retry_config).build()?)
+        }
+        ObjectStoreScheme::GoogleCloudStorage => {
+            let builder = GoogleCloudStorageBuilder::new().with_url(url.as_str());
+            let builder = configure(builder, options, |builder, key: GoogleConfigKey, value| {
+                builder.with_config(key, value)
+            });
+            Box::new(builder.with_retry(retry_config).build()?)
+        }
+        _ => return parse_url_opts_object_store(url, options),
+    };
+    Ok((store, path))
+}
+
+// Builds an HDFS object store, passing all options on as Hadoop configuration
+#[cfg(feature = "hdfs")]
+fn parse_url_opts_hdfs<I, K, V>(
+    url: &Url,
+    options: I,
+) -> Result<(Box<dyn ObjectStore>, Path), Error>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    let options = options
+        .into_iter()
+        .map(|(k, v)| (k.as_ref().to_string(), v.into()));
+    let store = hdfs_native_object_store::HdfsObjectStoreBuilder::new()
+        .with_url(url.as_str())
+        .with_config(options)
+        .build()?;
+    let path = Path::parse(url.path())?;
+    Ok((Box::new(store), path))
+}
+
+// Applies the options whose keys `builder` recognizes, like [object_store::parse_url_opts] does
+fn configure<B, K: FromStr>(
+    builder: B,
+    options: HashMap<String, String>,
+    with_config: impl Fn(B, K, String) -> B,
+) -> B {
+    options
+        .into_iter()
+        .fold(builder, |builder, (key, value)| match key.parse() {
+            Ok(key) => with_config(builder, key, value),
+            Err(_) => builder,
+        })
+}
+
+/// Retry and timeout settings for the object stores of the default engine. Failed requests are
+/// retried with exponential backoff, until either `max_retries` or `retry_timeout` is exceeded.
+/// Requests still failing transiently (e.g. with a server error or a timeout) surface as
+/// [`Error::ObjectStoreRetriesExhausted`], see [`Error::is_retryable`].
+///
+/// The defaults match those of the [object_store] crate.
+///
+/// [`Error::ObjectStoreRetriesExhausted`]: crate::Error::ObjectStoreRetriesExhausted
+/// [`Error::is_retryable`]: crate::Error::is_retryable
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of times to retry a request.
+    pub max_retries: usize,
+    /// The delay before the first retry.
+    pub init_backoff: Duration,
+    /// The maximum delay between two retries.
+    pub max_backoff: Duration,
+    /// The factor by which the delay between retries grows.
+    pub backoff_base: f64,
+    /// The maximum time to spend retrying a request.
+    pub retry_timeout: Duration,
+    /// The timeout of each individual request, or `None` to use the object store's default.
+    pub request_timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            init_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(15),
+            backoff_base: 2.0,
+            retry_timeout: Duration::from_secs(3 * 60),
+            request_timeout: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn retry_config(&self) -> RetryConfig {
+        RetryConfig {
+            backoff: BackoffConfig {
+                init_backoff: self.init_backoff,
+                max_backoff: self.max_backoff,
+                base: self.backoff_base,
+            },
+            max_retries: self.max_retries,
+            retry_timeout: self.retry_timeout,
+        }
+    }
+}
+
+// Translates cloud-independent storage options to the keys of the URL's object store builder, and
+// warns about options the builder would silently ignore.
+fn translate_storage_options<K, V>(
+    url: &Url,
+    options: impl IntoIterator<Item = (K, V)>,
+) -> HashMap<String, String>
+where
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    let scheme = ObjectStoreScheme::parse(url).ok().map(|(scheme, _)| scheme);
+    options
+        .into_iter()
+        .map(|(key, value)| {
+            let key = match (key.as_ref(), &scheme) {
+                (ANONYMOUS_STORAGE_OPTION, Some(ObjectStoreScheme::AmazonS3)) => {
+                    "aws_skip_signature"
+                }
+                (ANONYMOUS_STORAGE_OPTION, Some(ObjectStoreScheme::MicrosoftAzure)) => {
+                    "azure_skip_signature"
+                }
+                (ANONYMOUS_STORAGE_OPTION, Some(ObjectStoreScheme::GoogleCloudStorage)) => {
+                    "google_skip_signature"
+                }
+                (key, _) => key,
+            };
+            let known = match scheme {
+                Some(ObjectStoreScheme::AmazonS3) => key.parse::<AmazonS3ConfigKey>().is_ok(),
+                Some(ObjectStoreScheme::MicrosoftAzure) => key.parse::<AzureConfigKey>().is_ok(),
+                Some(ObjectStoreScheme::GoogleCloudStorage) => {
+                    key.parse::<GoogleConfigKey>().is_ok()
+                }
+                Some(ObjectStoreScheme::Http) => key.parse::<ClientConfigKey>().is_ok(),
+                _ => true,
+            };
+            if !known {
+                warn!("Ignoring unknown storage option {key} for {url}");
+            }
+            (key.to_string(), value.into())
+        })
+        .collect()
+}
+
+/// A set of pre-built [ObjectStore] instances, keyed by URL scheme and (optionally) bucket, i.e.
+/// the URL's host. Engines use it to share their existing (e.g. already authenticated) object store
+/// clients with a [`DefaultEngine`], see [`DefaultEngine::try_new_with_registry`].
+///
+/// Registered stores must be rooted at the bucket, like the stores [parse_url_opts] creates: kernel
+/// accesses a URL's object by its path within the store.
+///
+/// [`DefaultEngine`]: super::DefaultEngine
+/// [`DefaultEngine::try_new_with_registry`]: super::DefaultEngine::try_new_with_registry
+#[derive(Debug, Clone, Default)]
+pub struct ObjectStoreRegistry {
+    stores: HashMap<(String, Option<String>), Arc<dyn ObjectStore>>,
+}
+
+impl ObjectStoreRegistry {
+    /// Create a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `store` for all URLs with the given `scheme` (e.g. `s3`) whose bucket does not have
+    /// a store registered by [Self::register_bucket].
+    pub fn register_scheme(&mut self, scheme: impl Into<String>, store: Arc<dyn ObjectStore>) {
+        self.stores.insert((scheme.into(), None), store);
+    }
+
+    /// Register `store` for all URLs with the given `scheme` and `bucket`, e.g. `s3` and
+    /// `my-bucket` for `s3://my-bucket/path/to/table`.
+    pub fn register_bucket(
+        &mut self,
+        scheme: impl Into<String>,
+        bucket: impl Into<String>,
+        store: Arc<dyn ObjectStore>,
+    ) {
+        self.stores
+            .insert((scheme.into(), Some(bucket.into())), store);
+    }
+
+    /// Get the store registered for `url`, preferring a store registered for the URL's bucket
+    /// over one registered for its scheme.
+    pub fn get(&self, url: &Url) -> Option<Arc<dyn ObjectStore>> {
+        let scheme = url.scheme().to_string();
+        let bucket = url
+            .host_str()
+            .map(|host| (scheme.clone(), Some(host.to_string())));
+        bucket
+            .and_then(|key| self.stores.get(&key))
+            .or_else(|| self.stores.get(&(scheme, None)))
+            .cloned()
+    }
+
+    /// Get the store registered for `url`, or construct one with [parse_url_opts] if there is none.
+    pub fn get_or_parse<I, K, V>(
+        &self,
+        url: &Url,
+        options: I,
+    ) -> Result<Arc<dyn ObjectStore>, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        match self.get(url) {
+            Some(store) => Ok(store),
+            None => Ok(parse_url_opts(url, options)?.0.into()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -65,6 +355,7 @@ mod tests {
     use super::*;
 
     use hdfs_native_object_store::HdfsObjectStoreBuilder;
+    use object_store::memory::InMemory;
     use object_store::{self, path::Path};
 
     /// Example funciton of doing testing of a custom [HdfsObjectStore] construction
@@ -121,4 +412,177 @@ mod tests {
             panic!("Expected to get an error when constructing an HdfsObjectStore, but something didn't work as expected! Either the parse_url_opts_hdfs_native function didn't get called, or the hdfs-native-object-store no longer errors when it cannot connect to HDFS");
         }
     }
+
+    #[test]
+    #[cfg(feature = "hdfs")]
+    fn test_parse_url_opts_hdfs() {
+        // Constructing an HdfsObjectStore fails without an HDFS to connect to, so check that the
+        // error came from the HdfsObjectStore
+        let url = Url::parse("viewfs://example").unwrap();
+        match parse_url_opts(&url, HashMap::<String, String>::new()) {
+            Err(Error::Generic { store, .. }) => assert_eq!(store, "HdfsObjectStore"),
+            Err(unexpected) => panic!("Unexpected error happened: {unexpected:?}"),
+            Ok(_) => panic!("Expected an error when connecting to a non-existent HDFS"),
+        }
+    }
+
+    #[test]
+    fn test_translate_storage_options() {
+        let options = HashMap::from([
+            (ANONYMOUS_STORAGE_OPTION, "true"),
+            ("region", "us-west-2"),
+            ("unknown", "x"),
+        ]);
+        let translate = |url: &str| {
+            let url = Url::parse(url).unwrap();
+            let mut options: Vec<_> = translate_storage_options(&url, options.clone())
+                .into_keys()
+                .collect();
+            options.sort();
+            options
+        };
+        assert_eq!(
+            translate("s3://bucket/table"),
+            ["aws_skip_signature", "region", "unknown"]
+        );
+        assert_eq!(
+            translate("gs://bucket/table"),
+            ["google_skip_signature", "region", "unknown"]
+        );
+        assert_eq!(
+            translate("abfss://container@account.dfs.core.windows.net/table"),
+            ["azure_skip_signature", "region", "unknown"]
+        );
+        // Other URLs don't support anonymous access, so the option is passed through unchanged
+        assert_eq!(
+            translate("file:///table"),
+            [ANONYMOUS_STORAGE_OPTION, "region", "unknown"]
+        );
+
+        // The translated options configure the store without needing environment variables
+        let url = Url::parse("s3://bucket/table").unwrap();
+        assert!(parse_url_opts(&url, options).is_ok());
+    }
+
+    #[test]
+    fn test_parse_url_opts_with_retry_policy() {
+        let retry_policy = RetryPolicy {
+            max_retries: 2,
+            request_timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        let options = HashMap::from([("region", "us-west-2"), (ANONYMOUS_STORAGE_OPTION, "true")]);
+        for url in [
+            "s3://bucket/table",
+            "gs://bucket/table",
+            "abfss://container@account.dfs.core.windows.net/table",
+            "file:///table",
+        ] {
+            let url = Url::parse(url).unwrap();
+            let (_, path) =
+                parse_url_opts_with_retry_policy(&url, options.clone(), &retry_policy).unwrap();
+            assert_eq!(path.as_ref(), "table");
+        }
+    }
+
+    /// GET a file from an HTTP server answering the requests with the given `statuses`, in order,
+    /// with `max_retries` retries, and return the error converted to a kernel error.
+    fn get_with_statuses(statuses: &'static [&'static str], max_retries: usize) -> DeltaError {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        if statuses.is_empty() {
+            // refuse connections
+            drop(listener);
+        } else {
+            std::thread::spawn(move || {
+                for status in statuses {
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream);
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        line.clear();
+                    }
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    );
+                    reader.get_mut().write_all(response.as_bytes()).unwrap();
+                }
+            });
+        }
+        let retry_config = RetryConfig {
+            max_retries,
+            backoff: BackoffConfig {
+                init_backoff: Duration::from_millis(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let store = object_store::http::HttpBuilder::new()
+            .with_url(url)
+            .with_client_options(object_store::ClientOptions::new().with_allow_http(true))
+            .with_retry(retry_config)
+            .build()
+            .unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let err = runtime
+            .block_on(store.get(&Path::from("file")))
+            .unwrap_err();
+        err.into()
+    }
+
+    #[test]
+    fn test_transient_errors_are_retryable() {
+        // the final failure of the request decides whether it is transient
+        let err = get_with_statuses(&["503 Service Unavailable"; 3], 2);
+        assert!(
+            matches!(err, DeltaError::ObjectStoreRetriesExhausted(_)),
+            "{err}"
+        );
+        assert!(err.is_retryable());
+        let err = get_with_statuses(&["503 Service Unavailable", "400 Bad Request"], 2);
+        assert!(matches!(err, DeltaError::ObjectStore(_)), "{err}");
+        assert!(!err.is_retryable());
+        let err = get_with_statuses(&["429 Too Many Requests", "403 Forbidden"], 2);
+        assert!(!err.is_retryable(), "{err}");
+
+        // without retries, transient failures are still recognized
+        let err = get_with_statuses(&["500 Internal Server Error"], 0);
+        assert!(err.is_retryable(), "{err}");
+
+        // as are transport errors, e.g. a server that doesn't accept connections
+        let err = get_with_statuses(&[], 0);
+        assert!(err.is_retryable(), "{err}");
+
+        let not_found = Error::NotFound {
+            path: "table/_delta_log".into(),
+            source: "not found".into(),
+        };
+        assert!(!DeltaError::from(not_found).is_retryable());
+    }
+
+    #[test]
+    fn test_object_store_registry() {
+        let scheme_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let bucket_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut registry = ObjectStoreRegistry::new();
+        registry.register_scheme("s3", scheme_store.clone());
+        registry.register_bucket("s3", "special", bucket_store.clone());
+
+        let get = |url: &str| registry.get(&Url::parse(url).unwrap());
+        assert!(Arc::ptr_eq(
+            &get("s3://special/table").unwrap(),
+            &bucket_store
+        ));
+        assert!(Arc::ptr_eq(
+            &get("s3://other/table").unwrap(),
+            &scheme_store
+        ));
+        assert!(get("gs://special/table").is_none());
+
+        // Unregistered URLs fall back to constructing a store
+        let url = Url::parse("memory:///table").unwrap();
+        let options: HashMap<String, String> = HashMap::default();
+        assert!(registry.get_or_parse(&url, options).is_ok());
+    }
 }
@@ -0,0 +1,379 @@
+//! An [`Engine`] that keeps all of its files in memory, for unit-testing code that integrates with
+//! the kernel without touching the local filesystem or cloud storage. Enable it with the
+//! `test-utils` feature.
+//!
+//! Files live in a map from their [`Url`] to their contents, which tests can populate and inspect
+//! with [`InMemoryEngine::put`] and [`InMemoryEngine::get`]. Any URL scheme is accepted, e.g.
+//! `memory:///my_table/`. JSON and Parquet files are read and written with arrow, like the
+//! [`SyncEngine`](super::sync::SyncEngine) does for local files.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use bytes::{Buf as _, Bytes};
+use url::Url;
+
+use super::arrow_expression::ArrowEvaluationHandler;
+use super::arrow_utils::{parse_json as arrow_parse_json, to_json_bytes, to_parquet_bytes};
+use super::sync::json::try_create_from_json;
+use super::sync::parquet::try_create_from_parquet;
+use super::sync::read_files;
+use crate::schema::SchemaRef;
+use crate::utils::current_time_ms;
+use crate::{
+    DeltaResult, Engine, EngineData, Error, EvaluationHandler, FileDataReadResultIterator,
+    FileMeta, FileSlice, JsonHandler, ParquetFileMetadata, ParquetHandler, ParquetWriteOptions,
+    PredicateRef, StorageHandler,
+};
+
+#[derive(Debug, Clone)]
+struct InMemoryFile {
+    data: Bytes,
+    last_modified: i64,
+}
+
+/// The files of an [`InMemoryEngine`], shared by its handlers.
+#[derive(Debug, Default)]
+struct FileMap(RwLock<BTreeMap<Url, InMemoryFile>>);
+
+impl FileMap {
+    // The map is always left in a consistent state, so a poisoned lock is still usable
+    fn read(&self) -> RwLockReadGuard<'_, BTreeMap<Url, InMemoryFile>> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<Url, InMemoryFile>> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, location: &Url) -> DeltaResult<Bytes> {
+        self.read()
+            .get(location)
+            .map(|file| file.data.clone())
+            .ok_or_else(|| Error::file_not_found(location.as_str()))
+    }
+
+    /// Stores `data` at `location`, failing with [`Error::FileAlreadyExists`] if a file exists at
+    /// `location` and `overwrite` is false.
+    fn put(&self, location: &Url, data: Bytes, overwrite: bool) -> DeltaResult<FileMeta> {
+        let file = InMemoryFile {
+            data,
+            last_modified: current_time_ms()?,
+        };
+        let meta = FileMeta::new(location.clone(), file.last_modified, file.data.len() as u64);
+        let mut files = self.write();
+        if !overwrite && files.contains_key(location) {
+            return Err(Error::FileAlreadyExists(location.to_string()));
+        }
+        files.insert(location.clone(), file);
+        Ok(meta)
+    }
+}
+
+/// An [`Engine`] whose storage is an in-memory map of files. See the [module docs](self).
+///
+/// Clones of an `InMemoryEngine` share the same files.
+#[derive(Debug, Clone)]
+pub struct InMemoryEngine {
+    files: Arc<FileMap>,
+    storage_handler: Arc<InMemoryStorageHandler>,
+    json_handler: Arc<InMemoryJsonHandler>,
+    parquet_handler: Arc<InMemoryParquetHandler>,
+    evaluation_handler: Arc<ArrowEvaluationHandler>,
+}
+
+impl Default for InMemoryEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryEngine {
+    /// Create an engine without any files.
+    pub fn new() -> Self {
+        let files = Arc::new(FileMap::default());
+        InMemoryEngine {
+            storage_handler: Arc::new(InMemoryStorageHandler(files.clone())),
+            json_handler: Arc::new(InMemoryJsonHandler(files.clone())),
+            parquet_handler: Arc::new(InMemoryParquetHandler(files.clone())),
+            evaluation_handler: Arc::new(ArrowEvaluationHandler {}),
+            files,
+        }
+    }
+
+    /// Store `data` as the file at `location`, replacing any existing file.
+    pub fn put(&self, location: &Url, data: impl Into<Bytes>) -> DeltaResult<()> {
+        self.files.put(location, data.into(), true)?;
+        Ok(())
+    }
+
+    /// Get the contents of the file at `location`, if it exists.
+    pub fn get(&self, location: &Url) -> Option<Bytes> {
+        self.files.get(location).ok()
+    }
+
+    /// The locations of all files, in sorted order.
+    pub fn locations(&self) -> Vec<Url> {
+        self.files.read().keys().cloned().collect()
+    }
+}
+
+impl Engine for InMemoryEngine {
+    fn evaluation_handler(&self) -> Arc<dyn EvaluationHandler> {
+        self.evaluation_handler.clone()
+    }
+
+    fn storage_handler(&self) -> Arc<dyn StorageHandler> {
+        self.storage_handler.clone()
+    }
+
+    fn json_handler(&self) -> Arc<dyn JsonHandler> {
+        self.json_handler.clone()
+    }
+
+    fn parquet_handler(&self) -> Arc<dyn ParquetHandler> {
+        self.parquet_handler.clone()
+    }
+}
+
+#[derive(Debug)]
+struct InMemoryStorageHandler(Arc<FileMap>);
+
+impl StorageHandler for InMemoryStorageHandler {
+    /// List the files in the same directory that are lexicographically greater than the given
+    /// `path`, or all files in the directory if `path` ends with a `/`. The result is sorted by
+    /// file name.
+    fn list_from(
+        &self,
+        path: &Url,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>> {
+        let (dir, min_file_name) = match path.as_str().rsplit_once('/') {
+            Some((dir, file_name)) => (format!("{dir}/"), file_name),
+            None => {
+                return Err(Error::generic(format!(
+                    "Invalid path for list_from: {path}"
+                )))
+            }
+        };
+        let files: Vec<_> = self
+            .0
+            .read()
+            .iter()
+            .filter(|(location, _)| {
+                location
+                    .as_str()
+                    .strip_prefix(&dir)
+                    .is_some_and(|name| !name.contains('/') && name > min_file_name)
+            })
+            .map(|(location, file)| {
+                let size = file.data.len() as u64;
+                Ok(FileMeta::new(location.clone(), file.last_modified, size))
+            })
+            .collect();
+        Ok(Box::new(files.into_iter()))
+    }
+
+    fn read_files(
+        &self,
+        files: Vec<FileSlice>,
+    ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
+        let map = self.0.clone();
+        let iter = files.into_iter().map(move |(location, range)| {
+            let data = map.get(&location)?;
+            Ok(match range {
+                Some(range) => {
+                    let end =
+                        usize::try_from(range.end).map_or(data.len(), |end| end.min(data.len()));
+                    let start = usize::try_from(range.start).map_or(end, |start| start.min(end));
+                    data.slice(start..end)
+                }
+                None => data,
+            })
+        });
+        Ok(Box::new(iter))
+    }
+
+    /// Delete the given files. Missing files are ignored.
+    fn delete(&self, files: Vec<Url>) -> DeltaResult<()> {
+        let mut map = self.0.write();
+        for location in files {
+            map.remove(&location);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct InMemoryJsonHandler(Arc<FileMap>);
+
+impl JsonHandler for InMemoryJsonHandler {
+    fn parse_json(
+        &self,
+        json_strings: Box<dyn EngineData>,
+        output_schema: SchemaRef,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        arrow_parse_json(json_strings, output_schema)
+    }
+
+    fn read_json_files(
+        &self,
+        files: &[FileMeta],
+        schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let map = self.0.clone();
+        read_files(
+            files,
+            schema,
+            predicate,
+            move |location| map.get(location),
+            |data: Bytes, schema, arrow_schema, predicate| {
+                try_create_from_json(data.reader(), schema, arrow_schema, predicate)
+            },
+        )
+    }
+
+    fn write_json_file(
+        &self,
+        path: &Url,
+        data: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+        overwrite: bool,
+    ) -> DeltaResult<()> {
+        let buffer = to_json_bytes(data)?;
+        self.0.put(path, buffer.into(), overwrite)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct InMemoryParquetHandler(Arc<FileMap>);
+
+impl ParquetHandler for InMemoryParquetHandler {
+    fn read_parquet_files(
+        &self,
+        files: &[FileMeta],
+        schema: SchemaRef,
+        predicate: Option<PredicateRef>,
+    ) -> DeltaResult<FileDataReadResultIterator> {
+        let map = self.0.clone();
+        read_files(
+            files,
+            schema,
+            predicate,
+            move |location| map.get(location),
+            try_create_from_parquet::<Bytes>,
+        )
+    }
+
+    fn write_parquet_file(
+        &self,
+        location: &Url,
+        data: Box<dyn EngineData>,
+        options: &ParquetWriteOptions,
+    ) -> DeltaResult<ParquetFileMetadata> {
+        let (buffer, num_records) = to_parquet_bytes(data, options)?;
+        // data files are never overwritten
+        let file_meta = self.0.put(location, buffer.into(), false)?;
+        Ok(ParquetFileMetadata {
+            file_meta,
+            num_records: num_records as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrow::array::{Int64Array, RecordBatch};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::engine::tests::test_arrow_engine;
+    use crate::schema::{DataType, StructField, StructType};
+    use crate::Snapshot;
+
+    #[test]
+    fn test_in_memory_engine() {
+        let engine = InMemoryEngine::new();
+        test_arrow_engine(&engine, &Url::parse("memory:///").unwrap());
+    }
+
+    #[test]
+    fn test_put_get_and_delete() -> DeltaResult<()> {
+        let engine = InMemoryEngine::new();
+        let url = Url::parse("memory:///dir/file")?;
+        assert_eq!(engine.get(&url), None);
+        engine.put(&url, "hello world")?;
+        assert_eq!(engine.get(&url), Some(Bytes::from("hello world")));
+
+        let storage = engine.storage_handler();
+        let slices: Vec<_> = storage
+            .read_files(vec![(url.clone(), Some(6..11)), (url.clone(), None)])?
+            .collect::<DeltaResult<_>>()?;
+        assert_eq!(
+            slices,
+            vec![Bytes::from("world"), Bytes::from("hello world")]
+        );
+
+        storage.delete(vec![url.clone()])?;
+        assert_eq!(engine.locations(), Vec::<Url>::new());
+        let missing = storage.read_files(vec![(url, None)])?.next().unwrap();
+        assert!(matches!(missing, Err(Error::FileNotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_and_read_parquet_file() -> DeltaResult<()> {
+        let engine = InMemoryEngine::new();
+        let url = Url::parse("memory:///table/part-0.parquet")?;
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "id",
+                ArrowDataType::Int64,
+                true,
+            )])),
+            vec![Arc::new(Int64Array::from(vec![1, 2, 3]))],
+        )?;
+        let parquet = engine.parquet_handler();
+        let data = Box::new(ArrowEngineData::new(batch.clone()));
+        let written = parquet.write_parquet_file(&url, data, &Default::default())?;
+        assert_eq!(written.num_records, 3);
+        assert_eq!(engine.locations(), vec![url.clone()]);
+
+        // data files are never overwritten
+        let data = Box::new(ArrowEngineData::new(batch.clone()));
+        let res = parquet.write_parquet_file(&url, data, &Default::default());
+        assert!(matches!(res, Err(Error::FileAlreadyExists(_))));
+
+        let schema = Arc::new(StructType::try_new([StructField::nullable(
+            "id",
+            DataType::LONG,
+        )])?);
+        let batches: Vec<RecordBatch> = parquet
+            .read_parquet_files(&[written.file_meta], schema, None)?
+            .map(|data| Ok(ArrowEngineData::try_from_engine_data(data?)?.into()))
+            .collect::<DeltaResult<_>>()?;
+        assert_eq!(batches, vec![batch]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_of_in_memory_table() -> DeltaResult<()> {
+        let engine = InMemoryEngine::new();
+        let table_root = Url::parse("memory:///table/")?;
+        let commit = [
+            r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":2}}"#,
+            r#"{"metaData":{"id":"test","format":{"provider":"parquet","options":{}},"schemaString":"{\"type\":\"struct\",\"fields\":[{\"name\":\"id\",\"type\":\"long\",\"nullable\":true,\"metadata\":{}}]}","partitionColumns":[],"configuration":{},"createdTime":1677811175819}}"#,
+        ]
+        .join("\n");
+        engine.put(
+            &table_root.join("_delta_log/00000000000000000000.json")?,
+            commit,
+        )?;
+
+        let snapshot = Snapshot::builder_for(table_root).build(&engine)?;
+        assert_eq!(snapshot.version(), 0);
+        let expected = StructType::try_new([StructField::nullable("id", DataType::LONG)])?;
+        assert_eq!(snapshot.schema().as_ref(), &expected);
+        Ok(())
+    }
+}
@@ -5,26 +5,39 @@
 #[cfg(feature = "arrow-conversion")]
 pub mod arrow_conversion;
 
-#[cfg(all(feature = "arrow-expression", feature = "default-engine-base"))]
+#[cfg(all(
+    feature = "arrow-expression",
+    any(feature = "default-engine-base", feature = "sync-engine")
+))]
 pub mod arrow_expression;
 #[cfg(feature = "arrow-expression")]
 pub(crate) mod arrow_utils;
+#[cfg(feature = "arrow-expression")]
+pub(crate) mod arrow_variant;
 #[cfg(feature = "internal-api")]
 pub use self::arrow_utils::{parse_json, to_json_bytes};
 
+#[cfg(feature = "datafusion")]
+pub mod datafusion;
+
 #[cfg(feature = "default-engine-base")]
 pub mod default;
 
-#[cfg(test)]
-pub(crate) mod sync;
+#[cfg(feature = "sync-engine")]
+pub mod sync;
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(feature = "test-utils")]
+pub mod in_memory;
+
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
 pub mod arrow_data;
-#[cfg(feature = "default-engine-base")]
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
 pub(crate) mod arrow_get_data;
-#[cfg(feature = "default-engine-base")]
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+pub(crate) mod arrow_stats;
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
 pub(crate) mod ensure_data_types;
-#[cfg(feature = "default-engine-base")]
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
 pub mod parquet_row_group_skipping;
 
 #[cfg(test)]
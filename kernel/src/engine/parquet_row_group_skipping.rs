@@ -1,14 +1,27 @@
-//! An implementation of parquet row group skipping using data skipping predicates over footer stats.
+//! An implementation of parquet row group skipping using data skipping predicates over footer
+//! stats (and bloom filters, if provided), and of page skipping using the same predicates over the
+//! page index (if loaded).
 use crate::engine::arrow_utils::RowIndexBuilder;
-use crate::expressions::{ColumnName, DecimalData, Predicate, Scalar};
+use crate::expressions::{
+    BinaryPredicate, BinaryPredicateOp, ColumnName, DecimalData, Expression, JunctionPredicate,
+    JunctionPredicateOp, Predicate, Scalar,
+};
 use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
-use crate::parquet::arrow::arrow_reader::ArrowReaderBuilder;
-use crate::parquet::file::metadata::RowGroupMetaData;
+use crate::parquet::arrow::arrow_reader::{ArrowReaderBuilder, RowSelection, RowSelector};
+use crate::parquet::basic::Type as PhysicalType;
+use crate::parquet::bloom_filter::Sbbf;
+use crate::parquet::data_type::{
+    BoolType, ByteArrayType, DataType as ParquetDataType, DoubleType, FixedLenByteArrayType,
+    FloatType, Int32Type, Int64Type,
+};
+use crate::parquet::file::metadata::{ParquetMetaData, RowGroupMetaData};
+use crate::parquet::file::page_index::index::{Index, NativeIndex};
 use crate::parquet::file::statistics::Statistics;
 use crate::parquet::schema::types::ColumnDescPtr;
 use crate::schema::{DataType, DecimalType, PrimitiveType};
 use chrono::{DateTime, Days};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use tracing::debug;
 
 #[cfg(test)]
@@ -19,13 +32,32 @@ pub(crate) trait ParquetRowGroupSkipping {
     /// Instructs the parquet reader to perform row group skipping, eliminating any row group whose
     /// stats prove that none of the group's rows can satisfy the given `predicate`.
     ///
+    /// If the reader metadata includes the page index (see `ArrowReaderOptions::with_page_index`),
+    /// the predicate is also evaluated against the per-page stats of each surviving row group, and a
+    /// row selection is installed that skips every page whose stats prove that none of its rows can
+    /// satisfy the predicate.
+    ///
     /// If a [`RowIndexBuilder`] is provided, it will be updated to only include row indices of the
-    /// row groups that survived the filter.
+    /// row groups (and rows) that survived the filter.
+    // Only used by the sync engine, since the default engine also uses bloom filters
+    #[cfg_attr(not(feature = "sync-engine"), allow(dead_code))]
     fn with_row_group_filter(
         self,
         predicate: &Predicate,
         row_indexes: Option<&mut RowIndexBuilder>,
     ) -> Self;
+
+    /// Like [`Self::with_row_group_filter`], but additionally eliminates any row group whose bloom
+    /// filters prove that none of the group's rows can satisfy an equality or IN-list `predicate`.
+    ///
+    /// Fetching bloom filters requires IO, so the caller is responsible for reading them (see
+    /// [`bloom_filter_candidates`]). Row groups with no bloom filter are unaffected.
+    fn with_row_group_and_bloom_filter(
+        self,
+        predicate: &Predicate,
+        bloom_filters: &BloomFilters,
+        row_indexes: Option<&mut RowIndexBuilder>,
+    ) -> Self;
 }
 impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
     fn with_row_group_filter(
@@ -33,6 +65,16 @@ impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
         predicate: &Predicate,
         row_indexes: Option<&mut RowIndexBuilder>,
     ) -> Self {
+        self.with_row_group_and_bloom_filter(predicate, &BloomFilters::new(), row_indexes)
+    }
+
+    fn with_row_group_and_bloom_filter(
+        self,
+        predicate: &Predicate,
+        bloom_filters: &BloomFilters,
+        row_indexes: Option<&mut RowIndexBuilder>,
+    ) -> Self {
+        let field_indices = compute_field_indices(self.parquet_schema().columns(), predicate);
         let ordinals: Vec<_> = self
             .metadata()
             .row_groups()
@@ -40,14 +82,361 @@ impl<T> ParquetRowGroupSkipping for ArrowReaderBuilder<T> {
             .enumerate()
             .filter_map(|(ordinal, row_group)| {
                 // If the group survives the filter, return Some(ordinal) so filter_map keeps it.
-                RowGroupFilter::apply(row_group, predicate).then_some(ordinal)
+                let keep = RowGroupFilter::apply(row_group, predicate)
+                    && !BloomFilterSkipping::new(ordinal, row_group, &field_indices, bloom_filters)
+                        .excludes(predicate);
+                keep.then_some(ordinal)
             })
             .collect();
         debug!("with_row_group_filter({predicate:#?}) = {ordinals:?})");
+        let selectors = select_pages(self.metadata(), &ordinals, predicate);
+        let mut builder = self;
         if let Some(row_indexes) = row_indexes {
             row_indexes.select_row_groups(&ordinals);
+            if let Some(ref selectors) = selectors {
+                row_indexes.select_rows(selectors);
+            }
+        }
+        if let Some(selectors) = selectors {
+            builder = builder.with_row_selection(RowSelection::from(selectors));
+        }
+        builder.with_row_groups(ordinals)
+    }
+}
+
+/// Bloom filters of parquet column chunks, keyed by row group ordinal and (leaf) column index.
+pub(crate) type BloomFilters = HashMap<(usize, usize), Sbbf>;
+
+/// Returns the row group ordinal and column index of every column chunk whose bloom filter could
+/// help to skip a row group that survives stats-based skipping, because the predicate compares the
+/// column for equality with (or membership in a list of) literal values.
+#[cfg_attr(not(feature = "default-engine-base"), allow(dead_code))]
+pub(crate) fn bloom_filter_candidates(
+    metadata: &ParquetMetaData,
+    predicate: &Predicate,
+) -> Vec<(usize, usize)> {
+    let mut columns = HashSet::new();
+    collect_equality_columns(predicate, &mut columns);
+    if columns.is_empty() {
+        return vec![];
+    }
+    let column_indices: Vec<_> = metadata
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, f)| columns.contains(f.path().parts()).then_some(i))
+        .collect();
+    metadata
+        .row_groups()
+        .iter()
+        .enumerate()
+        .filter(|(_, row_group)| RowGroupFilter::apply(row_group, predicate))
+        .flat_map(|(ordinal, row_group)| {
+            column_indices
+                .iter()
+                .filter(|&&i| row_group.column(i).bloom_filter_offset().is_some())
+                .map(move |&i| (ordinal, i))
+        })
+        .collect()
+}
+
+/// Collects the columns that are compared for equality with literal values anywhere in the
+/// predicate (excluding the children of NOT, which can never be proven false by a bloom filter).
+#[cfg_attr(not(feature = "default-engine-base"), allow(dead_code))]
+fn collect_equality_columns<'a>(predicate: &'a Predicate, columns: &mut HashSet<&'a ColumnName>) {
+    match predicate {
+        Predicate::Junction(JunctionPredicate { preds, .. }) => {
+            for pred in preds {
+                collect_equality_columns(pred, columns);
+            }
+        }
+        Predicate::Binary(pred) => {
+            if let Some((col, _)) = equality_values(pred) {
+                columns.insert(col);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// If the predicate is `<col> = <value>`, `<value> = <col>` or `<col> IN (<values>)`, returns the
+/// column and the value(s) it is compared against.
+fn equality_values(pred: &BinaryPredicate) -> Option<(&ColumnName, &[Scalar])> {
+    use Expression::{Column, Literal};
+    match (&pred.op, pred.left.as_ref(), pred.right.as_ref()) {
+        (BinaryPredicateOp::Equal, Column(col), Literal(value))
+        | (BinaryPredicateOp::Equal, Literal(value), Column(col)) => {
+            Some((col, std::slice::from_ref(value)))
+        }
+        (BinaryPredicateOp::In, Column(col), Literal(Scalar::Array(values))) => {
+            #[allow(deprecated)]
+            let values = values.array_elements();
+            Some((col, values))
+        }
+        _ => None,
+    }
+}
+
+/// Uses the bloom filters of a row group's column chunks to prove that equality predicates cannot
+/// be satisfied by any row of the row group.
+struct BloomFilterSkipping<'a> {
+    ordinal: usize,
+    row_group: &'a RowGroupMetaData,
+    field_indices: &'a HashMap<ColumnName, usize>,
+    bloom_filters: &'a BloomFilters,
+}
+
+impl<'a> BloomFilterSkipping<'a> {
+    fn new(
+        ordinal: usize,
+        row_group: &'a RowGroupMetaData,
+        field_indices: &'a HashMap<ColumnName, usize>,
+        bloom_filters: &'a BloomFilters,
+    ) -> Self {
+        Self {
+            ordinal,
+            row_group,
+            field_indices,
+            bloom_filters,
+        }
+    }
+
+    /// Returns true if the bloom filters prove that no row of the row group satisfies `predicate`.
+    /// A return value of false means the predicate may or may not be satisfied.
+    fn excludes(&self, predicate: &Predicate) -> bool {
+        match predicate {
+            Predicate::Junction(JunctionPredicate { op, preds }) => match op {
+                JunctionPredicateOp::And => preds.iter().any(|pred| self.excludes(pred)),
+                JunctionPredicateOp::Or => preds.iter().all(|pred| self.excludes(pred)),
+            },
+            Predicate::Binary(pred) => equality_values(pred).is_some_and(|(col, values)| {
+                values.iter().all(|value| self.excludes_value(col, value))
+            }),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the column's bloom filter proves that no row contains the value.
+    fn excludes_value(&self, col: &ColumnName, value: &Scalar) -> bool {
+        let Some(&i) = self.field_indices.get(col) else {
+            return false;
+        };
+        let Some(sbbf) = self.bloom_filters.get(&(self.ordinal, i)) else {
+            return false;
+        };
+        // Bloom filters hash the plain-encoded physical value, so the literal must first be
+        // converted to the column's physical type. Types whose physical representation is
+        // ambiguous (e.g. decimals, timestamps, floats) are conservatively never skipped.
+        let physical_type = self.row_group.column(i).column_type();
+        let found = match (value, physical_type) {
+            (Scalar::Byte(v), PhysicalType::INT32) => sbbf.check(&i32::from(*v)),
+            (Scalar::Short(v), PhysicalType::INT32) => sbbf.check(&i32::from(*v)),
+            (Scalar::Integer(v), PhysicalType::INT32) => sbbf.check(v),
+            (Scalar::Date(v), PhysicalType::INT32) => sbbf.check(v),
+            (Scalar::Long(v), PhysicalType::INT64) => sbbf.check(v),
+            (Scalar::Long(v), PhysicalType::INT32) => match i32::try_from(*v) {
+                Ok(v) => sbbf.check(&v),
+                Err(_) => false, // an INT32 column cannot contain an out of range value
+            },
+            (Scalar::String(v), PhysicalType::BYTE_ARRAY) => sbbf.check(&v.as_str()),
+            (Scalar::Binary(v), PhysicalType::BYTE_ARRAY) => sbbf.check(v),
+            _ => return false,
+        };
+        !found
+    }
+}
+
+/// Evaluates the predicate against the page index of each of the given row groups, returning the
+/// rows to read (relative to the selected row groups) if any page could be skipped. Returns `None`
+/// if the page index was not loaded or no page could be skipped.
+fn select_pages(
+    metadata: &ParquetMetaData,
+    ordinals: &[usize],
+    predicate: &Predicate,
+) -> Option<Vec<RowSelector>> {
+    let column_index = metadata.column_index()?;
+    let offset_index = metadata.offset_index()?;
+    let field_indices =
+        compute_field_indices(metadata.file_metadata().schema_descr().columns(), predicate);
+    let mut selectors: Vec<RowSelector> = vec![];
+    for &ordinal in ordinals {
+        let num_rows = metadata.row_group(ordinal).num_rows();
+        let pages: HashMap<_, _> = field_indices
+            .iter()
+            .filter_map(|(col, &i)| {
+                let stats = page_stats(column_index.get(ordinal)?.get(i)?)?;
+                let first_rows: Vec<_> = offset_index
+                    .get(ordinal)?
+                    .get(i)?
+                    .page_locations()
+                    .iter()
+                    .map(|page| page.first_row_index)
+                    .collect();
+                (stats.len() == first_rows.len())
+                    .then_some((col, ColumnPages { first_rows, stats }))
+            })
+            .collect();
+
+        // Split the row group at every page boundary of every referenced column, so that each
+        // segment falls within exactly one page of each column.
+        let mut boundaries: Vec<_> = pages
+            .values()
+            .flat_map(|pages| pages.first_rows.iter().copied())
+            .chain([0, num_rows])
+            .filter(|row| (0..=num_rows).contains(row))
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        for segment in boundaries.windows(2) {
+            let row_count = (segment[1] - segment[0]) as usize;
+            let selector =
+                if PageFilter::new(&pages, segment[0]..segment[1], num_rows).apply(predicate) {
+                    RowSelector::select(row_count)
+                } else {
+                    RowSelector::skip(row_count)
+                };
+            match selectors.last_mut() {
+                Some(last) if last.skip == selector.skip => last.row_count += row_count,
+                _ => selectors.push(selector),
+            }
         }
-        self.with_row_groups(ordinals)
+    }
+    debug!("select_pages({predicate:#?}) = {selectors:?})");
+    selectors.iter().any(|s| s.skip).then_some(selectors)
+}
+
+/// Converts a column's page index into per-page [`Statistics`], so they can be interpreted the same
+/// way as row group stats. Returns `None` if the column has no (supported) page index.
+fn page_stats(index: &Index) -> Option<Vec<Statistics>> {
+    fn convert<T: ParquetDataType>(
+        index: &NativeIndex<T::T>,
+        to_stats: impl Fn(Option<T::T>, Option<T::T>, Option<u64>, bool) -> Statistics,
+    ) -> Vec<Statistics> {
+        index
+            .indexes
+            .iter()
+            .map(|page| {
+                let nulls = page.null_count.and_then(|n| u64::try_from(n).ok());
+                to_stats(page.min.clone(), page.max.clone(), nulls, false)
+            })
+            .collect()
+    }
+    let stats = match index {
+        Index::BOOLEAN(i) => convert::<BoolType>(i, |min, max, nulls, d| {
+            Statistics::boolean(min, max, None, nulls, d)
+        }),
+        Index::INT32(i) => convert::<Int32Type>(i, |min, max, nulls, d| {
+            Statistics::int32(min, max, None, nulls, d)
+        }),
+        Index::INT64(i) => convert::<Int64Type>(i, |min, max, nulls, d| {
+            Statistics::int64(min, max, None, nulls, d)
+        }),
+        Index::FLOAT(i) => convert::<FloatType>(i, |min, max, nulls, d| {
+            Statistics::float(min, max, None, nulls, d)
+        }),
+        Index::DOUBLE(i) => convert::<DoubleType>(i, |min, max, nulls, d| {
+            Statistics::double(min, max, None, nulls, d)
+        }),
+        Index::BYTE_ARRAY(i) => convert::<ByteArrayType>(i, |min, max, nulls, d| {
+            Statistics::byte_array(min, max, None, nulls, d)
+        }),
+        Index::FIXED_LEN_BYTE_ARRAY(i) => {
+            convert::<FixedLenByteArrayType>(i, |min, max, nulls, d| {
+                Statistics::fixed_len_byte_array(min, max, None, nulls, d)
+            })
+        }
+        Index::NONE | Index::INT96(_) => return None,
+    };
+    Some(stats)
+}
+
+/// The page stats of one column chunk, along with the first row (relative to the row group) of
+/// each page.
+struct ColumnPages {
+    first_rows: Vec<i64>,
+    stats: Vec<Statistics>,
+}
+
+/// The stats of the page that contains a given segment of rows, along with the page's row count.
+struct PageStats<'a> {
+    stats: &'a Statistics,
+    num_rows: i64,
+}
+
+/// A ParquetStatsSkippingFilter for page skipping. It evaluates a predicate over a segment of rows
+/// that lies entirely inside a single page of every referenced column, using the stats of those
+/// pages. Columns without a page index behave as if they had no stats.
+struct PageFilter<'a> {
+    pages: HashMap<&'a ColumnName, PageStats<'a>>,
+    num_rows: i64,
+}
+
+impl<'a> PageFilter<'a> {
+    /// Creates a new page filter for the given segment of a row group with `row_group_rows` rows.
+    fn new(
+        pages: &'a HashMap<&'a ColumnName, ColumnPages>,
+        segment: Range<i64>,
+        row_group_rows: i64,
+    ) -> Self {
+        let pages = pages
+            .iter()
+            .filter_map(|(&col, pages)| {
+                let page = pages
+                    .first_rows
+                    .partition_point(|&row| row <= segment.start);
+                let page = page.checked_sub(1)?;
+                let end = pages
+                    .first_rows
+                    .get(page + 1)
+                    .copied()
+                    .unwrap_or(row_group_rows);
+                let num_rows = end - pages.first_rows[page];
+                Some((
+                    col,
+                    PageStats {
+                        stats: &pages.stats[page],
+                        num_rows,
+                    },
+                ))
+            })
+            .collect();
+        Self {
+            pages,
+            num_rows: segment.end - segment.start,
+        }
+    }
+
+    /// Applies a filtering predicate to a segment of rows. Return value false means to skip it.
+    fn apply(&self, predicate: &Predicate) -> bool {
+        use crate::kernel_predicates::KernelPredicateEvaluator as _;
+        self.eval_sql_where(predicate) != Some(false)
+    }
+}
+
+impl ParquetStatsProvider for PageFilter<'_> {
+    fn get_parquet_min_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Scalar> {
+        min_stat_value(self.pages.get(col)?.stats, data_type)
+    }
+
+    fn get_parquet_max_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Scalar> {
+        max_stat_value(self.pages.get(col)?.stats, data_type)
+    }
+
+    fn get_parquet_nullcount_stat(&self, col: &ColumnName) -> Option<i64> {
+        // The segment may cover only part of the page, so the page's nullcount only translates to
+        // the segment when the page is either null-free or all-null.
+        let page = self.pages.get(col)?;
+        match nullcount_stat_value(page.stats)? {
+            0 => Some(0),
+            nullcount if nullcount == page.num_rows => Some(self.num_rows),
+            _ => None,
+        }
+    }
+
+    fn get_parquet_rowcount_stat(&self) -> i64 {
+        self.num_rows
     }
 }
 
@@ -80,122 +469,15 @@ impl<'a> RowGroupFilter<'a> {
             .get(col)
             .map(|&i| self.row_group.column(i).statistics())
     }
-
-    fn decimal_from_bytes(bytes: Option<&[u8]>, dtype: DecimalType) -> Option<Scalar> {
-        // WARNING: The bytes are stored in big-endian order; reverse and then 0-pad to 16 bytes.
-        let bytes = bytes.filter(|b| b.len() <= 16)?;
-        let mut bytes = Vec::from(bytes);
-        bytes.reverse();
-        bytes.resize(16, 0u8);
-        let bytes: [u8; 16] = bytes.try_into().ok()?;
-        let value = DecimalData::try_new(i128::from_le_bytes(bytes), dtype).ok()?;
-        Some(value.into())
-    }
-
-    fn timestamp_from_date(days: Option<&i32>) -> Option<Scalar> {
-        let days = u64::try_from(*days?).ok()?;
-        let timestamp = DateTime::UNIX_EPOCH.checked_add_days(Days::new(days))?;
-        let timestamp = timestamp.signed_duration_since(DateTime::UNIX_EPOCH);
-        Some(Scalar::TimestampNtz(timestamp.num_microseconds()?))
-    }
 }
 
 impl ParquetStatsProvider for RowGroupFilter<'_> {
-    // Extracts a stat value, converting from its physical type to the requested logical type.
-    //
-    // NOTE: This code is highly redundant with [`get_max_stat_value`] below, but parquet
-    // ValueStatistics<T> requires T to impl a private trait, so we can't factor out any kind of
-    // helper method. And macros are hard enough to read that it's not worth defining one.
     fn get_parquet_min_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Scalar> {
-        use PrimitiveType::*;
-        let value = match (data_type.as_primitive_opt()?, self.get_stats(col)??) {
-            (String, Statistics::ByteArray(s)) => s.min_opt()?.as_utf8().ok()?.into(),
-            (String, Statistics::FixedLenByteArray(s)) => s.min_opt()?.as_utf8().ok()?.into(),
-            (String, _) => return None,
-            (Long, Statistics::Int64(s)) => s.min_opt()?.into(),
-            (Long, Statistics::Int32(s)) => (*s.min_opt()? as i64).into(),
-            (Long, _) => return None,
-            (Integer, Statistics::Int32(s)) => s.min_opt()?.into(),
-            (Integer, _) => return None,
-            (Short, Statistics::Int32(s)) => (*s.min_opt()? as i16).into(),
-            (Short, _) => return None,
-            (Byte, Statistics::Int32(s)) => (*s.min_opt()? as i8).into(),
-            (Byte, _) => return None,
-            (Float, Statistics::Float(s)) => s.min_opt()?.into(),
-            (Float, _) => return None,
-            (Double, Statistics::Double(s)) => s.min_opt()?.into(),
-            (Double, Statistics::Float(s)) => (*s.min_opt()? as f64).into(),
-            (Double, _) => return None,
-            (Boolean, Statistics::Boolean(s)) => s.min_opt()?.into(),
-            (Boolean, _) => return None,
-            (Binary, Statistics::ByteArray(s)) => s.min_opt()?.data().into(),
-            (Binary, Statistics::FixedLenByteArray(s)) => s.min_opt()?.data().into(),
-            (Binary, _) => return None,
-            (Date, Statistics::Int32(s)) => Scalar::Date(*s.min_opt()?),
-            (Date, _) => return None,
-            (Timestamp, Statistics::Int64(s)) => Scalar::Timestamp(*s.min_opt()?),
-            (Timestamp, _) => return None, // TODO: Int96 timestamps
-            (TimestampNtz, Statistics::Int64(s)) => Scalar::TimestampNtz(*s.min_opt()?),
-            (TimestampNtz, Statistics::Int32(s)) => Self::timestamp_from_date(s.min_opt())?,
-            (TimestampNtz, _) => return None, // TODO: Int96 timestamps
-            (Decimal(d), Statistics::Int32(i)) => {
-                DecimalData::try_new(*i.min_opt()?, *d).ok()?.into()
-            }
-            (Decimal(d), Statistics::Int64(i)) => {
-                DecimalData::try_new(*i.min_opt()?, *d).ok()?.into()
-            }
-            (Decimal(d), Statistics::FixedLenByteArray(b)) => {
-                Self::decimal_from_bytes(b.min_bytes_opt(), *d)?
-            }
-            (Decimal(..), _) => return None,
-        };
-        Some(value)
+        min_stat_value(self.get_stats(col)??, data_type)
     }
 
     fn get_parquet_max_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Scalar> {
-        use PrimitiveType::*;
-        let value = match (data_type.as_primitive_opt()?, self.get_stats(col)??) {
-            (String, Statistics::ByteArray(s)) => s.max_opt()?.as_utf8().ok()?.into(),
-            (String, Statistics::FixedLenByteArray(s)) => s.max_opt()?.as_utf8().ok()?.into(),
-            (String, _) => return None,
-            (Long, Statistics::Int64(s)) => s.max_opt()?.into(),
-            (Long, Statistics::Int32(s)) => (*s.max_opt()? as i64).into(),
-            (Long, _) => return None,
-            (Integer, Statistics::Int32(s)) => s.max_opt()?.into(),
-            (Integer, _) => return None,
-            (Short, Statistics::Int32(s)) => (*s.max_opt()? as i16).into(),
-            (Short, _) => return None,
-            (Byte, Statistics::Int32(s)) => (*s.max_opt()? as i8).into(),
-            (Byte, _) => return None,
-            (Float, Statistics::Float(s)) => s.max_opt()?.into(),
-            (Float, _) => return None,
-            (Double, Statistics::Double(s)) => s.max_opt()?.into(),
-            (Double, Statistics::Float(s)) => (*s.max_opt()? as f64).into(),
-            (Double, _) => return None,
-            (Boolean, Statistics::Boolean(s)) => s.max_opt()?.into(),
-            (Boolean, _) => return None,
-            (Binary, Statistics::ByteArray(s)) => s.max_opt()?.data().into(),
-            (Binary, Statistics::FixedLenByteArray(s)) => s.max_opt()?.data().into(),
-            (Binary, _) => return None,
-            (Date, Statistics::Int32(s)) => Scalar::Date(*s.max_opt()?),
-            (Date, _) => return None,
-            (Timestamp, Statistics::Int64(s)) => Scalar::Timestamp(*s.max_opt()?),
-            (Timestamp, _) => return None, // TODO: Int96 timestamps
-            (TimestampNtz, Statistics::Int64(s)) => Scalar::TimestampNtz(*s.max_opt()?),
-            (TimestampNtz, Statistics::Int32(s)) => Self::timestamp_from_date(s.max_opt())?,
-            (TimestampNtz, _) => return None, // TODO: Int96 timestamps
-            (Decimal(d), Statistics::Int32(i)) => {
-                DecimalData::try_new(*i.max_opt()?, *d).ok()?.into()
-            }
-            (Decimal(d), Statistics::Int64(i)) => {
-                DecimalData::try_new(*i.max_opt()?, *d).ok()?.into()
-            }
-            (Decimal(d), Statistics::FixedLenByteArray(b)) => {
-                Self::decimal_from_bytes(b.max_bytes_opt(), *d)?
-            }
-            (Decimal(..), _) => return None,
-        };
-        Some(value)
+        max_stat_value(self.get_stats(col)??, data_type)
     }
 
     fn get_parquet_nullcount_stat(&self, col: &ColumnName) -> Option<i64> {
@@ -211,24 +493,7 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
             return Some(self.get_parquet_rowcount_stat()).filter(|_| false);
         };
 
-        // WARNING: [`Statistics::null_count_opt`] returns Some(0) when the underlying stat is
-        // missing, causing an IS NULL predicate to wrongly skip the file if it contains any NULL
-        // values. Manually drill into each arm's [`ValueStatistics`] for the stat's true.
-        let nullcount = match stats? {
-            Statistics::Boolean(s) => s.null_count_opt(),
-            Statistics::Int32(s) => s.null_count_opt(),
-            Statistics::Int64(s) => s.null_count_opt(),
-            Statistics::Int96(s) => s.null_count_opt(),
-            Statistics::Float(s) => s.null_count_opt(),
-            Statistics::Double(s) => s.null_count_opt(),
-            Statistics::ByteArray(s) => s.null_count_opt(),
-            Statistics::FixedLenByteArray(s) => s.null_count_opt(),
-        };
-
-        // Parquet nullcount stats are always u64, so we can directly return the value instead of
-        // wrapping it in a Scalar. We can safely cast it from u64 to i64 because the nullcount can
-        // never be larger than the rowcount and the parquet rowcount stat is i64.
-        Some(nullcount? as i64)
+        nullcount_stat_value(stats?)
     }
 
     fn get_parquet_rowcount_stat(&self) -> i64 {
@@ -236,6 +501,136 @@ impl ParquetStatsProvider for RowGroupFilter<'_> {
     }
 }
 
+/// Extracts a min stat value, converting from its physical type to the requested logical type.
+//
+// NOTE: This code is highly redundant with [`max_stat_value`] below, but parquet
+// ValueStatistics<T> requires T to impl a private trait, so we can't factor out any kind of
+// helper method. And macros are hard enough to read that it's not worth defining one.
+fn min_stat_value(stats: &Statistics, data_type: &DataType) -> Option<Scalar> {
+    use PrimitiveType::*;
+    let value = match (data_type.as_primitive_opt()?, stats) {
+        (String, Statistics::ByteArray(s)) => s.min_opt()?.as_utf8().ok()?.into(),
+        (String, Statistics::FixedLenByteArray(s)) => s.min_opt()?.as_utf8().ok()?.into(),
+        (String, _) => return None,
+        (Long, Statistics::Int64(s)) => s.min_opt()?.into(),
+        (Long, Statistics::Int32(s)) => (*s.min_opt()? as i64).into(),
+        (Long, _) => return None,
+        (Integer, Statistics::Int32(s)) => s.min_opt()?.into(),
+        (Integer, _) => return None,
+        (Short, Statistics::Int32(s)) => (*s.min_opt()? as i16).into(),
+        (Short, _) => return None,
+        (Byte, Statistics::Int32(s)) => (*s.min_opt()? as i8).into(),
+        (Byte, _) => return None,
+        (Float, Statistics::Float(s)) => s.min_opt()?.into(),
+        (Float, _) => return None,
+        (Double, Statistics::Double(s)) => s.min_opt()?.into(),
+        (Double, Statistics::Float(s)) => (*s.min_opt()? as f64).into(),
+        (Double, _) => return None,
+        (Boolean, Statistics::Boolean(s)) => s.min_opt()?.into(),
+        (Boolean, _) => return None,
+        (Binary, Statistics::ByteArray(s)) => s.min_opt()?.data().into(),
+        (Binary, Statistics::FixedLenByteArray(s)) => s.min_opt()?.data().into(),
+        (Binary, _) => return None,
+        (Date, Statistics::Int32(s)) => Scalar::Date(*s.min_opt()?),
+        (Date, _) => return None,
+        (Timestamp, Statistics::Int64(s)) => Scalar::Timestamp(*s.min_opt()?),
+        (Timestamp, _) => return None, // TODO: Int96 timestamps
+        (TimestampNtz, Statistics::Int64(s)) => Scalar::TimestampNtz(*s.min_opt()?),
+        (TimestampNtz, Statistics::Int32(s)) => timestamp_from_date(s.min_opt())?,
+        (TimestampNtz, _) => return None, // TODO: Int96 timestamps
+        (Decimal(d), Statistics::Int32(i)) => DecimalData::try_new(*i.min_opt()?, *d).ok()?.into(),
+        (Decimal(d), Statistics::Int64(i)) => DecimalData::try_new(*i.min_opt()?, *d).ok()?.into(),
+        (Decimal(d), Statistics::FixedLenByteArray(b)) => {
+            decimal_from_bytes(b.min_bytes_opt(), *d)?
+        }
+        (Decimal(..), _) => return None,
+    };
+    Some(value)
+}
+
+/// Extracts a max stat value, converting from its physical type to the requested logical type.
+fn max_stat_value(stats: &Statistics, data_type: &DataType) -> Option<Scalar> {
+    use PrimitiveType::*;
+    let value = match (data_type.as_primitive_opt()?, stats) {
+        (String, Statistics::ByteArray(s)) => s.max_opt()?.as_utf8().ok()?.into(),
+        (String, Statistics::FixedLenByteArray(s)) => s.max_opt()?.as_utf8().ok()?.into(),
+        (String, _) => return None,
+        (Long, Statistics::Int64(s)) => s.max_opt()?.into(),
+        (Long, Statistics::Int32(s)) => (*s.max_opt()? as i64).into(),
+        (Long, _) => return None,
+        (Integer, Statistics::Int32(s)) => s.max_opt()?.into(),
+        (Integer, _) => return None,
+        (Short, Statistics::Int32(s)) => (*s.max_opt()? as i16).into(),
+        (Short, _) => return None,
+        (Byte, Statistics::Int32(s)) => (*s.max_opt()? as i8).into(),
+        (Byte, _) => return None,
+        (Float, Statistics::Float(s)) => s.max_opt()?.into(),
+        (Float, _) => return None,
+        (Double, Statistics::Double(s)) => s.max_opt()?.into(),
+        (Double, Statistics::Float(s)) => (*s.max_opt()? as f64).into(),
+        (Double, _) => return None,
+        (Boolean, Statistics::Boolean(s)) => s.max_opt()?.into(),
+        (Boolean, _) => return None,
+        (Binary, Statistics::ByteArray(s)) => s.max_opt()?.data().into(),
+        (Binary, Statistics::FixedLenByteArray(s)) => s.max_opt()?.data().into(),
+        (Binary, _) => return None,
+        (Date, Statistics::Int32(s)) => Scalar::Date(*s.max_opt()?),
+        (Date, _) => return None,
+        (Timestamp, Statistics::Int64(s)) => Scalar::Timestamp(*s.max_opt()?),
+        (Timestamp, _) => return None, // TODO: Int96 timestamps
+        (TimestampNtz, Statistics::Int64(s)) => Scalar::TimestampNtz(*s.max_opt()?),
+        (TimestampNtz, Statistics::Int32(s)) => timestamp_from_date(s.max_opt())?,
+        (TimestampNtz, _) => return None, // TODO: Int96 timestamps
+        (Decimal(d), Statistics::Int32(i)) => DecimalData::try_new(*i.max_opt()?, *d).ok()?.into(),
+        (Decimal(d), Statistics::Int64(i)) => DecimalData::try_new(*i.max_opt()?, *d).ok()?.into(),
+        (Decimal(d), Statistics::FixedLenByteArray(b)) => {
+            decimal_from_bytes(b.max_bytes_opt(), *d)?
+        }
+        (Decimal(..), _) => return None,
+    };
+    Some(value)
+}
+
+/// Extracts the nullcount stat value, if present.
+fn nullcount_stat_value(stats: &Statistics) -> Option<i64> {
+    // WARNING: [`Statistics::null_count_opt`] returns Some(0) when the underlying stat is
+    // missing, causing an IS NULL predicate to wrongly skip the file if it contains any NULL
+    // values. Manually drill into each arm's [`ValueStatistics`] for the stat's true.
+    let nullcount = match stats {
+        Statistics::Boolean(s) => s.null_count_opt(),
+        Statistics::Int32(s) => s.null_count_opt(),
+        Statistics::Int64(s) => s.null_count_opt(),
+        Statistics::Int96(s) => s.null_count_opt(),
+        Statistics::Float(s) => s.null_count_opt(),
+        Statistics::Double(s) => s.null_count_opt(),
+        Statistics::ByteArray(s) => s.null_count_opt(),
+        Statistics::FixedLenByteArray(s) => s.null_count_opt(),
+    };
+
+    // Parquet nullcount stats are always u64, so we can directly return the value instead of
+    // wrapping it in a Scalar. We can safely cast it from u64 to i64 because the nullcount can
+    // never be larger than the rowcount and the parquet rowcount stat is i64.
+    Some(nullcount? as i64)
+}
+
+fn decimal_from_bytes(bytes: Option<&[u8]>, dtype: DecimalType) -> Option<Scalar> {
+    // WARNING: The bytes are stored in big-endian order; reverse and then 0-pad to 16 bytes.
+    let bytes = bytes.filter(|b| b.len() <= 16)?;
+    let mut bytes = Vec::from(bytes);
+    bytes.reverse();
+    bytes.resize(16, 0u8);
+    let bytes: [u8; 16] = bytes.try_into().ok()?;
+    let value = DecimalData::try_new(i128::from_le_bytes(bytes), dtype).ok()?;
+    Some(value.into())
+}
+
+fn timestamp_from_date(days: Option<&i32>) -> Option<Scalar> {
+    let days = u64::try_from(*days?).ok()?;
+    let timestamp = DateTime::UNIX_EPOCH.checked_add_days(Days::new(days))?;
+    let timestamp = timestamp.signed_duration_since(DateTime::UNIX_EPOCH);
+    Some(Scalar::TimestampNtz(timestamp.num_microseconds()?))
+}
+
 /// Given a predicate of interest and a set of parquet column descriptors, build a column ->
 /// index mapping for columns the predicate references. This ensures O(1) lookup times, for an
 /// overall O(n) cost to evaluate a predicate tree with n nodes.
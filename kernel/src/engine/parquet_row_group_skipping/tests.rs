@@ -435,3 +435,202 @@ fn test_get_stat_values() {
         )
     );
 }
+
+/// Writes 100 rows of `x: LONG = 0..100` as two row groups of 50 rows, each split into pages of
+/// 10 rows, and verifies that the page index lets a selective predicate skip all but one page.
+#[test]
+fn test_page_skipping() {
+    use crate::arrow::array::{AsArray as _, Int64Array, RecordBatch};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Int64Type, Schema};
+    use crate::expressions::{column_expr, Expression as Expr};
+    use crate::parquet::arrow::arrow_reader::{
+        ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+    };
+    use crate::parquet::arrow::ArrowWriter;
+    use crate::parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "x",
+        ArrowDataType::Int64,
+        false,
+    )]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![Arc::new(Int64Array::from_iter_values(0..100))],
+    )
+    .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_size(50)
+        .set_data_page_row_count_limit(10)
+        .set_write_batch_size(10)
+        .build();
+    let mut buffer = vec![];
+    let mut writer = ArrowWriter::try_new(&mut buffer, schema, Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    let buffer = bytes::Bytes::from(buffer);
+
+    let read = |predicate: &Predicate, page_index: bool| {
+        let options = ArrowReaderOptions::new().with_page_index(page_index);
+        let builder =
+            ParquetRecordBatchReaderBuilder::try_new_with_options(buffer.clone(), options).unwrap();
+        let mut row_indexes = RowIndexBuilder::new(builder.metadata().row_groups());
+        let values: Vec<i64> = builder
+            .with_row_group_filter(predicate, Some(&mut row_indexes))
+            .build()
+            .unwrap()
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                batch
+                    .column(0)
+                    .as_primitive::<Int64Type>()
+                    .values()
+                    .to_vec()
+            })
+            .collect();
+        (values, row_indexes.into_iter().collect::<Vec<_>>())
+    };
+
+    // Without the page index, only row group skipping applies
+    let predicate = Predicate::eq(column_expr!("x"), Expr::literal(72i64));
+    let (values, row_indexes) = read(&predicate, false);
+    assert_eq!(values, (50..100).collect::<Vec<_>>());
+    assert_eq!(row_indexes, values);
+
+    // With the page index, only the page containing the value is read
+    let (values, row_indexes) = read(&predicate, true);
+    assert_eq!(values, (70..80).collect::<Vec<_>>());
+    assert_eq!(row_indexes, values);
+
+    // Pages that survive in different row groups are all read, in order
+    let predicate = Predicate::or(
+        Predicate::lt(column_expr!("x"), Expr::literal(5i64)),
+        Predicate::gt(column_expr!("x"), Expr::literal(95i64)),
+    );
+    let (values, row_indexes) = read(&predicate, true);
+    let expected: Vec<i64> = (0..10).chain(90..100).collect();
+    assert_eq!(values, expected);
+    assert_eq!(row_indexes, expected);
+
+    // A predicate no page can satisfy reads nothing
+    let predicate = Predicate::gt(column_expr!("x"), Expr::literal(1000i64));
+    let (values, row_indexes) = read(&predicate, true);
+    assert!(values.is_empty());
+    assert!(row_indexes.is_empty());
+}
+
+/// Writes the even values of `x: LONG` in one row group and the odd values in another, so that
+/// their stats overlap completely, and verifies that bloom filters can tell them apart.
+#[test]
+fn test_bloom_filter_skipping() {
+    use crate::arrow::array::{Int64Array, RecordBatch};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema};
+    use crate::expressions::{column_expr, ArrayData, Expression as Expr};
+    use crate::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use crate::parquet::arrow::ArrowWriter;
+    use crate::parquet::file::properties::ReaderProperties;
+    use crate::parquet::file::properties::WriterProperties;
+    use crate::parquet::file::reader::{FileReader as _, SerializedFileReader};
+    use crate::parquet::file::serialized_reader::ReadOptionsBuilder;
+    use crate::schema::ArrayType;
+    use std::sync::Arc;
+
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "x",
+        ArrowDataType::Int64,
+        false,
+    )]));
+    let props = WriterProperties::builder()
+        .set_bloom_filter_enabled(true)
+        .set_bloom_filter_fpp(0.001)
+        .build();
+    let mut buffer = vec![];
+    let mut writer = ArrowWriter::try_new(&mut buffer, schema.clone(), Some(props)).unwrap();
+    for values in [(0..100).step_by(2), (1..100).step_by(2)] {
+        let values = Arc::new(Int64Array::from_iter_values(values));
+        let batch = RecordBatch::try_new(schema.clone(), vec![values]).unwrap();
+        writer.write(&batch).unwrap();
+        writer.flush().unwrap(); // end the row group
+    }
+    writer.close().unwrap();
+    let buffer = bytes::Bytes::from(buffer);
+
+    let properties = ReaderProperties::builder()
+        .set_read_bloom_filter(true)
+        .build();
+    let options = ReadOptionsBuilder::new()
+        .with_reader_properties(properties)
+        .build();
+    let file_reader = SerializedFileReader::new_with_options(buffer.clone(), options).unwrap();
+    let row_groups = |predicate: &Predicate| {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(buffer.clone()).unwrap();
+        let mut bloom_filters = BloomFilters::new();
+        for (row_group, column) in bloom_filter_candidates(builder.metadata(), predicate) {
+            let row_group_reader = file_reader.get_row_group(row_group).unwrap();
+            let bloom_filter = row_group_reader.get_column_bloom_filter(column).unwrap();
+            bloom_filters.insert((row_group, column), bloom_filter.clone());
+        }
+        let mut row_indexes = RowIndexBuilder::new(builder.metadata().row_groups());
+        let _ = builder.with_row_group_and_bloom_filter(
+            predicate,
+            &bloom_filters,
+            Some(&mut row_indexes),
+        );
+        // Each row group has 50 rows, so the first row index identifies the row group
+        let row_indexes: Vec<_> = row_indexes.into_iter().collect();
+        row_indexes
+            .chunks(50)
+            .map(|rows| rows[0] / 50)
+            .collect::<Vec<_>>()
+    };
+
+    let x = || column_expr!("x");
+    let in_list = |values: Vec<i64>| {
+        let values = ArrayData::try_new(ArrayType::new(DataType::LONG, false), values).unwrap();
+        Predicate::in_list(x(), values)
+    };
+
+    // Predicates that don't compare for equality have no bloom filter candidates
+    let metadata = ParquetRecordBatchReaderBuilder::try_new(buffer.clone())
+        .unwrap()
+        .metadata()
+        .clone();
+    let predicate = Predicate::gt(x(), Expr::literal(10i64));
+    assert!(bloom_filter_candidates(&metadata, &predicate).is_empty());
+    let predicate = Predicate::eq(x(), Expr::literal(42i64));
+    assert_eq!(
+        bloom_filter_candidates(&metadata, &predicate),
+        vec![(0, 0), (1, 0)]
+    );
+
+    let cases = [
+        (Predicate::eq(x(), Expr::literal(42i64)), vec![0]),
+        (Predicate::eq(Expr::literal(43i64), x()), vec![1]),
+        (Predicate::eq(x(), Expr::literal(1000i64)), vec![]),
+        (in_list(vec![42, 44]), vec![0]),
+        (in_list(vec![42, 43]), vec![0, 1]),
+        (
+            Predicate::or(
+                Predicate::eq(x(), Expr::literal(42i64)),
+                Predicate::eq(x(), Expr::literal(44i64)),
+            ),
+            vec![0],
+        ),
+        (
+            Predicate::and(
+                Predicate::gt(x(), Expr::literal(10i64)),
+                Predicate::eq(x(), Expr::literal(43i64)),
+            ),
+            vec![1],
+        ),
+        // Bloom filters can never prove that a value is present, so NOT is not skipped
+        (
+            Predicate::not(Predicate::eq(x(), Expr::literal(42i64))),
+            vec![0, 1],
+        ),
+    ];
+    for (predicate, expected) in cases {
+        assert_eq!(row_groups(&predicate), expected, "{predicate:?}");
+    }
+}
@@ -1,11 +1,11 @@
-use std::{fs::File, io::BufReader, io::Write};
+use std::io::{BufReader, Read, Write};
 
 use crate::arrow::datatypes::SchemaRef as ArrowSchemaRef;
 use crate::arrow::json::ReaderBuilder;
 use tempfile::NamedTempFile;
 use url::Url;
 
-use super::read_files;
+use super::{open_local_file, read_files};
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::parse_json as arrow_parse_json;
 use crate::engine::arrow_utils::to_json_bytes;
@@ -14,10 +14,11 @@ use crate::{
     DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, JsonHandler, PredicateRef,
 };
 
+#[derive(Debug)]
 pub(crate) struct SyncJsonHandler;
 
-fn try_create_from_json(
-    file: File,
+pub(crate) fn try_create_from_json(
+    file: impl Read,
     _schema: SchemaRef,
     arrow_schema: ArrowSchemaRef,
     _predicate: Option<PredicateRef>,
@@ -35,7 +36,13 @@ impl JsonHandler for SyncJsonHandler {
         schema: SchemaRef,
         predicate: Option<PredicateRef>,
     ) -> DeltaResult<FileDataReadResultIterator> {
-        read_files(files, schema, predicate, try_create_from_json)
+        read_files(
+            files,
+            schema,
+            predicate,
+            open_local_file,
+            try_create_from_json,
+        )
     }
 
     fn parse_json(
@@ -1,4 +1,10 @@
-//! A simple, single threaded, test-only [`Engine`] that can only read from the local filesystem
+//! A simple, synchronous and single threaded [`Engine`] that reads and writes tables on the local
+//! filesystem.
+//!
+//! Unlike the [default engine](crate::engine::default), it performs all IO by blocking the calling
+//! thread, and never spawns an async runtime (or any threads). This suits embedded and CLI use cases
+//! that read local tables, and don't want to pull in an async runtime. Enable it with the
+//! `sync-engine` feature.
 
 use super::arrow_expression::ArrowEvaluationHandler;
 use crate::engine::arrow_data::ArrowEngineData;
@@ -13,22 +19,33 @@ use itertools::Itertools;
 use std::fs::File;
 use std::sync::Arc;
 use tracing::debug;
+use url::Url;
 
 pub(crate) mod json;
-mod parquet;
+pub(crate) mod parquet;
 mod storage;
 
-/// This is a simple (test-only) implementation of [`Engine`]. It only supports reading data from
-/// the local filesystem, and internally represents data using `Arrow`.
-pub(crate) struct SyncEngine {
+/// This is a simple, synchronous implementation of [`Engine`]. It only supports reading and writing
+/// data on the local filesystem (`file://` URLs), and internally represents data using `Arrow`.
+///
+/// Commits are written to a temporary file first, which is then atomically moved into place unless
+/// the commit file already exists.
+#[derive(Debug)]
+pub struct SyncEngine {
     storage_handler: Arc<storage::SyncStorageHandler>,
     json_handler: Arc<json::SyncJsonHandler>,
     parquet_handler: Arc<parquet::SyncParquetHandler>,
     evaluation_handler: Arc<ArrowEvaluationHandler>,
 }
 
+impl Default for SyncEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SyncEngine {
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         SyncEngine {
             storage_handler: Arc::new(storage::SyncStorageHandler {}),
             json_handler: Arc::new(json::SyncJsonHandler {}),
@@ -57,17 +74,26 @@ impl Engine for SyncEngine {
     }
 }
 
-fn read_files<F, I>(
+fn open_local_file(location: &Url) -> DeltaResult<File> {
+    let path = location
+        .to_file_path()
+        .map_err(|_| Error::generic("can only read local files"))?;
+    Ok(File::open(path)?)
+}
+
+/// Reads `files` one after the other, opening each with `open` and parsing its contents with
+/// `try_create_from_file`.
+pub(crate) fn read_files<R, O, F, I>(
     files: &[FileMeta],
     schema: SchemaRef,
     predicate: Option<PredicateRef>,
+    mut open: O,
     mut try_create_from_file: F,
 ) -> DeltaResult<FileDataReadResultIterator>
 where
     I: Iterator<Item = DeltaResult<ArrowEngineData>> + Send + 'static,
-    F: FnMut(File, SchemaRef, ArrowSchemaRef, Option<PredicateRef>) -> DeltaResult<I>
-        + Send
-        + 'static,
+    O: FnMut(&Url) -> DeltaResult<R> + Send + 'static,
+    F: FnMut(R, SchemaRef, ArrowSchemaRef, Option<PredicateRef>) -> DeltaResult<I> + Send + 'static,
 {
     debug!("Reading files: {files:#?} with schema {schema:#?} and predicate {predicate:#?}");
     if files.is_empty() {
@@ -81,11 +107,8 @@ where
         .map(move |file| {
             let location = file.location;
             debug!("Reading {location:#?} with schema {schema:#?} and predicate {predicate:#?}");
-            let path = location
-                .to_file_path()
-                .map_err(|_| Error::generic("can only read local files"))?;
             try_create_from_file(
-                File::open(path)?,
+                open(&location)?,
                 schema.clone(),
                 arrow_schema.clone(),
                 predicate.clone(),
@@ -1,22 +1,30 @@
 use std::fs::File;
+use std::io::Write as _;
+use std::time::SystemTime;
 
 use crate::arrow::datatypes::SchemaRef as ArrowSchemaRef;
 use crate::parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatchReaderBuilder};
+use crate::parquet::file::reader::ChunkReader;
 
-use super::read_files;
+use super::{open_local_file, read_files};
 use crate::engine::arrow_data::ArrowEngineData;
 use crate::engine::arrow_utils::{
     fixup_parquet_read, generate_mask, get_requested_indices, ordering_needs_row_indexes,
-    RowIndexBuilder,
+    to_parquet_bytes, RowIndexBuilder,
 };
 use crate::engine::parquet_row_group_skipping::ParquetRowGroupSkipping;
 use crate::schema::SchemaRef;
-use crate::{DeltaResult, FileDataReadResultIterator, FileMeta, ParquetHandler, PredicateRef};
+use crate::{
+    DeltaResult, EngineData, Error, FileDataReadResultIterator, FileMeta, ParquetFileMetadata,
+    ParquetHandler, ParquetWriteOptions, PredicateRef,
+};
+use url::Url;
 
+#[derive(Debug)]
 pub(crate) struct SyncParquetHandler;
 
-fn try_create_from_parquet(
-    file: File,
+pub(crate) fn try_create_from_parquet<R: ChunkReader + 'static>(
+    file: R,
     schema: SchemaRef,
     _arrow_schema: ArrowSchemaRef,
     predicate: Option<PredicateRef>,
@@ -50,6 +58,99 @@ impl ParquetHandler for SyncParquetHandler {
         schema: SchemaRef,
         predicate: Option<PredicateRef>,
     ) -> DeltaResult<FileDataReadResultIterator> {
-        read_files(files, schema, predicate, try_create_from_parquet)
+        read_files(
+            files,
+            schema,
+            predicate,
+            open_local_file,
+            try_create_from_parquet,
+        )
+    }
+
+    fn write_parquet_file(
+        &self,
+        location: &Url,
+        data: Box<dyn EngineData>,
+        options: &ParquetWriteOptions,
+    ) -> DeltaResult<ParquetFileMetadata> {
+        let path = location
+            .to_file_path()
+            .map_err(|_| Error::generic("sync client can only write local files"))?;
+        let (buffer, num_records) = to_parquet_bytes(data, options)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // data files are never overwritten
+        let mut file = match File::options().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                return Err(Error::FileAlreadyExists(location.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        file.write_all(&buffer)?;
+        file.sync_all()?;
+        let metadata = file.metadata()?;
+        let last_modified = metadata
+            .modified()?
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|_| Error::generic("Failed to convert file timestamp to milliseconds"))?;
+        let last_modified = i64::try_from(last_modified.as_millis())
+            .map_err(|_| Error::generic("Failed to convert file modification time into i64"))?;
+        Ok(ParquetFileMetadata {
+            file_meta: FileMeta::new(location.clone(), last_modified, metadata.len()),
+            num_records: num_records as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::arrow::array::{Int64Array, RecordBatch};
+    use crate::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+    use crate::engine::arrow_data::ArrowEngineData;
+    use crate::schema::{DataType, StructField, StructType};
+
+    #[test]
+    fn test_write_parquet_file() -> DeltaResult<()> {
+        let test_dir = TempDir::new().unwrap();
+        let url = Url::from_file_path(test_dir.path().join("dir/part-0.parquet")).unwrap();
+        let schema = Arc::new(StructType::try_new([StructField::nullable(
+            "id",
+            DataType::LONG,
+        )])?);
+        let batch = RecordBatch::try_new(
+            Arc::new(ArrowSchema::new(vec![Field::new(
+                "id",
+                ArrowDataType::Int64,
+                true,
+            )])),
+            vec![Arc::new(Int64Array::from(vec![1, 2, 3]))],
+        )?;
+        let data = || Box::new(ArrowEngineData::new(batch.clone()));
+        let options = ParquetWriteOptions::default();
+
+        let handler = SyncParquetHandler;
+        let written = handler.write_parquet_file(&url, data(), &options)?;
+        assert_eq!(written.num_records, 3);
+        assert_eq!(written.file_meta.location, url);
+
+        let read: Vec<_> = handler
+            .read_parquet_files(&[written.file_meta], schema, None)?
+            .map(|data| {
+                ArrowEngineData::try_from_engine_data(data?).map(|d| d.record_batch().num_rows())
+            })
+            .collect::<DeltaResult<_>>()?;
+        assert_eq!(read, vec![3]);
+
+        // data files are never overwritten
+        let result = handler.write_parquet_file(&url, data(), &options);
+        assert!(matches!(result, Err(Error::FileAlreadyExists(_))));
+        Ok(())
     }
 }
@@ -4,6 +4,7 @@ use url::Url;
 
 use crate::{DeltaResult, Error, FileMeta, FileSlice, StorageHandler};
 
+#[derive(Debug)]
 pub(crate) struct SyncStorageHandler;
 
 impl StorageHandler for SyncStorageHandler {
@@ -57,19 +58,47 @@ impl StorageHandler for SyncStorageHandler {
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>> {
-        let iter = files.into_iter().map(|(url, _range_opt)| {
+        let iter = files.into_iter().map(|(url, range_opt)| {
             if url.scheme() == "file" {
                 if let Ok(file_path) = url.to_file_path() {
                     let bytes_vec_res = std::fs::read(file_path);
                     let bytes: std::io::Result<Bytes> =
                         bytes_vec_res.map(|bytes_vec| bytes_vec.into());
-                    return bytes.map_err(|_| Error::file_not_found(url.path()));
+                    let bytes = bytes.map_err(|_| Error::file_not_found(url.path()))?;
+                    return match range_opt {
+                        Some(range) => {
+                            let end = usize::try_from(range.end)
+                                .map_or(bytes.len(), |end| end.min(bytes.len()));
+                            let start =
+                                usize::try_from(range.start).map_or(end, |start| start.min(end));
+                            Ok(bytes.slice(start..end))
+                        }
+                        None => Ok(bytes),
+                    };
                 }
             }
             Err(Error::generic("Can only read local filesystem"))
         });
         Ok(Box::new(iter))
     }
+
+    /// Delete the given files. Missing files are ignored.
+    fn delete(&self, files: Vec<Url>) -> DeltaResult<()> {
+        for url in files {
+            if url.scheme() != "file" {
+                return Err(Error::generic("Can only delete from local filesystem"));
+            }
+            let file_path = url
+                .to_file_path()
+                .map_err(|_| Error::Generic(format!("Invalid path for delete: {url:?}")))?;
+            match std::fs::remove_file(file_path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -176,4 +205,22 @@ mod tests {
         assert_eq!(file_count, 1);
         Ok(())
     }
+
+    #[test]
+    fn test_read_file_ranges() -> Result<(), Box<dyn std::error::Error>> {
+        let storage = SyncStorageHandler;
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join(get_json_filename(1));
+        std::fs::write(&path, b"0123456789")?;
+        let url = Url::from_file_path(path).unwrap();
+        let read: Vec<_> = storage
+            .read_files(vec![
+                (url.clone(), Some(2..5)),
+                (url.clone(), None),
+                (url.clone(), Some(8..20)),
+            ])?
+            .try_collect()?;
+        assert_eq!(read, vec![&b"234"[..], &b"0123456789"[..], &b"89"[..]]);
+        Ok(())
+    }
 }
@@ -2,11 +2,13 @@
 
 use std::collections::HashMap;
 
+use bytes::Bytes;
 use tracing::debug;
 
 use crate::expressions::ArrayData;
 use crate::log_replay::HasSelectionVector;
 use crate::schema::{ColumnName, DataType, SchemaRef};
+use crate::utils::require;
 use crate::{AsAny, DeltaResult, Error};
 
 /// Engine data paired with a selection vector indicating which rows are logically selected.
@@ -19,13 +21,173 @@ pub struct FilteredEngineData {
     // The underlying engine data
     pub data: Box<dyn EngineData>,
     // The selection vector where `true` marks rows to include in results
-    pub selection_vector: Vec<bool>,
+    pub selection_vector: SelectionVector,
 }
 
 impl HasSelectionVector for FilteredEngineData {
     /// Returns true if any row in the selection vector is marked as selected
     fn has_selected_rows(&self) -> bool {
-        self.selection_vector.contains(&true)
+        self.selection_vector.has_selected_rows()
+    }
+}
+
+/// A selection vector packed into a bitmap, where a set bit marks a selected row.
+///
+/// Bits are packed least significant bit first, which is the layout of Arrow's boolean buffers, so
+/// engines can use the bitmap (see [`SelectionVector::as_bytes`]) as e.g. the filter of an Arrow
+/// batch without copying it. The bitmap is shared, so cloning a selection vector is cheap.
+#[derive(Clone, Default)]
+pub struct SelectionVector {
+    bits: Bytes,
+    len: usize,
+}
+
+impl SelectionVector {
+    /// Creates a selection vector of `len` rows from a bitmap packed least significant bit first.
+    /// Fails if `bits` holds fewer than `len` bits.
+    pub fn try_new(bits: Bytes, len: usize) -> DeltaResult<Self> {
+        require!(
+            bits.len() >= len.div_ceil(8),
+            Error::generic(format!(
+                "A bitmap of {} bytes can't hold a selection vector of {len} rows",
+                bits.len()
+            ))
+        );
+        Ok(Self::new_unchecked(bits, len))
+    }
+
+    /// Like [`SelectionVector::try_new`], for a bitmap known to hold at least `len` bits.
+    #[cfg_attr(not(feature = "arrow-conversion"), allow(dead_code))]
+    pub(crate) fn new_unchecked(bits: Bytes, len: usize) -> Self {
+        Self { bits, len }
+    }
+
+    /// The number of rows of the selection vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the selection vector has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the row at `index` is selected, or `None` if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        (index < self.len).then(|| self.bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Iterates over whether each row is selected.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = bool> + '_ {
+        (0..self.len).map(|index| self.bits[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// The number of selected rows.
+    pub fn count_selected(&self) -> usize {
+        let full_bytes = self.len / 8;
+        let full = self.bits[..full_bytes]
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum::<usize>();
+        let remainder = match self.len % 8 {
+            0 => 0,
+            bits => (self.bits[full_bytes] & ((1 << bits) - 1)).count_ones() as usize,
+        };
+        full + remainder
+    }
+
+    /// Whether any row is selected.
+    pub fn has_selected_rows(&self) -> bool {
+        self.count_selected() > 0
+    }
+
+    /// The packed bitmap. Bits past [`SelectionVector::len`] are unspecified.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// Converts the selection vector into its packed bitmap. Bits past [`SelectionVector::len`] are
+    /// unspecified.
+    pub fn into_bytes(self) -> Bytes {
+        self.bits
+    }
+
+    /// Copies the selection vector into a `Vec<bool>`.
+    pub fn to_vec(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+}
+
+impl FromIterator<bool> for SelectionVector {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = vec![];
+        let mut len = 0;
+        for selected in iter {
+            if len % 8 == 0 {
+                bits.push(0u8);
+            }
+            if selected {
+                if let Some(byte) = bits.last_mut() {
+                    *byte |= 1 << (len % 8);
+                }
+            }
+            len += 1;
+        }
+        Self {
+            bits: bits.into(),
+            len,
+        }
+    }
+}
+
+impl From<Vec<bool>> for SelectionVector {
+    fn from(selection_vector: Vec<bool>) -> Self {
+        selection_vector.into_iter().collect()
+    }
+}
+
+impl From<&[bool]> for SelectionVector {
+    fn from(selection_vector: &[bool]) -> Self {
+        selection_vector.iter().copied().collect()
+    }
+}
+
+impl std::fmt::Debug for SelectionVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Bits past the end of the selection vectors don't take part in comparisons
+impl PartialEq for SelectionVector {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for SelectionVector {}
+
+impl PartialEq<[bool]> for SelectionVector {
+    fn eq(&self, other: &[bool]) -> bool {
+        self.len == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+impl PartialEq<&[bool]> for SelectionVector {
+    fn eq(&self, other: &&[bool]) -> bool {
+        self == *other
+    }
+}
+
+impl<const N: usize> PartialEq<[bool; N]> for SelectionVector {
+    fn eq(&self, other: &[bool; N]) -> bool {
+        self == other.as_slice()
+    }
+}
+
+impl PartialEq<Vec<bool>> for SelectionVector {
+    fn eq(&self, other: &Vec<bool>) -> bool {
+        self == other.as_slice()
     }
 }
 
@@ -126,6 +288,13 @@ pub trait GetData<'a> {
         (get_list, ListItem<'a>),
         (get_map, MapItem<'a>)
     );
+
+    /// Exposes the data as a [`PrimitiveColumn`], if it holds a primitive type in a layout the
+    /// kernel can read directly. When every column an engine passes to a [`RowVisitor`] can do so,
+    /// the engine should call [`RowVisitor::visit_primitive`] instead of [`RowVisitor::visit`].
+    fn as_primitive_column(&'a self) -> Option<PrimitiveColumn<'a>> {
+        None
+    }
 }
 
 macro_rules! impl_null_get {
@@ -147,6 +316,151 @@ impl<'a> GetData<'a> for () {
         (get_list, ListItem<'a>),
         (get_map, MapItem<'a>)
     );
+
+    fn as_primitive_column(&'a self) -> Option<PrimitiveColumn<'a>> {
+        Some(PrimitiveColumn::Null)
+    }
+}
+
+/// A bitmap backing a [`PrimitiveColumn`], with bits packed least significant bit first (i.e. the
+/// layout of an Arrow bitmap), starting at bit `offset` of `bits`.
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnBitmap<'a> {
+    bits: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ColumnBitmap<'a> {
+    pub fn new(bits: &'a [u8], offset: usize) -> Self {
+        Self { bits, offset }
+    }
+
+    /// Returns the bit for row `i`. Bits past the end of the bitmap are unset.
+    #[inline]
+    pub fn get(&self, i: usize) -> bool {
+        let i = self.offset + i;
+        self.bits
+            .get(i / 8)
+            .is_some_and(|byte| byte & (1 << (i % 8)) != 0)
+    }
+}
+
+/// A column of primitive values that engines can hand to [`RowVisitor::visit_primitive`]. Unlike
+/// a [`GetData`] trait object, the kernel can read the values of these columns directly, without
+/// dynamic dispatch for every value. The layouts match those of the corresponding Arrow arrays.
+///
+/// A `validity` bitmap marks the non-null rows of the column. If it is `None`, no row is null.
+#[derive(Debug, Clone, Copy)]
+pub enum PrimitiveColumn<'a> {
+    /// A column whose values are all null (e.g. because it is missing from the data).
+    Null,
+    Boolean {
+        values: ColumnBitmap<'a>,
+        validity: Option<ColumnBitmap<'a>>,
+    },
+    Integer {
+        values: &'a [i32],
+        validity: Option<ColumnBitmap<'a>>,
+    },
+    Long {
+        values: &'a [i64],
+        validity: Option<ColumnBitmap<'a>>,
+    },
+    /// The string at row `i` is `data[offsets[i] - offsets[0]..offsets[i + 1] - offsets[0]]`, i.e.
+    /// `data` starts at the first row's value.
+    String {
+        offsets: &'a [i32],
+        data: &'a str,
+        validity: Option<ColumnBitmap<'a>>,
+    },
+}
+
+impl PrimitiveColumn<'_> {
+    #[inline]
+    fn is_valid(validity: &Option<ColumnBitmap<'_>>, i: usize) -> bool {
+        validity.is_none_or(|validity| validity.get(i))
+    }
+}
+
+impl<'a> GetData<'a> for PrimitiveColumn<'a> {
+    #[inline]
+    fn get_bool(&'a self, row_index: usize, field_name: &str) -> DeltaResult<Option<bool>> {
+        match self {
+            Self::Null => Ok(None),
+            Self::Boolean { values, validity } => {
+                Ok(Self::is_valid(validity, row_index).then(|| values.get(row_index)))
+            }
+            _ => Err(Error::UnexpectedColumnType(format!(
+                "{field_name} is not of type bool"
+            ))),
+        }
+    }
+
+    #[inline]
+    fn get_int(&'a self, row_index: usize, field_name: &str) -> DeltaResult<Option<i32>> {
+        match self {
+            Self::Null => Ok(None),
+            Self::Integer { values, validity } if Self::is_valid(validity, row_index) => {
+                values.get(row_index).copied().map(Some).ok_or_else(|| {
+                    Error::internal_error(format!("{field_name} has no row {row_index}"))
+                })
+            }
+            Self::Integer { .. } => Ok(None),
+            _ => Err(Error::UnexpectedColumnType(format!(
+                "{field_name} is not of type i32"
+            ))),
+        }
+    }
+
+    #[inline]
+    fn get_long(&'a self, row_index: usize, field_name: &str) -> DeltaResult<Option<i64>> {
+        match self {
+            Self::Null => Ok(None),
+            Self::Long { values, validity } if Self::is_valid(validity, row_index) => {
+                values.get(row_index).copied().map(Some).ok_or_else(|| {
+                    Error::internal_error(format!("{field_name} has no row {row_index}"))
+                })
+            }
+            Self::Long { .. } => Ok(None),
+            _ => Err(Error::UnexpectedColumnType(format!(
+                "{field_name} is not of type i64"
+            ))),
+        }
+    }
+
+    #[inline]
+    fn get_str(&'a self, row_index: usize, field_name: &str) -> DeltaResult<Option<&'a str>> {
+        match self {
+            Self::Null => Ok(None),
+            Self::String {
+                offsets,
+                data,
+                validity,
+            } if Self::is_valid(validity, row_index) => {
+                let value = offsets
+                    .first()
+                    .zip(offsets.get(row_index).zip(offsets.get(row_index + 1)))
+                    .and_then(|(base, (start, end))| {
+                        let start = usize::try_from(start - base).ok()?;
+                        let end = usize::try_from(end - base).ok()?;
+                        data.get(start..end)
+                    });
+                value.map(Some).ok_or_else(|| {
+                    Error::internal_error(format!(
+                        "{field_name} has invalid offsets for row {row_index}"
+                    ))
+                })
+            }
+            Self::String { .. } => Ok(None),
+            _ => Err(Error::UnexpectedColumnType(format!(
+                "{field_name} is not of type &str"
+            ))),
+        }
+    }
+
+    fn as_primitive_column(&'a self) -> Option<PrimitiveColumn<'a>> {
+        Some(*self)
+    }
 }
 
 /// This is a convenience wrapper over `GetData` to allow code like: `let name: Option<String> =
@@ -164,7 +478,7 @@ pub trait TypedGetData<'a, T> {
 macro_rules! impl_typed_get_data {
     ( $(($name: ident, $typ: ty)), * ) => {
         $(
-            impl<'a> TypedGetData<'a, $typ> for dyn GetData<'a> +'_ {
+            impl<'a, G: GetData<'a> + ?Sized> TypedGetData<'a, $typ> for G {
                 fn get_opt(&'a self, row_index: usize, field_name: &str) -> DeltaResult<Option<$typ>> {
                     self.$name(row_index, field_name)
                 }
@@ -182,7 +496,7 @@ impl_typed_get_data!(
     (get_map, MapItem<'a>)
 );
 
-impl<'a> TypedGetData<'a, String> for dyn GetData<'a> + '_ {
+impl<'a, G: GetData<'a> + ?Sized> TypedGetData<'a, String> for G {
     fn get_opt(&'a self, row_index: usize, field_name: &str) -> DeltaResult<Option<String>> {
         self.get_str(row_index, field_name)
             .map(|s| s.map(|s| s.to_string()))
@@ -191,7 +505,7 @@ impl<'a> TypedGetData<'a, String> for dyn GetData<'a> + '_ {
 
 /// Provide an impl to get a list field as a `Vec<String>`. Note that this will allocate the vector
 /// and allocate for each string entry.
-impl<'a> TypedGetData<'a, Vec<String>> for dyn GetData<'a> + '_ {
+impl<'a, G: GetData<'a> + ?Sized> TypedGetData<'a, Vec<String>> for G {
     fn get_opt(&'a self, row_index: usize, field_name: &str) -> DeltaResult<Option<Vec<String>>> {
         let list_opt: Option<ListItem<'_>> = self.get_opt(row_index, field_name)?;
         Ok(list_opt.map(|list| list.materialize()))
@@ -200,7 +514,7 @@ impl<'a> TypedGetData<'a, Vec<String>> for dyn GetData<'a> + '_ {
 
 /// Provide an impl to get a map field as a `HashMap<String, String>`. Note that this will
 /// allocate the map and allocate for each entry
-impl<'a> TypedGetData<'a, HashMap<String, String>> for dyn GetData<'a> + '_ {
+impl<'a, G: GetData<'a> + ?Sized> TypedGetData<'a, HashMap<String, String>> for G {
     fn get_opt(
         &'a self,
         row_index: usize,
@@ -230,6 +544,20 @@ pub trait RowVisitor {
     /// does not outlive the call to this function (i.e. it should be copied if needed).
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()>;
 
+    /// Have the visitor visit data whose selected columns are all [`PrimitiveColumn`]s. Engines
+    /// call this instead of [`RowVisitor::visit`] when they can expose every selected column that
+    /// way, which lets visitors of flat projections (e.g. the paths and deletion vectors of file
+    /// actions) read their columns in a tight loop. The default implementation forwards the
+    /// columns to [`RowVisitor::visit`] as getters.
+    fn visit_primitive<'a>(
+        &mut self,
+        row_count: usize,
+        columns: &'a [PrimitiveColumn<'a>],
+    ) -> DeltaResult<()> {
+        let getters: Vec<&'a dyn GetData<'a>> = columns.iter().map(|column| column as _).collect();
+        self.visit(row_count, &getters)
+    }
+
     /// Visit the rows of an [`EngineData`], selecting the leaf column names given by
     /// [`RowVisitor::selected_column_names_and_types`]. This is a thin wrapper around
     /// [`EngineData::visit_rows`] which in turn will eventually invoke [`RowVisitor::visit`].
@@ -315,3 +643,34 @@ pub trait EngineData: AsAny {
         columns: Vec<ArrayData>,
     ) -> DeltaResult<Box<dyn EngineData>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_vector() {
+        let selected = [
+            true, false, false, true, true, false, true, false, false, true,
+        ];
+        let selection_vector = SelectionVector::from(selected.as_slice());
+        assert_eq!(selection_vector.len(), 10);
+        assert_eq!(selection_vector.as_bytes(), [0b0101_1001, 0b10]);
+        assert_eq!(selection_vector.to_vec(), selected);
+        assert_eq!(selection_vector.get(3), Some(true));
+        assert_eq!(selection_vector.get(10), None);
+        assert_eq!(selection_vector.count_selected(), 5);
+        assert!(selection_vector.has_selected_rows());
+        assert_eq!(selection_vector, selected);
+
+        // Bits past the end of the bitmap are ignored
+        let padded = SelectionVector::try_new(Bytes::from_static(&[0b1111_1001, 0xff]), 3).unwrap();
+        assert_eq!(padded, [true, false, false]);
+        assert_eq!(padded.count_selected(), 1);
+        assert!(SelectionVector::try_new(Bytes::from_static(&[0xff]), 9).is_err());
+
+        let none_selected = SelectionVector::from(vec![false; 20]);
+        assert!(!none_selected.has_selected_rows());
+        assert!(SelectionVector::default().is_empty());
+    }
+}
@@ -1,16 +1,31 @@
 //! Definitions of errors that the delta kernel can encounter
+//!
+//! Engines that map kernel errors to their own error types need not match on every [`Error`]
+//! variant: each error has an [`ErrorCategory`] and a stable numeric [code](Error::code), tells
+//! whether it is [retryable](Error::is_retryable), and may carry an [`ErrorContext`] naming the
+//! table, version and column it concerns. [`Error::categorize`] turns an error into a
+//! [`CategorizedError`] with one variant per category that carries all of these.
+//!
+//! Kernel attaches the context it knows, e.g. the table and version when building a snapshot or
+//! scan, or the column a schema error concerns. Errors with context wrap the original error, so
+//! callers match on the specific [`Error`] variant through [`Error::inner`]. Engines can annotate
+//! errors further with [`Error::with_table`] and friends.
 
 use std::{
     backtrace::{Backtrace, BacktraceStatus},
+    fmt,
     num::ParseIntError,
     str::Utf8Error,
 };
 
+use url::Url;
+
+use crate::expressions::ColumnName;
 use crate::schema::{DataType, StructType};
 use crate::table_properties::ParseIntervalError;
 use crate::Version;
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
 use crate::arrow::error::ArrowError;
 #[cfg(feature = "default-engine-base")]
 use object_store;
@@ -32,8 +47,16 @@ pub enum Error {
         backtrace: Box<Backtrace>,
     },
 
+    /// An error annotated with the table, version and/or column it concerns. See
+    /// [`Error::context`].
+    #[error("{source} ({context})")]
+    WithContext {
+        source: Box<Self>,
+        context: Box<ErrorContext>,
+    },
+
     /// An error performing operations on arrow data
-    #[cfg(feature = "default-engine-base")]
+    #[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
     #[error(transparent)]
     Arrow(ArrowError),
 
@@ -68,7 +91,7 @@ pub enum Error {
     InternalError(String),
 
     /// An error enountered while working with parquet data
-    #[cfg(feature = "default-engine-base")]
+    #[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
     #[error("Arrow error: {0}")]
     Parquet(#[from] crate::parquet::errors::ParquetError),
 
@@ -79,6 +102,15 @@ pub enum Error {
     #[error("Error interacting with object store: {0}")]
     ObjectStore(object_store::Error),
 
+    /// An object store request failed transiently (e.g. with a server error or a timeout), and
+    /// kept failing while being retried, see
+    /// [`RetryPolicy`](crate::engine::default::storage::RetryPolicy). Retrying the operation later
+    /// may succeed. Only failures of requests by the cloud object stores' HTTP clients are
+    /// recognized as transient.
+    #[cfg(feature = "default-engine-base")]
+    #[error("Object store request failed after retrying: {0}")]
+    ObjectStoreRetriesExhausted(object_store::Error),
+
     /// An error working with paths from the object_store crate
     #[cfg(feature = "default-engine-base")]
     #[error("Object store path error: {0}")]
@@ -159,6 +191,10 @@ pub enum Error {
     #[error("Invalid table location: {0}.")]
     InvalidTableLocation(String),
 
+    /// Asked for a table at a location that doesn't contain a Delta log
+    #[error("Not a Delta table: {0}")]
+    NotADeltaTable(String),
+
     /// Precision or scale not compliant with delta specification
     #[error("Invalid decimal: {0}")]
     InvalidDecimal(String),
@@ -179,6 +215,12 @@ pub enum Error {
     #[error("File already exists: {0}")]
     FileAlreadyExists(String),
 
+    /// Another writer committed the table version a transaction tried to commit first, see
+    /// [`CommitResult::Conflict`](crate::transaction::CommitResult::Conflict). Retrying the
+    /// transaction on a fresh snapshot of the table may succeed.
+    #[error("Commit conflict: version {0} of the table was committed concurrently")]
+    CommitConflict(Version),
+
     /// Some functionality is currently unsupported
     #[error("Unsupported: {0}")]
     Unsupported(String),
@@ -206,6 +248,163 @@ pub enum Error {
     /// Schema mismatch has occurred or invalid schema used somewhere
     #[error("Schema error: {0}")]
     Schema(String),
+
+    /// Data to be written violates a column invariant of the table
+    #[error("Invariant violation: {0}")]
+    InvariantViolation(String),
+
+    /// The state of a table does not match the version checksum (`.crc`) file of its version
+    #[error("Checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+}
+
+/// The broad kind of an [`Error`], see [`Error::category`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// Accessing storage failed, or a location doesn't hold what was expected (e.g. a missing
+    /// file, or a location that isn't a Delta table)
+    Storage,
+    /// The Delta log or the table's data doesn't follow the Delta protocol (e.g. a missing
+    /// protocol action, an invalid checkpoint, or data that violates a column invariant)
+    Protocol,
+    /// A schema, column or value doesn't have the expected name, type or shape
+    Schema,
+    /// A concurrent operation on the table conflicted with this one (e.g. another writer committed
+    /// the same version first)
+    Concurrency,
+    /// The table or the requested operation uses something kernel doesn't support
+    Unsupported,
+    /// Any other error, including kernel bugs and failures of the libraries kernel uses
+    Internal,
+}
+
+/// Structured context of an [`Error`]: the table, version and column the failed operation
+/// concerned, where known. See [`Error::context`].
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The root URL of the table
+    pub table: Option<Url>,
+    /// The version of the table
+    pub version: Option<Version>,
+    /// The column, by its logical name
+    pub column: Option<ColumnName>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut sep = "";
+        if let Some(table) = &self.table {
+            write!(f, "table: {table}")?;
+            sep = ", ";
+        }
+        if let Some(version) = self.version {
+            write!(f, "{sep}version: {version}")?;
+            sep = ", ";
+        }
+        if let Some(column) = &self.column {
+            write!(f, "{sep}column: {column}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An [`Error`] classified by its [`ErrorCategory`], with one variant per category, see
+/// [`Error::categorize`]. This lets engines map kernel errors to their own error types with a
+/// single `match`, while kernel itself keeps returning (and callers keep matching on) the
+/// specific [`Error`] variants.
+#[non_exhaustive]
+#[derive(thiserror::Error, Debug)]
+pub enum CategorizedError {
+    /// See [`ErrorCategory::Storage`]
+    #[error(transparent)]
+    Storage(ErrorDetails),
+    /// See [`ErrorCategory::Protocol`]
+    #[error(transparent)]
+    Protocol(ErrorDetails),
+    /// See [`ErrorCategory::Schema`]
+    #[error(transparent)]
+    Schema(ErrorDetails),
+    /// See [`ErrorCategory::Concurrency`]
+    #[error(transparent)]
+    Concurrency(ErrorDetails),
+    /// See [`ErrorCategory::Unsupported`]
+    #[error(transparent)]
+    Unsupported(ErrorDetails),
+    /// See [`ErrorCategory::Internal`]
+    #[error(transparent)]
+    Internal(ErrorDetails),
+}
+
+impl CategorizedError {
+    /// The category of this error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::Storage(_) => ErrorCategory::Storage,
+            Self::Protocol(_) => ErrorCategory::Protocol,
+            Self::Schema(_) => ErrorCategory::Schema,
+            Self::Concurrency(_) => ErrorCategory::Concurrency,
+            Self::Unsupported(_) => ErrorCategory::Unsupported,
+            Self::Internal(_) => ErrorCategory::Internal,
+        }
+    }
+
+    /// The code, retryability, context and source of this error.
+    pub fn details(&self) -> &ErrorDetails {
+        match self {
+            Self::Storage(details)
+            | Self::Protocol(details)
+            | Self::Schema(details)
+            | Self::Concurrency(details)
+            | Self::Unsupported(details)
+            | Self::Internal(details) => details,
+        }
+    }
+
+    /// The [`Error`] this was categorized from.
+    pub fn into_error(self) -> Error {
+        match self {
+            Self::Storage(details)
+            | Self::Protocol(details)
+            | Self::Schema(details)
+            | Self::Concurrency(details)
+            | Self::Unsupported(details)
+            | Self::Internal(details) => details.error,
+        }
+    }
+}
+
+impl From<Error> for CategorizedError {
+    fn from(err: Error) -> Self {
+        err.categorize()
+    }
+}
+
+/// The details of a [`CategorizedError`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ErrorDetails {
+    /// The stable code of the error, see [`Error::code`]
+    pub code: u32,
+    /// Whether the error is retryable, see [`Error::is_retryable`]
+    pub retryable: bool,
+    /// The context of the error, empty if none is known. See [`Error::context`].
+    pub context: ErrorContext,
+    /// The error itself
+    pub error: Error,
+}
+
+impl fmt::Display for ErrorDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for ErrorDetails {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
 }
 
 // Convenience constructors for Error types that take a String argument
@@ -225,6 +424,9 @@ impl Error {
     pub fn file_not_found(path: impl ToString) -> Self {
         Self::FileNotFound(path.to_string())
     }
+    pub fn commit_conflict(version: Version) -> Self {
+        Self::CommitConflict(version)
+    }
     pub fn missing_column(name: impl ToString) -> Self {
         Self::MissingColumn(name.to_string()).with_backtrace()
     }
@@ -246,6 +448,9 @@ impl Error {
     pub fn invalid_table_location(location: impl ToString) -> Self {
         Self::InvalidTableLocation(location.to_string())
     }
+    pub fn not_a_delta_table(msg: impl ToString) -> Self {
+        Self::NotADeltaTable(msg.to_string())
+    }
     pub fn invalid_column_mapping_mode(mode: impl ToString) -> Self {
         Self::InvalidColumnMappingMode(mode.to_string())
     }
@@ -291,6 +496,194 @@ impl Error {
         Self::Schema(msg.to_string())
     }
 
+    pub fn invariant_violation(msg: impl ToString) -> Self {
+        Self::InvariantViolation(msg.to_string())
+    }
+
+    pub(crate) fn checksum_mismatch(msg: impl ToString) -> Self {
+        Self::ChecksumMismatch(msg.to_string())
+    }
+
+    /// A stable numeric code identifying the kind of this error, for engines to map errors to
+    /// their own error codes. The thousands digit of the code is that of its [`ErrorCategory`]:
+    /// 1xxx for [`Storage`], 2xxx for [`Protocol`], 3xxx for [`Schema`], 4xxx for
+    /// [`Concurrency`], 5xxx for [`Unsupported`] and 9xxx for [`Internal`]. Codes are never
+    /// changed or reused, so new kinds of errors get new codes.
+    ///
+    /// [`Storage`]: ErrorCategory::Storage
+    /// [`Protocol`]: ErrorCategory::Protocol
+    /// [`Schema`]: ErrorCategory::Schema
+    /// [`Concurrency`]: ErrorCategory::Concurrency
+    /// [`Unsupported`]: ErrorCategory::Unsupported
+    /// [`Internal`]: ErrorCategory::Internal
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::Backtraced { source, .. } | Self::WithContext { source, .. } => source.code(),
+            Self::IOError(_) => 1001,
+            #[cfg(feature = "default-engine-base")]
+            Self::ObjectStore(_) => 1002,
+            #[cfg(feature = "default-engine-base")]
+            Self::ObjectStoreRetriesExhausted(_) => 1003,
+            #[cfg(feature = "default-engine-base")]
+            Self::ObjectStorePath(_) => 1004,
+            #[cfg(feature = "default-engine-base")]
+            Self::Reqwest(_) => 1005,
+            Self::FileNotFound(_) => 1006,
+            Self::InvalidUrl(_) => 1007,
+            Self::InvalidTableLocation(_) => 1008,
+            Self::NotADeltaTable(_) => 1009,
+            Self::CheckpointWrite(_) => 1010,
+            Self::FileAlreadyExists(_) => 1011,
+            Self::MissingVersion => 2001,
+            Self::MissingMetadata => 2002,
+            Self::MissingProtocol => 2003,
+            Self::MissingMetadataAndProtocol => 2004,
+            Self::InvalidProtocol(_) => 2005,
+            Self::InvalidCheckpoint(_) => 2006,
+            Self::InvalidLogPath(_) => 2007,
+            Self::MalformedJson(_) => 2008,
+            Self::DeletionVector(_) => 2009,
+            Self::InvariantViolation(_) => 2010,
+            Self::ParseIntervalError(_) => 2011,
+            Self::ChecksumMismatch(_) => 2012,
+            Self::MissingColumn(_) => 3001,
+            Self::UnexpectedColumnType(_) => 3002,
+            Self::Schema(_) => 3003,
+            Self::InvalidColumnMappingMode(_) => 3004,
+            Self::InvalidDecimal(_) => 3005,
+            Self::InvalidStructData(_) => 3006,
+            Self::ChangeDataFeedIncompatibleSchema(..) => 3007,
+            Self::ParseError(..) => 3008,
+            Self::InvalidExpressionEvaluation(_) => 3009,
+            Self::LiteralExpressionTransformError(_) => 3010,
+            Self::CommitConflict(_) => 4001,
+            Self::Unsupported(_) => 5001,
+            Self::ChangeDataFeedUnsupported(_) => 5002,
+            Self::InternalError(_) => 9001,
+            Self::Generic(_) => 9002,
+            Self::GenericError { .. } => 9003,
+            #[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+            Self::Arrow(_) => 9004,
+            #[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+            Self::Parquet(_) => 9005,
+            Self::JoinFailure(_) => 9006,
+            Self::Utf8Error(_) => 9007,
+            Self::ParseIntError(_) => 9008,
+            Self::Extract(..) => 9009,
+            Self::EngineDataType(_) => 9010,
+            Self::MissingData(_) => 9011,
+        }
+    }
+
+    /// The [`ErrorCategory`] of this error.
+    pub fn category(&self) -> ErrorCategory {
+        match self.code() / 1000 {
+            1 => ErrorCategory::Storage,
+            2 => ErrorCategory::Protocol,
+            3 => ErrorCategory::Schema,
+            4 => ErrorCategory::Concurrency,
+            5 => ErrorCategory::Unsupported,
+            _ => ErrorCategory::Internal,
+        }
+    }
+
+    /// Classify this error into a [`CategorizedError`] carrying its code, retryability and context.
+    pub fn categorize(self) -> CategorizedError {
+        let category = self.category();
+        let details = ErrorDetails {
+            code: self.code(),
+            retryable: self.is_retryable(),
+            context: self.context().cloned().unwrap_or_default(),
+            error: self,
+        };
+        match category {
+            ErrorCategory::Storage => CategorizedError::Storage(details),
+            ErrorCategory::Protocol => CategorizedError::Protocol(details),
+            ErrorCategory::Schema => CategorizedError::Schema(details),
+            ErrorCategory::Concurrency => CategorizedError::Concurrency(details),
+            ErrorCategory::Unsupported => CategorizedError::Unsupported(details),
+            ErrorCategory::Internal => CategorizedError::Internal(details),
+        }
+    }
+
+    /// This error without the backtrace and context it may be wrapped in, e.g. to match on its
+    /// variant.
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::Backtraced { source, .. } | Self::WithContext { source, .. } => source.inner(),
+            err => err,
+        }
+    }
+
+    /// The table, version and column this error concerns, if known.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::WithContext { context, .. } => Some(context),
+            Self::Backtraced { source, .. } => source.context(),
+            _ => None,
+        }
+    }
+
+    /// Annotate this error with the root URL of the table it concerns, unless already known.
+    #[must_use]
+    pub fn with_table(self, table: &Url) -> Self {
+        self.with_context(|context| {
+            context.table.get_or_insert_with(|| table.clone());
+        })
+    }
+
+    /// Annotate this error with the table version it concerns, unless already known.
+    #[must_use]
+    pub fn with_version(self, version: Version) -> Self {
+        self.with_context(|context| {
+            context.version.get_or_insert(version);
+        })
+    }
+
+    /// Annotate this error with the column it concerns, unless already known.
+    #[must_use]
+    pub fn with_column(self, column: ColumnName) -> Self {
+        self.with_context(|context| {
+            context.column.get_or_insert(column);
+        })
+    }
+
+    fn with_context(self, update: impl FnOnce(&mut ErrorContext)) -> Self {
+        let (source, mut context) = match self {
+            // keep the backtrace last in the error message
+            Self::Backtraced { source, backtrace } => {
+                let source = Box::new(source.with_context(update));
+                return Self::Backtraced { source, backtrace };
+            }
+            Self::WithContext { source, context } => (source, context),
+            err => (Box::new(err), Box::default()),
+        };
+        update(&mut context);
+        Self::WithContext { source, context }
+    }
+
+    /// Whether this error is likely transient, such that retrying the failed operation may succeed.
+    ///
+    /// Commit conflicts ([`Error::CommitConflict`], i.e. another writer committing the same version
+    /// first) are retryable by rebuilding the operation on a fresh snapshot. Other writes failing
+    /// because their file exists ([`Error::FileAlreadyExists`]) are not, since retrying them can
+    /// never succeed. Object store
+    /// requests that kept failing despite the store's own retries are retryable too, since such
+    /// failures (timeouts, throttling, server errors) are transient; retrying the whole operation
+    /// later, with a backoff, may succeed. Other object store errors, e.g. missing permissions or
+    /// invalid configuration, are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Backtraced { source, .. } | Self::WithContext { source, .. } => {
+                source.is_retryable()
+            }
+            Self::CommitConflict(_) => true,
+            #[cfg(feature = "default-engine-base")]
+            Self::ObjectStoreRetriesExhausted(_) => true,
+            _ => false,
+        }
+    }
+
     // Capture a backtrace when the error is constructed.
     #[must_use]
     pub fn with_backtrace(self) -> Self {
@@ -322,7 +715,7 @@ from_with_backtrace!(
     (std::io::Error, IOError)
 );
 
-#[cfg(feature = "default-engine-base")]
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
 impl From<ArrowError> for Error {
     fn from(value: ArrowError) -> Self {
         Self::Arrow(value).with_backtrace()
@@ -334,7 +727,126 @@ impl From<object_store::Error> for Error {
     fn from(value: object_store::Error) -> Self {
         match value {
             object_store::Error::NotFound { path, .. } => Self::file_not_found(path),
+            err if is_transient(&err) => Self::ObjectStoreRetriesExhausted(err),
             err => Self::ObjectStore(err),
         }
     }
 }
+
+// Object store clients retry requests that fail transiently: with a server error (5xx),
+// throttling (429), a request timeout (408), or a transport error such as a failed connection or a
+// timeout. A request whose final failure is transient has therefore exhausted its retries (which
+// includes clients configured with `max_retries: 0`), while a request whose final failure isn't
+// (e.g. 400 Bad Request or 403 Forbidden) is not retryable, even if earlier attempts were retried.
+//
+// The client's error types are not public, so we check the status code in the messages of the
+// error chain ("... Server returned non-2xx status code: 503 Service Unavailable: ...") and the
+// reqwest error a transport error wraps. Errors of other object stores, or of clients reporting
+// failures differently, are not recognized and become `Error::ObjectStore`.
+#[cfg(feature = "default-engine-base")]
+fn is_transient(err: &object_store::Error) -> bool {
+    // other variants (e.g. `PermissionDenied` or `Precondition`) are never transient
+    if !matches!(err, object_store::Error::Generic { .. }) {
+        return false;
+    }
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err);
+    while let Some(err) = source {
+        if let Some(status) = status_code(&err.to_string()) {
+            return status >= 500 || status == 429 || status == 408;
+        }
+        if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+            return err.is_timeout() || err.is_connect() || err.is_request() || err.is_body();
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// The HTTP status code in an object store client's message for a request that failed with one.
+#[cfg(feature = "default-engine-base")]
+fn status_code(message: &str) -> Option<u16> {
+    let (_, status) = message.split_once("status code: ")?;
+    status.get(..3)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::column_name;
+
+    #[test]
+    fn test_error_codes_and_categories() {
+        let cases = [
+            (Error::file_not_found("x"), 1006, ErrorCategory::Storage),
+            (Error::not_a_delta_table("x"), 1009, ErrorCategory::Storage),
+            (Error::MissingProtocol, 2003, ErrorCategory::Protocol),
+            (Error::missing_column("x"), 3001, ErrorCategory::Schema),
+            (Error::unsupported("x"), 5001, ErrorCategory::Unsupported),
+            (Error::internal_error("x"), 9001, ErrorCategory::Internal),
+            (Error::generic("x"), 9002, ErrorCategory::Internal),
+        ];
+        for (err, code, category) in cases {
+            let err = err.with_version(1);
+            assert_eq!(err.code(), code, "{err}");
+            assert_eq!(err.category(), category, "{err}");
+            assert!(!err.is_retryable(), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_commit_conflicts_are_retryable() {
+        let err = Error::commit_conflict(1);
+        assert_eq!(err.code(), 4001);
+        assert_eq!(err.category(), ErrorCategory::Concurrency);
+        assert!(err.is_retryable());
+        assert!(err.with_version(1).with_backtrace().is_retryable());
+
+        // writing a file that exists (e.g. a data file) never succeeds when retried
+        let err = Error::FileAlreadyExists("part-00000.parquet".into());
+        assert_eq!(err.code(), 1011);
+        assert_eq!(err.category(), ErrorCategory::Storage);
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_error_context() {
+        let table = Url::parse("memory:///table/").unwrap();
+        let err = Error::missing_column("a.b");
+        assert_eq!(err.context(), None);
+
+        // the innermost context wins
+        let err = err
+            .with_column(column_name!("a.b"))
+            .with_version(3)
+            .with_table(&table)
+            .with_version(5);
+        let expected = ErrorContext {
+            table: Some(table),
+            version: Some(3),
+            column: Some(column_name!("a.b")),
+        };
+        assert_eq!(err.context(), Some(&expected));
+        assert!(matches!(err.inner(), Error::MissingColumn(name) if name == "a.b"));
+        assert!(err
+            .to_string()
+            .starts_with("a.b (table: memory:///table/, version: 3, column: a.b)"));
+    }
+
+    #[test]
+    fn test_categorize() {
+        let err = Error::file_not_found("x").with_version(2).categorize();
+        assert_eq!(err.category(), ErrorCategory::Storage);
+        let CategorizedError::Storage(details) = &err else {
+            panic!("unexpected category: {err:?}");
+        };
+        assert_eq!(details.code, 1006);
+        assert!(!details.retryable);
+        assert_eq!(details.context.version, Some(2));
+        assert!(matches!(err.into_error().inner(), Error::FileNotFound(_)));
+
+        let err = CategorizedError::from(Error::unsupported("x"));
+        assert!(matches!(err, CategorizedError::Unsupported(_)));
+        assert_eq!(err.details().context, ErrorContext::default());
+        assert_eq!(err.to_string(), Error::unsupported("x").to_string());
+    }
+}
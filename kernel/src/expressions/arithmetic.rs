@@ -0,0 +1,654 @@
+//! Type coercion and (scalar) evaluation of arithmetic and cast expressions.
+
+use std::cmp::{max, min};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+use crate::expressions::{BinaryExpressionOp, CastOverflowPolicy, DecimalData, Scalar};
+use crate::schema::{DataType, DecimalType, PrimitiveType};
+use crate::{DeltaResult, Error};
+
+/// The largest precision a decimal can have
+const MAX_DECIMAL_PRECISION: u8 = 38;
+
+/// The smallest scale a decimal result is reduced to when its precision is capped
+const MIN_ADJUSTED_DECIMAL_SCALE: u8 = 6;
+
+impl BinaryExpressionOp {
+    /// Returns the type of the result of applying this operator to operands of the given types,
+    /// or an error if the operator does not support them. The coercion rules follow Spark SQL,
+    /// which Delta writers use to evaluate e.g. generated column expressions:
+    ///
+    /// - Integral operands (`byte`, `short`, `integer`, `long`) are widened to the wider of the
+    ///   two types, which is also the result type, except that dividing two integral values
+    ///   converts both to `double`, producing a `double`.
+    /// - If either operand is a `float` or `double` and the other is numeric, the result is the
+    ///   wider floating-point type of the two (`double` if the other operand is a decimal).
+    /// - If either operand is a decimal and the other is a decimal or integral, integral operands
+    ///   are treated as the narrowest decimal holding all their values (e.g. `decimal(10,0)` for
+    ///   `integer`). The result of `decimal(p1,s1) op decimal(p2,s2)` is a decimal with:
+    ///
+    ///   | Operator | Scale               | Precision                       |
+    ///   |----------|---------------------|---------------------------------|
+    ///   | `+`, `-` | max(s1, s2)         | max(p1-s1, p2-s2) + scale + 1   |
+    ///   | `*`      | s1 + s2             | p1 + p2 + 1                     |
+    ///   | `/`      | max(6, s1 + p2 + 1) | p1 - s1 + s2 + scale            |
+    ///   | `%`      | max(s1, s2)         | min(p1-s1, p2-s2) + scale       |
+    ///
+    ///   A precision above 38 is capped at 38, and the scale reduced to preserve the integral
+    ///   digits (but not below 6, or the original scale if that is smaller).
+    /// - `date + n`, `n + date`, and `date - n` add (subtract) the integral number of days `n`,
+    ///   producing a `date`.
+    ///
+    /// Arithmetic involving a NULL value produces NULL. Integral and decimal overflow, as well as
+    /// division by zero of integral or decimal values, are errors; floating-point arithmetic
+    /// follows IEEE 754.
+    pub fn result_type(&self, left: &DataType, right: &DataType) -> DeltaResult<DataType> {
+        use BinaryExpressionOp::*;
+        use PrimitiveType::*;
+        let unsupported = || {
+            Error::invalid_expression(format!(
+                "Arithmetic operator {self} does not support operands of type {left} and {right}"
+            ))
+        };
+        let (DataType::Primitive(l), DataType::Primitive(r)) = (left, right) else {
+            return Err(unsupported());
+        };
+        let result = match (l, r) {
+            (Date, n) if integral_rank(n).is_some() && matches!(self, Plus | Minus) => Date,
+            (n, Date) if integral_rank(n).is_some() && *self == Plus => Date,
+            (l, r) => match (integral_rank(l), integral_rank(r)) {
+                (Some(_), Some(_)) if *self == Divide => Double,
+                (Some(lrank), Some(rrank)) => match lrank >= rrank {
+                    true => l.clone(),
+                    false => r.clone(),
+                },
+                _ if matches!((l, r), (Double, _) | (_, Double)) => {
+                    numeric_or(l, r, Double).ok_or_else(unsupported)?
+                }
+                _ if matches!((l, r), (Float, Decimal(_)) | (Decimal(_), Float)) => Double,
+                _ if matches!((l, r), (Float, _) | (_, Float)) => {
+                    numeric_or(l, r, Float).ok_or_else(unsupported)?
+                }
+                _ => match (to_decimal_type(l), to_decimal_type(r)) {
+                    (Some(l), Some(r)) => Decimal(self.decimal_result_type(l, r)),
+                    _ => return Err(unsupported()),
+                },
+            },
+        };
+        Ok(result.into())
+    }
+
+    // See the table in [`Self::result_type`]
+    fn decimal_result_type(&self, left: DecimalType, right: DecimalType) -> DecimalType {
+        use BinaryExpressionOp::*;
+        let (p1, s1) = (left.precision() as u32, left.scale() as u32);
+        let (p2, s2) = (right.precision() as u32, right.scale() as u32);
+        let (precision, scale) = match self {
+            Plus | Minus => {
+                let scale = max(s1, s2);
+                (max(p1 - s1, p2 - s2) + scale + 1, scale)
+            }
+            Multiply => (p1 + p2 + 1, s1 + s2),
+            Divide => {
+                let scale = max(MIN_ADJUSTED_DECIMAL_SCALE as u32, s1 + p2 + 1);
+                (p1 - s1 + s2 + scale, scale)
+            }
+            Modulo => {
+                let scale = max(s1, s2);
+                (min(p1 - s1, p2 - s2) + scale, scale)
+            }
+        };
+        let max_precision = MAX_DECIMAL_PRECISION as u32;
+        let (precision, scale) = if precision <= max_precision {
+            (precision, scale)
+        } else {
+            let integral_digits = precision - scale;
+            let min_scale = min(scale, MIN_ADJUSTED_DECIMAL_SCALE as u32);
+            let scale = max(max_precision.saturating_sub(integral_digits), min_scale);
+            (max_precision, scale)
+        };
+        // The precision and scale are both within range by construction
+        #[allow(clippy::unwrap_used)]
+        DecimalType::try_new(precision as u8, scale as u8).unwrap()
+    }
+}
+
+// Ranks integral types by width, or returns None for non-integral types.
+fn integral_rank(data_type: &PrimitiveType) -> Option<u8> {
+    use PrimitiveType::*;
+    match data_type {
+        Byte => Some(1),
+        Short => Some(2),
+        Integer => Some(3),
+        Long => Some(4),
+        _ => None,
+    }
+}
+
+// Returns `result` if both types are numeric, or None otherwise.
+fn numeric_or(
+    left: &PrimitiveType,
+    right: &PrimitiveType,
+    result: PrimitiveType,
+) -> Option<PrimitiveType> {
+    use PrimitiveType::*;
+    let is_numeric =
+        |t: &PrimitiveType| matches!(t, Float | Double | Decimal(_)) || integral_rank(t).is_some();
+    (is_numeric(left) && is_numeric(right)).then_some(result)
+}
+
+/// Returns the decimal type an operand of the given type is treated as in decimal arithmetic, or
+/// None if the type is neither decimal nor integral.
+pub(crate) fn to_decimal_type(data_type: &PrimitiveType) -> Option<DecimalType> {
+    use PrimitiveType::*;
+    let precision = match data_type {
+        Decimal(dtype) => return Some(*dtype),
+        Byte => 3,
+        Short => 5,
+        Integer => 10,
+        Long => 20,
+        _ => return None,
+    };
+    DecimalType::try_new(precision, 0).ok()
+}
+
+impl Scalar {
+    /// Applies an arithmetic operator to two scalars, following the coercion rules of
+    /// [`BinaryExpressionOp::result_type`]. Returns None if the operator does not support the
+    /// operand types, or if the operation overflows or divides by zero.
+    pub fn try_arithmetic(&self, op: BinaryExpressionOp, other: &Scalar) -> Option<Scalar> {
+        let result_type = op.result_type(&self.data_type(), &other.data_type()).ok()?;
+        if self.is_null() || other.is_null() {
+            return Some(Scalar::Null(result_type));
+        }
+        let DataType::Primitive(result_type) = result_type else {
+            return None;
+        };
+        use PrimitiveType::*;
+        let result = match result_type {
+            Byte => Scalar::Byte(integral_op(op, self, other)?.try_into().ok()?),
+            Short => Scalar::Short(integral_op(op, self, other)?.try_into().ok()?),
+            Integer => Scalar::Integer(integral_op(op, self, other)?.try_into().ok()?),
+            Long => Scalar::Long(integral_op(op, self, other)?),
+            Float => Scalar::Float(float_op(op, self.as_f64()? as f32, other.as_f64()? as f32)),
+            // Integral division produces a double, but still fails on division by zero
+            Double
+                if op == BinaryExpressionOp::Divide
+                    && self.as_i64().is_some()
+                    && other.as_i64() == Some(0) =>
+            {
+                return None
+            }
+            Double => Scalar::Double(float_op(op, self.as_f64()?, other.as_f64()?)),
+            Date => {
+                let (days, n) = match (self, other) {
+                    (Scalar::Date(days), n) => (*days, n.as_i64()?),
+                    (n, Scalar::Date(days)) => (*days, n.as_i64()?),
+                    _ => return None,
+                };
+                let n = i32::try_from(n).ok()?;
+                match op {
+                    BinaryExpressionOp::Minus => Scalar::Date(days.checked_sub(n)?),
+                    _ => Scalar::Date(days.checked_add(n)?),
+                }
+            }
+            Decimal(dtype) => Scalar::Decimal(decimal_op(op, self, other, dtype)?),
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Converts a scalar to the given type. Supported conversions are between numeric types (except
+    /// from floating point or decimal to integral types), and from strings to primitive types.
+    /// Values that cannot be represented in the target type produce NULL or None, depending on
+    /// `on_overflow`. Returns None if the conversion is not supported.
+    pub fn try_cast(&self, to: &DataType, on_overflow: CastOverflowPolicy) -> Option<Scalar> {
+        if self.is_null() {
+            return Some(Scalar::Null(to.clone()));
+        }
+        if self.data_type() == *to {
+            return Some(self.clone());
+        }
+        let DataType::Primitive(to_primitive) = to else {
+            return None;
+        };
+        use PrimitiveType::*;
+        let converted = match (self, to_primitive) {
+            (Scalar::String(s), _) => to_primitive.parse_scalar(s).ok(),
+            (_, Byte) => i8::try_from(self.as_i64()?).ok().map(Scalar::Byte),
+            (_, Short) => i16::try_from(self.as_i64()?).ok().map(Scalar::Short),
+            (_, Integer) => i32::try_from(self.as_i64()?).ok().map(Scalar::Integer),
+            (_, Long) => Some(Scalar::Long(self.as_i64()?)),
+            (_, Float) => Some(Scalar::Float(self.as_f64()? as f32)),
+            (_, Double) => Some(Scalar::Double(self.as_f64()?)),
+            (_, Decimal(dtype)) => {
+                let (bits, scale) = self.as_decimal_parts()?;
+                rescale(bits, scale, dtype.scale())
+                    .and_then(|bits| DecimalData::try_new(bits, *dtype).ok())
+                    .map(Scalar::Decimal)
+            }
+            _ => return None,
+        };
+        match (converted, on_overflow) {
+            (Some(value), _) => Some(value),
+            (None, CastOverflowPolicy::Null) => Some(Scalar::Null(to.clone())),
+            (None, CastOverflowPolicy::Error) => None,
+        }
+    }
+
+    // The value of an integral scalar
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Scalar::Byte(v) => Some(*v as i64),
+            Scalar::Short(v) => Some(*v as i64),
+            Scalar::Integer(v) => Some(*v as i64),
+            Scalar::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    // The value of a numeric scalar, converted to floating point
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Scalar::Float(v) => Some(*v as f64),
+            Scalar::Double(v) => Some(*v),
+            Scalar::Decimal(d) => Some(d.bits() as f64 / 10f64.powi(d.scale() as i32)),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+
+    // The unscaled value and scale of a decimal or integral scalar
+    fn as_decimal_parts(&self) -> Option<(i128, u8)> {
+        match self {
+            Scalar::Decimal(d) => Some((d.bits(), d.scale())),
+            _ => self.as_i64().map(|v| (v as i128, 0)),
+        }
+    }
+}
+
+fn integral_op(op: BinaryExpressionOp, left: &Scalar, right: &Scalar) -> Option<i64> {
+    use BinaryExpressionOp::*;
+    let (a, b) = (left.as_i64()?, right.as_i64()?);
+    match op {
+        Plus => a.checked_add(b),
+        Minus => a.checked_sub(b),
+        Multiply => a.checked_mul(b),
+        Divide => a.checked_div(b),
+        Modulo => a.checked_rem(b),
+    }
+}
+
+fn float_op<T>(op: BinaryExpressionOp, a: T, b: T) -> T
+where
+    T: Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Rem<Output = T>,
+{
+    use BinaryExpressionOp::*;
+    match op {
+        Plus => a + b,
+        Minus => a - b,
+        Multiply => a * b,
+        Divide => a / b,
+        Modulo => a % b,
+    }
+}
+
+fn decimal_op(
+    op: BinaryExpressionOp,
+    left: &Scalar,
+    right: &Scalar,
+    result_type: DecimalType,
+) -> Option<DecimalData> {
+    use BinaryExpressionOp::*;
+    let ((a, sa), (b, sb)) = (left.as_decimal_parts()?, right.as_decimal_parts()?);
+    let result_scale = result_type.scale();
+    let bits = match op {
+        Plus | Minus | Modulo => {
+            let scale = max(sa, sb);
+            let (a, b) = (rescale(a, sa, scale)?, rescale(b, sb, scale)?);
+            let bits = match op {
+                Plus => a.checked_add(b)?,
+                Minus => a.checked_sub(b)?,
+                _ => a.checked_rem(b)?,
+            };
+            rescale(bits, scale, result_scale)?
+        }
+        Multiply => rescale(a.checked_mul(b)?, sa + sb, result_scale)?,
+        Divide => {
+            // a / b at the result scale is (a * 10^(result_scale + sb - sa)) / b. The scaled
+            // operand can exceed i128 (e.g. 10^42 for decimal(10,2) / decimal(38,38)) even if the
+            // quotient doesn't, so the division is done on the magnitudes without scaling them.
+            if b == 0 {
+                return None;
+            }
+            let exponent = result_scale as i32 + sb as i32 - sa as i32;
+            let quotient = match exponent >= 0 {
+                true => {
+                    div_scaled_round_half_up(a.unsigned_abs(), exponent as u32, b.unsigned_abs())?
+                }
+                // a scaled divisor exceeding u128::MAX > 2 * |a| makes the quotient round to zero
+                false => match 10u128
+                    .checked_pow(exponent.unsigned_abs())
+                    .and_then(|scale| b.unsigned_abs().checked_mul(scale))
+                {
+                    Some(denominator) => {
+                        div_scaled_round_half_up(a.unsigned_abs(), 0, denominator)?
+                    }
+                    None => 0,
+                },
+            };
+            let quotient = i128::try_from(quotient).ok()?;
+            match (a < 0) != (b < 0) {
+                true => -quotient,
+                false => quotient,
+            }
+        }
+    };
+    DecimalData::try_new(bits, result_type).ok()
+}
+
+fn pow10(exponent: u32) -> Option<i128> {
+    10i128.checked_pow(exponent)
+}
+
+// Changes the scale of an unscaled decimal value, rounding half up if the scale is reduced.
+fn rescale(bits: i128, from_scale: u8, to_scale: u8) -> Option<i128> {
+    match to_scale >= from_scale {
+        true => bits.checked_mul(pow10((to_scale - from_scale) as u32)?),
+        false => div_round_half_up(bits, pow10((from_scale - to_scale) as u32)?),
+    }
+}
+
+// Divides, rounding to the nearest integer and away from zero on ties.
+fn div_round_half_up(numerator: i128, denominator: i128) -> Option<i128> {
+    let quotient = numerator.checked_div(denominator)?;
+    let remainder = numerator.checked_rem(denominator)?;
+    if remainder.unsigned_abs() * 2 >= denominator.unsigned_abs() {
+        let sign = numerator.signum() * denominator.signum();
+        quotient.checked_add(sign)
+    } else {
+        Some(quotient)
+    }
+}
+
+// Divides `numerator * 10^exponent` by the non-zero `denominator`, rounding half up. The quotient
+// is computed by long division, one decimal digit of the scaled numerator at a time, so that only
+// the quotient (and not the scaled numerator) needs to fit a u128.
+fn div_scaled_round_half_up(numerator: u128, exponent: u32, denominator: u128) -> Option<u128> {
+    let mut quotient = numerator.checked_div(denominator)?;
+    let mut remainder = numerator % denominator;
+    for _ in 0..exponent {
+        // 10 * remainder can overflow for denominators above u128::MAX / 10, so it is divided by
+        // the denominator by repeatedly adding the remainder, keeping the sum below the denominator
+        let (mut digit, mut sum) = (0, 0u128);
+        for _ in 0..10 {
+            sum += remainder;
+            if sum >= denominator {
+                sum -= denominator;
+                digit += 1;
+            }
+        }
+        quotient = quotient.checked_mul(10)?.checked_add(digit)?;
+        remainder = sum;
+    }
+    if remainder >= denominator - remainder {
+        quotient = quotient.checked_add(1)?;
+    }
+    Some(quotient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::test_utils::assert_null_aware_eq;
+
+    fn decimal(precision: u8, scale: u8) -> DataType {
+        DataType::decimal(precision, scale).unwrap()
+    }
+
+    #[test]
+    fn test_result_type() {
+        use BinaryExpressionOp::*;
+        let cases = [
+            (
+                Plus,
+                DataType::INTEGER,
+                DataType::INTEGER,
+                DataType::INTEGER,
+            ),
+            (Plus, DataType::BYTE, DataType::LONG, DataType::LONG),
+            (Divide, DataType::SHORT, DataType::BYTE, DataType::DOUBLE),
+            (Divide, DataType::LONG, DataType::LONG, DataType::DOUBLE),
+            (Divide, decimal(10, 2), DataType::INTEGER, decimal(21, 13)),
+            (Modulo, DataType::LONG, DataType::INTEGER, DataType::LONG),
+            (
+                Multiply,
+                DataType::INTEGER,
+                DataType::FLOAT,
+                DataType::FLOAT,
+            ),
+            (Minus, DataType::FLOAT, DataType::DOUBLE, DataType::DOUBLE),
+            (Plus, DataType::FLOAT, decimal(10, 2), DataType::DOUBLE),
+            (Plus, decimal(10, 2), decimal(5, 3), decimal(12, 3)),
+            (Minus, decimal(10, 2), DataType::INTEGER, decimal(13, 2)),
+            (Multiply, decimal(10, 2), decimal(5, 3), decimal(16, 5)),
+            (Divide, decimal(10, 2), decimal(5, 3), decimal(19, 8)),
+            (Modulo, decimal(10, 2), decimal(5, 3), decimal(5, 3)),
+            // precision is capped at 38, reducing the scale
+            (Multiply, decimal(38, 10), decimal(38, 10), decimal(38, 6)),
+            (Divide, decimal(38, 0), decimal(38, 0), decimal(38, 6)),
+            (Plus, DataType::DATE, DataType::INTEGER, DataType::DATE),
+            (Plus, DataType::SHORT, DataType::DATE, DataType::DATE),
+            (Minus, DataType::DATE, DataType::LONG, DataType::DATE),
+        ];
+        for (op, left, right, expected) in cases {
+            assert_eq!(
+                op.result_type(&left, &right).unwrap(),
+                expected,
+                "{left} {op} {right}"
+            );
+        }
+
+        let unsupported = [
+            (Plus, DataType::STRING, DataType::INTEGER),
+            (Plus, DataType::BOOLEAN, DataType::BOOLEAN),
+            (Minus, DataType::INTEGER, DataType::DATE),
+            (Multiply, DataType::DATE, DataType::INTEGER),
+            (Plus, DataType::DATE, DataType::DOUBLE),
+            (Plus, DataType::TIMESTAMP, DataType::LONG),
+        ];
+        for (op, left, right) in unsupported {
+            assert!(
+                op.result_type(&left, &right).is_err(),
+                "{left} {op} {right}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_scalar_arithmetic() {
+        use BinaryExpressionOp::*;
+        let dec =
+            |bits: i128, precision: u8, scale: u8| Scalar::decimal(bits, precision, scale).unwrap();
+        let cases = [
+            (
+                Plus,
+                Scalar::from(1i8),
+                Scalar::from(2i64),
+                Some(Scalar::Long(3)),
+            ),
+            (
+                Divide,
+                Scalar::from(7),
+                Scalar::from(-2),
+                Some(Scalar::Double(-3.5)),
+            ),
+            (
+                Modulo,
+                Scalar::from(7),
+                Scalar::from(-2),
+                Some(Scalar::Integer(1)),
+            ),
+            (
+                Plus,
+                Scalar::from(1.5f32),
+                Scalar::from(1),
+                Some(Scalar::Float(2.5)),
+            ),
+            (
+                Multiply,
+                Scalar::from(1.5),
+                Scalar::from(2i16),
+                Some(Scalar::Double(3.0)),
+            ),
+            (Plus, dec(150, 5, 2), dec(1, 2, 1), Some(dec(160, 6, 2))),
+            (
+                Minus,
+                dec(150, 5, 2),
+                Scalar::from(2),
+                Some(dec(-50, 13, 2)),
+            ),
+            (
+                Multiply,
+                dec(150, 5, 2),
+                dec(15, 3, 1),
+                Some(dec(2250, 9, 3)),
+            ),
+            (
+                Divide,
+                dec(100, 5, 2),
+                dec(3, 1, 0),
+                Some(dec(333333, 9, 6)),
+            ),
+            (
+                Divide,
+                dec(200, 5, 2),
+                dec(3, 1, 0),
+                Some(dec(666667, 9, 6)),
+            ),
+            // dividing by decimal(38,38) scales the dividend by 10^42, beyond the range of i128
+            (
+                Divide,
+                dec(100, 10, 2),
+                dec(5 * 10i128.pow(37), 38, 38),
+                Some(dec(2000000, 38, 6)),
+            ),
+            (
+                Divide,
+                dec(-100, 10, 2),
+                dec(3 * 10i128.pow(37), 38, 38),
+                Some(dec(-3333333, 38, 6)),
+            ),
+            (Divide, dec(9999999999, 10, 2), dec(1, 38, 38), None),
+            (Modulo, dec(1050, 5, 2), dec(3, 1, 0), Some(dec(150, 3, 2))),
+            (
+                Plus,
+                Scalar::Date(10),
+                Scalar::from(5),
+                Some(Scalar::Date(15)),
+            ),
+            (
+                Minus,
+                Scalar::Date(10),
+                Scalar::from(15i64),
+                Some(Scalar::Date(-5)),
+            ),
+            (
+                Plus,
+                Scalar::from(1),
+                Scalar::Null(DataType::LONG),
+                Some(Scalar::Null(DataType::LONG)),
+            ),
+            // overflow, division by zero, and unsupported types
+            (Plus, Scalar::from(i32::MAX), Scalar::from(1), None),
+            (Multiply, Scalar::from(100i8), Scalar::from(2i8), None),
+            (Divide, Scalar::from(1), Scalar::from(0), None),
+            (Divide, dec(1, 1, 0), dec(0, 1, 0), None),
+            (Plus, Scalar::from("a"), Scalar::from(1), None),
+        ];
+        for (op, left, right, expected) in cases {
+            assert_null_aware_eq!(
+                left.try_arithmetic(op, &right),
+                expected,
+                "{left} {op} {right}"
+            );
+        }
+        assert_eq!(
+            Scalar::from(1.0).try_arithmetic(Divide, &Scalar::from(0)),
+            Some(Scalar::Double(f64::INFINITY))
+        );
+    }
+
+    #[test]
+    fn test_div_scaled_round_half_up() {
+        assert_eq!(div_scaled_round_half_up(2, 2, 3), Some(67));
+        assert_eq!(div_scaled_round_half_up(1, 0, 0), None);
+        // 10 * remainder exceeds u128::MAX for remainders close to such a large denominator
+        let denominator = i128::MAX as u128;
+        assert_eq!(
+            div_scaled_round_half_up(denominator - 1, 2, denominator),
+            Some(100)
+        );
+        assert_eq!(div_scaled_round_half_up(u128::MAX / 10, 2, 1), None);
+    }
+
+    #[test]
+    fn test_scalar_cast() {
+        use CastOverflowPolicy::*;
+        let dec =
+            |bits: i128, precision: u8, scale: u8| Scalar::decimal(bits, precision, scale).unwrap();
+        let cases = [
+            (Scalar::from(1i8), DataType::LONG, Some(Scalar::Long(1))),
+            (Scalar::from(7i64), DataType::SHORT, Some(Scalar::Short(7))),
+            (Scalar::from(3), DataType::DOUBLE, Some(Scalar::Double(3.0))),
+            (Scalar::from(3), decimal(5, 2), Some(dec(300, 5, 2))),
+            (dec(1255, 5, 3), decimal(4, 2), Some(dec(126, 4, 2))),
+            (
+                Scalar::from("42"),
+                DataType::INTEGER,
+                Some(Scalar::Integer(42)),
+            ),
+            (
+                Scalar::from("1970-01-03"),
+                DataType::DATE,
+                Some(Scalar::Date(2)),
+            ),
+            (
+                Scalar::Null(DataType::STRING),
+                DataType::LONG,
+                Some(Scalar::Null(DataType::LONG)),
+            ),
+            // unsupported conversions
+            (Scalar::from(1.5), DataType::INTEGER, None),
+            (Scalar::from(true), DataType::INTEGER, None),
+            (Scalar::from(1), DataType::BOOLEAN, None),
+        ];
+        for (value, to, expected) in cases {
+            assert_null_aware_eq!(
+                value.try_cast(&to, Error),
+                expected,
+                "CAST({value} AS {to})"
+            );
+            assert_null_aware_eq!(
+                value.try_cast(&to, Null),
+                expected,
+                "TRY_CAST({value} AS {to})"
+            );
+        }
+
+        // values that do not fit the target type
+        let overflows = [
+            (Scalar::from(300), DataType::BYTE),
+            (Scalar::from(i64::MAX), DataType::INTEGER),
+            (dec(12345, 5, 2), decimal(3, 2)),
+            (Scalar::from("abc"), DataType::INTEGER),
+        ];
+        for (value, to) in overflows {
+            assert_eq!(value.try_cast(&to, Error), None, "CAST({value} AS {to})");
+            assert_null_aware_eq!(
+                value.try_cast(&to, Null),
+                Some(Scalar::Null(to.clone())),
+                "TRY_CAST({value} AS {to})"
+            );
+        }
+    }
+}
@@ -8,6 +8,11 @@ use std::ops::Deref;
 
 /// A (possibly nested) column name.
 #[derive(Debug, Clone, Default, PartialEq, PartialOrd, Eq, Ord)]
+#[cfg_attr(
+    feature = "expression-serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct ColumnName {
     path: Vec<String>,
 }
@@ -25,9 +30,10 @@ impl ColumnName {
     /// Naively splits a string at dots to create a column name.
     ///
     /// This method is _NOT_ recommended for production use, as it does not attempt to interpret
-    /// special characters in field names. For example, many systems would interpret the field name
-    /// `"a.b" . c ` as equivalent to `ColumnName::new(["\"a.b\"", "c"])` (two fields, whitespace
-    /// padding ignored), but this method would return three fields, including whitespace:
+    /// special characters in field names (use [`Self::parse`] instead). For example, many systems
+    /// would interpret the field name `"a.b" . c ` as equivalent to
+    /// `ColumnName::new(["\"a.b\"", "c"])` (two fields, whitespace padding ignored), but this
+    /// method would return three fields, including whitespace:
     ///
     /// ```
     /// # use delta_kernel::expressions::ColumnName;
@@ -40,6 +46,22 @@ impl ColumnName {
         Self::new(name.as_ref().split(FIELD_SEPARATOR))
     }
 
+    /// Parses a column name, the inverse of its [`Display`] representation. This is a convenience
+    /// wrapper around the [`FromStr`](#impl-FromStr-for-ColumnName) implementation (i.e.
+    /// `name.parse::<ColumnName>()`), which avoids spelling out the target type. Field names are
+    /// separated by dots, and field names enclosed in backticks may contain arbitrary characters,
+    /// with a literal backtick escaped by doubling it:
+    ///
+    /// ```
+    /// # use delta_kernel::expressions::ColumnName;
+    /// let name = ColumnName::parse("a.`weird.name`.`with``backtick`").unwrap();
+    /// assert_eq!(name, ColumnName::new(["a", "weird.name", "with`backtick"]));
+    /// assert_eq!(name.to_string(), "a.`weird.name`.`with``backtick`");
+    /// ```
+    pub fn parse(name: impl AsRef<str>) -> DeltaResult<Self> {
+        name.as_ref().parse()
+    }
+
     /// Parses a comma-separated list of column names, properly accounting for escapes and special
     /// characters, e.g.:
     ///
@@ -636,6 +658,26 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_column_name_parse() {
+        let cases = [
+            ColumnName::new(["a", "weird.name", "c"]),
+            ColumnName::new(["a,b", "c d", "e-f"]),
+            ColumnName::new(["`", "``", "`x`"]),
+            ColumnName::new(["héllo", "wörld"]),
+            ColumnName::new(["1st", "tab\tand\nnewline"]),
+        ];
+        for name in cases {
+            let parsed = ColumnName::parse(name.to_string()).unwrap();
+            assert_eq!(parsed, name);
+        }
+        assert_eq!(
+            ColumnName::parse("a.`weird.name`.c").unwrap(),
+            ColumnName::new(["a", "weird.name", "c"])
+        );
+        assert!(ColumnName::parse("a.b, c").is_err());
+    }
+
     #[test]
     fn test_parse_column_name_list() {
         let cases = [
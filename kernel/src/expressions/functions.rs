@@ -0,0 +1,217 @@
+//! Engine-defined scalar functions that can appear in kernel expressions.
+//!
+//! Engines register their functions in a [`ScalarFunctionRegistry`], which produces opaque
+//! expressions that invoke them. Kernel does not interpret such expressions: Expression transforms
+//! pass them through (only recursing into their arguments), and whenever kernel needs to evaluate
+//! one (e.g. for partition pruning or constraint checks), it evaluates the arguments and calls back
+//! to [`ScalarFunction::invoke`]. Engines evaluating the expressions themselves can recover the
+//! function with [`ScalarFunctionRegistry::function_of`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{Expression, OpaqueExpression, OpaqueExpressionOp, Scalar, ScalarExpressionEvaluator};
+use crate::{DeltaResult, Error};
+
+/// A scalar function defined and implemented by the engine.
+pub trait ScalarFunction: std::fmt::Debug + Send + Sync {
+    /// The name the function is registered under. Names are case-sensitive.
+    fn name(&self) -> &str;
+
+    /// Invokes this function on a single row of (already evaluated) arguments. An output of `Err`
+    /// indicates the function was invoked incorrectly, e.g. with the wrong number and/or types of
+    /// arguments.
+    fn invoke(&self, args: &[Scalar]) -> DeltaResult<Scalar>;
+}
+
+/// A shared reference to a [`ScalarFunction`] instance.
+pub type ScalarFunctionRef = Arc<dyn ScalarFunction>;
+
+/// A set of named engine-defined [`ScalarFunction`]s.
+#[derive(Debug, Clone, Default)]
+pub struct ScalarFunctionRegistry {
+    functions: HashMap<String, ScalarFunctionRef>,
+}
+
+impl ScalarFunctionRegistry {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a function under its [`ScalarFunction::name`]. Fails if a function with the same
+    /// name was already registered.
+    pub fn register(&mut self, function: impl ScalarFunction + 'static) -> DeltaResult<()> {
+        let name = function.name().to_string();
+        if self.functions.contains_key(&name) {
+            return Err(Error::generic(format!(
+                "Scalar function {name} is already registered"
+            )));
+        }
+        self.functions.insert(name, Arc::new(function));
+        Ok(())
+    }
+
+    /// Returns the function registered under `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&ScalarFunctionRef> {
+        self.functions.get(name)
+    }
+
+    /// Creates an expression that invokes the function registered under `name` on `args`.
+    pub fn call(
+        &self,
+        name: &str,
+        args: impl IntoIterator<Item = Expression>,
+    ) -> DeltaResult<Expression> {
+        let function = self
+            .get(name)
+            .ok_or_else(|| Error::invalid_expression(format!("Unknown scalar function {name}")))?;
+        Ok(Expression::opaque(ScalarFunctionOp(function.clone()), args))
+    }
+
+    /// Returns the function invoked by `expr`, if it is a function call created by
+    /// [`Self::call`].
+    pub fn function_of(expr: &Expression) -> Option<&ScalarFunctionRef> {
+        let Expression::Opaque(OpaqueExpression { op, .. }) = expr else {
+            return None;
+        };
+        let op = op.any_ref().downcast_ref::<ScalarFunctionOp>()?;
+        Some(&op.0)
+    }
+}
+
+/// Adapts a [`ScalarFunction`] to an [`OpaqueExpressionOp`]. Two calls are equal if they invoke
+/// the same function instance.
+struct ScalarFunctionOp(ScalarFunctionRef);
+
+// Expressions display opaque ops by their debug representation, so show the function name
+impl std::fmt::Debug for ScalarFunctionOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.name())
+    }
+}
+
+impl PartialEq for ScalarFunctionOp {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl OpaqueExpressionOp for ScalarFunctionOp {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn eval_expr_scalar(
+        &self,
+        eval_expr: &ScalarExpressionEvaluator<'_>,
+        exprs: &[Expression],
+    ) -> DeltaResult<Scalar> {
+        let args: Vec<_> = exprs
+            .iter()
+            .map(|expr| {
+                eval_expr(expr).ok_or_else(|| {
+                    Error::generic(format!(
+                        "Cannot evaluate argument {expr} of scalar function {}",
+                        self.0.name()
+                    ))
+                })
+            })
+            .collect::<DeltaResult<_>>()?;
+        self.0.invoke(&args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::transforms::ExpressionTransform;
+    use crate::expressions::{column_expr, column_name, ColumnName};
+    use crate::kernel_predicates::DefaultKernelPredicateEvaluator;
+    use crate::schema::DataType;
+
+    #[derive(Debug)]
+    struct Concat;
+
+    impl ScalarFunction for Concat {
+        fn name(&self) -> &str {
+            "concat"
+        }
+
+        fn invoke(&self, args: &[Scalar]) -> DeltaResult<Scalar> {
+            let mut result = String::new();
+            for arg in args {
+                match arg {
+                    Scalar::String(s) => result.push_str(s),
+                    Scalar::Null(_) => return Ok(Scalar::Null(DataType::STRING)),
+                    _ => return Err(Error::generic("concat expects string arguments")),
+                }
+            }
+            Ok(result.into())
+        }
+    }
+
+    struct RenameColumns;
+
+    impl<'a> ExpressionTransform<'a> for RenameColumns {
+        fn transform_expr_column(
+            &mut self,
+            name: &'a ColumnName,
+        ) -> Option<std::borrow::Cow<'a, ColumnName>> {
+            Some(std::borrow::Cow::Owned(column_name!("renamed").join(name)))
+        }
+    }
+
+    #[test]
+    fn test_scalar_function_registry() {
+        let mut registry = ScalarFunctionRegistry::new();
+        registry.register(Concat).unwrap();
+        assert!(registry.register(Concat).is_err());
+        assert!(registry.call("missing", [column_expr!("a")]).is_err());
+
+        let expr = registry
+            .call("concat", [column_expr!("a"), Expression::literal("!")])
+            .unwrap();
+        assert!(expr.to_string().starts_with("concat(Column(a), "));
+        assert_eq!(
+            ScalarFunctionRegistry::function_of(&expr).unwrap().name(),
+            "concat"
+        );
+        assert!(ScalarFunctionRegistry::function_of(&column_expr!("a")).is_none());
+
+        // Transforms pass the call through, but still rewrite its arguments
+        let renamed = RenameColumns.transform_expr(&expr).unwrap().into_owned();
+        let expected = registry
+            .call(
+                "concat",
+                [column_expr!("renamed.a"), Expression::literal("!")],
+            )
+            .unwrap();
+        assert_eq!(renamed, expected);
+        assert_eq!(
+            renamed.references(),
+            [&column_name!("renamed.a")].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_scalar_function_evaluation() {
+        let mut registry = ScalarFunctionRegistry::new();
+        registry.register(Concat).unwrap();
+        let resolver = HashMap::from([(column_name!("a"), Scalar::from("hello"))]);
+        let evaluator = DefaultKernelPredicateEvaluator::from(resolver);
+
+        let expr = registry
+            .call("concat", [column_expr!("a"), Expression::literal("!")])
+            .unwrap();
+        assert_eq!(evaluator.eval_expr(&expr), Some("hello!".into()));
+
+        // Engine errors and unresolvable arguments prevent evaluation
+        let expr = registry
+            .call("concat", [column_expr!("a"), Expression::literal(1)])
+            .unwrap();
+        assert_eq!(evaluator.eval_expr(&expr), None);
+        let expr = registry.call("concat", [column_expr!("b")]).unwrap();
+        assert_eq!(evaluator.eval_expr(&expr), None);
+    }
+}
@@ -5,12 +5,17 @@ use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 
 use itertools::Itertools;
+#[cfg(feature = "expression-serde")]
+use serde::{Deserialize, Serialize};
 
+pub(crate) use self::arithmetic::to_decimal_type;
 pub use self::column_names::{
     column_expr, column_expr_ref, column_name, column_pred, joined_column_expr, joined_column_name,
     ColumnName,
 };
+pub use self::functions::{ScalarFunction, ScalarFunctionRef, ScalarFunctionRegistry};
 pub use self::scalars::{ArrayData, DecimalData, MapData, Scalar, StructData};
+pub use self::simplify::{simplify, simplify_predicate};
 use self::transforms::{ExpressionTransform as _, GetColumnReferences};
 use crate::kernel_predicates::{
     DirectDataSkippingPredicateEvaluator, DirectPredicateEvaluator,
@@ -18,9 +23,19 @@ use crate::kernel_predicates::{
 };
 use crate::{DataType, DeltaResult, DynPartialEq};
 
+mod arithmetic;
 mod column_names;
+mod functions;
 pub(crate) mod literal_expression_transform;
 mod scalars;
+#[cfg(feature = "expression-serde")]
+mod serialization;
+mod simplify;
+pub(crate) mod sql_parser;
+#[cfg(feature = "substrait")]
+pub mod substrait;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub mod transforms;
 
 pub type ExpressionRef = std::sync::Arc<Expression>;
@@ -32,6 +47,7 @@ pub type PredicateRef = std::sync::Arc<Predicate>;
 
 /// A unary predicate operator.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum UnaryPredicateOp {
     /// Unary Is Null
     IsNull,
@@ -39,6 +55,7 @@ pub enum UnaryPredicateOp {
 
 /// A binary predicate operator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum BinaryPredicateOp {
     /// Comparison Less Than
     LessThan,
@@ -48,12 +65,20 @@ pub enum BinaryPredicateOp {
     Equal,
     /// Distinct
     Distinct,
-    /// IN
+    /// IN: either `<value> IN <array>` (membership in an array column or literal), or an IN-list
+    /// `<column> IN <array literal>` (see [`Predicate::in_list`])
     In,
+    /// SQL `LIKE`: `<value> LIKE <pattern>`, where the pattern is a string literal in which `%`
+    /// matches any sequence of characters, `_` matches any single character, and `\\` escapes the
+    /// character following it (see [`Predicate::like`])
+    Like,
+    /// Case-insensitive SQL `LIKE` (see [`Predicate::ilike`])
+    ILike,
 }
 
 /// A unary expression operator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum UnaryExpressionOp {
     /// Convert struct data to JSON-encoded strings
     ToJson,
@@ -61,6 +86,7 @@ pub enum UnaryExpressionOp {
 
 /// A binary expression operator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum BinaryExpressionOp {
     /// Arithmetic Plus
     Plus,
@@ -70,17 +96,35 @@ pub enum BinaryExpressionOp {
     Multiply,
     /// Arithmetic Divide
     Divide,
+    /// Arithmetic Modulo (remainder of division), with the sign of the dividend
+    Modulo,
 }
 
 /// A variadic expression operator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum VariadicExpressionOp {
     /// Collapse multiple values into one by taking the first non-null value
     Coalesce,
+    /// Takes exactly two values, and produces NULL if they are equal or else the first value
+    NullIf,
+}
+
+/// What a [`CastExpression`] produces for values that cannot be represented in the target type,
+/// e.g. when narrowing `300` to `byte` or parsing `'abc'` as `integer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
+pub enum CastOverflowPolicy {
+    /// Fail the evaluation with an error
+    #[default]
+    Error,
+    /// Produce NULL for the offending value
+    Null,
 }
 
 /// A junction (AND/OR) predicate operator.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum JunctionPredicateOp {
     /// Conjunction
     And,
@@ -191,6 +235,7 @@ pub type OpaquePredicateOpRef = Arc<dyn OpaquePredicateOp>;
 ////////////////////////////////////////////////////////////////////////
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct UnaryPredicate {
     /// The operator.
     pub op: UnaryPredicateOp,
@@ -199,6 +244,7 @@ pub struct UnaryPredicate {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct BinaryPredicate {
     /// The operator.
     pub op: BinaryPredicateOp,
@@ -209,6 +255,7 @@ pub struct BinaryPredicate {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct UnaryExpression {
     /// The operator.
     pub op: UnaryExpressionOp,
@@ -217,6 +264,7 @@ pub struct UnaryExpression {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct BinaryExpression {
     /// The operator.
     pub op: BinaryExpressionOp,
@@ -227,6 +275,7 @@ pub struct BinaryExpression {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct VariadicExpression {
     /// The operator.
     pub op: VariadicExpressionOp,
@@ -234,7 +283,57 @@ pub struct VariadicExpression {
     pub exprs: Vec<Expression>,
 }
 
+/// An explicit conversion of an expression's value to another data type.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
+pub struct CastExpression {
+    /// The input expression.
+    pub expr: Box<Expression>,
+    /// The data type to convert to.
+    pub to: DataType,
+    /// How to handle values that cannot be represented in the target type.
+    pub on_overflow: CastOverflowPolicy,
+}
+
+/// Identifies the element of a map, array, or struct that an [`ElementExpression`] extracts.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
+pub enum ElementAccessor {
+    /// The value a map associates with the given key (`map[key]`), or NULL if the key is absent.
+    MapValue(Scalar),
+    /// The array element at the given zero-based index (`array[index]`), or NULL if the index is
+    /// out of bounds.
+    ArrayElement(usize),
+    /// The struct field at the given zero-based ordinal. Unlike a column reference, this does not
+    /// depend on field names.
+    StructField(usize),
+}
+
+/// An expression that extracts one element of a map, array, or struct value. The result is NULL
+/// if the input value is NULL.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
+pub struct ElementExpression {
+    /// The map, array, or struct to extract an element from.
+    pub expr: Box<Expression>,
+    /// The element to extract.
+    pub accessor: ElementAccessor,
+}
+
+/// A conditional expression, `CASE WHEN <pred> THEN <expr> ... [ELSE <expr>] END`. Its value is
+/// that of the first branch whose condition is TRUE (NULL conditions count as not TRUE), or else
+/// the value of the ELSE expression (NULL, if absent).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
+pub struct CaseExpression {
+    /// The (WHEN condition, THEN result) branches, in evaluation order.
+    pub branches: Vec<(Predicate, Expression)>,
+    /// The result if no condition is TRUE.
+    pub else_expr: Option<Box<Expression>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct JunctionPredicate {
     /// The operator.
     pub op: JunctionPredicateOp,
@@ -277,6 +376,7 @@ impl OpaqueExpression {
 /// A transformation affecting a single field (one pieces of a [`Transform`]). The transformation
 /// could insert 0+ new fields after the target, or could replace the target with 0+ a new fields).
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct FieldTransform {
     /// The list of expressions this field transform emits at the target location.
     pub exprs: Vec<ExpressionRef>,
@@ -292,6 +392,7 @@ pub struct FieldTransform {
 /// relative field ordering. This is particularly useful for wide schemas where only a few columns
 /// need to be modified and/or dropped, or where a small number of columns need to be injected.
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub struct Transform {
     /// The path to the nested input struct this transform operates on (if any). If no path is
     /// given, the transform operates directly on top-level columns.
@@ -374,6 +475,7 @@ impl Transform {
 /// of literals. It is up to the expression evaluator to validate the
 /// expression against a schema and add appropriate casts as required.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum Expression {
     /// A literal value.
     Literal(Scalar),
@@ -392,8 +494,15 @@ pub enum Expression {
     Binary(BinaryExpression),
     /// An expression that takes a variable number of expressions as input.
     Variadic(VariadicExpression),
+    /// A conditional expression (`CASE WHEN ... THEN ... ELSE ... END`).
+    Case(CaseExpression),
+    /// A conversion of an expression's value to another data type.
+    Cast(CastExpression),
+    /// Access to an element of a map, array, or struct (e.g. `tags['env']`).
+    Element(ElementExpression),
     /// An expression that the engine defines and implements. Kernel interacts with the expression
     /// only through methods provided by the [`OpaqueExpressionOp`] trait.
+    #[cfg_attr(feature = "expression-serde", serde(skip))]
     Opaque(OpaqueExpression),
     /// An unknown expression (i.e. one that neither kernel nor engine attempts to evaluate). For
     /// data skipping purposes, kernel treats unknown expressions as if they were literal NULL
@@ -412,6 +521,7 @@ pub enum Expression {
 /// of literals. It is up to the predicate evaluator to validate the
 /// predicate against a schema and add appropriate casts as required.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "expression-serde", derive(Serialize, Deserialize))]
 pub enum Predicate {
     /// A boolean-valued expression, useful for e.g. `AND(<boolean_col1>, <boolean_col2>)`.
     BooleanExpression(Expression),
@@ -430,6 +540,7 @@ pub enum Predicate {
     Junction(JunctionPredicate),
     /// A predicate that the engine defines and implements. Kernel interacts with the predicate
     /// only through methods provided by the [`OpaquePredicateOp`] trait.
+    #[cfg_attr(feature = "expression-serde", serde(skip))]
     Opaque(OpaquePredicate),
     /// An unknown predicate (i.e. one that neither kernel nor engine attempts to evaluate). For
     /// data skipping purposes, kernel treats unknown predicates as if they were literal NULL values
@@ -451,7 +562,7 @@ impl BinaryPredicateOp {
     pub(crate) fn is_null_intolerant(&self) -> bool {
         use BinaryPredicateOp::*;
         match self {
-            LessThan | GreaterThan | Equal => true,
+            LessThan | GreaterThan | Equal | Like | ILike => true,
             Distinct | In => false, // tolerates NULL input
         }
     }
@@ -515,6 +626,38 @@ impl VariadicExpression {
     }
 }
 
+impl CastExpression {
+    fn new(expr: impl Into<Expression>, to: DataType, on_overflow: CastOverflowPolicy) -> Self {
+        let expr = Box::new(expr.into());
+        Self {
+            expr,
+            to,
+            on_overflow,
+        }
+    }
+}
+
+impl ElementExpression {
+    fn new(expr: impl Into<Expression>, accessor: ElementAccessor) -> Self {
+        let expr = Box::new(expr.into());
+        Self { expr, accessor }
+    }
+}
+
+impl CaseExpression {
+    fn new(
+        branches: impl IntoIterator<Item = (Predicate, Expression)>,
+        else_expr: Option<Expression>,
+    ) -> Self {
+        let branches = branches.into_iter().collect();
+        let else_expr = else_expr.map(Box::new);
+        Self {
+            branches,
+            else_expr,
+        }
+    }
+}
+
 impl JunctionPredicate {
     fn new(op: JunctionPredicateOp, preds: Vec<Predicate>) -> Self {
         Self { op, preds }
@@ -610,6 +753,26 @@ impl Expression {
         Predicate::distinct(self, other)
     }
 
+    /// Create a new predicate `self IN (values)`
+    pub fn in_list(self, values: ArrayData) -> Predicate {
+        Predicate::in_list(self, values)
+    }
+
+    /// Create a new predicate `self NOT IN (values)`
+    pub fn not_in_list(self, values: ArrayData) -> Predicate {
+        Predicate::not_in_list(self, values)
+    }
+
+    /// Create a new predicate `self LIKE pattern`
+    pub fn like(self, pattern: impl Into<String>) -> Predicate {
+        Predicate::like(self, pattern)
+    }
+
+    /// Create a new predicate `self ILIKE pattern`
+    pub fn ilike(self, pattern: impl Into<String>) -> Predicate {
+        Predicate::ilike(self, pattern)
+    }
+
     /// Creates a new unary expression
     pub fn unary(op: UnaryExpressionOp, expr: impl Into<Expression>) -> Self {
         Self::Unary(UnaryExpression::new(op, expr))
@@ -632,6 +795,77 @@ impl Expression {
         Self::Variadic(VariadicExpression::new(op, exprs))
     }
 
+    /// Creates a new expression `COALESCE(exprs...)`, which takes the first non-null value
+    pub fn coalesce(exprs: impl IntoIterator<Item = impl Into<Expression>>) -> Self {
+        Self::variadic(VariadicExpressionOp::Coalesce, exprs)
+    }
+
+    /// Creates a new expression `NULLIF(a, b)`, which is NULL if `a = b` and `a` otherwise
+    pub fn null_if(a: impl Into<Expression>, b: impl Into<Expression>) -> Self {
+        Self::variadic(VariadicExpressionOp::NullIf, [a.into(), b.into()])
+    }
+
+    /// Creates a new conditional expression `CASE WHEN <pred> THEN <expr> ... ELSE <else> END`.
+    /// The result is NULL if no condition is TRUE and there is no ELSE expression.
+    pub fn case(
+        branches: impl IntoIterator<Item = (Predicate, Expression)>,
+        else_expr: Option<Expression>,
+    ) -> Self {
+        Self::Case(CaseExpression::new(branches, else_expr))
+    }
+
+    /// Creates a new conditional expression `IF(pred, then, else)`, i.e. `CASE WHEN pred THEN then
+    /// ELSE else END`.
+    pub fn if_then_else(
+        pred: impl Into<Predicate>,
+        then_expr: impl Into<Expression>,
+        else_expr: impl Into<Expression>,
+    ) -> Self {
+        Self::case([(pred.into(), then_expr.into())], Some(else_expr.into()))
+    }
+
+    /// Creates a new expression `CAST(expr AS to)`, whose evaluation fails if a value cannot be
+    /// represented in the target type.
+    pub fn cast(expr: impl Into<Expression>, to: impl Into<DataType>) -> Self {
+        Self::cast_with_policy(expr, to, CastOverflowPolicy::Error)
+    }
+
+    /// Creates a new expression `TRY_CAST(expr AS to)`, which produces NULL for values that
+    /// cannot be represented in the target type.
+    pub fn try_cast(expr: impl Into<Expression>, to: impl Into<DataType>) -> Self {
+        Self::cast_with_policy(expr, to, CastOverflowPolicy::Null)
+    }
+
+    /// Creates a new cast expression with the given overflow policy
+    pub fn cast_with_policy(
+        expr: impl Into<Expression>,
+        to: impl Into<DataType>,
+        on_overflow: CastOverflowPolicy,
+    ) -> Self {
+        Self::Cast(CastExpression::new(expr, to.into(), on_overflow))
+    }
+
+    /// Creates a new expression `map[key]`, which is NULL if the map does not contain the key.
+    pub fn map_value(map: impl Into<Expression>, key: impl Into<Scalar>) -> Self {
+        Self::element(map, ElementAccessor::MapValue(key.into()))
+    }
+
+    /// Creates a new expression `array[index]` (zero-based), which is NULL if the index is out of
+    /// bounds.
+    pub fn array_element(array: impl Into<Expression>, index: usize) -> Self {
+        Self::element(array, ElementAccessor::ArrayElement(index))
+    }
+
+    /// Creates a new expression that extracts the struct field at the given (zero-based) ordinal.
+    pub fn struct_field(value: impl Into<Expression>, ordinal: usize) -> Self {
+        Self::element(value, ElementAccessor::StructField(ordinal))
+    }
+
+    /// Creates a new element access expression
+    pub fn element(expr: impl Into<Expression>, accessor: ElementAccessor) -> Self {
+        Self::Element(ElementExpression::new(expr, accessor))
+    }
+
     /// Creates a new opaque expression
     pub fn opaque(
         op: impl OpaqueExpressionOp,
@@ -654,6 +888,29 @@ impl Predicate {
         references.into_inner()
     }
 
+    /// Parses a SQL predicate, e.g. `a > 5 AND b IN ('x', 'y')`. Columns are not resolved against
+    /// any schema, so literal types are inferred from their values alone, e.g. `5` is an INTEGER
+    /// literal even if `a` is a LONG column. Use [`Self::parse_sql_with_schema`] instead if the
+    /// schema is known.
+    ///
+    /// The supported SQL subset covers column references, literals, arithmetic, comparisons,
+    /// `IS [NOT] NULL`, `[NOT] IN`, `[NOT] [I]LIKE`, `AND`/`OR`/`NOT`, `CASE WHEN`, `IF`,
+    /// `COALESCE`, `NULLIF`, and map and array subscripts.
+    #[cfg(feature = "sql-parser")]
+    pub fn parse_sql(sql: &str) -> DeltaResult<Self> {
+        sql_parser::parse_predicate_without_schema(sql)
+    }
+
+    /// Parses a SQL predicate, see [`Self::parse_sql`]. Column references are resolved against
+    /// `schema`, and literals compared against a column are coerced to that column's type.
+    #[cfg(feature = "sql-parser")]
+    pub fn parse_sql_with_schema(
+        sql: &str,
+        schema: &crate::schema::StructType,
+    ) -> DeltaResult<Self> {
+        sql_parser::parse_predicate(sql, schema)
+    }
+
     /// Creates a new boolean column reference. See also [`Expression::column`].
     pub fn column<A>(field_names: impl IntoIterator<Item = A>) -> Predicate
     where
@@ -730,6 +987,46 @@ impl Predicate {
         Self::binary(BinaryPredicateOp::Distinct, a, b)
     }
 
+    /// Create a new predicate `self IN (values)`, which is TRUE if `self` equals any of the
+    /// values. Following SQL semantics, it is NULL if `self` is NULL, or if no value matches but
+    /// the values include NULL.
+    pub fn in_list(a: impl Into<Expression>, values: ArrayData) -> Self {
+        Self::binary(BinaryPredicateOp::In, a, Scalar::Array(values))
+    }
+
+    /// Create a new predicate `self NOT IN (values)`, see [`Self::in_list`].
+    pub fn not_in_list(a: impl Into<Expression>, values: ArrayData) -> Self {
+        Self::not(Self::in_list(a, values))
+    }
+
+    /// Create a new predicate `self LIKE pattern`. In the pattern, `%` matches any sequence of
+    /// (zero or more) characters, `_` matches exactly one character, and a backslash escapes the
+    /// character that follows it. Use [`Self::like_with_escape`] for a different escape character.
+    pub fn like(a: impl Into<Expression>, pattern: impl Into<String>) -> Self {
+        Self::binary(BinaryPredicateOp::Like, a, Scalar::from(pattern.into()))
+    }
+
+    /// Create a new predicate `self LIKE pattern ESCAPE escape`, see [`Self::like`]. The pattern
+    /// is rewritten to use backslash as the escape character, which is what
+    /// [`BinaryPredicateOp::Like`] expects.
+    pub fn like_with_escape(
+        a: impl Into<Expression>,
+        pattern: impl AsRef<str>,
+        escape: char,
+    ) -> Self {
+        Self::like(a, normalize_like_escape(pattern.as_ref(), escape))
+    }
+
+    /// Create a new predicate `self NOT LIKE pattern`, see [`Self::like`].
+    pub fn not_like(a: impl Into<Expression>, pattern: impl Into<String>) -> Self {
+        Self::not(Self::like(a, pattern))
+    }
+
+    /// Create a new case-insensitive predicate `self ILIKE pattern`, see [`Self::like`].
+    pub fn ilike(a: impl Into<Expression>, pattern: impl Into<String>) -> Self {
+        Self::binary(BinaryPredicateOp::ILike, a, Scalar::from(pattern.into()))
+    }
+
     /// Create a new predicate `self AND other`
     pub fn and(a: impl Into<Self>, b: impl Into<Self>) -> Self {
         Self::and_from([a.into(), b.into()])
@@ -819,6 +1116,7 @@ impl Display for BinaryExpressionOp {
             Minus => write!(f, "-"),
             Multiply => write!(f, "*"),
             Divide => write!(f, "/"),
+            Modulo => write!(f, "%"),
         }
     }
 }
@@ -828,6 +1126,7 @@ impl Display for VariadicExpressionOp {
         use VariadicExpressionOp::*;
         match self {
             Coalesce => write!(f, "COALESCE"),
+            NullIf => write!(f, "NULLIF"),
         }
     }
 }
@@ -844,8 +1143,31 @@ impl Display for BinaryPredicateOp {
             // in our code we take care of this, but theirs might not ...
             Distinct => write!(f, "DISTINCT"),
             In => write!(f, "IN"),
+            Like => write!(f, "LIKE"),
+            ILike => write!(f, "ILIKE"),
+        }
+    }
+}
+
+// Rewrites a LIKE pattern that uses `escape` as its escape character into the equivalent pattern
+// that uses backslash instead. A trailing escape character is treated as a literal.
+fn normalize_like_escape(pattern: &str, escape: char) -> String {
+    if escape == '\\' {
+        return pattern.to_string();
+    }
+    let mut normalized = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c == escape => {
+                normalized.push('\\');
+                normalized.push(chars.next().unwrap_or(escape));
+            }
+            '\\' => normalized.push_str("\\\\"),
+            c => normalized.push(c),
         }
     }
+    normalized
 }
 
 // Helper for displaying the children of variadic expressions and predicates
@@ -894,6 +1216,32 @@ impl Display for Expression {
             Variadic(VariadicExpression { op, exprs }) => {
                 write!(f, "{op}({})", format_child_list(exprs))
             }
+            Case(CaseExpression {
+                branches,
+                else_expr,
+            }) => {
+                write!(f, "CASE")?;
+                for (when, then) in branches {
+                    write!(f, " WHEN {when} THEN {then}")?;
+                }
+                if let Some(else_expr) = else_expr {
+                    write!(f, " ELSE {else_expr}")?;
+                }
+                write!(f, " END")
+            }
+            Cast(CastExpression {
+                expr,
+                to,
+                on_overflow,
+            }) => match on_overflow {
+                CastOverflowPolicy::Error => write!(f, "CAST({expr} AS {to})"),
+                CastOverflowPolicy::Null => write!(f, "TRY_CAST({expr} AS {to})"),
+            },
+            Element(ElementExpression { expr, accessor }) => match accessor {
+                ElementAccessor::MapValue(key) => write!(f, "{expr}[{key}]"),
+                ElementAccessor::ArrayElement(index) => write!(f, "{expr}[{index}]"),
+                ElementAccessor::StructField(ordinal) => write!(f, "{expr}.#{ordinal}"),
+            },
             Opaque(OpaqueExpression { op, exprs }) => {
                 write!(f, "{op:?}({})", format_child_list(exprs))
             }
@@ -988,9 +1336,20 @@ impl<R: Into<Expression>> std::ops::Div<R> for Expression {
     }
 }
 
+impl<R: Into<Expression>> std::ops::Rem<R> for Expression {
+    type Output = Self;
+
+    fn rem(self, rhs: R) -> Self {
+        Self::binary(BinaryExpressionOp::Modulo, self, rhs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use super::{
+        column_expr, column_pred, normalize_like_escape, DataType, Expression as Expr,
+        Predicate as Pred,
+    };
 
     #[test]
     fn test_expression_format() {
@@ -1004,6 +1363,44 @@ mod tests {
                 Expr::struct_from([column_expr!("x"), Expr::literal(2), Expr::literal(10)]),
                 "Struct(Column(x), 2, 10)",
             ),
+            (
+                Expr::case(
+                    [
+                        (column_pred!("a"), Expr::literal(1)),
+                        (column_pred!("b"), Expr::literal(2)),
+                    ],
+                    None,
+                ),
+                "CASE WHEN Column(a) THEN 1 WHEN Column(b) THEN 2 END",
+            ),
+            (
+                Expr::if_then_else(column_pred!("a"), column_expr!("x"), Expr::literal(0)),
+                "CASE WHEN Column(a) THEN Column(x) ELSE 0 END",
+            ),
+            (
+                Expr::coalesce([column_expr!("x"), Expr::literal(0)]),
+                "COALESCE(Column(x), 0)",
+            ),
+            (
+                Expr::null_if(column_expr!("x"), Expr::literal(0)),
+                "NULLIF(Column(x), 0)",
+            ),
+            (
+                Expr::cast(column_expr!("x"), DataType::LONG),
+                "CAST(Column(x) AS long)",
+            ),
+            (
+                Expr::try_cast(column_expr!("x"), DataType::SHORT),
+                "TRY_CAST(Column(x) AS short)",
+            ),
+            (
+                Expr::map_value(column_expr!("tags"), "env"),
+                "Column(tags)['env']",
+            ),
+            (
+                Expr::struct_field(Expr::array_element(column_expr!("a"), 2), 0),
+                "Column(a)[2].#0",
+            ),
         ];
 
         for (expr, expected) in cases {
@@ -1047,6 +1444,8 @@ mod tests {
                 column_expr!("x").eq(Expr::literal("foo")),
                 "Column(x) = 'foo'",
             ),
+            (column_expr!("x").like("a%"), "Column(x) LIKE 'a%'"),
+            (column_expr!("x").ilike("a_"), "Column(x) ILIKE 'a_'"),
         ];
 
         for (pred, expected) in cases {
@@ -1054,4 +1453,13 @@ mod tests {
             assert_eq!(result, expected);
         }
     }
+
+    #[test]
+    fn test_normalize_like_escape() {
+        assert_eq!(normalize_like_escape(r"a\%", '\\'), r"a\%");
+        assert_eq!(normalize_like_escape("a#%b#_", '#'), r"a\%b\_");
+        assert_eq!(normalize_like_escape("a##b", '#'), r"a\#b");
+        assert_eq!(normalize_like_escape(r"a\b%", '#'), r"a\\b%");
+        assert_eq!(normalize_like_escape("ab#", '#'), r"ab\#");
+    }
 }
@@ -5,11 +5,15 @@ use std::fmt::{Display, Formatter};
 use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use itertools::Itertools;
 
+use crate::expressions::BinaryExpressionOp;
 use crate::schema::derive_macro_utils::ToDataType;
 use crate::schema::{ArrayType, DataType, DecimalType, MapType, PrimitiveType, StructField};
 use crate::utils::require;
 use crate::{DeltaResult, Error};
 
+/// The partition value Hive-style writers use for null values.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct DecimalData {
     bits: i128,
@@ -301,56 +305,112 @@ impl Scalar {
         Ok(Self::Timestamp(timestamp.timestamp_micros()))
     }
 
-    /// Attempts to add two scalars, returning None if they were incompatible.
+    /// Attempts to add two scalars, returning None if they were incompatible. See
+    /// [`Self::try_arithmetic`].
     pub fn try_add(&self, other: &Scalar) -> Option<Scalar> {
-        use Scalar::*;
-        let result = match (self, other) {
-            (Integer(a), Integer(b)) => Integer(a.checked_add(*b)?),
-            (Long(a), Long(b)) => Long(a.checked_add(*b)?),
-            (Short(a), Short(b)) => Short(a.checked_add(*b)?),
-            (Byte(a), Byte(b)) => Byte(a.checked_add(*b)?),
-            _ => return None,
-        };
-        Some(result)
+        self.try_arithmetic(BinaryExpressionOp::Plus, other)
     }
 
-    /// Attempts to subtract two scalars, returning None if they were incompatible.
+    /// Attempts to subtract two scalars, returning None if they were incompatible. See
+    /// [`Self::try_arithmetic`].
     pub fn try_sub(&self, other: &Scalar) -> Option<Scalar> {
-        use Scalar::*;
-        let result = match (self, other) {
-            (Integer(a), Integer(b)) => Integer(a.checked_sub(*b)?),
-            (Long(a), Long(b)) => Long(a.checked_sub(*b)?),
-            (Short(a), Short(b)) => Short(a.checked_sub(*b)?),
-            (Byte(a), Byte(b)) => Byte(a.checked_sub(*b)?),
-            _ => return None,
-        };
-        Some(result)
+        self.try_arithmetic(BinaryExpressionOp::Minus, other)
     }
 
-    /// Attempts to multiply two scalars, returning None if they were incompatible.
+    /// Attempts to multiply two scalars, returning None if they were incompatible. See
+    /// [`Self::try_arithmetic`].
     pub fn try_mul(&self, other: &Scalar) -> Option<Scalar> {
-        use Scalar::*;
-        let result = match (self, other) {
-            (Integer(a), Integer(b)) => Integer(a.checked_mul(*b)?),
-            (Long(a), Long(b)) => Long(a.checked_mul(*b)?),
-            (Short(a), Short(b)) => Short(a.checked_mul(*b)?),
-            (Byte(a), Byte(b)) => Byte(a.checked_mul(*b)?),
-            _ => return None,
-        };
-        Some(result)
+        self.try_arithmetic(BinaryExpressionOp::Multiply, other)
     }
 
-    /// Attempts to divide two scalars, returning None if they were incompatible.
+    /// Attempts to divide two scalars, returning None if they were incompatible. See
+    /// [`Self::try_arithmetic`].
     pub fn try_div(&self, other: &Scalar) -> Option<Scalar> {
+        self.try_arithmetic(BinaryExpressionOp::Divide, other)
+    }
+
+    /// Attempts to compute the remainder of dividing two scalars, returning None if they were
+    /// incompatible. See [`Self::try_arithmetic`].
+    pub fn try_rem(&self, other: &Scalar) -> Option<Scalar> {
+        self.try_arithmetic(BinaryExpressionOp::Modulo, other)
+    }
+
+    /// Serializes this scalar as a partition value, the inverse of [`PrimitiveType::parse_scalar`].
+    /// Returns `None` for null values. Timestamps and timestampNtz values are both written as
+    /// `{year}-{month}-{day} {hour}:{minute}:{second}.{micros}`, interpreted as UTC for timestamps.
+    ///
+    /// See <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#partition-value-serialization>
+    pub fn serialize_partition_value(&self) -> DeltaResult<Option<String>> {
         use Scalar::*;
-        let result = match (self, other) {
-            (Integer(a), Integer(b)) => Integer(a.checked_div(*b)?),
-            (Long(a), Long(b)) => Long(a.checked_div(*b)?),
-            (Short(a), Short(b)) => Short(a.checked_div(*b)?),
-            (Byte(a), Byte(b)) => Byte(a.checked_div(*b)?),
-            _ => return None,
+        let value = match self {
+            Null(_) => return Ok(None),
+            String(s) => s.clone(),
+            Binary(b) => std::string::String::from_utf8(b.clone()).map_err(|_| {
+                Error::generic("Binary partition values must be valid UTF-8 to be serialized")
+            })?,
+            Integer(i) => i.to_string(),
+            Long(i) => i.to_string(),
+            Short(i) => i.to_string(),
+            Byte(i) => i.to_string(),
+            Float(f) => f.to_string(),
+            Double(f) => f.to_string(),
+            Boolean(b) => b.to_string(),
+            Date(days) => {
+                let date = DateTime::UNIX_EPOCH
+                    .checked_add_signed(chrono::Duration::days(*days as i64))
+                    .ok_or_else(|| Error::generic(format!("Date out of range: {days}")))?;
+                date.format("%Y-%m-%d").to_string()
+            }
+            Timestamp(micros) | TimestampNtz(micros) => {
+                let timestamp = DateTime::from_timestamp_micros(*micros)
+                    .ok_or_else(|| Error::generic(format!("Timestamp out of range: {micros}")))?;
+                timestamp.format("%Y-%m-%d %H:%M:%S%.6f").to_string()
+            }
+            Decimal(d) => {
+                let scale = d.scale() as usize;
+                match scale {
+                    0 => d.bits().to_string(),
+                    _ => {
+                        let sign = if d.bits() < 0 { "-" } else { "" };
+                        let abs = d.bits().unsigned_abs();
+                        let divisor = 10_u128.pow(scale as u32);
+                        format!("{sign}{}.{:0>scale$}", abs / divisor, abs % divisor)
+                    }
+                }
+            }
+            Struct(_) | Array(_) | Map(_) => {
+                return Err(Error::unsupported(format!(
+                    "Cannot serialize a partition value of type {}",
+                    self.data_type()
+                )))
+            }
         };
-        Some(result)
+        Ok(Some(value))
+    }
+
+    /// Parses a partition value of type `data_type`, the inverse of
+    /// [`Self::serialize_partition_value`]. In addition to [`PrimitiveType::parse_scalar`]:
+    ///
+    /// - `__HIVE_DEFAULT_PARTITION__` is a null value, like the empty string.
+    /// - Decimals with fewer fractional digits than the scale of the type are rescaled, e.g. `1.5`
+    ///   is `1.50` as a `decimal(3, 2)`.
+    ///
+    /// Partition values of nested types are not supported.
+    ///
+    /// See <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#partition-value-serialization>
+    pub fn from_partition_value(raw: &str, data_type: &DataType) -> DeltaResult<Scalar> {
+        if raw.is_empty() || raw == HIVE_DEFAULT_PARTITION {
+            return Ok(Scalar::Null(data_type.clone()));
+        }
+        match data_type.as_primitive_opt() {
+            Some(PrimitiveType::Decimal(dtype)) => {
+                PrimitiveType::parse_decimal_rescaled(raw, *dtype)
+            }
+            Some(primitive) => primitive.parse_scalar(raw),
+            None => Err(Error::generic(format!(
+                "Unexpected partition column type: {data_type:?}"
+            ))),
+        }
     }
 }
 
@@ -629,6 +689,8 @@ impl PrimitiveType {
         DataType::Primitive(self.clone())
     }
 
+    /// Parses a scalar of this type from its string representation, as used for partition values.
+    /// The empty string is a null value. See also [`Scalar::from_partition_value`].
     pub fn parse_scalar(&self, raw: &str) -> Result<Scalar, Error> {
         use PrimitiveType::*;
 
@@ -667,7 +729,7 @@ impl PrimitiveType {
             // NOTE: Timestamp and TimestampNtz are both parsed into microsecond since unix epoch.
             // They may both have the format `{year}-{month}-{day} {hour}:{minute}:{second}`.
             // Timestamps may additionally be encoded as a ISO 8601 formatted string such as
-            // `1970-01-01T00:00:00.123456Z`.
+            // `1970-01-01T00:00:00.123456Z` or `1970-01-01T01:00:00+01:00`, whose offset is applied.
             //
             // The difference arises mostly in how they are to be handled on the engine side - i.e. timestampNTZ
             // is not adjusted to UTC, this is just so we can (de-)serialize it as a date sting.
@@ -676,8 +738,7 @@ impl PrimitiveType {
                 let mut timestamp = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S%.f");
 
                 if timestamp.is_err() && *self == Timestamp {
-                    // Note: `%+` specifies the ISO 8601 / RFC 3339 format
-                    timestamp = NaiveDateTime::parse_from_str(raw, "%+");
+                    timestamp = DateTime::parse_from_rfc3339(raw).map(|ts| ts.naive_utc());
                 }
                 let timestamp = timestamp.map_err(|_| self.parse_error(raw))?;
                 let timestamp = Utc.from_utc_datetime(&timestamp);
@@ -714,6 +775,29 @@ impl PrimitiveType {
     }
 
     fn parse_decimal(raw: &str, dtype: DecimalType) -> Result<Scalar, Error> {
+        let (int, scale) = Self::parse_unscaled_decimal(raw, dtype)?;
+        let parse_error = || PrimitiveType::from(dtype).parse_error(raw);
+        require!(scale == dtype.scale() as i128, parse_error());
+        Ok(Scalar::Decimal(DecimalData::try_new(int, dtype)?))
+    }
+
+    /// Like [`Self::parse_decimal`], but values with fewer fractional digits than the scale of
+    /// `dtype` are rescaled to it.
+    fn parse_decimal_rescaled(raw: &str, dtype: DecimalType) -> Result<Scalar, Error> {
+        let (int, scale) = Self::parse_unscaled_decimal(raw, dtype)?;
+        let parse_error = || PrimitiveType::from(dtype).parse_error(raw);
+        let int = (dtype.scale() as i128)
+            .checked_sub(scale)
+            .and_then(|shift| u32::try_from(shift).ok())
+            .and_then(|shift| 10_i128.checked_pow(shift))
+            .and_then(|factor| int.checked_mul(factor))
+            .ok_or_else(parse_error)?;
+        Ok(Scalar::Decimal(DecimalData::try_new(int, dtype)?))
+    }
+
+    /// Parse a decimal in plain or scientific notation into its unscaled value and scale, e.g.
+    /// `1.23` into `(123, 2)` and `1.2E3` into `(12, -2)`.
+    fn parse_unscaled_decimal(raw: &str, dtype: DecimalType) -> Result<(i128, i128), Error> {
         let (base, exp): (&str, i128) = match raw.find(['e', 'E']) {
             None => (raw, 0), // no 'e' or 'E', so there's no exponent
             Some(pos) => {
@@ -741,16 +825,12 @@ impl PrimitiveType {
             }
         };
 
-        // we can assume this won't underflow since `frac_digits` is at minimum 0, and exp is at
-        // most i128::MAX, and 0-i128::MAX doesn't underflow
-        let scale = frac_digits - exp;
-        let scale: u8 = scale.try_into().map_err(|_| parse_error())?;
-        require!(scale == dtype.scale(), parse_error());
+        let scale = frac_digits.checked_sub(exp).ok_or_else(parse_error)?;
         let int: i128 = match frac_part {
             None => int_part.parse()?,
             Some(frac_part) => format!("{int_part}{frac_part}").parse()?,
         };
-        Ok(Scalar::Decimal(DecimalData::try_new(int, dtype)?))
+        Ok((int, scale))
     }
 }
 
@@ -1035,6 +1115,106 @@ mod tests {
         assert_timestamp_fails(&p_type, "1971-07-22");
     }
 
+    #[test]
+    fn test_from_partition_value() {
+        let cases = [
+            ("", DataType::INTEGER, Scalar::Null(DataType::INTEGER)),
+            (
+                "__HIVE_DEFAULT_PARTITION__",
+                DataType::DATE,
+                Scalar::Null(DataType::DATE),
+            ),
+            ("2011-01-11", DataType::DATE, Scalar::Date(14985)),
+            (
+                "2011-01-11 13:06:07.123456",
+                DataType::TIMESTAMP_NTZ,
+                Scalar::TimestampNtz(1294751167123456),
+            ),
+            (
+                "2011-01-11T14:06:07.123456+01:00",
+                DataType::TIMESTAMP,
+                Scalar::Timestamp(1294751167123456),
+            ),
+            (
+                "1.5",
+                DataType::decimal(3, 2).unwrap(),
+                Scalar::decimal(150, 3, 2).unwrap(),
+            ),
+            (
+                "1E2",
+                DataType::decimal(3, 0).unwrap(),
+                Scalar::decimal(100, 3, 0).unwrap(),
+            ),
+            (
+                "-0.05",
+                DataType::decimal(3, 2).unwrap(),
+                Scalar::decimal(-5, 3, 2).unwrap(),
+            ),
+            ("\u{1}\u{2}", DataType::BINARY, Scalar::Binary(vec![1, 2])),
+            ("a b", DataType::STRING, Scalar::String("a b".to_string())),
+            (
+                "-Infinity",
+                DataType::DOUBLE,
+                Scalar::Double(f64::NEG_INFINITY),
+            ),
+        ];
+        for (raw, data_type, expected) in cases {
+            let scalar = Scalar::from_partition_value(raw, &data_type).unwrap();
+            assert_eq!(scalar.data_type(), data_type);
+            if !scalar.is_null() {
+                assert_eq!(scalar, expected, "Parsing {raw:?}");
+            }
+        }
+
+        let decimal = DataType::decimal(3, 2).unwrap();
+        let array = DataType::from(ArrayType::new(DataType::INTEGER, false));
+        for (raw, data_type) in [
+            ("1.234", &decimal),
+            ("12.34", &decimal),
+            ("1E-3", &decimal),
+            ("2011-01-11", &DataType::TIMESTAMP),
+            ("[1]", &array),
+        ] {
+            let res = Scalar::from_partition_value(raw, data_type);
+            assert!(res.is_err(), "Parsed {raw:?} as {data_type}");
+        }
+    }
+
+    #[test]
+    fn test_serialize_partition_value() {
+        let cases = [
+            (
+                Scalar::TimestampNtz(1294751167123456),
+                "2011-01-11 13:06:07.123456",
+            ),
+            (Scalar::TimestampNtz(-1), "1969-12-31 23:59:59.999999"),
+            (Scalar::Timestamp(0), "1970-01-01 00:00:00.000000"),
+            (Scalar::Date(-1), "1969-12-31"),
+            (Scalar::decimal(-5, 3, 2).unwrap(), "-0.05"),
+            (Scalar::decimal(1234, 4, 0).unwrap(), "1234"),
+            (Scalar::Boolean(true), "true"),
+            (Scalar::Long(-7), "-7"),
+            (Scalar::Double(1.5), "1.5"),
+            (Scalar::String("a b".to_string()), "a b"),
+            (Scalar::Binary(b"abc".to_vec()), "abc"),
+        ];
+        for (scalar, expected) in cases {
+            let serialized = scalar.serialize_partition_value().unwrap().unwrap();
+            assert_eq!(serialized, expected);
+            let DataType::Primitive(p_type) = scalar.data_type() else {
+                panic!("expected a primitive type");
+            };
+            assert_eq!(p_type.parse_scalar(&serialized).unwrap(), scalar);
+        }
+
+        let null = Scalar::Null(DataType::TIMESTAMP_NTZ);
+        assert_eq!(null.serialize_partition_value().unwrap(), None);
+        let array = Scalar::Array(
+            ArrayData::try_new(ArrayType::new(DataType::INTEGER, false), [1]).unwrap(),
+        );
+        assert!(array.serialize_partition_value().is_err());
+    }
+
     #[test]
     fn test_partial_cmp() {
         let a = Scalar::Integer(1);
@@ -0,0 +1,337 @@
+//! Serde support for kernel expressions, predicates, and scalars, enabled by the
+//! `expression-serde` feature. This allows e.g. a distributed engine to ship pushdown predicates
+//! from its driver to its executors.
+//!
+//! [`Expression`] and [`Predicate`] use serde's default (externally tagged) enum encoding. Opaque
+//! expressions and predicates are engine-defined and cannot be serialized.
+//!
+//! A [`Scalar`] is encoded as `{"type": <type>, "value": <value>}`, where the type follows the
+//! Delta schema serialization format, and the value is encoded according to that type:
+//!
+//! | Type                                | Value encoding                                       |
+//! |-------------------------------------|------------------------------------------------------|
+//! | `byte`, `short`, `integer`, `long`  | JSON number                                          |
+//! | `float`, `double`                   | JSON number, or `"NaN"`, `"Infinity"`, `"-Infinity"` |
+//! | `string`                            | JSON string                                          |
+//! | `boolean`                           | JSON boolean                                         |
+//! | `date`                              | JSON number of days since the UNIX epoch             |
+//! | `timestamp`, `timestamp_ntz`        | JSON number of microseconds since the UNIX epoch     |
+//! | `binary`                            | JSON string of lowercase hex digits                  |
+//! | `decimal(p,s)`                      | JSON string of the unscaled integer value            |
+//! | `struct`                            | JSON array of the field values, in field order       |
+//! | `array`                             | JSON array of the elements                           |
+//! | `map`                               | JSON array of `[key, value]` pairs                   |
+//!
+//! A NULL value (including a NULL nested in a struct, array, or map) is encoded as JSON `null`.
+//!
+//! [`Expression`]: super::Expression
+//! [`Predicate`]: super::Predicate
+
+use itertools::Itertools;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::expressions::{ArrayData, DecimalData, MapData, Scalar, StructData};
+use crate::schema::{DataType, PrimitiveType};
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+#[derive(Serialize, Deserialize)]
+struct EncodedScalar {
+    #[serde(rename = "type")]
+    data_type: DataType,
+    value: Value,
+}
+
+impl Serialize for Scalar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoded = EncodedScalar {
+            data_type: self.data_type(),
+            value: encode_value(self),
+        };
+        encoded.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Scalar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let EncodedScalar { data_type, value } = EncodedScalar::deserialize(deserializer)?;
+        decode_value(&data_type, value).map_err(D::Error::custom)
+    }
+}
+
+fn encode_value(scalar: &Scalar) -> Value {
+    use Scalar::*;
+    match scalar {
+        Integer(v) => Value::from(*v),
+        Long(v) => Value::from(*v),
+        Short(v) => Value::from(*v),
+        Byte(v) => Value::from(*v),
+        Float(v) => encode_float(*v as f64),
+        Double(v) => encode_float(*v),
+        String(s) => Value::from(s.as_str()),
+        Boolean(b) => Value::from(*b),
+        Date(days) => Value::from(*days),
+        Timestamp(micros) | TimestampNtz(micros) => Value::from(*micros),
+        Binary(bytes) => Value::from(bytes.iter().map(|b| format!("{b:02x}")).join("")),
+        Decimal(d) => Value::from(d.bits().to_string()),
+        Null(_) => Value::Null,
+        Struct(data) => data.values().iter().map(encode_value).collect(),
+        Array(data) => {
+            #[allow(deprecated)]
+            let elements = data.array_elements();
+            elements.iter().map(encode_value).collect()
+        }
+        Map(data) => data
+            .pairs()
+            .iter()
+            .map(|(k, v)| Value::Array(vec![encode_value(k), encode_value(v)]))
+            .collect(),
+    }
+}
+
+// JSON numbers cannot represent NaN and infinities, so those become strings
+fn encode_float(v: f64) -> Value {
+    match v {
+        v if v.is_nan() => Value::from("NaN"),
+        f64::INFINITY => Value::from("Infinity"),
+        f64::NEG_INFINITY => Value::from("-Infinity"),
+        v => Value::from(v),
+    }
+}
+
+fn decode_value(data_type: &DataType, value: Value) -> DeltaResult<Scalar> {
+    let invalid =
+        |value: &Value| Error::generic(format!("Invalid encoding of a {data_type} value: {value}"));
+    if value.is_null() {
+        return Ok(Scalar::Null(data_type.clone()));
+    }
+    let scalar = match data_type {
+        DataType::Primitive(ptype) => {
+            decode_primitive(ptype, &value).ok_or_else(|| invalid(&value))?
+        }
+        DataType::Struct(struct_type) => {
+            let values = match value {
+                Value::Array(values) => values,
+                value => return Err(invalid(&value)),
+            };
+            require!(
+                values.len() == struct_type.fields().len(),
+                Error::generic(format!(
+                    "Expected {} values for a {data_type} value, but got {}",
+                    struct_type.fields().len(),
+                    values.len()
+                ))
+            );
+            let values = struct_type
+                .fields()
+                .zip(values)
+                .map(|(field, value)| decode_value(field.data_type(), value))
+                .try_collect()?;
+            let fields = struct_type.fields().cloned().collect();
+            Scalar::Struct(StructData::try_new(fields, values)?)
+        }
+        DataType::Array(array_type) => {
+            let elements = match value {
+                Value::Array(elements) => elements,
+                value => return Err(invalid(&value)),
+            };
+            let elements: Vec<_> = elements
+                .into_iter()
+                .map(|element| decode_value(array_type.element_type(), element))
+                .try_collect()?;
+            Scalar::Array(ArrayData::try_new(array_type.as_ref().clone(), elements)?)
+        }
+        DataType::Map(map_type) => {
+            let pairs = match value {
+                Value::Array(pairs) => pairs,
+                value => return Err(invalid(&value)),
+            };
+            let pairs: Vec<_> = pairs
+                .into_iter()
+                .map(|pair| match pair {
+                    Value::Array(pair) => match <[Value; 2]>::try_from(pair) {
+                        Ok([k, v]) => Ok((
+                            decode_value(map_type.key_type(), k)?,
+                            decode_value(map_type.value_type(), v)?,
+                        )),
+                        Err(pair) => Err(invalid(&Value::Array(pair))),
+                    },
+                    pair => Err(invalid(&pair)),
+                })
+                .try_collect()?;
+            Scalar::Map(MapData::try_new(map_type.as_ref().clone(), pairs)?)
+        }
+        DataType::Variant(_) => {
+            return Err(Error::unsupported("Variant values cannot be deserialized"))
+        }
+    };
+    Ok(scalar)
+}
+
+fn decode_primitive(ptype: &PrimitiveType, value: &Value) -> Option<Scalar> {
+    use PrimitiveType::*;
+    let scalar = match ptype {
+        Byte => Scalar::Byte(value.as_i64()?.try_into().ok()?),
+        Short => Scalar::Short(value.as_i64()?.try_into().ok()?),
+        Integer => Scalar::Integer(value.as_i64()?.try_into().ok()?),
+        Long => Scalar::Long(value.as_i64()?),
+        Float => Scalar::Float(decode_float(value)? as f32),
+        Double => Scalar::Double(decode_float(value)?),
+        String => Scalar::String(value.as_str()?.to_string()),
+        Boolean => Scalar::Boolean(value.as_bool()?),
+        Date => Scalar::Date(value.as_i64()?.try_into().ok()?),
+        Timestamp => Scalar::Timestamp(value.as_i64()?),
+        TimestampNtz => Scalar::TimestampNtz(value.as_i64()?),
+        Binary => Scalar::Binary(decode_hex(value.as_str()?)?),
+        Decimal(dtype) => {
+            let bits: i128 = value.as_str()?.parse().ok()?;
+            Scalar::Decimal(DecimalData::try_new(bits, *dtype).ok()?)
+        }
+    };
+    Some(scalar)
+}
+
+fn decode_float(value: &Value) -> Option<f64> {
+    match value.as_str() {
+        Some("NaN") => Some(f64::NAN),
+        Some("Infinity") => Some(f64::INFINITY),
+        Some("-Infinity") => Some(f64::NEG_INFINITY),
+        Some(_) => None,
+        None => value.as_f64(),
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use crate::schema::{ArrayType, MapType, StructField};
+
+    fn roundtrip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        let json = serde_json::to_string(value).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_scalar_encoding() {
+        let cases = [
+            (Scalar::from(7), r#"{"type":"integer","value":7}"#),
+            (Scalar::from(-7i64), r#"{"type":"long","value":-7}"#),
+            (Scalar::from("it's"), r#"{"type":"string","value":"it's"}"#),
+            (
+                Scalar::Double(f64::NEG_INFINITY),
+                r#"{"type":"double","value":"-Infinity"}"#,
+            ),
+            (Scalar::Date(19000), r#"{"type":"date","value":19000}"#),
+            (
+                Scalar::Timestamp(1_700_000_000_123_456),
+                r#"{"type":"timestamp","value":1700000000123456}"#,
+            ),
+            (
+                Scalar::Binary(vec![0x00, 0xab, 0x10]),
+                r#"{"type":"binary","value":"00ab10"}"#,
+            ),
+            (
+                Scalar::decimal(i128::MAX / 10, 38, 2).unwrap(),
+                r#"{"type":"decimal(38,2)","value":"17014118346046923173168730371588410572"}"#,
+            ),
+            (
+                Scalar::Null(DataType::SHORT),
+                r#"{"type":"short","value":null}"#,
+            ),
+        ];
+        for (scalar, expected) in cases {
+            assert_eq!(serde_json::to_string(&scalar).unwrap(), expected);
+            let decoded: Scalar = serde_json::from_str(expected).unwrap();
+            assert_eq!(decoded.data_type(), scalar.data_type());
+            assert_eq!(encode_value(&decoded), encode_value(&scalar), "{expected}");
+        }
+
+        let nan: Scalar = serde_json::from_str(r#"{"type":"float","value":"NaN"}"#).unwrap();
+        assert!(matches!(nan, Scalar::Float(v) if v.is_nan()));
+    }
+
+    #[test]
+    fn test_nested_scalar_roundtrip() {
+        let array_type = ArrayType::new(DataType::INTEGER, true);
+        let array = ArrayData::try_new(array_type.clone(), [Some(1), None, Some(3)]).unwrap();
+        let map_type = MapType::new(DataType::STRING, DataType::LONG, true);
+        let map = MapData::try_new(map_type, [("a", Some(1i64)), ("b", None)]).unwrap();
+        let fields = vec![
+            StructField::nullable("ids", array_type),
+            StructField::nullable("tags", map.map_type().clone()),
+            StructField::nullable("name", DataType::STRING),
+        ];
+        let values = vec![
+            Scalar::Array(array),
+            Scalar::Map(map),
+            Scalar::Null(DataType::STRING),
+        ];
+        let scalar = Scalar::Struct(StructData::try_new(fields, values).unwrap());
+
+        let json = serde_json::to_value(&scalar).unwrap();
+        assert_eq!(
+            json["value"],
+            serde_json::json!([[1, null, 3], [["a", 1], ["b", null]], null])
+        );
+        let decoded = roundtrip(&scalar);
+        assert_eq!(decoded.data_type(), scalar.data_type());
+        assert_eq!(encode_value(&decoded), encode_value(&scalar));
+    }
+
+    #[test]
+    fn test_invalid_scalar_encoding() {
+        for json in [
+            r#"{"type":"byte","value":300}"#,
+            r#"{"type":"binary","value":"abc"}"#,
+            r#"{"type":"decimal(3,1)","value":"12345"}"#,
+            r#"{"type":"integer","value":"7"}"#,
+            r#"{"type":"integer"}"#,
+        ] {
+            assert!(serde_json::from_str::<Scalar>(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_expression_roundtrip() {
+        let expressions = [
+            column_expr!("a.b") + Expr::literal(1),
+            Expr::case(
+                [(column_pred!("flag"), Expr::literal("yes"))],
+                Some(Expr::null_if(column_expr!("s"), Expr::literal(""))),
+            ),
+            Expr::try_cast(Expr::map_value(column_expr!("tags"), "env"), DataType::LONG),
+            Expr::struct_from([column_expr!("x"), Expr::literal(2.5)]),
+        ];
+        for expr in expressions {
+            assert_eq!(roundtrip(&expr), expr, "{expr}");
+        }
+
+        let preds = [
+            Pred::and(
+                Pred::gt(column_expr!("x"), Expr::literal(10i64)),
+                Pred::not(Pred::is_null(column_expr!("y"))),
+            ),
+            Pred::like(column_expr!("s"), "a%"),
+            Pred::or(column_pred!("flag"), Pred::literal(false)),
+        ];
+        for pred in preds {
+            assert_eq!(roundtrip(&pred), pred, "{pred}");
+        }
+
+        let json = serde_json::to_value(column_expr!("a.b")).unwrap();
+        assert_eq!(json, serde_json::json!({"Column": ["a", "b"]}));
+    }
+}
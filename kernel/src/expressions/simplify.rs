@@ -0,0 +1,389 @@
+//! Expression simplification, see [`simplify`] and [`simplify_predicate`].
+
+use std::sync::Arc;
+
+use crate::expressions::{
+    BinaryExpression, BinaryPredicate, BinaryPredicateOp, CaseExpression, CastExpression,
+    ElementExpression, Expression, JunctionPredicate, JunctionPredicateOp, OpaqueExpression,
+    OpaquePredicate, Predicate, Scalar, UnaryExpression, UnaryPredicate, UnaryPredicateOp,
+    VariadicExpression, VariadicExpressionOp,
+};
+use crate::kernel_predicates::{
+    DefaultKernelPredicateEvaluator, EmptyColumnResolver, KernelPredicateEvaluatorDefaults,
+};
+
+/// Rewrites an expression into an equivalent and (usually) cheaper form:
+///
+/// * Operations whose inputs are all literals are replaced by their result, e.g. `1 + 2` becomes
+///   `3` and `CAST('7' AS long)` becomes `7`. Operations that fail at evaluation time (e.g. due to
+///   overflow) are left unchanged, so the engine still reports the error.
+/// * `COALESCE` drops NULL literal inputs, as well as all inputs after the first non-NULL literal.
+/// * `CASE` drops branches whose condition is always FALSE or NULL, and branches after the first
+///   condition that is always TRUE.
+/// * Predicates nested inside the expression are simplified by [`simplify_predicate`].
+///
+/// Column references, transforms, and opaque and unknown expressions are never rewritten, but the
+/// inputs of opaque expressions are still simplified.
+pub fn simplify(expr: Expression) -> Expression {
+    use Expression::*;
+    match expr {
+        Literal(_) | Column(_) | Transform(_) | Unknown(_) => expr,
+        Expression::Predicate(pred) => Expression::from_pred(simplify_predicate(*pred)),
+        Struct(exprs) => Struct(
+            exprs
+                .into_iter()
+                .map(|expr| Arc::new(simplify(Arc::unwrap_or_clone(expr))))
+                .collect(),
+        ),
+        Unary(UnaryExpression { op, expr }) => Expression::unary(op, simplify(*expr)),
+        Binary(BinaryExpression { op, left, right }) => {
+            fold_constant(Expression::binary(op, simplify(*left), simplify(*right)))
+        }
+        Variadic(VariadicExpression { op, exprs }) => {
+            let mut exprs: Vec<_> = exprs.into_iter().map(simplify).collect();
+            if op == VariadicExpressionOp::Coalesce {
+                // Inputs after the first non-NULL literal are never reached, and NULL literals
+                // never contribute to the result (unless all inputs are NULL).
+                if let Some(i) = exprs
+                    .iter()
+                    .position(|e| matches!(e, Literal(v) if !v.is_null()))
+                {
+                    exprs.truncate(i + 1);
+                }
+                if exprs.iter().all(is_null_literal) {
+                    exprs.truncate(1);
+                } else {
+                    exprs.retain(|expr| !is_null_literal(expr));
+                }
+                if exprs.len() == 1 {
+                    return exprs.swap_remove(0);
+                }
+            }
+            fold_constant(Variadic(VariadicExpression { op, exprs }))
+        }
+        Case(case) => simplify_case(case),
+        Cast(CastExpression {
+            expr,
+            to,
+            on_overflow,
+        }) => fold_constant(Expression::cast_with_policy(
+            simplify(*expr),
+            to,
+            on_overflow,
+        )),
+        Element(ElementExpression { expr, accessor }) => {
+            fold_constant(Expression::element(simplify(*expr), accessor))
+        }
+        Opaque(OpaqueExpression { op, exprs }) => Opaque(OpaqueExpression {
+            op,
+            exprs: exprs.into_iter().map(simplify).collect(),
+        }),
+    }
+}
+
+/// Rewrites a predicate into an equivalent and (usually) cheaper form:
+///
+/// * Nested AND (OR) predicates are flattened, e.g. `AND(a, AND(b, c))` becomes `AND(a, b, c)`.
+/// * AND (OR) inputs that are always TRUE (FALSE) are dropped, and an input that is always FALSE
+///   (TRUE) replaces the whole junction.
+/// * Double negation is eliminated, e.g. `NOT(NOT(a))` becomes `a`.
+/// * Comparisons and NULL checks whose inputs are all literals are replaced by their result, and
+///   NULL-intolerant comparisons against a NULL literal become NULL.
+/// * Expressions nested inside the predicate are simplified by [`simplify`].
+///
+/// Opaque and unknown predicates are never rewritten, but the inputs of opaque predicates are
+/// still simplified.
+pub fn simplify_predicate(pred: Predicate) -> Predicate {
+    use Predicate::*;
+    match pred {
+        BooleanExpression(expr) => Predicate::from_expr(simplify(expr)),
+        Not(pred) => match simplify_predicate(*pred) {
+            Not(pred) => *pred,
+            pred => match literal_value(&pred) {
+                Some(Some(value)) => Predicate::literal(!value),
+                Some(None) => pred, // NOT(NULL) is NULL
+                None => Predicate::not(pred),
+            },
+        },
+        Unary(UnaryPredicate { op, expr }) => match (op, simplify(*expr)) {
+            (UnaryPredicateOp::IsNull, Expression::Literal(value)) => {
+                Predicate::literal(value.is_null())
+            }
+            (op, expr) => Predicate::unary(op, expr),
+        },
+        Binary(BinaryPredicate { op, left, right }) => {
+            let (left, right) = (simplify(*left), simplify(*right));
+            if let (Expression::Literal(a), Expression::Literal(b)) = (&left, &right) {
+                if let Some(value) = eval_literal_comparison(op, a, b) {
+                    return Predicate::literal(value);
+                }
+            }
+            if op.is_null_intolerant() && (is_null_literal(&left) || is_null_literal(&right)) {
+                return Predicate::null_literal();
+            }
+            Predicate::binary(op, left, right)
+        }
+        Junction(JunctionPredicate { op, preds }) => simplify_junction(op, preds),
+        Opaque(OpaquePredicate { op, exprs }) => Opaque(OpaquePredicate {
+            op,
+            exprs: exprs.into_iter().map(simplify).collect(),
+        }),
+        Unknown(_) => pred,
+    }
+}
+
+fn simplify_junction(op: JunctionPredicateOp, preds: Vec<Predicate>) -> Predicate {
+    // AND (OR) is dominated by any FALSE (TRUE) input, and ignores any TRUE (FALSE) input
+    let dominator = match op {
+        JunctionPredicateOp::And => false,
+        JunctionPredicateOp::Or => true,
+    };
+    let mut simplified = Vec::with_capacity(preds.len());
+    for pred in preds.into_iter().map(simplify_predicate) {
+        match pred {
+            // The nested junction was already simplified, so its inputs can be taken as-is
+            Predicate::Junction(JunctionPredicate {
+                op: nested_op,
+                preds,
+            }) if nested_op == op => simplified.extend(preds),
+            pred => match literal_value(&pred) {
+                Some(Some(value)) if value == dominator => return Predicate::literal(dominator),
+                Some(Some(_)) => (),
+                _ => simplified.push(pred),
+            },
+        }
+    }
+    match simplified.len() {
+        0 => Predicate::literal(!dominator),
+        1 => simplified.swap_remove(0),
+        _ => Predicate::junction(op, simplified),
+    }
+}
+
+fn simplify_case(case: CaseExpression) -> Expression {
+    let CaseExpression {
+        branches,
+        else_expr,
+    } = case;
+    let mut else_expr = else_expr.map(|expr| simplify(*expr));
+    let mut simplified = Vec::with_capacity(branches.len());
+    let mut last_pruned = None;
+    for (when, then) in branches {
+        let when = simplify_predicate(when);
+        match literal_value(&when) {
+            // This branch is always taken (if reached), so later branches are never reached
+            Some(Some(true)) => {
+                else_expr = Some(simplify(then));
+                break;
+            }
+            // This branch is never taken
+            Some(_) => last_pruned = Some((when, then)),
+            None => simplified.push((when, simplify(then))),
+        }
+    }
+    match else_expr {
+        Some(else_expr) if simplified.is_empty() => else_expr,
+        else_expr => {
+            // Without an ELSE, at least one branch must remain to determine the result type
+            if simplified.is_empty() {
+                simplified.extend(last_pruned);
+            }
+            Expression::case(simplified, else_expr)
+        }
+    }
+}
+
+// Replaces an operation whose inputs are all literals with its result, if kernel can compute it.
+fn fold_constant(expr: Expression) -> Expression {
+    use Expression::*;
+    let inputs_are_literal = match &expr {
+        Binary(BinaryExpression { left, right, .. }) => {
+            matches!((left.as_ref(), right.as_ref()), (Literal(_), Literal(_)))
+        }
+        Variadic(VariadicExpression { exprs, .. }) => exprs.iter().all(|e| matches!(e, Literal(_))),
+        Cast(CastExpression { expr, .. }) | Element(ElementExpression { expr, .. }) => {
+            matches!(expr.as_ref(), Literal(_))
+        }
+        _ => false,
+    };
+    if !inputs_are_literal {
+        return expr;
+    }
+    let evaluator = DefaultKernelPredicateEvaluator::from(EmptyColumnResolver);
+    match evaluator.eval_expr(&expr) {
+        Some(value) => Literal(value),
+        None => expr,
+    }
+}
+
+// Evaluates a comparison between two literals. Returns None if the result is NULL or if kernel
+// cannot evaluate the comparison.
+fn eval_literal_comparison(op: BinaryPredicateOp, a: &Scalar, b: &Scalar) -> Option<bool> {
+    match op {
+        // DISTINCT is a NULL-safe inequality
+        BinaryPredicateOp::Distinct => match (a.is_null(), b.is_null()) {
+            (true, true) => Some(false),
+            (true, false) | (false, true) => Some(true),
+            (false, false) => KernelPredicateEvaluatorDefaults::eval_pred_binary_scalars(
+                BinaryPredicateOp::Equal,
+                a,
+                b,
+                true,
+            ),
+        },
+        _ => KernelPredicateEvaluatorDefaults::eval_pred_binary_scalars(op, a, b, false),
+    }
+}
+
+// Returns `Some(Some(value))` for a boolean literal predicate, and `Some(None)` for a NULL literal.
+fn literal_value(pred: &Predicate) -> Option<Option<bool>> {
+    match pred {
+        Predicate::BooleanExpression(Expression::Literal(Scalar::Boolean(value))) => {
+            Some(Some(*value))
+        }
+        Predicate::BooleanExpression(Expression::Literal(Scalar::Null(_))) => Some(None),
+        _ => None,
+    }
+}
+
+fn is_null_literal(expr: &Expression) -> bool {
+    matches!(expr, Expression::Literal(value) if value.is_null())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::test_utils::assert_null_aware_eq;
+    use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use crate::schema::DataType;
+
+    #[test]
+    fn test_simplify_expression() {
+        let cases = [
+            (
+                column_expr!("x") + Expr::literal(1) * Expr::literal(2),
+                column_expr!("x") + Expr::literal(2),
+            ),
+            (
+                Expr::cast(Expr::literal("7"), DataType::LONG),
+                Expr::literal(7i64),
+            ),
+            // Evaluation errors are left for the engine to report
+            (
+                Expr::literal(1) / Expr::literal(0),
+                Expr::literal(1) / Expr::literal(0),
+            ),
+            (
+                Expr::coalesce([
+                    Expr::null_literal(DataType::INTEGER),
+                    column_expr!("x"),
+                    Expr::literal(3),
+                    column_expr!("y"),
+                ]),
+                Expr::coalesce([column_expr!("x"), Expr::literal(3)]),
+            ),
+            (
+                Expr::coalesce([Expr::null_literal(DataType::INTEGER), column_expr!("x")]),
+                column_expr!("x"),
+            ),
+            (
+                Expr::null_if(Expr::literal(1), Expr::literal(1)),
+                Expr::null_literal(DataType::INTEGER),
+            ),
+            (
+                Expr::case(
+                    [
+                        (Pred::literal(false), Expr::literal(1)),
+                        (column_pred!("a"), Expr::literal(2)),
+                        (Pred::not(Pred::literal(false)), Expr::literal(3)),
+                        (column_pred!("b"), Expr::literal(4)),
+                    ],
+                    Some(Expr::literal(5)),
+                ),
+                Expr::case(
+                    [(column_pred!("a"), Expr::literal(2))],
+                    Some(Expr::literal(3)),
+                ),
+            ),
+            (
+                Expr::if_then_else(
+                    Pred::lt(Expr::literal(1), Expr::literal(2)),
+                    column_expr!("x"),
+                    column_expr!("y"),
+                ),
+                column_expr!("x"),
+            ),
+            // A CASE without ELSE keeps one branch to preserve its result type
+            (
+                Expr::case([(Pred::literal(false), Expr::literal(1))], None),
+                Expr::case([(Pred::literal(false), Expr::literal(1))], None),
+            ),
+            (
+                Expr::struct_from([Expr::literal(1) + Expr::literal(1)]),
+                Expr::struct_from([Expr::literal(2)]),
+            ),
+            (
+                Expr::from_pred(Pred::not(Pred::not(column_pred!("a")))),
+                column_expr!("a"),
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_null_aware_eq!(simplify(input.clone()), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_simplify_predicate() {
+        let x_gt_1 = || Pred::gt(column_expr!("x"), Expr::literal(1));
+        let y_lt_2 = || Pred::lt(column_expr!("y"), Expr::literal(2));
+        let cases = [
+            (
+                Pred::and(x_gt_1(), Pred::and(y_lt_2(), Pred::literal(true))),
+                Pred::and(x_gt_1(), y_lt_2()),
+            ),
+            (
+                Pred::or(x_gt_1(), Pred::or(y_lt_2(), Pred::literal(true))),
+                Pred::literal(true),
+            ),
+            (
+                Pred::and(x_gt_1(), Pred::eq(Expr::literal(1), Expr::literal(2))),
+                Pred::literal(false),
+            ),
+            (
+                Pred::or(Pred::literal(false), Pred::not(Pred::not(x_gt_1()))),
+                x_gt_1(),
+            ),
+            (
+                Pred::and_from([Pred::literal(true), Pred::literal(true)]),
+                Pred::literal(true),
+            ),
+            // NULL inputs are preserved, since they matter when the junction is negated
+            (
+                Pred::and(Pred::null_literal(), x_gt_1()),
+                Pred::and(Pred::null_literal(), x_gt_1()),
+            ),
+            (
+                Pred::lt(column_expr!("x"), Expr::null_literal(DataType::INTEGER)),
+                Pred::null_literal(),
+            ),
+            (
+                Pred::distinct(Expr::null_literal(DataType::INTEGER), Expr::literal(1)),
+                Pred::literal(true),
+            ),
+            (Pred::is_null(Expr::literal(1)), Pred::literal(false)),
+            (
+                Pred::not(Pred::is_null(Expr::literal(1))),
+                Pred::literal(true),
+            ),
+            (Pred::not(Pred::null_literal()), Pred::null_literal()),
+            (
+                Pred::gt(column_expr!("x"), Expr::literal(2) + Expr::literal(3)),
+                Pred::gt(column_expr!("x"), Expr::literal(5)),
+            ),
+            (Pred::like(Expr::literal("abc"), "a%"), Pred::literal(true)),
+        ];
+        for (input, expected) in cases {
+            assert_null_aware_eq!(simplify_predicate(input.clone()), expected, "{input}");
+        }
+    }
+}
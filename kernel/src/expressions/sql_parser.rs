@@ -0,0 +1,1266 @@
+//! A small parser for the subset of (Spark) SQL expressions that Delta writers persist in table
+//! metadata, e.g. the expressions of legacy column invariants (`delta.invariants`), generated
+//! columns (`delta.generationExpression`), and column defaults (`CURRENT_DEFAULT`).
+//!
+//! The supported grammar covers column references (dotted and/or backtick-quoted), numeric,
+//! string, and boolean literals, `NULL`, the arithmetic operators `+`, `-`, `*`, `/`, and `%`, the
+//! comparison operators `=`, `==`, `!=`, `<>`, `<`, `<=`, `>`, `>=`, and `<=>`, `IS [NOT] NULL`,
+//! `[NOT] IN` with a list of literals, `[NOT] LIKE` and `[NOT] ILIKE` with a literal pattern,
+//! `AND`/`OR`/`NOT` with parentheses, the conditionals `CASE WHEN ... THEN ... [ELSE ...] END` and
+//! `IF(cond, then, else)`, the functions `COALESCE` and `NULLIF`, `CAST(expr AS type)` and
+//! `TRY_CAST(expr AS type)` to primitive types, and map and array subscripts with literal keys and
+//! indexes (`map['key']`, `array[0]`). Keywords, function names, and type names are case
+//! insensitive.
+//!
+//! Columns are resolved against a schema, and literals compared against a column are coerced to
+//! that column's type, so that e.g. `x > 3` works for a LONG column and `d >= '2020-01-01'` works
+//! for a DATE column. Predicates can also be parsed without a schema, in which case column types
+//! are unknown and literal types are inferred from their values alone.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use itertools::Itertools;
+
+use crate::expressions::{
+    ArrayData, BinaryExpressionOp, CastOverflowPolicy, ColumnName, Expression, Predicate, Scalar,
+    VariadicExpressionOp,
+};
+use crate::schema::{ArrayType, DataType, PrimitiveType, StructType};
+use crate::{DeltaResult, Error};
+
+/// Parses `sql` as a boolean predicate, resolving any column references against `schema`.
+pub(crate) fn parse_predicate(sql: &str, schema: &StructType) -> DeltaResult<Predicate> {
+    Parser::new(sql, Some(schema))?.parse_predicate(sql)
+}
+
+/// Parses `sql` as a boolean predicate without a schema. Column references are not resolved, and
+/// literals are not coerced to the types of the columns they are compared against.
+#[cfg_attr(not(feature = "sql-parser"), allow(dead_code))]
+pub(crate) fn parse_predicate_without_schema(sql: &str) -> DeltaResult<Predicate> {
+    Parser::new(sql, None)?.parse_predicate(sql)
+}
+
+/// Parses `sql` as a (non-boolean) value expression, resolving any column references against
+/// `schema`. Returns the parsed expression along with its result type. Untyped literals are
+/// coerced to `result_type`, if given.
+pub(crate) fn parse_expression(
+    sql: &str,
+    schema: &StructType,
+    result_type: Option<&DataType>,
+) -> DeltaResult<(Expression, DataType)> {
+    let mut parser = Parser::new(sql, Some(schema))?;
+    let operand = parser.parse_additive()?;
+    parser.expect_end(sql)?;
+    operand.into_typed(result_type)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An unquoted identifier, which may also be a keyword
+    Ident(String),
+    /// A backtick-quoted identifier, which is never a keyword
+    QuotedIdent(String),
+    Number(String),
+    String(String),
+    Dot,
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Comma,
+    Op(&'static str),
+}
+
+fn tokenize(sql: &str) -> DeltaResult<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '.' => Token::Dot,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            ',' => Token::Comma,
+            '-' => Token::Op("-"),
+            '+' => Token::Op("+"),
+            '*' => Token::Op("*"),
+            '/' => Token::Op("/"),
+            '%' => Token::Op("%"),
+            '`' => Token::QuotedIdent(parse_quoted(&mut chars, '`')?),
+            '\'' | '"' => Token::String(parse_quoted(&mut chars, c)?),
+            '=' => {
+                // `==` is an alias for `=`
+                chars.next_if_eq(&'=');
+                Token::Op("=")
+            }
+            '!' if chars.next_if_eq(&'=').is_some() => Token::Op("!="),
+            '<' if chars.next_if_eq(&'>').is_some() => Token::Op("!="),
+            '<' if chars.next_if_eq(&'=').is_some() => match chars.next_if_eq(&'>') {
+                Some(_) => Token::Op("<=>"),
+                None => Token::Op("<="),
+            },
+            '<' => Token::Op("<"),
+            '>' if chars.next_if_eq(&'=').is_some() => Token::Op(">="),
+            '>' => Token::Op(">"),
+            c if c.is_ascii_digit() => {
+                let mut number = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '.') {
+                    number.push(c);
+                    // allow a signed exponent, e.g. `1.5e-3`
+                    if c == 'e' || c == 'E' {
+                        if let Some(sign) = chars.next_if(|c| *c == '-' || *c == '+') {
+                            number.push(sign);
+                        }
+                    }
+                }
+                Token::Number(number)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                }
+                Token::Ident(ident)
+            }
+            other => {
+                return Err(Error::invalid_expression(format!(
+                    "Unexpected character {other:?} in SQL expression: {sql}"
+                )))
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Parses the remainder of a quoted string or identifier, whose opening `quote` was already
+/// consumed. The quote character can be escaped by doubling it; string literals additionally
+/// support backslash escapes.
+fn parse_quoted(chars: &mut Peekable<Chars<'_>>, quote: char) -> DeltaResult<String> {
+    let mut result = String::new();
+    loop {
+        match chars.next() {
+            Some(c) if c == quote && chars.next_if_eq(&quote).is_none() => return Ok(result),
+            Some('\\') if quote != '`' => match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some(c) => result.push(c),
+                None => break,
+            },
+            Some(c) => result.push(c),
+            None => break,
+        }
+    }
+    Err(Error::invalid_expression(format!(
+        "No closing {quote:?} after {result:?} in SQL expression"
+    )))
+}
+
+/// A parsed operand of a comparison or arithmetic operation
+enum Operand {
+    /// An expression of known type, e.g. a column reference or arithmetic involving columns
+    Typed(Expression, DataType),
+    /// A literal, whose type is determined by the context it is used in
+    Literal(Literal),
+    /// An expression of unknown type, e.g. a column reference when parsing without a schema
+    Untyped(Expression),
+}
+
+#[derive(Clone)]
+enum Literal {
+    Number(String),
+    String(String),
+    Boolean(bool),
+    Null,
+}
+
+impl Operand {
+    /// Creates an operand for an expression whose type may be unknown.
+    fn new(expr: Expression, data_type: Option<DataType>) -> Self {
+        match data_type {
+            Some(data_type) => Operand::Typed(expr, data_type),
+            None => Operand::Untyped(expr),
+        }
+    }
+
+    fn data_type(&self) -> Option<&DataType> {
+        match self {
+            Operand::Typed(_, data_type) => Some(data_type),
+            Operand::Literal(_) | Operand::Untyped(_) => None,
+        }
+    }
+
+    /// Converts this operand to an expression, coercing a literal to the `hint` type if possible.
+    fn into_expression(self, hint: Option<&DataType>) -> DeltaResult<Expression> {
+        match self {
+            Operand::Typed(expr, _) | Operand::Untyped(expr) => Ok(expr),
+            Operand::Literal(literal) => Ok(Expression::literal(literal_to_scalar(literal, hint)?)),
+        }
+    }
+
+    /// Converts this operand to a typed expression, inferring the type of a literal from its
+    /// value if it has no `hint` type.
+    fn into_typed(self, hint: Option<&DataType>) -> DeltaResult<(Expression, DataType)> {
+        match self {
+            Operand::Typed(expr, data_type) => Ok((expr, data_type)),
+            Operand::Literal(literal) => {
+                let scalar = literal_to_scalar(literal, hint)?;
+                let data_type = scalar.data_type();
+                Ok((Expression::literal(scalar), data_type))
+            }
+            Operand::Untyped(expr) => Err(Error::invalid_expression(format!(
+                "Cannot determine the type of {expr} in SQL expression without a schema"
+            ))),
+        }
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    schema: Option<&'a StructType>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(sql: &str, schema: Option<&'a StructType>) -> DeltaResult<Self> {
+        Ok(Self {
+            tokens: tokenize(sql)?,
+            pos: 0,
+            schema,
+        })
+    }
+
+    fn parse_predicate(mut self, sql: &str) -> DeltaResult<Predicate> {
+        let predicate = self.parse_or()?;
+        self.expect_end(sql)?;
+        Ok(predicate)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn next_if_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_end(&self, sql: &str) -> DeltaResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(Error::invalid_expression(format!(
+                "Unexpected token {token:?} in SQL expression: {sql}"
+            ))),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> DeltaResult<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(Error::invalid_expression(format!(
+                "Expected {expected:?} in SQL expression but found {other:?}"
+            ))),
+        }
+    }
+
+    fn parse_or(&mut self) -> DeltaResult<Predicate> {
+        let mut predicate = self.parse_and()?;
+        while self.next_if_keyword("OR") {
+            predicate = Predicate::or(predicate, self.parse_and()?);
+        }
+        Ok(predicate)
+    }
+
+    fn parse_and(&mut self) -> DeltaResult<Predicate> {
+        let mut predicate = self.parse_not()?;
+        while self.next_if_keyword("AND") {
+            predicate = Predicate::and(predicate, self.parse_not()?);
+        }
+        Ok(predicate)
+    }
+
+    fn parse_not(&mut self) -> DeltaResult<Predicate> {
+        if self.next_if_keyword("NOT") {
+            return Ok(Predicate::not(self.parse_not()?));
+        }
+        if self.peek() == Some(&Token::LeftParen) {
+            // A parenthesis either groups a predicate, e.g. `(a OR b) AND c`, or an arithmetic
+            // expression, e.g. `(a + b) > c`. Try the former first, and fall back to the latter.
+            let start = self.pos;
+            self.pos += 1;
+            if let Ok(predicate) = self.parse_or() {
+                if self.peek() == Some(&Token::RightParen) {
+                    self.pos += 1;
+                    if !matches!(self.peek(), Some(Token::Op(_))) {
+                        return Ok(predicate);
+                    }
+                }
+            }
+            self.pos = start;
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> DeltaResult<Predicate> {
+        let left = self.parse_additive()?;
+        if self.next_if_keyword("IS") {
+            let negated = self.next_if_keyword("NOT");
+            if !self.next_if_keyword("NULL") {
+                return Err(Error::invalid_expression(
+                    "Expected NULL after IS [NOT] in SQL expression",
+                ));
+            }
+            let expr = left.into_expression(None)?;
+            let predicate = if negated {
+                Predicate::is_not_null(expr)
+            } else {
+                Predicate::is_null(expr)
+            };
+            return Ok(predicate);
+        }
+
+        // Here, NOT can only negate a subsequent IN or LIKE, e.g. `x NOT IN (1, 2)`
+        let negated = self.next_if_keyword("NOT");
+        let predicate = if self.next_if_keyword("IN") {
+            self.parse_in_list(left)?
+        } else if self.next_if_keyword("LIKE") {
+            Predicate::like(
+                left.into_expression(Some(&DataType::STRING))?,
+                self.parse_pattern()?,
+            )
+        } else if self.next_if_keyword("ILIKE") {
+            Predicate::ilike(
+                left.into_expression(Some(&DataType::STRING))?,
+                self.parse_pattern()?,
+            )
+        } else if negated {
+            return Err(Error::invalid_expression(
+                "Expected IN, LIKE, or ILIKE after NOT in SQL expression",
+            ));
+        } else {
+            return self.parse_binary_comparison(left);
+        };
+        Ok(match negated {
+            true => Predicate::not(predicate),
+            false => predicate,
+        })
+    }
+
+    fn parse_binary_comparison(&mut self, left: Operand) -> DeltaResult<Predicate> {
+        let Some(op) = self.next_if_op(&["=", "!=", "<", "<=", ">", ">=", "<=>"]) else {
+            return self.to_predicate(left);
+        };
+        let right = self.parse_additive()?;
+
+        // Coerce a literal on either side to the type of the other side
+        let left_type = left.data_type().cloned();
+        let left = left.into_expression(right.data_type())?;
+        let right = right.into_expression(left_type.as_ref())?;
+        let predicate = match op {
+            "=" => Predicate::eq(left, right),
+            "!=" => Predicate::ne(left, right),
+            "<" => Predicate::lt(left, right),
+            "<=" => Predicate::le(left, right),
+            ">" => Predicate::gt(left, right),
+            ">=" => Predicate::ge(left, right),
+            _ => Predicate::not(Predicate::distinct(left, right)),
+        };
+        Ok(predicate)
+    }
+
+    /// Parses the remainder of `<left> IN (<literal>, ...)`, whose `IN` keyword was already
+    /// consumed. The literals are coerced to the type of `left` if known, or else to the type of the
+    /// first non-NULL literal.
+    fn parse_in_list(&mut self, left: Operand) -> DeltaResult<Predicate> {
+        self.expect(Token::LeftParen)?;
+        let mut values = vec![];
+        loop {
+            let Operand::Literal(value) = self.parse_operand()? else {
+                return Err(Error::unsupported(
+                    "Only literal values are supported in IN lists in SQL expressions",
+                ));
+            };
+            values.push(value);
+            if self.peek() != Some(&Token::Comma) {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.expect(Token::RightParen)?;
+
+        let element_type = match left.data_type() {
+            Some(data_type) => data_type.clone(),
+            None => match values.iter().find(|value| !matches!(value, Literal::Null)) {
+                Some(value) => literal_to_scalar(value.clone(), None)?.data_type(),
+                None => DataType::STRING,
+            },
+        };
+        let values: Vec<_> = values
+            .into_iter()
+            .map(|value| literal_to_scalar(value, Some(&element_type)))
+            .try_collect()?;
+        let contains_null = values.iter().any(Scalar::is_null);
+        let left = left.into_expression(Some(&element_type))?;
+        let values = ArrayData::try_new(ArrayType::new(element_type, contains_null), values)?;
+        Ok(Predicate::in_list(left, values))
+    }
+
+    /// Parses the string literal pattern of a `[I]LIKE` predicate.
+    fn parse_pattern(&mut self) -> DeltaResult<String> {
+        match self.next() {
+            Some(Token::String(pattern)) => Ok(pattern),
+            other => Err(Error::invalid_expression(format!(
+                "Expected a string pattern after LIKE in SQL expression but found {other:?}"
+            ))),
+        }
+    }
+
+    fn parse_additive(&mut self) -> DeltaResult<Operand> {
+        let mut left = self.parse_multiplicative()?;
+        while let Some(op) = self.next_if_op(&["+", "-"]) {
+            let right = self.parse_multiplicative()?;
+            left = Self::arithmetic(op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    fn parse_multiplicative(&mut self) -> DeltaResult<Operand> {
+        let mut left = self.parse_operand()?;
+        while let Some(op) = self.next_if_op(&["*", "/", "%"]) {
+            let right = self.parse_operand()?;
+            left = Self::arithmetic(op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    /// Builds a binary arithmetic expression, coercing a literal on either side to the type of
+    /// the other side. The result type follows [`BinaryExpressionOp::result_type`].
+    fn arithmetic(op: &str, left: Operand, right: Operand) -> DeltaResult<Operand> {
+        let op = match op {
+            "+" => BinaryExpressionOp::Plus,
+            "-" => BinaryExpressionOp::Minus,
+            "*" => BinaryExpressionOp::Multiply,
+            "/" => BinaryExpressionOp::Divide,
+            _ => BinaryExpressionOp::Modulo,
+        };
+        if matches!(left, Operand::Untyped(_)) || matches!(right, Operand::Untyped(_)) {
+            let left_type = left.data_type().cloned();
+            let left = left.into_expression(right.data_type())?;
+            let right = right.into_expression(left_type.as_ref())?;
+            return Ok(Operand::Untyped(Expression::binary(op, left, right)));
+        }
+        let right_type = right.data_type().cloned();
+        let (left, left_type) = left.into_typed(right_type.as_ref())?;
+        let (right, right_type) = right.into_typed(Some(&left_type))?;
+        let data_type = op.result_type(&left_type, &right_type)?;
+        Ok(Operand::Typed(
+            Expression::binary(op, left, right),
+            data_type,
+        ))
+    }
+
+    fn parse_operand(&mut self) -> DeltaResult<Operand> {
+        let literal = match self.next() {
+            Some(Token::Number(number)) => Literal::Number(number),
+            Some(Token::Op("-")) => match self.next() {
+                Some(Token::Number(number)) => Literal::Number(format!("-{number}")),
+                other => {
+                    return Err(Error::invalid_expression(format!(
+                        "Expected a number after '-' in SQL expression but found {other:?}"
+                    )))
+                }
+            },
+            Some(Token::String(s)) => Literal::String(s),
+            Some(Token::LeftParen) => {
+                let operand = self.parse_additive()?;
+                self.expect(Token::RightParen)?;
+                return Ok(operand);
+            }
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("TRUE") => {
+                Literal::Boolean(true)
+            }
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("FALSE") => {
+                Literal::Boolean(false)
+            }
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("NULL") => Literal::Null,
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("CASE") => {
+                return self.parse_case();
+            }
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::LeftParen) => {
+                return self.parse_function(&name);
+            }
+            Some(Token::Ident(field) | Token::QuotedIdent(field)) => {
+                let mut path = vec![field];
+                while self.peek() == Some(&Token::Dot) {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Ident(field) | Token::QuotedIdent(field)) => path.push(field),
+                        other => {
+                            return Err(Error::invalid_expression(format!(
+                            "Expected a field name after '.' in SQL expression but found {other:?}"
+                        )))
+                        }
+                    }
+                }
+                let column = ColumnName::new(path);
+                let data_type = self.column_type(&column)?;
+                return self.parse_subscripts(Expression::Column(column), data_type);
+            }
+            other => {
+                return Err(Error::invalid_expression(format!(
+                    "Expected a column or literal in SQL expression but found {other:?}"
+                )))
+            }
+        };
+        Ok(Operand::Literal(literal))
+    }
+
+    /// Parses any `[key]` or `[index]` subscripts that follow a map or array operand. If the
+    /// operand's type is unknown, a numeric subscript is taken as an array index and any other
+    /// subscript as a map key.
+    fn parse_subscripts(
+        &mut self,
+        mut expr: Expression,
+        mut data_type: Option<DataType>,
+    ) -> DeltaResult<Operand> {
+        while self.peek() == Some(&Token::LeftBracket) {
+            self.pos += 1;
+            let Operand::Literal(subscript) = self.parse_operand()? else {
+                return Err(Error::unsupported(
+                    "Only literal subscripts are supported in SQL expressions",
+                ));
+            };
+            self.expect(Token::RightBracket)?;
+            (expr, data_type) = match (data_type, subscript) {
+                (Some(DataType::Map(map)), key) => {
+                    let key = literal_to_scalar(key, Some(map.key_type()))?;
+                    (
+                        Expression::map_value(expr, key),
+                        Some(map.value_type().clone()),
+                    )
+                }
+                (Some(DataType::Array(array)), Literal::Number(index)) => {
+                    let element_type = array.element_type().clone();
+                    let index = parse_array_index(&index)?;
+                    (Expression::array_element(expr, index), Some(element_type))
+                }
+                (None, Literal::Number(index)) => (
+                    Expression::array_element(expr, parse_array_index(&index)?),
+                    None,
+                ),
+                (None, key) => (
+                    Expression::map_value(expr, literal_to_scalar(key, None)?),
+                    None,
+                ),
+                (Some(data_type), _) => {
+                    return Err(Error::invalid_expression(format!(
+                        "Invalid subscript of {expr} with type {data_type} in SQL expression"
+                    )))
+                }
+            };
+        }
+        Ok(Operand::new(expr, data_type))
+    }
+
+    /// Parses the remainder of `CASE WHEN <cond> THEN <value> ... [ELSE <value>] END`, whose `CASE`
+    /// keyword was already consumed.
+    fn parse_case(&mut self) -> DeltaResult<Operand> {
+        let mut branches = vec![];
+        while self.next_if_keyword("WHEN") {
+            let when = self.parse_or()?;
+            if !self.next_if_keyword("THEN") {
+                return Err(Error::invalid_expression(
+                    "Expected THEN after CASE WHEN condition in SQL expression",
+                ));
+            }
+            branches.push((when, self.parse_additive()?));
+        }
+        if branches.is_empty() {
+            return Err(Error::invalid_expression(
+                "Expected WHEN after CASE in SQL expression",
+            ));
+        }
+        let else_operand = match self.next_if_keyword("ELSE") {
+            true => Some(self.parse_additive()?),
+            false => None,
+        };
+        if !self.next_if_keyword("END") {
+            return Err(Error::invalid_expression(
+                "Expected END after CASE expression in SQL expression",
+            ));
+        }
+        Self::conditional(branches, else_operand)
+    }
+
+    /// Parses the argument list of a call to the function `name`, whose name was already consumed.
+    fn parse_function(&mut self, name: &str) -> DeltaResult<Operand> {
+        let op = match name.to_ascii_uppercase().as_str() {
+            "IF" => return self.parse_if(),
+            "CAST" => return self.parse_cast(CastOverflowPolicy::Error),
+            "TRY_CAST" => return self.parse_cast(CastOverflowPolicy::Null),
+            "COALESCE" => VariadicExpressionOp::Coalesce,
+            "NULLIF" => VariadicExpressionOp::NullIf,
+            _ => {
+                return Err(Error::unsupported(format!(
+                    "Unsupported function {name} in SQL expression"
+                )))
+            }
+        };
+        self.expect(Token::LeftParen)?;
+        let mut args = vec![self.parse_additive()?];
+        while self.peek() == Some(&Token::Comma) {
+            self.pos += 1;
+            args.push(self.parse_additive()?);
+        }
+        self.expect(Token::RightParen)?;
+        if op == VariadicExpressionOp::NullIf && args.len() != 2 {
+            return Err(Error::invalid_expression(format!(
+                "NULLIF takes two arguments but got {} in SQL expression",
+                args.len()
+            )));
+        }
+        let (exprs, data_type) = Self::unify(args)?;
+        Ok(Operand::new(Expression::variadic(op, exprs), data_type))
+    }
+
+    /// Parses the remainder of `IF(<cond>, <then>, <else>)`, whose `IF` keyword was already
+    /// consumed.
+    fn parse_if(&mut self) -> DeltaResult<Operand> {
+        self.expect(Token::LeftParen)?;
+        let when = self.parse_or()?;
+        self.expect(Token::Comma)?;
+        let then = self.parse_additive()?;
+        self.expect(Token::Comma)?;
+        let else_operand = self.parse_additive()?;
+        self.expect(Token::RightParen)?;
+        Self::conditional(vec![(when, then)], Some(else_operand))
+    }
+
+    /// Parses the remainder of `CAST(<value> AS <type>)` (or `TRY_CAST`), whose function name was
+    /// already consumed.
+    fn parse_cast(&mut self, on_overflow: CastOverflowPolicy) -> DeltaResult<Operand> {
+        self.expect(Token::LeftParen)?;
+        let expr = self.parse_additive()?.into_expression(None)?;
+        if !self.next_if_keyword("AS") {
+            return Err(Error::invalid_expression(
+                "Expected AS after CAST value in SQL expression",
+            ));
+        }
+        let to = self.parse_data_type()?;
+        self.expect(Token::RightParen)?;
+        Ok(Operand::Typed(
+            Expression::cast_with_policy(expr, to.clone(), on_overflow),
+            to,
+        ))
+    }
+
+    /// Parses the name of a primitive type, e.g. `BIGINT` or `DECIMAL(10, 2)`.
+    fn parse_data_type(&mut self) -> DeltaResult<DataType> {
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name,
+            other => {
+                return Err(Error::invalid_expression(format!(
+                    "Expected a type name in SQL expression but found {other:?}"
+                )))
+            }
+        };
+        let data_type = match name.to_ascii_uppercase().as_str() {
+            "STRING" => DataType::STRING,
+            "BOOLEAN" => DataType::BOOLEAN,
+            "TINYINT" | "BYTE" => DataType::BYTE,
+            "SMALLINT" | "SHORT" => DataType::SHORT,
+            "INT" | "INTEGER" => DataType::INTEGER,
+            "BIGINT" | "LONG" => DataType::LONG,
+            "FLOAT" | "REAL" => DataType::FLOAT,
+            "DOUBLE" => DataType::DOUBLE,
+            "BINARY" => DataType::BINARY,
+            "DATE" => DataType::DATE,
+            "TIMESTAMP" => DataType::TIMESTAMP,
+            "TIMESTAMP_NTZ" => DataType::TIMESTAMP_NTZ,
+            "DECIMAL" | "DEC" | "NUMERIC" => {
+                // Spark defaults to DECIMAL(10, 0) if the precision and scale are omitted
+                let (mut precision, mut scale) = (10, 0);
+                if self.peek() == Some(&Token::LeftParen) {
+                    self.pos += 1;
+                    precision = self.parse_type_parameter()?;
+                    scale = match self.peek() {
+                        Some(Token::Comma) => {
+                            self.pos += 1;
+                            self.parse_type_parameter()?
+                        }
+                        _ => 0,
+                    };
+                    self.expect(Token::RightParen)?;
+                }
+                DataType::decimal(precision, scale)?
+            }
+            _ => {
+                return Err(Error::unsupported(format!(
+                    "Unsupported type {name} in SQL expression"
+                )))
+            }
+        };
+        Ok(data_type)
+    }
+
+    fn parse_type_parameter(&mut self) -> DeltaResult<u8> {
+        match self.next() {
+            Some(Token::Number(number)) => number
+                .parse()
+                .map_err(|_| Error::invalid_expression(format!("Invalid type parameter {number}"))),
+            other => Err(Error::invalid_expression(format!(
+                "Expected a type parameter in SQL expression but found {other:?}"
+            ))),
+        }
+    }
+
+    /// Builds a conditional expression, whose results are converted to a common type (see
+    /// [`Self::unify`]).
+    fn conditional(
+        branches: Vec<(Predicate, Operand)>,
+        else_operand: Option<Operand>,
+    ) -> DeltaResult<Operand> {
+        let (whens, thens): (Vec<_>, Vec<_>) = branches.into_iter().unzip();
+        let has_else = else_operand.is_some();
+        let (mut results, result_type) = Self::unify(thens.into_iter().chain(else_operand))?;
+        let else_expr = if has_else { results.pop() } else { None };
+        Ok(Operand::new(
+            Expression::case(whens.into_iter().zip(results), else_expr),
+            result_type,
+        ))
+    }
+
+    /// Converts operands that must share a type to expressions. Literals are coerced to the type
+    /// of the first typed operand; if all operands are literals, the first one determines the type.
+    /// The common type is unknown if any operand's type is unknown.
+    fn unify(
+        operands: impl IntoIterator<Item = Operand>,
+    ) -> DeltaResult<(Vec<Expression>, Option<DataType>)> {
+        let operands: Vec<_> = operands.into_iter().collect();
+        let is_untyped = operands.iter().any(|o| matches!(o, Operand::Untyped(_)));
+        let mut result_type = operands.iter().find_map(Operand::data_type).cloned();
+        let mut exprs = Vec::with_capacity(operands.len());
+        for operand in operands {
+            let expr = match operand {
+                Operand::Untyped(expr) => expr,
+                operand => {
+                    let (expr, data_type) = operand.into_typed(result_type.as_ref())?;
+                    result_type.get_or_insert(data_type);
+                    expr
+                }
+            };
+            exprs.push(expr);
+        }
+        Ok((exprs, result_type.filter(|_| !is_untyped)))
+    }
+
+    /// Resolves the type of a (possibly nested) column against the schema, if there is one.
+    fn column_type(&self, column: &ColumnName) -> DeltaResult<Option<DataType>> {
+        let Some(mut schema) = self.schema else {
+            return Ok(None);
+        };
+        let not_found = || {
+            Error::missing_column(format!("Column {column} not found in schema"))
+                .with_column(column.clone())
+        };
+        let (last, parents) = column.path().split_last().ok_or_else(not_found)?;
+        for name in parents {
+            match schema.field(name).map(|field| field.data_type()) {
+                Some(DataType::Struct(inner)) => schema = inner.as_ref(),
+                _ => return Err(not_found()),
+            }
+        }
+        let field = schema.field(last).ok_or_else(not_found)?;
+        Ok(Some(field.data_type().clone()))
+    }
+
+    fn to_predicate(&self, operand: Operand) -> DeltaResult<Predicate> {
+        match operand {
+            Operand::Typed(expr, data_type) if data_type == DataType::BOOLEAN => {
+                Ok(Predicate::from_expr(expr))
+            }
+            Operand::Typed(expr, _) => Err(Error::invalid_expression(format!(
+                "Non-boolean expression {expr} used as a predicate in SQL expression"
+            ))),
+            // Without a schema, we have to trust that the expression is boolean
+            Operand::Untyped(expr) => Ok(Predicate::from_expr(expr)),
+            Operand::Literal(Literal::Boolean(value)) => Ok(Predicate::literal(value)),
+            Operand::Literal(Literal::Null) => Ok(Predicate::null_literal()),
+            Operand::Literal(_) => Err(Error::invalid_expression(
+                "Non-boolean literal used as a predicate in SQL expression",
+            )),
+        }
+    }
+}
+
+fn parse_array_index(index: &str) -> DeltaResult<usize> {
+    index
+        .parse()
+        .map_err(|_| Error::invalid_expression(format!("Invalid array index {index}")))
+}
+
+/// Converts a literal to a [`Scalar`], coercing it to the `hint` type if one is available.
+fn literal_to_scalar(literal: Literal, hint: Option<&DataType>) -> DeltaResult<Scalar> {
+    let primitive = hint.and_then(DataType::as_primitive_opt);
+    match (literal, primitive) {
+        (Literal::Null, _) => Ok(Scalar::Null(hint.cloned().unwrap_or(DataType::STRING))),
+        (Literal::Boolean(value), _) => Ok(Scalar::Boolean(value)),
+        (Literal::String(s), None | Some(PrimitiveType::String)) => Ok(Scalar::String(s)),
+        (Literal::String(s) | Literal::Number(s), Some(ptype)) => ptype.parse_scalar(&s),
+        (Literal::Number(number), None) => {
+            if let Ok(value) = number.parse::<i32>() {
+                Ok(Scalar::Integer(value))
+            } else if let Ok(value) = number.parse::<i64>() {
+                Ok(Scalar::Long(value))
+            } else {
+                number.parse::<f64>().map(Scalar::Double).map_err(|_| {
+                    Error::invalid_expression(format!("Invalid numeric literal {number}"))
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::test_utils::assert_null_aware_eq;
+    use crate::expressions::{column_expr, column_name};
+    use crate::schema::{ArrayType, MapType, StructField};
+
+    fn test_schema() -> StructType {
+        StructType::new_unchecked([
+            StructField::nullable("x", DataType::LONG),
+            StructField::nullable("flag", DataType::BOOLEAN),
+            StructField::nullable("d", DataType::DATE),
+            StructField::nullable(
+                "s",
+                StructType::new_unchecked([
+                    StructField::nullable("y", DataType::INTEGER),
+                    StructField::nullable("weird.name", DataType::STRING),
+                ]),
+            ),
+            StructField::nullable(
+                "tags",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+            StructField::nullable("ids", ArrayType::new(DataType::LONG, true)),
+        ])
+    }
+
+    #[test]
+    fn test_parse_comparisons() {
+        let schema = test_schema();
+        let parse = |sql| parse_predicate(sql, &schema).unwrap();
+
+        assert_eq!(
+            parse("x > 3"),
+            Predicate::gt(column_expr!("x"), Scalar::Long(3))
+        );
+        assert_eq!(
+            parse("3 <= x"),
+            Predicate::le(Scalar::Long(3), column_expr!("x"))
+        );
+        assert_eq!(
+            parse("s.y <> -1"),
+            Predicate::ne(column_expr!("s.y"), Scalar::Integer(-1))
+        );
+        assert_eq!(
+            parse("s.`weird.name` == 'it''s'"),
+            Predicate::eq(
+                Expression::column(["s", "weird.name"]),
+                Scalar::String("it's".into())
+            )
+        );
+        assert_eq!(
+            parse("d >= '1970-01-02'"),
+            Predicate::ge(column_expr!("d"), Scalar::Date(1))
+        );
+        assert_null_aware_eq!(
+            parse("x <=> NULL"),
+            Predicate::not(Predicate::distinct(
+                column_expr!("x"),
+                Scalar::Null(DataType::LONG),
+            )),
+        );
+    }
+
+    #[test]
+    fn test_parse_junctions() {
+        let schema = test_schema();
+        let parse = |sql| parse_predicate(sql, &schema).unwrap();
+
+        assert_eq!(
+            parse("x IS NOT NULL and (flag OR NOT x = 1)"),
+            Predicate::and(
+                Predicate::is_not_null(column_expr!("x")),
+                Predicate::or(
+                    Predicate::from_expr(column_expr!("flag")),
+                    Predicate::not(Predicate::eq(column_expr!("x"), Scalar::Long(1)))
+                )
+            )
+        );
+        assert_eq!(parse("TRUE"), Predicate::literal(true));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let schema = test_schema();
+        for sql in [
+            "missing > 1",
+            "x > ",
+            "x > 1 1",
+            "(x > 1",
+            "x > 'abc'",
+            "s.y.z = 1",
+            "x",
+            "'unterminated",
+            "x # 1",
+        ] {
+            assert!(parse_predicate(sql, &schema).is_err(), "{sql} should fail");
+        }
+
+        // errors about unknown columns name the column
+        let err = parse_predicate("s.missing > 1", &schema).unwrap_err();
+        assert!(matches!(err.inner(), Error::MissingColumn(_)));
+        assert_eq!(
+            err.context().and_then(|context| context.column.clone()),
+            Some(column_name!("s.missing"))
+        );
+    }
+
+    #[test]
+    fn test_parse_arithmetic() {
+        let schema = test_schema();
+
+        let (expr, data_type) = parse_expression("x + 2 * s.y", &schema, None).unwrap();
+        assert_eq!(
+            expr,
+            Expression::binary(
+                BinaryExpressionOp::Plus,
+                column_expr!("x"),
+                Expression::binary(
+                    BinaryExpressionOp::Multiply,
+                    Scalar::Integer(2),
+                    column_expr!("s.y")
+                )
+            )
+        );
+        assert_eq!(data_type, DataType::LONG);
+
+        let (expr, data_type) = parse_expression("10", &schema, Some(&DataType::LONG)).unwrap();
+        assert_eq!(expr, Expression::literal(Scalar::Long(10)));
+        assert_eq!(data_type, DataType::LONG);
+
+        assert_eq!(
+            parse_predicate("(x - 1) / 2 >= 3", &schema).unwrap(),
+            Predicate::ge(
+                Expression::binary(
+                    BinaryExpressionOp::Divide,
+                    Expression::binary(
+                        BinaryExpressionOp::Minus,
+                        column_expr!("x"),
+                        Scalar::Long(1)
+                    ),
+                    Scalar::Long(2)
+                ),
+                Scalar::Double(3.0)
+            )
+        );
+
+        let (expr, data_type) = parse_expression("s.y % 3", &schema, None).unwrap();
+        assert_eq!(
+            expr,
+            Expression::binary(
+                BinaryExpressionOp::Modulo,
+                column_expr!("s.y"),
+                Scalar::Integer(3)
+            )
+        );
+        assert_eq!(data_type, DataType::INTEGER);
+
+        assert!(parse_expression("x > 1", &schema, None).is_err());
+        assert!(parse_expression("year(d)", &schema, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        let schema = test_schema();
+
+        let (expr, data_type) = parse_expression("coalesce(s.y, 0)", &schema, None).unwrap();
+        assert_eq!(
+            expr,
+            Expression::coalesce([column_expr!("s.y"), Expression::literal(0)])
+        );
+        assert_eq!(data_type, DataType::INTEGER);
+
+        assert_eq!(
+            parse_predicate("NULLIF(x, 0) IS NULL", &schema).unwrap(),
+            Predicate::is_null(Expression::null_if(column_expr!("x"), Scalar::Long(0)))
+        );
+        assert_eq!(
+            parse_predicate("COALESCE(flag, FALSE)", &schema).unwrap(),
+            Predicate::from_expr(Expression::coalesce([
+                column_expr!("flag"),
+                Expression::literal(false)
+            ]))
+        );
+
+        let (expr, data_type) = parse_expression("CAST(d AS TIMESTAMP)", &schema, None).unwrap();
+        assert_eq!(
+            expr,
+            Expression::cast(column_expr!("d"), DataType::TIMESTAMP)
+        );
+        assert_eq!(data_type, DataType::TIMESTAMP);
+
+        let (expr, data_type) =
+            parse_expression("try_cast(x + 1 AS decimal(12, 2))", &schema, None).unwrap();
+        let decimal = DataType::decimal(12, 2).unwrap();
+        assert_eq!(
+            expr,
+            Expression::try_cast(
+                Expression::binary(BinaryExpressionOp::Plus, column_expr!("x"), Scalar::Long(1)),
+                decimal.clone()
+            )
+        );
+        assert_eq!(data_type, decimal);
+
+        assert_eq!(
+            parse_predicate("CAST(s.y AS BIGINT) = x", &schema).unwrap(),
+            Predicate::eq(
+                Expression::cast(column_expr!("s.y"), DataType::LONG),
+                column_expr!("x")
+            )
+        );
+
+        for sql in [
+            "NULLIF(x, 1, 2)",
+            "NULLIF(x)",
+            "COALESCE()",
+            "COALESCE(x,)",
+            "year(d)",
+            "CAST(x)",
+            "CAST(x AS)",
+            "CAST(x AS ARRAY<INT>)",
+            "CAST(x AS DECIMAL(10, 2, 1))",
+            "CAST(x AS DECIMAL(100, 0))",
+        ] {
+            assert!(
+                parse_expression(sql, &schema, None).is_err(),
+                "{sql} should fail"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_subscripts() {
+        let schema = test_schema();
+
+        assert_eq!(
+            parse_predicate("tags['env'] = 'prod'", &schema).unwrap(),
+            Predicate::eq(
+                Expression::map_value(column_expr!("tags"), "env"),
+                Scalar::String("prod".into())
+            )
+        );
+        let (expr, data_type) = parse_expression("ids[1] + 1", &schema, None).unwrap();
+        assert_eq!(
+            expr,
+            Expression::binary(
+                BinaryExpressionOp::Plus,
+                Expression::array_element(column_expr!("ids"), 1),
+                Scalar::Long(1)
+            )
+        );
+        assert_eq!(data_type, DataType::LONG);
+
+        for sql in [
+            "x[0] = 1",
+            "ids['a'] = 1",
+            "ids[-1] = 1",
+            "tags[x] = 'a'",
+            "ids[0 = 1",
+        ] {
+            assert!(parse_predicate(sql, &schema).is_err(), "{sql} should fail");
+        }
+    }
+
+    #[test]
+    fn test_parse_conditionals() {
+        let schema = test_schema();
+
+        let (expr, data_type) = parse_expression(
+            "CASE WHEN x > 10 THEN 1 WHEN flag THEN x ELSE NULL END",
+            &schema,
+            None,
+        )
+        .unwrap();
+        assert_null_aware_eq!(
+            expr,
+            Expression::case(
+                [
+                    (
+                        Predicate::gt(column_expr!("x"), Scalar::Long(10)),
+                        Expression::literal(Scalar::Long(1)),
+                    ),
+                    (
+                        Predicate::from_expr(column_expr!("flag")),
+                        column_expr!("x"),
+                    ),
+                ],
+                Some(Expression::literal(Scalar::Null(DataType::LONG))),
+            ),
+        );
+        assert_eq!(data_type, DataType::LONG);
+
+        let (expr, data_type) =
+            parse_expression("if(s.y IS NULL, 'a', 'b')", &schema, None).unwrap();
+        assert_eq!(
+            expr,
+            Expression::if_then_else(
+                Predicate::is_null(column_expr!("s.y")),
+                Scalar::String("a".into()),
+                Scalar::String("b".into())
+            )
+        );
+        assert_eq!(data_type, DataType::STRING);
+
+        assert_eq!(
+            parse_predicate("CASE WHEN flag THEN x END >= 3", &schema).unwrap(),
+            Predicate::ge(
+                Expression::case(
+                    [(
+                        Predicate::from_expr(column_expr!("flag")),
+                        column_expr!("x")
+                    )],
+                    None
+                ),
+                Scalar::Long(3)
+            )
+        );
+
+        for sql in [
+            "CASE ELSE 1 END",
+            "CASE WHEN flag THEN 1",
+            "CASE WHEN flag 1 END",
+            "IF(flag, 1)",
+            "CASE WHEN flag THEN 1 ELSE 2 ELSE 3 END",
+        ] {
+            assert!(
+                parse_expression(sql, &schema, None).is_err(),
+                "{sql} should fail"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_in_and_like() {
+        let schema = test_schema();
+        let parse = |sql| parse_predicate(sql, &schema).unwrap();
+
+        let values = ArrayData::try_new(ArrayType::new(DataType::LONG, false), [1i64, 2]).unwrap();
+        assert_null_aware_eq!(
+            parse("x IN (1, 2)"),
+            Predicate::in_list(column_expr!("x"), values),
+        );
+        let values = ArrayData::try_new(
+            ArrayType::new(DataType::LONG, true),
+            [Scalar::Long(1), Scalar::Null(DataType::LONG)],
+        )
+        .unwrap();
+        assert_null_aware_eq!(
+            parse("x NOT IN (1, NULL)"),
+            Predicate::not_in_list(column_expr!("x"), values),
+        );
+        assert_eq!(
+            parse("s.`weird.name` LIKE 'a%'"),
+            Predicate::like(Expression::column(["s", "weird.name"]), "a%")
+        );
+        assert_eq!(
+            parse("s.`weird.name` not ilike 'A_'"),
+            Predicate::not(Predicate::ilike(
+                Expression::column(["s", "weird.name"]),
+                "A_"
+            ))
+        );
+
+        for sql in [
+            "x IN (s.y)",
+            "x IN ()",
+            "x IN (1, 'abc')",
+            "x NOT 1",
+            "s.`weird.name` LIKE s.`weird.name`",
+        ] {
+            assert!(parse_predicate(sql, &schema).is_err(), "{sql} should fail");
+        }
+    }
+
+    #[test]
+    fn test_parse_without_schema() {
+        let parse = |sql| parse_predicate_without_schema(sql).unwrap();
+
+        let values =
+            ArrayData::try_new(ArrayType::new(DataType::STRING, false), ["x", "y"]).unwrap();
+        assert_null_aware_eq!(
+            parse("a > 5 AND b IN ('x','y')"),
+            Predicate::and(
+                Predicate::gt(column_expr!("a"), Scalar::Integer(5)),
+                Predicate::in_list(column_expr!("b"), values),
+            ),
+        );
+        assert_eq!(
+            parse("a.b + 1 > 3000000000"),
+            Predicate::gt(
+                column_expr!("a.b") + Expression::literal(1),
+                Scalar::Long(3000000000)
+            )
+        );
+        assert_eq!(
+            parse("flag OR tags['env'] = 'prod'"),
+            Predicate::or(
+                Predicate::from_expr(column_expr!("flag")),
+                Predicate::eq(
+                    Expression::map_value(column_expr!("tags"), "env"),
+                    Scalar::from("prod")
+                )
+            )
+        );
+        assert_eq!(
+            parse("coalesce(ids[0], 0) = 0"),
+            Predicate::eq(
+                Expression::coalesce([
+                    Expression::array_element(column_expr!("ids"), 0),
+                    Expression::literal(0)
+                ]),
+                Scalar::Integer(0)
+            )
+        );
+
+        assert!(parse_predicate_without_schema("a >").is_err());
+    }
+}
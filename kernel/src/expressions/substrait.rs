@@ -0,0 +1,1139 @@
+//! Conversion between kernel [`Expression`]s and [`Predicate`]s and [Substrait] expressions,
+//! enabled by the `substrait` feature. This allows engines that consume (or produce) Substrait to
+//! exchange filters with kernel without an engine-specific translation layer.
+//!
+//! Substrait refers to columns by ordinal rather than by name, so every conversion takes the schema
+//! that column references resolve against. Substrait functions are referenced by anchors that a
+//! plan declares in its extensions; [`SubstraitExtensions`] tracks those declarations, using the
+//! functions of the standard Substrait extensions.
+//!
+//! Not all expressions can be converted in either direction. Kernel-specific expressions (such as
+//! [`Transform`], opaque expressions, or `NULLIF`) have no Substrait equivalent, and Substrait
+//! struct types cannot be converted back to kernel, because they do not carry field names.
+//!
+//! [Substrait]: https://substrait.io
+//! [`Transform`]: crate::expressions::Transform
+
+use itertools::Itertools;
+use substrait::proto;
+use substrait::proto::expression::field_reference::{ReferenceType, RootReference, RootType};
+use substrait::proto::expression::literal::LiteralType;
+use substrait::proto::expression::{
+    cast, if_then, literal, nested, reference_segment, FieldReference, IfThen, Literal, Nested,
+    ReferenceSegment, RexType, ScalarFunction, SingularOrList,
+};
+use substrait::proto::extensions::simple_extension_declaration::{ExtensionFunction, MappingType};
+use substrait::proto::extensions::{SimpleExtensionDeclaration, SimpleExtensionUri};
+use substrait::proto::function_argument::ArgType;
+use substrait::proto::r#type::{Kind, Nullability};
+use substrait::proto::{r#type, FunctionArgument, FunctionOption};
+
+use crate::expressions::{
+    ArrayData, BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp,
+    CaseExpression, CastExpression, CastOverflowPolicy, ColumnName, ElementAccessor,
+    ElementExpression, Expression, JunctionPredicate, JunctionPredicateOp, MapData, Predicate,
+    Scalar, UnaryPredicate, UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
+};
+use crate::schema::{ArrayType, DataType, MapType, PrimitiveType, StructType};
+use crate::{DeltaResult, Error};
+
+const FUNCTIONS_ARITHMETIC: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_arithmetic.yaml";
+const FUNCTIONS_BOOLEAN: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_boolean.yaml";
+const FUNCTIONS_COMPARISON: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_comparison.yaml";
+const FUNCTIONS_STRING: &str =
+    "https://github.com/substrait-io/substrait/blob/main/extensions/functions_string.yaml";
+
+// Kernel timestamps have microsecond precision
+const TIMESTAMP_PRECISION: i32 = 6;
+
+/// The extension functions referenced by Substrait expressions. When converting to Substrait, each
+/// function is declared the first time it is used, and the engine is responsible to include
+/// [`Self::uris`] and [`Self::declarations`] in the plan it produces. When converting from
+/// Substrait, the extensions come from the plan the expressions belong to.
+#[derive(Debug, Clone, Default)]
+pub struct SubstraitExtensions {
+    uris: Vec<SimpleExtensionUri>,
+    functions: Vec<ExtensionFunction>,
+}
+
+impl SubstraitExtensions {
+    /// Creates a new, empty set of extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collects the extension functions a Substrait plan declares. Other kinds of extensions
+    /// (types and type variations) are ignored.
+    pub fn from_declarations<'a>(
+        uris: impl IntoIterator<Item = &'a SimpleExtensionUri>,
+        declarations: impl IntoIterator<Item = &'a SimpleExtensionDeclaration>,
+    ) -> Self {
+        let functions = declarations
+            .into_iter()
+            .filter_map(|declaration| match &declaration.mapping_type {
+                Some(MappingType::ExtensionFunction(function)) => Some(function.clone()),
+                _ => None,
+            })
+            .collect();
+        Self {
+            uris: uris.into_iter().cloned().collect(),
+            functions,
+        }
+    }
+
+    /// The extension URIs that the declared functions belong to.
+    pub fn uris(&self) -> &[SimpleExtensionUri] {
+        &self.uris
+    }
+
+    /// The declarations of all functions referenced by converted expressions.
+    pub fn declarations(&self) -> Vec<SimpleExtensionDeclaration> {
+        self.functions
+            .iter()
+            .map(|function| SimpleExtensionDeclaration {
+                mapping_type: Some(MappingType::ExtensionFunction(function.clone())),
+            })
+            .collect()
+    }
+
+    /// Returns the anchor of the function `name` from the extension `uri`, declaring the function
+    /// first if needed.
+    fn function_anchor(&mut self, uri: &str, name: &str) -> u32 {
+        let uri_anchor = match self.uris.iter().find(|u| u.uri == uri) {
+            Some(u) => u.extension_uri_anchor,
+            None => {
+                let anchor = self.uris.iter().map(|u| u.extension_uri_anchor).max();
+                let anchor = anchor.map_or(1, |anchor| anchor + 1);
+                self.uris.push(SimpleExtensionUri {
+                    extension_uri_anchor: anchor,
+                    uri: uri.to_string(),
+                });
+                anchor
+            }
+        };
+        let existing = self
+            .functions
+            .iter()
+            .find(|f| f.extension_uri_reference == uri_anchor && f.name == name);
+        if let Some(function) = existing {
+            return function.function_anchor;
+        }
+        let anchor = self.functions.iter().map(|f| f.function_anchor).max();
+        let anchor = anchor.map_or(1, |anchor| anchor + 1);
+        self.functions.push(ExtensionFunction {
+            extension_uri_reference: uri_anchor,
+            function_anchor: anchor,
+            name: name.to_string(),
+            ..Default::default()
+        });
+        anchor
+    }
+
+    /// Returns the name of the function with the given anchor, without any signature suffix, e.g.
+    /// `add` for a function declared as `add:i64_i64`.
+    fn function_name(&self, anchor: u32) -> DeltaResult<&str> {
+        let function = self.functions.iter().find(|f| f.function_anchor == anchor);
+        let function = function.ok_or_else(|| {
+            Error::invalid_expression(format!("Undeclared Substrait function anchor {anchor}"))
+        })?;
+        Ok(function.name.split(':').next().unwrap_or_default())
+    }
+}
+
+/// Converts a kernel expression to a Substrait expression. Column references are resolved against
+/// `schema`, and any functions used are declared in `extensions`.
+pub fn expression_to_substrait(
+    expr: &Expression,
+    schema: &StructType,
+    extensions: &mut SubstraitExtensions,
+) -> DeltaResult<proto::Expression> {
+    ToSubstrait { schema, extensions }.expression(expr)
+}
+
+/// Converts a kernel predicate to a (boolean) Substrait expression, see
+/// [`expression_to_substrait`].
+pub fn predicate_to_substrait(
+    pred: &Predicate,
+    schema: &StructType,
+    extensions: &mut SubstraitExtensions,
+) -> DeltaResult<proto::Expression> {
+    ToSubstrait { schema, extensions }.predicate(pred)
+}
+
+/// Converts a Substrait expression to a kernel expression. Field references are resolved against
+/// `schema`, and function references against `extensions`.
+pub fn expression_from_substrait(
+    expr: &proto::Expression,
+    schema: &StructType,
+    extensions: &SubstraitExtensions,
+) -> DeltaResult<Expression> {
+    FromSubstrait { schema, extensions }.expression(expr)
+}
+
+/// Converts a (boolean) Substrait expression to a kernel predicate, see
+/// [`expression_from_substrait`].
+pub fn predicate_from_substrait(
+    expr: &proto::Expression,
+    schema: &StructType,
+    extensions: &SubstraitExtensions,
+) -> DeltaResult<Predicate> {
+    FromSubstrait { schema, extensions }.predicate(expr)
+}
+
+fn rex(rex_type: RexType) -> proto::Expression {
+    proto::Expression {
+        rex_type: Some(rex_type),
+    }
+}
+
+fn unsupported_expression(expr: impl std::fmt::Display) -> Error {
+    Error::unsupported(format!("Cannot convert {expr} to Substrait"))
+}
+
+struct ToSubstrait<'a> {
+    schema: &'a StructType,
+    extensions: &'a mut SubstraitExtensions,
+}
+
+impl ToSubstrait<'_> {
+    fn expression(&mut self, expr: &Expression) -> DeltaResult<proto::Expression> {
+        let result = match expr {
+            Expression::Literal(value) => rex(RexType::Literal(literal_to_substrait(value)?)),
+            Expression::Column(name) => self.column(name)?,
+            Expression::Predicate(pred) => self.predicate(pred)?,
+            Expression::Struct(exprs) => {
+                let fields = exprs
+                    .iter()
+                    .map(|expr| self.expression(expr))
+                    .try_collect()?;
+                rex(RexType::Nested(Nested {
+                    nested_type: Some(nested::NestedType::Struct(nested::Struct { fields })),
+                    ..Default::default()
+                }))
+            }
+            Expression::Binary(BinaryExpression { op, left, right }) => {
+                let name = match op {
+                    BinaryExpressionOp::Plus => "add",
+                    BinaryExpressionOp::Minus => "subtract",
+                    BinaryExpressionOp::Multiply => "multiply",
+                    BinaryExpressionOp::Divide => "divide",
+                    BinaryExpressionOp::Modulo => "modulus",
+                };
+                let args = [self.expression(left)?, self.expression(right)?];
+                self.call(FUNCTIONS_ARITHMETIC, name, args, vec![])
+            }
+            Expression::Variadic(VariadicExpression {
+                op: VariadicExpressionOp::Coalesce,
+                exprs,
+            }) => {
+                let args: Vec<_> = exprs
+                    .iter()
+                    .map(|expr| self.expression(expr))
+                    .try_collect()?;
+                self.call(FUNCTIONS_COMPARISON, "coalesce", args, vec![])
+            }
+            Expression::Case(CaseExpression {
+                branches,
+                else_expr,
+            }) => {
+                let ifs = branches
+                    .iter()
+                    .map(|(when, then)| {
+                        Ok::<_, Error>(if_then::IfClause {
+                            r#if: Some(self.predicate(when)?),
+                            then: Some(self.expression(then)?),
+                        })
+                    })
+                    .try_collect()?;
+                let r#else = match else_expr {
+                    Some(else_expr) => Some(Box::new(self.expression(else_expr)?)),
+                    None => None,
+                };
+                rex(RexType::IfThen(Box::new(IfThen { ifs, r#else })))
+            }
+            Expression::Cast(CastExpression {
+                expr,
+                to,
+                on_overflow,
+            }) => {
+                let failure_behavior = match on_overflow {
+                    CastOverflowPolicy::Error => cast::FailureBehavior::ThrowException,
+                    CastOverflowPolicy::Null => cast::FailureBehavior::ReturnNull,
+                };
+                rex(RexType::Cast(Box::new(proto::expression::Cast {
+                    r#type: Some(type_to_substrait(to, true)?),
+                    input: Some(Box::new(self.expression(expr)?)),
+                    failure_behavior: failure_behavior as i32,
+                })))
+            }
+            Expression::Element(ElementExpression { expr, accessor }) => {
+                let reference_type = match accessor {
+                    ElementAccessor::MapValue(key) => reference_segment::ReferenceType::MapKey(
+                        Box::new(reference_segment::MapKey {
+                            map_key: Some(literal_to_substrait(key)?),
+                            child: None,
+                        }),
+                    ),
+                    ElementAccessor::ArrayElement(index) => {
+                        reference_segment::ReferenceType::ListElement(Box::new(
+                            reference_segment::ListElement {
+                                offset: to_i32(*index)?,
+                                child: None,
+                            },
+                        ))
+                    }
+                    ElementAccessor::StructField(ordinal) => {
+                        reference_segment::ReferenceType::StructField(Box::new(
+                            reference_segment::StructField {
+                                field: to_i32(*ordinal)?,
+                                child: None,
+                            },
+                        ))
+                    }
+                };
+                let segment = ReferenceSegment {
+                    reference_type: Some(reference_type),
+                };
+                let root = RootType::Expression(Box::new(self.expression(expr)?));
+                field_reference(segment, root)
+            }
+            Expression::Variadic(VariadicExpression {
+                op: VariadicExpressionOp::NullIf,
+                ..
+            })
+            | Expression::Unary(_)
+            | Expression::Transform(_)
+            | Expression::Opaque(_)
+            | Expression::Unknown(_) => return Err(unsupported_expression(expr)),
+        };
+        Ok(result)
+    }
+
+    fn predicate(&mut self, pred: &Predicate) -> DeltaResult<proto::Expression> {
+        let result = match pred {
+            Predicate::BooleanExpression(expr) => self.expression(expr)?,
+            Predicate::Not(pred) => {
+                let arg = self.predicate(pred)?;
+                self.call(FUNCTIONS_BOOLEAN, "not", [arg], vec![])
+            }
+            Predicate::Unary(UnaryPredicate {
+                op: UnaryPredicateOp::IsNull,
+                expr,
+            }) => {
+                let arg = self.expression(expr)?;
+                self.call(FUNCTIONS_COMPARISON, "is_null", [arg], vec![])
+            }
+            Predicate::Binary(BinaryPredicate {
+                op: BinaryPredicateOp::In,
+                left,
+                right,
+            }) => {
+                let Expression::Literal(Scalar::Array(values)) = right.as_ref() else {
+                    return Err(unsupported_expression(pred));
+                };
+                #[allow(deprecated)]
+                let options = values
+                    .array_elements()
+                    .iter()
+                    .map(|value| {
+                        Ok::<_, Error>(rex(RexType::Literal(literal_to_substrait(value)?)))
+                    })
+                    .try_collect()?;
+                rex(RexType::SingularOrList(Box::new(SingularOrList {
+                    value: Some(Box::new(self.expression(left)?)),
+                    options,
+                })))
+            }
+            Predicate::Binary(BinaryPredicate { op, left, right }) => {
+                let (uri, name, options) = match op {
+                    BinaryPredicateOp::LessThan => (FUNCTIONS_COMPARISON, "lt", vec![]),
+                    BinaryPredicateOp::GreaterThan => (FUNCTIONS_COMPARISON, "gt", vec![]),
+                    BinaryPredicateOp::Equal => (FUNCTIONS_COMPARISON, "equal", vec![]),
+                    BinaryPredicateOp::Distinct => {
+                        (FUNCTIONS_COMPARISON, "is_distinct_from", vec![])
+                    }
+                    BinaryPredicateOp::Like => (FUNCTIONS_STRING, "like", vec![]),
+                    BinaryPredicateOp::ILike => {
+                        let option = FunctionOption {
+                            name: "case_sensitivity".to_string(),
+                            preference: vec!["CASE_INSENSITIVE".to_string()],
+                        };
+                        (FUNCTIONS_STRING, "like", vec![option])
+                    }
+                    BinaryPredicateOp::In => return Err(unsupported_expression(pred)),
+                };
+                let args = [self.expression(left)?, self.expression(right)?];
+                self.call(uri, name, args, options)
+            }
+            Predicate::Junction(JunctionPredicate { op, preds }) => {
+                let name = match op {
+                    JunctionPredicateOp::And => "and",
+                    JunctionPredicateOp::Or => "or",
+                };
+                let args: Vec<_> = preds
+                    .iter()
+                    .map(|pred| self.predicate(pred))
+                    .try_collect()?;
+                self.call(FUNCTIONS_BOOLEAN, name, args, vec![])
+            }
+            Predicate::Opaque(_) | Predicate::Unknown(_) => {
+                return Err(unsupported_expression(pred))
+            }
+        };
+        Ok(result)
+    }
+
+    // Substrait columns are a chain of struct field ordinals, starting from the input schema
+    fn column(&self, name: &ColumnName) -> DeltaResult<proto::Expression> {
+        let not_found = || Error::missing_column(format!("Column {name} not found in schema"));
+        let mut ordinals = Vec::with_capacity(name.path().len());
+        let mut schema = Some(self.schema);
+        for field_name in name.path() {
+            let (ordinal, field) = schema
+                .and_then(|schema| schema.field_with_index(field_name))
+                .ok_or_else(not_found)?;
+            ordinals.push(to_i32(ordinal)?);
+            schema = match field.data_type() {
+                DataType::Struct(inner) => Some(inner),
+                _ => None,
+            };
+        }
+        let segment = ordinals.into_iter().rev().fold(None, |child, field| {
+            let field = reference_segment::StructField {
+                field,
+                child: child.map(Box::new),
+            };
+            Some(ReferenceSegment {
+                reference_type: Some(reference_segment::ReferenceType::StructField(Box::new(
+                    field,
+                ))),
+            })
+        });
+        let segment = segment.ok_or_else(not_found)?;
+        Ok(field_reference(
+            segment,
+            RootType::RootReference(RootReference {}),
+        ))
+    }
+
+    fn call(
+        &mut self,
+        uri: &str,
+        name: &str,
+        args: impl IntoIterator<Item = proto::Expression>,
+        options: Vec<FunctionOption>,
+    ) -> proto::Expression {
+        let arguments = args
+            .into_iter()
+            .map(|arg| FunctionArgument {
+                arg_type: Some(ArgType::Value(arg)),
+            })
+            .collect();
+        rex(RexType::ScalarFunction(ScalarFunction {
+            function_reference: self.extensions.function_anchor(uri, name),
+            arguments,
+            options,
+            ..Default::default()
+        }))
+    }
+}
+
+fn field_reference(segment: ReferenceSegment, root: RootType) -> proto::Expression {
+    rex(RexType::Selection(Box::new(FieldReference {
+        reference_type: Some(ReferenceType::DirectReference(segment)),
+        root_type: Some(root),
+    })))
+}
+
+fn to_i32(value: usize) -> DeltaResult<i32> {
+    value
+        .try_into()
+        .map_err(|_| Error::invalid_expression(format!("Ordinal {value} is too large")))
+}
+
+fn literal_to_substrait(value: &Scalar) -> DeltaResult<Literal> {
+    let literal_type = match value {
+        Scalar::Integer(v) => LiteralType::I32(*v),
+        Scalar::Long(v) => LiteralType::I64(*v),
+        Scalar::Short(v) => LiteralType::I16((*v).into()),
+        Scalar::Byte(v) => LiteralType::I8((*v).into()),
+        Scalar::Float(v) => LiteralType::Fp32(*v),
+        Scalar::Double(v) => LiteralType::Fp64(*v),
+        Scalar::String(s) => LiteralType::String(s.clone()),
+        Scalar::Boolean(b) => LiteralType::Boolean(*b),
+        Scalar::Timestamp(micros) => {
+            LiteralType::PrecisionTimestampTz(literal::PrecisionTimestamp {
+                precision: TIMESTAMP_PRECISION,
+                value: *micros,
+            })
+        }
+        Scalar::TimestampNtz(micros) => {
+            LiteralType::PrecisionTimestamp(literal::PrecisionTimestamp {
+                precision: TIMESTAMP_PRECISION,
+                value: *micros,
+            })
+        }
+        Scalar::Date(days) => LiteralType::Date(*days),
+        Scalar::Binary(bytes) => LiteralType::Binary(bytes.clone()),
+        Scalar::Decimal(d) => LiteralType::Decimal(literal::Decimal {
+            value: d.bits().to_le_bytes().to_vec(),
+            precision: d.precision().into(),
+            scale: d.scale().into(),
+        }),
+        Scalar::Null(data_type) => LiteralType::Null(type_to_substrait(data_type, true)?),
+        Scalar::Struct(data) => LiteralType::Struct(literal::Struct {
+            fields: data
+                .values()
+                .iter()
+                .map(literal_to_substrait)
+                .try_collect()?,
+        }),
+        Scalar::Array(data) => {
+            #[allow(deprecated)]
+            let elements = data.array_elements();
+            if elements.is_empty() {
+                let element_type = data.array_type().element_type();
+                LiteralType::EmptyList(r#type::List {
+                    r#type: Some(Box::new(type_to_substrait(
+                        element_type,
+                        data.array_type().contains_null(),
+                    )?)),
+                    ..Default::default()
+                })
+            } else {
+                LiteralType::List(literal::List {
+                    values: elements.iter().map(literal_to_substrait).try_collect()?,
+                })
+            }
+        }
+        Scalar::Map(data) => {
+            let map_type = data.map_type();
+            if data.pairs().is_empty() {
+                LiteralType::EmptyMap(r#type::Map {
+                    key: Some(Box::new(type_to_substrait(map_type.key_type(), false)?)),
+                    value: Some(Box::new(type_to_substrait(
+                        map_type.value_type(),
+                        map_type.value_contains_null(),
+                    )?)),
+                    ..Default::default()
+                })
+            } else {
+                let key_values = data
+                    .pairs()
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok::<_, Error>(literal::map::KeyValue {
+                            key: Some(literal_to_substrait(key)?),
+                            value: Some(literal_to_substrait(value)?),
+                        })
+                    })
+                    .try_collect()?;
+                LiteralType::Map(literal::Map { key_values })
+            }
+        }
+    };
+    Ok(Literal {
+        nullable: value.is_null(),
+        literal_type: Some(literal_type),
+        ..Default::default()
+    })
+}
+
+fn type_to_substrait(data_type: &DataType, nullable: bool) -> DeltaResult<proto::Type> {
+    let nullability = match nullable {
+        true => Nullability::Nullable,
+        false => Nullability::Required,
+    } as i32;
+    let kind = match data_type {
+        DataType::Primitive(ptype) => match ptype {
+            PrimitiveType::Byte => Kind::I8(r#type::I8 {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Short => Kind::I16(r#type::I16 {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Integer => Kind::I32(r#type::I32 {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Long => Kind::I64(r#type::I64 {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Float => Kind::Fp32(r#type::Fp32 {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Double => Kind::Fp64(r#type::Fp64 {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::String => Kind::String(r#type::String {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Boolean => Kind::Bool(r#type::Boolean {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Binary => Kind::Binary(r#type::Binary {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Date => Kind::Date(r#type::Date {
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Timestamp => Kind::PrecisionTimestampTz(r#type::PrecisionTimestampTz {
+                precision: TIMESTAMP_PRECISION,
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::TimestampNtz => Kind::PrecisionTimestamp(r#type::PrecisionTimestamp {
+                precision: TIMESTAMP_PRECISION,
+                nullability,
+                ..Default::default()
+            }),
+            PrimitiveType::Decimal(dtype) => Kind::Decimal(r#type::Decimal {
+                precision: dtype.precision().into(),
+                scale: dtype.scale().into(),
+                nullability,
+                ..Default::default()
+            }),
+        },
+        DataType::Struct(struct_type) => Kind::Struct(r#type::Struct {
+            types: struct_type
+                .fields()
+                .map(|field| type_to_substrait(field.data_type(), field.is_nullable()))
+                .try_collect()?,
+            nullability,
+            ..Default::default()
+        }),
+        DataType::Array(array_type) => Kind::List(Box::new(r#type::List {
+            r#type: Some(Box::new(type_to_substrait(
+                array_type.element_type(),
+                array_type.contains_null(),
+            )?)),
+            nullability,
+            ..Default::default()
+        })),
+        DataType::Map(map_type) => Kind::Map(Box::new(r#type::Map {
+            key: Some(Box::new(type_to_substrait(map_type.key_type(), false)?)),
+            value: Some(Box::new(type_to_substrait(
+                map_type.value_type(),
+                map_type.value_contains_null(),
+            )?)),
+            nullability,
+            ..Default::default()
+        })),
+        DataType::Variant(_) => return Err(unsupported_expression(data_type)),
+    };
+    Ok(proto::Type { kind: Some(kind) })
+}
+
+fn type_from_substrait(substrait_type: &proto::Type) -> DeltaResult<DataType> {
+    let unsupported = || {
+        Error::unsupported(format!(
+            "Cannot convert Substrait type {substrait_type:?} to kernel"
+        ))
+    };
+    let data_type = match substrait_type.kind.as_ref().ok_or_else(unsupported)? {
+        Kind::Bool(_) => DataType::BOOLEAN,
+        Kind::I8(_) => DataType::BYTE,
+        Kind::I16(_) => DataType::SHORT,
+        Kind::I32(_) => DataType::INTEGER,
+        Kind::I64(_) => DataType::LONG,
+        Kind::Fp32(_) => DataType::FLOAT,
+        Kind::Fp64(_) => DataType::DOUBLE,
+        Kind::String(_) => DataType::STRING,
+        Kind::Binary(_) => DataType::BINARY,
+        Kind::Date(_) => DataType::DATE,
+        Kind::PrecisionTimestampTz(t) if t.precision == TIMESTAMP_PRECISION => DataType::TIMESTAMP,
+        Kind::PrecisionTimestamp(t) if t.precision == TIMESTAMP_PRECISION => {
+            DataType::TIMESTAMP_NTZ
+        }
+        Kind::Decimal(d) => {
+            let precision = d.precision.try_into().map_err(|_| unsupported())?;
+            let scale = d.scale.try_into().map_err(|_| unsupported())?;
+            DataType::decimal(precision, scale)?
+        }
+        Kind::List(list) => {
+            let element_type = list.r#type.as_deref().ok_or_else(unsupported)?;
+            let contains_null = is_nullable(element_type);
+            ArrayType::new(type_from_substrait(element_type)?, contains_null).into()
+        }
+        Kind::Map(map) => {
+            let key_type = map.key.as_deref().ok_or_else(unsupported)?;
+            let value_type = map.value.as_deref().ok_or_else(unsupported)?;
+            MapType::new(
+                type_from_substrait(key_type)?,
+                type_from_substrait(value_type)?,
+                is_nullable(value_type),
+            )
+            .into()
+        }
+        // Substrait struct types have no field names
+        _ => return Err(unsupported()),
+    };
+    Ok(data_type)
+}
+
+fn is_nullable(substrait_type: &proto::Type) -> bool {
+    let nullability = match &substrait_type.kind {
+        Some(Kind::Bool(t)) => t.nullability,
+        Some(Kind::I8(t)) => t.nullability,
+        Some(Kind::I16(t)) => t.nullability,
+        Some(Kind::I32(t)) => t.nullability,
+        Some(Kind::I64(t)) => t.nullability,
+        Some(Kind::Fp32(t)) => t.nullability,
+        Some(Kind::Fp64(t)) => t.nullability,
+        Some(Kind::String(t)) => t.nullability,
+        Some(Kind::Binary(t)) => t.nullability,
+        Some(Kind::Date(t)) => t.nullability,
+        Some(Kind::PrecisionTimestamp(t)) => t.nullability,
+        Some(Kind::PrecisionTimestampTz(t)) => t.nullability,
+        Some(Kind::Decimal(t)) => t.nullability,
+        Some(Kind::List(t)) => t.nullability,
+        Some(Kind::Map(t)) => t.nullability,
+        Some(Kind::Struct(t)) => t.nullability,
+        _ => return true,
+    };
+    nullability != Nullability::Required as i32
+}
+
+fn literal_from_substrait(literal: &Literal) -> DeltaResult<Scalar> {
+    let invalid = || Error::invalid_expression(format!("Invalid Substrait literal {literal:?}"));
+    let literal_type = literal.literal_type.as_ref().ok_or_else(invalid)?;
+    let value = match literal_type {
+        LiteralType::Boolean(b) => Scalar::Boolean(*b),
+        LiteralType::I8(v) => Scalar::Byte((*v).try_into().map_err(|_| invalid())?),
+        LiteralType::I16(v) => Scalar::Short((*v).try_into().map_err(|_| invalid())?),
+        LiteralType::I32(v) => Scalar::Integer(*v),
+        LiteralType::I64(v) => Scalar::Long(*v),
+        LiteralType::Fp32(v) => Scalar::Float(*v),
+        LiteralType::Fp64(v) => Scalar::Double(*v),
+        LiteralType::String(s) => Scalar::String(s.clone()),
+        LiteralType::Binary(bytes) => Scalar::Binary(bytes.clone()),
+        LiteralType::Date(days) => Scalar::Date(*days),
+        LiteralType::PrecisionTimestampTz(t) if t.precision == TIMESTAMP_PRECISION => {
+            Scalar::Timestamp(t.value)
+        }
+        LiteralType::PrecisionTimestamp(t) if t.precision == TIMESTAMP_PRECISION => {
+            Scalar::TimestampNtz(t.value)
+        }
+        LiteralType::Decimal(d) => {
+            let bits: [u8; 16] = d.value.as_slice().try_into().map_err(|_| invalid())?;
+            let precision = d.precision.try_into().map_err(|_| invalid())?;
+            let scale = d.scale.try_into().map_err(|_| invalid())?;
+            Scalar::decimal(i128::from_le_bytes(bits), precision, scale)?
+        }
+        LiteralType::Null(data_type) => Scalar::Null(type_from_substrait(data_type)?),
+        LiteralType::List(list) => {
+            let values: Vec<_> = list
+                .values
+                .iter()
+                .map(literal_from_substrait)
+                .try_collect()?;
+            let element_type = values.first().ok_or_else(invalid)?.data_type();
+            let contains_null = values.iter().any(Scalar::is_null);
+            let array_type = ArrayType::new(element_type, contains_null);
+            Scalar::Array(ArrayData::try_new(array_type, values)?)
+        }
+        LiteralType::EmptyList(list) => {
+            let element_type = list.r#type.as_deref().ok_or_else(invalid)?;
+            let contains_null = is_nullable(element_type);
+            let array_type = ArrayType::new(type_from_substrait(element_type)?, contains_null);
+            Scalar::Array(ArrayData::try_new(array_type, Vec::<Scalar>::new())?)
+        }
+        LiteralType::Map(map) => {
+            let pairs: Vec<_> = map
+                .key_values
+                .iter()
+                .map(|kv| {
+                    let key = kv.key.as_ref().ok_or_else(invalid)?;
+                    let value = kv.value.as_ref().ok_or_else(invalid)?;
+                    Ok::<_, Error>((literal_from_substrait(key)?, literal_from_substrait(value)?))
+                })
+                .try_collect()?;
+            let (key, value) = pairs.first().ok_or_else(invalid)?;
+            let value_contains_null = pairs.iter().any(|(_, value)| value.is_null());
+            let map_type = MapType::new(key.data_type(), value.data_type(), value_contains_null);
+            Scalar::Map(MapData::try_new(map_type, pairs)?)
+        }
+        LiteralType::EmptyMap(map) => {
+            let key_type = map.key.as_deref().ok_or_else(invalid)?;
+            let value_type = map.value.as_deref().ok_or_else(invalid)?;
+            let map_type = MapType::new(
+                type_from_substrait(key_type)?,
+                type_from_substrait(value_type)?,
+                is_nullable(value_type),
+            );
+            Scalar::Map(MapData::try_new(map_type, Vec::<(Scalar, Scalar)>::new())?)
+        }
+        _ => {
+            return Err(Error::unsupported(format!(
+                "Cannot convert Substrait literal {literal:?} to kernel"
+            )))
+        }
+    };
+    Ok(value)
+}
+
+struct FromSubstrait<'a> {
+    schema: &'a StructType,
+    extensions: &'a SubstraitExtensions,
+}
+
+impl FromSubstrait<'_> {
+    fn expression(&self, expr: &proto::Expression) -> DeltaResult<Expression> {
+        let unsupported = || {
+            Error::unsupported(format!(
+                "Cannot convert Substrait expression {expr:?} to kernel"
+            ))
+        };
+        let result = match expr.rex_type.as_ref().ok_or_else(unsupported)? {
+            RexType::Literal(literal) => Expression::literal(literal_from_substrait(literal)?),
+            RexType::Selection(reference) => self.field_reference(reference)?,
+            RexType::ScalarFunction(function) => self.scalar_function(function)?,
+            RexType::IfThen(if_then) => {
+                let branches: Vec<_> = if_then
+                    .ifs
+                    .iter()
+                    .map(|clause| {
+                        let when = clause.r#if.as_ref().ok_or_else(unsupported)?;
+                        let then = clause.then.as_ref().ok_or_else(unsupported)?;
+                        Ok::<_, Error>((self.predicate(when)?, self.expression(then)?))
+                    })
+                    .try_collect()?;
+                let else_expr = match if_then.r#else.as_deref() {
+                    Some(else_expr) => Some(self.expression(else_expr)?),
+                    None => None,
+                };
+                Expression::case(branches, else_expr)
+            }
+            RexType::Cast(cast) => {
+                let input = cast.input.as_deref().ok_or_else(unsupported)?;
+                let to = cast.r#type.as_ref().ok_or_else(unsupported)?;
+                let on_overflow = match cast::FailureBehavior::try_from(cast.failure_behavior) {
+                    Ok(cast::FailureBehavior::ReturnNull) => CastOverflowPolicy::Null,
+                    _ => CastOverflowPolicy::Error,
+                };
+                Expression::cast_with_policy(
+                    self.expression(input)?,
+                    type_from_substrait(to)?,
+                    on_overflow,
+                )
+            }
+            RexType::SingularOrList(list) => Expression::from_pred(self.in_list(list)?),
+            RexType::Nested(Nested {
+                nested_type: Some(nested::NestedType::Struct(nested::Struct { fields })),
+                ..
+            }) => {
+                let fields: Vec<_> = fields.iter().map(|e| self.expression(e)).try_collect()?;
+                Expression::struct_from(fields)
+            }
+            _ => return Err(unsupported()),
+        };
+        Ok(result)
+    }
+
+    fn predicate(&self, expr: &proto::Expression) -> DeltaResult<Predicate> {
+        Ok(Predicate::from_expr(self.expression(expr)?))
+    }
+
+    fn in_list(&self, list: &SingularOrList) -> DeltaResult<Predicate> {
+        let unsupported = || {
+            Error::unsupported(format!(
+                "Only literal IN lists can be converted to kernel: {list:?}"
+            ))
+        };
+        let value = list.value.as_deref().ok_or_else(unsupported)?;
+        let values: Vec<_> = list
+            .options
+            .iter()
+            .map(|option| match &option.rex_type {
+                Some(RexType::Literal(literal)) => literal_from_substrait(literal),
+                _ => Err(unsupported()),
+            })
+            .try_collect()?;
+        let element_type = values.first().ok_or_else(unsupported)?.data_type();
+        let contains_null = values.iter().any(Scalar::is_null);
+        let values = ArrayData::try_new(ArrayType::new(element_type, contains_null), values)?;
+        Ok(Predicate::in_list(self.expression(value)?, values))
+    }
+
+    // Leading struct field segments rooted at the input schema form a column reference. Any other
+    // segments become element accesses on the expression referenced so far.
+    fn field_reference(&self, reference: &FieldReference) -> DeltaResult<Expression> {
+        let invalid = || {
+            Error::unsupported(format!(
+                "Cannot convert Substrait field reference {reference:?} to kernel"
+            ))
+        };
+        let Some(ReferenceType::DirectReference(segment)) = &reference.reference_type else {
+            return Err(invalid());
+        };
+        let mut base = match &reference.root_type {
+            Some(RootType::Expression(expr)) => Some(self.expression(expr)?),
+            Some(RootType::RootReference(_)) | None => None,
+            Some(RootType::OuterReference(_)) => return Err(invalid()),
+        };
+        let mut column = vec![];
+        let mut schema = Some(self.schema);
+        let mut next = Some(segment);
+        while let Some(segment) = next {
+            use reference_segment::ReferenceType::*;
+            let reference_type = segment.reference_type.as_ref().ok_or_else(invalid)?;
+            next = match reference_type {
+                StructField(field) if base.is_none() => {
+                    let ordinal = usize::try_from(field.field).map_err(|_| invalid())?;
+                    let field_schema = schema.and_then(|schema| schema.fields().nth(ordinal));
+                    let field_schema = field_schema.ok_or_else(|| {
+                        Error::missing_column(format!(
+                            "Field ordinal {ordinal} of column {} not found in schema",
+                            ColumnName::new(&column)
+                        ))
+                    })?;
+                    column.push(field_schema.name().clone());
+                    schema = match field_schema.data_type() {
+                        DataType::Struct(inner) => Some(inner),
+                        _ => None,
+                    };
+                    field.child.as_deref()
+                }
+                reference_type => {
+                    let expr = match base.take() {
+                        Some(expr) => expr,
+                        None if !column.is_empty() => Expression::column(column.drain(..)),
+                        None => return Err(invalid()),
+                    };
+                    let (accessor, child) = match reference_type {
+                        StructField(field) => {
+                            let ordinal = field.field.try_into().map_err(|_| invalid())?;
+                            (ElementAccessor::StructField(ordinal), &field.child)
+                        }
+                        ListElement(element) => {
+                            let index = element.offset.try_into().map_err(|_| invalid())?;
+                            (ElementAccessor::ArrayElement(index), &element.child)
+                        }
+                        MapKey(map_key) => {
+                            let key = map_key.map_key.as_ref().ok_or_else(invalid)?;
+                            let key = literal_from_substrait(key)?;
+                            (ElementAccessor::MapValue(key), &map_key.child)
+                        }
+                    };
+                    base = Some(Expression::element(expr, accessor));
+                    child.as_deref()
+                }
+            };
+        }
+        match base {
+            Some(expr) => Ok(expr),
+            None if !column.is_empty() => Ok(Expression::column(column)),
+            None => Err(invalid()),
+        }
+    }
+
+    fn scalar_function(&self, function: &ScalarFunction) -> DeltaResult<Expression> {
+        let name = self.extensions.function_name(function.function_reference)?;
+        let args: Vec<_> = function
+            .arguments
+            .iter()
+            .map(|arg| match &arg.arg_type {
+                Some(ArgType::Value(expr)) => self.expression(expr),
+                _ => Err(Error::unsupported(format!(
+                    "Unsupported argument {arg:?} of Substrait function {name}"
+                ))),
+            })
+            .try_collect()?;
+        let arity = |expected: usize| {
+            Error::invalid_expression(format!(
+                "Substrait function {name} takes {expected} arguments, but got {}",
+                args.len()
+            ))
+        };
+        let unary = || match <[Expression; 1]>::try_from(args.clone()) {
+            Ok([arg]) => Ok(arg),
+            Err(_) => Err(arity(1)),
+        };
+        let binary = || match <[Expression; 2]>::try_from(args.clone()) {
+            Ok([a, b]) => Ok((a, b)),
+            Err(_) => Err(arity(2)),
+        };
+        let arithmetic = |op| -> DeltaResult<_> {
+            let (a, b) = binary()?;
+            Ok(Expression::binary(op, a, b))
+        };
+        let comparison = |f: fn(Expression, Expression) -> Predicate| -> DeltaResult<_> {
+            let (a, b) = binary()?;
+            Ok(Expression::from_pred(f(a, b)))
+        };
+        let junction = |op| {
+            let preds = args.iter().cloned().map(Predicate::from_expr);
+            Expression::from_pred(Predicate::junction(op, preds))
+        };
+        let result = match name {
+            "add" => arithmetic(BinaryExpressionOp::Plus)?,
+            "subtract" => arithmetic(BinaryExpressionOp::Minus)?,
+            "multiply" => arithmetic(BinaryExpressionOp::Multiply)?,
+            "divide" => arithmetic(BinaryExpressionOp::Divide)?,
+            "modulus" => arithmetic(BinaryExpressionOp::Modulo)?,
+            "coalesce" => Expression::coalesce(args.iter().cloned()),
+            "equal" => comparison(Predicate::eq)?,
+            "not_equal" => comparison(Predicate::ne)?,
+            "lt" => comparison(Predicate::lt)?,
+            "lte" => comparison(Predicate::le)?,
+            "gt" => comparison(Predicate::gt)?,
+            "gte" => comparison(Predicate::ge)?,
+            "is_distinct_from" => comparison(Predicate::distinct)?,
+            "is_not_distinct_from" => comparison(|a, b| Predicate::not(Predicate::distinct(a, b)))?,
+            "is_null" => Expression::from_pred(Predicate::is_null(unary()?)),
+            "is_not_null" => Expression::from_pred(Predicate::is_not_null(unary()?)),
+            "and" => junction(JunctionPredicateOp::And),
+            "or" => junction(JunctionPredicateOp::Or),
+            "not" => Expression::from_pred(Predicate::not(Predicate::from_expr(unary()?))),
+            "like" => {
+                let (value, Expression::Literal(Scalar::String(pattern))) = binary()? else {
+                    return Err(Error::unsupported(
+                        "Only literal patterns of Substrait function like can be converted",
+                    ));
+                };
+                let case_insensitive = function.options.iter().any(|option| {
+                    option.name == "case_sensitivity"
+                        && option.preference.first().map(String::as_str) == Some("CASE_INSENSITIVE")
+                });
+                match case_insensitive {
+                    true => Expression::from_pred(Predicate::ilike(value, pattern)),
+                    false => Expression::from_pred(Predicate::like(value, pattern)),
+                }
+            }
+            _ => {
+                return Err(Error::unsupported(format!(
+                    "Cannot convert Substrait function {name} to kernel"
+                )))
+            }
+        };
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use crate::schema::StructField;
+
+    fn test_schema() -> StructType {
+        StructType::new_unchecked([
+            StructField::nullable("x", DataType::LONG),
+            StructField::nullable("flag", DataType::BOOLEAN),
+            StructField::nullable(
+                "s",
+                StructType::new_unchecked([
+                    StructField::nullable("y", DataType::INTEGER),
+                    StructField::nullable("name", DataType::STRING),
+                ]),
+            ),
+            StructField::nullable(
+                "tags",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+            StructField::nullable("ids", ArrayType::new(DataType::LONG, true)),
+        ])
+    }
+
+    #[test]
+    fn test_predicate_roundtrip() {
+        let schema = test_schema();
+        let values = ArrayData::try_new(ArrayType::new(DataType::LONG, false), [1i64, 2]).unwrap();
+        let preds = [
+            Pred::and_from([
+                Pred::gt(column_expr!("x"), Expr::literal(10i64)),
+                Pred::not(Pred::is_null(column_expr!("s.y"))),
+                column_pred!("flag"),
+            ]),
+            Pred::or(
+                Pred::eq(
+                    Expr::map_value(column_expr!("tags"), "env"),
+                    Expr::literal("prod"),
+                ),
+                Pred::distinct(
+                    Expr::array_element(column_expr!("ids"), 0),
+                    Expr::null_literal(DataType::LONG),
+                ),
+            ),
+            Pred::in_list(column_expr!("x"), values),
+            Pred::ilike(column_expr!("s.name"), "a%"),
+            Pred::lt(
+                Expr::try_cast(column_expr!("s.y") + Expr::literal(1), DataType::LONG),
+                Expr::literal(Scalar::decimal(12345, 10, 2).unwrap()),
+            ),
+        ];
+        let mut extensions = SubstraitExtensions::new();
+        for pred in preds {
+            let substrait = predicate_to_substrait(&pred, &schema, &mut extensions).unwrap();
+            let decoded = predicate_from_substrait(&substrait, &schema, &extensions).unwrap();
+            assert_eq!(decoded, pred, "{pred}");
+        }
+
+        // Each function is declared only once, no matter how often it is used
+        let declarations = extensions.declarations();
+        let names: Vec<_> = declarations
+            .iter()
+            .filter_map(|d| match &d.mapping_type {
+                Some(MappingType::ExtensionFunction(f)) => Some(f.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names.iter().filter(|name| **name == "not").count(), 1);
+        assert!(names.contains(&"like"));
+        assert_eq!(extensions.uris().len(), 4);
+    }
+
+    #[test]
+    fn test_expression_roundtrip() {
+        let schema = test_schema();
+        let exprs = [
+            Expr::coalesce([column_expr!("s.y"), Expr::literal(0)]),
+            Expr::case(
+                [(
+                    column_pred!("flag"),
+                    column_expr!("x") * Expr::literal(2i64),
+                )],
+                Some(Expr::literal(0i64)),
+            ),
+            Expr::struct_from([column_expr!("x"), Expr::literal(Scalar::Timestamp(1))]),
+            Expr::struct_field(column_expr!("s"), 1),
+        ];
+        let mut extensions = SubstraitExtensions::new();
+        for expr in exprs {
+            let substrait = expression_to_substrait(&expr, &schema, &mut extensions).unwrap();
+            let decoded = expression_from_substrait(&substrait, &schema, &extensions).unwrap();
+            assert_eq!(decoded, expr, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_unsupported_conversions() {
+        let schema = test_schema();
+        let mut extensions = SubstraitExtensions::new();
+        for expr in [
+            column_expr!("missing"),
+            Expr::null_if(column_expr!("x"), Expr::literal(1i64)),
+            Expr::unknown("mystery"),
+        ] {
+            assert!(expression_to_substrait(&expr, &schema, &mut extensions).is_err());
+        }
+
+        // Functions must be declared
+        let substrait =
+            predicate_to_substrait(&Pred::not(column_pred!("flag")), &schema, &mut extensions);
+        let result = predicate_from_substrait(&substrait.unwrap(), &schema, &Default::default());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Helpers for comparing scalars, expressions and predicates in tests.
+//!
+//! The `PartialEq` of [`Scalar`] follows SQL semantics: null, array, struct and map scalars never
+//! compare equal to anything, and neither do expressions and predicates with such literals. The
+//! [`NullAwareEq`] equality instead considers nulls of the same type equal, and compares nested
+//! scalars element-wise, so tests can check the exact results of e.g. parsing or simplification.
+use std::borrow::Cow;
+
+use super::transforms::ExpressionTransform;
+use super::{Expression, Predicate, Scalar};
+
+/// Equality under which nulls of the same type are equal, see the [module docs](self).
+pub(crate) trait NullAwareEq {
+    fn null_aware_eq(&self, other: &Self) -> bool;
+}
+
+impl NullAwareEq for Scalar {
+    fn null_aware_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Scalar::Null(a), Scalar::Null(b)) => a == b,
+            #[allow(deprecated)]
+            (Scalar::Array(a), Scalar::Array(b)) => {
+                a.array_type() == b.array_type()
+                    && a.array_elements().null_aware_eq(b.array_elements())
+            }
+            (Scalar::Struct(a), Scalar::Struct(b)) => {
+                a.fields() == b.fields() && a.values().null_aware_eq(b.values())
+            }
+            (Scalar::Map(a), Scalar::Map(b)) => {
+                a.map_type() == b.map_type()
+                    && a.pairs().len() == b.pairs().len()
+                    && a.pairs()
+                        .iter()
+                        .zip(b.pairs())
+                        .all(|((k1, v1), (k2, v2))| k1.null_aware_eq(k2) && v1.null_aware_eq(v2))
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl<T: NullAwareEq> NullAwareEq for [T] {
+    fn null_aware_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.null_aware_eq(b))
+    }
+}
+
+impl<T: NullAwareEq> NullAwareEq for Vec<T> {
+    fn null_aware_eq(&self, other: &Self) -> bool {
+        self.as_slice().null_aware_eq(other.as_slice())
+    }
+}
+
+impl<T: NullAwareEq> NullAwareEq for Option<T> {
+    fn null_aware_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.null_aware_eq(b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+// Replaces all literals with the same (comparable) placeholder, and collects the replaced literals
+// in order. Two expressions are equal if they are equal after the replacement, and their literals
+// are equal.
+#[derive(Default)]
+struct LiteralCollector {
+    literals: Vec<Scalar>,
+}
+
+impl<'a> ExpressionTransform<'a> for LiteralCollector {
+    fn transform_expr_literal(&mut self, value: &'a Scalar) -> Option<Cow<'a, Scalar>> {
+        self.literals.push(value.clone());
+        Some(Cow::Owned(Scalar::Boolean(true)))
+    }
+}
+
+impl NullAwareEq for Expression {
+    fn null_aware_eq(&self, other: &Self) -> bool {
+        let (mut a, mut b) = (LiteralCollector::default(), LiteralCollector::default());
+        a.transform_expr(self) == b.transform_expr(other) && a.literals.null_aware_eq(&b.literals)
+    }
+}
+
+impl NullAwareEq for Predicate {
+    fn null_aware_eq(&self, other: &Self) -> bool {
+        let (mut a, mut b) = (LiteralCollector::default(), LiteralCollector::default());
+        a.transform_pred(self) == b.transform_pred(other) && a.literals.null_aware_eq(&b.literals)
+    }
+}
+
+/// Like `assert_eq!`, but compares the values with [`NullAwareEq`].
+macro_rules! assert_null_aware_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::expressions::test_utils::assert_null_aware_eq!($left, $right, "")
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => assert!(
+                $crate::expressions::test_utils::NullAwareEq::null_aware_eq(left, right),
+                "assertion `left == right` failed: {}\n  left: {left:?}\n right: {right:?}",
+                format_args!($($arg)+)
+            ),
+        }
+    };
+}
+pub(crate) use assert_null_aware_eq;
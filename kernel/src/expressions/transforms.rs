@@ -3,9 +3,10 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::expressions::{
-    BinaryExpression, BinaryPredicate, ColumnName, Expression, ExpressionRef, JunctionPredicate,
-    OpaqueExpression, OpaquePredicate, Predicate, Scalar, Transform, UnaryExpression,
-    UnaryPredicate, VariadicExpression,
+    BinaryExpression, BinaryPredicate, CaseExpression, CastExpression, ColumnName,
+    ElementExpression, Expression, ExpressionRef, JunctionPredicate, OpaqueExpression,
+    OpaquePredicate, Predicate, Scalar, Transform, UnaryExpression, UnaryPredicate,
+    VariadicExpression,
 };
 use crate::utils::CowExt as _;
 
@@ -127,6 +128,27 @@ pub trait ExpressionTransform<'a> {
         self.recurse_into_expr_variadic(expr)
     }
 
+    /// Called for each [`CaseExpression`] encountered during the traversal. Implementations can
+    /// call [`Self::recurse_into_expr_case`] if they wish to recursively transform the children.
+    fn transform_expr_case(&mut self, expr: &'a CaseExpression) -> Option<Cow<'a, CaseExpression>> {
+        self.recurse_into_expr_case(expr)
+    }
+
+    /// Called for each [`CastExpression`] encountered during the traversal. Implementations can
+    /// call [`Self::recurse_into_expr_cast`] if they wish to recursively transform the child.
+    fn transform_expr_cast(&mut self, expr: &'a CastExpression) -> Option<Cow<'a, CastExpression>> {
+        self.recurse_into_expr_cast(expr)
+    }
+
+    /// Called for each [`ElementExpression`] encountered during the traversal. Implementations can
+    /// call [`Self::recurse_into_expr_element`] if they wish to recursively transform the child.
+    fn transform_expr_element(
+        &mut self,
+        expr: &'a ElementExpression,
+    ) -> Option<Cow<'a, ElementExpression>> {
+        self.recurse_into_expr_element(expr)
+    }
+
     /// Called for each [`JunctionPredicate`] encountered during the traversal. Implementations can
     /// call [`Self::recurse_into_pred_junction`] if they wish to recursively transform the children.
     fn transform_pred_junction(
@@ -179,6 +201,15 @@ pub trait ExpressionTransform<'a> {
             Expression::Variadic(v) => self
                 .transform_expr_variadic(v)?
                 .map_owned_or_else(expr, Expression::Variadic),
+            Expression::Case(c) => self
+                .transform_expr_case(c)?
+                .map_owned_or_else(expr, Expression::Case),
+            Expression::Cast(c) => self
+                .transform_expr_cast(c)?
+                .map_owned_or_else(expr, Expression::Cast),
+            Expression::Element(e) => self
+                .transform_expr_element(e)?
+                .map_owned_or_else(expr, Expression::Element),
             Expression::Opaque(o) => self
                 .transform_expr_opaque(o)?
                 .map_owned_or_else(expr, Expression::Opaque),
@@ -312,6 +343,56 @@ pub trait ExpressionTransform<'a> {
         Some(nested_result.map_owned_or_else(v, |exprs| VariadicExpression::new(v.op, exprs)))
     }
 
+    /// Recursively transforms a conditional expression's children. Returns `None` if at least one
+    /// child was removed, `Some(Cow::Owned)` if at least one child changed, and
+    /// `Some(Cow::Borrowed)` otherwise.
+    fn recurse_into_expr_case(&mut self, c: &'a CaseExpression) -> Option<Cow<'a, CaseExpression>> {
+        let mut changed = false;
+        let mut branches = Vec::with_capacity(c.branches.len());
+        for (when, then) in &c.branches {
+            let when = self.transform_pred(when)?;
+            let then = self.transform_expr(then)?;
+            changed |= matches!(when, Cow::Owned(_)) || matches!(then, Cow::Owned(_));
+            branches.push((when, then));
+        }
+        let else_expr = match &c.else_expr {
+            Some(else_expr) => Some(self.transform_expr(else_expr)?),
+            None => None,
+        };
+        changed |= matches!(else_expr, Some(Cow::Owned(_)));
+        if !changed {
+            return Some(Cow::Borrowed(c));
+        }
+        let branches = branches
+            .into_iter()
+            .map(|(when, then)| (when.into_owned(), then.into_owned()));
+        let else_expr = else_expr.map(Cow::into_owned);
+        Some(Cow::Owned(CaseExpression::new(branches, else_expr)))
+    }
+
+    /// Recursively transforms a cast expression's child. Returns `None` if the child was removed,
+    /// `Some(Cow::Owned)` if the child was changed, and `Some(Cow::Borrowed)` otherwise.
+    fn recurse_into_expr_cast(&mut self, c: &'a CastExpression) -> Option<Cow<'a, CastExpression>> {
+        let nested_result = self.transform_expr(&c.expr)?;
+        Some(nested_result.map_owned_or_else(c, |expr| {
+            CastExpression::new(expr, c.to.clone(), c.on_overflow)
+        }))
+    }
+
+    /// Recursively transforms an element access expression's child. Returns `None` if the child
+    /// was removed, `Some(Cow::Owned)` if the child was changed, and `Some(Cow::Borrowed)`
+    /// otherwise.
+    fn recurse_into_expr_element(
+        &mut self,
+        e: &'a ElementExpression,
+    ) -> Option<Cow<'a, ElementExpression>> {
+        let nested_result = self.transform_expr(&e.expr)?;
+        Some(
+            nested_result
+                .map_owned_or_else(e, |expr| ElementExpression::new(expr, e.accessor.clone())),
+        )
+    }
+
     /// Recursively transforms a junction predicate's children. Returns `None` if all children were
     /// removed, `Some(Cow::Owned)` if at least one child was changed or removed, and
     /// `Some(Cow::Borrowed)` otherwise.
@@ -484,6 +565,21 @@ impl<'a> ExpressionTransform<'a> for ExpressionDepthChecker {
         self.depth_limited(Self::recurse_into_expr_binary, expr)
     }
 
+    fn transform_expr_case(&mut self, expr: &'a CaseExpression) -> Option<Cow<'a, CaseExpression>> {
+        self.depth_limited(Self::recurse_into_expr_case, expr)
+    }
+
+    fn transform_expr_cast(&mut self, expr: &'a CastExpression) -> Option<Cow<'a, CastExpression>> {
+        self.depth_limited(Self::recurse_into_expr_cast, expr)
+    }
+
+    fn transform_expr_element(
+        &mut self,
+        expr: &'a ElementExpression,
+    ) -> Option<Cow<'a, ElementExpression>> {
+        self.depth_limited(Self::recurse_into_expr_element, expr)
+    }
+
     fn transform_pred_binary(
         &mut self,
         pred: &'a BinaryPredicate,
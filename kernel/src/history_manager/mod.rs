@@ -1 +1,290 @@
+//! This module converts timestamps to table versions, e.g. to resolve the timestamp bounds of a
+//! change data feed query.
+//!
+//! The timestamp of a commit is its in-commit timestamp if in-commit timestamps were enabled when
+//! the commit was written, and the modification time of the commit file otherwise. File
+//! modification times need not increase with the version, so, like other Delta implementations, we
+//! adjust them to be strictly increasing: a commit whose modification time is not greater than
+//! that of the previous commit is treated as committed one millisecond after it.
+use std::slice;
+
+use search::{binary_search_by_key_with_bounds, Bound, SearchError};
+
+use crate::actions::visitors::InCommitTimestampVisitor;
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::snapshot::Snapshot;
+use crate::{DeltaResult, Engine, Error, FileMeta, RowVisitor as _, Version};
+
 pub(crate) mod search;
+
+/// How to convert a timestamp that does not exactly match the timestamp of a commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimestampBound {
+    /// Convert to the earliest version committed at or after the timestamp.
+    AtOrAfter,
+    /// Convert to the latest version committed at or before the timestamp.
+    AtOrBefore,
+}
+
+/// Convert `timestamp` (in milliseconds since the unix epoch) to a version of the table, following
+/// `bound`. Only the versions up to the version of `snapshot` are considered.
+///
+/// Fails if no version satisfies the bound, i.e. if the timestamp is after the latest commit
+/// ([`TimestampBound::AtOrAfter`]) or before the earliest commit still in the log
+/// ([`TimestampBound::AtOrBefore`]).
+pub(crate) fn timestamp_to_version(
+    snapshot: &Snapshot,
+    engine: &dyn Engine,
+    timestamp: i64,
+    bound: TimestampBound,
+) -> DeltaResult<Version> {
+    let log_segment = LogSegment::for_timestamp_conversion(
+        engine.storage_handler().as_ref(),
+        snapshot.log_segment().log_root.clone(),
+        snapshot.version(),
+        None,
+    )?;
+    let commits = log_segment.ascending_commit_files.as_slice();
+    let ict_enablement = snapshot
+        .table_configuration()
+        .in_commit_timestamp_enablement()?;
+
+    // Commits from the enablement of in-commit timestamps onwards have increasing in-commit
+    // timestamps, which are searched by reading the commits. Older commits are searched by their
+    // adjusted file modification times, which doesn't require reading them.
+    let ict_start = match ict_enablement {
+        Some((enablement_version, _)) => {
+            commits.partition_point(|commit| commit.version < enablement_version)
+        }
+        None => commits.len(),
+    };
+    let (file_time_commits, ict_commits) = commits.split_at(ict_start);
+    let file_times: Vec<i64> = file_time_commits
+        .iter()
+        .scan(i64::MIN, |previous, commit| {
+            *previous = commit
+                .location
+                .last_modified
+                .max(previous.saturating_add(1));
+            Some(*previous)
+        })
+        .collect();
+    let search_file_times = |bound| {
+        binary_search_by_key_with_bounds(&file_times, timestamp, |t| Ok::<_, Error>(*t), bound)
+    };
+    let search_icts = |bound| {
+        binary_search_by_key_with_bounds(
+            ict_commits,
+            timestamp,
+            |commit| read_in_commit_timestamp(engine, commit),
+            bound,
+        )
+        .map(|index| ict_start + index)
+    };
+
+    let index = match bound {
+        // the earliest commit at or after the timestamp is an in-commit timestamp commit only if
+        // all older commits are before the timestamp
+        TimestampBound::AtOrAfter => match search_file_times(Bound::LeastUpper) {
+            Err(SearchError::OutOfRange) => search_icts(Bound::LeastUpper),
+            result => result,
+        },
+        // the latest commit at or before the timestamp is an older commit only if all in-commit
+        // timestamp commits are after the timestamp
+        TimestampBound::AtOrBefore => match search_icts(Bound::GreatestLower) {
+            Err(SearchError::OutOfRange) => search_file_times(Bound::GreatestLower),
+            result => result,
+        },
+    };
+    match index {
+        Ok(index) => Ok(commits[index].version),
+        Err(SearchError::KeyFunctionError(err)) => Err(err),
+        Err(SearchError::OutOfRange) => Err(match bound {
+            TimestampBound::AtOrAfter => Error::generic(format!(
+                "Timestamp {timestamp} is after the latest commit of the table (version {})",
+                snapshot.version()
+            )),
+            TimestampBound::AtOrBefore => Error::generic(format!(
+                "Timestamp {timestamp} is before the earliest available commit of the table (version {})",
+                log_segment.ascending_commit_files.first().map_or(snapshot.version(), |c| c.version)
+            )),
+        }),
+    }
+}
+
+/// The in-commit timestamp of the version of `snapshot`, or `None` if in-commit timestamps are not
+/// enabled at that version. This reads the commit of the version.
+pub(crate) fn snapshot_in_commit_timestamp(
+    snapshot: &Snapshot,
+    engine: &dyn Engine,
+) -> DeltaResult<Option<i64>> {
+    if !snapshot
+        .table_configuration()
+        .is_in_commit_timestamps_enabled()
+    {
+        return Ok(None);
+    }
+    let log_segment = snapshot.log_segment();
+    let commit = match log_segment.ascending_commit_files.last() {
+        Some(commit) if commit.version == snapshot.version() => commit.clone(),
+        // the snapshot was loaded from a checkpoint of its version, so its commit wasn't listed
+        _ => {
+            let location = log_segment
+                .log_root
+                .join(&format!("{:020}.json", snapshot.version()))?;
+            ParsedLogPath::try_from(FileMeta::new(location, 0, 0))?.ok_or_else(|| {
+                Error::internal_error("Failed to create the commit path of the snapshot version")
+            })?
+        }
+    };
+    read_in_commit_timestamp(engine, &commit).map(Some)
+}
+
+/// Read the in-commit timestamp of `commit`, which must have one.
+fn read_in_commit_timestamp(engine: &dyn Engine, commit: &ParsedLogPath) -> DeltaResult<i64> {
+    let mut actions = engine.json_handler().read_json_files(
+        slice::from_ref(&commit.location),
+        InCommitTimestampVisitor::schema(),
+        None,
+    )?;
+    let mut visitor = InCommitTimestampVisitor::default();
+    if let Some(actions) = actions.next() {
+        visitor.visit_rows_of(actions?.as_ref())?;
+    }
+    visitor.in_commit_timestamp.ok_or_else(|| {
+        Error::generic(format!(
+            "In-commit timestamps are enabled, but commit {} has no in-commit timestamp",
+            commit.version
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use url::Url;
+
+    use super::*;
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+
+    const PROTOCOL: &str = r#"{"protocol":{"minReaderVersion":1,"minWriterVersion":7,"writerFeatures":["inCommitTimestamp"]}}"#;
+
+    fn metadata(configuration: &str) -> String {
+        format!(
+            r#"{{"metaData":{{"id":"id","format":{{"provider":"parquet","options":{{}}}},"schemaString":"{{\"type\":\"struct\",\"fields\":[{{\"name\":\"id\",\"type\":\"long\",\"nullable\":true,\"metadata\":{{}}}}]}}","partitionColumns":[],"configuration":{{{configuration}}}}}}}"#
+        )
+    }
+
+    const COMMIT_INFO: &str = r#"{"commitInfo":{"timestamp":0}}"#;
+
+    fn commit_info(in_commit_timestamp: i64) -> String {
+        format!(
+            r#"{{"commitInfo":{{"timestamp":{in_commit_timestamp},"inCommitTimestamp":{in_commit_timestamp}}}}}"#
+        )
+    }
+
+    /// Write the given commits to an in-memory table, sleeping between them so that their file
+    /// modification times increase, and return the snapshot of the table together with the
+    /// modification times of the commits.
+    async fn write_commits(
+        commits: Vec<Vec<String>>,
+    ) -> (
+        Arc<Snapshot>,
+        DefaultEngine<TokioBackgroundExecutor>,
+        Vec<i64>,
+    ) {
+        let store = Arc::new(InMemory::new());
+        let mut modification_times = vec![];
+        for (version, actions) in commits.iter().enumerate() {
+            let path = Path::from(format!("_delta_log/{version:020}.json"));
+            store.put(&path, actions.join("\n").into()).await.unwrap();
+            let meta = store.head(&path).await.unwrap();
+            modification_times.push(meta.last_modified.timestamp_millis());
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///").unwrap();
+        let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+        (snapshot, engine, modification_times)
+    }
+
+    #[tokio::test]
+    async fn test_timestamp_to_version_file_modification_times() {
+        let (snapshot, engine, times) = write_commits(vec![
+            vec![PROTOCOL.to_string(), metadata("")],
+            vec![COMMIT_INFO.to_string()],
+            vec![COMMIT_INFO.to_string()],
+        ])
+        .await;
+        let to_version =
+            |timestamp, bound| timestamp_to_version(&snapshot, &engine, timestamp, bound);
+
+        for (version, time) in times.iter().enumerate() {
+            let version = version as Version;
+            assert_eq!(
+                to_version(*time, TimestampBound::AtOrAfter).unwrap(),
+                version
+            );
+            assert_eq!(
+                to_version(*time, TimestampBound::AtOrBefore).unwrap(),
+                version
+            );
+        }
+        assert_eq!(
+            to_version(times[1] + 1, TimestampBound::AtOrAfter).unwrap(),
+            2
+        );
+        assert_eq!(
+            to_version(times[1] + 1, TimestampBound::AtOrBefore).unwrap(),
+            1
+        );
+        assert_eq!(
+            to_version(times[0] - 1, TimestampBound::AtOrAfter).unwrap(),
+            0
+        );
+        assert!(to_version(times[0] - 1, TimestampBound::AtOrBefore).is_err());
+        assert_eq!(
+            to_version(times[2] + 1, TimestampBound::AtOrBefore).unwrap(),
+            2
+        );
+        assert!(to_version(times[2] + 1, TimestampBound::AtOrAfter).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timestamp_to_version_in_commit_timestamps() {
+        // in-commit timestamps are enabled at version 2, and are far in the future compared to
+        // the file modification times of versions 0 and 1
+        let enablement = r#""delta.enableInCommitTimestamps":"true","delta.inCommitTimestampEnablementVersion":"2","delta.inCommitTimestampEnablementTimestamp":"4000000000000""#;
+        let (snapshot, engine, times) = write_commits(vec![
+            vec![PROTOCOL.to_string(), metadata("")],
+            vec![COMMIT_INFO.to_string()],
+            vec![commit_info(4_000_000_000_000), metadata(enablement)],
+            vec![commit_info(4_000_000_000_010)],
+            vec![commit_info(4_000_000_000_020)],
+        ])
+        .await;
+        let to_version =
+            |timestamp, bound| timestamp_to_version(&snapshot, &engine, timestamp, bound);
+
+        assert_eq!(to_version(times[1], TimestampBound::AtOrBefore).unwrap(), 1);
+        assert_eq!(
+            to_version(times[1] + 1, TimestampBound::AtOrAfter).unwrap(),
+            2
+        );
+        assert_eq!(to_version(times[4], TimestampBound::AtOrBefore).unwrap(), 1);
+        let ict = 4_000_000_000_000;
+        assert_eq!(to_version(ict, TimestampBound::AtOrAfter).unwrap(), 2);
+        assert_eq!(to_version(ict, TimestampBound::AtOrBefore).unwrap(), 2);
+        assert_eq!(to_version(ict + 15, TimestampBound::AtOrAfter).unwrap(), 4);
+        assert_eq!(to_version(ict + 15, TimestampBound::AtOrBefore).unwrap(), 3);
+        assert_eq!(to_version(ict + 20, TimestampBound::AtOrAfter).unwrap(), 4);
+        assert!(to_version(ict + 21, TimestampBound::AtOrAfter).is_err());
+    }
+}
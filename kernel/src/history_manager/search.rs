@@ -12,7 +12,6 @@ use std::fmt::Debug;
 ///
 /// * [`Bound::GreatestLower`] - Finds the largest index `i` such that `values[i] <= key`.
 ///   This represents the last element less than or equal to the search key.
-#[allow(unused)]
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum Bound {
     LeastUpper,
@@ -21,7 +20,6 @@ pub(crate) enum Bound {
 
 /// Represents the errors that can occur when performing binary search using
 /// [`binary_search_by_key_with_bounds`].
-#[allow(unused)]
 #[derive(Debug)]
 pub(crate) enum SearchError<T: Error> {
     /// Error that occurs when a search goes out of range. The meaning of "out of range" depends on
@@ -129,7 +127,6 @@ pub(crate) enum SearchError<T: Error> {
 /// );
 /// assert!(matches!(result, Err(SearchError::KeyFunctionError(_))));
 /// ```
-#[allow(unused)]
 pub(crate) fn binary_search_by_key_with_bounds<'a, T, K: Ord + Debug, E: Error>(
     values: &'a [T],
     key: K,
@@ -4,10 +4,11 @@
 //! but data skipping "evaluation" actually produces a transformed predicate that replaces column
 //! references with stats column references, which log replay will instruct the engine to evaluate.
 use crate::expressions::{
-    BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, ColumnName,
-    Expression as Expr, JunctionPredicate, JunctionPredicateOp, OpaqueExpression,
-    OpaqueExpressionOpRef, OpaquePredicate, OpaquePredicateOpRef, Predicate as Pred, Scalar,
-    UnaryPredicate, UnaryPredicateOp,
+    BinaryExpression, BinaryExpressionOp, BinaryPredicate, BinaryPredicateOp, CaseExpression,
+    CastExpression, ColumnName, ElementAccessor, ElementExpression, Expression as Expr,
+    JunctionPredicate, JunctionPredicateOp, OpaqueExpression, OpaqueExpressionOpRef,
+    OpaquePredicate, OpaquePredicateOpRef, Predicate as Pred, Scalar, UnaryPredicate,
+    UnaryPredicateOp, VariadicExpression, VariadicExpressionOp,
 };
 use crate::schema::DataType;
 
@@ -19,6 +20,10 @@ pub(crate) mod parquet_stats_skipping;
 #[cfg(test)]
 mod tests;
 
+/// The largest IN-list for which data skipping checks every list value against the column stats.
+/// Longer lists only check the range spanned by their values, to keep the output small.
+pub(crate) const MAX_IN_LIST_VALUES_FOR_DATA_SKIPPING: usize = 32;
+
 // NOTE: When creating `&dyn Foo` for some `impl<'a> Bar<'a>`, the compiler infers `&'r dyn Foo +
 // 'a` (and then elides the lifetimes because `'a: 'r`). Creating a type alias for `dyn Foo` causes
 // the compiler to infer `dyn Foo + 'static` (the lifetime of the alias). Which in turn requires
@@ -182,6 +187,9 @@ pub trait KernelPredicateEvaluator {
             | Expr::Unary(_)
             | Expr::Binary(_)
             | Expr::Variadic(_)
+            | Expr::Case(_)
+            | Expr::Cast(_)
+            | Expr::Element(_)
             | Expr::Unknown(_) => None,
         }
     }
@@ -208,6 +216,9 @@ pub trait KernelPredicateEvaluator {
                 | Expr::Unary(_)
                 | Expr::Binary(_)
                 | Expr::Variadic(_)
+                | Expr::Case(_)
+                | Expr::Cast(_)
+                | Expr::Element(_)
                 | Expr::Opaque(_)
                 | Expr::Unknown(_) => {
                     debug!("Unsupported operand: IS [NOT] NULL: {expr:?}");
@@ -249,7 +260,20 @@ pub trait KernelPredicateEvaluator {
         _val: &Scalar,
         _inverted: bool,
     ) -> Option<Self::Output> {
-        None // TODO?
+        None
+    }
+
+    /// A (possibly inverted) pattern match, e.g. `<col> [NOT] [I]LIKE <pattern>`.
+    ///
+    /// Unsupported by default, but implementations can override it if they wish.
+    fn eval_pred_like(
+        &self,
+        _col: &ColumnName,
+        _pattern: &Scalar,
+        _case_insensitive: bool,
+        _inverted: bool,
+    ) -> Option<Self::Output> {
+        None
     }
 
     /// Dispatches a (possibly inverted) binary expression to each operator's specific implementation.
@@ -274,6 +298,8 @@ pub trait KernelPredicateEvaluator {
                 Equal => self.eval_pred_eq(col, val, inverted),
                 Distinct => self.eval_pred_distinct(col, val, inverted),
                 In => self.eval_pred_in(col, val, inverted),
+                Like => self.eval_pred_like(col, val, false, inverted),
+                ILike => self.eval_pred_like(col, val, true, inverted),
             },
             (Literal(val), Column(col)) => match op {
                 // NOTE: The column has to be on the left, so e.g. `10 < x` becomes `x > 10`
@@ -281,7 +307,7 @@ pub trait KernelPredicateEvaluator {
                 GreaterThan => self.eval_pred_lt(col, val, inverted),
                 Equal => self.eval_pred_eq(col, val, inverted),
                 Distinct => self.eval_pred_distinct(col, val, inverted),
-                In => None, // arg order is semantically important
+                In | Like | ILike => None, // arg order is semantically important
             },
             _ => {
                 debug!("Unsupported binary operand(s): {left:?} {op:?} {right:?}");
@@ -535,6 +561,17 @@ impl KernelPredicateEvaluatorDefaults {
             Equal => Self::partial_cmp_scalars(Ordering::Equal, left, right, inverted),
             LessThan => Self::partial_cmp_scalars(Ordering::Less, left, right, inverted),
             GreaterThan => Self::partial_cmp_scalars(Ordering::Greater, left, right, inverted),
+            Like | ILike => {
+                let (Scalar::String(value), Scalar::String(pattern)) = (left, right) else {
+                    debug!("Unsupported LIKE operands: {left:?} {op:?} {right:?}");
+                    return None;
+                };
+                let matched = match op {
+                    ILike => like_matches(&value.to_lowercase(), &pattern.to_lowercase()),
+                    _ => like_matches(value, pattern),
+                };
+                Some(matched != inverted)
+            }
             Distinct | In => {
                 debug!("Unsupported binary operator: {left:?} {op:?} {right:?}");
                 None
@@ -571,6 +608,90 @@ impl KernelPredicateEvaluatorDefaults {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum LikeToken {
+    AnyString,
+    AnyChar,
+    Char(char),
+}
+
+// Splits a (backslash-escaped) LIKE pattern into literal characters and wildcards.
+fn tokenize_like_pattern(pattern: &str) -> Vec<LikeToken> {
+    let mut tokens = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '%' => LikeToken::AnyString,
+            '_' => LikeToken::AnyChar,
+            '\\' => LikeToken::Char(chars.next().unwrap_or('\\')),
+            c => LikeToken::Char(c),
+        });
+    }
+    tokens
+}
+
+/// Checks whether `value` matches the (backslash-escaped) LIKE `pattern`.
+fn like_matches(value: &str, pattern: &str) -> bool {
+    let tokens = tokenize_like_pattern(pattern);
+    let value: Vec<char> = value.chars().collect();
+    // Match greedily, and on mismatch backtrack to the most recent `%` and let it consume one more
+    // character. Tracks (token after the `%`, value position it resumes from).
+    let (mut v, mut t) = (0, 0);
+    let mut backtrack = None;
+    while v < value.len() {
+        match tokens.get(t) {
+            Some(LikeToken::AnyString) => {
+                t += 1;
+                backtrack = Some((t, v));
+            }
+            Some(LikeToken::AnyChar) => (v, t) = (v + 1, t + 1),
+            Some(LikeToken::Char(c)) if *c == value[v] => (v, t) = (v + 1, t + 1),
+            _ => match backtrack {
+                Some((bt, bv)) => {
+                    (v, t) = (bv + 1, bt);
+                    backtrack = Some((bt, bv + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[t..]
+        .iter()
+        .all(|token| *token == LikeToken::AnyString)
+}
+
+/// Extracts the literal prefix of a (backslash-escaped) LIKE pattern, i.e. everything before the
+/// first wildcard. Every string matching the pattern starts with this prefix. The returned flag is
+/// true if the pattern has no wildcards at all, in which case only the prefix itself matches.
+fn like_pattern_prefix(pattern: &str) -> (String, bool) {
+    let mut prefix = String::new();
+    for token in tokenize_like_pattern(pattern) {
+        match token {
+            LikeToken::Char(c) => prefix.push(c),
+            LikeToken::AnyString | LikeToken::AnyChar => return (prefix, false),
+        }
+    }
+    (prefix, true)
+}
+
+/// Returns an exclusive upper bound for the strings that start with `prefix`, if one exists, by
+/// incrementing the last character of the prefix that is not already `char::MAX`.
+fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(c) = chars.pop() {
+        // The next char after the last one before the surrogate range is the first one after it
+        let next = match c {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(c as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
 /// Resolves columns as scalars, as a building block for [`DefaultKernelPredicateEvaluator`].
 pub(crate) trait ResolveColumnAsScalar {
     fn resolve_column(&self, col: &ColumnName) -> Option<Scalar>;
@@ -623,10 +744,84 @@ impl<R: ResolveColumnAsScalar> DefaultKernelPredicateEvaluator<R> {
                     BinaryExpressionOp::Minus => Scalar::try_sub,
                     BinaryExpressionOp::Multiply => Scalar::try_mul,
                     BinaryExpressionOp::Divide => Scalar::try_div,
+                    BinaryExpressionOp::Modulo => Scalar::try_rem,
                 };
                 op_fn(&self.eval_expr(left)?, &self.eval_expr(right)?)
             }
-            Expr::Variadic(_) => None, // TODO
+            Expr::Variadic(VariadicExpression { op, exprs }) => match op {
+                VariadicExpressionOp::Coalesce => {
+                    let mut result = None;
+                    for expr in exprs {
+                        let value = self.eval_expr(expr)?;
+                        if !value.is_null() {
+                            return Some(value);
+                        }
+                        result = Some(value);
+                    }
+                    result
+                }
+                VariadicExpressionOp::NullIf => {
+                    let [a, b] = &exprs[..] else {
+                        return None;
+                    };
+                    let (a, b) = (self.eval_expr(a)?, self.eval_expr(b)?);
+                    if a.is_null() || b.is_null() {
+                        return Some(a);
+                    }
+                    match a.partial_cmp(&b)? {
+                        Ordering::Equal => Some(Scalar::Null(a.data_type())),
+                        _ => Some(a),
+                    }
+                }
+            },
+            Expr::Case(CaseExpression {
+                branches,
+                else_expr,
+            }) => {
+                for (when, then) in branches {
+                    // NOTE: A NULL condition would move on to the next branch, but we can't tell
+                    // it apart from a condition we failed to evaluate.
+                    if self.eval_pred(when, false)? {
+                        return self.eval_expr(then);
+                    }
+                }
+                // Without an ELSE, the result is a NULL of unknown type
+                self.eval_expr(else_expr.as_deref()?)
+            }
+            Expr::Cast(CastExpression {
+                expr,
+                to,
+                on_overflow,
+            }) => self.eval_expr(expr)?.try_cast(to, *on_overflow),
+            Expr::Element(ElementExpression { expr, accessor }) => {
+                match (self.eval_expr(expr)?, accessor) {
+                    (Scalar::Map(map), ElementAccessor::MapValue(key)) => {
+                        let value = map.pairs().iter().find(|(k, _)| k == key);
+                        let null = || Scalar::Null(map.map_type().value_type().clone());
+                        Some(value.map_or_else(null, |(_, v)| v.clone()))
+                    }
+                    (Scalar::Array(array), ElementAccessor::ArrayElement(index)) => {
+                        #[allow(deprecated)]
+                        let element = array.array_elements().get(*index).cloned();
+                        let null = || Scalar::Null(array.array_type().element_type().clone());
+                        Some(element.unwrap_or_else(null))
+                    }
+                    (Scalar::Struct(data), ElementAccessor::StructField(ordinal)) => {
+                        data.values().get(*ordinal).cloned()
+                    }
+                    (Scalar::Null(DataType::Map(map)), ElementAccessor::MapValue(_)) => {
+                        Some(Scalar::Null(map.value_type().clone()))
+                    }
+                    (Scalar::Null(DataType::Array(array)), ElementAccessor::ArrayElement(_)) => {
+                        Some(Scalar::Null(array.element_type().clone()))
+                    }
+                    (Scalar::Null(DataType::Struct(fields)), ElementAccessor::StructField(i)) => {
+                        let field = fields.fields().nth(*i)?;
+                        Some(Scalar::Null(field.data_type().clone()))
+                    }
+                    _ => None,
+                }
+            }
             Expr::Opaque(OpaqueExpression { op, exprs }) => op
                 .eval_expr_scalar(&|expr| self.eval_expr(expr), exprs)
                 .inspect_err(|err| {
@@ -678,6 +873,38 @@ impl<R: ResolveColumnAsScalar> KernelPredicateEvaluator for DefaultKernelPredica
         self.eval_pred_binary_scalars(BinaryPredicateOp::Equal, &col, val, inverted)
     }
 
+    fn eval_pred_in(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<bool> {
+        let col = self.resolve_column(col)?;
+        let Scalar::Array(values) = val else {
+            return None;
+        };
+        #[allow(deprecated)]
+        let values = values.array_elements();
+        // `NULL IN (...)` is NULL, and so is `x IN (..., NULL)` if no value matches
+        let found = match col.is_null() {
+            true => None,
+            false if values.contains(&col) => Some(true),
+            false if values.iter().any(Scalar::is_null) => None,
+            false => Some(false),
+        };
+        found.map(|found| found != inverted)
+    }
+
+    fn eval_pred_like(
+        &self,
+        col: &ColumnName,
+        pattern: &Scalar,
+        case_insensitive: bool,
+        inverted: bool,
+    ) -> Option<bool> {
+        let col = self.resolve_column(col)?;
+        let op = match case_insensitive {
+            true => BinaryPredicateOp::ILike,
+            false => BinaryPredicateOp::Like,
+        };
+        self.eval_pred_binary_scalars(op, &col, pattern, inverted)
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
@@ -889,6 +1116,100 @@ pub trait DataSkippingPredicateEvaluator {
         }
     }
 
+    /// See [`KernelPredicateEvaluator::eval_pred_in`]
+    ///
+    /// A column could contain one of the values if `min <= v <= max` for some list value `v`. For
+    /// IN-lists longer than [`MAX_IN_LIST_VALUES_FOR_DATA_SKIPPING`], we only check whether the
+    /// range spanned by the values overlaps with `[min, max]`, which may keep files whose values
+    /// all fall between consecutive list values.
+    fn eval_pred_in(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output> {
+        // NOT IN could only skip files whose values all equal one of the list values, which stats
+        // cannot prove (except when min = max).
+        if inverted {
+            return None;
+        }
+        let Scalar::Array(values) = val else {
+            return None;
+        };
+        #[allow(deprecated)]
+        let values: Vec<_> = values
+            .array_elements()
+            .iter()
+            .filter(|v| !v.is_null())
+            .collect();
+        match values[..] {
+            // `x IN ()` and `x IN (NULL)` are never TRUE
+            [] => self.eval_pred_scalar(&Scalar::from(false), false),
+            [value] => self.eval_pred_eq(col, value, false),
+            _ if values.len() <= MAX_IN_LIST_VALUES_FOR_DATA_SKIPPING => {
+                // Keep if `col = v` could be TRUE for any of the values
+                let mut preds = values
+                    .iter()
+                    .map(|value| self.eval_pred_eq(col, value, false));
+                self.finish_eval_pred_junction(JunctionPredicateOp::Or, &mut preds, false)
+            }
+            _ => {
+                let (mut lo, mut hi) = (values[0], values[0]);
+                for &value in &values[1..] {
+                    match value.partial_cmp(lo)? {
+                        Ordering::Less => lo = value,
+                        _ if value.partial_cmp(hi)? == Ordering::Greater => hi = value,
+                        _ => {}
+                    }
+                }
+                // Keep if `NOT(min > hi) AND NOT(max < lo)`
+                let preds = [
+                    self.partial_cmp_min_stat(col, hi, Ordering::Greater, true),
+                    self.partial_cmp_max_stat(col, lo, Ordering::Less, true),
+                ];
+                self.finish_eval_pred_junction(
+                    JunctionPredicateOp::And,
+                    &mut preds.into_iter(),
+                    false,
+                )
+            }
+        }
+    }
+
+    /// See [`KernelPredicateEvaluator::eval_pred_like`]
+    ///
+    /// Every string matching a case-sensitive pattern such as `'abc%'` starts with the pattern's
+    /// literal prefix (`abc`), and so falls in the range `[abc, abd)`. Patterns without a literal
+    /// prefix (e.g. `'%abc'`), case-insensitive patterns, and NOT LIKE cannot skip anything.
+    fn eval_pred_like(
+        &self,
+        col: &ColumnName,
+        pattern: &Scalar,
+        case_insensitive: bool,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        if case_insensitive || inverted {
+            return None;
+        }
+        let Scalar::String(pattern) = pattern else {
+            return None;
+        };
+        let (prefix, exact) = like_pattern_prefix(pattern);
+        if exact {
+            // A pattern without wildcards only matches itself
+            return self.eval_pred_eq(col, &Scalar::from(prefix), false);
+        }
+        if prefix.is_empty() {
+            return None;
+        }
+        // Keep if `NOT(max < prefix) AND min < upper_bound`
+        let upper_bound = prefix_upper_bound(&prefix);
+        let max_pred = self.partial_cmp_max_stat(col, &Scalar::from(prefix), Ordering::Less, true);
+        let Some(upper_bound) = upper_bound else {
+            return max_pred;
+        };
+        let preds = [
+            max_pred,
+            self.partial_cmp_min_stat(col, &Scalar::from(upper_bound), Ordering::Less, false),
+        ];
+        self.finish_eval_pred_junction(JunctionPredicateOp::And, &mut preds.into_iter(), false)
+    }
+
     /// See [`KernelPredicateEvaluator::eval_pred_eq`]
     fn eval_pred_eq(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output> {
         let (op, preds) = if inverted {
@@ -937,6 +1258,20 @@ impl<T: DataSkippingPredicateEvaluator + ?Sized> KernelPredicateEvaluator for T
         self.eval_pred_eq(col, val, inverted)
     }
 
+    fn eval_pred_in(&self, col: &ColumnName, val: &Scalar, inverted: bool) -> Option<Self::Output> {
+        self.eval_pred_in(col, val, inverted)
+    }
+
+    fn eval_pred_like(
+        &self,
+        col: &ColumnName,
+        pattern: &Scalar,
+        case_insensitive: bool,
+        inverted: bool,
+    ) -> Option<Self::Output> {
+        self.eval_pred_like(col, pattern, case_insensitive, inverted)
+    }
+
     fn eval_pred_binary_scalars(
         &self,
         op: BinaryPredicateOp,
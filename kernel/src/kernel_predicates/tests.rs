@@ -1,11 +1,13 @@
 use super::*;
+use crate::expressions::test_utils::assert_null_aware_eq;
 use crate::expressions::{
-    column_expr, column_name, column_pred, ArrayData, Expression as Expr, OpaqueExpressionOp,
-    OpaquePredicateOp, Predicate as Pred, ScalarExpressionEvaluator, StructData,
+    column_expr, column_name, column_pred, ArrayData, Expression as Expr, MapData,
+    OpaqueExpressionOp, OpaquePredicateOp, Predicate as Pred, ScalarExpressionEvaluator,
+    StructData,
 };
 use crate::kernel_predicates::parquet_stats_skipping::ParquetStatsProvider;
 use crate::scan::data_skipping::as_data_skipping_predicate;
-use crate::schema::ArrayType;
+use crate::schema::{ArrayType, MapType};
 use crate::DataType;
 use crate::DeltaResult;
 
@@ -190,15 +192,20 @@ fn test_default_scalar_arithmetic() {
     let left = &[Byte(2), Short(200), Integer(20000), Long(2000000)];
     let right = &[Byte(3), Short(30), Integer(3000), Long(300000)];
     let expected = [
-        (Byte(5), Byte(-1), Byte(6), Byte(0)),
-        (Short(230), Short(170), Short(6000), Short(6)),
+        (Byte(5), Byte(-1), Byte(6), Double(2.0 / 3.0)),
+        (Short(230), Short(170), Short(6000), Double(200.0 / 30.0)),
         (
             Integer(23000),
             Integer(17000),
             Integer(60000000),
-            Integer(6),
+            Double(20000.0 / 3000.0),
+        ),
+        (
+            Long(2300000),
+            Long(1700000),
+            Long(600000000000),
+            Double(2000000.0 / 300000.0),
         ),
-        (Long(2300000), Long(1700000), Long(600000000000), Long(6)),
     ];
 
     let filter = DefaultKernelPredicateEvaluator::from(Scalar::from(1));
@@ -231,26 +238,33 @@ fn test_default_scalar_arithmetic() {
         None,
         "add(string, string)"
     );
+
+    // Mixed integral types are widened (and divided as doubles)
     expect_eq!(
         filter.eval_expr(&(Expr::literal(1i8) + Expr::literal(1i64))),
-        None,
+        Some(Long(2)),
         "add(byte, long)"
     );
     expect_eq!(
         filter.eval_expr(&(Expr::literal(1i8) - Expr::literal(1i64))),
-        None,
+        Some(Long(0)),
         "sub(byte, long)"
     );
     expect_eq!(
         filter.eval_expr(&(Expr::literal(1i8) * Expr::literal(1i64))),
-        None,
+        Some(Long(1)),
         "mul(byte, long)"
     );
     expect_eq!(
         filter.eval_expr(&(Expr::literal(1i8) / Expr::literal(1i64))),
-        None,
+        Some(Double(1.0)),
         "div(byte, long)"
     );
+    expect_eq!(
+        filter.eval_expr(&(Expr::literal(7i8) % Expr::literal(3i64))),
+        Some(Long(1)),
+        "rem(byte, long)"
+    );
 
     // Addition overflow
     let args = [
@@ -314,6 +328,133 @@ fn test_default_scalar_arithmetic() {
 }
 
 // Verifies that eval_binary_scalars uses partial_cmp_scalars correctly
+#[test]
+fn test_eval_case() {
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::from(1));
+    let case = |x: Scalar| {
+        Expr::case(
+            [
+                (
+                    Pred::lt(Expr::literal(x.clone()), Expr::literal(0)),
+                    Expr::literal("neg"),
+                ),
+                (
+                    Pred::eq(Expr::literal(x), Expr::literal(0)),
+                    Expr::literal("zero"),
+                ),
+            ],
+            Some(Expr::literal("pos")),
+        )
+    };
+    expect_eq!(
+        filter.eval_expr(&case(Scalar::from(-3))),
+        Some(Scalar::from("neg")),
+        "-3"
+    );
+    expect_eq!(
+        filter.eval_expr(&case(Scalar::from(0))),
+        Some(Scalar::from("zero")),
+        "0"
+    );
+    expect_eq!(
+        filter.eval_expr(&case(Scalar::from(7))),
+        Some(Scalar::from("pos")),
+        "7"
+    );
+
+    // NULL conditions are indistinguishable from unknown ones, so the result is unknown
+    let null = Scalar::Null(DataType::INTEGER);
+    expect_eq!(filter.eval_expr(&case(null)), None, "NULL");
+
+    // No match and no ELSE
+    let expr = Expr::case([(Pred::literal(false), Expr::literal(1))], None);
+    expect_eq!(filter.eval_expr(&expr), None, "no ELSE");
+}
+
+#[test]
+fn test_eval_coalesce_and_null_if() {
+    let filter = DefaultKernelPredicateEvaluator::from(EmptyColumnResolver);
+    let null = || Expr::literal(Scalar::Null(DataType::INTEGER));
+
+    let expr = Expr::coalesce([null(), Expr::literal(2), Expr::literal(3)]);
+    expect_eq!(filter.eval_expr(&expr), Some(Scalar::from(2)), "{expr}");
+    let expr = Expr::coalesce([null(), null()]);
+    assert_null_aware_eq!(
+        filter.eval_expr(&expr),
+        Some(Scalar::Null(DataType::INTEGER)),
+        "{expr}"
+    );
+    let expr = Expr::coalesce([null(), column_expr!("unknown"), Expr::literal(3)]);
+    expect_eq!(filter.eval_expr(&expr), None, "{expr}");
+
+    let expr = Expr::null_if(Expr::literal(1), Expr::literal(1));
+    assert_null_aware_eq!(
+        filter.eval_expr(&expr),
+        Some(Scalar::Null(DataType::INTEGER)),
+        "{expr}"
+    );
+    let expr = Expr::null_if(Expr::literal(1), Expr::literal(2));
+    expect_eq!(filter.eval_expr(&expr), Some(Scalar::from(1)), "{expr}");
+    let expr = Expr::null_if(Expr::literal(1), null());
+    expect_eq!(filter.eval_expr(&expr), Some(Scalar::from(1)), "{expr}");
+}
+
+#[test]
+fn test_eval_cast() {
+    let filter = DefaultKernelPredicateEvaluator::from(EmptyColumnResolver);
+    let expr = Expr::cast(Expr::literal(3), DataType::LONG);
+    expect_eq!(filter.eval_expr(&expr), Some(Scalar::Long(3)), "{expr}");
+    let expr = Expr::cast(Expr::literal(300), DataType::BYTE);
+    expect_eq!(filter.eval_expr(&expr), None, "{expr}");
+    let expr = Expr::try_cast(Expr::literal(300), DataType::BYTE);
+    assert_null_aware_eq!(
+        filter.eval_expr(&expr),
+        Some(Scalar::Null(DataType::BYTE)),
+        "{expr}"
+    );
+    let expr = Expr::cast(Expr::literal("12"), DataType::SHORT);
+    expect_eq!(filter.eval_expr(&expr), Some(Scalar::Short(12)), "{expr}");
+}
+
+#[test]
+fn test_eval_element() {
+    let filter = DefaultKernelPredicateEvaluator::from(EmptyColumnResolver);
+    let map_type = MapType::new(DataType::STRING, DataType::INTEGER, true);
+    let map = MapData::try_new(map_type.clone(), [("a", 1), ("b", 2)]).unwrap();
+    let map = Expr::literal(Scalar::Map(map));
+    let array_type = ArrayType::new(DataType::LONG, true);
+    let array = ArrayData::try_new(array_type, [10i64, 20]).unwrap();
+    let array = Expr::literal(Scalar::Array(array));
+
+    let expr = Expr::map_value(map.clone(), "b");
+    expect_eq!(filter.eval_expr(&expr), Some(Scalar::Integer(2)), "{expr}");
+    let expr = Expr::map_value(map, "c");
+    assert_null_aware_eq!(
+        filter.eval_expr(&expr),
+        Some(Scalar::Null(DataType::INTEGER)),
+        "{expr}"
+    );
+    let expr = Expr::array_element(array.clone(), 1);
+    expect_eq!(filter.eval_expr(&expr), Some(Scalar::Long(20)), "{expr}");
+    let expr = Expr::array_element(array.clone(), 2);
+    assert_null_aware_eq!(
+        filter.eval_expr(&expr),
+        Some(Scalar::Null(DataType::LONG)),
+        "{expr}"
+    );
+    let null_map = Expr::literal(Scalar::Null(map_type.into()));
+    let expr = Expr::map_value(null_map, "a");
+    assert_null_aware_eq!(
+        filter.eval_expr(&expr),
+        Some(Scalar::Null(DataType::INTEGER)),
+        "{expr}"
+    );
+
+    // Mismatched accessors cannot be evaluated
+    let expr = Expr::struct_field(array, 0);
+    expect_eq!(filter.eval_expr(&expr), None, "{expr}");
+}
+
 #[test]
 fn test_eval_binary_scalars() {
     use BinaryPredicateOp::*;
@@ -356,6 +497,174 @@ fn test_eval_binary_scalars() {
     }
 }
 
+#[test]
+fn test_eval_in() {
+    let list = |values: Vec<Scalar>| {
+        Scalar::Array(ArrayData::try_new(ArrayType::new(DataType::INTEGER, true), values).unwrap())
+    };
+    let null = Scalar::Null(DataType::INTEGER);
+    let col = &column_name!("x");
+
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::from(1));
+    let values = list(vec![1.into(), 2.into()]);
+    expect_eq!(
+        filter.eval_pred_in(col, &values, false),
+        Some(true),
+        "x IN (1, 2) (x = 1)"
+    );
+    expect_eq!(
+        filter.eval_pred_in(col, &values, true),
+        Some(false),
+        "x NOT IN (1, 2) (x = 1)"
+    );
+    let values = list(vec![2.into(), 3.into()]);
+    expect_eq!(
+        filter.eval_pred_in(col, &values, false),
+        Some(false),
+        "x IN (2, 3) (x = 1)"
+    );
+    expect_eq!(
+        filter.eval_pred_in(col, &values, true),
+        Some(true),
+        "x NOT IN (2, 3) (x = 1)"
+    );
+    let values = list(vec![2.into(), null.clone()]);
+    expect_eq!(
+        filter.eval_pred_in(col, &values, false),
+        None,
+        "x IN (2, NULL) (x = 1)"
+    );
+    expect_eq!(
+        filter.eval_pred_in(col, &values, true),
+        None,
+        "x NOT IN (2, NULL) (x = 1)"
+    );
+    let values = list(vec![1.into(), null.clone()]);
+    expect_eq!(
+        filter.eval_pred_in(col, &values, false),
+        Some(true),
+        "x IN (1, NULL) (x = 1)"
+    );
+
+    let filter = DefaultKernelPredicateEvaluator::from(null);
+    let values = list(vec![1.into(), 2.into()]);
+    expect_eq!(
+        filter.eval_pred_in(col, &values, false),
+        None,
+        "x IN (1, 2) (x = NULL)"
+    );
+    expect_eq!(
+        filter.eval_pred_in(col, &values, true),
+        None,
+        "x NOT IN (1, 2) (x = NULL)"
+    );
+
+    // routing through eval_pred
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::from(2));
+    let values = ArrayData::try_new(ArrayType::new(DataType::INTEGER, false), [1, 2]).unwrap();
+    let pred = Pred::in_list(column_expr!("x"), values.clone());
+    expect_eq!(filter.eval(&pred), Some(true), "x IN (1, 2) (x = 2)");
+    let pred = Pred::not_in_list(column_expr!("x"), values);
+    expect_eq!(filter.eval(&pred), Some(false), "x NOT IN (1, 2) (x = 2)");
+}
+
+#[test]
+fn test_like_matches() {
+    let cases = [
+        ("abc", "abc", true),
+        ("abc", "ab", false),
+        ("abc", "a%", true),
+        ("abc", "%c", true),
+        ("abc", "%b%", true),
+        ("abc", "%", true),
+        ("", "%", true),
+        ("", "_", false),
+        ("abc", "a_c", true),
+        ("abc", "a_", false),
+        ("abcbc", "a%bc", true),
+        ("abcbd", "a%bc", false),
+        ("a%c", r"a\%c", true),
+        ("abc", r"a\%c", false),
+        ("a_c", r"a\_c", true),
+        ("a\\c", r"a\\c", true),
+        ("ünïcødé", "_n%d_", true),
+    ];
+    for (value, pattern, expected) in cases {
+        expect_eq!(
+            like_matches(value, pattern),
+            expected,
+            "'{value}' LIKE '{pattern}'"
+        );
+    }
+}
+
+#[test]
+fn test_like_pattern_prefix() {
+    assert_eq!(like_pattern_prefix("abc%"), ("abc".to_string(), false));
+    assert_eq!(like_pattern_prefix("ab_c"), ("ab".to_string(), false));
+    assert_eq!(like_pattern_prefix("%abc"), ("".to_string(), false));
+    assert_eq!(like_pattern_prefix(r"a\%b%"), ("a%b".to_string(), false));
+    assert_eq!(like_pattern_prefix("abc"), ("abc".to_string(), true));
+
+    assert_eq!(prefix_upper_bound("abc"), Some("abd".to_string()));
+    assert_eq!(
+        prefix_upper_bound("a\u{D7FF}"),
+        Some("a\u{E000}".to_string())
+    );
+    assert_eq!(prefix_upper_bound("a\u{10FFFF}"), Some("b".to_string()));
+    assert_eq!(prefix_upper_bound("\u{10FFFF}"), None);
+}
+
+#[test]
+fn test_eval_like() {
+    let col = &column_name!("x");
+    let pattern = |p: &str| Scalar::from(p);
+
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::from("Hello"));
+    expect_eq!(
+        filter.eval_pred_like(col, &pattern("He%"), false, false),
+        Some(true),
+        "x LIKE 'He%' (x = 'Hello')"
+    );
+    expect_eq!(
+        filter.eval_pred_like(col, &pattern("He%"), false, true),
+        Some(false),
+        "x NOT LIKE 'He%' (x = 'Hello')"
+    );
+    expect_eq!(
+        filter.eval_pred_like(col, &pattern("he%"), false, false),
+        Some(false),
+        "x LIKE 'he%' (x = 'Hello')"
+    );
+    expect_eq!(
+        filter.eval_pred_like(col, &pattern("he%"), true, false),
+        Some(true),
+        "x ILIKE 'he%' (x = 'Hello')"
+    );
+
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::Null(DataType::STRING));
+    expect_eq!(
+        filter.eval_pred_like(col, &pattern("He%"), false, false),
+        None,
+        "x LIKE 'He%' (x = NULL)"
+    );
+
+    // routing through eval_pred
+    let filter = DefaultKernelPredicateEvaluator::from(Scalar::from("Hello"));
+    let pred = Pred::like(column_expr!("x"), "%llo");
+    expect_eq!(
+        filter.eval(&pred),
+        Some(true),
+        "x LIKE '%llo' (x = 'Hello')"
+    );
+    let pred = Pred::like_with_escape(column_expr!("x"), "Hello#%", '#');
+    expect_eq!(
+        filter.eval(&pred),
+        Some(false),
+        "x LIKE 'Hello#%' ESCAPE '#' (x = 'Hello')"
+    );
+}
+
 // NOTE: We're testing routing here -- the actual comparisons are already validated by test_eval_binary_scalars.
 #[test]
 fn test_eval_binary_columns() {
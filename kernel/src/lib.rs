@@ -49,6 +49,16 @@
 //! is exposed via the [`JsonHandler`] and [`ParquetHandler`] respectively. When reading files,
 //! connectors are asked to provide the context information they require to execute the actual
 //! operation. This is done by invoking methods on the [`StorageHandler`] trait.
+//!
+//! # Concurrency
+//!
+//! Kernel doesn't spawn threads itself (except within the default engine), but its types are
+//! designed to be used by multithreaded engines. [`Engine`]s and the [`EngineData`] they return
+//! must be `Send + Sync`. A [`Snapshot`] is immutable and shared as a [`SnapshotRef`] (an `Arc`),
+//! and a [`Scan`](scan::Scan) is `Send + Sync` and cheap to clone, so both can be shared across
+//! threads without locking. [`ScanMetadata`](scan::ScanMetadata) is `Send + Sync` as well, so the
+//! files of a scan can be planned on one thread and read on others, as the
+//! [read-table-multi-threaded] example does.
 
 #![cfg_attr(all(doc, NIGHTLY_CHANNEL), feature(doc_auto_cfg))]
 #![warn(
@@ -88,14 +98,23 @@ use self::schema::{DataType, SchemaRef};
 
 mod action_reconciliation;
 pub mod actions;
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+pub mod analyze;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+#[cfg(feature = "async-engine")]
+pub mod async_engine;
 pub mod checkpoint;
 pub mod engine_data;
 pub mod error;
 pub mod expressions;
 mod log_compaction;
 mod log_path;
+mod log_store;
+pub mod restore;
 pub mod scan;
 pub mod schema;
+pub mod shallow_clone;
 pub mod snapshot;
 pub mod table_changes;
 pub mod table_configuration;
@@ -103,6 +122,7 @@ pub mod table_features;
 pub mod table_properties;
 pub mod transaction;
 pub(crate) mod transforms;
+pub mod vacuum;
 
 pub use log_path::LogPath;
 
@@ -530,10 +550,27 @@ pub trait StorageHandler: AsAny {
         -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<FileMeta>>>>;
 
     /// Read data specified by the start and end offset from the file.
+    ///
+    /// The data of the files is returned in the order of `files`. If a range is given, only the
+    /// bytes in that range are returned, otherwise the whole file.
     fn read_files(
         &self,
         files: Vec<FileSlice>,
     ) -> DeltaResult<Box<dyn Iterator<Item = DeltaResult<Bytes>>>>;
+
+    /// Delete the given files. Files that don't exist are ignored, so that retrying a partially
+    /// failed deletion succeeds.
+    ///
+    /// The default implementation returns an [`Error::Unsupported`] error, for engines that never
+    /// delete files (deleting files is only needed by [`VacuumPlan::execute`]).
+    ///
+    /// [`VacuumPlan::execute`]: crate::vacuum::VacuumPlan::execute
+    fn delete(&self, files: Vec<Url>) -> DeltaResult<()> {
+        Err(Error::unsupported(format!(
+            "This StorageHandler does not support deleting files (deleting {} files)",
+            files.len()
+        )))
+    }
 }
 
 /// Provides JSON handling functionality to Delta Kernel.
@@ -607,6 +644,75 @@ pub trait JsonHandler: AsAny {
     ) -> DeltaResult<()>;
 }
 
+/// Writes commit files to the Delta log.
+///
+/// Multiple writers committing to the same table concurrently is only safe if a commit file is
+/// written atomically and only if it doesn't exist yet ("put-if-absent"): exactly one of the
+/// writers attempting to commit a given version must succeed. How to achieve this depends on the
+/// storage system, e.g. conditional writes on object stores or an atomic rename on file systems.
+///
+/// Engines that don't provide a [`LogStore`] (see [`Engine::log_store`]) write commits with
+/// [`JsonHandler::write_json_file`], which must then provide these guarantees itself.
+pub trait LogStore: AsAny {
+    /// Atomically write the commit file at `commit_path`, containing one JSON object per row of
+    /// the given `actions` (as written by [`JsonHandler::write_json_file`]). The write must fail
+    /// with [`Error::FileAlreadyExists`] if the commit file already exists, in which case the
+    /// existing commit file must be left unchanged.
+    fn write_commit(
+        &self,
+        commit_path: &Url,
+        actions: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+    ) -> DeltaResult<()>;
+}
+
+/// The compression codec used by [`ParquetHandler::write_parquet_file`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParquetCompression {
+    /// No compression
+    Uncompressed,
+    /// Snappy compression (the default, as for Delta data files written by Spark)
+    #[default]
+    Snappy,
+    /// Gzip compression, at the codec's default level
+    Gzip,
+    /// Zstandard compression, at the codec's default level
+    Zstd,
+    /// LZ4 compression (the `LZ4_RAW` parquet codec)
+    Lz4,
+}
+
+/// Options that control how [`ParquetHandler::write_parquet_file`] writes a parquet file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParquetWriteOptions {
+    /// The compression codec for all columns.
+    pub compression: ParquetCompression,
+    /// The maximum number of rows in each row group, or `None` to use the engine's default.
+    pub max_row_group_size: Option<usize>,
+}
+
+impl ParquetWriteOptions {
+    /// Use the given compression codec for all columns.
+    pub fn with_compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Limit each row group to at most `max_row_group_size` rows.
+    pub fn with_max_row_group_size(mut self, max_row_group_size: usize) -> Self {
+        self.max_row_group_size = Some(max_row_group_size);
+        self
+    }
+}
+
+/// Metadata of a parquet file written by [`ParquetHandler::write_parquet_file`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParquetFileMetadata {
+    /// The location, modification time and size of the written file.
+    pub file_meta: FileMeta,
+    /// The number of records in the written file.
+    pub num_records: u64,
+}
+
 /// Provides Parquet file related functionalities to Delta Kernel.
 ///
 /// Connectors can leverage this trait to provide their own custom
@@ -686,6 +792,35 @@ pub trait ParquetHandler: AsAny {
         physical_schema: SchemaRef,
         predicate: Option<PredicateRef>,
     ) -> DeltaResult<FileDataReadResultIterator>;
+
+    /// Write `data` as a single parquet file at `location`, overwriting any existing file, and
+    /// return the metadata of the written file.
+    ///
+    /// Any field of `data` whose schema carries [`ColumnMetadataKey::ParquetFieldId`] metadata
+    /// (as the physical schema does under column mapping) must be written with that field ID, so
+    /// that the file can later be read by field ID (see [`Self::read_parquet_files`]).
+    ///
+    /// # Parameters
+    ///
+    /// - `location` - URL specifying the location of the parquet file to write
+    /// - `data` - The data to write, whose columns become the columns of the parquet file
+    /// - `options` - Compression and row group sizing for the written file
+    ///
+    /// The default implementation returns an [`Error::Unsupported`] error, for engines that only
+    /// read parquet files.
+    ///
+    /// [`ColumnMetadataKey::ParquetFieldId`]: crate::schema::ColumnMetadataKey
+    fn write_parquet_file(
+        &self,
+        location: &Url,
+        data: Box<dyn EngineData>,
+        options: &ParquetWriteOptions,
+    ) -> DeltaResult<ParquetFileMetadata> {
+        let _ = (data, options);
+        Err(Error::unsupported(format!(
+            "This ParquetHandler does not support writing parquet files (writing {location})"
+        )))
+    }
 }
 
 /// The `Engine` trait encapsulates all the functionality an engine or connector needs to provide
@@ -705,6 +840,12 @@ pub trait Engine: AsAny {
 
     /// Get the connector provided [`ParquetHandler`].
     fn parquet_handler(&self) -> Arc<dyn ParquetHandler>;
+
+    /// Get the connector provided [`LogStore`], used to write commits. By default, commits are
+    /// written with [`JsonHandler::write_json_file`] without overwriting existing files.
+    fn log_store(&self) -> Arc<dyn LogStore> {
+        Arc::new(log_store::JsonHandlerLogStore::new(self.json_handler()))
+    }
 }
 
 // we have an 'internal' feature flag: default-engine-base, which is actually just the shared
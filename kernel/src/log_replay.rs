@@ -16,11 +16,13 @@
 use crate::actions::deletion_vector::DeletionVectorDescriptor;
 use crate::engine_data::{GetData, TypedGetData};
 use crate::scan::data_skipping::DataSkippingFilter;
+use crate::utils::StringInterner;
 use crate::{DeltaResult, EngineData};
 
 use delta_kernel_derive::internal_api;
 
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use tracing::debug;
 
@@ -28,17 +30,48 @@ use tracing::debug;
 /// of adds and removes during log replay.
 #[derive(Debug, Hash, Eq, PartialEq)]
 pub(crate) struct FileActionKey {
-    pub(crate) path: String,
+    pub(crate) path: Arc<str>,
     pub(crate) dv_unique_id: Option<String>,
 }
 
-impl FileActionKey {
-    pub(crate) fn new(path: impl Into<String>, dv_unique_id: Option<String>) -> Self {
-        let path = path.into();
+/// A [`FileActionKey`] whose path is borrowed from the batch of actions it was extracted from.
+#[derive(Debug)]
+pub(crate) struct FileActionKeyRef<'a> {
+    pub(crate) path: &'a str,
+    pub(crate) dv_unique_id: Option<String>,
+}
+
+impl<'a> FileActionKeyRef<'a> {
+    pub(crate) fn new(path: &'a str, dv_unique_id: Option<String>) -> Self {
         Self { path, dv_unique_id }
     }
 }
 
+/// The file actions seen so far during log replay (see [`FileActionDeduplicator`]).
+///
+/// The paths of the seen file actions are interned: a file is usually added and then removed in
+/// different commits, and is re-added whenever its deletion vector changes, so its path is only
+/// allocated once. This also lets the (much more numerous) file actions of checkpoint batches,
+/// whose paths were mostly never seen in a commit, be looked up without allocating.
+#[derive(Debug, Default)]
+pub(crate) struct SeenFileKeys {
+    keys: HashSet<FileActionKey>,
+    paths: StringInterner,
+}
+
+impl SeenFileKeys {
+    /// The number of distinct file actions seen.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
 /// Maintains state and provides functionality for deduplicating file actions during log replay.
 ///
 /// This struct is embedded in visitors to track which files have been seen across multiple
@@ -53,7 +86,7 @@ pub(crate) struct FileActionDeduplicator<'seen> {
     /// A set of (data file path, dv_unique_id) pairs that have been seen thus
     /// far in the log for deduplication. This is a mutable reference to the set
     /// of seen file keys that persists across multiple log batches.
-    seen_file_keys: &'seen mut HashSet<FileActionKey>,
+    seen_file_keys: &'seen mut SeenFileKeys,
     // TODO: Consider renaming to `is_commit_batch`, `deduplicate_batch`, or `save_batch`
     // to better reflect its role in deduplication logic.
     /// Whether we're processing a log batch (as opposed to a checkpoint)
@@ -70,7 +103,7 @@ pub(crate) struct FileActionDeduplicator<'seen> {
 
 impl<'seen> FileActionDeduplicator<'seen> {
     pub(crate) fn new(
-        seen_file_keys: &'seen mut HashSet<FileActionKey>,
+        seen_file_keys: &'seen mut SeenFileKeys,
         is_log_batch: bool,
         add_path_index: usize,
         remove_path_index: usize,
@@ -91,29 +124,41 @@ impl<'seen> FileActionDeduplicator<'seen> {
     /// should be ignored). If not already seen, register it so we can recognize future duplicates.
     /// Returns `true` if we have seen the file and should ignore it, `false` if we have not seen it
     /// and should process it.
-    pub(crate) fn check_and_record_seen(&mut self, key: FileActionKey) -> bool {
+    pub(crate) fn check_and_record_seen(&mut self, key: FileActionKeyRef<'_>) -> bool {
         // Note: each (add.path + add.dv_unique_id()) pair has a
         // unique Add + Remove pair in the log. For example:
         // https://github.com/delta-io/delta/blob/master/spark/src/test/resources/delta/table-with-dv-large/_delta_log/00000000000000000001.json
 
-        if self.seen_file_keys.contains(&key) {
-            debug!(
-                "Ignoring duplicate ({}, {:?}) in scan, is log {}",
-                key.path, key.dv_unique_id, self.is_log_batch
-            );
-            true
-        } else {
-            debug!(
-                "Including ({}, {:?}) in scan, is log {}",
-                key.path, key.dv_unique_id, self.is_log_batch
-            );
-            if self.is_log_batch {
-                // Remember file actions from this batch so we can ignore duplicates as we process
-                // batches from older commit and/or checkpoint files. We don't track checkpoint
-                // batches because they are already the oldest actions and never replace anything.
-                self.seen_file_keys.insert(key);
+        let seen = &mut *self.seen_file_keys;
+        // A path that was never interned belongs to no seen file action
+        let path = match seen.paths.get(key.path) {
+            Some(path) => Some(path.clone()),
+            None if self.is_log_batch => Some(seen.paths.intern(key.path)),
+            None => None,
+        };
+        let key = path.map(|path| FileActionKey {
+            path,
+            dv_unique_id: key.dv_unique_id,
+        });
+        match key {
+            Some(key) if seen.keys.contains(&key) => {
+                debug!(
+                    "Ignoring duplicate ({}, {:?}) in scan, is log {}",
+                    key.path, key.dv_unique_id, self.is_log_batch
+                );
+                true
+            }
+            key => {
+                debug!("Including {key:?} in scan, is log {}", self.is_log_batch);
+                if let Some(key) = key.filter(|_| self.is_log_batch) {
+                    // Remember file actions from this batch so we can ignore duplicates as we
+                    // process batches from older commit and/or checkpoint files. We don't track
+                    // checkpoint batches because they are already the oldest actions and never
+                    // replace anything.
+                    seen.keys.insert(key);
+                }
+                false
             }
-            false
         }
     }
 
@@ -124,10 +169,10 @@ impl<'seen> FileActionDeduplicator<'seen> {
     /// - `dv_start_index` retrieves the storage type (`deletionVector.storageType`).
     /// - `dv_start_index + 1` retrieves the path or inline deletion vector (`deletionVector.pathOrInlineDv`).
     /// - `dv_start_index + 2` retrieves the optional offset (`deletionVector.offset`).
-    fn extract_dv_unique_id<'a>(
+    fn extract_dv_unique_id<'a, G: GetData<'a> + ?Sized>(
         &self,
         i: usize,
-        getters: &[&'a dyn GetData<'a>],
+        getters: &[&'a G],
         dv_start_index: usize,
     ) -> DeltaResult<Option<String>> {
         match getters[dv_start_index].get_opt(i, "deletionVector.storageType")? {
@@ -159,16 +204,16 @@ impl<'seen> FileActionDeduplicator<'seen> {
     /// - `Ok(Some((key, is_add)))`: When a file action is found, returns the key and whether it's an add operation
     /// - `Ok(None)`: When no file action is found
     /// - `Err(...)`: On any error during extraction
-    pub(crate) fn extract_file_action<'a>(
+    pub(crate) fn extract_file_action<'a, G: GetData<'a> + ?Sized>(
         &self,
         i: usize,
-        getters: &[&'a dyn GetData<'a>],
+        getters: &[&'a G],
         skip_removes: bool,
-    ) -> DeltaResult<Option<(FileActionKey, bool)>> {
+    ) -> DeltaResult<Option<(FileActionKeyRef<'a>, bool)>> {
         // Try to extract an add action by the required path column
         if let Some(path) = getters[self.add_path_index].get_str(i, "add.path")? {
             let dv_unique_id = self.extract_dv_unique_id(i, getters, self.add_dv_start_index)?;
-            return Ok(Some((FileActionKey::new(path, dv_unique_id), true)));
+            return Ok(Some((FileActionKeyRef::new(path, dv_unique_id), true)));
         }
 
         // The AddRemoveDedupVisitor skips remove actions when extracting file actions from a checkpoint batch.
@@ -179,7 +224,7 @@ impl<'seen> FileActionDeduplicator<'seen> {
         // Try to extract a remove action by the required path column
         if let Some(path) = getters[self.remove_path_index].get_str(i, "remove.path")? {
             let dv_unique_id = self.extract_dv_unique_id(i, getters, self.remove_dv_start_index)?;
-            return Ok(Some((FileActionKey::new(path, dv_unique_id), false)));
+            return Ok(Some((FileActionKeyRef::new(path, dv_unique_id), false)));
         }
 
         // No file action found
@@ -324,6 +369,27 @@ pub(crate) trait LogReplayProcessor: Sized {
             })
     }
 
+    /// Like [`Self::process_actions_iter`], but for a stream of [`ActionsBatch`]s, e.g. read with
+    /// an [`AsyncEngine`].
+    ///
+    /// [`AsyncEngine`]: crate::async_engine::AsyncEngine
+    #[cfg(feature = "async-engine")]
+    fn process_actions_stream(
+        mut self,
+        action_stream: impl futures::Stream<Item = DeltaResult<ActionsBatch>>,
+    ) -> impl futures::Stream<Item = DeltaResult<Self::Output>> {
+        use futures::{future, StreamExt as _};
+
+        action_stream
+            .map(move |actions_batch| self.process_actions_batch(actions_batch?))
+            .filter(|res| {
+                let has_selected_rows = res
+                    .as_ref()
+                    .map_or(true, |result| result.has_selected_rows());
+                future::ready(has_selected_rows)
+            })
+    }
+
     /// Builds the initial selection vector for the action batch, used to filter out rows that
     /// are not relevant to the current processor's purpose (e.g., checkpointing, scanning).
     /// This method performs a first pass of filtering using an optional [`DataSkippingFilter`].
@@ -334,13 +400,18 @@ pub(crate) trait LogReplayProcessor: Sized {
     ///
     /// # Parameters
     /// - `batch`: A reference to the batch of actions to be processed.
+    /// - `is_log_batch`: Whether the batch is from a commit log (`true`) or a checkpoint (`false`).
     ///
     /// # Returns
     /// A `DeltaResult<Vec<bool>>`, where each boolean indicates if the corresponding row should be included.
     /// If no filter is provided, all rows are selected.
-    fn build_selection_vector(&self, batch: &dyn EngineData) -> DeltaResult<Vec<bool>> {
+    fn build_selection_vector(
+        &self,
+        batch: &dyn EngineData,
+        is_log_batch: bool,
+    ) -> DeltaResult<Vec<bool>> {
         match self.data_skipping_filter() {
-            Some(filter) => filter.apply(batch),
+            Some(filter) => filter.apply(batch, is_log_batch),
             None => Ok(vec![true; batch.len()]), // If no filter is provided, select all rows
         }
     }
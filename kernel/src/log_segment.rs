@@ -8,11 +8,13 @@ use crate::actions::{
     get_log_schema, Metadata, Protocol, ADD_NAME, METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
     SIDECAR_NAME,
 };
+#[cfg(feature = "async-engine")]
+use crate::async_engine;
 use crate::last_checkpoint_hint::LastCheckpointHint;
 use crate::log_replay::ActionsBatch;
 use crate::path::{LogPathFileType, ParsedLogPath};
 use crate::schema::SchemaRef;
-use crate::utils::require;
+use crate::utils::{enter_span, new_span, require};
 use crate::{
     DeltaResult, Engine, EngineData, Error, Expression, FileMeta, ParquetHandler, Predicate,
     PredicateRef, RowVisitor, StorageHandler, Version,
@@ -24,6 +26,10 @@ pub use crate::listed_log_files::ListedLogFiles;
 #[cfg(not(feature = "internal-api"))]
 use crate::listed_log_files::ListedLogFiles;
 
+#[cfg(feature = "async-engine")]
+use futures::future;
+#[cfg(feature = "async-engine")]
+use futures::stream::{self, BoxStream, StreamExt as _, TryStreamExt as _};
 use itertools::Itertools;
 use tracing::{debug, warn};
 use url::Url;
@@ -31,6 +37,17 @@ use url::Url;
 #[cfg(test)]
 mod tests;
 
+/// The batches of actions read from the commit files of a [`LogSegment`], newest first.
+pub(crate) type CommitBatches = Vec<Box<dyn EngineData>>;
+
+// filter out log files that do not contain metadata or protocol information
+static META_PREDICATE: LazyLock<Option<PredicateRef>> = LazyLock::new(|| {
+    Some(Arc::new(Predicate::or(
+        Expression::column([METADATA_NAME, "id"]).is_not_null(),
+        Expression::column([PROTOCOL_NAME, "minReaderVersion"]).is_not_null(),
+    )))
+});
+
 /// A [`LogSegment`] represents a contiguous section of the log and is made of checkpoint files
 /// and commit files and guarantees the following:
 ///     1. Commit file versions will not have any gaps between them.
@@ -182,6 +199,15 @@ impl LogSegment {
             _ => ListedLogFiles::list(storage, &log_root, log_tail, None, time_travel_version)?,
         };
 
+        // The log of a table always has a commit or checkpoint for its latest version. (An older
+        // version may legitimately have none left, e.g. after its commits were cleaned up.)
+        require!(
+            time_travel_version.is_some()
+                || !listed_files.ascending_commit_files.is_empty()
+                || !listed_files.checkpoint_parts.is_empty(),
+            Error::not_a_delta_table(format!("No commits or checkpoints found in {log_root}"))
+        );
+
         LogSegment::try_new(listed_files, log_root, time_travel_version)
     }
 
@@ -224,7 +250,6 @@ impl LogSegment {
         LogSegment::try_new(listed_files, log_root, end_version)
     }
 
-    #[allow(unused)]
     /// Constructs a [`LogSegment`] to be used for timestamp conversion. This [`LogSegment`] will
     /// consist only of contiguous commit files up to `end_version` (inclusive). If present,
     /// `limit` specifies the maximum length of the returned log segment. The log segment may be
@@ -358,24 +383,13 @@ impl LogSegment {
     /// sidecar files contain the actual file actions that would otherwise be
     /// stored directly in the checkpoint. The sidecar file batches are chained to the
     /// checkpoint batch in the top level iterator to be returned.
-    fn create_checkpoint_stream(
+    pub(crate) fn create_checkpoint_stream(
         &self,
         engine: &dyn Engine,
         checkpoint_read_schema: SchemaRef,
         meta_predicate: Option<PredicateRef>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
-        let need_file_actions = checkpoint_read_schema.contains(ADD_NAME)
-            || checkpoint_read_schema.contains(REMOVE_NAME);
-
-        // Only validate sidecar requirement if we actually have checkpoint files
-        if !self.checkpoint_parts.is_empty() {
-            require!(
-                !need_file_actions || checkpoint_read_schema.contains(SIDECAR_NAME),
-                Error::invalid_checkpoint(
-                    "If the checkpoint read schema contains file actions, it must contain the sidecar column"
-                )
-            );
-        }
+        let need_file_actions = self.need_file_actions(&checkpoint_read_schema)?;
 
         let checkpoint_file_meta: Vec<_> = self
             .checkpoint_parts
@@ -416,7 +430,7 @@ impl LogSegment {
 
         let log_root = self.log_root.clone();
 
-        let actions_iter = actions
+        let mut actions_iter = actions
             .map(move |checkpoint_batch_result| -> DeltaResult<_> {
                 let checkpoint_batch = checkpoint_batch_result?;
                 // This closure maps the checkpoint batch to an iterator of batches
@@ -450,7 +464,46 @@ impl LogSegment {
             .flatten_ok()
             .map(|result| result?); // result-result to result
 
-        Ok(actions_iter)
+        // the checkpoint (and its sidecars) are read as the caller consumes the batches
+        let span = new_span!(
+            INFO,
+            "read_checkpoint",
+            log_root = %self.log_root,
+            version = self.checkpoint_version
+        );
+        Ok(std::iter::from_fn(move || {
+            span.in_scope(|| actions_iter.next())
+        }))
+    }
+
+    /// Returns whether reading the checkpoint with `checkpoint_read_schema` requires reading its
+    /// sidecar files, i.e. whether the schema contains file actions.
+    fn need_file_actions(&self, checkpoint_read_schema: &SchemaRef) -> DeltaResult<bool> {
+        let need_file_actions = checkpoint_read_schema.contains(ADD_NAME)
+            || checkpoint_read_schema.contains(REMOVE_NAME);
+
+        // Only validate sidecar requirement if we actually have checkpoint files
+        if !self.checkpoint_parts.is_empty() {
+            require!(
+                !need_file_actions || checkpoint_read_schema.contains(SIDECAR_NAME),
+                Error::invalid_checkpoint(
+                    "If the checkpoint read schema contains file actions, it must contain the sidecar column"
+                )
+            );
+        }
+        Ok(need_file_actions)
+    }
+
+    /// Returns the sidecar files the given checkpoint batch references.
+    fn sidecar_files(log_root: &Url, batch: &dyn EngineData) -> DeltaResult<Vec<FileMeta>> {
+        // Visit the rows of the checkpoint batch to extract sidecar file references
+        let mut visitor = SidecarVisitor::default();
+        visitor.visit_rows_of(batch)?;
+        visitor
+            .sidecars
+            .iter()
+            .map(|sidecar| sidecar.to_filemeta(log_root))
+            .try_collect()
     }
 
     /// Processes sidecar files for the given checkpoint batch.
@@ -464,21 +517,13 @@ impl LogSegment {
         checkpoint_read_schema: SchemaRef,
         meta_predicate: Option<PredicateRef>,
     ) -> DeltaResult<Option<impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send>> {
-        // Visit the rows of the checkpoint batch to extract sidecar file references
-        let mut visitor = SidecarVisitor::default();
-        visitor.visit_rows_of(batch)?;
+        let sidecar_files = Self::sidecar_files(&log_root, batch)?;
 
         // If there are no sidecar files, return early
-        if visitor.sidecars.is_empty() {
+        if sidecar_files.is_empty() {
             return Ok(None);
         }
 
-        let sidecar_files: Vec<_> = visitor
-            .sidecars
-            .iter()
-            .map(|sidecar| sidecar.to_filemeta(&log_root))
-            .try_collect()?;
-
         // Read the sidecar files and return an iterator of sidecar file batches
         Ok(Some(parquet_handler.read_parquet_files(
             &sidecar_files,
@@ -487,23 +532,128 @@ impl LogSegment {
         )?))
     }
 
+    /// Like [`Self::read_actions`], but reads the log files with an [`AsyncEngine`], returning a
+    /// stream of [`ActionsBatch`]s.
+    ///
+    /// [`AsyncEngine`]: async_engine::AsyncEngine
+    #[cfg(feature = "async-engine")]
+    pub(crate) fn read_actions_async(
+        &self,
+        engine: &dyn async_engine::AsyncEngine,
+        commit_read_schema: SchemaRef,
+        checkpoint_read_schema: SchemaRef,
+        meta_predicate: Option<PredicateRef>,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<ActionsBatch>>> {
+        let commits_and_compactions = self.find_commit_cover();
+        let commit_stream = engine
+            .json_handler()
+            .read_json_files(
+                &commits_and_compactions,
+                commit_read_schema,
+                meta_predicate.clone(),
+            )?
+            .map_ok(|batch| ActionsBatch::new(batch, true));
+
+        let checkpoint_stream =
+            self.create_checkpoint_stream_async(engine, checkpoint_read_schema, meta_predicate)?;
+
+        Ok(commit_stream.chain(checkpoint_stream).boxed())
+    }
+
+    /// Like [`Self::create_checkpoint_stream`], but reads the checkpoint and its sidecar files with
+    /// an [`AsyncEngine`], returning a stream of [`ActionsBatch`]s.
+    ///
+    /// [`AsyncEngine`]: async_engine::AsyncEngine
+    #[cfg(feature = "async-engine")]
+    pub(crate) fn create_checkpoint_stream_async(
+        &self,
+        engine: &dyn async_engine::AsyncEngine,
+        checkpoint_read_schema: SchemaRef,
+        meta_predicate: Option<PredicateRef>,
+    ) -> DeltaResult<BoxStream<'static, DeltaResult<ActionsBatch>>> {
+        let need_file_actions = self.need_file_actions(&checkpoint_read_schema)?;
+
+        let checkpoint_file_meta: Vec<_> = self
+            .checkpoint_parts
+            .iter()
+            .map(|f| f.location.clone())
+            .collect();
+
+        let parquet_handler = engine.parquet_handler();
+        let actions = match self.checkpoint_parts.first() {
+            Some(parsed_log_path) if parsed_log_path.extension == "json" => {
+                engine.json_handler().read_json_files(
+                    &checkpoint_file_meta,
+                    checkpoint_read_schema.clone(),
+                    meta_predicate.clone(),
+                )?
+            }
+            Some(parsed_log_path) if parsed_log_path.extension == "parquet" => parquet_handler
+                .read_parquet_files(
+                    &checkpoint_file_meta,
+                    checkpoint_read_schema.clone(),
+                    meta_predicate.clone(),
+                )?,
+            Some(parsed_log_path) => {
+                return Err(Error::generic(format!(
+                    "Unsupported checkpoint file type: {}",
+                    parsed_log_path.extension,
+                )));
+            }
+            None => stream::empty().boxed(),
+        };
+
+        // Multi-part checkpoints never have sidecars
+        let read_sidecars = need_file_actions && checkpoint_file_meta.len() == 1;
+        let log_root = self.log_root.clone();
+        let actions = actions
+            .map_ok(move |checkpoint_batch| {
+                let sidecar_files = match read_sidecars {
+                    true => Self::sidecar_files(&log_root, checkpoint_batch.as_ref()),
+                    false => Ok(vec![]),
+                };
+                let sidecar_content = match sidecar_files {
+                    Ok(files) if files.is_empty() => Ok(stream::empty().boxed()),
+                    Ok(files) => parquet_handler.read_parquet_files(
+                        &files,
+                        checkpoint_read_schema.clone(),
+                        meta_predicate.clone(),
+                    ),
+                    Err(err) => Err(err),
+                };
+                match sidecar_content {
+                    Ok(sidecar_content) => stream::once(future::ready(Ok(checkpoint_batch)))
+                        .chain(sidecar_content)
+                        .boxed(),
+                    Err(err) => stream::once(future::ready(Err(err))).boxed(),
+                }
+            })
+            .try_flatten()
+            .map_ok(|batch| ActionsBatch::new(batch, false));
+        Ok(actions.boxed())
+    }
+
     // Do a lightweight protocol+metadata log replay to find the latest Protocol and Metadata in
     // the LogSegment
     pub(crate) fn protocol_and_metadata(
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<(Option<Metadata>, Option<Protocol>)> {
+        enter_span!(
+            INFO,
+            "protocol_and_metadata",
+            log_root = %self.log_root,
+            version = self.end_version
+        );
         let actions_batches = self.replay_for_metadata(engine)?;
         let (mut metadata_opt, mut protocol_opt) = (None, None);
         for actions_batch in actions_batches {
             let actions = actions_batch?.actions;
-            if metadata_opt.is_none() {
-                metadata_opt = Metadata::try_new_from_data(actions.as_ref())?;
-            }
-            if protocol_opt.is_none() {
-                protocol_opt = Protocol::try_new_from_data(actions.as_ref())?;
-            }
-            if metadata_opt.is_some() && protocol_opt.is_some() {
+            if Self::find_protocol_and_metadata(
+                actions.as_ref(),
+                &mut metadata_opt,
+                &mut protocol_opt,
+            )? {
                 // we've found both, we can stop
                 break;
             }
@@ -511,9 +661,81 @@ impl LogSegment {
         Ok((metadata_opt, protocol_opt))
     }
 
+    /// Like [`Self::read_metadata`], but reads every commit file in full with `commit_read_schema`
+    /// (which must include the protocol and metadata actions) and also returns the commit batches,
+    /// newest first, so that a later replay of the file actions need not read the commits again.
+    /// Only the protocol and metadata of the checkpoint are read, if the commits don't have them.
+    pub(crate) fn read_metadata_with_commits(
+        &self,
+        engine: &dyn Engine,
+        commit_read_schema: SchemaRef,
+    ) -> DeltaResult<(Metadata, Protocol, CommitBatches)> {
+        enter_span!(
+            INFO,
+            "protocol_and_metadata",
+            log_root = %self.log_root,
+            version = self.end_version
+        );
+        let (mut metadata_opt, mut protocol_opt) = (None, None);
+        let commit_batches: Vec<_> = engine
+            .json_handler()
+            .read_json_files(&self.find_commit_cover(), commit_read_schema, None)?
+            .map(|batch| -> DeltaResult<_> {
+                let batch = batch?;
+                Self::find_protocol_and_metadata(
+                    batch.as_ref(),
+                    &mut metadata_opt,
+                    &mut protocol_opt,
+                )?;
+                Ok(batch)
+            })
+            .try_collect()?;
+        if metadata_opt.is_none() || protocol_opt.is_none() {
+            let schema = get_log_schema().project(&[PROTOCOL_NAME, METADATA_NAME])?;
+            for actions_batch in
+                self.create_checkpoint_stream(engine, schema, META_PREDICATE.clone())?
+            {
+                let actions = actions_batch?.actions;
+                if Self::find_protocol_and_metadata(
+                    actions.as_ref(),
+                    &mut metadata_opt,
+                    &mut protocol_opt,
+                )? {
+                    break;
+                }
+            }
+        }
+        let (metadata, protocol) = Self::require_metadata_and_protocol(metadata_opt, protocol_opt)?;
+        Ok((metadata, protocol, commit_batches))
+    }
+
+    /// Fills in whichever of `metadata_opt` and `protocol_opt` is still missing from the actions of
+    /// `batch`, which is newer than any batch seen before. Returns true once both are found.
+    fn find_protocol_and_metadata(
+        batch: &dyn EngineData,
+        metadata_opt: &mut Option<Metadata>,
+        protocol_opt: &mut Option<Protocol>,
+    ) -> DeltaResult<bool> {
+        if metadata_opt.is_none() {
+            *metadata_opt = Metadata::try_new_from_data(batch)?;
+        }
+        if protocol_opt.is_none() {
+            *protocol_opt = Protocol::try_new_from_data(batch)?;
+        }
+        Ok(metadata_opt.is_some() && protocol_opt.is_some())
+    }
+
     // Get the most up-to-date Protocol and Metadata actions
     pub(crate) fn read_metadata(&self, engine: &dyn Engine) -> DeltaResult<(Metadata, Protocol)> {
-        match self.protocol_and_metadata(engine)? {
+        let (metadata_opt, protocol_opt) = self.protocol_and_metadata(engine)?;
+        Self::require_metadata_and_protocol(metadata_opt, protocol_opt)
+    }
+
+    fn require_metadata_and_protocol(
+        metadata_opt: Option<Metadata>,
+        protocol_opt: Option<Protocol>,
+    ) -> DeltaResult<(Metadata, Protocol)> {
+        match (metadata_opt, protocol_opt) {
             (Some(m), Some(p)) => Ok((m, p)),
             (None, Some(_)) => Err(Error::MissingMetadata),
             (Some(_), None) => Err(Error::MissingProtocol),
@@ -527,13 +749,6 @@ impl LogSegment {
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
         let schema = get_log_schema().project(&[PROTOCOL_NAME, METADATA_NAME])?;
-        // filter out log files that do not contain metadata or protocol information
-        static META_PREDICATE: LazyLock<Option<PredicateRef>> = LazyLock::new(|| {
-            Some(Arc::new(Predicate::or(
-                Expression::column([METADATA_NAME, "id"]).is_not_null(),
-                Expression::column([PROTOCOL_NAME, "minReaderVersion"]).is_not_null(),
-            )))
-        });
         // read the same protocol and metadata schema for both commits and checkpoints
         self.read_actions(engine, schema.clone(), schema, META_PREDICATE.clone())
     }
@@ -0,0 +1,30 @@
+//! The [`LogStore`] used by engines that don't provide their own.
+
+use std::sync::Arc;
+
+use url::Url;
+
+use crate::{DeltaResult, EngineData, JsonHandler, LogStore};
+
+/// A [`LogStore`] that writes commits with [`JsonHandler::write_json_file`], relying on the
+/// handler to not overwrite existing files atomically.
+pub(crate) struct JsonHandlerLogStore {
+    json_handler: Arc<dyn JsonHandler>,
+}
+
+impl JsonHandlerLogStore {
+    pub(crate) fn new(json_handler: Arc<dyn JsonHandler>) -> Self {
+        Self { json_handler }
+    }
+}
+
+impl LogStore for JsonHandlerLogStore {
+    fn write_commit(
+        &self,
+        commit_path: &Url,
+        actions: Box<dyn Iterator<Item = DeltaResult<Box<dyn EngineData>>> + Send + '_>,
+    ) -> DeltaResult<()> {
+        self.json_handler
+            .write_json_file(commit_path, actions, false)
+    }
+}
@@ -0,0 +1,372 @@
+//! This module implements RESTORE, i.e. restoring a table to the state of a previous version.
+//!
+//! The entry point for this API is [`Snapshot::restore`] (kernel has no separate table type: a
+//! snapshot of the latest version is the handle to write to a table, as for [transactions]).
+//! Restoring a table commits a new version whose table state matches the state of the restored
+//! version:
+//! 1. files of the restored version that are no longer in the table are added back,
+//! 2. files in the table that aren't part of the restored version are removed,
+//! 3. the metadata of the restored version (e.g. its schema and table properties) is restored, and
+//! 4. the protocol is upgraded to support the table features of the restored version, if needed.
+//!    The protocol is never downgraded, since the table may contain data written with features
+//!    that the restored version didn't use yet (e.g. in its history).
+//!
+//! All file actions are committed with `dataChange = true`, since restoring changes the data of
+//! the table. Restoring fails if any data file (or deletion vector file) of the restored version
+//! doesn't exist anymore, e.g. because it was deleted by a VACUUM. Like any other removal of files,
+//! restoring an append-only table fails if the table has files that the restored version didn't.
+//!
+//! ```no_run
+//! # use delta_kernel::{DeltaResult, Engine, Snapshot};
+//! # use delta_kernel::restore::RestoreTarget;
+//! # fn restore(engine: &dyn Engine) -> DeltaResult<()> {
+//! let url = delta_kernel::try_parse_uri("./tests/data/basic_partitioned")?;
+//! let snapshot = Snapshot::builder_for(url).build(engine)?;
+//! snapshot.restore(RestoreTarget::Version(0)).commit(engine)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Note that the existence of the restored files is checked by listing the table root with
+//! [`StorageHandler::list_from`], which must return all files under the table root (including those
+//! in partition directories), as object-store based implementations do.
+//!
+//! [`Snapshot::restore`]: crate::Snapshot::restore
+//! [transactions]: crate::Snapshot::transaction
+//! [`StorageHandler::list_from`]: crate::StorageHandler::list_from
+use std::collections::{HashMap, HashSet};
+
+use crate::action_reconciliation::log_replay::ActionReconciliationProcessor;
+use crate::actions::visitors::AddVisitor;
+use crate::actions::{
+    get_log_add_schema, get_log_metadata_schema, get_log_protocol_schema, get_log_remove_schema,
+    Add, Metadata, Protocol, Remove,
+};
+use crate::checkpoint::CHECKPOINT_ACTIONS_SCHEMA;
+use crate::engine_data::{GetData, RowVisitor, SelectionVector, TypedGetData as _};
+use crate::history_manager::{timestamp_to_version, TimestampBound};
+use crate::log_replay::LogReplayProcessor as _;
+use crate::schema::{ColumnName, DataType};
+use crate::snapshot::{Snapshot, SnapshotRef};
+use crate::transaction::CommitResult;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, EngineData, Error, IntoEngineData, Version};
+
+/// The version of the table to restore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreTarget {
+    /// Restore the given table version.
+    Version(Version),
+    /// Restore the latest table version committed at or before the given timestamp (in
+    /// milliseconds since the Unix epoch). The commit timestamp of a version is its in-commit
+    /// timestamp if in-commit timestamps are enabled, and the modification time of its commit file
+    /// otherwise.
+    Timestamp(i64),
+}
+
+/// Restores a table to the state of a previous version. Created with [`Snapshot::restore`].
+///
+/// [`Snapshot::restore`]: crate::Snapshot::restore
+#[derive(Debug)]
+pub struct Restore {
+    snapshot: SnapshotRef,
+    target: RestoreTarget,
+    engine_info: Option<String>,
+}
+
+impl Restore {
+    pub(crate) fn new(snapshot: SnapshotRef, target: RestoreTarget) -> Self {
+        Self {
+            snapshot,
+            target,
+            engine_info: None,
+        }
+    }
+
+    /// Set the engine info field of the commit info action of the restore commit.
+    pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
+        self.engine_info = Some(engine_info.into());
+        self
+    }
+
+    /// Commit a new table version that restores the table to the target version. Like
+    /// [`Transaction::commit`], this returns a [`CommitResult::Conflict`] if another writer
+    /// committed the next table version first.
+    ///
+    /// Note that this replays the log of both the current and the restored version, and lists all
+    /// files in the table directory.
+    ///
+    /// [`Transaction::commit`]: crate::transaction::Transaction::commit
+    pub fn commit(self, engine: &dyn Engine) -> DeltaResult<CommitResult> {
+        let target_version = self.target_version(engine)?;
+        require!(
+            target_version <= self.snapshot.version(),
+            Error::generic(format!(
+                "Cannot restore table to version {target_version}, which is newer than the current version {}",
+                self.snapshot.version()
+            ))
+        );
+        let target_snapshot = Snapshot::builder_for(self.snapshot.table_root().clone())
+            .at_version(target_version)
+            .build(engine)?;
+
+        let mut current_files = live_files(&self.snapshot, engine)?;
+        let mut files_to_add = vec![];
+        for (key, add) in live_files(&target_snapshot, engine)? {
+            if current_files.remove(&key).is_none() {
+                files_to_add.push(add);
+            }
+        }
+        let files_to_remove = current_files.into_values().collect();
+        self.ensure_files_exist(engine, &files_to_add, target_version)?;
+
+        let metadata = (target_snapshot.metadata() != self.snapshot.metadata())
+            .then(|| target_snapshot.metadata().clone());
+        let protocol = restored_protocol(self.snapshot.protocol(), target_snapshot.protocol())?;
+
+        let mut transaction = self
+            .snapshot
+            .clone()
+            .transaction()?
+            .with_operation("RESTORE".to_string())
+            .with_restore(RestoreActions {
+                files_to_add,
+                files_to_remove,
+                metadata,
+                protocol,
+            });
+        if let Some(engine_info) = self.engine_info {
+            transaction = transaction.with_engine_info(engine_info);
+        }
+        transaction.commit(engine)
+    }
+
+    /// Resolve the target of the restore to a table version.
+    fn target_version(&self, engine: &dyn Engine) -> DeltaResult<Version> {
+        let timestamp = match self.target {
+            RestoreTarget::Version(version) => return Ok(version),
+            RestoreTarget::Timestamp(timestamp) => timestamp,
+        };
+        timestamp_to_version(
+            &self.snapshot,
+            engine,
+            timestamp,
+            TimestampBound::AtOrBefore,
+        )
+        .map_err(|err| {
+            Error::generic(format!(
+                "Cannot restore table to timestamp {timestamp}: {err}"
+            ))
+        })
+    }
+
+    /// Ensure that the data files (and deletion vector files) to add back to the table still exist.
+    fn ensure_files_exist(
+        &self,
+        engine: &dyn Engine,
+        files_to_add: &[Add],
+        target_version: Version,
+    ) -> DeltaResult<()> {
+        if files_to_add.is_empty() {
+            return Ok(());
+        }
+        let table_root = self.snapshot.table_root();
+        let existing_files = engine
+            .storage_handler()
+            .list_from(table_root)?
+            .map(|file| Ok(file?.location.to_string()))
+            .collect::<DeltaResult<HashSet<_>>>()?;
+        for add in files_to_add {
+            let path = table_root.join(&add.path).map_err(|_| {
+                Error::generic(format!("Invalid file path in the log: {}", add.path))
+            })?;
+            let dv_path = match &add.deletion_vector {
+                Some(dv) => dv.absolute_path(table_root)?,
+                None => None,
+            };
+            for file in std::iter::once(path).chain(dv_path) {
+                require!(
+                    existing_files.contains(file.as_str()),
+                    Error::generic(format!(
+                        "Cannot restore table to version {target_version}: file {file} no longer exists"
+                    ))
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The protocol to commit when restoring a table with protocol `current` to a version with
+/// protocol `target`, if any. The protocol is upgraded to support the table features of both
+/// versions, and never downgraded.
+fn restored_protocol(current: &Protocol, target: &Protocol) -> DeltaResult<Option<Protocol>> {
+    let current_reader_features = current.effective_reader_features();
+    let current_writer_features = current.effective_writer_features();
+    let missing_reader_features: Vec<_> = target
+        .effective_reader_features()
+        .into_iter()
+        .filter(|feature| !current_reader_features.contains(feature))
+        .collect();
+    let missing_writer_features: Vec<_> = target
+        .effective_writer_features()
+        .into_iter()
+        .filter(|feature| !current_writer_features.contains(feature))
+        .collect();
+    if missing_reader_features.is_empty() && missing_writer_features.is_empty() {
+        return Ok(None);
+    }
+    let protocol = current.with_features(missing_reader_features, missing_writer_features)?;
+    protocol.ensure_write_supported()?;
+    Ok(Some(protocol))
+}
+
+/// The actions committed by a restore, see [`Transaction::with_restore`].
+///
+/// [`Transaction::with_restore`]: crate::transaction::Transaction::with_restore
+#[derive(Debug, Clone)]
+pub(crate) struct RestoreActions {
+    /// The files of the restored version to add back to the table.
+    files_to_add: Vec<Add>,
+    /// The files of the table (as described by their add actions) to remove.
+    files_to_remove: Vec<Add>,
+    /// The metadata of the restored version, if it differs from the current metadata.
+    metadata: Option<Metadata>,
+    /// The upgraded protocol, if the restored version uses features the table doesn't support.
+    protocol: Option<Protocol>,
+}
+
+impl RestoreActions {
+    /// Whether the restore removes any files from the table.
+    pub(crate) fn removes_files(&self) -> bool {
+        !self.files_to_remove.is_empty()
+    }
+
+    /// Convert the restore into protocol, metadata, add and remove actions, where the remove
+    /// actions are committed with the given `deletion_timestamp`.
+    pub(crate) fn into_engine_data(
+        self,
+        deletion_timestamp: i64,
+        engine: &dyn Engine,
+    ) -> Vec<DeltaResult<Box<dyn EngineData>>> {
+        let protocol = self
+            .protocol
+            .map(|protocol| protocol.into_engine_data(get_log_protocol_schema().clone(), engine));
+        let metadata = self
+            .metadata
+            .map(|metadata| metadata.into_engine_data(get_log_metadata_schema().clone(), engine));
+        let adds = self.files_to_add.into_iter().map(|add| {
+            let add = Add {
+                data_change: true,
+                ..add
+            };
+            add.into_engine_data(get_log_add_schema().clone(), engine)
+        });
+        let removes = self.files_to_remove.into_iter().map(|add| {
+            let remove = Remove {
+                path: add.path,
+                deletion_timestamp: Some(deletion_timestamp),
+                data_change: true,
+                extended_file_metadata: Some(true),
+                partition_values: Some(add.partition_values),
+                size: Some(add.size),
+                tags: add.tags,
+                deletion_vector: add.deletion_vector,
+                base_row_id: add.base_row_id,
+                default_row_commit_version: add.default_row_commit_version,
+            };
+            remove.into_engine_data(get_log_remove_schema().clone(), engine)
+        });
+        protocol
+            .into_iter()
+            .chain(metadata)
+            .chain(adds)
+            .chain(removes)
+            .collect()
+    }
+}
+
+/// Files are identified by their path and deletion vector, as in log replay.
+type FileKey = (String, Option<String>);
+
+/// The add actions of all files in the table at the given snapshot, keyed by file.
+pub(crate) fn live_files(
+    snapshot: &Snapshot,
+    engine: &dyn Engine,
+) -> DeltaResult<HashMap<FileKey, Add>> {
+    let actions = snapshot.log_segment().read_actions(
+        engine,
+        CHECKPOINT_ACTIONS_SCHEMA.clone(),
+        CHECKPOINT_ACTIONS_SCHEMA.clone(),
+        None,
+    )?;
+    let mut visitor = LiveFilesVisitor {
+        selection_vector: Default::default(),
+        files: HashMap::new(),
+    };
+    // all tombstones are treated as expired, so only the add actions of live files are selected
+    for batch in ActionReconciliationProcessor::new(i64::MAX, None).process_actions_iter(actions) {
+        let filtered_data = batch?.filtered_data;
+        visitor.selection_vector = filtered_data.selection_vector;
+        visitor.visit_rows_of(filtered_data.data.as_ref())?;
+    }
+    Ok(visitor.files)
+}
+
+/// Collects the selected add actions.
+struct LiveFilesVisitor {
+    selection_vector: SelectionVector,
+    files: HashMap<FileKey, Add>,
+}
+
+impl RowVisitor for LiveFilesVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        AddVisitor::names_and_types()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        for i in 0..row_count {
+            if !self.selection_vector.get(i).unwrap_or(true) {
+                continue;
+            }
+            if let Some(path) = getters[0].get_opt(i, "add.path")? {
+                let add = AddVisitor::visit_add(i, path, getters)?;
+                self.files
+                    .insert((add.path.clone(), add.dv_unique_id()), add);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_features::{ReaderFeature, WriterFeature};
+
+    #[test]
+    fn test_restored_protocol() {
+        let legacy = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        assert_eq!(restored_protocol(&legacy, &legacy).unwrap(), None);
+
+        // the protocol is never downgraded
+        let older = Protocol::try_new(1, 1, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        assert_eq!(restored_protocol(&legacy, &older).unwrap(), None);
+
+        // features of the restored version are added
+        let with_features = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::DeletionVectors]),
+            Some([WriterFeature::DeletionVectors, WriterFeature::AppendOnly]),
+        )
+        .unwrap();
+        let protocol = restored_protocol(&legacy, &with_features).unwrap().unwrap();
+        assert_eq!(protocol.min_reader_version(), 3);
+        assert_eq!(protocol.min_writer_version(), 7);
+        assert!(protocol.has_reader_feature(&ReaderFeature::DeletionVectors));
+        assert!(protocol.has_writer_feature(&WriterFeature::DeletionVectors));
+        assert!(protocol.has_writer_feature(&WriterFeature::AppendOnly));
+        assert!(protocol.has_writer_feature(&WriterFeature::Invariants));
+    }
+}
@@ -10,6 +10,14 @@ use crate::transaction::add_files_schema;
 use crate::utils::require;
 use crate::{DeltaResult, Engine, Error, Snapshot};
 
+/// The names of the physical columns that materialize the row IDs and row commit versions of a
+/// table, as given by the `delta.rowTracking.materialized*ColumnName` table properties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MaterializedRowTrackingColumns<'a> {
+    pub(crate) row_id: &'a str,
+    pub(crate) row_commit_version: &'a str,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct RowTrackingDomainMetadata {
@@ -105,7 +113,7 @@ impl RowTrackingVisitor {
 impl RowVisitor for RowTrackingVisitor {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| add_files_schema().leaves(None));
+            LazyLock::new(|| add_files_schema().column_names_and_types(None));
         NAMES_AND_TYPES.as_ref()
     }
 
@@ -131,8 +139,16 @@ impl RowVisitor for RowTrackingVisitor {
                             .to_string(),
                     )
                 })?;
-            batch_base_row_ids.push(current_hwm + 1);
-            current_hwm += num_records;
+            require!(
+                num_records >= 0,
+                Error::generic(format!(
+                    "numRecords must not be negative when row tracking is enabled, got {num_records}"
+                ))
+            );
+            // Fail rather than wrap around, which would assign duplicate row IDs
+            let overflow = || Error::generic("Row ID high water mark overflow");
+            batch_base_row_ids.push(current_hwm.checked_add(1).ok_or_else(overflow)?);
+            current_hwm = current_hwm.checked_add(num_records).ok_or_else(overflow)?;
         }
 
         self.base_row_id_batches.push(batch_base_row_ids);
@@ -189,7 +205,7 @@ mod tests {
     fn test_num_records_field_index() {
         // Verify that the correct numRecords field index is hard-coded in the RowTrackingVisitor
         let num_records_field_index = add_files_schema()
-            .leaves(None)
+            .column_names_and_types(None)
             .as_ref()
             .0
             .iter()
@@ -334,13 +350,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_visit_invalid_num_records() -> DeltaResult<()> {
+        let unit_mock = ();
+
+        let mut visitor = RowTrackingVisitor::new(Some(0), None);
+        let num_records_mock = MockGetData::new(vec![Some(3), Some(-1)]);
+        let getters = create_getters(&num_records_mock, &unit_mock);
+        let result = visitor.visit(2, &getters);
+        assert_result_error_with_message(result, "numRecords must not be negative");
+
+        let mut visitor = RowTrackingVisitor::new(Some(i64::MAX - 10), None);
+        let num_records_mock = MockGetData::new(vec![Some(5), Some(10)]);
+        let getters = create_getters(&num_records_mock, &unit_mock);
+        let result = visitor.visit(2, &getters);
+        assert_result_error_with_message(result, "Row ID high water mark overflow");
+
+        // A failed visit leaves the visitor untouched
+        assert!(visitor.base_row_id_batches.is_empty());
+        assert_eq!(visitor.row_id_high_water_mark, i64::MAX - 10);
+
+        Ok(())
+    }
+
     #[test]
     fn test_selected_column_names_and_types() {
         let visitor = RowTrackingVisitor::new(Some(0), None);
         let (names, types) = visitor.selected_column_names_and_types();
 
-        // Should return the same as add_files_schema().leaves(None)
-        let expected = add_files_schema().leaves(None);
+        // Should return the same as add_files_schema().column_names_and_types(None)
+        let expected = add_files_schema().column_names_and_types(None);
         assert_eq!(names, expected.as_ref().0);
         assert_eq!(types, expected.as_ref().1);
     }
@@ -1,11 +1,12 @@
 use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::sync::{Arc, LazyLock};
 
 use tracing::debug;
 
-use crate::actions::get_log_add_schema;
 use crate::actions::visitors::SelectionVectorVisitor;
+use crate::actions::{get_log_add_schema, ADD_NAME};
 use crate::error::DeltaResult;
 use crate::expressions::{
     column_expr, joined_column_expr, BinaryPredicateOp, ColumnName, Expression as Expr,
@@ -15,7 +16,9 @@ use crate::expressions::{
 use crate::kernel_predicates::{
     DataSkippingPredicateEvaluator, KernelPredicateEvaluator, KernelPredicateEvaluatorDefaults,
 };
+use crate::log_replay::ActionsBatch;
 use crate::schema::{DataType, PrimitiveType, SchemaRef, SchemaTransform, StructField, StructType};
+use crate::utils::enter_span;
 use crate::{
     Engine, EngineData, ExpressionEvaluator, JsonHandler, PredicateEvaluator, RowVisitor as _,
 };
@@ -41,18 +44,152 @@ mod tests;
 ///   predicate is dropped.
 #[cfg(test)]
 pub(crate) fn as_data_skipping_predicate(pred: &Pred) -> Option<Pred> {
-    DataSkippingPredicateCreator.eval(pred)
+    DataSkippingPredicateCreator::default().eval(pred)
 }
 
 /// Like `as_data_skipping_predicate`, but invokes [`KernelPredicateEvaluator::eval_sql_where`]
-/// instead of [`KernelPredicateEvaluator::eval`].
-fn as_sql_data_skipping_predicate(pred: &Pred) -> Option<Pred> {
-    DataSkippingPredicateCreator.eval_sql_where(pred)
+/// instead of [`KernelPredicateEvaluator::eval`]. The min/max stats of `collated_columns` are not
+/// used.
+fn as_sql_data_skipping_predicate(
+    pred: &Pred,
+    collated_columns: HashSet<ColumnName>,
+) -> Option<Pred> {
+    DataSkippingPredicateCreator { collated_columns }.eval_sql_where(pred)
+}
+
+/// Returns the (physical) names of all string columns in `schema` with a non-binary collation. The
+/// min/max stats of such columns are not ordered by the column's collation, so they are unsafe to
+/// use for data skipping. Columns whose collation cannot be parsed are conservatively included.
+fn get_collated_columns(schema: &StructType) -> HashSet<ColumnName> {
+    fn visit(
+        fields: &mut dyn Iterator<Item = &StructField>,
+        path: &mut Vec<String>,
+        collated_columns: &mut HashSet<ColumnName>,
+    ) {
+        for field in fields {
+            path.push(field.name().clone());
+            match field.data_type() {
+                DataType::Struct(inner) => visit(&mut inner.fields(), path, collated_columns),
+                &DataType::STRING => {
+                    let is_binary = match field.collation() {
+                        Ok(collation) => collation.is_none_or(|c| c.is_utf8_binary()),
+                        Err(_) => false,
+                    };
+                    if !is_binary {
+                        collated_columns.insert(ColumnName::new(path.clone()));
+                    }
+                }
+                _ => {}
+            }
+            path.pop();
+        }
+    }
+    let mut collated_columns = HashSet::new();
+    visit(&mut schema.fields(), &mut vec![], &mut collated_columns);
+    collated_columns
+}
+
+/// Returns the schema of the file statistics (`numRecords`, `nullCount`, `minValues` and
+/// `maxValues`) for the columns of `data_schema`, or `None` if `data_schema` has no columns. Stats
+/// may not be available for all columns, so all fields of the stats schema are nullable.
+pub(crate) fn stats_schema(data_schema: &StructType) -> Option<SchemaRef> {
+    // Convert all fields into nullable, as stats may not be available for all columns
+    // (and usually aren't for partition columns).
+    struct NullableStatsTransform;
+    impl<'a> SchemaTransform<'a> for NullableStatsTransform {
+        fn transform_struct_field(
+            &mut self,
+            field: &'a StructField,
+        ) -> Option<Cow<'a, StructField>> {
+            use Cow::*;
+            let field = match self.transform(&field.data_type)? {
+                Borrowed(_) if field.is_nullable() => Borrowed(field),
+                data_type => Owned(StructField {
+                    name: field.name.clone(),
+                    data_type: data_type.into_owned(),
+                    nullable: true,
+                    metadata: field.metadata.clone(),
+                }),
+            };
+            Some(field)
+        }
+    }
+
+    // Convert a min/max stats schema into a nullcount schema (all leaf fields are LONG)
+    struct NullCountStatsTransform;
+    impl<'a> SchemaTransform<'a> for NullCountStatsTransform {
+        fn transform_primitive(
+            &mut self,
+            _ptype: &'a PrimitiveType,
+        ) -> Option<Cow<'a, PrimitiveType>> {
+            Some(Cow::Owned(PrimitiveType::Long))
+        }
+    }
+
+    let stats_schema = NullableStatsTransform
+        .transform_struct(data_schema)?
+        .into_owned();
+
+    let nullcount_schema = NullCountStatsTransform
+        .transform_struct(&stats_schema)?
+        .into_owned();
+    Some(Arc::new(StructType::new_unchecked([
+        StructField::nullable("numRecords", DataType::LONG),
+        StructField::nullable("nullCount", nullcount_schema),
+        StructField::nullable("minValues", stats_schema.clone()),
+        StructField::nullable("maxValues", stats_schema),
+    ])))
+}
+
+/// Returns `schema` (a schema of actions) with a nullable `stats_parsed` field of type
+/// `stats_schema` added to the end of its `add` struct, to also read the parsed file statistics of
+/// add actions from checkpoints that have them (see `delta.checkpoint.writeStatsAsStruct`).
+pub(crate) fn with_parsed_stats(schema: &StructType, stats_schema: &SchemaRef) -> SchemaRef {
+    let fields = schema.fields().map(|field| match field.data_type() {
+        DataType::Struct(add) if field.name() == ADD_NAME => {
+            let stats_parsed = StructField::nullable("stats_parsed", stats_schema.as_ref().clone());
+            let add = StructType::new_unchecked(add.fields().cloned().chain([stats_parsed]));
+            StructField {
+                data_type: add.into(),
+                ..field.clone()
+            }
+        }
+        _ => field.clone(),
+    });
+    Arc::new(StructType::new_unchecked(fields))
+}
+
+/// Returns `schema` (a schema of actions) without the `stats` field of its `add` struct, to read add
+/// actions without the cost of reading (and, for commits, parsing) their JSON file statistics.
+pub(crate) fn without_stats(schema: &StructType) -> SchemaRef {
+    let fields = schema.fields().map(|field| match field.data_type() {
+        DataType::Struct(add) if field.name() == ADD_NAME => {
+            let add = StructType::new_unchecked(
+                add.fields()
+                    .filter(|field| field.name() != "stats")
+                    .cloned(),
+            );
+            StructField {
+                data_type: add.into(),
+                ..field.clone()
+            }
+        }
+        _ => field.clone(),
+    });
+    Arc::new(StructType::new_unchecked(fields))
+}
+
+/// Evaluators to select the parsed stats (`add.stats_parsed`) of checkpoint batches
+struct ParsedStatsSelector {
+    select_stats_evaluator: Arc<dyn ExpressionEvaluator>,
+    // true for add actions that have JSON stats but no parsed stats
+    missing_stats_evaluator: Arc<dyn PredicateEvaluator>,
 }
 
 pub(crate) struct DataSkippingFilter {
     stats_schema: SchemaRef,
     select_stats_evaluator: Arc<dyn ExpressionEvaluator>,
+    parsed_stats_selector: Option<ParsedStatsSelector>,
     skipping_evaluator: Arc<dyn PredicateEvaluator>,
     filter_evaluator: Arc<dyn PredicateEvaluator>,
     json_handler: Arc<dyn JsonHandler>,
@@ -76,56 +213,12 @@ impl DataSkippingFilter {
         let (predicate, referenced_schema) = physical_predicate?;
         debug!("Creating a data skipping filter for {:#?}", predicate);
 
-        // Convert all fields into nullable, as stats may not be available for all columns
-        // (and usually aren't for partition columns).
-        struct NullableStatsTransform;
-        impl<'a> SchemaTransform<'a> for NullableStatsTransform {
-            fn transform_struct_field(
-                &mut self,
-                field: &'a StructField,
-            ) -> Option<Cow<'a, StructField>> {
-                use Cow::*;
-                let field = match self.transform(&field.data_type)? {
-                    Borrowed(_) if field.is_nullable() => Borrowed(field),
-                    data_type => Owned(StructField {
-                        name: field.name.clone(),
-                        data_type: data_type.into_owned(),
-                        nullable: true,
-                        metadata: field.metadata.clone(),
-                    }),
-                };
-                Some(field)
-            }
-        }
-
-        // Convert a min/max stats schema into a nullcount schema (all leaf fields are LONG)
-        struct NullCountStatsTransform;
-        impl<'a> SchemaTransform<'a> for NullCountStatsTransform {
-            fn transform_primitive(
-                &mut self,
-                _ptype: &'a PrimitiveType,
-            ) -> Option<Cow<'a, PrimitiveType>> {
-                Some(Cow::Owned(PrimitiveType::Long))
-            }
-        }
-
-        let stats_schema = NullableStatsTransform
-            .transform_struct(&referenced_schema)?
-            .into_owned();
-
-        let nullcount_schema = NullCountStatsTransform
-            .transform_struct(&stats_schema)?
-            .into_owned();
-        let stats_schema = Arc::new(StructType::new_unchecked([
-            StructField::nullable("numRecords", DataType::LONG),
-            StructField::nullable("nullCount", nullcount_schema),
-            StructField::nullable("minValues", stats_schema.clone()),
-            StructField::nullable("maxValues", stats_schema),
-        ]));
+        let stats_schema = stats_schema(&referenced_schema)?;
 
         // Skipping happens in several steps:
         //
-        // 1. The stats selector fetches add.stats from the metadata
+        // 1. The stats selector fetches add.stats from the metadata (or add.stats_parsed, if
+        //    checkpoints are read with parsed stats)
         //
         // 2. The predicate (skipping evaluator) produces false for any file whose stats prove we
         //    can safely skip it. A value of true means the stats say we must keep the file, and
@@ -141,9 +234,13 @@ impl DataSkippingFilter {
             DataType::STRING,
         );
 
+        let collated_columns = get_collated_columns(&referenced_schema);
         let skipping_evaluator = engine.evaluation_handler().new_predicate_evaluator(
             stats_schema.clone(),
-            Arc::new(as_sql_data_skipping_predicate(&predicate)?),
+            Arc::new(as_sql_data_skipping_predicate(
+                &predicate,
+                collated_columns,
+            )?),
         );
 
         let filter_evaluator = engine
@@ -153,15 +250,114 @@ impl DataSkippingFilter {
         Some(Self {
             stats_schema,
             select_stats_evaluator,
+            parsed_stats_selector: None,
             skipping_evaluator,
             filter_evaluator,
             json_handler: engine.json_handler(),
         })
     }
 
-    /// Apply the DataSkippingFilter to an EngineData batch of actions. Returns a selection vector
-    /// which can be applied to the actions to find those that passed data skipping.
-    pub(crate) fn apply(&self, actions: &dyn EngineData) -> DeltaResult<Vec<bool>> {
+    /// Skip the files of checkpoint batches by their parsed stats rather than parsing their JSON
+    /// stats. The checkpoint batches must have been read with `add.stats_parsed` (see
+    /// [`with_parsed_stats`]) of the [`stats_schema`] of the predicate's referenced columns. Add
+    /// actions without parsed stats fall back to their JSON stats.
+    pub(crate) fn with_parsed_stats(mut self, engine: &dyn Engine) -> Self {
+        let input_schema = with_parsed_stats(get_log_add_schema(), &self.stats_schema);
+        let stats_expr = Expr::struct_from(
+            self.stats_schema
+                .fields()
+                .map(|field| Expr::column(["add", "stats_parsed", field.name()])),
+        );
+        let select_stats_evaluator = engine.evaluation_handler().new_expression_evaluator(
+            input_schema.clone(),
+            Arc::new(stats_expr),
+            self.stats_schema.as_ref().clone().into(),
+        );
+        let missing_stats_pred = Pred::and(
+            column_expr!("add.stats").is_not_null(),
+            column_expr!("add.stats_parsed").is_null(),
+        );
+        let missing_stats_evaluator = engine
+            .evaluation_handler()
+            .new_predicate_evaluator(input_schema, Arc::new(missing_stats_pred));
+        self.parsed_stats_selector = Some(ParsedStatsSelector {
+            select_stats_evaluator,
+            missing_stats_evaluator,
+        });
+        self
+    }
+
+    /// Apply the DataSkippingFilter to an EngineData batch of actions, read from a commit if
+    /// `is_log_batch` or else from a checkpoint. Returns a selection vector which can be applied to
+    /// the actions to find those that passed data skipping.
+    pub(crate) fn apply(
+        &self,
+        actions: &dyn EngineData,
+        is_log_batch: bool,
+    ) -> DeltaResult<Vec<bool>> {
+        enter_span!(DEBUG, "data_skipping", rows = actions.len(), is_log_batch);
+        let parsed_stats_selector = match self.parsed_stats_selector {
+            Some(ref selector) if !is_log_batch => selector,
+            _ => return self.apply_to_json_stats(actions),
+        };
+        let parsed_stats = parsed_stats_selector
+            .select_stats_evaluator
+            .evaluate(actions)?;
+        assert_eq!(parsed_stats.len(), actions.len());
+        let mut selection_vector = self.apply_to_parsed_stats(parsed_stats.as_ref())?;
+
+        // fall back to the JSON stats of any files that have no parsed stats
+        let missing_stats = parsed_stats_selector
+            .missing_stats_evaluator
+            .evaluate(actions)?;
+        let mut visitor = SelectionVectorVisitor::default();
+        visitor.visit_rows_of(missing_stats.as_ref())?;
+        if visitor.selection_vector.contains(&true) {
+            let json_selection_vector = self.apply_to_json_stats(actions)?;
+            for ((selected, missing), json_selected) in selection_vector
+                .iter_mut()
+                .zip(visitor.selection_vector)
+                .zip(json_selection_vector)
+            {
+                if missing {
+                    *selected = json_selected;
+                }
+            }
+        }
+        Ok(selection_vector)
+    }
+
+    /// Apply the DataSkippingFilter to each of `batches` on a separate thread (see
+    /// [`DataSkippingFilter::apply`]), returning the batches with their selection vectors in the
+    /// order of `batches`. Batches that failed to be read are passed through as-is.
+    pub(crate) fn apply_concurrently(
+        &self,
+        batches: Vec<DeltaResult<ActionsBatch>>,
+    ) -> Vec<DeltaResult<(ActionsBatch, Vec<bool>)>> {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = batches
+                .into_iter()
+                .map(|batch| {
+                    scope.spawn(move || {
+                        let batch = batch?;
+                        let selection_vector =
+                            self.apply(batch.actions.as_ref(), batch.is_log_batch)?;
+                        Ok((batch, selection_vector))
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .collect()
+        })
+    }
+
+    fn apply_to_json_stats(&self, actions: &dyn EngineData) -> DeltaResult<Vec<bool>> {
         // retrieve and parse stats from actions data
         let stats = self.select_stats_evaluator.evaluate(actions)?;
         assert_eq!(stats.len(), actions.len());
@@ -169,14 +365,17 @@ impl DataSkippingFilter {
             .json_handler
             .parse_json(stats, self.stats_schema.clone())?;
         assert_eq!(parsed_stats.len(), actions.len());
+        self.apply_to_parsed_stats(parsed_stats.as_ref())
+    }
 
+    fn apply_to_parsed_stats(&self, parsed_stats: &dyn EngineData) -> DeltaResult<Vec<bool>> {
         // evaluate the predicate on the parsed stats, then convert to selection vector
-        let skipping_predicate = self.skipping_evaluator.evaluate(&*parsed_stats)?;
-        assert_eq!(skipping_predicate.len(), actions.len());
+        let skipping_predicate = self.skipping_evaluator.evaluate(parsed_stats)?;
+        assert_eq!(skipping_predicate.len(), parsed_stats.len());
         let selection_vector = self
             .filter_evaluator
             .evaluate(skipping_predicate.as_ref())?;
-        assert_eq!(selection_vector.len(), actions.len());
+        assert_eq!(selection_vector.len(), parsed_stats.len());
 
         // visit the engine's selection vector to produce a Vec<bool>
         let mut visitor = SelectionVectorVisitor::default();
@@ -192,7 +391,11 @@ impl DataSkippingFilter {
     }
 }
 
-struct DataSkippingPredicateCreator;
+#[derive(Default)]
+struct DataSkippingPredicateCreator {
+    /// Collated string columns, whose min/max stats are not used for data skipping.
+    collated_columns: HashSet<ColumnName>,
+}
 
 impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
     type Output = Pred;
@@ -200,17 +403,12 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
 
     /// Retrieves the minimum value of a column, if it exists and has the requested type.
     fn get_min_stat(&self, col: &ColumnName, _data_type: &DataType) -> Option<Expr> {
-        Some(joined_column_expr!("minValues", col))
+        (!self.collated_columns.contains(col)).then(|| joined_column_expr!("minValues", col))
     }
 
     /// Retrieves the maximum value of a column, if it exists and has the requested type.
-    // TODO(#1002): we currently don't support file skipping on timestamp columns' max stat since
-    // they are truncated to milliseconds in add.stats.
-    fn get_max_stat(&self, col: &ColumnName, data_type: &DataType) -> Option<Expr> {
-        match data_type {
-            &DataType::TIMESTAMP | &DataType::TIMESTAMP_NTZ => None,
-            _ => Some(joined_column_expr!("maxValues", col)),
-        }
+    fn get_max_stat(&self, col: &ColumnName, _data_type: &DataType) -> Option<Expr> {
+        (!self.collated_columns.contains(col)).then(|| joined_column_expr!("maxValues", col))
     }
 
     /// Retrieves the null count of a column, if it exists.
@@ -223,6 +421,33 @@ impl DataSkippingPredicateEvaluator for DataSkippingPredicateCreator {
         Some(column_expr!("numRecords"))
     }
 
+    /// Timestamp max-stats in add.stats are truncated to milliseconds, so the true max of a file
+    /// can be up to 999 microseconds larger than the recorded stat. Comparisons that would skip a
+    /// file because its max is too small are therefore made against `val - 999us` instead, and
+    /// (in)equality against the max-stat is never used for skipping.
+    fn partial_cmp_max_stat(
+        &self,
+        col: &ColumnName,
+        val: &Scalar,
+        ord: Ordering,
+        inverted: bool,
+    ) -> Option<Pred> {
+        let max = self.get_max_stat(col, &val.data_type())?;
+        let adjusted = match (val, ord, inverted) {
+            (Scalar::Timestamp(_) | Scalar::TimestampNtz(_), Ordering::Equal, _) => return None,
+            (Scalar::Timestamp(micros), Ordering::Greater, false)
+            | (Scalar::Timestamp(micros), Ordering::Less, true) => {
+                Scalar::Timestamp(micros.checked_sub(999)?)
+            }
+            (Scalar::TimestampNtz(micros), Ordering::Greater, false)
+            | (Scalar::TimestampNtz(micros), Ordering::Less, true) => {
+                Scalar::TimestampNtz(micros.checked_sub(999)?)
+            }
+            _ => val.clone(),
+        };
+        self.eval_partial_cmp(ord, max, &adjusted, inverted)
+    }
+
     fn eval_partial_cmp(
         &self,
         ord: Ordering,
@@ -1,7 +1,10 @@
 use super::*;
 
 use crate::expressions::column_name;
-use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, UnimplementedColumnResolver};
+use crate::kernel_predicates::{
+    DefaultKernelPredicateEvaluator, UnimplementedColumnResolver,
+    MAX_IN_LIST_VALUES_FOR_DATA_SKIPPING,
+};
 use std::collections::HashMap;
 
 const TRUE: Option<bool> = Some(true);
@@ -289,7 +292,7 @@ fn test_sql_where() {
                 expect,
                 "{pred:#?} became {skipping_pred:#?} ({min}..{max}, {nulls} nulls)"
             );
-            let skipping_sql_pred = as_sql_data_skipping_predicate(pred).unwrap();
+            let skipping_sql_pred = as_sql_data_skipping_predicate(pred, HashSet::new()).unwrap();
             expect_eq!(
                 filter.eval(&skipping_sql_pred),
                 expect_sql,
@@ -340,39 +343,10 @@ fn test_sql_where() {
     do_test(ALL_NULL, pred, MISSING, None, None);
 }
 
-// TODO(#1002): we currently don't support file skipping on timestamp columns' max stat since they
-// are truncated to milliseconds in add.stats.
+// Timestamp max stats are truncated to milliseconds in add.stats, so max-stat comparisons are
+// widened by 999 microseconds and max-stat (in)equality never skips.
 #[test]
-fn test_timestamp_skipping_disabled() {
-    let creator = DataSkippingPredicateCreator;
-    let col = &column_name!("timestamp_col");
-
-    assert!(
-        creator.get_min_stat(col, &DataType::TIMESTAMP).is_some(),
-        "get_min_stat should return Some: allow data skipping on timestamp minValues"
-    );
-    assert_eq!(
-        creator.get_max_stat(col, &DataType::TIMESTAMP),
-        None,
-        "get_max_stat should return None: no data skipping on timestamp maxValues"
-    );
-    assert!(
-        creator
-            .get_min_stat(col, &DataType::TIMESTAMP_NTZ)
-            .is_some(),
-        "get_min_stat should return Some: allow data skipping on timestamp_ntz minValues"
-    );
-    assert_eq!(
-        creator.get_max_stat(col, &DataType::TIMESTAMP_NTZ),
-        None,
-        "get_max_stat should return None: no data skipping on timestamp_ntz maxValues"
-    );
-}
-
-// TODO(#1002): we currently don't support file skipping on timestamp columns' max stat since they
-// are truncated to milliseconds in add.stats.
-#[test]
-fn test_timestamp_predicates_dont_data_skip() {
+fn test_timestamp_predicates_data_skip() {
     let col = &column_expr!("ts_col");
     for timestamp in [&Scalar::Timestamp(1000000), &Scalar::TimestampNtz(1000000)] {
         // LT will do minValues -> OK
@@ -383,19 +357,26 @@ fn test_timestamp_predicates_dont_data_skip() {
             "Column(minValues.ts_col) < 1000000"
         );
 
-        // GT will do maxValues -> BLOCKED
+        // GT will do maxValues, adjusted for truncation
         let pred = Pred::gt(col.clone(), timestamp.clone());
         let skipping_pred = as_data_skipping_predicate(&pred);
-        assert!(
-            skipping_pred.is_none(),
-            "Expected no data skipping for timestamp predicate: {pred:#?}, got {skipping_pred:#?}"
+        assert_eq!(
+            skipping_pred.unwrap().to_string(),
+            "Column(maxValues.ts_col) > 999001"
+        );
+
+        let pred = Pred::ge(col.clone(), timestamp.clone());
+        let skipping_pred = as_data_skipping_predicate(&pred);
+        assert_eq!(
+            skipping_pred.unwrap().to_string(),
+            "NOT(Column(maxValues.ts_col) < 999001)"
         );
 
         let pred = Pred::eq(col.clone(), timestamp.clone());
         let skipping_pred = as_data_skipping_predicate(&pred);
         assert_eq!(
             skipping_pred.unwrap().to_string(),
-            "AND(NOT(Column(minValues.ts_col) > 1000000), null)"
+            "AND(NOT(Column(minValues.ts_col) > 1000000), NOT(Column(maxValues.ts_col) < 999001))"
         );
 
         let pred = Pred::ne(col.clone(), timestamp.clone());
@@ -405,4 +386,250 @@ fn test_timestamp_predicates_dont_data_skip() {
             "OR(NOT(Column(minValues.ts_col) = 1000000), null)"
         );
     }
+
+    // Adjusting the literal must not overflow
+    let pred = Pred::gt(col.clone(), Scalar::TimestampNtz(i64::MIN));
+    assert!(as_data_skipping_predicate(&pred).is_none());
+}
+
+#[test]
+fn test_in_list_data_skip() {
+    use crate::expressions::ArrayData;
+    use crate::schema::ArrayType;
+
+    let col = &column_expr!("x");
+    let list = |values: Vec<Scalar>| {
+        ArrayData::try_new(ArrayType::new(DataType::INTEGER, true), values).unwrap()
+    };
+
+    // some list value must fall in [min, max]
+    let pred = Pred::in_list(col.clone(), list(vec![5.into(), 1.into()]));
+    assert_eq!(
+        as_data_skipping_predicate(&pred).unwrap().to_string(),
+        "OR(AND(NOT(Column(minValues.x) > 5), NOT(Column(maxValues.x) < 5)), \
+         AND(NOT(Column(minValues.x) > 1), NOT(Column(maxValues.x) < 1)))"
+    );
+
+    // long lists only check that the range of the list values overlaps with [min, max]
+    let values = (1..=MAX_IN_LIST_VALUES_FOR_DATA_SKIPPING as i32 + 1).rev();
+    let pred = Pred::in_list(col.clone(), list(values.map(Scalar::from).collect()));
+    assert_eq!(
+        as_data_skipping_predicate(&pred).unwrap().to_string(),
+        "AND(NOT(Column(minValues.x) > 33), NOT(Column(maxValues.x) < 1))"
+    );
+
+    // NULL list values never match
+    let pred = Pred::in_list(
+        col.clone(),
+        list(vec![Scalar::Null(DataType::INTEGER), 2.into()]),
+    );
+    assert_eq!(
+        as_data_skipping_predicate(&pred).unwrap().to_string(),
+        "AND(NOT(Column(minValues.x) > 2), NOT(Column(maxValues.x) < 2))"
+    );
+    let pred = Pred::in_list(col.clone(), list(vec![Scalar::Null(DataType::INTEGER)]));
+    assert_eq!(
+        as_data_skipping_predicate(&pred).unwrap().to_string(),
+        "false"
+    );
+
+    // NOT IN cannot skip
+    let pred = Pred::not_in_list(col.clone(), list(vec![1.into(), 2.into()]));
+    assert!(as_data_skipping_predicate(&pred).is_none());
+
+    // direct evaluation over stats
+    let min_max = |min: i32, max: i32| {
+        HashMap::from([
+            (column_name!("minValues.x"), Scalar::from(min)),
+            (column_name!("maxValues.x"), Scalar::from(max)),
+        ])
+    };
+    let pred = Pred::in_list(
+        col.clone(),
+        list(vec![10.into(), 20.into(), 1000000.into()]),
+    );
+    let skipping_pred = as_data_skipping_predicate(&pred).unwrap();
+    for (min, max, expected) in [
+        (0, 5, false),
+        (0, 10, true),
+        (12, 15, false),
+        (12, 25, true),
+        (21, 30, false),
+        // stats that fall between list values
+        (500, 600, false),
+        (500, 2000000, true),
+    ] {
+        let filter = DefaultKernelPredicateEvaluator::from(min_max(min, max));
+        expect_eq!(
+            filter.eval(&skipping_pred),
+            Some(expected),
+            "x IN (10, 20, 1000000) (min = {min}, max = {max})"
+        );
+    }
+}
+
+#[test]
+fn test_like_data_skip() {
+    let col = &column_expr!("x");
+
+    // a literal prefix restricts the range of matching values
+    let pred = Pred::like(col.clone(), "abc%");
+    assert_eq!(
+        as_data_skipping_predicate(&pred).unwrap().to_string(),
+        "AND(NOT(Column(maxValues.x) < 'abc'), Column(minValues.x) < 'abd')"
+    );
+
+    // a pattern without wildcards is an equality check
+    let pred = Pred::like(col.clone(), r"a\%c");
+    assert_eq!(
+        as_data_skipping_predicate(&pred).unwrap().to_string(),
+        "AND(NOT(Column(minValues.x) > 'a%c'), NOT(Column(maxValues.x) < 'a%c'))"
+    );
+
+    // no literal prefix, case-insensitive, and NOT LIKE cannot skip
+    for pred in [
+        Pred::like(col.clone(), "%abc"),
+        Pred::ilike(col.clone(), "abc%"),
+        Pred::not_like(col.clone(), "abc%"),
+    ] {
+        assert!(as_data_skipping_predicate(&pred).is_none(), "{pred}");
+    }
+
+    // direct evaluation over stats
+    let min_max = |min: &str, max: &str| {
+        HashMap::from([
+            (column_name!("minValues.x"), Scalar::from(min)),
+            (column_name!("maxValues.x"), Scalar::from(max)),
+        ])
+    };
+    let pred = Pred::like(col.clone(), "ab_%");
+    let skipping_pred = as_data_skipping_predicate(&pred).unwrap();
+    for (min, max, expected) in [
+        ("a", "aa", false),
+        ("a", "ab", true),
+        ("abz", "b", true),
+        ("ac", "b", false),
+    ] {
+        let filter = DefaultKernelPredicateEvaluator::from(min_max(min, max));
+        expect_eq!(
+            filter.eval(&skipping_pred),
+            Some(expected),
+            "x LIKE 'ab_%' (min = {min}, max = {max})"
+        );
+    }
+}
+
+#[test]
+fn test_collated_columns_dont_data_skip() {
+    let schema = StructType::new_unchecked([
+        StructField::nullable("plain", DataType::STRING),
+        StructField::nullable("binary", DataType::STRING)
+            .add_metadata([("delta.collation", "SPARK.UTF8_BINARY")]),
+        StructField::nullable(
+            "nested",
+            StructType::new_unchecked([StructField::nullable("lcase", DataType::STRING)
+                .add_metadata([("delta.collation", "SPARK.UTF8_LCASE")])]),
+        ),
+        StructField::nullable("invalid", DataType::STRING)
+            .add_metadata([("delta.collation", "bogus")]),
+    ]);
+    let collated_columns = get_collated_columns(&schema);
+    assert_eq!(
+        collated_columns,
+        HashSet::from([column_name!("nested.lcase"), column_name!("invalid")])
+    );
+    let creator = DataSkippingPredicateCreator { collated_columns };
+
+    let pred = Pred::and(
+        Pred::gt(column_expr!("nested.lcase"), Scalar::from("a")),
+        Pred::gt(column_expr!("binary"), Scalar::from("a")),
+    );
+    let skipping_pred = creator.eval(&pred);
+    assert_eq!(
+        skipping_pred.unwrap().to_string(),
+        "AND(null, Column(maxValues.binary) > 'a')"
+    );
+
+    // null checks still use the null counts of collated columns
+    let pred = Pred::is_null(column_expr!("nested.lcase"));
+    let skipping_pred = creator.eval(&pred);
+    assert_eq!(
+        skipping_pred.unwrap().to_string(),
+        "NOT(Column(nullCount.nested.lcase) = 0)"
+    );
+
+    let pred = Pred::eq(column_expr!("invalid"), Scalar::from("a"));
+    let skipping_pred = creator.eval(&pred);
+    assert_eq!(skipping_pred.unwrap().to_string(), "AND(null)");
+}
+
+#[test]
+fn test_data_skipping_prefers_parsed_stats_of_checkpoints() {
+    use crate::arrow::array::StringArray;
+    use crate::engine::sync::SyncEngine;
+    use crate::utils::test_utils::string_array_to_engine_data;
+
+    let engine = SyncEngine::new();
+    let referenced_schema = Arc::new(StructType::new_unchecked([StructField::nullable(
+        "x",
+        DataType::INTEGER,
+    )]));
+    let predicate = Arc::new(Pred::gt(column_expr!("x"), Expr::literal(10)));
+    let filter = || {
+        DataSkippingFilter::new(
+            &engine,
+            Some((predicate.clone(), referenced_schema.clone())),
+        )
+        .unwrap()
+    };
+
+    // The JSON and parsed stats of the first two files disagree, to tell which were used. The third
+    // file has no parsed stats, and the last row is not an add action.
+    let stats = |max: i32| {
+        format!(
+            r#"{{"numRecords":1,"nullCount":{{"x":0}},"minValues":{{"x":{max}}},"maxValues":{{"x":{max}}}}}"#
+        )
+    };
+    let add = |path: &str, json_max: i32, parsed_max: Option<i32>| {
+        let stats_parsed = parsed_max.map_or("null".to_string(), stats);
+        format!(
+            r#"{{"add":{{"path":"{path}","partitionValues":{{}},"size":1,"modificationTime":0,"dataChange":true,"stats":{},"stats_parsed":{stats_parsed}}}}}"#,
+            serde_json::to_string(&stats(json_max)).unwrap()
+        )
+    };
+    let json_strings: StringArray = vec![
+        add("file1", 5, Some(20)),
+        add("file2", 20, Some(5)),
+        add("file3", 5, None),
+        "{}".to_string(),
+    ]
+    .into();
+    let stats_schema = stats_schema(&referenced_schema).unwrap();
+    let actions = engine
+        .json_handler()
+        .parse_json(
+            string_array_to_engine_data(json_strings),
+            with_parsed_stats(get_log_add_schema(), &stats_schema),
+        )
+        .unwrap();
+
+    let filter_with_parsed_stats = filter().with_parsed_stats(&engine);
+    assert_eq!(
+        filter_with_parsed_stats
+            .apply(actions.as_ref(), false)
+            .unwrap(),
+        [true, false, false, true]
+    );
+    // commit batches have no parsed stats
+    assert_eq!(
+        filter_with_parsed_stats
+            .apply(actions.as_ref(), true)
+            .unwrap(),
+        [false, true, false, true]
+    );
+    // parsed stats are only used if checkpoints were read with them
+    assert_eq!(
+        filter().apply(actions.as_ref(), false).unwrap(),
+        [false, true, false, true]
+    );
 }
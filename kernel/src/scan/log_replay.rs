@@ -1,15 +1,22 @@
 use std::clone::Clone;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, LazyLock};
 
+#[cfg(feature = "async-engine")]
+use futures::Stream;
+use itertools::Either;
+
 use super::data_skipping::DataSkippingFilter;
+use super::report::ScanMetrics;
 use super::ScanMetadata;
 use crate::actions::deletion_vector::DeletionVectorDescriptor;
 use crate::actions::get_log_add_schema;
-use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::engine_data::{GetData, MapItem, RowVisitor, TypedGetData as _};
 use crate::expressions::{column_name, ColumnName, Expression, ExpressionRef, PredicateRef};
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, KernelPredicateEvaluator as _};
-use crate::log_replay::{ActionsBatch, FileActionDeduplicator, FileActionKey, LogReplayProcessor};
+use crate::log_replay::{
+    ActionsBatch, FileActionDeduplicator, HasSelectionVector as _, LogReplayProcessor, SeenFileKeys,
+};
 use crate::scan::Scalar;
 use crate::schema::ToSchema as _;
 use crate::schema::{ColumnNamesAndTypes, DataType, MapType, SchemaRef, StructField, StructType};
@@ -48,30 +55,145 @@ pub(crate) struct ScanLogReplayProcessor {
     /// A set of (data file path, dv_unique_id) pairs that have been seen thus
     /// far in the log. This is used to filter out files with Remove actions as
     /// well as duplicate entries in the log.
-    seen_file_keys: HashSet<FileActionKey>,
+    seen_file_keys: SeenFileKeys,
+    /// The counters of the scan's [`ScanReport`] to update.
+    ///
+    /// [`ScanReport`]: super::report::ScanReport
+    metrics: Arc<ScanMetrics>,
+}
+
+/// The file statistics that the batches of actions replayed for a scan were read with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StatsColumns {
+    /// No stats, because the scan neither skips files nor returns their stats. The `stats` of the
+    /// scan rows are null.
+    Omitted,
+    /// The JSON stats (`add.stats`).
+    Json,
+    /// The JSON stats and, for checkpoint batches, the parsed stats (`add.stats_parsed`) to skip
+    /// files with (see [`DataSkippingFilter::with_parsed_stats`]).
+    JsonAndParsed,
 }
 
 impl ScanLogReplayProcessor {
-    /// Create a new [`ScanLogReplayProcessor`] instance
+    /// Create a new [`ScanLogReplayProcessor`] instance for batches read with `stats_columns`.
     fn new(
         engine: &dyn Engine,
         physical_predicate: Option<(PredicateRef, SchemaRef)>,
+        stats_columns: StatsColumns,
         logical_schema: SchemaRef,
         transform_spec: Option<Arc<TransformSpec>>,
+        metrics: Arc<ScanMetrics>,
     ) -> Self {
+        // Without stats there is nothing to skip files with
+        let data_skipping_filter = match stats_columns {
+            StatsColumns::Omitted => None,
+            StatsColumns::Json => DataSkippingFilter::new(engine, physical_predicate.clone()),
+            StatsColumns::JsonAndParsed => {
+                DataSkippingFilter::new(engine, physical_predicate.clone())
+                    .map(|filter| filter.with_parsed_stats(engine))
+            }
+        };
         Self {
-            partition_filter: physical_predicate.as_ref().map(|(e, _)| e.clone()),
-            data_skipping_filter: DataSkippingFilter::new(engine, physical_predicate),
+            partition_filter: physical_predicate.map(|(e, _)| e),
+            data_skipping_filter,
             add_transform: engine.evaluation_handler().new_expression_evaluator(
                 get_log_add_schema().clone(),
-                get_add_transform_expr(),
+                get_add_transform_expr(stats_columns != StatsColumns::Omitted),
                 SCAN_ROW_DATATYPE.clone(),
             ),
             seen_file_keys: Default::default(),
             logical_schema,
             transform_spec,
+            metrics,
         }
     }
+
+    /// Like [`LogReplayProcessor::process_actions_iter`], but applies data skipping to up to
+    /// `skipping_parallelism` batches at a time, each on its own thread. The skipped batches are
+    /// still deduplicated one at a time in the order of `action_iter`, so the output is the same.
+    fn process_actions_iter_with_parallelism(
+        mut self,
+        mut action_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
+        skipping_parallelism: usize,
+    ) -> impl Iterator<Item = DeltaResult<ScanMetadata>> {
+        let filter = match self.data_skipping_filter.take() {
+            Some(filter) if skipping_parallelism > 1 => filter,
+            filter => {
+                self.data_skipping_filter = filter;
+                return Either::Left(self.process_actions_iter(action_iter));
+            }
+        };
+        let mut skipped_batches = VecDeque::new();
+        let skipped_batches = std::iter::from_fn(move || {
+            if skipped_batches.is_empty() {
+                let batches = action_iter.by_ref().take(skipping_parallelism).collect();
+                skipped_batches = filter.apply_concurrently(batches).into();
+            }
+            skipped_batches.pop_front()
+        });
+        let scan_metadata = skipped_batches
+            .map(move |skipped_batch| {
+                let (actions_batch, selection_vector) = skipped_batch?;
+                self.process_skipped_batch(actions_batch, selection_vector)
+            })
+            .filter(|res| {
+                res.as_ref()
+                    .map_or(true, |result| result.has_selected_rows())
+            });
+        Either::Right(scan_metadata)
+    }
+
+    // Deduplicates and transforms a batch of actions whose files data skipping deselected in
+    // `selection_vector`.
+    fn process_skipped_batch(
+        &mut self,
+        actions_batch: ActionsBatch,
+        selection_vector: Vec<bool>,
+    ) -> DeltaResult<ScanMetadata> {
+        let ActionsBatch {
+            actions,
+            is_log_batch,
+        } = actions_batch;
+        assert_eq!(selection_vector.len(), actions.len());
+        // data skipping only deselects add actions
+        let files_pruned_by_stats = selection_vector
+            .iter()
+            .filter(|selected| !**selected)
+            .count();
+
+        let mut visitor = AddRemoveDedupVisitor::new(
+            &mut self.seen_file_keys,
+            selection_vector,
+            self.logical_schema.clone(),
+            self.transform_spec.clone(),
+            self.partition_filter.clone(),
+            is_log_batch,
+        );
+        visitor.visit_rows_of(actions.as_ref())?;
+
+        let metrics = &self.metrics;
+        let files_planned = visitor.selection_vector.iter().filter(|s| **s).count();
+        ScanMetrics::add(
+            &metrics.files_seen,
+            visitor.files_seen + files_pruned_by_stats as u64,
+        );
+        ScanMetrics::add(&metrics.files_pruned_by_stats, files_pruned_by_stats as u64);
+        ScanMetrics::add(
+            &metrics.files_pruned_by_partition,
+            visitor.files_pruned_by_partition,
+        );
+        ScanMetrics::add(&metrics.files_planned, files_planned as u64);
+        ScanMetrics::add(&metrics.bytes_planned, visitor.bytes_selected);
+
+        // TODO: Teach expression eval to respect the selection vector we just computed so carefully!
+        let result = self.add_transform.evaluate(actions.as_ref())?;
+        Ok(ScanMetadata::new(
+            result,
+            visitor.selection_vector,
+            visitor.row_transform_exprs,
+        ))
+    }
 }
 
 /// A visitor that deduplicates a stream of add and remove actions into a stream of valid adds. Log
@@ -85,6 +207,11 @@ struct AddRemoveDedupVisitor<'seen> {
     transform_spec: Option<Arc<TransformSpec>>,
     partition_filter: Option<PredicateRef>,
     row_transform_exprs: Vec<Option<ExpressionRef>>,
+    /// The number of add actions visited.
+    files_seen: u64,
+    files_pruned_by_partition: u64,
+    /// The total size of the selected add actions.
+    bytes_selected: u64,
 }
 
 impl AddRemoveDedupVisitor<'_> {
@@ -92,12 +219,15 @@ impl AddRemoveDedupVisitor<'_> {
     // `selected_column_names_and_types()`
     const ADD_PATH_INDEX: usize = 0; // Position of "add.path" in getters
     const ADD_PARTITION_VALUES_INDEX: usize = 1; // Position of "add.partitionValues" in getters
-    const ADD_DV_START_INDEX: usize = 2; // Start position of add deletion vector columns
-    const REMOVE_PATH_INDEX: usize = 5; // Position of "remove.path" in getters
-    const REMOVE_DV_START_INDEX: usize = 6; // Start position of remove deletion vector columns
+    const ADD_SIZE_INDEX: usize = 2; // Position of "add.size" in getters
+    const ADD_DV_START_INDEX: usize = 3; // Start position of add deletion vector columns
+    const ADD_BASE_ROW_ID_INDEX: usize = 6; // Position of "add.baseRowId" in getters
+    const ADD_DEFAULT_ROW_COMMIT_VERSION_INDEX: usize = 7; // Position of "add.defaultRowCommitVersion" in getters
+    const REMOVE_PATH_INDEX: usize = 8; // Position of "remove.path" in getters
+    const REMOVE_DV_START_INDEX: usize = 9; // Start position of remove deletion vector columns
 
     fn new(
-        seen: &mut HashSet<FileActionKey>,
+        seen: &mut SeenFileKeys,
         selection_vector: Vec<bool>,
         logical_schema: SchemaRef,
         transform_spec: Option<Arc<TransformSpec>>,
@@ -118,13 +248,13 @@ impl AddRemoveDedupVisitor<'_> {
             transform_spec,
             partition_filter,
             row_transform_exprs: Vec::new(),
+            files_seen: 0,
+            files_pruned_by_partition: 0,
+            bytes_selected: 0,
         }
     }
 
-    fn is_file_partition_pruned(
-        &self,
-        partition_values: &HashMap<usize, (String, Scalar)>,
-    ) -> bool {
+    fn is_file_partition_pruned(&self, partition_values: &HashMap<usize, (&str, Scalar)>) -> bool {
         if partition_values.is_empty() {
             return false;
         }
@@ -133,7 +263,7 @@ impl AddRemoveDedupVisitor<'_> {
         };
         let partition_values: HashMap<_, _> = partition_values
             .values()
-            .map(|(k, v)| (ColumnName::new([k]), v.clone()))
+            .map(|(k, v)| (ColumnName::new([*k]), v.clone()))
             .collect();
         let evaluator = DefaultKernelPredicateEvaluator::from(partition_values);
         evaluator.eval_sql_where(partition_filter) == Some(false)
@@ -143,8 +273,8 @@ impl AddRemoveDedupVisitor<'_> {
     /// is not an Add action, or the file has already been seen previously.
     fn is_valid_add<'a>(&mut self, i: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<bool> {
         // When processing file actions, we extract path and deletion vector information based on action type:
-        // - For Add actions: path is at index 0, followed by DV fields at indexes 2-4
-        // - For Remove actions (in log batches only): path is at index 5, followed by DV fields at indexes 6-8
+        // - For Add actions: path is at index 0, followed by DV fields at indexes 3-5
+        // - For Remove actions (in log batches only): path is at index 8, followed by DV fields at indexes 9-11
         // The file extraction logic selects the appropriate indexes based on whether we found a valid path.
         // Remove getters are not included when visiting a non-log batch (checkpoint batch), so do
         // not try to extract remove actions in that case.
@@ -156,6 +286,9 @@ impl AddRemoveDedupVisitor<'_> {
         else {
             return Ok(false);
         };
+        if is_add {
+            self.files_seen += 1;
+        }
 
         // Apply partition pruning (to adds only) before deduplication, so that we don't waste memory
         // tracking pruned files. Removes don't get pruned and we'll still have to track them.
@@ -165,11 +298,15 @@ impl AddRemoveDedupVisitor<'_> {
         // encounter if the table's schema was replaced after the most recent checkpoint.
         let partition_values = match &self.transform_spec {
             Some(transform) if is_add => {
-                let partition_values =
+                // Look partition values up in the map rather than materializing it as a HashMap
+                let partition_values: MapItem<'_> =
                     getters[Self::ADD_PARTITION_VALUES_INDEX].get(i, "add.partitionValues")?;
                 let partition_values =
-                    parse_partition_values(&self.logical_schema, transform, &partition_values)?;
+                    parse_partition_values(&self.logical_schema, transform, |name| {
+                        partition_values.get(name)
+                    })?;
                 if self.is_file_partition_pruned(&partition_values) {
+                    self.files_pruned_by_partition += 1;
                     return Ok(false);
                 }
                 partition_values
@@ -184,13 +321,27 @@ impl AddRemoveDedupVisitor<'_> {
         let transform = self
             .transform_spec
             .as_ref()
-            .map(|transform| get_transform_expr(transform, partition_values))
+            .map(|transform| {
+                let base_row_id =
+                    getters[Self::ADD_BASE_ROW_ID_INDEX].get_opt(i, "add.baseRowId")?;
+                let default_row_commit_version = getters
+                    [Self::ADD_DEFAULT_ROW_COMMIT_VERSION_INDEX]
+                    .get_opt(i, "add.defaultRowCommitVersion")?;
+                get_transform_expr(
+                    transform,
+                    partition_values,
+                    base_row_id,
+                    default_row_commit_version,
+                )
+            })
             .transpose()?;
         if transform.is_some() {
             // fill in any needed `None`s for previous rows
             self.row_transform_exprs.resize_with(i, Default::default);
             self.row_transform_exprs.push(transform);
         }
+        let size: i64 = getters[Self::ADD_SIZE_INDEX].get(i, "add.size")?;
+        self.bytes_selected += u64::try_from(size).unwrap_or_default();
         Ok(true)
     }
 }
@@ -201,13 +352,17 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
             const STRING: DataType = DataType::STRING;
             const INTEGER: DataType = DataType::INTEGER;
+            const LONG: DataType = DataType::LONG;
             let ss_map: DataType = MapType::new(STRING, STRING, true).into();
             let types_and_names = vec![
                 (STRING, column_name!("add.path")),
                 (ss_map, column_name!("add.partitionValues")),
+                (LONG, column_name!("add.size")),
                 (STRING, column_name!("add.deletionVector.storageType")),
                 (STRING, column_name!("add.deletionVector.pathOrInlineDv")),
                 (INTEGER, column_name!("add.deletionVector.offset")),
+                (LONG, column_name!("add.baseRowId")),
+                (LONG, column_name!("add.defaultRowCommitVersion")),
                 (STRING, column_name!("remove.path")),
                 (STRING, column_name!("remove.deletionVector.storageType")),
                 (STRING, column_name!("remove.deletionVector.pathOrInlineDv")),
@@ -222,13 +377,13 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
         } else {
             // All checkpoint actions are already reconciled and Remove actions in checkpoint files
             // only serve as tombstones for vacuum jobs. So we only need to examine the adds here.
-            (&names[..5], &types[..5])
+            (&names[..8], &types[..8])
         }
     }
 
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         let is_log_batch = self.deduplicator.is_log_batch();
-        let expected_getters = if is_log_batch { 9 } else { 5 };
+        let expected_getters = if is_log_batch { 12 } else { 8 };
         require!(
             getters.len() == expected_getters,
             Error::InternalError(format!(
@@ -252,8 +407,11 @@ impl RowVisitor for AddRemoveDedupVisitor<'_> {
 pub(crate) static SCAN_ROW_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(|| {
     // Note that fields projected out of a nullable struct must be nullable
     let partition_values = MapType::new(DataType::STRING, DataType::STRING, true);
-    let file_constant_values =
-        StructType::new_unchecked([StructField::nullable("partitionValues", partition_values)]);
+    let file_constant_values = StructType::new_unchecked([
+        StructField::nullable("partitionValues", partition_values),
+        StructField::nullable("baseRowId", DataType::LONG),
+        StructField::nullable("defaultRowCommitVersion", DataType::LONG),
+    ]);
     Arc::new(StructType::new_unchecked([
         StructField::nullable("path", DataType::STRING),
         StructField::nullable("size", DataType::LONG),
@@ -267,21 +425,32 @@ pub(crate) static SCAN_ROW_SCHEMA: LazyLock<Arc<StructType>> = LazyLock::new(||
 pub(crate) static SCAN_ROW_DATATYPE: LazyLock<DataType> =
     LazyLock::new(|| SCAN_ROW_SCHEMA.clone().into());
 
-fn get_add_transform_expr() -> ExpressionRef {
+/// The expression to transform add actions into scan rows. Unless `include_stats`, the actions were
+/// read without `add.stats`, and the `stats` of the scan rows are null.
+fn get_add_transform_expr(include_stats: bool) -> ExpressionRef {
     use crate::expressions::column_expr_ref;
-    static EXPR: LazyLock<ExpressionRef> = LazyLock::new(|| {
+    fn add_transform_expr(stats: ExpressionRef) -> ExpressionRef {
         Arc::new(Expression::Struct(vec![
             column_expr_ref!("add.path"),
             column_expr_ref!("add.size"),
             column_expr_ref!("add.modificationTime"),
-            column_expr_ref!("add.stats"),
+            stats,
             column_expr_ref!("add.deletionVector"),
-            Arc::new(Expression::Struct(vec![column_expr_ref!(
-                "add.partitionValues"
-            )])),
+            Arc::new(Expression::Struct(vec![
+                column_expr_ref!("add.partitionValues"),
+                column_expr_ref!("add.baseRowId"),
+                column_expr_ref!("add.defaultRowCommitVersion"),
+            ])),
         ]))
-    });
-    EXPR.clone()
+    }
+    static EXPR: LazyLock<ExpressionRef> =
+        LazyLock::new(|| add_transform_expr(column_expr_ref!("add.stats")));
+    static EXPR_WITHOUT_STATS: LazyLock<ExpressionRef> =
+        LazyLock::new(|| add_transform_expr(Arc::new(Expression::null_literal(DataType::STRING))));
+    match include_stats {
+        true => EXPR.clone(),
+        false => EXPR_WITHOUT_STATS.clone(),
+    }
 }
 
 // TODO: remove once `scan_metadata_from` is pub.
@@ -297,6 +466,8 @@ pub(crate) fn get_scan_metadata_transform_expr() -> ExpressionRef {
                 column_expr_ref!("modificationTime"),
                 column_expr_ref!("stats"),
                 column_expr_ref!("deletionVector"),
+                column_expr_ref!("fileConstantValues.baseRowId"),
+                column_expr_ref!("fileConstantValues.defaultRowCommitVersion"),
             ],
         ))]))
     });
@@ -307,33 +478,12 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
     type Output = ScanMetadata;
 
     fn process_actions_batch(&mut self, actions_batch: ActionsBatch) -> DeltaResult<Self::Output> {
-        let ActionsBatch {
-            actions,
-            is_log_batch,
-        } = actions_batch;
         // Build an initial selection vector for the batch which has had the data skipping filter
         // applied. The selection vector is further updated by the deduplication visitor to remove
         // rows that are not valid adds.
-        let selection_vector = self.build_selection_vector(actions.as_ref())?;
-        assert_eq!(selection_vector.len(), actions.len());
-
-        let mut visitor = AddRemoveDedupVisitor::new(
-            &mut self.seen_file_keys,
-            selection_vector,
-            self.logical_schema.clone(),
-            self.transform_spec.clone(),
-            self.partition_filter.clone(),
-            is_log_batch,
-        );
-        visitor.visit_rows_of(actions.as_ref())?;
-
-        // TODO: Teach expression eval to respect the selection vector we just computed so carefully!
-        let result = self.add_transform.evaluate(actions.as_ref())?;
-        Ok(ScanMetadata::new(
-            result,
-            visitor.selection_vector,
-            visitor.row_transform_exprs,
-        ))
+        let selection_vector = self
+            .build_selection_vector(actions_batch.actions.as_ref(), actions_batch.is_log_batch)?;
+        self.process_skipped_batch(actions_batch, selection_vector)
     }
 
     fn data_skipping_filter(&self) -> Option<&DataSkippingFilter> {
@@ -347,17 +497,61 @@ impl LogReplayProcessor for ScanLogReplayProcessor {
 /// that is selected in the returned `engine_data` _must_ be processed to complete the scan.
 /// Non-selected rows _must_ be ignored.
 ///
+/// The batches of `action_iter` were read with `stats_columns`. If [`StatsColumns::JsonAndParsed`],
+/// the checkpoint batches have the parsed stats of the columns `physical_predicate` references (see
+/// [`with_parsed_stats`]), which data skipping then prefers over their JSON stats. The processed
+/// and skipped files are counted in `metrics`. Data skipping is applied to up to
+/// `skipping_parallelism` batches concurrently.
+///
 /// Note: The iterator of [`ActionsBatch`]s ('action_iter' parameter) must be sorted by the order of
 /// the actions in the log from most recent to least recent.
+///
+/// [`with_parsed_stats`]: super::data_skipping::with_parsed_stats
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn scan_action_iter(
     engine: &dyn Engine,
     action_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
     logical_schema: SchemaRef,
     transform_spec: Option<Arc<TransformSpec>>,
     physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    stats_columns: StatsColumns,
+    skipping_parallelism: usize,
+    metrics: Arc<ScanMetrics>,
 ) -> impl Iterator<Item = DeltaResult<ScanMetadata>> {
-    ScanLogReplayProcessor::new(engine, physical_predicate, logical_schema, transform_spec)
-        .process_actions_iter(action_iter)
+    ScanLogReplayProcessor::new(
+        engine,
+        physical_predicate,
+        stats_columns,
+        logical_schema,
+        transform_spec,
+        metrics,
+    )
+    .process_actions_iter_with_parallelism(action_iter, skipping_parallelism)
+}
+
+/// Like [`scan_action_iter`], but for a stream of [`ActionsBatch`]s, e.g. read with an
+/// [`AsyncEngine`]. Data skipping is applied to one batch at a time.
+///
+/// [`AsyncEngine`]: crate::async_engine::AsyncEngine
+#[cfg(feature = "async-engine")]
+pub(crate) fn scan_action_stream(
+    engine: &dyn Engine,
+    action_stream: impl Stream<Item = DeltaResult<ActionsBatch>>,
+    logical_schema: SchemaRef,
+    transform_spec: Option<Arc<TransformSpec>>,
+    physical_predicate: Option<(PredicateRef, SchemaRef)>,
+    stats_columns: StatsColumns,
+    metrics: Arc<ScanMetrics>,
+) -> impl Stream<Item = DeltaResult<ScanMetadata>> {
+    ScanLogReplayProcessor::new(
+        engine,
+        physical_predicate,
+        stats_columns,
+        logical_schema,
+        transform_spec,
+        metrics,
+    )
+    .process_actions_stream(action_stream)
 }
 
 #[cfg(test)]
@@ -381,7 +575,7 @@ mod tests {
         ExpressionRef,
     };
 
-    use super::scan_action_iter;
+    use super::{scan_action_iter, StatsColumns};
 
     // dv-info is more complex to validate, we validate that works in the test for visit_scan_files
     // in state.rs
@@ -441,6 +635,9 @@ mod tests {
             logical_schema,
             None,
             None,
+            StatsColumns::Json,
+            1,
+            Default::default(),
         );
         for res in iter {
             let scan_metadata = res.unwrap();
@@ -458,8 +655,13 @@ mod tests {
             StructField::new("date", DataType::DATE, true),
         ]));
         let partition_cols = ["date".to_string()];
-        let state_info =
-            StateInfo::try_new(schema.as_ref(), &partition_cols, ColumnMappingMode::None).unwrap();
+        let state_info = StateInfo::try_new(
+            schema.as_ref(),
+            &partition_cols,
+            ColumnMappingMode::None,
+            None,
+        )
+        .unwrap();
         let static_transform = Some(Arc::new(get_transform_spec(&state_info.all_fields)));
         let batch = vec![add_batch_with_partition_col()];
         let iter = scan_action_iter(
@@ -470,6 +672,9 @@ mod tests {
             schema,
             static_transform,
             None,
+            StatsColumns::Json,
+            1,
+            Default::default(),
         );
 
         fn validate_transform(transform: Option<&ExpressionRef>, expected_date_offset: i32) {
@@ -5,37 +5,51 @@ use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, LazyLock};
 
 use delta_kernel_derive::internal_api;
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use tracing::debug;
 use url::Url;
 
 use self::log_replay::get_scan_metadata_transform_expr;
 use crate::actions::deletion_vector::{
-    deletion_treemap_to_bools, split_vector, DeletionVectorDescriptor,
+    deletion_treemap_to_bools, split_vector, DeletionVectorCache, DeletionVectorDescriptor,
 };
 use crate::actions::{get_log_schema, ADD_NAME, REMOVE_NAME, SIDECAR_NAME};
 use crate::engine_data::FilteredEngineData;
 use crate::expressions::transforms::ExpressionTransform;
-use crate::expressions::{ColumnName, ExpressionRef, Predicate, PredicateRef, Scalar};
+use crate::expressions::{
+    ColumnName, ExpressionRef, JunctionPredicate, JunctionPredicateOp, Predicate, PredicateRef,
+    Scalar,
+};
 use crate::kernel_predicates::{DefaultKernelPredicateEvaluator, EmptyColumnResolver};
 use crate::listed_log_files::ListedLogFiles;
 use crate::log_replay::{ActionsBatch, HasSelectionVector};
 use crate::log_segment::LogSegment;
+use crate::row_tracking::MaterializedRowTrackingColumns;
 use crate::scan::state::{DvInfo, Stats};
 use crate::schema::ToSchema as _;
 use crate::schema::{
-    ArrayType, DataType, MapType, PrimitiveType, Schema, SchemaRef, SchemaTransform, StructField,
-    StructType,
+    ArrayType, DataType, MapType, MetadataColumnSpec, PrimitiveType, Schema, SchemaRef,
+    SchemaTransform, StructField, StructType,
 };
 use crate::snapshot::SnapshotRef;
-use crate::table_features::ColumnMappingMode;
-use crate::transforms::{get_transform_spec, ColumnType};
+use crate::table_features::{has_widened_fields, ColumnMappingMode};
+use crate::transforms::{get_transform_spec, ColumnType, TransformSpec};
+use crate::utils::{assert_send_sync, new_span};
 use crate::{DeltaResult, Engine, EngineData, Error, FileMeta, Version};
 
-use self::log_replay::scan_action_iter;
+use self::data_skipping::{stats_schema, with_parsed_stats, without_stats};
+#[cfg(feature = "async-engine")]
+use self::log_replay::scan_action_stream;
+use self::log_replay::{scan_action_iter, StatsColumns};
+use self::replay_actions::{RegisteredVisitor, ReplayActionType, ReplayActionVisitor};
+use self::report::{ScanMetrics, ScanReport};
+#[cfg(feature = "async-engine")]
+use crate::async_engine::{self, ProcessingEngine};
 
 pub(crate) mod data_skipping;
 pub mod log_replay;
+pub mod replay_actions;
+pub mod report;
 pub mod state;
 
 // safety: we define get_log_schema() and _know_ it contains ADD_NAME and REMOVE_NAME
@@ -47,11 +61,19 @@ static COMMIT_READ_SCHEMA: LazyLock<SchemaRef> =
 static CHECKPOINT_READ_SCHEMA: LazyLock<SchemaRef> =
     LazyLock::new(|| get_log_schema().project(&[ADD_NAME, SIDECAR_NAME]).unwrap());
 
+/// The name of the row index column read to compute row IDs when the query doesn't request one.
+const INTERNAL_ROW_INDEX_COLUMN_NAME: &str = "_delta_kernel_row_index";
+
 /// Builder to scan a snapshot of a table.
 pub struct ScanBuilder {
     snapshot: SnapshotRef,
     schema: Option<SchemaRef>,
     predicate: Option<PredicateRef>,
+    deletion_vector_cache: Option<Arc<DeletionVectorCache>>,
+    case_insensitive: bool,
+    include_stats: bool,
+    skipping_parallelism: usize,
+    replay_action_visitor: Option<RegisteredVisitor>,
 }
 
 impl std::fmt::Debug for ScanBuilder {
@@ -59,6 +81,9 @@ impl std::fmt::Debug for ScanBuilder {
         f.debug_struct("ScanBuilder")
             .field("schema", &self.schema)
             .field("predicate", &self.predicate)
+            .field("case_insensitive", &self.case_insensitive)
+            .field("include_stats", &self.include_stats)
+            .field("skipping_parallelism", &self.skipping_parallelism)
             .finish()
     }
 }
@@ -70,6 +95,11 @@ impl ScanBuilder {
             snapshot: snapshot.into(),
             schema: None,
             predicate: None,
+            deletion_vector_cache: None,
+            case_insensitive: false,
+            include_stats: false,
+            skipping_parallelism: 1,
+            replay_action_visitor: None,
         }
     }
 
@@ -107,26 +137,132 @@ impl ScanBuilder {
         self
     }
 
+    /// Provide a [`DeletionVectorCache`] for [`Scan::execute`] to read deletion vectors through.
+    /// Sharing a cache between scans of the same table avoids fetching and decoding deletion
+    /// vectors again.
+    pub fn with_deletion_vector_cache(mut self, cache: Arc<DeletionVectorCache>) -> Self {
+        self.deletion_vector_cache = Some(cache);
+        self
+    }
+
+    /// Resolve column names in the schema, the predicate, and the table's partition columns
+    /// against the table schema ignoring case, as engines with case-insensitive SQL semantics
+    /// expect (e.g. `SELECT ID` on a table with column `id`). Resolved columns take the name used
+    /// by the table schema, including in the scan's logical schema. Columns whose names match more
+    /// than one table column are ambiguous, and cause [`ScanBuilder::build`] to fail.
+    ///
+    /// Names are resolved through nested structs, but not through arrays or maps. By default,
+    /// name resolution is case-sensitive.
+    pub fn with_case_insensitive_resolution(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Include the statistics of each file (the `stats` of the scan rows, and the [`Stats`] passed to
+    /// [`ScanMetadata::visit_scan_files`] callbacks) in the scan metadata, e.g. for engines that
+    /// use the files' row counts. By default, the stats of files are only read from the log when
+    /// the scan's predicate needs them to skip files, because reading and parsing them is a large
+    /// part of the cost of replaying the log; otherwise the stats of the scan rows are null.
+    ///
+    /// [`Stats`]: state::Stats
+    pub fn with_stats(mut self, include_stats: bool) -> Self {
+        self.include_stats = include_stats;
+        self
+    }
+
+    /// Skip files by their stats on up to `skipping_parallelism` threads, each evaluating the
+    /// scan's predicate over a different batch of add actions, which speeds up planning on tables
+    /// with many files. The batches are still reconciled and returned in log order, so the scan
+    /// metadata is the same. Zero is treated as one.
+    ///
+    /// By default, files are skipped on the thread that consumes [`Scan::scan_metadata`].
+    pub fn with_skipping_parallelism(mut self, skipping_parallelism: usize) -> Self {
+        self.skipping_parallelism = skipping_parallelism;
+        self
+    }
+
+    /// Pass the batches of actions that [`Scan::scan_metadata`] reads while replaying the log to
+    /// `visitor`, including the actions of the given `action_types` (see
+    /// [`ReplayActionVisitor::visit_actions`]). This lets engines that need e.g. the
+    /// `domainMetadata` or `txn` actions of the table get them without replaying the log again.
+    ///
+    /// The visitor is called as the scan metadata is iterated, so it has seen all actions once the
+    /// iterator is exhausted. It is not called at all if the scan's predicate statically skips all
+    /// files, since no log is replayed then.
+    pub fn with_replay_action_visitor(
+        mut self,
+        action_types: impl IntoIterator<Item = ReplayActionType>,
+        visitor: Arc<dyn ReplayActionVisitor>,
+    ) -> Self {
+        self.replay_action_visitor = Some(RegisteredVisitor {
+            action_types: action_types.into_iter().collect(),
+            visitor,
+        });
+        self
+    }
+
     /// Build the [`Scan`].
     ///
     /// This does not scan the table at this point, but does do some work to ensure that the
     /// provided schema make sense, and to prepare some metadata that the scan will need.  The
     /// [`Scan`] type itself can be used to fetch the files and associated metadata required to
     /// perform actual data reads.
+    ///
+    /// Errors carry the table and version of the snapshot as [`ErrorContext`].
+    ///
+    /// [`ErrorContext`]: crate::error::ErrorContext
     pub fn build(self) -> DeltaResult<Scan> {
+        let snapshot = self.snapshot.clone();
+        self.build_impl().map_err(|err| {
+            err.with_table(snapshot.table_root())
+                .with_version(snapshot.version())
+        })
+    }
+
+    fn build_impl(self) -> DeltaResult<Scan> {
         // if no schema is provided, use snapshot's entire schema (e.g. SELECT *)
-        let logical_schema = self.schema.unwrap_or_else(|| self.snapshot.schema());
+        let mut logical_schema = self.schema.unwrap_or_else(|| self.snapshot.schema());
+        let mut predicate = self.predicate;
+        let mut partition_columns = Cow::Borrowed(&self.snapshot.metadata().partition_columns);
+        if self.case_insensitive {
+            let table_schema = self.snapshot.schema();
+            logical_schema = Arc::new(resolve_schema_case_insensitive(
+                &logical_schema,
+                &table_schema,
+            )?);
+            predicate = predicate
+                .map(|predicate| resolve_predicate_case_insensitive(&predicate, &logical_schema))
+                .transpose()?
+                .map(Arc::new);
+            partition_columns = Cow::Owned(
+                partition_columns
+                    .iter()
+                    .map(|name| -> DeltaResult<_> {
+                        let field = table_schema.field_case_insensitive(name)?;
+                        Ok(field.map_or_else(|| name.clone(), |field| field.name().clone()))
+                    })
+                    .try_collect()?,
+            );
+        }
+        let table_configuration = self.snapshot.table_configuration();
+        let row_tracking_columns = (logical_schema
+            .contains_metadata_column(&MetadataColumnSpec::RowId)
+            || logical_schema.contains_metadata_column(&MetadataColumnSpec::RowCommitVersion))
+        .then(|| table_configuration.materialized_row_tracking_columns())
+        .transpose()?;
         let state_info = StateInfo::try_new(
             logical_schema.as_ref(),
-            &self.snapshot.metadata().partition_columns,
-            self.snapshot.table_configuration().column_mapping_mode(),
+            &partition_columns,
+            table_configuration.column_mapping_mode(),
+            row_tracking_columns,
         )?;
 
-        let physical_predicate = match self.predicate {
+        let physical_predicate = match predicate {
             Some(predicate) => PhysicalPredicate::try_new(&predicate, &logical_schema)?,
             None => PhysicalPredicate::None,
         };
 
+        let have_widened_cols = has_widened_fields(&logical_schema);
         Ok(Scan {
             snapshot: self.snapshot,
             logical_schema,
@@ -134,10 +270,89 @@ impl ScanBuilder {
             physical_predicate,
             all_fields: Arc::new(state_info.all_fields),
             have_partition_cols: state_info.have_partition_cols,
+            have_row_tracking_cols: state_info.have_row_tracking_cols,
+            have_widened_cols,
+            deletion_vector_cache: self.deletion_vector_cache,
+            include_stats: self.include_stats,
+            skipping_parallelism: self.skipping_parallelism,
+            replay_action_visitor: self.replay_action_visitor,
+            metrics: Default::default(),
+        })
+    }
+}
+
+// Renames the fields of `requested` (and of any structs nested directly in them) to the names of the
+// table schema fields they match ignoring case. Metadata columns and unmatched fields are kept as-is.
+fn resolve_schema_case_insensitive(
+    requested: &StructType,
+    table_schema: &StructType,
+) -> DeltaResult<StructType> {
+    StructType::try_from_results(requested.fields().map(|field| -> DeltaResult<_> {
+        if field.is_metadata_column() {
+            return Ok(field.clone());
+        }
+        let Some(table_field) = table_schema.field_case_insensitive(field.name())? else {
+            return Ok(field.clone());
+        };
+        let data_type = match (field.data_type(), table_field.data_type()) {
+            (DataType::Struct(requested), DataType::Struct(table_schema)) => {
+                resolve_schema_case_insensitive(requested, table_schema)?.into()
+            }
+            (data_type, _) => data_type.clone(),
+        };
+        Ok(StructField {
+            name: table_field.name().clone(),
+            data_type,
+            ..field.clone()
+        })
+    }))
+}
+
+// Rewrites the column references of `predicate` to the names of the schema fields they match
+// ignoring case. Unmatched references are kept as-is, so that they fail to resolve later.
+fn resolve_predicate_case_insensitive(
+    predicate: &Predicate,
+    schema: &StructType,
+) -> DeltaResult<Predicate> {
+    let column_mappings = predicate
+        .references()
+        .into_iter()
+        .map(|column| {
+            Ok((
+                column.clone(),
+                resolve_column_case_insensitive(column, schema)?,
+            ))
         })
+        .collect::<DeltaResult<_>>()?;
+    let mut apply_mappings = ApplyColumnMappings { column_mappings };
+    match apply_mappings.transform_pred(predicate) {
+        Some(predicate) => Ok(predicate.into_owned()),
+        None => Err(Error::internal_error(
+            "Failed to resolve predicate column references",
+        )),
     }
 }
 
+fn resolve_column_case_insensitive(
+    column: &ColumnName,
+    schema: &StructType,
+) -> DeltaResult<ColumnName> {
+    let mut resolved = Vec::with_capacity(column.len());
+    let mut current = Some(schema);
+    for name in column.iter() {
+        let field = match current {
+            Some(schema) => schema.field_case_insensitive(name)?,
+            None => None,
+        };
+        current = match field.map(StructField::data_type) {
+            Some(DataType::Struct(schema)) => Some(schema),
+            _ => None,
+        };
+        resolved.push(field.map_or(name, StructField::name).clone());
+    }
+    Ok(ColumnName::new(resolved))
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum PhysicalPredicate {
     Some(PredicateRef, SchemaRef),
@@ -177,7 +392,8 @@ impl PhysicalPredicate {
             // columns are missing/invalid. Just blow up instead of trying to handle it gracefully.
             return Err(Error::missing_column(format!(
                 "Predicate references unknown column: {unresolved}"
-            )));
+            ))
+            .with_column(unresolved.clone()));
         }
         let Some(schema) = schema_opt else {
             // The predicate doesn't statically skip all files, and it doesn't reference any columns
@@ -349,7 +565,7 @@ impl ScanMetadata {
         Self {
             scan_files: FilteredEngineData {
                 data,
-                selection_vector,
+                selection_vector: selection_vector.into(),
             },
             scan_file_transforms,
         }
@@ -358,12 +574,17 @@ impl ScanMetadata {
 
 impl HasSelectionVector for ScanMetadata {
     fn has_selected_rows(&self) -> bool {
-        self.scan_files.selection_vector.contains(&true)
+        self.scan_files.selection_vector.has_selected_rows()
     }
 }
 
 /// The result of building a scan over a table. This can be used to get the actual data from
 /// scanning the table.
+///
+/// A `Scan` is `Send + Sync` and cheap to clone, e.g. to plan and read files on different threads.
+/// Clones share the snapshot, the deletion vector cache and the metrics of the scan, so the
+/// [`ScanReport`] of a scan includes the work done by all its clones.
+#[derive(Clone)]
 pub struct Scan {
     snapshot: SnapshotRef,
     logical_schema: SchemaRef,
@@ -371,6 +592,13 @@ pub struct Scan {
     physical_predicate: PhysicalPredicate,
     all_fields: Arc<Vec<ColumnType>>,
     have_partition_cols: bool,
+    have_row_tracking_cols: bool,
+    have_widened_cols: bool,
+    deletion_vector_cache: Option<Arc<DeletionVectorCache>>,
+    include_stats: bool,
+    skipping_parallelism: usize,
+    replay_action_visitor: Option<RegisteredVisitor>,
+    metrics: Arc<ScanMetrics>,
 }
 
 impl std::fmt::Debug for Scan {
@@ -382,6 +610,18 @@ impl std::fmt::Debug for Scan {
     }
 }
 
+// scans are shared across threads, see the concurrency model in the crate docs. There is no
+// separate global scan state (`GlobalScanState` was replaced by the accessors of `Scan`), but the
+// per-file state passed to `ScanMetadata::visit_scan_files` callbacks is sent to the threads that
+// read the files.
+const _: () = {
+    assert_send_sync::<ScanBuilder>();
+    assert_send_sync::<Scan>();
+    assert_send_sync::<ScanMetadata>();
+    assert_send_sync::<state::DvInfo>();
+    assert_send_sync::<state::Stats>();
+};
+
 impl Scan {
     /// The table's root URL. Any relative paths returned from `scan_data` (or in a callback from
     /// [`ScanMetadata::visit_scan_files`]) must be resolved against this root to get the actual path to
@@ -426,6 +666,14 @@ impl Scan {
         }
     }
 
+    /// Get a [`ScanReport`] of the work this scan has performed so far, e.g. how many files data
+    /// skipping pruned. The report covers all calls to [`Scan::scan_metadata`] and
+    /// [`Scan::execute`] of this scan, so engines should get it once they have consumed the
+    /// iterators these return.
+    pub fn report(&self) -> ScanReport {
+        self.metrics.report()
+    }
+
     /// Get an iterator of [`ScanMetadata`]s that should be used to facilitate a scan. This handles
     /// log-replay, reconciling Add and Remove actions, and applying data skipping (if possible).
     /// Each item in the returned iterator is a struct of:
@@ -449,7 +697,11 @@ impl Scan {
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
-        self.scan_metadata_inner(engine, self.replay_for_scan_metadata(engine)?)
+        self.scan_metadata_inner(
+            engine,
+            self.replay_for_scan_metadata(engine)?,
+            self.stats_columns(),
+        )
     }
 
     /// Get an updated iterator of [`ScanMetadata`]s based on an existing iterator of [`EngineData`]s.
@@ -513,6 +765,8 @@ impl Scan {
                     StructField::nullable("modificationTime", DataType::LONG),
                     StructField::nullable("stats", DataType::STRING),
                     StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+                    StructField::nullable("baseRowId", DataType::LONG),
+                    StructField::nullable("defaultRowCommitVersion", DataType::LONG),
                 ]),
             )])
         });
@@ -543,7 +797,11 @@ impl Scan {
         // to apply file skipping and provide the required transformations.
         if existing_version == self.snapshot.version() {
             let scan = existing_data.into_iter().map(apply_transform);
-            return Ok(Box::new(self.scan_metadata_inner(engine, scan)?));
+            return Ok(Box::new(self.scan_metadata_inner(
+                engine,
+                scan,
+                StatsColumns::Json,
+            )?));
         }
 
         let log_segment = self.snapshot.log_segment();
@@ -581,34 +839,131 @@ impl Scan {
             )?
             .chain(existing_data.into_iter().map(apply_transform));
 
-        Ok(Box::new(self.scan_metadata_inner(engine, it)?))
+        Ok(Box::new(self.scan_metadata_inner(
+            engine,
+            it,
+            StatsColumns::Json,
+        )?))
     }
 
     fn scan_metadata_inner(
         &self,
         engine: &dyn Engine,
         action_batch_iter: impl Iterator<Item = DeltaResult<ActionsBatch>>,
+        stats_columns: StatsColumns,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanMetadata>>> {
-        // Compute the static part of the transformation. This is `None` if no transformation is
-        // needed. We need transforms for:
-        // - Partition columns: Must be injected from partition values
-        // - Column mapping: Physical field names must be mapped to logical field names via output schema
-        let static_transform = (self.have_partition_cols
-            || self.snapshot.column_mapping_mode() != ColumnMappingMode::None)
-            .then(|| Arc::new(get_transform_spec(&self.all_fields)));
-        let physical_predicate = match self.physical_predicate.clone() {
-            PhysicalPredicate::StaticSkipAll => return Ok(None.into_iter().flatten()),
-            PhysicalPredicate::Some(predicate, schema) => Some((predicate, schema)),
-            PhysicalPredicate::None => None,
+        let it = self.replay_predicate().map(|physical_predicate| {
+            scan_action_iter(
+                engine,
+                action_batch_iter,
+                self.logical_schema.clone(),
+                self.static_transform(),
+                physical_predicate,
+                stats_columns,
+                self.skipping_parallelism,
+                self.metrics.clone(),
+            )
+        });
+        let metrics = self.metrics.clone();
+        let span = new_span!(
+            INFO,
+            "scan_metadata",
+            table = %self.table_root(),
+            version = self.snapshot.version()
+        );
+        let mut it = it.into_iter().flatten();
+        Ok(std::iter::from_fn(move || {
+            span.in_scope(|| ScanMetrics::time(&metrics.planning_nanos, || it.next()))
+        }))
+    }
+
+    /// Like [`Scan::scan_metadata`], but replays the log with an [`AsyncEngine`], returning a
+    /// [`Stream`] of [`ScanMetadata`]s. The stream reads the log as it is polled, so no thread
+    /// blocks while the engine waits on I/O. The [`ScanReport::planning_duration`] of the scan only
+    /// covers the time spent polling the stream.
+    ///
+    /// [`AsyncEngine`]: async_engine::AsyncEngine
+    /// [`Stream`]: futures::Stream
+    #[cfg(feature = "async-engine")]
+    pub fn scan_metadata_async(
+        &self,
+        engine: &dyn async_engine::AsyncEngine,
+    ) -> DeltaResult<impl futures::Stream<Item = DeltaResult<ScanMetadata>> + Send> {
+        use futures::stream::{self, StreamExt as _};
+
+        let action_stream = self.replay_for_scan_metadata_async(engine)?;
+        let processing_engine = ProcessingEngine::new(engine);
+        let mut replay = match self.replay_predicate() {
+            Some(physical_predicate) => scan_action_stream(
+                &processing_engine,
+                action_stream,
+                self.logical_schema.clone(),
+                self.static_transform(),
+                physical_predicate,
+                self.stats_columns(),
+                self.metrics.clone(),
+            )
+            .boxed(),
+            None => stream::empty().boxed(),
         };
-        let it = scan_action_iter(
-            engine,
-            action_batch_iter,
-            self.logical_schema.clone(),
-            static_transform,
-            physical_predicate,
+        let metrics = self.metrics.clone();
+        let span = new_span!(
+            INFO,
+            "scan_metadata",
+            table = %self.table_root(),
+            version = self.snapshot.version()
         );
-        Ok(Some(it).into_iter().flatten())
+        Ok(stream::poll_fn(move |cx| {
+            span.in_scope(|| {
+                ScanMetrics::time(&metrics.planning_nanos, || replay.poll_next_unpin(cx))
+            })
+        }))
+    }
+
+    /// The static part of the transformation of the scan's files. This is `None` if no
+    /// transformation is needed. We need transforms for:
+    /// - Partition columns: Must be injected from partition values
+    /// - Column mapping: Physical field names must be mapped to logical field names via output schema
+    /// - Row tracking columns: Must be computed from materialized columns and file metadata
+    /// - Type widening: Narrower physical types of older files must be upcast via output schema
+    fn static_transform(&self) -> Option<Arc<TransformSpec>> {
+        (self.have_partition_cols
+            || self.have_row_tracking_cols
+            || self.have_widened_cols
+            || self.snapshot.column_mapping_mode() != ColumnMappingMode::None)
+            .then(|| Arc::new(get_transform_spec(&self.all_fields)))
+    }
+
+    /// The physical predicate to replay the log with, or `None` if the predicate skips all files,
+    /// so there is no log to replay.
+    #[allow(clippy::type_complexity)]
+    fn replay_predicate(&self) -> Option<Option<(PredicateRef, SchemaRef)>> {
+        match self.physical_predicate.clone() {
+            PhysicalPredicate::StaticSkipAll => None,
+            PhysicalPredicate::Some(predicate, schema) => Some(Some((predicate, schema))),
+            PhysicalPredicate::None => Some(None),
+        }
+    }
+
+    /// The schema of the parsed stats (`add.stats_parsed`) to read from checkpoints for data
+    /// skipping, which checkpoints written with `delta.checkpoint.writeStatsAsStruct` have. These are
+    /// much cheaper to skip files with than the JSON stats of the same files. `None` if the scan does
+    /// no data skipping.
+    fn parsed_stats_schema(&self) -> Option<SchemaRef> {
+        match self.physical_predicate {
+            PhysicalPredicate::Some(_, ref referenced_schema) => stats_schema(referenced_schema),
+            PhysicalPredicate::StaticSkipAll | PhysicalPredicate::None => None,
+        }
+    }
+
+    /// The file statistics to replay the log with: none, unless the scan skips files by their stats
+    /// or was asked to include them (see [`ScanBuilder::with_stats`]).
+    fn stats_columns(&self) -> StatsColumns {
+        match self.parsed_stats_schema() {
+            Some(_) => StatsColumns::JsonAndParsed,
+            None if self.include_stats => StatsColumns::Json,
+            None => StatsColumns::Omitted,
+        }
     }
 
     // Factored out to facilitate testing
@@ -616,14 +971,89 @@ impl Scan {
         &self,
         engine: &dyn Engine,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<ActionsBatch>> + Send> {
+        let (commit_read_schema, checkpoint_read_schema) = self.replay_read_schemas()?;
         // NOTE: We don't pass any meta-predicate because we expect no meaningful row group skipping
         // when ~every checkpoint file will contain the adds and removes we are looking for.
-        self.snapshot.log_segment().read_actions(
-            engine,
-            COMMIT_READ_SCHEMA.clone(),
-            CHECKPOINT_READ_SCHEMA.clone(),
-            None,
-        )
+        let log_segment = self.snapshot.log_segment();
+        // Reuse the commits read to build the snapshot, if it kept them (they have all columns)
+        let actions = if let Some(commit_batches) = self.snapshot.take_commit_batches() {
+            let commits = commit_batches
+                .into_iter()
+                .map(|batch| Ok(ActionsBatch::new(batch, true)));
+            let checkpoint =
+                log_segment.create_checkpoint_stream(engine, checkpoint_read_schema, None)?;
+            Either::Left(commits.chain(checkpoint))
+        } else {
+            Either::Right(log_segment.read_actions(
+                engine,
+                commit_read_schema,
+                checkpoint_read_schema,
+                None,
+            )?)
+        };
+        Ok(match self.replay_action_visitor.clone() {
+            Some(visitor) => Either::Left(actions.map(move |batch| visitor.visit(batch))),
+            None => Either::Right(actions),
+        })
+    }
+
+    /// Like [`Self::replay_for_scan_metadata`], but reads the log with an
+    /// [`async_engine::AsyncEngine`].
+    #[cfg(feature = "async-engine")]
+    fn replay_for_scan_metadata_async(
+        &self,
+        engine: &dyn async_engine::AsyncEngine,
+    ) -> DeltaResult<futures::stream::BoxStream<'static, DeltaResult<ActionsBatch>>> {
+        use futures::stream::{self, StreamExt as _};
+
+        let (commit_read_schema, checkpoint_read_schema) = self.replay_read_schemas()?;
+        let log_segment = self.snapshot.log_segment();
+        let actions = if let Some(commit_batches) = self.snapshot.take_commit_batches() {
+            let commits =
+                stream::iter(commit_batches).map(|batch| Ok(ActionsBatch::new(batch, true)));
+            let checkpoint =
+                log_segment.create_checkpoint_stream_async(engine, checkpoint_read_schema, None)?;
+            commits.chain(checkpoint).boxed()
+        } else {
+            log_segment.read_actions_async(
+                engine,
+                commit_read_schema,
+                checkpoint_read_schema,
+                None,
+            )?
+        };
+        Ok(match self.replay_action_visitor.clone() {
+            Some(visitor) => actions.map(move |batch| visitor.visit(batch)).boxed(),
+            None => actions,
+        })
+    }
+
+    /// The (physical) schemas to read the commit and checkpoint files with when replaying the log
+    /// for scan metadata, including the actions of the replay action visitor, if any.
+    fn replay_read_schemas(&self) -> DeltaResult<(SchemaRef, SchemaRef)> {
+        let (commit_schema, checkpoint_schema) = self.scan_read_schemas();
+        match self.replay_action_visitor {
+            Some(ref visitor) => visitor.read_schemas(commit_schema, checkpoint_schema),
+            None => Ok((commit_schema, checkpoint_schema)),
+        }
+    }
+
+    /// The (physical) schemas to read the commit and checkpoint files with to find the files of
+    /// the scan.
+    fn scan_read_schemas(&self) -> (SchemaRef, SchemaRef) {
+        match self.parsed_stats_schema() {
+            Some(stats_schema) => (
+                COMMIT_READ_SCHEMA.clone(),
+                with_parsed_stats(&CHECKPOINT_READ_SCHEMA, &stats_schema),
+            ),
+            None if self.include_stats => {
+                (COMMIT_READ_SCHEMA.clone(), CHECKPOINT_READ_SCHEMA.clone())
+            }
+            None => (
+                without_stats(&COMMIT_READ_SCHEMA),
+                without_stats(&CHECKPOINT_READ_SCHEMA),
+            ),
+        }
     }
 
     /// Perform an "all in one" scan. This will use the provided `engine` to read and process all
@@ -637,7 +1067,7 @@ impl Scan {
     pub fn execute(
         &self,
         engine: Arc<dyn Engine>,
-    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>> + use<'_>> {
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<ScanResult>>> {
         struct ScanFile {
             path: String,
             size: i64,
@@ -667,24 +1097,57 @@ impl Scan {
         );
 
         let table_root = self.snapshot.table_root().clone();
+        let file_predicate = self.file_skipping_predicate();
 
         let scan_metadata_iter = self.scan_metadata(engine.as_ref())?;
+        let dv_engine = engine.clone(); // Arc clone
+        let dv_table_root = table_root.clone();
+        let dv_metrics = self.metrics.clone();
+        let dv_cache = self.deletion_vector_cache.clone();
         let scan_files_iter = scan_metadata_iter
-            .map(|res| {
+            .map(move |res| {
                 let scan_metadata = res?;
-                let scan_files = vec![];
-                scan_metadata.visit_scan_files(scan_files, scan_metadata_callback)
+                let scan_files = scan_metadata.visit_scan_files(vec![], scan_metadata_callback)?;
+                // read the deletion vectors of all files of the batch together, so that the engine
+                // can fetch them concurrently
+                let dvs: Vec<_> = scan_files
+                    .iter()
+                    .filter_map(|scan_file| scan_file.dv_info.deletion_vector.as_ref())
+                    .collect();
+                let treemaps = ScanMetrics::time(&dv_metrics.deletion_vector_nanos, || {
+                    DeletionVectorDescriptor::read_all(
+                        dv_engine.storage_handler().as_ref(),
+                        &dv_table_root,
+                        &dvs,
+                        dv_cache.as_deref(),
+                    )
+                })?;
+                ScanMetrics::add(&dv_metrics.deletion_vectors_loaded, treemaps.len() as u64);
+                let mut treemaps = treemaps.into_iter();
+                let scan_files: Vec<_> = scan_files
+                    .into_iter()
+                    .map(|scan_file| {
+                        let treemap = match scan_file.dv_info.deletion_vector {
+                            Some(_) => treemaps.next(),
+                            None => None,
+                        };
+                        (scan_file, treemap)
+                    })
+                    .collect();
+                Ok::<_, Error>(scan_files)
             })
-            // Iterator<DeltaResult<Vec<ScanFile>>> to Iterator<DeltaResult<ScanFile>>
+            // Iterator<DeltaResult<Vec<(ScanFile, Option<RoaringTreemap>)>>> to
+            // Iterator<DeltaResult<(ScanFile, Option<RoaringTreemap>)>>
             .flatten_ok();
 
+        let physical_schema = self.physical_schema().clone();
+        let logical_schema = self.logical_schema().clone();
+        let metrics = self.metrics.clone();
         let result = scan_files_iter
             .map(move |scan_file| -> DeltaResult<_> {
-                let scan_file = scan_file?;
+                let (scan_file, treemap) = scan_file?;
                 let file_path = table_root.join(&scan_file.path)?;
-                let mut selection_vector = scan_file
-                    .dv_info
-                    .get_selection_vector(engine.as_ref(), &table_root)?;
+                let mut selection_vector = treemap.map(deletion_treemap_to_bools);
                 let meta = FileMeta {
                     last_modified: 0,
                     size: scan_file.size.try_into().map_err(|_| {
@@ -693,27 +1156,32 @@ impl Scan {
                     location: file_path,
                 };
 
-                // WARNING: We validated the physical predicate against a schema that includes
-                // partition columns, but the read schema we use here does _NOT_ include partition
-                // columns. So we cannot safely assume that all column references are valid. See
-                // https://github.com/delta-io/delta-kernel-rs/issues/434 for more details.
-                //
-                // TODO(#860): we disable predicate pushdown until we support row indexes.
-                let read_result_iter = engine.parquet_handler().read_parquet_files(
-                    &[meta],
-                    self.physical_schema().clone(),
-                    None,
-                )?;
+                // Let the parquet reader skip row groups, unless the file has a deletion vector:
+                // its selection vector covers all rows of the file, and would no longer line up
+                // with the rows read.
+                let predicate = match selection_vector {
+                    Some(_) => None,
+                    None => file_predicate.clone(),
+                };
+                let read_result_iter = ScanMetrics::time(&metrics.read_nanos, || {
+                    engine.parquet_handler().read_parquet_files(
+                        &[meta],
+                        physical_schema.clone(),
+                        predicate,
+                    )
+                })?;
 
                 let engine = engine.clone(); // Arc clone
-                Ok(read_result_iter.map(move |read_result| -> DeltaResult<_> {
+                let physical_schema = physical_schema.clone();
+                let logical_schema = logical_schema.clone();
+                let mut results = read_result_iter.map(move |read_result| -> DeltaResult<_> {
                     let read_result = read_result?;
                     // transform the physical data into the correct logical form
                     let logical = state::transform_to_logical(
                         engine.as_ref(),
                         read_result,
-                        self.physical_schema(),
-                        self.logical_schema(),
+                        &physical_schema,
+                        &logical_schema,
                         scan_file.transform.clone(), // Arc clone
                     );
                     let len = logical.as_ref().map_or(0, |res| res.len());
@@ -729,6 +1197,11 @@ impl Scan {
                     };
                     selection_vector = rest;
                     Ok(result)
+                });
+                // time reading (and transforming) the data as the caller consumes it
+                let metrics = metrics.clone();
+                Ok(std::iter::from_fn(move || {
+                    ScanMetrics::time(&metrics.read_nanos, || results.next())
                 }))
             })
             // Iterator<DeltaResult<Iterator<DeltaResult<ScanResult>>>> to Iterator<DeltaResult<DeltaResult<ScanResult>>>
@@ -739,6 +1212,53 @@ impl Scan {
     }
 }
 
+impl Scan {
+    /// The part of the physical predicate that the parquet reader can use to skip row groups of
+    /// data files, if any.
+    ///
+    /// NOTE: We validated the physical predicate against a schema that includes partition columns,
+    /// but the read schema does _NOT_ include partition columns, and the reader considers columns
+    /// missing from a file to be all-null. See
+    /// https://github.com/delta-io/delta-kernel-rs/issues/434 for more details. So we only keep
+    /// the top-level conjuncts of the predicate that exclusively reference columns of the read
+    /// schema.
+    fn file_skipping_predicate(&self) -> Option<PredicateRef> {
+        let PhysicalPredicate::Some(predicate, _) = &self.physical_predicate else {
+            return None;
+        };
+        let read_columns: HashSet<_> = self
+            .physical_schema
+            .fields()
+            .filter(|field| !field.is_metadata_column())
+            .map(|field| field.name())
+            .collect();
+        let only_reads_columns = |pred: &Predicate| {
+            pred.references().iter().all(|column| {
+                column
+                    .first()
+                    .is_some_and(|name| read_columns.contains(name))
+            })
+        };
+        let conjuncts: Vec<_> = match predicate.as_ref() {
+            Predicate::Junction(JunctionPredicate {
+                op: JunctionPredicateOp::And,
+                preds,
+            }) => preds
+                .iter()
+                .filter(|pred| only_reads_columns(pred))
+                .cloned()
+                .collect(),
+            pred if only_reads_columns(pred) => vec![pred.clone()],
+            _ => vec![],
+        };
+        match conjuncts.len() {
+            0 => None,
+            1 if conjuncts[0] == **predicate => Some(predicate.clone()),
+            _ => Some(Arc::new(Predicate::and_from(conjuncts))),
+        }
+    }
+}
+
 /// Get the schema that scan rows (from [`Scan::scan_metadata`]) will be returned with.
 ///
 /// It is:
@@ -747,7 +1267,7 @@ impl Scan {
 ///    path: string,
 ///    size: long,
 ///    modificationTime: long,
-///    stats: string,
+///    stats: string, // null unless the scan skips files by their stats or includes them
 ///    deletionVector: {
 ///      storageType: string,
 ///      pathOrInlineDv: string,
@@ -756,7 +1276,9 @@ impl Scan {
 ///      cardinality: long,
 ///    },
 ///    fileConstantValues: {
-///      partitionValues: map<string, string>
+///      partitionValues: map<string, string>,
+///      baseRowId: long,
+///      defaultRowCommitVersion: long,
 ///    }
 /// }
 /// ```
@@ -772,23 +1294,39 @@ struct StateInfo {
     read_fields: Vec<StructField>,
     /// True if this query references any partition columns.
     have_partition_cols: bool,
+    /// True if this query references any row tracking (row ID or row commit version) columns.
+    have_row_tracking_cols: bool,
 }
 
 impl StateInfo {
-    /// Get the state needed to process a scan.
+    /// Get the state needed to process a scan. `row_tracking_columns` must be given if the schema
+    /// requests row IDs or row commit versions.
     fn try_new(
         logical_schema: &Schema,
         partition_columns: &[String],
         column_mapping_mode: ColumnMappingMode,
+        row_tracking_columns: Option<MaterializedRowTrackingColumns<'_>>,
     ) -> DeltaResult<Self> {
         let mut have_partition_cols = false;
+        let mut have_row_tracking_cols = false;
         let mut read_fields = Vec::with_capacity(logical_schema.num_fields());
         let mut read_field_names = HashSet::with_capacity(logical_schema.num_fields());
 
+        // Row IDs that are not materialized are computed from the row indexes of the file, so we
+        // read the row index column requested by the query, or an internal one if there is none.
+        let requested_row_index_column = logical_schema
+            .metadata_column(&MetadataColumnSpec::RowIndex)
+            .map(|field| field.name().to_string());
+        let needs_internal_row_index = requested_row_index_column.is_none()
+            && logical_schema.contains_metadata_column(&MetadataColumnSpec::RowId);
+        let row_index_column = requested_row_index_column
+            .unwrap_or_else(|| INTERNAL_ROW_INDEX_COLUMN_NAME.to_string());
+
         // Loop over all selected fields and note if they are columns that will be read from the
         // parquet file ([`ColumnType::Selected`]) or if they are partition columns and will need to
-        // be filled in by evaluating an expression ([`ColumnType::Partition`])
-        let all_fields = logical_schema
+        // be filled in by evaluating an expression ([`ColumnType::Partition`]). Row tracking columns
+        // are read from their materialized columns, and completed by evaluating an expression.
+        let mut all_fields: Vec<_> = logical_schema
             .fields()
             .enumerate()
             .map(|(index, logical_field)| -> DeltaResult<_> {
@@ -805,6 +1343,40 @@ impl StateInfo {
                     // data type, which we need to properly materialize the column.
                     have_partition_cols = true;
                     Ok(ColumnType::Partition(index))
+                } else if let Some(
+                    spec @ (MetadataColumnSpec::RowId | MetadataColumnSpec::RowCommitVersion),
+                ) = logical_field.get_metadata_column_spec()
+                {
+                    // Row tracking columns are read from their materialized columns, which are
+                    // missing (null) in files that don't materialize them. The transform fills in
+                    // those values from file metadata.
+                    let Some(row_tracking_columns) = row_tracking_columns else {
+                        return Err(Error::internal_error(
+                            "Row tracking columns were requested without materialized column names",
+                        ));
+                    };
+                    have_row_tracking_cols = true;
+                    if spec == MetadataColumnSpec::RowId {
+                        let materialized_column = row_tracking_columns.row_id.to_string();
+                        read_fields.push(StructField::nullable(
+                            materialized_column.clone(),
+                            DataType::LONG,
+                        ));
+                        Ok(ColumnType::RowId {
+                            materialized_column,
+                            row_index_column: row_index_column.clone(),
+                        })
+                    } else {
+                        let materialized_column =
+                            row_tracking_columns.row_commit_version.to_string();
+                        read_fields.push(StructField::nullable(
+                            materialized_column.clone(),
+                            DataType::LONG,
+                        ));
+                        Ok(ColumnType::RowCommitVersion {
+                            materialized_column,
+                        })
+                    }
                 } else {
                     // Add to read schema, store field so we can build a `Column` expression later
                     // if needed (i.e. if we have partition columns)
@@ -821,6 +1393,15 @@ impl StateInfo {
                 }
             })
             .try_collect()?;
+        if needs_internal_row_index {
+            read_fields.push(StructField::create_metadata_column(
+                INTERNAL_ROW_INDEX_COLUMN_NAME,
+                MetadataColumnSpec::RowIndex,
+            ));
+            all_fields.push(ColumnType::Internal(
+                INTERNAL_ROW_INDEX_COLUMN_NAME.to_string(),
+            ));
+        }
 
         // This iteration runs in O(3) time since each metadata column can appear at most once in the schema
         for metadata_column in logical_schema.metadata_columns() {
@@ -836,6 +1417,7 @@ impl StateInfo {
             all_fields,
             read_fields,
             have_partition_cols,
+            have_row_tracking_cols,
         })
     }
 }
@@ -865,7 +1447,7 @@ pub(crate) mod test_utils {
             arrow_data::ArrowEngineData,
             sync::{json::SyncJsonHandler, SyncEngine},
         },
-        scan::log_replay::scan_action_iter,
+        scan::log_replay::{scan_action_iter, StatsColumns},
         schema::SchemaRef,
         JsonHandler,
     };
@@ -973,6 +1555,9 @@ pub(crate) mod test_utils {
             logical_schema,
             transform_spec,
             None,
+            StatsColumns::Json,
+            1,
+            Default::default(),
         );
         let mut batch_count = 0;
         for res in iter {
@@ -990,13 +1575,16 @@ pub(crate) mod test_utils {
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
+    use std::time::Duration;
 
     use crate::arrow::array::BooleanArray;
     use crate::arrow::compute::filter_record_batch;
     use crate::arrow::record_batch::RecordBatch;
     use crate::engine::arrow_data::ArrowEngineData;
     use crate::engine::sync::SyncEngine;
-    use crate::expressions::{column_expr, column_pred, Expression as Expr, Predicate as Pred};
+    use crate::expressions::{
+        column_expr, column_name, column_pred, Expression as Expr, Predicate as Pred,
+    };
     use crate::schema::{ColumnMetadataKey, PrimitiveType};
     use crate::Snapshot;
 
@@ -1219,6 +1807,118 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scan_metadata_single_pass_replay() {
+        // copy the table so that its commits can be deleted after building the snapshot
+        let source = PathBuf::from("./tests/data/with_checkpoint_no_last_checkpoint/_delta_log");
+        let table = tempfile::tempdir().unwrap();
+        let log_dir = table.path().join("_delta_log");
+        std::fs::create_dir(&log_dir).unwrap();
+        for entry in std::fs::read_dir(source).unwrap() {
+            let entry = entry.unwrap();
+            std::fs::copy(entry.path(), log_dir.join(entry.file_name())).unwrap();
+        }
+        let url = url::Url::from_directory_path(table.path()).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::builder_for(url.clone()).build(&engine).unwrap();
+        let expected = get_files_for_scan(snapshot.scan_builder().build().unwrap(), &engine);
+        let expected = expected.unwrap();
+
+        let snapshot = Snapshot::builder_for(url)
+            .with_single_pass_replay(true)
+            .build(&engine)
+            .unwrap();
+        for entry in std::fs::read_dir(&log_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                std::fs::remove_file(path).unwrap();
+            }
+        }
+
+        // the first scan replays the commits kept by the snapshot, later scans read them again
+        let scan = snapshot.clone().scan_builder().build().unwrap();
+        assert_eq!(get_files_for_scan(scan, &engine).unwrap(), expected);
+        let scan = snapshot.scan_builder().build().unwrap();
+        assert!(get_files_for_scan(scan, &engine).is_err());
+    }
+
+    #[test]
+    fn test_scan_metadata_stats() {
+        let path =
+            std::fs::canonicalize(PathBuf::from("./tests/data/table-without-dv-small/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+
+        fn num_records(scan: Scan, engine: &dyn Engine) -> Vec<Option<u64>> {
+            fn callback(
+                num_records: &mut Vec<Option<u64>>,
+                _: &str,
+                _: i64,
+                stats: Option<Stats>,
+                _: DvInfo,
+                _: Option<ExpressionRef>,
+                _: HashMap<String, String>,
+            ) {
+                num_records.push(stats.map(|stats| stats.num_records));
+            }
+            let mut num_records = vec![];
+            for res in scan.scan_metadata(engine).unwrap() {
+                num_records = res
+                    .unwrap()
+                    .visit_scan_files(num_records, callback)
+                    .unwrap();
+            }
+            num_records
+        }
+
+        // without a predicate the stats aren't read unless requested
+        let scan = snapshot.clone().scan_builder().build().unwrap();
+        assert_eq!(scan.stats_columns(), StatsColumns::Omitted);
+        assert_eq!(num_records(scan, &engine), vec![None]);
+
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_stats(true)
+            .build()
+            .unwrap();
+        assert_eq!(scan.stats_columns(), StatsColumns::Json);
+        assert_eq!(num_records(scan, &engine), vec![Some(10)]);
+
+        // data skipping reads the stats anyway, so they are returned too
+        let predicate = Arc::new(column_expr!("value").gt(Expr::literal(3i64)));
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(predicate)
+            .build()
+            .unwrap();
+        assert_eq!(scan.stats_columns(), StatsColumns::JsonAndParsed);
+        assert_eq!(num_records(scan, &engine), vec![Some(10)]);
+    }
+
+    #[test]
+    fn test_scan_metadata_type_widening_transforms() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/type-widening/")).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        let scan = snapshot.scan_builder().build().unwrap();
+        assert!(scan.have_widened_cols);
+
+        // every file needs a transform to upcast its narrower physical types
+        let transforms: Vec<_> = scan
+            .scan_metadata(&engine)
+            .unwrap()
+            .map_ok(|metadata| metadata.scan_file_transforms)
+            .flatten_ok()
+            .try_collect()
+            .unwrap();
+        assert_eq!(transforms.iter().flatten().count(), 2);
+    }
+
     #[test_log::test]
     fn test_scan_metadata() {
         let path =
@@ -1358,7 +2058,7 @@ mod tests {
 
         for (raw, data_type, expected) in &cases {
             let value = crate::transforms::parse_partition_value_raw(
-                Some(&raw.to_string()),
+                Some(raw),
                 &DataType::Primitive(data_type.clone()),
             )
             .unwrap();
@@ -1386,6 +2086,104 @@ mod tests {
         assert_eq!(data.len(), 5);
     }
 
+    // Collects the app ids of the `txn` actions and the operations of the `commitInfo` actions
+    // visited during log replay, along with whether they are from a commit
+    #[derive(Default)]
+    struct CollectingVisitor {
+        actions: std::sync::Mutex<Vec<(String, bool)>>,
+    }
+
+    impl ReplayActionVisitor for CollectingVisitor {
+        fn visit_actions(&self, actions: &dyn EngineData, is_log_batch: bool) -> DeltaResult<()> {
+            use crate::engine_data::{GetData, RowVisitor};
+
+            struct Rows<'a>(&'a mut Vec<(String, bool)>, bool);
+            impl RowVisitor for Rows<'_> {
+                fn selected_column_names_and_types(
+                    &self,
+                ) -> (&'static [ColumnName], &'static [DataType]) {
+                    static NAMES_AND_TYPES: LazyLock<crate::schema::ColumnNamesAndTypes> =
+                        LazyLock::new(|| {
+                            let names = vec![
+                                ColumnName::new(["txn", "appId"]),
+                                ColumnName::new(["commitInfo", "operation"]),
+                            ];
+                            (names, vec![DataType::STRING, DataType::STRING]).into()
+                        });
+                    NAMES_AND_TYPES.as_ref()
+                }
+
+                fn visit<'a>(
+                    &mut self,
+                    row_count: usize,
+                    getters: &[&'a dyn GetData<'a>],
+                ) -> DeltaResult<()> {
+                    for i in 0..row_count {
+                        if let Some(app_id) = getters[0].get_str(i, "txn.appId")? {
+                            self.0.push((format!("txn {app_id}"), self.1));
+                        }
+                        if let Some(operation) = getters[1].get_str(i, "commitInfo.operation")? {
+                            self.0.push((format!("commitInfo {operation}"), self.1));
+                        }
+                    }
+                    Ok(())
+                }
+            }
+            let mut collected = self.actions.lock().unwrap();
+            Rows(&mut collected, is_log_batch).visit_rows_of(actions)
+        }
+    }
+
+    fn visit_replay_actions(table: &str) -> Vec<(String, bool)> {
+        let path = std::fs::canonicalize(PathBuf::from(table)).unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        let visitor = Arc::new(CollectingVisitor::default());
+        let scan = snapshot
+            .scan_builder()
+            .with_replay_action_visitor(
+                [
+                    ReplayActionType::SetTransaction,
+                    ReplayActionType::CommitInfo,
+                ],
+                visitor.clone(),
+            )
+            .build()
+            .unwrap();
+        let files: Vec<_> = scan
+            .scan_metadata(&engine)
+            .unwrap()
+            .map_ok(|scan_metadata| scan_metadata.scan_files.selection_vector.len())
+            .try_collect()
+            .unwrap();
+        // the scan still finds its files
+        assert!(!files.is_empty());
+        let actions = visitor.actions.lock().unwrap().clone();
+        actions
+    }
+
+    #[test]
+    fn test_replay_action_visitor() {
+        let actions = visit_replay_actions("./tests/data/app-txn-no-checkpoint/");
+        let expected = [
+            ("commitInfo WRITE", true),
+            ("txn my-app", true),
+            ("txn my-app2", true),
+            ("commitInfo WRITE", true),
+            ("txn my-app", true),
+        ];
+        let expected = expected.map(|(action, is_log_batch)| (action.to_string(), is_log_batch));
+        assert_eq!(actions, expected);
+
+        // the actions of the table state are read from the checkpoint
+        let mut actions = visit_replay_actions("./tests/data/app-txn-checkpoint/");
+        actions.sort();
+        let expected = [("txn my-app", false), ("txn my-app2", false)];
+        let expected = expected.map(|(action, is_log_batch)| (action.to_string(), is_log_batch));
+        assert_eq!(actions, expected);
+    }
+
     #[test]
     fn test_data_row_group_skipping() {
         let path = std::fs::canonicalize(PathBuf::from("./tests/data/parquet_row_group_skipping/"));
@@ -1414,11 +2212,7 @@ mod tests {
         let data: Vec<_> = scan.execute(engine.clone()).unwrap().try_collect().unwrap();
         assert_eq!(data.len(), 1);
 
-        // TODO(#860): we disable predicate pushdown until we support row indexes. Update this test
-        // accordingly after support is reintroduced.
-        //
-        // Effective predicate pushdown, so no data files should be returned. BUT since we disabled
-        // predicate pushdown, the one data file is still returned.
+        // Effective predicate pushdown, so no data should be returned.
         let predicate = Arc::new(int_col.lt(value));
         let scan = snapshot
             .scan_builder()
@@ -1426,7 +2220,334 @@ mod tests {
             .build()
             .unwrap();
         let data: Vec<_> = scan.execute(engine).unwrap().try_collect().unwrap();
-        assert_eq!(data.len(), 1);
+        assert_eq!(data.len(), 0);
+    }
+
+    #[test]
+    fn test_scan_with_deletion_vector_cache() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+        let cache = Arc::new(DeletionVectorCache::default());
+
+        let masks = |cache: Option<&Arc<DeletionVectorCache>>| -> Vec<_> {
+            let mut builder = snapshot.clone().scan_builder();
+            if let Some(cache) = cache {
+                builder = builder.with_deletion_vector_cache(cache.clone());
+            }
+            let scan = builder.build().unwrap();
+            scan.execute(engine.clone())
+                .unwrap()
+                .map_ok(|result| result.raw_mask)
+                .try_collect()
+                .unwrap()
+        };
+        let expected = masks(None);
+        assert_eq!(masks(Some(&cache)), expected);
+        assert_eq!(cache.len(), 1);
+        // the second scan reads the deletion vector from the cache
+        assert_eq!(masks(Some(&cache)), expected);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_scan_report() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+
+        // the stats of 3 files show them to only have numbers <= 3, and 2 of the others are in
+        // other partitions (letter=e and letter=null)
+        let predicate = Pred::and(
+            Pred::eq(column_expr!("letter"), Expr::literal("a")),
+            Pred::gt(column_expr!("number"), Expr::literal(3i64)),
+        );
+        let scan = snapshot
+            .scan_builder()
+            .with_predicate(Arc::new(predicate))
+            .build()
+            .unwrap();
+        assert_eq!(scan.report(), ScanReport::default());
+        for scan_metadata in scan.scan_metadata(&engine).unwrap() {
+            scan_metadata.unwrap();
+        }
+
+        let report = scan.report();
+        assert_eq!(report.files_seen, 6);
+        assert_eq!(report.files_pruned_by_stats, 3);
+        assert_eq!(report.files_pruned_by_partition, 2);
+        assert_eq!(report.files_planned, 1);
+        assert_eq!(report.bytes_planned, 751);
+        assert_eq!(report.deletion_vectors_loaded, 0);
+        assert!(report.planning_duration > Duration::ZERO);
+        assert_eq!(report.read_duration, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_scan_with_skipping_parallelism() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+
+        let predicate = Arc::new(Pred::gt(column_expr!("number"), Expr::literal(3i64)));
+        let files = |skipping_parallelism| {
+            let scan = snapshot
+                .clone()
+                .scan_builder()
+                .with_predicate(predicate.clone())
+                .with_skipping_parallelism(skipping_parallelism)
+                .build()
+                .unwrap();
+            get_files_for_scan(scan, &engine).unwrap()
+        };
+        // the stats of 3 of the 6 files show them to only have numbers <= 3
+        let expected = files(1);
+        assert_eq!(expected.len(), 3);
+        for skipping_parallelism in [0, 2, 8] {
+            assert_eq!(files(skipping_parallelism), expected);
+        }
+    }
+
+    #[test]
+    fn test_scan_report_of_execute() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = Arc::new(SyncEngine::new());
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref()).unwrap();
+        let scan = snapshot.scan_builder().build().unwrap();
+        for result in scan.execute(engine.clone()).unwrap() {
+            result.unwrap();
+        }
+
+        let report = scan.report();
+        assert_eq!(report.files_seen, 2);
+        assert_eq!(report.files_planned, 1);
+        assert_eq!(report.deletion_vectors_loaded, 1);
+        assert!(report.deletion_vector_duration > Duration::ZERO);
+        assert!(report.read_duration > Duration::ZERO);
+    }
+
+    #[cfg(feature = "tracing-spans")]
+    #[test]
+    fn test_tracing_spans() {
+        use std::sync::Mutex;
+        use tracing::span::{Attributes, Id};
+        use tracing_subscriber::layer::{Context, SubscriberExt as _};
+        use tracing_subscriber::Layer;
+
+        /// Records the names of all spans created
+        #[derive(Clone, Default)]
+        struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+        impl<S: tracing::Subscriber> Layer<S> for SpanNames {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+                self.0.lock().unwrap().push(attrs.metadata().name());
+            }
+        }
+
+        let path = std::fs::canonicalize(PathBuf::from(
+            "./tests/data/with_checkpoint_no_last_checkpoint/",
+        ))
+        .unwrap();
+        let url = url::Url::from_directory_path(path).unwrap();
+        let engine = SyncEngine::new();
+        let span_names = SpanNames::default();
+        let subscriber = tracing_subscriber::registry().with(span_names.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+            let predicate = Pred::eq(column_expr!("letter"), Expr::literal("a"));
+            let scan = snapshot
+                .scan_builder()
+                .with_predicate(Arc::new(predicate))
+                .build()
+                .unwrap();
+            for scan_metadata in scan.scan_metadata(&engine).unwrap() {
+                scan_metadata.unwrap();
+            }
+        });
+
+        let span_names: HashSet<_> = span_names.0.lock().unwrap().iter().copied().collect();
+        let expected = [
+            "snapshot",
+            "list_log_files",
+            "protocol_and_metadata",
+            "read_checkpoint",
+            "scan_metadata",
+            "data_skipping",
+        ];
+        assert_eq!(span_names, expected.into_iter().collect());
+    }
+
+    #[test]
+    fn test_scan_case_insensitive_resolution() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+
+        let schema = Arc::new(StructType::new_unchecked([
+            StructField::nullable("LETTER", DataType::STRING),
+            StructField::nullable("Number", DataType::LONG),
+        ]));
+        let predicate = Arc::new(Pred::gt(column_expr!("NUMBER"), Expr::literal(1i64)));
+        let scan = snapshot
+            .clone()
+            .scan_builder()
+            .with_schema(schema.clone())
+            .with_predicate(predicate.clone())
+            .with_case_insensitive_resolution(true)
+            .build()
+            .unwrap();
+        let names: Vec<_> = scan.logical_schema().fields().map(|f| f.name()).collect();
+        assert_eq!(names, ["letter", "number"]);
+        assert!(scan.have_partition_cols);
+        assert_eq!(
+            scan.physical_predicate(),
+            Some(Arc::new(Pred::gt(
+                column_expr!("number"),
+                Expr::literal(1i64)
+            )))
+        );
+
+        // Case-sensitive resolution does not find the predicate column
+        let result = snapshot
+            .clone()
+            .scan_builder()
+            .with_schema(schema)
+            .with_predicate(predicate)
+            .build();
+        assert!(result.is_err());
+
+        // Two requested columns resolve to the same table column
+        let schema = Arc::new(StructType::new_unchecked([
+            StructField::nullable("number", DataType::LONG),
+            StructField::nullable("NUMBER", DataType::LONG),
+        ]));
+        let result = snapshot
+            .scan_builder()
+            .with_schema(schema)
+            .with_case_insensitive_resolution(true)
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_file_skipping_predicate() {
+        let path = std::fs::canonicalize(PathBuf::from("./tests/data/basic_partitioned/"));
+        let url = url::Url::from_directory_path(path.unwrap()).unwrap();
+        let engine = SyncEngine::new();
+        let snapshot = Snapshot::builder_for(url).build(&engine).unwrap();
+        let file_predicate = |predicate: Pred| {
+            snapshot
+                .clone()
+                .scan_builder()
+                .with_predicate(Arc::new(predicate))
+                .build()
+                .unwrap()
+                .file_skipping_predicate()
+        };
+
+        let number_gt = Pred::gt(column_expr!("number"), Expr::literal(1i64));
+        let letter_eq = Pred::eq(column_expr!("letter"), Expr::literal("a"));
+        assert_eq!(
+            file_predicate(number_gt.clone()),
+            Some(Arc::new(number_gt.clone()))
+        );
+        // Conjuncts that reference partition columns are dropped
+        assert_eq!(
+            file_predicate(Pred::and(letter_eq.clone(), number_gt.clone())),
+            Some(Arc::new(Pred::and_from([number_gt.clone()])))
+        );
+        assert_eq!(file_predicate(letter_eq.clone()), None);
+        assert_eq!(file_predicate(Pred::or(letter_eq, number_gt)), None);
+    }
+
+    #[test]
+    fn test_resolve_column_case_insensitive() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable(
+                "Nested",
+                StructType::new_unchecked([StructField::nullable("Leaf", DataType::INTEGER)]),
+            ),
+            StructField::nullable("dup", DataType::INTEGER),
+            StructField::nullable("DUP", DataType::INTEGER),
+        ]);
+        let resolve = |column| resolve_column_case_insensitive(&column, &schema);
+        assert_eq!(
+            resolve(column_name!("nested.leaf")).unwrap(),
+            column_name!("Nested.Leaf")
+        );
+        // Unknown columns are kept as-is
+        assert_eq!(
+            resolve(column_name!("nested.missing.x")).unwrap(),
+            column_name!("Nested.missing.x")
+        );
+        assert!(resolve(column_name!("Dup")).is_err());
+    }
+
+    #[test]
+    fn test_state_info_row_tracking_columns() {
+        let schema = StructType::new_unchecked([StructField::nullable("id", DataType::LONG)])
+            .add_metadata_column("row_id", MetadataColumnSpec::RowId)
+            .unwrap()
+            .add_metadata_column("row_commit_version", MetadataColumnSpec::RowCommitVersion)
+            .unwrap();
+        let columns = MaterializedRowTrackingColumns {
+            row_id: "row_id_col",
+            row_commit_version: "row_commit_version_col",
+        };
+        let state_info =
+            StateInfo::try_new(&schema, &[], ColumnMappingMode::None, Some(columns)).unwrap();
+        assert!(state_info.have_row_tracking_cols);
+        assert_eq!(
+            state_info.all_fields,
+            vec![
+                ColumnType::Selected("id".to_string()),
+                ColumnType::RowId {
+                    materialized_column: "row_id_col".to_string(),
+                    row_index_column: INTERNAL_ROW_INDEX_COLUMN_NAME.to_string(),
+                },
+                ColumnType::RowCommitVersion {
+                    materialized_column: "row_commit_version_col".to_string(),
+                },
+                ColumnType::Internal(INTERNAL_ROW_INDEX_COLUMN_NAME.to_string()),
+            ]
+        );
+        let read_fields: Vec<_> = state_info.read_fields.iter().map(|f| f.name()).collect();
+        assert_eq!(
+            read_fields,
+            vec![
+                "id",
+                "row_id_col",
+                "row_commit_version_col",
+                INTERNAL_ROW_INDEX_COLUMN_NAME
+            ]
+        );
+
+        // a requested row index column is reused to compute row IDs
+        let schema = StructType::new_unchecked([StructField::nullable("id", DataType::LONG)])
+            .add_metadata_column("row_id", MetadataColumnSpec::RowId)
+            .unwrap()
+            .add_metadata_column("row_index", MetadataColumnSpec::RowIndex)
+            .unwrap();
+        let state_info =
+            StateInfo::try_new(&schema, &[], ColumnMappingMode::None, Some(columns)).unwrap();
+        assert_eq!(
+            state_info.all_fields,
+            vec![
+                ColumnType::Selected("id".to_string()),
+                ColumnType::RowId {
+                    materialized_column: "row_id_col".to_string(),
+                    row_index_column: "row_index".to_string(),
+                },
+                ColumnType::Selected("row_index".to_string()),
+            ]
+        );
+
+        // row tracking columns can't be read without the materialized column names
+        assert!(StateInfo::try_new(&schema, &[], ColumnMappingMode::None, None).is_err());
     }
 
     #[test]
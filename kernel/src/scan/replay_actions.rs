@@ -0,0 +1,101 @@
+//! Visiting additional actions in the same log replay that a scan uses to find its files, see
+//! [`ScanBuilder::with_replay_action_visitor`].
+//!
+//! [`ScanBuilder::with_replay_action_visitor`]: crate::scan::ScanBuilder::with_replay_action_visitor
+use std::sync::Arc;
+
+use itertools::Itertools as _;
+
+use crate::actions::{
+    get_log_schema, CDC_NAME, COMMIT_INFO_NAME, DOMAIN_METADATA_NAME, SET_TRANSACTION_NAME,
+};
+use crate::log_replay::ActionsBatch;
+use crate::schema::{SchemaRef, StructType};
+use crate::{DeltaResult, EngineData};
+
+/// A type of action that a [`ReplayActionVisitor`] can visit, in addition to the `add` and
+/// `remove` actions that a scan always reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReplayActionType {
+    /// `commitInfo` actions, which are only found in commits.
+    CommitInfo,
+    /// `txn` (set transaction) actions.
+    SetTransaction,
+    /// `domainMetadata` actions.
+    DomainMetadata,
+    /// `cdc` actions, which are only found in commits.
+    Cdc,
+}
+
+impl ReplayActionType {
+    /// The name of the column of the actions of this type in the log, e.g. `commitInfo`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CommitInfo => COMMIT_INFO_NAME,
+            Self::SetTransaction => SET_TRANSACTION_NAME,
+            Self::DomainMetadata => DOMAIN_METADATA_NAME,
+            Self::Cdc => CDC_NAME,
+        }
+    }
+}
+
+/// Visits the batches of actions that a scan reads while replaying the log (see
+/// [`Scan::scan_metadata`]), so that engines that need other actions of the log (e.g. the
+/// `domainMetadata` of the table) don't have to replay the log a second time.
+///
+/// [`Scan::scan_metadata`]: crate::scan::Scan::scan_metadata
+pub trait ReplayActionVisitor: Send + Sync {
+    /// Visit a batch of actions. Besides the columns the scan reads, the batch has a nullable
+    /// column for each [`ReplayActionType`] the visitor was registered for, named by
+    /// [`ReplayActionType::name`] and with the schema of the action in the log, so it can be
+    /// visited with a [`RowVisitor`]. Batches of commits (`is_log_batch`) come first, newest
+    /// first, followed by the batches of the checkpoint, so the first action seen for e.g. an app
+    /// id or domain is the latest one. Checkpoints only contain the actions that are part of the
+    /// table state, so their `commitInfo` and `cdc` columns are always null.
+    ///
+    /// Returning an error fails the scan metadata iteration with that error.
+    ///
+    /// [`RowVisitor`]: crate::RowVisitor
+    fn visit_actions(&self, actions: &dyn EngineData, is_log_batch: bool) -> DeltaResult<()>;
+}
+
+/// A [`ReplayActionVisitor`] with the action types it was registered for.
+#[derive(Clone)]
+pub(crate) struct RegisteredVisitor {
+    pub(crate) action_types: Vec<ReplayActionType>,
+    pub(crate) visitor: Arc<dyn ReplayActionVisitor>,
+}
+
+impl RegisteredVisitor {
+    /// The `commit_schema` and `checkpoint_schema` to read the log with, extended by the columns
+    /// of the registered action types.
+    pub(crate) fn read_schemas(
+        &self,
+        commit_schema: SchemaRef,
+        checkpoint_schema: SchemaRef,
+    ) -> DeltaResult<(SchemaRef, SchemaRef)> {
+        let extend = |schema: SchemaRef| -> DeltaResult<SchemaRef> {
+            let fields = self
+                .action_types
+                .iter()
+                .unique()
+                .filter(|action_type| schema.field(action_type.name()).is_none())
+                .filter_map(|action_type| get_log_schema().field(action_type.name()).cloned())
+                .collect::<Vec<_>>();
+            if fields.is_empty() {
+                return Ok(schema);
+            }
+            let fields = schema.fields().cloned().chain(fields);
+            Ok(Arc::new(StructType::try_new(fields)?))
+        };
+        Ok((extend(commit_schema)?, extend(checkpoint_schema)?))
+    }
+
+    /// Pass `batch` to the visitor, and return it for the scan to process.
+    pub(crate) fn visit(&self, batch: DeltaResult<ActionsBatch>) -> DeltaResult<ActionsBatch> {
+        let batch = batch?;
+        self.visitor
+            .visit_actions(batch.actions.as_ref(), batch.is_log_batch)?;
+        Ok(batch)
+    }
+}
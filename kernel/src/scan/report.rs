@@ -0,0 +1,96 @@
+//! Reporting of the work a [`Scan`] performs, e.g. for engines to include in the output of
+//! `EXPLAIN ANALYZE`.
+//!
+//! [`Scan`]: super::Scan
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A summary of the work a [`Scan`] has performed so far, returned by [`Scan::report`].
+///
+/// The report accumulates over all calls to [`Scan::scan_metadata`] and [`Scan::execute`] of the
+/// scan, including work done on the scan's behalf by iterators that are still being consumed.
+///
+/// [`Scan`]: super::Scan
+/// [`Scan::report`]: super::Scan::report
+/// [`Scan::scan_metadata`]: super::Scan::scan_metadata
+/// [`Scan::execute`]: super::Scan::execute
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanReport {
+    /// The number of add actions read from the log during log replay, before any pruning or
+    /// reconciliation with remove actions.
+    pub files_seen: u64,
+    /// The number of add actions skipped because their partition values don't satisfy the scan's
+    /// predicate.
+    pub files_pruned_by_partition: u64,
+    /// The number of add actions skipped because their statistics show that no row of the file
+    /// satisfies the scan's predicate.
+    pub files_pruned_by_stats: u64,
+    /// The number of files selected for the scan to read.
+    pub files_planned: u64,
+    /// The total size in bytes of the files selected for the scan to read.
+    pub bytes_planned: u64,
+    /// The number of deletion vectors loaded by [`Scan::execute`]. Engines that read deletion
+    /// vectors themselves (e.g. with [`DvInfo::get_selection_vector`]) must track those
+    /// separately.
+    ///
+    /// [`Scan::execute`]: super::Scan::execute
+    /// [`DvInfo::get_selection_vector`]: super::state::DvInfo::get_selection_vector
+    pub deletion_vectors_loaded: u64,
+    /// The time spent replaying the log to produce scan metadata, including reading the log.
+    pub planning_duration: Duration,
+    /// The time [`Scan::execute`] spent loading deletion vectors.
+    ///
+    /// [`Scan::execute`]: super::Scan::execute
+    pub deletion_vector_duration: Duration,
+    /// The time [`Scan::execute`] spent reading data files and transforming their data to its
+    /// logical form.
+    ///
+    /// [`Scan::execute`]: super::Scan::execute
+    pub read_duration: Duration,
+}
+
+/// The counters backing a [`ScanReport`], shared by a scan and the iterators it returns.
+#[derive(Debug, Default)]
+pub(crate) struct ScanMetrics {
+    pub(crate) files_seen: AtomicU64,
+    pub(crate) files_pruned_by_partition: AtomicU64,
+    pub(crate) files_pruned_by_stats: AtomicU64,
+    pub(crate) files_planned: AtomicU64,
+    pub(crate) bytes_planned: AtomicU64,
+    pub(crate) deletion_vectors_loaded: AtomicU64,
+    pub(crate) planning_nanos: AtomicU64,
+    pub(crate) deletion_vector_nanos: AtomicU64,
+    pub(crate) read_nanos: AtomicU64,
+}
+
+impl ScanMetrics {
+    /// Adds `value` to `counter`.
+    pub(crate) fn add(counter: &AtomicU64, value: u64) {
+        counter.fetch_add(value, Ordering::Relaxed);
+    }
+
+    /// Runs `f`, adding the time it took to `nanos`.
+    pub(crate) fn time<T>(nanos: &AtomicU64, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        let elapsed = start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+        Self::add(nanos, elapsed);
+        result
+    }
+
+    pub(crate) fn report(&self) -> ScanReport {
+        let get = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        ScanReport {
+            files_seen: get(&self.files_seen),
+            files_pruned_by_partition: get(&self.files_pruned_by_partition),
+            files_pruned_by_stats: get(&self.files_pruned_by_stats),
+            files_planned: get(&self.files_planned),
+            bytes_planned: get(&self.bytes_planned),
+            deletion_vectors_loaded: get(&self.deletion_vectors_loaded),
+            planning_duration: Duration::from_nanos(get(&self.planning_nanos)),
+            deletion_vector_duration: Duration::from_nanos(get(&self.deletion_vector_nanos)),
+            read_duration: Duration::from_nanos(get(&self.read_nanos)),
+        }
+    }
+}
@@ -10,7 +10,7 @@ use crate::utils::require;
 use crate::ExpressionRef;
 use crate::{
     actions::{deletion_vector::DeletionVectorDescriptor, visitors::visit_deletion_vector_at},
-    engine_data::{GetData, RowVisitor, TypedGetData as _},
+    engine_data::{GetData, RowVisitor, SelectionVector, TypedGetData as _},
     schema::{ColumnName, ColumnNamesAndTypes, DataType, SchemaRef},
     DeltaResult, Engine, EngineData, Error,
 };
@@ -167,26 +167,26 @@ impl ScanMetadata {
 // add some visitor magic for engines
 struct ScanFileVisitor<'a, T> {
     callback: ScanCallback<T>,
-    selection_vector: &'a [bool],
+    selection_vector: &'a SelectionVector,
     transforms: &'a [Option<ExpressionRef>],
     context: T,
 }
 impl<T> RowVisitor for ScanFileVisitor<'_, T> {
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| SCAN_ROW_SCHEMA.leaves(None));
+            LazyLock::new(|| SCAN_ROW_SCHEMA.column_names_and_types(None));
         NAMES_AND_TYPES.as_ref()
     }
     fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 10,
+            getters.len() == 12,
             Error::InternalError(format!(
                 "Wrong number of ScanFileVisitor getters: {}",
                 getters.len()
             ))
         );
         for row_index in 0..row_count {
-            if !self.selection_vector[row_index] {
+            if !self.selection_vector.get(row_index).unwrap_or(true) {
                 // skip skipped rows
                 continue;
             }
@@ -19,12 +19,17 @@
 //!  assert!(schema.can_read_as(&read_schema).is_ok());
 //!  ````
 //!
+//! Writers can use [`SchemaComparison::check_write_compatible`] to check whether data with a given
+//! schema can be written to a table, which reports every incompatibility it finds.
+//!
 //! [`Schema`]: crate::schema::Schema
 use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 
+use crate::expressions::ColumnName;
 use crate::utils::require;
 
-use super::{DataType, StructField, StructType};
+use super::{DataType, PrimitiveType, StructField, StructType};
 
 /// The nullability flag of a schema's field. This can be compared with a read schema field's
 /// nullability flag using [`Nullable::can_read_as`].
@@ -35,7 +40,7 @@ pub(crate) struct Nullable(bool);
 /// Represents the ways a schema comparison can fail.
 #[allow(unused)]
 #[derive(Debug, thiserror::Error)]
-pub(crate) enum Error {
+pub enum Error {
     #[error("The nullability was tightened for a field")]
     NullabilityTightening,
     #[error("Field names do not match")]
@@ -52,15 +57,287 @@ pub(crate) enum Error {
 
 /// A [`std::result::Result`] that has the schema comparison [`Error`] as the error variant.
 #[allow(unused)]
-pub(crate) type SchemaComparisonResult = Result<(), Error>;
+pub type SchemaComparisonResult = Result<(), Error>;
 
-/// Represents a schema compatibility check for the type. If `self` can be read as `read_type`,
-/// this function returns `Ok(())`. Otherwise, this function returns `Err`.
+/// Represents schema compatibility checks for the type.
 ///
 /// TODO (Oussama): Remove the `allow(unused)` once this is used in CDF.
 #[allow(unused)]
-pub(crate) trait SchemaComparison {
+pub trait SchemaComparison {
+    /// If `self` can be read as `read_type`, this function returns `Ok(())`. Otherwise, this
+    /// function returns `Err`.
     fn can_read_as(&self, read_type: &Self) -> SchemaComparisonResult;
+
+    /// Checks whether data of type `data_schema` can be written to a table (or column) of type
+    /// `table_schema`, returning all incompatibilities found if not. See
+    /// [`WriteCompatibilityOptions`] for the rules.
+    fn check_write_compatible(
+        table_schema: &Self,
+        data_schema: &Self,
+        options: &WriteCompatibilityOptions,
+    ) -> Result<(), Vec<Incompatibility>>;
+}
+
+/// Options for [`SchemaComparison::check_write_compatible`]. Data can be written to a table when:
+///     1. Every data column is present in the table. Column names are matched case-insensitively
+///        unless `case_sensitive` is set, and must not collide when compared case-insensitively.
+///     2. Every table column that is missing from the data is nullable (it is written as NULL),
+///        unless `allow_missing_columns` is false, in which case no column may be missing.
+///     3. No data column is nullable if the corresponding table column is non-nullable. The same
+///        applies to array elements and map values.
+///     4. Each data column has the same type as the table column, or a type that can be widened to
+///        it if `allow_type_widening` is set. See [`can_widen_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteCompatibilityOptions {
+    /// Whether column names must match exactly, including case.
+    pub case_sensitive: bool,
+    /// Whether data columns may have types narrower than the table columns.
+    pub allow_type_widening: bool,
+    /// Whether nullable table columns may be missing from the data.
+    pub allow_missing_columns: bool,
+}
+
+impl Default for WriteCompatibilityOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: false,
+            allow_type_widening: false,
+            allow_missing_columns: true,
+        }
+    }
+}
+
+/// A single reason why data cannot be written to a table, see
+/// [`SchemaComparison::check_write_compatible`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Incompatibility {
+    /// The path of the offending column. Array elements, map keys, and map values are referred to
+    /// as `element`, `key`, and `value` respectively.
+    pub path: ColumnName,
+    /// What is wrong with the column.
+    pub kind: IncompatibilityKind,
+}
+
+/// The kinds of [`Incompatibility`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncompatibilityKind {
+    /// The data is nullable, but the table column is not.
+    NullabilityTightening,
+    /// The data type cannot be written to the table column's type.
+    TypeMismatch {
+        table_type: DataType,
+        data_type: DataType,
+    },
+    /// The table column is missing from the data, and is non-nullable (or missing columns are
+    /// not allowed).
+    MissingColumn,
+    /// The data column does not exist in the table.
+    ExtraColumn,
+    /// The data column matches a table column only when ignoring case, and names are
+    /// case-sensitive.
+    CaseMismatch { table_name: String },
+    /// Several columns of the same struct have the same name when ignoring case.
+    DuplicateColumn,
+}
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let path = &self.path;
+        match &self.kind {
+            IncompatibilityKind::NullabilityTightening => {
+                write!(
+                    f,
+                    "{path}: nullable data cannot be written to non-nullable column"
+                )
+            }
+            IncompatibilityKind::TypeMismatch {
+                table_type,
+                data_type,
+            } => write!(
+                f,
+                "{path}: cannot write {data_type} to column of type {table_type}"
+            ),
+            IncompatibilityKind::MissingColumn => write!(f, "{path}: column missing from data"),
+            IncompatibilityKind::ExtraColumn => write!(f, "{path}: column not present in table"),
+            IncompatibilityKind::CaseMismatch { table_name } => {
+                write!(
+                    f,
+                    "{path}: column name differs in case from table column {table_name}"
+                )
+            }
+            IncompatibilityKind::DuplicateColumn => {
+                write!(f, "{path}: duplicate column name (ignoring case)")
+            }
+        }
+    }
+}
+
+/// Returns true if values of type `from` can be widened to type `to` without loss, following the
+/// Delta type widening rules:
+///     1. Integers can be widened to wider integers (`byte` -> `short` -> `integer` -> `long`).
+///     2. `float` can be widened to `double`, as can `byte`, `short`, and `integer`.
+///     3. `date` can be widened to `timestamp_ntz`.
+///     4. Decimals can be widened to decimals with at least as many integer and fractional digits.
+///     5. Integers can be widened to decimals with enough integer digits to hold them.
+pub fn can_widen_type(from: &DataType, to: &DataType) -> bool {
+    use PrimitiveType::*;
+    let (DataType::Primitive(from), DataType::Primitive(to)) = (from, to) else {
+        return false;
+    };
+    // The number of decimal digits needed to hold any value of an integer type
+    let integer_digits = |ptype: &PrimitiveType| match ptype {
+        Byte => Some(3),
+        Short => Some(5),
+        Integer => Some(10),
+        Long => Some(20),
+        _ => None,
+    };
+    match (from, to) {
+        (Byte, Short | Integer | Long) | (Short, Integer | Long) | (Integer, Long) => true,
+        (Byte | Short | Integer | Float, Double) => true,
+        (Date, TimestampNtz) => true,
+        (Decimal(from), Decimal(to)) => {
+            to.scale() >= from.scale()
+                && to.precision() - to.scale() >= from.precision() - from.scale()
+        }
+        (from, Decimal(to)) => {
+            integer_digits(from).is_some_and(|d| to.precision() - to.scale() >= d)
+        }
+        _ => false,
+    }
+}
+
+/// Accumulates the incompatibilities found while recursively comparing a table and data schema.
+struct WriteCompatibilityChecker<'a> {
+    options: &'a WriteCompatibilityOptions,
+    path: Vec<String>,
+    incompatibilities: Vec<Incompatibility>,
+}
+
+impl<'a> WriteCompatibilityChecker<'a> {
+    fn new(options: &'a WriteCompatibilityOptions) -> Self {
+        Self {
+            options,
+            path: vec![],
+            incompatibilities: vec![],
+        }
+    }
+
+    fn finish(self) -> Result<(), Vec<Incompatibility>> {
+        match self.incompatibilities.is_empty() {
+            true => Ok(()),
+            false => Err(self.incompatibilities),
+        }
+    }
+
+    fn report(&mut self, kind: IncompatibilityKind) {
+        self.report_at(None, kind);
+    }
+
+    fn report_at(&mut self, name: Option<&str>, kind: IncompatibilityKind) {
+        let path = ColumnName::new(self.path.iter().map(String::as_str).chain(name));
+        self.incompatibilities.push(Incompatibility { path, kind });
+    }
+
+    fn nested(&mut self, name: &str, check: impl FnOnce(&mut Self)) {
+        self.path.push(name.to_string());
+        check(self);
+        self.path.pop();
+    }
+
+    fn check_nullability(&mut self, table_nullable: bool, data_nullable: bool) {
+        if data_nullable && !table_nullable {
+            self.report(IncompatibilityKind::NullabilityTightening);
+        }
+    }
+
+    fn check_struct(&mut self, table_struct: &StructType, data_struct: &StructType) {
+        let case_sensitive = self.options.case_sensitive;
+        let normalize = |name: &str| match case_sensitive {
+            true => name.to_string(),
+            false => name.to_lowercase(),
+        };
+        let mut table_fields = HashMap::new();
+        let mut table_fields_lowercase = HashMap::new();
+        let mut duplicates = vec![];
+        for field in table_struct.fields() {
+            table_fields.insert(normalize(field.name()), field);
+            if table_fields_lowercase
+                .insert(field.name().to_lowercase(), field)
+                .is_some()
+            {
+                duplicates.push(field.name().clone());
+            }
+        }
+        let mut data_names_lowercase = HashSet::new();
+        let mut matched = HashSet::new();
+        for data_field in data_struct.fields() {
+            let name = data_field.name();
+            if !data_names_lowercase.insert(name.to_lowercase()) {
+                duplicates.push(name.clone());
+                continue;
+            }
+            if let Some(table_field) = table_fields.get(&normalize(name)) {
+                matched.insert(table_field.name().to_lowercase());
+                self.nested(name, |this| this.check_field(table_field, data_field));
+            } else if let Some(table_field) = table_fields_lowercase.get(&name.to_lowercase()) {
+                let table_name = table_field.name().clone();
+                matched.insert(table_name.to_lowercase());
+                self.report_at(Some(name), IncompatibilityKind::CaseMismatch { table_name });
+            } else {
+                self.report_at(Some(name), IncompatibilityKind::ExtraColumn);
+            }
+        }
+        for name in duplicates {
+            self.report_at(Some(&name), IncompatibilityKind::DuplicateColumn);
+        }
+        for table_field in table_struct.fields() {
+            let missing = !matched.contains(&table_field.name().to_lowercase());
+            if missing && !(self.options.allow_missing_columns && table_field.is_nullable()) {
+                self.report_at(Some(table_field.name()), IncompatibilityKind::MissingColumn);
+            }
+        }
+    }
+
+    fn check_field(&mut self, table_field: &StructField, data_field: &StructField) {
+        self.check_nullability(table_field.is_nullable(), data_field.is_nullable());
+        self.check_type(table_field.data_type(), data_field.data_type());
+    }
+
+    fn check_type(&mut self, table_type: &DataType, data_type: &DataType) {
+        match (table_type, data_type) {
+            (DataType::Struct(table_struct), DataType::Struct(data_struct)) => {
+                self.check_struct(table_struct, data_struct)
+            }
+            (DataType::Array(table_array), DataType::Array(data_array)) => {
+                self.nested("element", |this| {
+                    this.check_nullability(table_array.contains_null(), data_array.contains_null());
+                    this.check_type(table_array.element_type(), data_array.element_type());
+                })
+            }
+            (DataType::Map(table_map), DataType::Map(data_map)) => {
+                self.nested("key", |this| {
+                    this.check_type(table_map.key_type(), data_map.key_type())
+                });
+                self.nested("value", |this| {
+                    this.check_nullability(
+                        table_map.value_contains_null(),
+                        data_map.value_contains_null(),
+                    );
+                    this.check_type(table_map.value_type(), data_map.value_type());
+                });
+            }
+            (table_type, data_type) => {
+                let widening = self.options.allow_type_widening;
+                if table_type != data_type && !(widening && can_widen_type(data_type, table_type)) {
+                    self.report(IncompatibilityKind::TypeMismatch {
+                        table_type: table_type.clone(),
+                        data_type: data_type.clone(),
+                    });
+                }
+            }
+        }
+    }
 }
 
 impl SchemaComparison for Nullable {
@@ -74,6 +351,16 @@ impl SchemaComparison for Nullable {
         require!(read_nullable.0 || !self.0, Error::NullabilityTightening);
         Ok(())
     }
+
+    fn check_write_compatible(
+        table_nullable: &Nullable,
+        data_nullable: &Nullable,
+        options: &WriteCompatibilityOptions,
+    ) -> Result<(), Vec<Incompatibility>> {
+        let mut checker = WriteCompatibilityChecker::new(options);
+        checker.check_nullability(table_nullable.0, data_nullable.0);
+        checker.finish()
+    }
 }
 
 impl SchemaComparison for StructField {
@@ -88,6 +375,19 @@ impl SchemaComparison for StructField {
         self.data_type().can_read_as(read_field.data_type())?;
         Ok(())
     }
+
+    /// Compares the fields' nullability and data types, regardless of their names.
+    fn check_write_compatible(
+        table_field: &StructField,
+        data_field: &StructField,
+        options: &WriteCompatibilityOptions,
+    ) -> Result<(), Vec<Incompatibility>> {
+        let mut checker = WriteCompatibilityChecker::new(options);
+        checker.nested(data_field.name(), |checker| {
+            checker.check_field(table_field, data_field)
+        });
+        checker.finish()
+    }
 }
 impl SchemaComparison for StructType {
     /// Returns `Ok` if this [`StructType`] can be read as `read_type`. This is the case when:
@@ -135,6 +435,16 @@ impl SchemaComparison for StructType {
         }
         Ok(())
     }
+
+    fn check_write_compatible(
+        table_schema: &StructType,
+        data_schema: &StructType,
+        options: &WriteCompatibilityOptions,
+    ) -> Result<(), Vec<Incompatibility>> {
+        let mut checker = WriteCompatibilityChecker::new(options);
+        checker.check_struct(table_schema, data_schema);
+        checker.finish()
+    }
 }
 
 impl SchemaComparison for DataType {
@@ -172,11 +482,25 @@ impl SchemaComparison for DataType {
         };
         Ok(())
     }
+
+    fn check_write_compatible(
+        table_type: &DataType,
+        data_type: &DataType,
+        options: &WriteCompatibilityOptions,
+    ) -> Result<(), Vec<Incompatibility>> {
+        let mut checker = WriteCompatibilityChecker::new(options);
+        checker.check_type(table_type, data_type);
+        checker.finish()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::schema::compare::{Error, SchemaComparison};
+    use crate::expressions::column_name;
+    use crate::schema::compare::{
+        can_widen_type, Error, Incompatibility, IncompatibilityKind, SchemaComparison,
+        WriteCompatibilityOptions,
+    };
     use crate::schema::{ArrayType, DataType, MapType, StructField, StructType};
 
     #[test]
@@ -402,4 +726,141 @@ mod tests {
             Err(Error::InvalidSchema)
         ));
     }
+
+    #[test]
+    fn write_compatible_reports_all_incompatibilities() {
+        let table_nested = StructType::new_unchecked([
+            StructField::new("a", DataType::INTEGER, false),
+            StructField::new("b", DataType::STRING, true),
+        ]);
+        let table_schema = StructType::new_unchecked([
+            StructField::new("id", DataType::LONG, false),
+            StructField::new("Name", DataType::STRING, true),
+            StructField::new("required", DataType::STRING, false),
+            StructField::new("optional", DataType::STRING, true),
+            StructField::new("nested", table_nested, true),
+            StructField::new("tags", ArrayType::new(DataType::STRING, false), true),
+        ]);
+        let data_nested = StructType::new_unchecked([
+            StructField::new("a", DataType::INTEGER, true),
+            StructField::new("b", DataType::INTEGER, true),
+        ]);
+        let data_schema = StructType::new_unchecked([
+            StructField::new("id", DataType::INTEGER, false),
+            StructField::new("name", DataType::STRING, true),
+            StructField::new("nested", data_nested, true),
+            StructField::new("tags", ArrayType::new(DataType::STRING, true), true),
+            StructField::new("extra", DataType::STRING, true),
+        ]);
+
+        let options = WriteCompatibilityOptions::default();
+        let incompatibility = |path, kind| Incompatibility { path, kind };
+        let type_mismatch = |table_type, data_type| IncompatibilityKind::TypeMismatch {
+            table_type,
+            data_type,
+        };
+        let expected = vec![
+            incompatibility(
+                column_name!("id"),
+                type_mismatch(DataType::LONG, DataType::INTEGER),
+            ),
+            incompatibility(
+                column_name!("nested.a"),
+                IncompatibilityKind::NullabilityTightening,
+            ),
+            incompatibility(
+                column_name!("nested.b"),
+                type_mismatch(DataType::STRING, DataType::INTEGER),
+            ),
+            incompatibility(
+                column_name!("tags.element"),
+                IncompatibilityKind::NullabilityTightening,
+            ),
+            incompatibility(column_name!("extra"), IncompatibilityKind::ExtraColumn),
+            incompatibility(column_name!("required"), IncompatibilityKind::MissingColumn),
+        ];
+        assert_eq!(
+            StructType::check_write_compatible(&table_schema, &data_schema, &options),
+            Err(expected.clone())
+        );
+        assert_eq!(
+            expected[0].to_string(),
+            "id: cannot write integer to column of type long"
+        );
+
+        // Type widening allows writing narrower types; case sensitivity rejects `name`
+        let options = WriteCompatibilityOptions {
+            case_sensitive: true,
+            allow_type_widening: true,
+            allow_missing_columns: false,
+        };
+        let errors =
+            StructType::check_write_compatible(&table_schema, &data_schema, &options).unwrap_err();
+        assert!(!errors.iter().any(|e| e.path == column_name!("id")));
+        assert!(errors.contains(&incompatibility(
+            column_name!("name"),
+            IncompatibilityKind::CaseMismatch {
+                table_name: "Name".to_string()
+            },
+        )));
+        assert!(errors.contains(&incompatibility(
+            column_name!("optional"),
+            IncompatibilityKind::MissingColumn,
+        )));
+
+        // Compatible schemas, and duplicate names
+        assert!(StructType::check_write_compatible(&table_schema, &table_schema, &options).is_ok());
+        let duplicates = StructType::new_unchecked([
+            StructField::new("id", DataType::LONG, false),
+            StructField::new("ID", DataType::LONG, false),
+        ]);
+        let errors =
+            StructType::check_write_compatible(&table_schema, &duplicates, &options).unwrap_err();
+        assert!(errors.contains(&incompatibility(
+            column_name!("ID"),
+            IncompatibilityKind::DuplicateColumn,
+        )));
+    }
+
+    #[test]
+    fn type_widening_rules() {
+        let widenable = [
+            (DataType::BYTE, DataType::LONG),
+            (DataType::INTEGER, DataType::DOUBLE),
+            (DataType::FLOAT, DataType::DOUBLE),
+            (DataType::DATE, DataType::TIMESTAMP_NTZ),
+            (
+                DataType::decimal(10, 2).unwrap(),
+                DataType::decimal(12, 4).unwrap(),
+            ),
+            (DataType::INTEGER, DataType::decimal(12, 2).unwrap()),
+        ];
+        for (from, to) in widenable {
+            assert!(can_widen_type(&from, &to), "{from} -> {to}");
+            assert!(!can_widen_type(&to, &from), "{to} -> {from}");
+        }
+        let not_widenable = [
+            (DataType::LONG, DataType::DOUBLE),
+            (DataType::DATE, DataType::TIMESTAMP),
+            (
+                DataType::decimal(10, 2).unwrap(),
+                DataType::decimal(10, 4).unwrap(),
+            ),
+            (DataType::LONG, DataType::decimal(20, 1).unwrap()),
+            (DataType::STRING, DataType::STRING),
+        ];
+        for (from, to) in not_widenable {
+            assert!(!can_widen_type(&from, &to), "{from} -> {to}");
+        }
+
+        let widened = DataType::check_write_compatible(
+            &DataType::LONG,
+            &DataType::SHORT,
+            &WriteCompatibilityOptions {
+                allow_type_widening: true,
+                ..Default::default()
+            },
+        );
+        assert!(widened.is_ok());
+    }
 }
@@ -0,0 +1,239 @@
+//! Schema diffs, e.g. between the schemas of two versions of a table. See [`StructType::diff`].
+
+use super::{ColumnMetadataKey, ColumnName, DataType, MetadataValue, StructField, StructType};
+
+/// A change of a column between two schemas, see [`StructType::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A column was added.
+    ColumnAdded {
+        column: ColumnName,
+        field: StructField,
+    },
+    /// A column was removed. The column is named by its path in the old schema.
+    ColumnRemoved {
+        column: ColumnName,
+        field: StructField,
+    },
+    /// A column was renamed. Renames (other than changes of case) are only detected for columns
+    /// with column mapping ids.
+    ColumnRenamed { from: ColumnName, to: ColumnName },
+    /// The type of a column changed. Changes of the fields of struct columns are reported as
+    /// changes of the individual fields instead.
+    TypeChanged {
+        column: ColumnName,
+        from: DataType,
+        to: DataType,
+    },
+    /// The nullability of a column changed.
+    NullabilityChanged { column: ColumnName, nullable: bool },
+    /// The metadata of a column changed, e.g. its comment.
+    MetadataChanged { column: ColumnName },
+}
+
+impl StructType {
+    /// Returns the changes of the columns of `new` compared to this schema. Columns are matched
+    /// by their column mapping id if they have one, or else by name (ignoring case). All columns
+    /// except removed ones are named by their path in `new`.
+    ///
+    /// The changes of each column of `new` are reported in order, including the changes of the
+    /// fields of struct columns, followed by the removed columns.
+    pub fn diff(&self, new: &StructType) -> Vec<SchemaChange> {
+        let mut changes = vec![];
+        diff_structs(self, new, &mut vec![], &mut changes);
+        changes
+    }
+}
+
+fn diff_structs(
+    old: &StructType,
+    new: &StructType,
+    path: &mut Vec<String>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    let column_name = |path: &[String], name: &String| ColumnName::new(path.iter().chain([name]));
+    for field in new.fields() {
+        let column = column_name(path, field.name());
+        let Some(old_field) = find_field(old, field) else {
+            changes.push(SchemaChange::ColumnAdded {
+                column,
+                field: field.clone(),
+            });
+            continue;
+        };
+        if old_field.name() != field.name() {
+            changes.push(SchemaChange::ColumnRenamed {
+                from: column_name(path, old_field.name()),
+                to: column.clone(),
+            });
+        }
+        match (old_field.data_type(), field.data_type()) {
+            (DataType::Struct(old_struct), DataType::Struct(new_struct)) => {
+                path.push(field.name().clone());
+                diff_structs(old_struct, new_struct, path, changes);
+                path.pop();
+            }
+            (from, to) if from != to => changes.push(SchemaChange::TypeChanged {
+                column: column.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            _ => {}
+        }
+        if old_field.is_nullable() != field.is_nullable() {
+            changes.push(SchemaChange::NullabilityChanged {
+                column: column.clone(),
+                nullable: field.is_nullable(),
+            });
+        }
+        if old_field.metadata() != field.metadata() {
+            changes.push(SchemaChange::MetadataChanged { column });
+        }
+    }
+    let removed = old
+        .fields()
+        .filter(|old_field| find_field(new, old_field).is_none())
+        .map(|old_field| SchemaChange::ColumnRemoved {
+            column: column_name(path, old_field.name()),
+            field: old_field.clone(),
+        });
+    changes.extend(removed);
+}
+
+fn column_mapping_id(field: &StructField) -> Option<&MetadataValue> {
+    field.get_config_value(&ColumnMetadataKey::ColumnMappingId)
+}
+
+// Finds the field of `schema` that matches `field`, by column mapping id or name
+fn find_field<'a>(schema: &'a StructType, field: &StructField) -> Option<&'a StructField> {
+    match column_mapping_id(field) {
+        Some(id) => schema
+            .fields()
+            .find(|candidate| column_mapping_id(candidate) == Some(id)),
+        None => {
+            let name = field.name().to_lowercase();
+            schema
+                .fields()
+                .find(|candidate| candidate.name().to_lowercase() == name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ArrayType, ColumnMetadataKey};
+
+    fn with_id(field: StructField, id: i64) -> StructField {
+        field.add_metadata([(ColumnMetadataKey::ColumnMappingId.as_ref(), id)])
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::not_null(
+                "s",
+                StructType::new_unchecked([StructField::nullable("x", DataType::STRING)]),
+            ),
+        ]);
+        assert!(schema.diff(&schema).is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = StructType::new_unchecked([
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::nullable("b", DataType::STRING),
+            StructField::nullable(
+                "s",
+                StructType::new_unchecked([
+                    StructField::nullable("x", DataType::INTEGER),
+                    StructField::nullable("y", DataType::INTEGER),
+                ]),
+            ),
+        ]);
+        let new = StructType::new_unchecked([
+            StructField::not_null("a", DataType::LONG),
+            StructField::nullable(
+                "s",
+                StructType::new_unchecked([
+                    StructField::nullable("X", DataType::INTEGER),
+                    StructField::nullable("z", ArrayType::new(DataType::STRING, true)),
+                ]),
+            )
+            .with_metadata([("comment", "a struct")]),
+            StructField::nullable("c", DataType::DATE),
+        ]);
+        let column = |path: &[&str]| ColumnName::new(path.iter().copied());
+        assert_eq!(
+            old.diff(&new),
+            [
+                SchemaChange::TypeChanged {
+                    column: column(&["a"]),
+                    from: DataType::INTEGER,
+                    to: DataType::LONG,
+                },
+                SchemaChange::NullabilityChanged {
+                    column: column(&["a"]),
+                    nullable: false,
+                },
+                // names are matched ignoring case
+                SchemaChange::ColumnRenamed {
+                    from: column(&["s", "x"]),
+                    to: column(&["s", "X"]),
+                },
+                SchemaChange::ColumnAdded {
+                    column: column(&["s", "z"]),
+                    field: StructField::nullable("z", ArrayType::new(DataType::STRING, true)),
+                },
+                SchemaChange::ColumnRemoved {
+                    column: column(&["s", "y"]),
+                    field: StructField::nullable("y", DataType::INTEGER),
+                },
+                SchemaChange::MetadataChanged {
+                    column: column(&["s"]),
+                },
+                SchemaChange::ColumnAdded {
+                    column: column(&["c"]),
+                    field: StructField::nullable("c", DataType::DATE),
+                },
+                SchemaChange::ColumnRemoved {
+                    column: column(&["b"]),
+                    field: StructField::nullable("b", DataType::STRING),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_column_mapping() {
+        let old = StructType::new_unchecked([
+            with_id(StructField::nullable("a", DataType::INTEGER), 1),
+            with_id(StructField::nullable("b", DataType::STRING), 2),
+        ]);
+        // `a` is renamed and `b` is dropped and re-added, so it is a different column
+        let new = StructType::new_unchecked([
+            with_id(StructField::nullable("renamed", DataType::INTEGER), 1),
+            with_id(StructField::nullable("b", DataType::STRING), 3),
+        ]);
+        let column = |name: &str| ColumnName::new([name]);
+        assert_eq!(
+            old.diff(&new),
+            [
+                SchemaChange::ColumnRenamed {
+                    from: column("a"),
+                    to: column("renamed"),
+                },
+                SchemaChange::ColumnAdded {
+                    column: column("b"),
+                    field: new.field("b").unwrap().clone(),
+                },
+                SchemaChange::ColumnRemoved {
+                    column: column("b"),
+                    field: old.field("b").unwrap().clone(),
+                },
+            ]
+        );
+    }
+}
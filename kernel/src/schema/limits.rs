@@ -0,0 +1,222 @@
+//! Limits on the shape of schemas, which protect kernel against untrusted schema input. Schemas that
+//! arrive from table metadata or over FFI can be adversarial (e.g. thousands of levels of nesting or
+//! millions of fields), and recursive algorithms over them could overflow the stack or exhaust
+//! memory. Deserializing a [`StructType`] enforces [`SchemaLimits::DEFAULT`], and
+//! [`StructType::validate_limits`] can check any schema against custom limits.
+
+use std::collections::HashSet;
+
+use super::{DataType, StructType};
+use crate::{DeltaResult, Error};
+
+/// Limits on the size and nesting of a schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchemaLimits {
+    /// The maximum nesting depth. The fields of a schema are at depth 1, and each struct, array,
+    /// map, or variant type nests its children one level deeper.
+    pub max_depth: usize,
+    /// The maximum number of fields in a schema, including all nested struct fields.
+    pub max_fields: usize,
+}
+
+impl SchemaLimits {
+    /// The limits enforced when deserializing a [`StructType`].
+    pub const DEFAULT: SchemaLimits = SchemaLimits {
+        max_depth: 100,
+        max_fields: 100_000,
+    };
+}
+
+impl Default for SchemaLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl StructType {
+    /// Checks that this schema does not exceed `limits`, and that no struct in it has two fields
+    /// whose names differ only by case (Delta column names are case-insensitive). Returns a schema
+    /// error describing the first violation found.
+    pub fn validate_limits(&self, limits: &SchemaLimits) -> DeltaResult<()> {
+        let mut checker = LimitChecker::new(limits, true);
+        checker.check_struct(self, 1)
+    }
+
+    /// Enforces [`SchemaLimits::DEFAULT`] while deserializing a schema. Case-insensitive duplicate
+    /// names are not rejected here, only by [`Self::validate_limits`].
+    pub(crate) fn ensure_default_limits(&self) -> DeltaResult<()> {
+        let mut checker = LimitChecker::new(&SchemaLimits::DEFAULT, false);
+        checker.check_struct(self, 1)
+    }
+}
+
+/// Walks a schema, failing as soon as a limit is exceeded. Recursion never goes deeper than
+/// `max_depth`, so the checker itself is safe to run on arbitrarily deep schemas.
+struct LimitChecker<'a> {
+    limits: &'a SchemaLimits,
+    check_case_insensitive_duplicates: bool,
+    num_fields: usize,
+    path: Vec<String>,
+}
+
+impl<'a> LimitChecker<'a> {
+    fn new(limits: &'a SchemaLimits, check_case_insensitive_duplicates: bool) -> Self {
+        Self {
+            limits,
+            check_case_insensitive_duplicates,
+            num_fields: 0,
+            path: vec![],
+        }
+    }
+
+    fn check_depth(&self, depth: usize) -> DeltaResult<()> {
+        if depth > self.limits.max_depth {
+            return Err(Error::schema(format!(
+                "Schema exceeds the maximum nesting depth of {} at {}",
+                self.limits.max_depth,
+                self.path.join(".")
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_struct(&mut self, stype: &StructType, depth: usize) -> DeltaResult<()> {
+        self.check_depth(depth)?;
+        self.num_fields += stype.num_fields();
+        if self.num_fields > self.limits.max_fields {
+            return Err(Error::schema(format!(
+                "Schema exceeds the maximum number of fields of {}",
+                self.limits.max_fields
+            )));
+        }
+        let mut names = HashSet::new();
+        for field in stype.fields() {
+            if self.check_case_insensitive_duplicates && !names.insert(field.name().to_lowercase())
+            {
+                self.path.push(field.name().clone());
+                return Err(Error::schema(format!(
+                    "Duplicate field name (ignoring case): {}",
+                    self.path.join(".")
+                )));
+            }
+            self.path.push(field.name().clone());
+            self.check_type(field.data_type(), depth)?;
+            self.path.pop();
+        }
+        Ok(())
+    }
+
+    // Checks the children of a type whose parent is at `depth`
+    fn check_type(&mut self, data_type: &DataType, depth: usize) -> DeltaResult<()> {
+        match data_type {
+            DataType::Primitive(_) => Ok(()),
+            DataType::Struct(stype) | DataType::Variant(stype) => {
+                self.check_struct(stype, depth + 1)
+            }
+            DataType::Array(atype) => {
+                self.check_depth(depth + 1)?;
+                self.check_type(atype.element_type(), depth + 1)
+            }
+            DataType::Map(mtype) => {
+                self.check_depth(depth + 1)?;
+                self.check_type(mtype.key_type(), depth + 1)?;
+                self.check_type(mtype.value_type(), depth + 1)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ArrayType, StructField};
+
+    // Nests an integer field `depth` levels deep, alternating between structs and arrays
+    fn nested_schema(depth: usize) -> StructType {
+        let mut data_type = DataType::INTEGER;
+        for i in 1..depth {
+            data_type = match i % 2 {
+                0 => ArrayType::new(data_type, true).into(),
+                _ => StructType::new_unchecked([StructField::nullable("f", data_type)]).into(),
+            };
+        }
+        StructType::new_unchecked([StructField::nullable("f", data_type)])
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limits = SchemaLimits {
+            max_depth: 10,
+            max_fields: 1000,
+        };
+        assert!(nested_schema(10).validate_limits(&limits).is_ok());
+        let err = nested_schema(11).validate_limits(&limits).unwrap_err();
+        assert!(
+            err.to_string().contains("maximum nesting depth of 10"),
+            "{err}"
+        );
+
+        // Checking a very deep schema must not overflow the stack
+        assert!(nested_schema(1_000)
+            .validate_limits(&SchemaLimits::DEFAULT)
+            .is_err());
+    }
+
+    #[test]
+    fn test_field_count_limit() {
+        let limits = SchemaLimits {
+            max_depth: 10,
+            max_fields: 3,
+        };
+        let nested = StructType::new_unchecked([
+            StructField::nullable("a", DataType::INTEGER),
+            StructField::nullable("b", DataType::INTEGER),
+        ]);
+        let schema = StructType::new_unchecked([StructField::nullable("s", nested.clone())]);
+        assert!(schema.validate_limits(&limits).is_ok());
+        let schema = StructType::new_unchecked([
+            StructField::nullable("s", nested),
+            StructField::nullable("t", DataType::INTEGER),
+        ]);
+        let err = schema.validate_limits(&limits).unwrap_err();
+        assert!(
+            err.to_string().contains("maximum number of fields of 3"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_duplicates() {
+        let nested = StructType::new_unchecked([
+            StructField::nullable("id", DataType::INTEGER),
+            StructField::nullable("ID", DataType::INTEGER),
+        ]);
+        let schema = StructType::new_unchecked([StructField::nullable("s", nested.clone())]);
+        let err = schema.validate_limits(&SchemaLimits::DEFAULT).unwrap_err();
+        assert!(err.to_string().contains("s.ID"), "{err}");
+
+        // Construction only rejects exact duplicates
+        assert!(StructType::try_new([StructField::nullable("s", nested)]).is_ok());
+    }
+
+    #[test]
+    fn test_deserialization_enforces_default_limits() {
+        let json = serde_json::to_string(&nested_schema(20)).unwrap();
+        let schema: StructType = serde_json::from_str(&json).unwrap();
+        assert_eq!(schema, nested_schema(20));
+
+        // Construction doesn't check the limits, but deserialization does
+        let fields = (0..=SchemaLimits::DEFAULT.max_fields)
+            .map(|i| StructField::nullable(format!("f{i}"), DataType::INTEGER));
+        let json = serde_json::to_string(&StructType::try_new(fields).unwrap()).unwrap();
+        let err = serde_json::from_str::<StructType>(&json).unwrap_err();
+        assert!(
+            err.to_string().contains("maximum number of fields"),
+            "{err}"
+        );
+
+        // serde_json's recursion limit rejects very deep schemas before they reach kernel
+        let json = serde_json::to_string(&nested_schema(1_000)).unwrap();
+        assert!(serde_json::from_str::<StructType>(&json).is_err());
+    }
+}
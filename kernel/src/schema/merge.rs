@@ -0,0 +1,196 @@
+//! Schema merging, following the Delta `mergeSchema` semantics. See [`StructType::merge`].
+
+use super::compare::can_widen_type;
+use super::{ArrayType, DataType, MapType, StructField, StructType};
+use crate::{DeltaResult, Error};
+
+/// Options for [`StructType::merge`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchemaMergeOptions {
+    /// Whether a column whose types differ may be merged into the wider of the two types. See
+    /// [`can_widen_type`] for the widening rules.
+    pub allow_type_widening: bool,
+}
+
+impl StructType {
+    /// Merges `other` into this schema, returning the merged schema. The rules are:
+    ///     1. Fields are matched by name, ignoring case. The merged schema keeps the fields (and
+    ///        field names and metadata) of this schema in order, followed by the fields only present
+    ///        in `other` in their order.
+    ///     2. A merged field is nullable if either field is nullable. The same applies to array
+    ///        elements and map values.
+    ///     3. Struct fields (including those nested in arrays and maps) are merged recursively.
+    ///     4. Otherwise, the types of both fields must be the same, or one must be widenable to the
+    ///        other if `options.allow_type_widening` is set, in which case the wider type is used.
+    ///
+    /// Note that merging does not record type changes in field metadata; callers that widen the
+    /// type of existing table columns are responsible for doing so.
+    pub fn merge(&self, other: &StructType, options: &SchemaMergeOptions) -> DeltaResult<Self> {
+        merge_structs(self, other, options, &mut vec![])
+    }
+}
+
+fn merge_structs(
+    current: &StructType,
+    other: &StructType,
+    options: &SchemaMergeOptions,
+    path: &mut Vec<String>,
+) -> DeltaResult<StructType> {
+    let find = |schema: &'_ StructType, name: &str| {
+        let name = name.to_lowercase();
+        schema
+            .fields()
+            .find(|field| field.name().to_lowercase() == name)
+            .cloned()
+    };
+    let mut fields = Vec::with_capacity(current.num_fields());
+    for field in current.fields() {
+        let merged = match find(other, field.name()) {
+            Some(other_field) => {
+                path.push(field.name().clone());
+                let data_type =
+                    merge_types(field.data_type(), other_field.data_type(), options, path)?;
+                path.pop();
+                StructField {
+                    data_type,
+                    nullable: field.is_nullable() || other_field.is_nullable(),
+                    ..field.clone()
+                }
+            }
+            None => field.clone(),
+        };
+        fields.push(merged);
+    }
+    let new_fields = other
+        .fields()
+        .filter(|field| find(current, field.name()).is_none())
+        .cloned();
+    fields.extend(new_fields);
+    StructType::try_new(fields)
+}
+
+fn merge_types(
+    current: &DataType,
+    other: &DataType,
+    options: &SchemaMergeOptions,
+    path: &mut Vec<String>,
+) -> DeltaResult<DataType> {
+    let result = match (current, other) {
+        (DataType::Struct(current), DataType::Struct(other)) => {
+            merge_structs(current, other, options, path)?.into()
+        }
+        (DataType::Array(current), DataType::Array(other)) => {
+            path.push("element".to_string());
+            let element_type =
+                merge_types(current.element_type(), other.element_type(), options, path)?;
+            path.pop();
+            let contains_null = current.contains_null() || other.contains_null();
+            ArrayType::new(element_type, contains_null).into()
+        }
+        (DataType::Map(current), DataType::Map(other)) => {
+            path.push("key".to_string());
+            let key_type = merge_types(current.key_type(), other.key_type(), options, path)?;
+            path.pop();
+            path.push("value".to_string());
+            let value_type = merge_types(current.value_type(), other.value_type(), options, path)?;
+            path.pop();
+            let value_contains_null = current.value_contains_null() || other.value_contains_null();
+            MapType::new(key_type, value_type, value_contains_null).into()
+        }
+        (current, other) if current == other => current.clone(),
+        (current, other) if options.allow_type_widening && can_widen_type(current, other) => {
+            other.clone()
+        }
+        (current, other) if options.allow_type_widening && can_widen_type(other, current) => {
+            current.clone()
+        }
+        (current, other) => {
+            return Err(Error::schema(format!(
+                "Cannot merge field {} of type {current} with type {other}",
+                path.join(".")
+            )))
+        }
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_schemas() {
+        let current = StructType::new_unchecked([
+            StructField::not_null("id", DataType::INTEGER),
+            StructField::nullable(
+                "nested",
+                StructType::new_unchecked([StructField::not_null("a", DataType::STRING)]),
+            ),
+            StructField::nullable(
+                "tags",
+                MapType::new(DataType::STRING, DataType::STRING, false),
+            ),
+        ]);
+        let other = StructType::new_unchecked([
+            StructField::nullable("ID", DataType::INTEGER),
+            StructField::nullable("new", DataType::DATE),
+            StructField::nullable(
+                "nested",
+                StructType::new_unchecked([
+                    StructField::not_null("b", DataType::LONG),
+                    StructField::nullable("a", DataType::STRING),
+                ]),
+            ),
+            StructField::nullable(
+                "tags",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+        ]);
+        let expected = StructType::new_unchecked([
+            StructField::nullable("id", DataType::INTEGER),
+            StructField::nullable(
+                "nested",
+                StructType::new_unchecked([
+                    StructField::nullable("a", DataType::STRING),
+                    StructField::not_null("b", DataType::LONG),
+                ]),
+            ),
+            StructField::nullable(
+                "tags",
+                MapType::new(DataType::STRING, DataType::STRING, true),
+            ),
+            StructField::nullable("new", DataType::DATE),
+        ]);
+        let options = SchemaMergeOptions::default();
+        assert_eq!(current.merge(&other, &options).unwrap(), expected);
+        assert_eq!(current.merge(&current, &options).unwrap(), current);
+    }
+
+    #[test]
+    fn test_merge_type_widening() {
+        let current = StructType::new_unchecked([StructField::nullable(
+            "values",
+            ArrayType::new(DataType::INTEGER, false),
+        )]);
+        let other = StructType::new_unchecked([StructField::nullable(
+            "values",
+            ArrayType::new(DataType::LONG, false),
+        )]);
+
+        let err = current
+            .merge(&other, &SchemaMergeOptions::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("values.element"), "{err}");
+
+        let options = SchemaMergeOptions {
+            allow_type_widening: true,
+        };
+        // The wider type wins, no matter which side it is on
+        assert_eq!(current.merge(&other, &options).unwrap(), other);
+        assert_eq!(other.merge(&current, &options).unwrap(), other);
+
+        let incompatible =
+            StructType::new_unchecked([StructField::nullable("values", DataType::STRING)]);
+        assert!(current.merge(&incompatible, &options).is_err());
+    }
+}
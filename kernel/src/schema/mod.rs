@@ -19,7 +19,14 @@ use crate::utils::{require, CowExt as _};
 use crate::{DeltaResult, Error};
 use delta_kernel_derive::internal_api;
 
-pub(crate) mod compare;
+pub use self::diff::SchemaChange;
+pub use self::limits::SchemaLimits;
+pub use self::merge::SchemaMergeOptions;
+
+pub mod compare;
+mod diff;
+mod limits;
+mod merge;
 
 #[cfg(feature = "internal-api")]
 pub mod derive_macro_utils;
@@ -95,6 +102,9 @@ impl From<bool> for MetadataValue {
 pub enum ColumnMetadataKey {
     ColumnMappingId,
     ColumnMappingPhysicalName,
+    ColumnMappingNestedIds,
+    Collation,
+    CurrentDefault,
     ParquetFieldId,
     GenerationExpression,
     IdentityStart,
@@ -104,6 +114,7 @@ pub enum ColumnMetadataKey {
     InternalColumn,
     Invariants,
     MetadataSpec,
+    TypeChanges,
 }
 
 impl AsRef<str> for ColumnMetadataKey {
@@ -111,6 +122,9 @@ impl AsRef<str> for ColumnMetadataKey {
         match self {
             Self::ColumnMappingId => "delta.columnMapping.id",
             Self::ColumnMappingPhysicalName => "delta.columnMapping.physicalName",
+            Self::ColumnMappingNestedIds => "delta.columnMapping.nested.ids",
+            Self::Collation => "delta.collation",
+            Self::CurrentDefault => "CURRENT_DEFAULT",
             Self::ParquetFieldId => "parquet.field.id",
             Self::GenerationExpression => "delta.generationExpression",
             Self::IdentityAllowExplicitInsert => "delta.identity.allowExplicitInsert",
@@ -120,6 +134,7 @@ impl AsRef<str> for ColumnMetadataKey {
             Self::InternalColumn => "delta.isInternalColumn",
             Self::Invariants => "delta.invariants",
             Self::MetadataSpec => "delta.metadataSpec",
+            Self::TypeChanges => "delta.typeChanges",
         }
     }
 }
@@ -176,6 +191,72 @@ impl FromStr for MetadataColumnSpec {
     }
 }
 
+/// The collation of a string column, parsed from a collation identifier of the form
+/// `provider.name[.version]`, e.g. `ICU.en_US.74` or `SPARK.UTF8_LCASE`.
+///
+/// See: <https://github.com/delta-io/delta/blob/master/protocol_rfcs/collated-string-type.md>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collation {
+    provider: String,
+    name: String,
+    version: Option<String>,
+}
+
+impl Collation {
+    /// The provider of this collation, e.g. `ICU` or `SPARK`.
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    /// The name of this collation within its provider, e.g. `en_US` or `UTF8_LCASE`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The version of the provider this collation belongs to, if any.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns true if this is the default `SPARK.UTF8_BINARY` collation, which compares strings by
+    /// their UTF-8 bytes.
+    pub fn is_utf8_binary(&self) -> bool {
+        self.provider.eq_ignore_ascii_case("spark") && self.name.eq_ignore_ascii_case("utf8_binary")
+    }
+}
+
+impl FromStr for Collation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '.');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(provider), Some(name), version)
+                if !provider.is_empty()
+                    && !name.is_empty()
+                    && version.is_none_or(|v| !v.is_empty()) =>
+            {
+                Ok(Self {
+                    provider: provider.to_string(),
+                    name: name.to_string(),
+                    version: version.map(str::to_string),
+                })
+            }
+            _ => Err(Error::Schema(format!("Invalid collation identifier: {s}"))),
+        }
+    }
+}
+
+impl Display for Collation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.provider, self.name)?;
+        if let Some(version) = &self.version {
+            write!(f, ".{version}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
 pub struct StructField {
     /// Name of this (possibly nested) column
@@ -312,6 +393,19 @@ impl StructField {
         self.metadata.get(key.as_ref())
     }
 
+    /// Returns the collation of this field, parsed from its [`ColumnMetadataKey::Collation`]
+    /// metadata, or `None` if the field has no collation.
+    pub fn collation(&self) -> DeltaResult<Option<Collation>> {
+        match self.get_config_value(&ColumnMetadataKey::Collation) {
+            None => Ok(None),
+            Some(MetadataValue::String(collation)) => Ok(Some(collation.parse()?)),
+            Some(other) => Err(Error::Schema(format!(
+                "Invalid collation for field {}: {other}",
+                self.name
+            ))),
+        }
+    }
+
     /// Get the physical name for this field as it should be read from parquet.
     ///
     /// NOTE: Caller affirms that the schema was already validated by
@@ -525,6 +619,10 @@ impl StructType {
     /// - the schema contains duplicate field names
     /// - the schema contains duplicate metadata columns
     /// - the schema contains nested metadata columns
+    ///
+    /// Since nested schemas are built bottom-up, this does not check [`SchemaLimits`], which would
+    /// re-walk the nested structs at every level. Deserialized schemas are checked against
+    /// [`SchemaLimits::DEFAULT`], and other schemas can be checked with [`Self::validate_limits`].
     pub fn try_new(fields: impl IntoIterator<Item = StructField>) -> DeltaResult<Self> {
         let mut field_map = IndexMap::new();
         let mut metadata_columns = HashMap::new();
@@ -599,10 +697,9 @@ impl StructType {
     /// from this order in this schema. Returns an Err if a specified field doesn't exist.
     pub fn project_as_struct(&self, names: &[impl AsRef<str>]) -> DeltaResult<StructType> {
         let fields = names.iter().map(|name| {
-            self.fields
-                .get(name.as_ref())
-                .cloned()
-                .ok_or_else(|| Error::missing_column(name.as_ref()))
+            self.fields.get(name.as_ref()).cloned().ok_or_else(|| {
+                Error::missing_column(name.as_ref()).with_column(ColumnName::new([name.as_ref()]))
+            })
         });
         Self::try_from_results(fields)
     }
@@ -654,6 +751,30 @@ impl StructType {
         self.fields.get(name.as_ref())
     }
 
+    /// Gets the field whose name matches `name` ignoring case. Returns an error if more than one
+    /// field matches, since the reference is then ambiguous.
+    pub fn field_case_insensitive(
+        &self,
+        name: impl AsRef<str>,
+    ) -> DeltaResult<Option<&StructField>> {
+        let name = name.as_ref();
+        let lowercase_name = name.to_lowercase();
+        let mut matches = self
+            .fields()
+            .filter(|field| field.name().to_lowercase() == lowercase_name);
+        let Some(found) = matches.next() else {
+            return Ok(None);
+        };
+        if let Some(other) = matches.next() {
+            return Err(Error::schema(format!(
+                "Ambiguous reference to field {name}: matches both {} and {}",
+                found.name(),
+                other.name()
+            )));
+        }
+        Ok(Some(found))
+    }
+
     /// Gets the field with the given name and its index.
     pub fn field_with_index(&self, name: impl AsRef<str>) -> Option<(usize, &StructField)> {
         self.fields
@@ -713,12 +834,37 @@ impl StructType {
     /// fields are considered leaves even if they contain `StructType` entries/elements.
     #[allow(unused)]
     #[internal_api]
-    pub(crate) fn leaves<'s>(&self, own_name: impl Into<Option<&'s str>>) -> ColumnNamesAndTypes {
+    pub(crate) fn column_names_and_types<'s>(
+        &self,
+        own_name: impl Into<Option<&'s str>>,
+    ) -> ColumnNamesAndTypes {
         let mut get_leaves = GetSchemaLeaves::new(own_name.into());
         let _ = get_leaves.transform_struct(self);
         (get_leaves.names, get_leaves.types).into()
     }
 
+    /// Returns an iterator over all leaf columns of this schema, in schema order. Each leaf is
+    /// returned as its full path, its data type, and the metadata of the [`StructField`] it belongs
+    /// to. Unlike [`Self::fields`], this traverses through nested structs, arrays and maps: array
+    /// elements contribute the path component `element`, and map keys and values contribute `key`
+    /// and `value`. For example, the schema `a: struct<b: int>, c: array<struct<d: int>>` has the
+    /// leaves `a.b` and `c.element.d`. Leaves nested in arrays and maps report the metadata of the
+    /// field that contains the array or map.
+    ///
+    /// Primitive and variant types are leaves, as are structs without any fields.
+    pub fn leaves(
+        &self,
+    ) -> impl Iterator<Item = (ColumnName, &DataType, &HashMap<String, MetadataValue>)> {
+        let mut leaves = vec![];
+        let mut path = vec![];
+        for field in self.fields() {
+            path.push(field.name().clone());
+            collect_leaves(field.data_type(), &field.metadata, &mut path, &mut leaves);
+            path.pop();
+        }
+        leaves.into_iter()
+    }
+
     /// Applies physical name mappings to this field. If the `column_mapping_mode` is
     /// [`ColumnMappingMode::Id`], then each StructField will have its parquet field id in the
     /// [`ColumnMetadataKey::ParquetFieldId`] metadata field.
@@ -968,6 +1114,13 @@ impl InvariantChecker {
         let _ = checker.transform_struct(schema);
         checker.has_invariants
     }
+
+    /// Checks if any column nested in the given data type has invariants defined.
+    pub(crate) fn data_type_has_invariants(data_type: &DataType) -> bool {
+        let mut checker = InvariantChecker::default();
+        let _ = checker.transform(data_type);
+        checker.has_invariants
+    }
 }
 
 /// Helper for RowVisitor implementations
@@ -1019,11 +1172,26 @@ impl<'de> Deserialize<'de> for StructType {
         D: serde::Deserializer<'de>,
         Self: Sized,
     {
-        let helper = StructTypeSerDeHelper::deserialize(deserializer)?;
-        StructType::try_new(helper.fields).map_err(serde::de::Error::custom)
+        // Nested structs are deserialized without checking the limits (see
+        // `deserialize_nested_struct`), so the whole schema is checked once here
+        let schema = *deserialize_nested_struct(deserializer)?;
+        schema
+            .ensure_default_limits()
+            .map_err(serde::de::Error::custom)?;
+        Ok(schema)
     }
 }
 
+// Deserializes a struct nested in a schema, whose limits are checked along with the whole schema
+fn deserialize_nested_struct<'de, D>(deserializer: D) -> Result<Box<StructType>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let helper = StructTypeSerDeHelper::deserialize(deserializer)?;
+    let schema = StructType::try_new(helper.fields).map_err(serde::de::Error::custom)?;
+    Ok(Box::new(schema))
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ArrayType {
@@ -1272,6 +1440,7 @@ pub enum DataType {
     Array(Box<ArrayType>),
     /// A struct is used to represent both the top-level schema of the table as well
     /// as struct columns that contain nested columns.
+    #[serde(deserialize_with = "deserialize_nested_struct")]
     Struct(Box<StructType>),
     /// A map stores an arbitrary length collection of key-value pairs
     /// with a single keyType and a single valueType
@@ -1434,7 +1603,49 @@ impl Display for DataType {
 ///
 /// The provided `recurse_into_xxx` methods encapsulate the boilerplate work of recursing into the
 /// child schema elements of each schema element. Implementations can call these as needed but will
-/// generally not need to override them.
+/// generally not need to override them. Note that a struct whose fields were all filtered out is
+/// itself filtered out, along with the field, array, or map that contains it.
+///
+/// This trait is a stable public API, meant for engines that need to rewrite schemas (e.g. to strip
+/// columns, rename them, or annotate them with metadata) without reimplementing the recursion over
+/// structs, arrays, maps, and variants. [`Self::transform_schema`] is a convenient entry point for
+/// transforming a whole table schema.
+///
+/// # Example
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use delta_kernel::schema::{ArrayType, DataType, SchemaTransform, StructField, StructType};
+/// // Removes fields named `secret` and upper-cases and tags all other field names, at any depth
+/// struct Redact;
+/// impl<'a> SchemaTransform<'a> for Redact {
+///     fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+///         if field.name() == "secret" {
+///             return None;
+///         }
+///         let field = self.recurse_into_struct_field(field)?;
+///         let field = field
+///             .with_name(field.name().to_uppercase())
+///             .add_metadata([("redacted", "true")]);
+///         Some(Cow::Owned(field))
+///     }
+/// }
+///
+/// let inner = StructType::try_new([
+///     StructField::nullable("id", DataType::LONG),
+///     StructField::nullable("secret", DataType::STRING),
+/// ])?;
+/// let schema = StructType::try_new([
+///     StructField::nullable("items", ArrayType::new(inner.into(), true)),
+///     StructField::nullable("secret", DataType::STRING),
+/// ])?;
+/// let redacted = Redact.transform_schema(&schema);
+/// assert_eq!(redacted.field_names().collect::<Vec<_>>(), ["ITEMS"]);
+/// let DataType::Array(items) = redacted.field("ITEMS").unwrap().data_type() else { panic!() };
+/// let DataType::Struct(inner) = items.element_type() else { panic!() };
+/// assert_eq!(inner.field_names().collect::<Vec<_>>(), ["ID"]);
+/// # Ok::<(), delta_kernel::Error>(())
+/// ```
 pub trait SchemaTransform<'a> {
     /// Called for each primitive encountered during the schema traversal.
     fn transform_primitive(&mut self, ptype: &'a PrimitiveType) -> Option<Cow<'a, PrimitiveType>> {
@@ -1491,6 +1702,15 @@ pub trait SchemaTransform<'a> {
         self.recurse_into_struct(stype)
     }
 
+    /// Entry point for transforming a table schema. Unlike [`Self::transform_struct`], always
+    /// produces a schema, which is empty if all fields were filtered out.
+    fn transform_schema(&mut self, schema: &'a StructType) -> StructType {
+        match self.transform_struct(schema) {
+            Some(schema) => schema.into_owned(),
+            None => StructType::new_unchecked([]),
+        }
+    }
+
     /// General entry point for a recursive traversal over any data type. Also invoked internally to
     /// dispatch on nested data types encountered during the traversal.
     fn transform(&mut self, data_type: &'a DataType) -> Option<Cow<'a, DataType>> {
@@ -1585,6 +1805,35 @@ pub trait SchemaTransform<'a> {
     }
 }
 
+// Adds the leaves of `data_type`, which belongs to a field with `metadata` and is found at `path`
+fn collect_leaves<'a>(
+    data_type: &'a DataType,
+    metadata: &'a HashMap<String, MetadataValue>,
+    path: &mut Vec<String>,
+    leaves: &mut Vec<(ColumnName, &'a DataType, &'a HashMap<String, MetadataValue>)>,
+) {
+    let children: Vec<(&str, &DataType, &HashMap<String, MetadataValue>)> = match data_type {
+        DataType::Struct(stype) if stype.num_fields() > 0 => stype
+            .fields()
+            .map(|field| (field.name().as_str(), field.data_type(), &field.metadata))
+            .collect(),
+        DataType::Array(atype) => vec![("element", atype.element_type(), metadata)],
+        DataType::Map(mtype) => vec![
+            ("key", mtype.key_type(), metadata),
+            ("value", mtype.value_type(), metadata),
+        ],
+        _ => {
+            leaves.push((ColumnName::new(path.iter()), data_type, metadata));
+            return;
+        }
+    };
+    for (name, data_type, metadata) in children {
+        path.push(name.to_string());
+        collect_leaves(data_type, metadata, path, leaves);
+        path.pop();
+    }
+}
+
 struct GetSchemaLeaves {
     path: Vec<String>,
     names: Vec<ColumnName>,
@@ -2669,6 +2918,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_collation() -> DeltaResult<()> {
+        let field = StructField::nullable("s", DataType::STRING);
+        assert_eq!(field.collation()?, None);
+
+        let field = field.add_metadata([("delta.collation", "ICU.en_US.74")]);
+        let collation = field.collation()?.unwrap();
+        assert_eq!(collation.provider(), "ICU");
+        assert_eq!(collation.name(), "en_US");
+        assert_eq!(collation.version(), Some("74"));
+        assert_eq!(collation.to_string(), "ICU.en_US.74");
+        assert!(!collation.is_utf8_binary());
+
+        let collation: Collation = "spark.UTF8_BINARY".parse()?;
+        assert_eq!(collation.version(), None);
+        assert!(collation.is_utf8_binary());
+
+        for invalid in ["", "ICU", "ICU.", ".en_US", "ICU.en_US."] {
+            assert!(invalid.parse::<Collation>().is_err(), "{invalid}");
+        }
+        let field = StructField::nullable("s", DataType::STRING)
+            .add_metadata([("delta.collation", MetadataValue::Number(1))]);
+        assert!(field.collation().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_default_row_index_column() {
         let field = StructField::default_row_index_column();
@@ -2887,4 +3162,131 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_leaves() -> DeltaResult<()> {
+        let schema = StructType::try_new([
+            StructField::nullable("id", DataType::LONG).add_metadata([("id", true)]),
+            StructField::nullable(
+                "nested",
+                StructType::try_new([
+                    StructField::nullable("a", DataType::STRING).add_metadata([("a", true)]),
+                    StructField::nullable("empty", StructType::try_new([])?),
+                ])?,
+            ),
+            StructField::nullable(
+                "array",
+                ArrayType::new(
+                    StructType::try_new([StructField::nullable("b", DataType::DATE)])?.into(),
+                    true,
+                ),
+            )
+            .add_metadata([("array", true)]),
+            StructField::nullable(
+                "map",
+                MapType::new(
+                    DataType::STRING,
+                    ArrayType::new(DataType::INTEGER, false),
+                    true,
+                ),
+            )
+            .add_metadata([("map", true)]),
+            StructField::nullable("variant", DataType::unshredded_variant()),
+        ])?;
+        let leaves: Vec<_> = schema
+            .leaves()
+            .map(|(name, data_type, metadata)| {
+                let mut keys: Vec<_> = metadata.keys().cloned().collect();
+                keys.sort();
+                (name, data_type.clone(), keys)
+            })
+            .collect();
+        let expected = vec![
+            (column_name!("id"), DataType::LONG, vec!["id".to_string()]),
+            (
+                column_name!("nested.a"),
+                DataType::STRING,
+                vec!["a".to_string()],
+            ),
+            (
+                column_name!("nested.empty"),
+                StructType::try_new([])?.into(),
+                vec![],
+            ),
+            (column_name!("array.element.b"), DataType::DATE, vec![]),
+            (
+                column_name!("map.key"),
+                DataType::STRING,
+                vec!["map".to_string()],
+            ),
+            (
+                column_name!("map.value.element"),
+                DataType::INTEGER,
+                vec!["map".to_string()],
+            ),
+            (
+                column_name!("variant"),
+                DataType::unshredded_variant(),
+                vec![],
+            ),
+        ];
+        assert_eq!(leaves, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_transform_recursion() -> DeltaResult<()> {
+        // Strips fields named `drop` and tags all remaining fields, at any depth
+        struct StripAndTag;
+        impl<'a> SchemaTransform<'a> for StripAndTag {
+            fn transform_struct_field(
+                &mut self,
+                field: &'a StructField,
+            ) -> Option<Cow<'a, StructField>> {
+                if field.name() == "drop" {
+                    return None;
+                }
+                let field = self.recurse_into_struct_field(field)?.into_owned();
+                Some(Cow::Owned(field.add_metadata([("tagged", true)])))
+            }
+        }
+        let tagged = |field: StructField| field.add_metadata([("tagged", true)]);
+
+        let nested = StructType::try_new([
+            StructField::nullable("keep", DataType::INTEGER),
+            StructField::nullable("drop", DataType::INTEGER),
+        ])?;
+        let only_drop = StructType::try_new([StructField::nullable("drop", DataType::INTEGER)])?;
+        let schema = StructType::try_new([
+            StructField::nullable("map", MapType::new(DataType::STRING, nested.clone(), true)),
+            StructField::nullable("array", ArrayType::new(nested.into(), false)),
+            StructField::nullable("gone", only_drop.clone()),
+            StructField::nullable("variant", DataType::unshredded_variant()),
+        ])?;
+
+        let expected_nested =
+            StructType::try_new([tagged(StructField::nullable("keep", DataType::INTEGER))])?;
+        let DataType::Variant(variant) = DataType::unshredded_variant() else {
+            panic!("expected variant type");
+        };
+        let expected_variant = DataType::Variant(Box::new(StructType::try_new(
+            variant.fields().cloned().map(tagged),
+        )?));
+        let expected = StructType::try_new([
+            tagged(StructField::nullable(
+                "map",
+                MapType::new(DataType::STRING, expected_nested.clone(), true),
+            )),
+            tagged(StructField::nullable(
+                "array",
+                ArrayType::new(expected_nested.into(), false),
+            )),
+            tagged(StructField::nullable("variant", expected_variant)),
+        ])?;
+        assert_eq!(StripAndTag.transform_schema(&schema), expected);
+
+        // A schema whose fields are all filtered out becomes empty
+        assert_eq!(StripAndTag.transform_schema(&only_drop).num_fields(), 0);
+        Ok(())
+    }
 }
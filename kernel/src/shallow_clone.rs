@@ -0,0 +1,219 @@
+//! This module implements shallow CLONE, i.e. creating a new table whose data files are those of
+//! a version of an existing (source) table.
+//!
+//! The entry point for this API is [`Snapshot::shallow_clone`]. Cloning commits version 0 of the
+//! new (target) table, containing
+//! 1. a `commitInfo` action with operation `CLONE`,
+//! 2. the protocol of the source table,
+//! 3. the metadata of the source table, with a new table id,
+//! 4. the domain metadata of the source table, and
+//! 5. an add action for each file of the source table. Since no data files are copied, these add
+//!    actions reference the files of the source table by absolute paths. Deletion vectors stored
+//!    relative to the source table root are also converted to absolute paths.
+//!
+//! Readers resolve the paths of add actions against the table root, which leaves absolute paths
+//! unchanged, so the target table can be read like any other table. Note that the target table
+//! breaks if the referenced files are deleted from the source table, e.g. by a VACUUM of the
+//! source table.
+//!
+//! ```no_run
+//! # use delta_kernel::{DeltaResult, Engine, Snapshot};
+//! # fn shallow_clone(engine: &dyn Engine) -> DeltaResult<()> {
+//! let url = delta_kernel::try_parse_uri("./tests/data/basic_partitioned")?;
+//! let snapshot = Snapshot::builder_for(url).build(engine)?;
+//! let target = delta_kernel::try_parse_uri("./tests/data/basic_partitioned_clone")?;
+//! snapshot.shallow_clone(target).commit(engine)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Snapshot::shallow_clone`]: crate::Snapshot::shallow_clone
+use std::collections::HashMap;
+use std::iter;
+
+use url::Url;
+
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::actions::domain_metadata::all_domain_metadatas;
+use crate::actions::{
+    get_log_add_schema, get_log_commit_info_schema, get_log_domain_metadata_schema,
+    get_log_metadata_schema, get_log_protocol_schema, Add, CommitInfo, Metadata,
+};
+use crate::path::ParsedLogPath;
+use crate::restore::live_files;
+use crate::snapshot::SnapshotRef;
+use crate::utils::current_time_ms;
+use crate::{DeltaResult, Engine, Error, IntoEngineData};
+
+/// Creates a new table that shallow clones a snapshot of an existing table. Created with
+/// [`Snapshot::shallow_clone`].
+///
+/// [`Snapshot::shallow_clone`]: crate::Snapshot::shallow_clone
+#[derive(Debug)]
+pub struct ShallowClone {
+    source: SnapshotRef,
+    target: Url,
+    engine_info: Option<String>,
+}
+
+impl ShallowClone {
+    pub(crate) fn new(source: SnapshotRef, target: Url) -> Self {
+        Self {
+            source,
+            target,
+            engine_info: None,
+        }
+    }
+
+    /// Set the engine info field of the commit info action of the clone commit.
+    pub fn with_engine_info(mut self, engine_info: impl Into<String>) -> Self {
+        self.engine_info = Some(engine_info.into());
+        self
+    }
+
+    /// Create the target table by committing its version 0. Fails if the target table already
+    /// exists.
+    ///
+    /// Note that this replays the whole log of the source table.
+    pub fn commit(self, engine: &dyn Engine) -> DeltaResult<()> {
+        // the target table has the same protocol and table properties, so writing it must be
+        // supported as well
+        self.source.table_configuration().ensure_write_supported()?;
+        let source_root = self.source.table_root();
+        let timestamp = current_time_ms()?;
+
+        let mut commit_info = CommitInfo::new(
+            timestamp,
+            Some("CLONE".to_string()),
+            self.engine_info.clone(),
+        );
+        commit_info.operation_parameters = Some(HashMap::from([
+            ("source".to_string(), source_root.to_string()),
+            (
+                "sourceVersion".to_string(),
+                self.source.version().to_string(),
+            ),
+            ("isShallow".to_string(), "true".to_string()),
+        ]));
+        let commit_info_action =
+            commit_info.into_engine_data(get_log_commit_info_schema().clone(), engine);
+
+        let protocol_action = self
+            .source
+            .protocol()
+            .clone()
+            .into_engine_data(get_log_protocol_schema().clone(), engine);
+
+        let metadata = Metadata {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_time: Some(timestamp),
+            ..self.source.metadata().clone()
+        };
+        let metadata_action = metadata.into_engine_data(get_log_metadata_schema().clone(), engine);
+
+        let domain_metadata_actions = all_domain_metadatas(self.source.log_segment(), engine)?
+            .into_values()
+            .map(|domain_metadata| {
+                domain_metadata.into_engine_data(get_log_domain_metadata_schema().clone(), engine)
+            });
+
+        let mut add_actions = vec![];
+        for add in live_files(&self.source, engine)?.into_values() {
+            add_actions.push(
+                absolute_add(add, source_root)?
+                    .into_engine_data(get_log_add_schema().clone(), engine),
+            );
+        }
+
+        let actions = iter::once(commit_info_action)
+            .chain(iter::once(protocol_action))
+            .chain(iter::once(metadata_action))
+            .chain(domain_metadata_actions)
+            .chain(add_actions);
+        let commit_path = ParsedLogPath::new_commit(&self.target, 0)?;
+        match engine
+            .log_store()
+            .write_commit(&commit_path.location, Box::new(actions))
+        {
+            Err(Error::FileAlreadyExists(_)) => Err(Error::generic(format!(
+                "Cannot clone into {}: a table already exists at this location",
+                self.target
+            ))),
+            result => result,
+        }
+    }
+}
+
+/// Convert an add action of the source table into an add action of the target table, which
+/// references the file (and its deletion vector, if any) by absolute path.
+fn absolute_add(add: Add, source_root: &Url) -> DeltaResult<Add> {
+    let path = source_root
+        .join(&add.path)
+        .map_err(|_| Error::generic(format!("Invalid file path in the log: {}", add.path)))?;
+    let deletion_vector = match add.deletion_vector {
+        Some(dv) if dv.storage_type == "u" => {
+            let dv_path = dv.absolute_path(source_root)?.ok_or_else(|| {
+                Error::internal_error("Deletion vector stored as 'u' must have a path")
+            })?;
+            Some(DeletionVectorDescriptor {
+                storage_type: "p".to_string(),
+                path_or_inline_dv: dv_path.to_string(),
+                ..dv
+            })
+        }
+        dv => dv,
+    };
+    Ok(Add {
+        path: path.to_string(),
+        data_change: true,
+        deletion_vector,
+        ..add
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_add() {
+        let source_root = Url::parse("s3://bucket/source/").unwrap();
+        let add = Add {
+            path: "a=1/part-00000.parquet".to_string(),
+            deletion_vector: Some(DeletionVectorDescriptor {
+                storage_type: "u".to_string(),
+                path_or_inline_dv: "vBn[lx{q8@P<9BNH/isA".to_string(),
+                offset: Some(1),
+                size_in_bytes: 36,
+                cardinality: 2,
+            }),
+            ..Default::default()
+        };
+        let add = absolute_add(add, &source_root).unwrap();
+        assert_eq!(add.path, "s3://bucket/source/a=1/part-00000.parquet");
+        assert!(add.data_change);
+        let dv = add.deletion_vector.unwrap();
+        assert_eq!(dv.storage_type, "p");
+        assert_eq!(
+            dv.path_or_inline_dv,
+            "s3://bucket/source/deletion_vector_61d16c75-6994-46b7-a15b-8b538852e50e.bin"
+        );
+        assert_eq!(dv.offset, Some(1));
+
+        // absolute paths and inline deletion vectors are unchanged
+        let add = Add {
+            path: "s3://bucket/other/part-00000.parquet".to_string(),
+            deletion_vector: Some(DeletionVectorDescriptor {
+                storage_type: "i".to_string(),
+                path_or_inline_dv: "wi5b=000010000siXQKl0rr91000f55c8Xg0@@D72lkbi5=-{L".to_string(),
+                offset: None,
+                size_in_bytes: 40,
+                cardinality: 6,
+            }),
+            ..Default::default()
+        };
+        let absolute = absolute_add(add.clone(), &source_root).unwrap();
+        assert_eq!(absolute.path, add.path);
+        assert_eq!(absolute.deletion_vector, add.deletion_vector);
+    }
+}
@@ -1,44 +1,81 @@
 //! In-memory representation of snapshots of tables (snapshot is a table at given point in time, it
 //! has schema etc.)
 
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex};
 
 use crate::action_reconciliation::calculate_transaction_expiration_timestamp;
 use crate::actions::domain_metadata::domain_metadata_configuration;
 use crate::actions::set_transaction::SetTransactionScanner;
-use crate::actions::{Metadata, Protocol, INTERNAL_DOMAIN_PREFIX};
+use crate::actions::{
+    get_log_schema, Metadata, Protocol, ADD_NAME, INTERNAL_DOMAIN_PREFIX, METADATA_NAME,
+    PROTOCOL_NAME, REMOVE_NAME,
+};
 use crate::checkpoint::CheckpointWriter;
 use crate::listed_log_files::ListedLogFiles;
-use crate::log_segment::LogSegment;
+use crate::log_segment::{CommitBatches, LogSegment};
+use crate::restore::{Restore, RestoreTarget};
 use crate::scan::ScanBuilder;
 use crate::schema::SchemaRef;
+use crate::shallow_clone::ShallowClone;
 use crate::table_configuration::TableConfiguration;
 use crate::table_features::ColumnMappingMode;
 use crate::table_properties::TableProperties;
 use crate::transaction::Transaction;
+use crate::utils::{assert_send_sync, require};
+use crate::vacuum::Vacuum;
 use crate::LogCompactionWriter;
 use crate::{DeltaResult, Engine, Error, ParsedLogPath, Version};
 use delta_kernel_derive::internal_api;
+#[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+use {
+    crate::analyze::Analyze, crate::arrow::array::RecordBatch,
+    crate::arrow::compute::filter_record_batch, crate::engine::arrow_data::ArrowEngineData,
+    crate::PredicateRef,
+};
 
 mod builder;
+mod diagnostics;
+mod integrity;
+mod schema_history;
 pub use builder::SnapshotBuilder;
+pub use diagnostics::{LogDiagnostics, LogIssue};
+pub use integrity::CrcVerification;
+pub use schema_history::SchemaVersionChange;
 
 use tracing::debug;
 use url::Url;
 
 pub type SnapshotRef = Arc<Snapshot>;
 
+// snapshots are shared across threads, see the concurrency model in the crate docs
+const _: () = assert_send_sync::<Snapshot>();
+
 // TODO expose methods for accessing the files of a table (with file pruning).
 /// In-memory representation of a specific snapshot of a Delta table. While a `DeltaTable` exists
 /// throughout time, `Snapshot`s represent a view of a table at a specific point in time; they
 /// have a defined schema (which may change over time for any given table), specific version, and
 /// frozen log segment.
-#[derive(PartialEq, Eq)]
+///
+/// A `Snapshot` is immutable and `Send + Sync`, so it can be shared across threads without
+/// locking. It is usually shared as a [`SnapshotRef`], which is cheap to clone.
 pub struct Snapshot {
     log_segment: LogSegment,
     table_configuration: TableConfiguration,
+    /// The batches of the commits of `log_segment`, newest first, if they were kept for the first
+    /// scan of the snapshot to reuse (see [`SnapshotBuilder::with_single_pass_replay`]).
+    commit_batches: Mutex<Option<CommitBatches>>,
+}
+
+// The commit batches kept for the first scan are a cache of the log, not part of the snapshot
+impl PartialEq for Snapshot {
+    fn eq(&self, other: &Self) -> bool {
+        self.log_segment == other.log_segment
+            && self.table_configuration == other.table_configuration
+    }
 }
 
+impl Eq for Snapshot {}
+
 impl Drop for Snapshot {
     fn drop(&mut self) {
         debug!("Dropping snapshot");
@@ -63,6 +100,16 @@ impl Snapshot {
         SnapshotBuilder::new_for(table_root)
     }
 
+    /// Create a new [`SnapshotBuilder`] for the table at the given location, which may be a URL
+    /// or a (relative) local path. The location is normalized to a directory URL, with aliased
+    /// schemes such as `s3a://` replaced by their canonical scheme (see `try_parse_uri`).
+    ///
+    /// Building the snapshot fails with [`Error::NotADeltaTable`] if the location doesn't contain
+    /// a Delta log.
+    pub fn builder_for_uri(uri: impl AsRef<str>) -> DeltaResult<SnapshotBuilder> {
+        SnapshotBuilder::try_new_for_uri(uri)
+    }
+
     /// Create a new [`SnapshotBuilder`] to incrementally update a [`Snapshot`] to a more recent
     /// version.
     ///
@@ -91,6 +138,7 @@ impl Snapshot {
         Self {
             log_segment,
             table_configuration,
+            commit_batches: Default::default(),
         }
     }
 
@@ -247,14 +295,45 @@ impl Snapshot {
         engine: &dyn Engine,
     ) -> DeltaResult<Self> {
         let (metadata, protocol) = log_segment.read_metadata(engine)?;
+        let table_configuration =
+            TableConfiguration::try_new(metadata, protocol, location, log_segment.end_version)?;
+        Ok(Self::new(log_segment, table_configuration))
+    }
+
+    /// Create a new [`Snapshot`] instance, keeping the commits read to find its protocol and
+    /// metadata for its first scan (see [`SnapshotBuilder::with_single_pass_replay`]).
+    pub(crate) fn try_new_from_log_segment_with_commits(
+        location: Url,
+        log_segment: LogSegment,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Self> {
+        // the protocol and metadata of the snapshot, and the file actions of its scans
+        // safety: we define get_log_schema() and _know_ it contains these actions
+        #[allow(clippy::unwrap_used)]
+        static COMMIT_READ_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+            get_log_schema()
+                .project(&[ADD_NAME, REMOVE_NAME, PROTOCOL_NAME, METADATA_NAME])
+                .unwrap()
+        });
+        let (metadata, protocol, commit_batches) =
+            log_segment.read_metadata_with_commits(engine, COMMIT_READ_SCHEMA.clone())?;
         let table_configuration =
             TableConfiguration::try_new(metadata, protocol, location, log_segment.end_version)?;
         Ok(Self {
             log_segment,
             table_configuration,
+            commit_batches: Mutex::new(Some(commit_batches)),
         })
     }
 
+    /// Takes the commit batches kept for the first scan of the snapshot, if any are left.
+    pub(crate) fn take_commit_batches(&self) -> Option<CommitBatches> {
+        self.commit_batches
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
     /// Creates a [`CheckpointWriter`] for generating a checkpoint from this snapshot.
     ///
     /// See the [`crate::checkpoint`] module documentation for more details on checkpoint types
@@ -263,6 +342,39 @@ impl Snapshot {
         CheckpointWriter::try_new(self)
     }
 
+    /// Creates a [`Vacuum`] for deleting files that are no longer referenced by this snapshot of
+    /// the table.
+    ///
+    /// See the [`crate::vacuum`] module documentation for details on which files are deleted.
+    pub fn vacuum(self: Arc<Self>) -> Vacuum {
+        Vacuum::new(self)
+    }
+
+    /// Creates a [`Restore`] for restoring the table to the state of a previous version, by
+    /// committing a new version on top of this snapshot.
+    ///
+    /// See the [`crate::restore`] module documentation for details.
+    pub fn restore(self: Arc<Self>, target: RestoreTarget) -> Restore {
+        Restore::new(self, target)
+    }
+
+    /// Creates an [`Analyze`] for computing the missing or stale statistics of the data files of
+    /// the table, by committing a new version on top of this snapshot.
+    ///
+    /// See the [`crate::analyze`] module documentation for details.
+    #[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+    pub fn analyze(self: Arc<Self>) -> Analyze {
+        Analyze::new(self)
+    }
+
+    /// Creates a [`ShallowClone`] for creating a new table at `target` (the root URL of the new
+    /// table) that references the data files of this snapshot of the table.
+    ///
+    /// See the [`crate::shallow_clone`] module documentation for details.
+    pub fn shallow_clone(self: Arc<Self>, target: Url) -> ShallowClone {
+        ShallowClone::new(self, target)
+    }
+
     /// Creates a [`LogCompactionWriter`] for generating a log compaction file.
     ///
     /// Log compaction aggregates commit files in a version range into a single compacted file,
@@ -304,6 +416,61 @@ impl Snapshot {
         self.table_configuration.schema()
     }
 
+    /// Table [`Schema`] at the given `version`, which must not be newer than this `Snapshot`s
+    /// version.
+    ///
+    /// [`Schema`]: crate::schema::Schema
+    pub fn schema_at(&self, engine: &dyn Engine, version: Version) -> DeltaResult<SchemaRef> {
+        if version == self.version() {
+            return Ok(self.schema());
+        }
+        require!(
+            version < self.version(),
+            Error::generic(format!(
+                "Cannot get the schema of version {version}, which is newer than the snapshot version {}",
+                self.version()
+            ))
+        );
+        let snapshot = Snapshot::builder_for(self.table_root().clone())
+            .at_version(version)
+            .build(engine)?;
+        Ok(snapshot.schema())
+    }
+
+    /// The changes of the table schema committed after `start_version`, up to and including
+    /// `end_version`, which must not be newer than this `Snapshot`s version. Each change is
+    /// compared to the schema of the previous change, or the schema at `start_version` for the
+    /// first one. Versions that don't change the schema (e.g. that only change table properties)
+    /// are skipped.
+    ///
+    /// This can be used to detect whether a schema (e.g. of a cached query plan or a streaming
+    /// read) is still valid at a later version, and to explain how it changed if not.
+    pub fn schema_changes(
+        &self,
+        engine: &dyn Engine,
+        start_version: Version,
+        end_version: Version,
+    ) -> DeltaResult<Vec<SchemaVersionChange>> {
+        require!(
+            start_version <= end_version && end_version <= self.version(),
+            Error::generic(format!(
+                "Invalid version range {start_version}..={end_version} of schema changes for the snapshot version {}",
+                self.version()
+            ))
+        );
+        let schema = self.schema_at(engine, start_version)?;
+        if start_version == end_version {
+            return Ok(vec![]);
+        }
+        let log_segment = LogSegment::for_table_changes(
+            engine.storage_handler().as_ref(),
+            self.log_segment.log_root.clone(),
+            start_version + 1,
+            end_version,
+        )?;
+        schema_history::schema_changes(engine, &log_segment, schema)
+    }
+
     /// Table [`Metadata`] at this `Snapshot`s version.
     #[internal_api]
     pub(crate) fn metadata(&self) -> &Metadata {
@@ -311,9 +478,7 @@ impl Snapshot {
     }
 
     /// Table [`Protocol`] at this `Snapshot`s version.
-    #[allow(dead_code)]
-    #[internal_api]
-    pub(crate) fn protocol(&self) -> &Protocol {
+    pub fn protocol(&self) -> &Protocol {
         self.table_configuration.protocol()
     }
 
@@ -340,6 +505,42 @@ impl Snapshot {
         ScanBuilder::new(self)
     }
 
+    /// Read the contents of the table at this `Snapshot`s version as arrow [`RecordBatch`]es, for
+    /// applications that just want the table's data. This scans the given `columns` (or all
+    /// columns if `None`), skipping files that `predicate` shows to have no matching rows, and
+    /// returns the logical data of the scanned files without the rows deleted by deletion vectors.
+    ///
+    /// Note that the predicate only skips files, so the batches may include rows that don't
+    /// satisfy it. The `engine` must return [`ArrowEngineData`], as the default engine does. Use a
+    /// [`ScanBuilder`] (see [`Snapshot::scan_builder`]) for more control over the scan.
+    #[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+    pub fn scan_arrow(
+        self: Arc<Self>,
+        engine: Arc<dyn Engine>,
+        columns: Option<&[&str]>,
+        predicate: impl Into<Option<PredicateRef>>,
+    ) -> DeltaResult<impl Iterator<Item = DeltaResult<RecordBatch>>> {
+        let schema = columns
+            .map(|columns| self.schema().project(columns))
+            .transpose()?;
+        let scan = self
+            .scan_builder()
+            .with_schema_opt(schema)
+            .with_predicate(predicate)
+            .build()?;
+        let results = scan.execute(engine)?.map(|result| {
+            let result = result?;
+            let mask = result.full_mask();
+            let batch: RecordBatch =
+                ArrowEngineData::try_from_engine_data(result.raw_data?)?.into();
+            match mask {
+                Some(mask) => Ok(filter_record_batch(&batch, &mask.into())?),
+                None => Ok(batch),
+            }
+        });
+        Ok(results)
+    }
+
     /// Create a [`Transaction`] for this `SnapshotRef`.
     pub fn transaction(self: Arc<Self>) -> DeltaResult<Transaction> {
         Transaction::try_new(self)
@@ -487,7 +688,7 @@ mod tests {
             .at_version(0)
             .build(&engine);
         assert!(matches!(
-            snapshot_res,
+            snapshot_res.as_ref().map_err(Error::inner),
             Err(Error::Generic(msg)) if msg == "Requested snapshot version 0 is older than snapshot hint version 1"
         ));
 
@@ -574,7 +775,7 @@ mod tests {
         assert_eq!(snapshot, expected);
         // version exceeds latest version of the table = err
         assert!(matches!(
-            Snapshot::builder_from(base_snapshot.clone()).at_version(1).build(&engine),
+            Snapshot::builder_from(base_snapshot.clone()).at_version(1).build(&engine).as_ref().map_err(Error::inner),
             Err(Error::Generic(msg)) if msg == "Requested snapshot version 1 is newer than the latest version 0"
         ));
 
@@ -641,7 +842,7 @@ mod tests {
             .at_version(0)
             .build(&engine)?;
         assert!(matches!(
-            Snapshot::builder_from(base_snapshot.clone()).at_version(2).build(&engine),
+            Snapshot::builder_from(base_snapshot.clone()).at_version(2).build(&engine).as_ref().map_err(Error::inner),
             Err(Error::Generic(msg)) if msg == "LogSegment end version 1 not the same as the specified end version 2"
         ));
 
@@ -1,6 +1,9 @@
 //! Builder for creating [`Snapshot`] instances.
 use crate::log_segment::LogSegment;
+use crate::snapshot::diagnostics::{best_effort_log_segment, LogDiagnostics};
+use crate::snapshot::integrity::{verify_crc, CrcVerification};
 use crate::snapshot::SnapshotRef;
+use crate::utils::{ensure_trailing_slash, enter_span, try_parse_uri};
 use crate::LogPath;
 use crate::{DeltaResult, Engine, Error, Snapshot, Version};
 
@@ -34,24 +37,34 @@ pub struct SnapshotBuilder {
     existing_snapshot: Option<SnapshotRef>,
     version: Option<Version>,
     log_tail: Vec<LogPath>,
+    single_pass_replay: bool,
+    crc_verification: CrcVerification,
 }
 
 impl SnapshotBuilder {
     pub(crate) fn new_for(table_root: Url) -> Self {
         Self {
-            table_root: Some(table_root),
+            table_root: Some(ensure_trailing_slash(table_root)),
             existing_snapshot: None,
             version: None,
             log_tail: Vec::new(),
+            single_pass_replay: false,
+            crc_verification: CrcVerification::Disabled,
         }
     }
 
+    pub(crate) fn try_new_for_uri(uri: impl AsRef<str>) -> DeltaResult<Self> {
+        Ok(Self::new_for(try_parse_uri(uri)?))
+    }
+
     pub(crate) fn new_from(existing_snapshot: SnapshotRef) -> Self {
         Self {
             table_root: None,
             existing_snapshot: Some(existing_snapshot),
             version: None,
             log_tail: Vec::new(),
+            single_pass_replay: false,
+            crc_verification: CrcVerification::Disabled,
         }
     }
 
@@ -73,6 +86,37 @@ impl SnapshotBuilder {
         self
     }
 
+    /// Replay the log in a single pass for both the snapshot and its first scan. Building a
+    /// snapshot replays the log for the table's protocol and metadata, and [`Scan::scan_metadata`]
+    /// replays it again for the table's files. With a single pass, building the snapshot reads its
+    /// commits in full and keeps them in memory for the first scan of the snapshot to reuse, so the
+    /// common "build a snapshot, then scan it" flow reads each commit once. Later scans of the
+    /// snapshot read the log again.
+    ///
+    /// This is worth it when the snapshot is built to be scanned, but wastes the memory of the
+    /// commits since the last checkpoint otherwise. It only applies to snapshots built from
+    /// scratch (see [`Snapshot::builder_for`]). By default, commits are not kept.
+    ///
+    /// [`Scan::scan_metadata`]: crate::scan::Scan::scan_metadata
+    pub fn with_single_pass_replay(mut self, single_pass_replay: bool) -> Self {
+        self.single_pass_replay = single_pass_replay;
+        self
+    }
+
+    /// Verify the snapshot against the version checksum (`.crc`) file of its version, if the table
+    /// has one. With verification enabled, building the snapshot also replays the log for the
+    /// files of the table and fails with [`Error::ChecksumMismatch`] if their number, total size or
+    /// size histogram (and with [`CrcVerification::AllFiles`], their paths and sizes) do not match
+    /// the checksum file.
+    ///
+    /// This trades latency for detecting corrupted or incomplete logs, so it is only worth it when
+    /// correctness matters more than the cost of an extra log replay. By default, snapshots are not
+    /// verified.
+    pub fn with_crc_verification(mut self, crc_verification: CrcVerification) -> Self {
+        self.crc_verification = crc_verification;
+        self
+    }
+
     /// Create a new [`Snapshot`]. This returns a [`SnapshotRef`] (`Arc<Snapshot>`), perhaps
     /// returning a reference to an existing snapshot if the request to build a new snapshot
     /// matches the version of an existing snapshot.
@@ -80,22 +124,108 @@ impl SnapshotBuilder {
     /// # Parameters
     ///
     /// - `engine`: Implementation of [`Engine`] apis.
+    ///
+    /// Errors carry the table and the requested version (if any) as [`ErrorContext`].
+    ///
+    /// [`ErrorContext`]: crate::error::ErrorContext
     pub fn build(self, engine: &dyn Engine) -> DeltaResult<SnapshotRef> {
+        let table_root = self.table_root.clone().or_else(|| {
+            let existing_snapshot = self.existing_snapshot.as_ref();
+            existing_snapshot.map(|snapshot| snapshot.table_root().clone())
+        });
+        let version = self.version;
+        let crc_verification = self.crc_verification;
+        let snapshot = self.build_impl(engine).and_then(|snapshot| {
+            verify_crc(&snapshot, engine, crc_verification)?;
+            Ok(snapshot)
+        });
+        snapshot.map_err(|err| {
+            let err = match &table_root {
+                Some(table_root) => err.with_table(table_root),
+                None => err,
+            };
+            match version {
+                Some(version) => err.with_version(version),
+                None => err,
+            }
+        })
+    }
+
+    /// Create a new [`Snapshot`] of a table whose log may be damaged, e.g. for recovery tooling.
+    /// Unlike [`Self::build`], this tolerates missing commits, commits with truncated lines and
+    /// commits with unparseable actions after the latest checkpoint. The snapshot is built at the
+    /// last version before the first such commit, and the returned [`LogDiagnostics`] reports the
+    /// damage found and the versions that were skipped.
+    ///
+    /// Every commit after the latest checkpoint is read in full to check it, so this is
+    /// considerably more expensive than [`Self::build`]. A damaged checkpoint is still an error,
+    /// and so is a log without any consistent version. The snapshot is always built from scratch,
+    /// also when this builder was created from an existing snapshot.
+    pub fn build_best_effort(
+        self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<(SnapshotRef, LogDiagnostics)> {
+        let table_root = match (self.table_root, self.existing_snapshot) {
+            (Some(table_root), _) => table_root,
+            (None, Some(existing_snapshot)) => existing_snapshot.table_root().clone(),
+            (None, None) => {
+                return Err(Error::internal_error(
+                    "SnapshotBuilder should have either table_root or existing_snapshot",
+                ))
+            }
+        };
+        let version = self.version;
+        let build = || {
+            enter_span!(INFO, "snapshot_best_effort", table = %table_root, version);
+            let log_tail = self.log_tail.into_iter().map(Into::into).collect();
+            let (log_segment, diagnostics) = best_effort_log_segment(
+                engine,
+                table_root.join("_delta_log/")?,
+                log_tail,
+                version,
+            )?;
+            let snapshot =
+                Snapshot::try_new_from_log_segment(table_root.clone(), log_segment, engine)?;
+            Ok((snapshot.into(), diagnostics))
+        };
+        build().map_err(|err: Error| {
+            let err = err.with_table(&table_root);
+            match version {
+                Some(version) => err.with_version(version),
+                None => err,
+            }
+        })
+    }
+
+    fn build_impl(self, engine: &dyn Engine) -> DeltaResult<SnapshotRef> {
         let log_tail = self.log_tail.into_iter().map(Into::into).collect();
         if let Some(table_root) = self.table_root {
+            enter_span!(INFO, "snapshot", table = %table_root, version = self.version);
             let log_segment = LogSegment::for_snapshot(
                 engine.storage_handler().as_ref(),
                 table_root.join("_delta_log/")?,
                 log_tail,
                 self.version,
             )?;
-            Ok(Snapshot::try_new_from_log_segment(table_root, log_segment, engine)?.into())
+            let snapshot = match self.single_pass_replay {
+                true => {
+                    Snapshot::try_new_from_log_segment_with_commits(table_root, log_segment, engine)
+                }
+                false => Snapshot::try_new_from_log_segment(table_root, log_segment, engine),
+            };
+            Ok(snapshot?.into())
         } else {
             let existing_snapshot = self.existing_snapshot.ok_or_else(|| {
                 Error::internal_error(
                     "SnapshotBuilder should have either table_root or existing_snapshot",
                 )
             })?;
+            enter_span!(
+                INFO,
+                "snapshot",
+                table = %existing_snapshot.table_root(),
+                version = self.version
+            );
             Snapshot::try_new_from(existing_snapshot, log_tail, engine, self.version)
         }
     }
@@ -108,6 +238,7 @@ mod tests {
     use crate::engine::default::{executor::tokio::TokioBackgroundExecutor, DefaultEngine};
 
     use itertools::Itertools;
+    use object_store::local::LocalFileSystem;
     use object_store::memory::InMemory;
     use object_store::ObjectStore;
     use serde_json::json;
@@ -128,7 +259,7 @@ mod tests {
         (engine, store, table_root)
     }
 
-    fn create_table(store: &Arc<dyn ObjectStore>, _table_root: &Url) -> DeltaResult<()> {
+    fn create_table(store: &Arc<dyn ObjectStore>, table_root: &Url) -> DeltaResult<()> {
         let protocol = json!({
             "minReaderVersion": 3,
             "minWriterVersion": 7,
@@ -165,7 +296,8 @@ mod tests {
             .collect_vec()
             .join("\n");
 
-        let path = object_store::path::Path::from(format!("_delta_log/{:020}.json", 0).as_str());
+        let path = format!("{}/_delta_log/{:020}.json", table_root.path(), 0);
+        let path = object_store::path::Path::from(path.as_str());
         futures::executor::block_on(async { store.put(&path, commit0_data.into()).await })?;
 
         // Create commit 1 with a single addFile action
@@ -188,7 +320,8 @@ mod tests {
             .collect_vec()
             .join("\n");
 
-        let path = object_store::path::Path::from(format!("_delta_log/{:020}.json", 1).as_str());
+        let path = format!("{}/_delta_log/{:020}.json", table_root.path(), 1);
+        let path = object_store::path::Path::from(path.as_str());
         futures::executor::block_on(async { store.put(&path, commit1_data.into()).await })?;
 
         Ok(())
@@ -208,6 +341,33 @@ mod tests {
             .build(engine)?;
         assert_eq!(snapshot.version(), 0);
 
+        // the table root is a directory, however it was written
+        assert_eq!(snapshot.table_root().as_str(), "memory:///test_table/");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_builder_not_a_delta_table() -> Result<(), Box<dyn std::error::Error>> {
+        let (engine, store, table_root) = setup_test();
+        create_table(&store, &table_root)?;
+
+        let other_root = Url::parse("memory:///other_table/")?;
+        let err = SnapshotBuilder::new_for(other_root.clone())
+            .build(engine.as_ref())
+            .unwrap_err();
+        assert!(matches!(err.inner(), Error::NotADeltaTable(_)));
+        assert_eq!(err.context().unwrap().table, Some(other_root));
+
+        // a local directory without a log
+        let dir = tempfile::tempdir()?;
+        let builder = SnapshotBuilder::try_new_for_uri(dir.path().to_str().unwrap())?;
+        let engine = DefaultEngine::new(
+            Arc::new(LocalFileSystem::new()),
+            Arc::new(TokioBackgroundExecutor::new()),
+        );
+        let err = builder.build(&engine).unwrap_err();
+        assert!(matches!(err.inner(), Error::NotADeltaTable(_)));
         Ok(())
     }
 }
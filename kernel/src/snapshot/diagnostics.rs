@@ -0,0 +1,297 @@
+//! Best-effort loading of snapshots of tables with a damaged log, see
+//! [`SnapshotBuilder::build_best_effort`].
+//!
+//! [`SnapshotBuilder::build_best_effort`]: crate::snapshot::SnapshotBuilder::build_best_effort
+use std::fmt::{Display, Formatter};
+
+use tracing::warn;
+use url::Url;
+
+use crate::actions::get_log_schema;
+use crate::listed_log_files::ListedLogFiles;
+use crate::log_segment::LogSegment;
+use crate::path::ParsedLogPath;
+use crate::{DeltaResult, Engine, Error, Version};
+
+/// A report of the damage found in the log of a table by
+/// [`SnapshotBuilder::build_best_effort`]. The snapshot is built at the last version before the
+/// first damaged commit, so that it only reflects a consistent prefix of the log.
+///
+/// [`SnapshotBuilder::build_best_effort`]: crate::snapshot::SnapshotBuilder::build_best_effort
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogDiagnostics {
+    /// The latest commit version found in the log, if any. This is newer than the version of the
+    /// snapshot if commits were skipped.
+    pub latest_listed_version: Option<Version>,
+    /// The damage found in the commits after the latest checkpoint, ordered by version.
+    pub issues: Vec<LogIssue>,
+    /// The versions of the commits found in the log that the snapshot does not include, because
+    /// they come after a damaged part of the log.
+    pub skipped_versions: Vec<Version>,
+}
+
+impl LogDiagnostics {
+    /// Whether the log is undamaged, i.e. the snapshot includes every commit found in the log.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A damaged part of the log of a table, see [`LogDiagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogIssue {
+    /// The commit of `version` is missing, although the log has newer commits.
+    MissingCommit { version: Version },
+    /// The commit of `version` could not be read, e.g. because it has a truncated line or an
+    /// action that does not match the schema of its type.
+    UnreadableCommit {
+        version: Version,
+        location: Url,
+        error: String,
+    },
+}
+
+impl LogIssue {
+    /// The version of the damaged commit.
+    pub fn version(&self) -> Version {
+        match self {
+            Self::MissingCommit { version } | Self::UnreadableCommit { version, .. } => *version,
+        }
+    }
+}
+
+impl Display for LogIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingCommit { version } => write!(f, "Commit {version} is missing"),
+            Self::UnreadableCommit {
+                version,
+                location,
+                error,
+            } => write!(f, "Commit {version} at {location} is unreadable: {error}"),
+        }
+    }
+}
+
+/// Builds the log segment of the latest version (up to `end_version`) of the log at `log_root` up
+/// to which the log is consistent. Every commit after the latest checkpoint is read in full to
+/// check it, and the damaged ones are reported in the returned [`LogDiagnostics`].
+pub(crate) fn best_effort_log_segment(
+    engine: &dyn Engine,
+    log_root: Url,
+    log_tail: Vec<ParsedLogPath>,
+    end_version: Option<Version>,
+) -> DeltaResult<(LogSegment, LogDiagnostics)> {
+    let storage = engine.storage_handler();
+    // the `_last_checkpoint` hint may itself be damaged, so list the whole log
+    let listed_files = ListedLogFiles::list(
+        storage.as_ref(),
+        &log_root,
+        log_tail.clone(),
+        None,
+        end_version,
+    )?;
+    let checkpoint_version = listed_files.checkpoint_parts.first().map(|cp| cp.version);
+    let commits = listed_files.ascending_commit_files;
+
+    let mut diagnostics = LogDiagnostics {
+        latest_listed_version: commits.last().map(|commit| commit.version),
+        ..Default::default()
+    };
+    let mut next_version = checkpoint_version.map_or(0, |version| version + 1);
+    for commit in &commits {
+        diagnostics.issues.extend(
+            (next_version..commit.version).map(|version| LogIssue::MissingCommit { version }),
+        );
+        next_version = commit.version + 1;
+
+        let files = std::slice::from_ref(&commit.location);
+        let read_result = engine
+            .json_handler()
+            .read_json_files(files, get_log_schema().clone(), None)
+            .and_then(|mut batches| batches.try_for_each(|batch| batch.map(|_| ())));
+        if let Err(err) = read_result {
+            diagnostics.issues.push(LogIssue::UnreadableCommit {
+                version: commit.version,
+                location: commit.location.location.clone(),
+                error: err.to_string(),
+            });
+        }
+    }
+
+    // the snapshot stops right before the first damaged commit
+    let consistent_version = match diagnostics.issues.first() {
+        Some(issue) => {
+            let version = issue.version().checked_sub(1).filter(|version| {
+                checkpoint_version.is_some_and(|checkpoint| checkpoint <= *version)
+                    || commits.first().is_some_and(|commit| commit.version == 0)
+            });
+            let Some(version) = version else {
+                return Err(Error::generic(format!(
+                    "The log at {log_root} has no consistent version: {issue}"
+                )));
+            };
+            version
+        }
+        None => commits
+            .last()
+            .map(|commit| commit.version)
+            .or(checkpoint_version)
+            .ok_or_else(|| {
+                Error::not_a_delta_table(format!("No commits or checkpoints found in {log_root}"))
+            })?,
+    };
+    for issue in &diagnostics.issues {
+        warn!("Damaged log at {log_root}: {issue}");
+    }
+    diagnostics.skipped_versions = commits
+        .iter()
+        .map(|commit| commit.version)
+        .filter(|version| *version > consistent_version)
+        .collect();
+
+    let log_segment =
+        LogSegment::for_snapshot(storage.as_ref(), log_root, log_tail, consistent_version)?;
+    Ok((log_segment, diagnostics))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::json;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::Snapshot;
+
+    use super::*;
+
+    fn commit_path(version: Version) -> Path {
+        Path::from(format!("test_table/_delta_log/{version:020}.json"))
+    }
+
+    fn add_action(path: &str) -> String {
+        json!({
+            "add": {
+                "path": path,
+                "partitionValues": {},
+                "size": 1,
+                "modificationTime": 0,
+                "dataChange": true
+            }
+        })
+        .to_string()
+    }
+
+    async fn setup_table(
+        num_commits: u64,
+    ) -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let store = Arc::new(InMemory::new());
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let metadata = json!({
+            "metaData": {
+                "id": "test",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[]}",
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 0
+            }
+        });
+        let commit0 = format!("{protocol}\n{metadata}\n{}", add_action("0.parquet"));
+        store.put(&commit_path(0), commit0.into()).await.unwrap();
+        for version in 1..num_commits {
+            let data = add_action(&format!("{version}.parquet"));
+            store.put(&commit_path(version), data.into()).await.unwrap();
+        }
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///test_table/").unwrap();
+        (store, engine, table_root)
+    }
+
+    #[tokio::test]
+    async fn test_clean_log() {
+        let (_, engine, table_root) = setup_table(3).await;
+        let (snapshot, diagnostics) = Snapshot::builder_for(table_root)
+            .build_best_effort(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 2);
+        assert!(diagnostics.is_clean());
+        assert_eq!(diagnostics.latest_listed_version, Some(2));
+        assert!(diagnostics.skipped_versions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_commit() {
+        let (store, engine, table_root) = setup_table(5).await;
+        store.delete(&commit_path(2)).await.unwrap();
+
+        let (snapshot, diagnostics) = Snapshot::builder_for(table_root.clone())
+            .build_best_effort(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 1);
+        assert_eq!(
+            diagnostics,
+            LogDiagnostics {
+                latest_listed_version: Some(4),
+                issues: vec![LogIssue::MissingCommit { version: 2 }],
+                skipped_versions: vec![3, 4],
+            }
+        );
+        // without best effort, the gap is an error
+        assert!(Snapshot::builder_for(table_root).build(&engine).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_commits() {
+        let (store, engine, table_root) = setup_table(5).await;
+        let mut truncated = add_action("2.parquet");
+        truncated.truncate(truncated.len() / 2);
+        store.put(&commit_path(2), truncated.into()).await.unwrap();
+        let unparseable = json!({"add": {"path": {"not": "a string"}}}).to_string();
+        store
+            .put(&commit_path(4), unparseable.into())
+            .await
+            .unwrap();
+
+        let (snapshot, diagnostics) = Snapshot::builder_for(table_root)
+            .build_best_effort(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 1);
+        assert_eq!(diagnostics.skipped_versions, [2, 3, 4]);
+        let versions: Vec<_> = diagnostics.issues.iter().map(LogIssue::version).collect();
+        assert_eq!(versions, [2, 4]);
+        assert!(diagnostics
+            .issues
+            .iter()
+            .all(|issue| matches!(issue, LogIssue::UnreadableCommit { .. })));
+
+        let scan = snapshot.scan_builder().build().unwrap();
+        let mut files = vec![];
+        for scan_metadata in scan.scan_metadata(&engine).unwrap() {
+            files = scan_metadata
+                .unwrap()
+                .visit_scan_files(files, |files: &mut Vec<String>, path, _, _, _, _, _| {
+                    files.push(path.to_string())
+                })
+                .unwrap();
+        }
+        files.sort();
+        assert_eq!(files, ["0.parquet", "1.parquet"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_consistent_version() {
+        let (store, engine, table_root) = setup_table(2).await;
+        store.put(&commit_path(0), "{".into()).await.unwrap();
+
+        let err = Snapshot::builder_for(table_root)
+            .build_best_effort(&engine)
+            .unwrap_err();
+        assert!(err.to_string().contains("no consistent version"), "{err}");
+    }
+}
@@ -0,0 +1,348 @@
+//! Verification of snapshots against the version checksum (`.crc`) file of their version, see
+//! [`SnapshotBuilder::with_crc_verification`].
+//!
+//! [`SnapshotBuilder::with_crc_verification`]: crate::snapshot::SnapshotBuilder::with_crc_verification
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::snapshot::SnapshotRef;
+use crate::{DeltaResult, Engine, Error};
+
+/// How a snapshot is verified against the version checksum (`.crc`) file of its version, see
+/// [`SnapshotBuilder::with_crc_verification`]. Snapshots of versions without a checksum file are
+/// never verified.
+///
+/// [`SnapshotBuilder::with_crc_verification`]: crate::snapshot::SnapshotBuilder::with_crc_verification
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CrcVerification {
+    /// Don't verify the snapshot.
+    #[default]
+    Disabled,
+    /// Verify the number of files, the size of the table and the file size histogram (if any)
+    /// derived from replaying the log against the checksum file.
+    Totals,
+    /// Verify the totals as with [`CrcVerification::Totals`], and also verify the path and size
+    /// of every file against the list of files of the checksum file (if any).
+    AllFiles,
+}
+
+/// The parts of a checksum file that are verified, see `Crc` in `actions::crc` for the full
+/// schema.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CrcTotals {
+    table_size_bytes: i64,
+    num_files: i64,
+    num_metadata: i64,
+    num_protocol: i64,
+    file_size_histogram: Option<CrcFileSizeHistogram>,
+    all_files: Option<Vec<CrcFile>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CrcFileSizeHistogram {
+    sorted_bin_boundaries: Vec<i64>,
+    file_counts: Vec<i64>,
+    total_bytes: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrcFile {
+    path: String,
+    size: i64,
+}
+
+/// Verifies `snapshot` against the checksum file of its version according to `mode`, replaying
+/// its log for the files of the table. Returns a [`Error::ChecksumMismatch`] if the table state
+/// does not match the checksum file.
+pub(crate) fn verify_crc(
+    snapshot: &SnapshotRef,
+    engine: &dyn Engine,
+    mode: CrcVerification,
+) -> DeltaResult<()> {
+    if mode == CrcVerification::Disabled {
+        return Ok(());
+    }
+    let crc_file = snapshot.log_segment().latest_crc_file.as_ref();
+    let Some(crc_file) = crc_file.filter(|crc_file| crc_file.version == snapshot.version()) else {
+        debug!("No checksum file for version {}", snapshot.version());
+        return Ok(());
+    };
+    let location = &crc_file.location.location;
+    let storage = engine.storage_handler();
+    let data = match storage.read_files(vec![(location.clone(), None)])?.next() {
+        Some(data) => data?,
+        None => {
+            return Err(Error::checksum_mismatch(format!(
+                "Empty checksum file {location}"
+            )))
+        }
+    };
+    let crc: CrcTotals = serde_json::from_slice(&data)?;
+
+    let files = live_files(snapshot, engine)?;
+    let num_files = files.len() as i64;
+    let table_size_bytes = files.values().sum::<i64>();
+    let mismatch = |what: &str, actual: i64, expected: i64| {
+        Error::checksum_mismatch(format!(
+            "{what} is {actual}, but the checksum file {location} records {expected}"
+        ))
+    };
+    if crc.num_metadata != 1 || crc.num_protocol != 1 {
+        return Err(Error::checksum_mismatch(format!(
+            "The checksum file {location} records {} metadata and {} protocol actions, expected 1",
+            crc.num_metadata, crc.num_protocol
+        )));
+    }
+    if num_files != crc.num_files {
+        return Err(mismatch("Number of files", num_files, crc.num_files));
+    }
+    if table_size_bytes != crc.table_size_bytes {
+        return Err(mismatch(
+            "Table size in bytes",
+            table_size_bytes,
+            crc.table_size_bytes,
+        ));
+    }
+    if let Some(histogram) = crc.file_size_histogram {
+        verify_histogram(&histogram, files.values().copied(), location.as_str())?;
+    }
+    if let (CrcVerification::AllFiles, Some(all_files)) = (mode, crc.all_files) {
+        let expected: HashMap<_, _> = all_files
+            .into_iter()
+            .map(|file| (file.path, file.size))
+            .collect();
+        if let Some((path, size)) = files
+            .iter()
+            .find(|(path, size)| expected.get(*path) != Some(*size))
+        {
+            return Err(Error::checksum_mismatch(match expected.get(path) {
+                Some(expected) => format!(
+                    "Size of file {path} is {size}, but the checksum file {location} records {expected}"
+                ),
+                None => format!("File {path} is missing from the checksum file {location}"),
+            }));
+        }
+        if let Some(path) = expected.keys().find(|path| !files.contains_key(*path)) {
+            return Err(Error::checksum_mismatch(format!(
+                "File {path} of the checksum file {location} is not in the table"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The path and size of every live file of `snapshot`.
+fn live_files(snapshot: &SnapshotRef, engine: &dyn Engine) -> DeltaResult<HashMap<String, i64>> {
+    let scan = snapshot.clone().scan_builder().build()?;
+    let mut files = HashMap::new();
+    for scan_metadata in scan.scan_metadata(engine)? {
+        files = scan_metadata?.visit_scan_files(
+            files,
+            |files: &mut HashMap<String, i64>, path, size, _, _, _, _| {
+                files.insert(path.to_string(), size);
+            },
+        )?;
+    }
+    Ok(files)
+}
+
+fn verify_histogram(
+    histogram: &CrcFileSizeHistogram,
+    sizes: impl Iterator<Item = i64>,
+    location: &str,
+) -> DeltaResult<()> {
+    let boundaries = &histogram.sorted_bin_boundaries;
+    if boundaries.first() != Some(&0)
+        || histogram.file_counts.len() != boundaries.len()
+        || histogram.total_bytes.len() != boundaries.len()
+    {
+        return Err(Error::checksum_mismatch(format!(
+            "Invalid file size histogram in the checksum file {location}"
+        )));
+    }
+    let mut file_counts = vec![0; boundaries.len()];
+    let mut total_bytes = vec![0; boundaries.len()];
+    for size in sizes {
+        // the bin of a file is the last one starting at or before its size
+        let bin = boundaries.partition_point(|boundary| *boundary <= size);
+        let bin = bin.saturating_sub(1);
+        file_counts[bin] += 1;
+        total_bytes[bin] += size;
+    }
+    if file_counts != histogram.file_counts || total_bytes != histogram.total_bytes {
+        return Err(Error::checksum_mismatch(format!(
+            "File size histogram is {file_counts:?} files of {total_bytes:?} bytes, but the \
+            checksum file {location} records {:?} files of {:?} bytes",
+            histogram.file_counts, histogram.total_bytes
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::{json, Value};
+    use url::Url;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::Snapshot;
+
+    use super::*;
+
+    const CRC_PATH: &str = "test_table/_delta_log/00000000000000000001.crc";
+
+    async fn setup_table() -> (Arc<InMemory>, DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let store = Arc::new(InMemory::new());
+        let add = |path: &str, size: i64| {
+            json!({
+                "add": {
+                    "path": path,
+                    "partitionValues": {},
+                    "size": size,
+                    "modificationTime": 0,
+                    "dataChange": true
+                }
+            })
+        };
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let metadata = json!({
+            "metaData": {
+                "id": "test",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": "{\"type\":\"struct\",\"fields\":[]}",
+                "partitionColumns": [],
+                "configuration": {},
+                "createdTime": 0
+            }
+        });
+        let commit0 = format!("{protocol}\n{metadata}\n{}", add("a.parquet", 10));
+        let commit1 = add("b.parquet", 200).to_string();
+        for (version, commit) in [commit0, commit1].into_iter().enumerate() {
+            let path = Path::from(format!("test_table/_delta_log/{version:020}.json"));
+            store.put(&path, commit.into()).await.unwrap();
+        }
+        let engine = DefaultEngine::new(store.clone(), Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///test_table/").unwrap();
+        (store, engine, table_root)
+    }
+
+    fn crc(num_files: i64, table_size_bytes: i64) -> Value {
+        json!({
+            "tableSizeBytes": table_size_bytes,
+            "numFiles": num_files,
+            "numMetadata": 1,
+            "numProtocol": 1,
+            "fileSizeHistogram": {
+                "sortedBinBoundaries": [0, 100],
+                "fileCounts": [1, 1],
+                "totalBytes": [10, 200]
+            },
+            "allFiles": [
+                {"path": "a.parquet", "size": 10},
+                {"path": "b.parquet", "size": 200}
+            ]
+        })
+    }
+
+    async fn build(
+        crc: Value,
+        mode: CrcVerification,
+    ) -> DeltaResult<(SnapshotRef, DefaultEngine<TokioBackgroundExecutor>)> {
+        let (store, engine, table_root) = setup_table().await;
+        let path = Path::from(CRC_PATH);
+        store.put(&path, crc.to_string().into()).await.unwrap();
+        let snapshot = Snapshot::builder_for(table_root)
+            .with_crc_verification(mode)
+            .build(&engine)?;
+        Ok((snapshot, engine))
+    }
+
+    fn assert_checksum_mismatch(result: DeltaResult<impl std::fmt::Debug>, expected: &str) {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err.inner(), Error::ChecksumMismatch(_)),
+            "unexpected error: {err}"
+        );
+        assert!(err.to_string().contains(expected), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_matching_crc() {
+        for mode in [CrcVerification::Totals, CrcVerification::AllFiles] {
+            let (snapshot, _) = build(crc(2, 210), mode).await.unwrap();
+            assert_eq!(snapshot.version(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_totals() {
+        assert_checksum_mismatch(
+            build(crc(3, 210), CrcVerification::Totals).await,
+            "Number of files is 2",
+        );
+        assert_checksum_mismatch(
+            build(crc(2, 211), CrcVerification::Totals).await,
+            "Table size in bytes is 210",
+        );
+        let mut bad_histogram = crc(2, 210);
+        bad_histogram["fileSizeHistogram"]["sortedBinBoundaries"] = json!([0, 1000]);
+        assert_checksum_mismatch(
+            build(bad_histogram, CrcVerification::Totals).await,
+            "File size histogram is [2, 0] files of [210, 0] bytes",
+        );
+        // mismatches are only found when verification is enabled
+        build(crc(3, 211), CrcVerification::Disabled).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_files() {
+        let mut renamed = crc(2, 210);
+        renamed["allFiles"][1]["path"] = json!("c.parquet");
+        // the totals still match
+        build(renamed.clone(), CrcVerification::Totals)
+            .await
+            .unwrap();
+        assert_checksum_mismatch(
+            build(renamed, CrcVerification::AllFiles).await,
+            "is missing from the checksum file",
+        );
+
+        let mut resized = crc(2, 210);
+        resized["allFiles"][0]["size"] = json!(11);
+        assert_checksum_mismatch(
+            build(resized, CrcVerification::AllFiles).await,
+            "Size of file a.parquet is 10",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_crc_for_version() {
+        let (store, engine, table_root) = setup_table().await;
+        // only the older version has a (mismatched) checksum file
+        let path = Path::from("test_table/_delta_log/00000000000000000000.crc");
+        store
+            .put(&path, crc(5, 5).to_string().into())
+            .await
+            .unwrap();
+        let snapshot = Snapshot::builder_for(table_root.clone())
+            .with_crc_verification(CrcVerification::AllFiles)
+            .build(&engine)
+            .unwrap();
+        assert_eq!(snapshot.version(), 1);
+        let result = Snapshot::builder_for(table_root)
+            .at_version(0)
+            .with_crc_verification(CrcVerification::Totals)
+            .build(&engine);
+        assert_checksum_mismatch(result, "Number of files is 1");
+    }
+}
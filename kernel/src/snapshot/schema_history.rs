@@ -0,0 +1,178 @@
+//! The history of the schema of a table, see [`Snapshot::schema_changes`].
+//!
+//! [`Snapshot::schema_changes`]: crate::Snapshot::schema_changes
+use std::slice;
+use std::sync::Arc;
+
+use crate::actions::{get_log_metadata_schema, Metadata};
+use crate::log_segment::LogSegment;
+use crate::schema::{SchemaChange, SchemaRef};
+use crate::{DeltaResult, Engine, Version};
+
+/// A change of the schema of a table, committed in a table version. See
+/// [`Snapshot::schema_changes`].
+///
+/// [`Snapshot::schema_changes`]: crate::Snapshot::schema_changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaVersionChange {
+    /// The version that changed the schema.
+    pub version: Version,
+    /// The schema of the table as of `version`.
+    pub schema: SchemaRef,
+    /// The changes of the columns compared to the schema of the previous version.
+    pub changes: Vec<SchemaChange>,
+}
+
+/// Collects the schema changes committed by the commits of `log_segment`, starting from the
+/// `schema` of the version before the first commit.
+pub(crate) fn schema_changes(
+    engine: &dyn Engine,
+    log_segment: &LogSegment,
+    mut schema: SchemaRef,
+) -> DeltaResult<Vec<SchemaVersionChange>> {
+    let mut schema_changes = vec![];
+    for commit in &log_segment.ascending_commit_files {
+        let actions = engine.json_handler().read_json_files(
+            slice::from_ref(&commit.location),
+            get_log_metadata_schema().clone(),
+            None,
+        )?;
+        let mut metadata = None;
+        for actions in actions {
+            metadata = Metadata::try_new_from_data(actions?.as_ref())?.or(metadata);
+        }
+        let Some(metadata) = metadata else {
+            continue;
+        };
+        let new_schema = Arc::new(metadata.parse_schema()?);
+        let changes = schema.diff(&new_schema);
+        // metadata actions that only change e.g. table properties don't change the schema
+        if !changes.is_empty() {
+            schema_changes.push(SchemaVersionChange {
+                version: commit.version,
+                schema: new_schema.clone(),
+                changes,
+            });
+        }
+        schema = new_schema;
+    }
+    Ok(schema_changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::ObjectStore;
+    use serde_json::{json, Value};
+    use url::Url;
+
+    use crate::engine::default::executor::tokio::TokioBackgroundExecutor;
+    use crate::engine::default::DefaultEngine;
+    use crate::schema::{ColumnName, DataType, StructField, StructType};
+    use crate::Snapshot;
+
+    use super::*;
+
+    fn metadata_action(fields: Value, configuration: Value) -> String {
+        let schema_string = json!({"type": "struct", "fields": fields}).to_string();
+        json!({
+            "metaData": {
+                "id": "test",
+                "format": {"provider": "parquet", "options": {}},
+                "schemaString": schema_string,
+                "partitionColumns": [],
+                "configuration": configuration,
+                "createdTime": 0
+            }
+        })
+        .to_string()
+    }
+
+    fn field(name: &str, data_type: &str) -> Value {
+        json!({"name": name, "type": data_type, "nullable": true, "metadata": {}})
+    }
+
+    // Creates a table whose versions 1 and 3 change the schema, and whose version 2 only changes
+    // the table properties
+    async fn setup_table() -> (DefaultEngine<TokioBackgroundExecutor>, Url) {
+        let store = Arc::new(InMemory::new());
+        let protocol = json!({"protocol": {"minReaderVersion": 1, "minWriterVersion": 2}});
+        let commits = [
+            format!(
+                "{protocol}\n{}",
+                metadata_action(json!([field("a", "integer")]), json!({}))
+            ),
+            metadata_action(
+                json!([field("a", "integer"), field("b", "string")]),
+                json!({}),
+            ),
+            metadata_action(
+                json!([field("a", "integer"), field("b", "string")]),
+                json!({"delta.appendOnly": "true"}),
+            ),
+            metadata_action(json!([field("a", "long")]), json!({})),
+        ];
+        for (version, commit) in commits.into_iter().enumerate() {
+            let path = Path::from(format!("test_table/_delta_log/{version:020}.json"));
+            store.put(&path, commit.into()).await.unwrap();
+        }
+        let engine = DefaultEngine::new(store, Arc::new(TokioBackgroundExecutor::new()));
+        let table_root = Url::parse("memory:///test_table/").unwrap();
+        (engine, table_root)
+    }
+
+    #[tokio::test]
+    async fn test_schema_at() {
+        let (engine, table_root) = setup_table().await;
+        let snapshot = Snapshot::builder_for(table_root)
+            .at_version(2)
+            .build(&engine)
+            .unwrap();
+        let schema = snapshot.schema_at(&engine, 0).unwrap();
+        let expected = StructType::new_unchecked([StructField::nullable("a", DataType::INTEGER)]);
+        assert_eq!(schema.as_ref(), &expected);
+        assert_eq!(snapshot.schema_at(&engine, 2).unwrap(), snapshot.schema());
+        // versions after the snapshot version can't be read
+        assert!(snapshot.schema_at(&engine, 3).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_schema_changes() {
+        let (engine, table_root) = setup_table().await;
+        let snapshot = Snapshot::builder_for(table_root).build(&engine).unwrap();
+
+        let changes = snapshot.schema_changes(&engine, 0, 3).unwrap();
+        let versions: Vec<_> = changes.iter().map(|change| change.version).collect();
+        assert_eq!(versions, [1, 3]);
+        assert_eq!(
+            changes[0].changes,
+            [SchemaChange::ColumnAdded {
+                column: ColumnName::new(["b"]),
+                field: StructField::nullable("b", DataType::STRING),
+            }]
+        );
+        assert_eq!(
+            changes[1].changes,
+            [
+                SchemaChange::TypeChanged {
+                    column: ColumnName::new(["a"]),
+                    from: DataType::INTEGER,
+                    to: DataType::LONG,
+                },
+                SchemaChange::ColumnRemoved {
+                    column: ColumnName::new(["b"]),
+                    field: StructField::nullable("b", DataType::STRING),
+                },
+            ]
+        );
+        assert_eq!(changes[1].schema, snapshot.schema());
+
+        // the changes are relative to the schema at the start version
+        let changes = snapshot.schema_changes(&engine, 1, 2).unwrap();
+        assert!(changes.is_empty());
+        assert!(snapshot.schema_changes(&engine, 3, 3).unwrap().is_empty());
+        assert!(snapshot.schema_changes(&engine, 2, 1).is_err());
+        assert!(snapshot.schema_changes(&engine, 0, 4).is_err());
+    }
+}
@@ -7,8 +7,8 @@ use std::sync::{Arc, LazyLock};
 
 use crate::actions::visitors::{visit_deletion_vector_at, visit_protocol_at};
 use crate::actions::{
-    get_log_add_schema, Add, Cdc, Metadata, Protocol, Remove, ADD_NAME, CDC_NAME, METADATA_NAME,
-    PROTOCOL_NAME, REMOVE_NAME,
+    get_log_add_schema, Add, Cdc, Metadata, Protocol, Remove, ADD_NAME, CDC_NAME, COMMIT_INFO_NAME,
+    METADATA_NAME, PROTOCOL_NAME, REMOVE_NAME,
 };
 use crate::engine_data::{GetData, TypedGetData};
 use crate::expressions::{column_name, ColumnName};
@@ -97,11 +97,9 @@ pub(crate) fn table_changes_action_iter(
 ///
 /// See https://github.com/delta-io/delta/blob/master/PROTOCOL.md#deletion-vectors
 ///
-/// TODO: When the kernel supports in-commit timestamps, we will also have to inspect CommitInfo
-/// actions to find the timestamp. These are generated when incommit timestamps is enabled.
-/// This must be done in the first phase because the second phase lazily transforms engine data with
-/// an extra timestamp column. Thus, the timestamp must be known ahead of time.
-/// See https://github.com/delta-io/delta-kernel-rs/issues/559
+/// This phase also finds the in-commit timestamp of the `CommitInfo` action, if present. This must
+/// be done in the first phase because the second phase lazily transforms engine data with an extra
+/// timestamp column. Thus, the timestamp must be known ahead of time.
 ///
 /// 2. Scan file generation phase [`LogReplayScanner::into_scan_batches`]: This iterates over every
 ///    action in the commit, and generates [`TableChangesScanMetadata`]. It does so by transforming the
@@ -121,15 +119,9 @@ struct LogReplayScanner {
     remove_dvs: HashMap<String, DvInfo>,
     // The commit file that this replay scanner will operate on.
     commit_file: ParsedLogPath,
-    // The timestamp associated with this commit. This is the file modification time
-    // from the commit's [`FileMeta`].
-    //
-    //
-    // TODO when incommit timestamps are supported: If there is a [`CommitInfo`] with a timestamp
-    // generated by in-commit timestamps, that timestamp will be used instead.
-    //
-    // Note: This will be used once an expression is introduced to transform the engine data in
-    // [`TableChangesScanMetadata`]
+    // The timestamp associated with this commit. This is the in-commit timestamp of the commit's
+    // [`CommitInfo`] if in-commit timestamps are enabled, and the file modification time from the
+    // commit's [`FileMeta`] otherwise.
     timestamp: i64,
 }
 
@@ -167,6 +159,7 @@ impl LogReplayScanner {
         let mut remove_dvs = HashMap::default();
         let mut add_paths = HashSet::default();
         let mut has_cdc_action = false;
+        let mut in_commit_timestamp = None;
         for actions in action_iter {
             let actions = actions?;
 
@@ -174,6 +167,7 @@ impl LogReplayScanner {
                 add_paths: &mut add_paths,
                 remove_dvs: &mut remove_dvs,
                 has_cdc_action: &mut has_cdc_action,
+                in_commit_timestamp: &mut in_commit_timestamp,
                 protocol: None,
                 metadata_info: None,
             };
@@ -206,7 +200,7 @@ impl LogReplayScanner {
             remove_dvs.retain(|rm_path, _| add_paths.contains(rm_path));
         }
         Ok(LogReplayScanner {
-            timestamp: commit_file.location.last_modified,
+            timestamp: in_commit_timestamp.unwrap_or(commit_file.location.last_modified),
             commit_file,
             has_cdc_action,
             remove_dvs,
@@ -224,7 +218,6 @@ impl LogReplayScanner {
             has_cdc_action,
             remove_dvs,
             commit_file,
-            // TODO: Add the timestamp as a column with an expression
             timestamp,
         } = self;
         let remove_dvs = Arc::new(remove_dvs);
@@ -252,7 +245,7 @@ impl LogReplayScanner {
             // We start our selection vector based on what was filtered. We will add to this vector
             // below if a file has been removed. Note: None implies all files passed data skipping.
             let selection_vector = match &filter {
-                Some(filter) => filter.apply(actions.as_ref())?,
+                Some(filter) => filter.apply(actions.as_ref(), true)?,
                 None => vec![true; actions.len()],
             };
 
@@ -276,6 +269,7 @@ struct PreparePhaseVisitor<'a> {
     protocol: Option<Protocol>,
     metadata_info: Option<(String, HashMap<String, String>)>,
     has_cdc_action: &'a mut bool,
+    in_commit_timestamp: &'a mut Option<i64>,
     add_paths: &'a mut HashSet<String>,
     remove_dvs: &'a mut HashMap<String, DvInfo>,
 }
@@ -287,6 +281,15 @@ impl PreparePhaseVisitor<'_> {
             StructField::nullable(CDC_NAME, Cdc::to_schema()),
             StructField::nullable(METADATA_NAME, Metadata::to_schema()),
             StructField::nullable(PROTOCOL_NAME, Protocol::to_schema()),
+            // Only the in-commit timestamp is needed, and reading the full commit info would fail on
+            // commits whose (free-form) operation parameters are not all strings
+            StructField::nullable(
+                COMMIT_INFO_NAME,
+                StructType::new_unchecked([StructField::nullable(
+                    "inCommitTimestamp",
+                    DataType::LONG,
+                )]),
+            ),
         ]))
     }
 }
@@ -318,6 +321,7 @@ impl RowVisitor for PreparePhaseVisitor<'_> {
                 (INTEGER, column_name!("protocol.minWriterVersion")),
                 (string_list.clone(), column_name!("protocol.readerFeatures")),
                 (string_list, column_name!("protocol.writerFeatures")),
+                (LONG, column_name!("commitInfo.inCommitTimestamp")),
             ];
             let (types, names) = types_and_names.into_iter().unzip();
             (names, types).into()
@@ -327,7 +331,7 @@ impl RowVisitor for PreparePhaseVisitor<'_> {
 
     fn visit<'b>(&mut self, row_count: usize, getters: &[&'b dyn GetData<'b>]) -> DeltaResult<()> {
         require!(
-            getters.len() == 16,
+            getters.len() == 17,
             Error::InternalError(format!(
                 "Wrong number of PreparePhaseVisitor getters: {}",
                 getters.len()
@@ -352,8 +356,13 @@ impl RowVisitor for PreparePhaseVisitor<'_> {
                 let configuration_map_opt = getters[11].get_opt(i, "metadata.configuration")?;
                 let configuration = configuration_map_opt.unwrap_or_else(HashMap::new);
                 self.metadata_info = Some((schema.to_string(), configuration));
-            } else if let Some(protocol) = visit_protocol_at(i, &getters[12..])? {
+            } else if let Some(protocol) = visit_protocol_at(i, &getters[12..=15])? {
                 self.protocol = Some(protocol);
+            } else if let Some(timestamp) =
+                getters[16].get_opt(i, "commitInfo.inCommitTimestamp")?
+            {
+                // in-commit timestamps are only written while they are enabled
+                *self.in_commit_timestamp = Some(timestamp);
             }
         }
         Ok(())
@@ -37,6 +37,7 @@ use scan::TableChangesScanBuilder;
 use url::Url;
 
 use crate::actions::{ensure_supported_features, Protocol};
+use crate::history_manager::{timestamp_to_version, TimestampBound};
 use crate::log_segment::LogSegment;
 use crate::path::AsUrl;
 use crate::schema::{DataType, Schema, StructField, StructType};
@@ -52,7 +53,7 @@ mod resolve_dvs;
 pub mod scan;
 mod scan_file;
 
-static CHANGE_TYPE_COL_NAME: &str = "_change_type";
+pub(crate) static CHANGE_TYPE_COL_NAME: &str = "_change_type";
 static COMMIT_VERSION_COL_NAME: &str = "_commit_version";
 static COMMIT_TIMESTAMP_COL_NAME: &str = "_commit_timestamp";
 static ADD_CHANGE_TYPE: &str = "insert";
@@ -70,12 +71,10 @@ static CDF_FIELDS: LazyLock<[StructField; 3]> = LazyLock::new(|| {
 /// - `_change_type`: String representing the type of change that for that commit. This may be one
 ///   of `delete`, `insert`, `update_preimage`, or `update_postimage`.
 /// - `_commit_version`: Long representing the commit the change occurred in.
-/// - `_commit_timestamp`: Time at which the commit occurred. The timestamp is retrieved from the
-///   file modification time of the log file. No timezone is associated with the timestamp.
-///
-///   Currently, in-commit timestamps (ICT) is not supported. In the future when ICT is enabled, the
-///   timestamp will be retrieved from the `inCommitTimestamp` field of the CommitInfo` action.
-///   See issue [#559](https://github.com/delta-io/delta-kernel-rs/issues/559)
+/// - `_commit_timestamp`: Time at which the commit occurred. If in-commit timestamps (ICT) are
+///   enabled, the timestamp is retrieved from the `inCommitTimestamp` field of the `CommitInfo`
+///   action. Otherwise, it is the file modification time of the log file. No timezone is
+///   associated with the timestamp.
 ///   For details on In-Commit Timestamps, see the [Protocol](https://github.com/delta-io/delta/blob/master/PROTOCOL.md#in-commit-timestamps).
 ///
 ///
@@ -105,6 +104,8 @@ static CDF_FIELDS: LazyLock<[StructField; 3]> = LazyLock::new(|| {
 ///  let table_changes = TableChanges::try_new(url, engine.as_ref(), 0, Some(1))?;
 ///  # Ok::<(), Error>(())
 ///  ````
+///  The range can also be given by commit timestamps with [`TableChanges::try_new_with_timestamps`],
+///  and [`TableChanges::try_next`] gets the changes committed after a `TableChanges`.
 /// For more details, see the following sections of the protocol:
 /// - [Add CDC File](https://github.com/delta-io/delta/blob/master/PROTOCOL.md#add-cdc-file)
 /// - [Change Data Files](https://github.com/delta-io/delta/blob/master/PROTOCOL.md#change-data-files).
@@ -133,6 +134,12 @@ impl TableChanges {
     /// - `start_version`: The start version of the change data feed
     /// - `end_version`: The end version (inclusive) of the change data feed. If this is none, this
     ///   defaults to the newest table version.
+    ///
+    /// # Errors
+    /// Returns [`Error::ChangeDataFeedUnsupported`] with the start (end) version if change data
+    /// feed is not enabled at the start (end) version, e.g. because the range starts before change
+    /// data feed was enabled for the table. Versions in between are checked when the changes are
+    /// read, which fails with the first version that does not have change data feed enabled.
     pub fn try_new(
         table_root: Url,
         engine: &dyn Engine,
@@ -216,6 +223,79 @@ impl TableChanges {
         })
     }
 
+    /// Creates a new [`TableChanges`] instance for the versions committed in the given timestamp
+    /// range (in milliseconds since the Unix epoch). The commit timestamp of a version is its
+    /// in-commit timestamp if in-commit timestamps are enabled, and the modification time of its
+    /// commit file otherwise.
+    ///
+    /// The start timestamp is resolved to the first version committed at or after it, and the end
+    /// timestamp to the last version committed at or before it. If there is no end timestamp, the
+    /// range ends at the newest table version. This fails if the start timestamp is after the
+    /// newest commit, if the end timestamp is before the oldest commit in the log, or if no version
+    /// was committed in the range. Otherwise, this checks the same properties as
+    /// [`TableChanges::try_new`].
+    pub fn try_new_with_timestamps(
+        table_root: Url,
+        engine: &dyn Engine,
+        start_timestamp: i64,
+        end_timestamp: Option<i64>,
+    ) -> DeltaResult<Self> {
+        if let Some(end_timestamp) = end_timestamp {
+            require!(
+                start_timestamp <= end_timestamp,
+                Error::generic(format!(
+                    "Failed to build TableChanges: start timestamp {start_timestamp} is after end timestamp {end_timestamp}"
+                ))
+            );
+        }
+        let latest_snapshot = Snapshot::builder_for(table_root.clone()).build(engine)?;
+        let start_version = timestamp_to_version(
+            &latest_snapshot,
+            engine,
+            start_timestamp,
+            TimestampBound::AtOrAfter,
+        )?;
+        let end_version = match end_timestamp {
+            Some(end_timestamp) => timestamp_to_version(
+                &latest_snapshot,
+                engine,
+                end_timestamp,
+                TimestampBound::AtOrBefore,
+            )?,
+            None => latest_snapshot.version(),
+        };
+        require!(
+            start_version <= end_version,
+            Error::generic(format!(
+                "Failed to build TableChanges: no version was committed between timestamps {start_timestamp} and {}",
+                end_timestamp.unwrap_or(start_timestamp)
+            ))
+        );
+        Self::try_new(table_root, engine, start_version, Some(end_version))
+    }
+
+    /// Creates a [`TableChanges`] instance for the versions committed after the end version of
+    /// this one, up to the newest table version. Returns `None` if no newer version has been
+    /// committed yet.
+    ///
+    /// Repeatedly calling this on the returned `TableChanges` follows the table as it is being
+    /// written to, which is how a streaming change data feed source reads all changes since its
+    /// starting version. Like [`TableChanges::try_new`], this fails if change data feed is not
+    /// enabled at the start or end of the new range.
+    pub fn try_next(&self, engine: &dyn Engine) -> DeltaResult<Option<Self>> {
+        let latest_snapshot = Snapshot::builder_from(self.end_snapshot.clone()).build(engine)?;
+        if latest_snapshot.version() == self.end_version() {
+            return Ok(None);
+        }
+        Self::try_new(
+            self.table_root.clone(),
+            engine,
+            self.end_version() + 1,
+            Some(latest_snapshot.version()),
+        )
+        .map(Some)
+    }
+
     /// The start version of the `TableChanges`.
     pub fn start_version(&self) -> Version {
         self.start_version
@@ -270,8 +350,12 @@ fn check_cdf_table_properties(table_properties: &TableProperties) -> DeltaResult
 /// Ensures that Change Data Feed is supported for a table with this [`Protocol`] .
 /// See the documentation of [`TableChanges`] for more details.
 fn ensure_cdf_read_supported(protocol: &Protocol) -> DeltaResult<()> {
-    static CDF_SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> =
-        LazyLock::new(|| vec![ReaderFeature::DeletionVectors]);
+    static CDF_SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = LazyLock::new(|| {
+        vec![
+            ReaderFeature::DeletionVectors,
+            ReaderFeature::TimestampWithoutTimezone,
+        ]
+    });
     match &protocol.reader_features() {
         // if min_reader_version = 3 and all reader features are subset of supported => OK
         Some(reader_features) if protocol.min_reader_version() == 3 => {
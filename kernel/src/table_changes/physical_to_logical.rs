@@ -50,7 +50,7 @@ pub(crate) fn physical_to_logical_expr(
                     ));
                 };
                 let name = field.physical_name();
-                let raw_value = scan_file.partition_values.get(name);
+                let raw_value = scan_file.partition_values.get(name).map(String::as_str);
                 let value_expression =
                     crate::transforms::parse_partition_value_raw(raw_value, field.data_type())?;
                 Ok(value_expression.into())
@@ -60,6 +60,11 @@ pub(crate) fn physical_to_logical_expr(
                 let generated_column = cdf_columns.remove(field_name.as_str());
                 Ok(generated_column.unwrap_or_else(|| ColumnName::new([field_name]).into()))
             }
+            ColumnType::RowId { .. }
+            | ColumnType::RowCommitVersion { .. }
+            | ColumnType::Internal(_) => Err(Error::unsupported(
+                "Row tracking columns are not supported in change data feed scans",
+            )),
         })
         .map(|expr| expr.map(Arc::new))
         .try_collect()?;
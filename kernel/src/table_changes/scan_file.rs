@@ -167,7 +167,7 @@ impl<T> RowVisitor for CdfScanFileVisitor<'_, T> {
 
     fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
         static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> =
-            LazyLock::new(|| cdf_scan_row_schema().leaves(None));
+            LazyLock::new(|| cdf_scan_row_schema().column_names_and_types(None));
         NAMES_AND_TYPES.as_ref()
     }
 }
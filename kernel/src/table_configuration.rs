@@ -8,18 +8,22 @@
 //! [`TableProperties`].
 //!
 //! [`Schema`]: crate::schema::Schema
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
 use url::Url;
 
 use crate::actions::{ensure_supported_features, Metadata, Protocol};
+use crate::row_tracking::MaterializedRowTrackingColumns;
+use crate::scan::data_skipping::stats_schema;
 use crate::schema::variant_utils::validate_variant_type_feature_support;
-use crate::schema::{InvariantChecker, SchemaRef};
+use crate::schema::{ColumnName, DataType, SchemaRef, SchemaTransform, StructField};
 use crate::table_features::{
     column_mapping_mode, validate_schema_column_mapping, validate_timestamp_ntz_feature_support,
     ColumnMappingMode, ReaderFeature, WriterFeature,
 };
-use crate::table_properties::TableProperties;
+use crate::table_properties::{DataSkippingNumIndexedCols, TableProperties, UniversalFormat};
 use crate::{DeltaResult, Error, Version};
 use delta_kernel_derive::internal_api;
 
@@ -121,6 +125,25 @@ impl TableConfiguration {
         )
     }
 
+    /// This table configuration with the table properties in `configuration_updates` set, i.e. the
+    /// table properties a transaction updating them commits. Unlike [`Self::try_new_from`], this
+    /// keeps the schema and column mapping mode, and doesn't validate the configuration again.
+    pub(crate) fn with_configuration_updates(
+        &self,
+        configuration_updates: &HashMap<String, String>,
+    ) -> Self {
+        if configuration_updates.is_empty() {
+            return self.clone();
+        }
+        let mut metadata = self.metadata.clone();
+        metadata.configuration.extend(configuration_updates.clone());
+        Self {
+            table_properties: metadata.parse_table_properties(),
+            metadata,
+            ..self.clone()
+        }
+    }
+
     /// The [`Metadata`] for this table at this version.
     #[internal_api]
     pub(crate) fn metadata(&self) -> &Metadata {
@@ -169,16 +192,6 @@ impl TableConfiguration {
     pub(crate) fn ensure_write_supported(&self) -> DeltaResult<()> {
         self.protocol.ensure_write_supported()?;
 
-        // for now we don't allow invariants so although we support writer version 2 and the
-        // ColumnInvariant TableFeature we _must_ check here that they are not actually in use
-        if self.is_invariants_supported()
-            && InvariantChecker::has_invariants(self.schema().as_ref())
-        {
-            return Err(Error::unsupported(
-                "Column invariants are not yet supported",
-            ));
-        }
-
         // Fail if row tracking is both enabled and suspended
         if self.is_row_tracking_enabled() && self.is_row_tracking_suspended() {
             return Err(Error::unsupported(
@@ -189,14 +202,43 @@ impl TableConfiguration {
         Ok(())
     }
 
+    /// Returns `Ok` if the kernel supports running VACUUM on this table. VACUUM deletes files, so
+    /// the writer protocol must always be supported. Tables with the vacuumProtocolCheck feature
+    /// additionally require VACUUM to validate the reader protocol.
+    ///
+    /// See: <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#vacuum-protocol-check>
+    pub(crate) fn ensure_vacuum_supported(&self) -> DeltaResult<()> {
+        if self.is_vacuum_protocol_check_supported() {
+            self.protocol.ensure_read_supported()?;
+        }
+        self.protocol.ensure_write_supported()
+    }
+
+    /// Returns `true` if the table supports the vacuumProtocolCheck table feature. This requires
+    /// reader version 3, writer version 7, and the feature in both the reader and writer features.
+    pub(crate) fn is_vacuum_protocol_check_supported(&self) -> bool {
+        self.protocol.min_reader_version() == 3
+            && self.protocol.min_writer_version() == 7
+            && self
+                .protocol
+                .has_reader_feature(&ReaderFeature::VacuumProtocolCheck)
+            && self
+                .protocol
+                .has_writer_feature(&WriterFeature::VacuumProtocolCheck)
+    }
+
     /// Returns `true` if kernel supports reading Change Data Feed on this table.
     /// See the documentation of [`TableChanges`] for more details.
     ///
     /// [`TableChanges`]: crate::table_changes::TableChanges
     #[internal_api]
     pub(crate) fn is_cdf_read_supported(&self) -> bool {
-        static CDF_SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> =
-            LazyLock::new(|| vec![ReaderFeature::DeletionVectors]);
+        static CDF_SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = LazyLock::new(|| {
+            vec![
+                ReaderFeature::DeletionVectors,
+                ReaderFeature::TimestampWithoutTimezone,
+            ]
+        });
         let protocol_supported = match self.protocol.reader_features() {
             // if min_reader_version = 3 and all reader features are subset of supported => OK
             Some(reader_features) if self.protocol.min_reader_version() == 3 => {
@@ -264,7 +306,6 @@ impl TableConfiguration {
         }
     }
 
-    #[allow(unused)]
     pub(crate) fn is_append_only_enabled(&self) -> bool {
         self.is_append_only_supported() && self.table_properties.append_only.unwrap_or(false)
     }
@@ -278,6 +319,69 @@ impl TableConfiguration {
         }
     }
 
+    /// Returns `true` if the table supports the column defaults (allowColumnDefaults) table feature.
+    pub(crate) fn is_column_defaults_supported(&self) -> bool {
+        self.protocol.min_writer_version() == 7
+            && self
+                .protocol
+                .has_writer_feature(&WriterFeature::AllowColumnDefaults)
+    }
+
+    /// Returns `true` if the table supports the generated columns table feature.
+    pub(crate) fn is_generated_columns_supported(&self) -> bool {
+        let protocol = &self.protocol;
+        match protocol.min_writer_version() {
+            7 => protocol.has_writer_feature(&WriterFeature::GeneratedColumns),
+            version => (4..=6).contains(&version),
+        }
+    }
+
+    /// Returns `true` if the table supports the column mapping table feature (which doesn't imply
+    /// that column mapping is enabled, see [`Self::column_mapping_mode`]).
+    pub(crate) fn is_column_mapping_supported(&self) -> bool {
+        let protocol = &self.protocol;
+        match (protocol.min_reader_version(), protocol.min_writer_version()) {
+            (3, 7) => {
+                protocol.has_reader_feature(&ReaderFeature::ColumnMapping)
+                    && protocol.has_writer_feature(&WriterFeature::ColumnMapping)
+            }
+            (reader_version, writer_version) => reader_version >= 2 && writer_version >= 5,
+        }
+    }
+
+    /// Returns `true` if IcebergCompatV2 is enabled on this table, i.e. the table supports the
+    /// icebergCompatV2 writer feature and the `delta.enableIcebergCompatV2` table property is set
+    /// to `true`. Writes to such tables must then satisfy the IcebergCompatV2 constraints.
+    pub(crate) fn is_iceberg_compat_v2_enabled(&self) -> bool {
+        self.protocol.min_writer_version() == 7
+            && self
+                .protocol
+                .has_writer_feature(&WriterFeature::IcebergCompatV2)
+            && self
+                .table_properties
+                .enable_iceberg_compat_v2
+                .unwrap_or(false)
+    }
+
+    /// The formats (besides Delta) in which the table metadata is made available with UniForm,
+    /// as set by the `delta.universalFormat.enabledFormats` table property. Writes to such tables
+    /// must keep them compatible with these formats.
+    pub(crate) fn universal_formats(&self) -> &[UniversalFormat] {
+        self.table_properties
+            .universal_format_enabled_formats
+            .as_deref()
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the table supports the identity columns table feature.
+    pub(crate) fn is_identity_columns_supported(&self) -> bool {
+        let protocol = &self.protocol;
+        match protocol.min_writer_version() {
+            7 => protocol.has_writer_feature(&WriterFeature::IdentityColumns),
+            version => version == 6,
+        }
+    }
+
     /// Returns `true` if V2 checkpoint is supported on this table. To support V2 checkpoint,
     /// a table must support reader version 3, writer version 7, and the v2Checkpoint feature in
     /// both the protocol's readerFeatures and writerFeatures.
@@ -323,7 +427,6 @@ impl TableConfiguration {
     /// If in-commit timestamps is not supported, or not enabled, this returns `None`.
     /// If in-commit timestams is enabled, but the enablement version or timestamp is not present,
     /// this returns an error.
-    #[allow(unused)]
     pub(crate) fn in_commit_timestamp_enablement(&self) -> DeltaResult<Option<(Version, i64)>> {
         if !self.is_in_commit_timestamps_enabled() {
             return Ok(None);
@@ -397,6 +500,65 @@ impl TableConfiguration {
             .unwrap_or(false)
     }
 
+    /// Returns the names of the columns that materialize row IDs and row commit versions in the
+    /// data files of this table, which are needed to read row IDs and row commit versions.
+    ///
+    /// Fails if row tracking is not enabled (see [`Self::is_row_tracking_enabled`]), or if the
+    /// materialized column names are missing from the table properties.
+    pub(crate) fn materialized_row_tracking_columns(
+        &self,
+    ) -> DeltaResult<MaterializedRowTrackingColumns<'_>> {
+        if !self.is_row_tracking_enabled() {
+            return Err(Error::unsupported(
+                "Row IDs and row commit versions can only be read from tables with row tracking enabled",
+            ));
+        }
+        let properties = self.table_properties();
+        match (
+            &properties.materialized_row_id_column_name,
+            &properties.materialized_row_commit_version_column_name,
+        ) {
+            (Some(row_id), Some(row_commit_version)) => Ok(MaterializedRowTrackingColumns {
+                row_id,
+                row_commit_version,
+            }),
+            _ => Err(Error::generic(
+                "Row tracking is enabled, but the materialized row tracking column names are missing",
+            )),
+        }
+    }
+
+    /// The schema of the parsed file statistics (the `stats_parsed` field of add actions in
+    /// checkpoints) of this table, or `None` if statistics are not collected for any column. See
+    /// [`Self::stats_columns_schema`] for the columns statistics are collected for.
+    pub(crate) fn stats_parsed_schema(&self) -> Option<SchemaRef> {
+        stats_schema(self.stats_columns_schema()?.as_ref())
+    }
+
+    /// The physical schema of the columns file statistics are collected for, or `None` if
+    /// statistics are not collected for any column.
+    ///
+    /// Statistics cover the non-partition columns of the table (by physical name) that are
+    /// eligible for data skipping, limited to `delta.dataSkippingStatsColumns` if set, or else to
+    /// the first `delta.dataSkippingNumIndexedCols` (by default 32) leaf columns.
+    pub(crate) fn stats_columns_schema(&self) -> Option<SchemaRef> {
+        let properties = self.table_properties();
+        let mut filter = StatsColumnFilter {
+            partition_columns: self.metadata.partition_columns(),
+            stats_columns: properties.data_skipping_stats_columns.as_deref(),
+            num_indexed_cols: match properties.data_skipping_num_indexed_cols_or_default() {
+                DataSkippingNumIndexedCols::AllColumns => None,
+                DataSkippingNumIndexedCols::NumColumns(n) => Some(n),
+            },
+            path: vec![],
+        };
+        let stats_columns = filter
+            .transform_struct(&self.schema)?
+            .make_physical(self.column_mapping_mode);
+        let stats_columns = StripFieldMetadata.transform_struct(&stats_columns)?;
+        Some(Arc::new(stats_columns.into_owned()))
+    }
+
     /// Returns `true` if row tracking information should be written for this table.
     ///
     /// Row tracking information should be written when:
@@ -410,6 +572,64 @@ impl TableConfiguration {
     }
 }
 
+/// Filters a (logical) table schema down to the columns statistics are collected for.
+struct StatsColumnFilter<'a> {
+    partition_columns: &'a [String],
+    stats_columns: Option<&'a [ColumnName]>,
+    /// The number of leaf columns left to include, or `None` to include all of them
+    num_indexed_cols: Option<u64>,
+    path: Vec<String>,
+}
+
+impl<'a> SchemaTransform<'a> for StatsColumnFilter<'_> {
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        if self.path.is_empty() && self.partition_columns.contains(field.name()) {
+            return None;
+        }
+        self.path.push(field.name().clone());
+        let field = match field.data_type() {
+            DataType::Struct(_) => self.recurse_into_struct_field(field),
+            DataType::Primitive(_) => self.include_leaf().then_some(Cow::Borrowed(field)),
+            // min/max stats of nested collections and variants are not used for data skipping
+            DataType::Array(_) | DataType::Map(_) | DataType::Variant(_) => None,
+        };
+        self.path.pop();
+        field
+    }
+}
+
+impl StatsColumnFilter<'_> {
+    // Whether to include the leaf column at the current path
+    fn include_leaf(&mut self) -> bool {
+        if let Some(stats_columns) = self.stats_columns {
+            // a stats column may also name a struct, to include all of its leaf columns
+            return stats_columns
+                .iter()
+                .any(|column| self.path.starts_with(column.path()));
+        }
+        match &mut self.num_indexed_cols {
+            Some(0) => false,
+            Some(n) => {
+                *n -= 1;
+                true
+            }
+            None => true,
+        }
+    }
+}
+
+// Stats are keyed by physical column name, so they need no column mapping (or other) metadata
+struct StripFieldMetadata;
+impl<'a> SchemaTransform<'a> for StripFieldMetadata {
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        let field = self.recurse_into_struct_field(field)?.into_owned();
+        Some(Cow::Owned(StructField {
+            metadata: Default::default(),
+            ..field
+        }))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -417,6 +637,8 @@ mod test {
     use url::Url;
 
     use crate::actions::{Metadata, Protocol};
+    use crate::expressions::column_name;
+    use crate::schema::{DataType, StructType};
     use crate::table_features::{ReaderFeature, WriterFeature};
     use crate::table_properties::TableProperties;
     use crate::utils::test_utils::assert_result_error_with_message;
@@ -696,6 +918,73 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_vacuum_protocol_check() {
+        let metadata = Metadata {
+            schema_string: r#"{"type":"struct","fields":[{"name":"value","type":"integer","nullable":true,"metadata":{}}]}"#.to_string(),
+            ..Default::default()
+        };
+        let table_root = Url::try_from("file:///").unwrap();
+
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::VacuumProtocolCheck]),
+            Some([WriterFeature::VacuumProtocolCheck]),
+        )
+        .unwrap();
+        let table_config =
+            TableConfiguration::try_new(metadata.clone(), protocol, table_root.clone(), 0).unwrap();
+        assert!(table_config.is_vacuum_protocol_check_supported());
+        table_config.ensure_vacuum_supported().unwrap();
+
+        // VACUUM requires the writer protocol to be supported
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::VacuumProtocolCheck]),
+            Some([
+                WriterFeature::VacuumProtocolCheck,
+                WriterFeature::CheckConstraints,
+            ]),
+        )
+        .unwrap();
+        let table_config =
+            TableConfiguration::try_new(metadata.clone(), protocol, table_root.clone(), 0).unwrap();
+        assert_result_error_with_message(
+            table_config.ensure_vacuum_supported(),
+            "Unsupported: Unknown WriterFeatures: \"checkConstraints\"",
+        );
+
+        let protocol = Protocol::try_new(1, 2, None::<Vec<String>>, None::<Vec<String>>).unwrap();
+        let table_config = TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap();
+        assert!(!table_config.is_vacuum_protocol_check_supported());
+        table_config.ensure_vacuum_supported().unwrap();
+    }
+
+    #[test]
+    fn test_timestamp_ntz_cdf_read_supported() {
+        let schema_string = r#"{"type":"struct","fields":[{"name":"ts","type":"timestamp_ntz","nullable":true,"metadata":{}}]}"#.to_string();
+        let metadata = Metadata {
+            schema_string,
+            configuration: HashMap::from_iter([(
+                "delta.enableChangeDataFeed".to_string(),
+                "true".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::TimestampWithoutTimezone]),
+            Some([WriterFeature::TimestampWithoutTimezone]),
+        )
+        .unwrap();
+        let table_root = Url::try_from("file:///").unwrap();
+        let table_config = TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap();
+        assert!(table_config.is_cdf_read_supported());
+    }
+
     #[test]
     fn test_variant_validation_integration() {
         // Schema with VARIANT column
@@ -738,4 +1027,82 @@ mod test {
             "Should succeed when VARIANT is used with required features"
         );
     }
+
+    #[test]
+    fn test_stats_parsed_schema() {
+        let schema_string = r#"{"type":"struct","fields":[
+            {"name":"a","type":"integer","nullable":false,"metadata":{}},
+            {"name":"p","type":"string","nullable":true,"metadata":{}},
+            {"name":"s","type":{"type":"struct","fields":[
+                {"name":"x","type":"long","nullable":true,"metadata":{}},
+                {"name":"y","type":"string","nullable":true,"metadata":{}}
+            ]},"nullable":true,"metadata":{}},
+            {"name":"l","type":{"type":"array","elementType":"integer","containsNull":true},"nullable":true,"metadata":{}}
+        ]}"#;
+        let stats_schema = |configuration: &[(&str, &str)]| {
+            let metadata = Metadata {
+                configuration: configuration
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                schema_string: schema_string.to_string(),
+                partition_columns: vec!["p".to_string()],
+                ..Default::default()
+            };
+            let protocol = Protocol::try_new(
+                3,
+                7,
+                Some::<Vec<String>>(vec![]),
+                Some::<Vec<String>>(vec![]),
+            )
+            .unwrap();
+            let table_root = Url::try_from("file:///").unwrap();
+            let table_config =
+                TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap();
+            table_config.stats_parsed_schema()
+        };
+        let min_max_columns = |stats_schema: &StructType| {
+            let Some(DataType::Struct(min_values)) =
+                stats_schema.field("minValues").map(|f| f.data_type())
+            else {
+                panic!("minValues must be a struct");
+            };
+            min_values
+                .leaves()
+                .map(|(column, _, _)| column)
+                .collect::<Vec<_>>()
+        };
+
+        // by default, all (eligible, non-partition) leaf columns, with nullable stats
+        let schema = stats_schema(&[]).unwrap();
+        assert_eq!(
+            min_max_columns(&schema),
+            [column_name!("a"), column_name!("s.x"), column_name!("s.y")]
+        );
+        let Some(DataType::Struct(null_count)) = schema.field("nullCount").map(|f| f.data_type())
+        else {
+            panic!("nullCount must be a struct");
+        };
+        let a_null_count = null_count.field("a").unwrap();
+        assert!(a_null_count.is_nullable());
+        assert_eq!(a_null_count.data_type(), &DataType::LONG);
+
+        let schema = stats_schema(&[("delta.dataSkippingNumIndexedCols", "2")]).unwrap();
+        assert_eq!(
+            min_max_columns(&schema),
+            [column_name!("a"), column_name!("s.x")]
+        );
+
+        let schema = stats_schema(&[
+            ("delta.dataSkippingNumIndexedCols", "1"),
+            ("delta.dataSkippingStatsColumns", "s"),
+        ])
+        .unwrap();
+        assert_eq!(
+            min_max_columns(&schema),
+            [column_name!("s.x"), column_name!("s.y")]
+        );
+
+        assert!(stats_schema(&[("delta.dataSkippingNumIndexedCols", "0")]).is_none());
+    }
 }
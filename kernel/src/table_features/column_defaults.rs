@@ -0,0 +1,162 @@
+//! Support for the column defaults (`allowColumnDefaults`) writer feature.
+//!
+//! The default value of a column is defined by the SQL expression stored in its `CURRENT_DEFAULT`
+//! metadata. Writers use it for rows that don't specify a value for the column. Default
+//! expressions are constant, i.e. they cannot reference other columns.
+
+use std::sync::Arc;
+
+use crate::expressions::sql_parser::parse_expression;
+use crate::expressions::{Expression, ExpressionRef, Scalar};
+use crate::schema::{ColumnMetadataKey, DataType, MetadataValue, SchemaRef, StructType};
+use crate::{DeltaResult, Engine, EngineData, Error};
+
+/// A single (top-level) column with a default value, along with its parsed default expression.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ColumnDefault {
+    /// The name of the column
+    pub(crate) name: String,
+    /// The default expression's SQL, as stored in the column metadata
+    pub(crate) sql: String,
+    /// The parsed default expression
+    pub(crate) expression: ExpressionRef,
+}
+
+impl ColumnDefault {
+    fn try_new(name: String, data_type: &DataType, sql: String) -> DeltaResult<Self> {
+        // Default expressions are resolved against an empty schema, since they are constant
+        let (expression, result_type) =
+            parse_expression(&sql, &StructType::new_unchecked([]), Some(data_type)).map_err(
+                |e| {
+                    Error::unsupported(format!(
+                        "Cannot parse default expression `{sql}` of column {name}: {e}"
+                    ))
+                },
+            )?;
+        if result_type != *data_type {
+            return Err(Error::unsupported(format!(
+                "Default expression `{sql}` of column {name} produces {result_type}, but the column has type {data_type}"
+            )));
+        }
+        Ok(Self {
+            name,
+            sql,
+            expression: Arc::new(expression),
+        })
+    }
+}
+
+/// Extracts and parses the default expressions of all top-level columns in `schema` that have one.
+pub(crate) fn get_column_defaults(schema: &StructType) -> DeltaResult<Vec<ColumnDefault>> {
+    schema
+        .fields()
+        .filter_map(|field| {
+            let value = field.get_config_value(&ColumnMetadataKey::CurrentDefault)?;
+            let sql = match value {
+                MetadataValue::String(sql) => sql.clone(),
+                other => {
+                    return Some(Err(Error::generic(format!(
+                        "Invalid default expression for column {}: {other}",
+                        field.name()
+                    ))))
+                }
+            };
+            Some(ColumnDefault::try_new(
+                field.name().clone(),
+                field.data_type(),
+                sql,
+            ))
+        })
+        .collect()
+}
+
+/// Fills in the top-level columns of the logical table `schema` that are missing from `data`
+/// (whose schema is `data_schema`), using their default values if they have one and NULL
+/// otherwise. Returns an error if a missing column is non-nullable and has no default.
+pub(crate) fn fill_missing_columns(
+    engine: &dyn Engine,
+    schema: &SchemaRef,
+    column_defaults: &[ColumnDefault],
+    data_schema: &SchemaRef,
+    data: &dyn EngineData,
+) -> DeltaResult<Box<dyn EngineData>> {
+    let columns: Vec<_> = schema
+        .fields()
+        .map(|field| {
+            if data_schema.field(field.name()).is_some() {
+                return Ok(Expression::column([field.name()]));
+            }
+            let default = column_defaults
+                .iter()
+                .find(|column| column.name == *field.name());
+            match default {
+                Some(default) => Ok(default.expression.as_ref().clone()),
+                None if field.is_nullable() => {
+                    Ok(Expression::literal(Scalar::Null(field.data_type().clone())))
+                }
+                None => Err(Error::generic(format!(
+                    "Missing value for non-nullable column {} without a default value",
+                    field.name()
+                ))),
+            }
+        })
+        .collect::<DeltaResult<_>>()?;
+    let evaluator = engine.evaluation_handler().new_expression_evaluator(
+        data_schema.clone(),
+        Arc::new(Expression::struct_from(columns)),
+        schema.clone().into(),
+    );
+    evaluator.evaluate(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::StructField;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    fn default_field(name: &str, data_type: DataType, sql: &str) -> StructField {
+        StructField::nullable(name, data_type).with_metadata([(
+            ColumnMetadataKey::CurrentDefault.as_ref(),
+            MetadataValue::String(sql.to_string()),
+        )])
+    }
+
+    #[test]
+    fn test_get_column_defaults() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable("x", DataType::LONG),
+            default_field("y", DataType::LONG, "42"),
+            default_field("s", DataType::STRING, "'abc'"),
+        ]);
+        let column_defaults = get_column_defaults(&schema).unwrap();
+        assert_eq!(column_defaults.len(), 2);
+        assert_eq!(column_defaults[0].name, "y");
+        assert_eq!(column_defaults[0].sql, "42");
+        assert_eq!(
+            *column_defaults[0].expression,
+            Expression::literal(Scalar::Long(42))
+        );
+        assert_eq!(
+            *column_defaults[1].expression,
+            Expression::literal(Scalar::from("abc"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_column_defaults() {
+        // default expressions cannot reference other columns
+        let schema = StructType::new_unchecked([
+            StructField::nullable("x", DataType::LONG),
+            default_field("y", DataType::LONG, "x + 1"),
+        ]);
+        assert_result_error_with_message(
+            get_column_defaults(&schema),
+            "Cannot parse default expression `x + 1` of column y",
+        );
+
+        let schema =
+            StructType::new_unchecked([default_field("y", DataType::INTEGER, "'abc' + 1")]);
+        assert!(get_column_defaults(&schema).is_err());
+    }
+}
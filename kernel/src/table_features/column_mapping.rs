@@ -2,7 +2,8 @@
 use super::ReaderFeature;
 use crate::actions::Protocol;
 use crate::schema::{
-    ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField, StructType,
+    ColumnMetadataKey, ColumnName, DataType, MetadataValue, Schema, SchemaTransform, StructField,
+    StructType,
 };
 use crate::table_properties::TableProperties;
 use crate::{DeltaResult, Error};
@@ -58,6 +59,94 @@ pub fn validate_schema_column_mapping(schema: &Schema, mode: ColumnMappingMode)
     }
 }
 
+/// Assigns column mapping annotations (`delta.columnMapping.id` and
+/// `delta.columnMapping.physicalName`) to every (possibly nested) field of `schema` that lacks
+/// them, as needed for new fields of a table with column mapping, or when enabling column mapping
+/// on a table. Column IDs are assigned in schema order above `max_column_id` (and above any column
+/// ID already present in `schema`), and `max_column_id` is updated to the highest assigned ID.
+///
+/// New physical names are of the form `col-<uuid>`, unless `use_logical_names` is set, in which case
+/// each field keeps its logical name as physical name. The latter is needed when enabling column
+/// mapping on an existing table, whose data files still use the logical names.
+pub(crate) fn assign_column_mapping_metadata(
+    schema: &StructType,
+    max_column_id: &mut i64,
+    use_logical_names: bool,
+) -> StructType {
+    let mut max_existing_id = MaxColumnId(*max_column_id);
+    let _ = max_existing_id.transform_struct(schema);
+    let mut assigner = AssignColumnMappingMetadata {
+        max_column_id: max_existing_id.0,
+        use_logical_names,
+    };
+    let schema = match assigner.transform_struct(schema) {
+        Some(schema) => schema.into_owned(),
+        None => schema.clone(),
+    };
+    *max_column_id = assigner.max_column_id;
+    schema
+}
+
+/// Finds the highest `delta.columnMapping.id` among the (possibly nested) fields of a schema.
+struct MaxColumnId(i64);
+
+impl<'a> SchemaTransform<'a> for MaxColumnId {
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        if let Some(MetadataValue::Number(id)) =
+            field.get_config_value(&ColumnMetadataKey::ColumnMappingId)
+        {
+            self.0 = self.0.max(*id);
+        }
+        self.recurse_into_struct_field(field)
+    }
+    fn transform_variant(&mut self, stype: &'a StructType) -> Option<Cow<'a, StructType>> {
+        Some(Cow::Borrowed(stype))
+    }
+}
+
+struct AssignColumnMappingMetadata {
+    max_column_id: i64,
+    use_logical_names: bool,
+}
+
+impl<'a> SchemaTransform<'a> for AssignColumnMappingMetadata {
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        let id_key = ColumnMetadataKey::ColumnMappingId.as_ref();
+        let physical_name_key = ColumnMetadataKey::ColumnMappingPhysicalName.as_ref();
+        // assign the parent's ID before recursing, so that IDs follow the schema order
+        let id = (!field.metadata.contains_key(id_key)).then(|| {
+            self.max_column_id += 1;
+            self.max_column_id
+        });
+        let field = self.recurse_into_struct_field(field)?;
+        if id.is_none() && field.metadata.contains_key(physical_name_key) {
+            return Some(field);
+        }
+
+        let mut field = field.into_owned();
+        if let Some(id) = id {
+            field
+                .metadata
+                .insert(id_key.to_string(), MetadataValue::Number(id));
+        }
+        if !field.metadata.contains_key(physical_name_key) {
+            let physical_name = match self.use_logical_names {
+                true => field.name.clone(),
+                false => format!("col-{}", uuid::Uuid::new_v4()),
+            };
+            field.metadata.insert(
+                physical_name_key.to_string(),
+                MetadataValue::String(physical_name),
+            );
+        }
+        Some(Cow::Owned(field))
+    }
+    fn transform_variant(&mut self, stype: &'a StructType) -> Option<Cow<'a, StructType>> {
+        // variant fields don't carry column mapping annotations
+        Some(Cow::Borrowed(stype))
+    }
+}
+
 struct ValidateColumnMappings<'a> {
     mode: ColumnMappingMode,
     path: Vec<&'a str>,
@@ -340,6 +429,44 @@ mod tests {
             });
     }
 
+    #[test]
+    fn test_assign_column_mapping_metadata() {
+        let schema = create_schema(None, None, "4", "\"col-5f422f40\"");
+        let mut max_column_id = 2;
+        let assigned = assign_column_mapping_metadata(&schema, &mut max_column_id, false);
+        assert_eq!(max_column_id, 5);
+        validate_schema_column_mapping(&assigned, ColumnMappingMode::Name).unwrap();
+
+        // the existing annotations of the outer field are kept
+        let outer = assigned.field("e").unwrap();
+        assert_eq!(outer.metadata(), schema.field("e").unwrap().metadata());
+        let DataType::Array(array_type) = outer.data_type() else {
+            panic!("expected an array");
+        };
+        let DataType::Struct(inner) = array_type.element_type() else {
+            panic!("expected a struct");
+        };
+        let inner = inner.field("d").unwrap();
+        assert_eq!(
+            inner.get_config_value(&ColumnMetadataKey::ColumnMappingId),
+            Some(&MetadataValue::Number(5))
+        );
+        assert!(inner.physical_name().starts_with("col-"));
+
+        // when enabling column mapping, fields keep their logical names as physical names
+        let schema = create_schema(None, None, None, None);
+        let mut max_column_id = 0;
+        let assigned = assign_column_mapping_metadata(&schema, &mut max_column_id, true);
+        assert_eq!(max_column_id, 2);
+        validate_schema_column_mapping(&assigned, ColumnMappingMode::Id).unwrap();
+        let outer = assigned.field("e").unwrap();
+        assert_eq!(outer.physical_name(), "e");
+        assert_eq!(
+            outer.get_config_value(&ColumnMetadataKey::ColumnMappingId),
+            Some(&MetadataValue::Number(1))
+        );
+    }
+
     #[test]
     fn test_column_mapping_disabled() {
         let schema = create_schema(None, None, None, None);
@@ -0,0 +1,252 @@
+//! Support for the generated columns (`generatedColumns`) writer feature.
+//!
+//! The value of a generated column is defined by the SQL expression stored in its
+//! `delta.generationExpression` metadata, computed from other columns of the same row. Writers must
+//! ensure that every written value of a generated column equals the result of its generation
+//! expression (where NULL equals NULL).
+//!
+//! Generation expressions are parsed with the kernel's SQL parser, which supports only a subset of
+//! Spark SQL. A generated column whose expression cannot be parsed, or whose expression's type
+//! does not match the column's type, cannot be validated (or computed) by the kernel. Such columns
+//! don't make the table unwritable, but checking (or computing) generated columns of written data
+//! fails with an [`Error::Unsupported`] naming them, so that data is never committed unchecked.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use crate::actions::visitors::SelectionVectorVisitor;
+use crate::expressions::sql_parser::parse_expression;
+use crate::expressions::{
+    ColumnName, Expression, ExpressionRef, Predicate, PredicateRef, Transform,
+};
+use crate::schema::{ColumnMetadataKey, DataType, MetadataValue, SchemaRef, StructType};
+use crate::{DeltaResult, Engine, EngineData, Error, RowVisitor as _};
+
+/// A single (top-level) generated column along with its parsed generation expression.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GeneratedColumn {
+    /// The name of the generated column
+    pub(crate) name: String,
+    /// The generation expression's SQL, as stored in the column metadata
+    pub(crate) sql: String,
+    /// The parsed generation expression
+    pub(crate) expression: ExpressionRef,
+    /// A predicate that evaluates to `true` for every row whose value of the generated column does
+    /// not match the generation expression
+    mismatch: PredicateRef,
+}
+
+impl GeneratedColumn {
+    fn try_new(
+        name: String,
+        data_type: &DataType,
+        sql: String,
+        schema: &StructType,
+    ) -> DeltaResult<Self> {
+        let (expression, result_type) =
+            parse_expression(&sql, schema, Some(data_type)).map_err(|e| {
+                Error::unsupported(format!(
+                    "Cannot parse generation expression `{sql}` of column {name}: {e}"
+                ))
+            })?;
+        if result_type != *data_type {
+            return Err(Error::unsupported(format!(
+                "Generation expression `{sql}` of column {name} produces {result_type}, but the column has type {data_type}"
+            )));
+        }
+        let mismatch = Predicate::distinct(Expression::column([name.as_str()]), expression.clone());
+        Ok(Self {
+            name,
+            sql,
+            expression: Arc::new(expression),
+            mismatch: Arc::new(mismatch),
+        })
+    }
+}
+
+/// Extracts and parses the generation expressions of all generated columns in `schema`. Per the
+/// Delta protocol, only top-level columns can be generated columns. Returns the generated columns
+/// the kernel can validate, along with the names of those whose expressions it cannot handle (see
+/// the [module docs](self)).
+pub(crate) fn get_generated_columns(
+    schema: &StructType,
+) -> DeltaResult<(Vec<GeneratedColumn>, Vec<String>)> {
+    let mut generated_columns = vec![];
+    let mut unsupported_columns = vec![];
+    for field in schema.fields() {
+        let Some(value) = field.get_config_value(&ColumnMetadataKey::GenerationExpression) else {
+            continue;
+        };
+        let MetadataValue::String(sql) = value else {
+            return Err(Error::generic(format!(
+                "Invalid generation expression for column {}: {value}",
+                field.name()
+            )));
+        };
+        match GeneratedColumn::try_new(field.name().clone(), field.data_type(), sql.clone(), schema)
+        {
+            Ok(column) => generated_columns.push(column),
+            Err(_) => unsupported_columns.push(field.name().clone()),
+        }
+    }
+    Ok((generated_columns, unsupported_columns))
+}
+
+/// Returns an [`Error::Unsupported`] naming the `unsupported_columns`, if there are any, since
+/// written values of those generated columns cannot be validated (or computed).
+fn ensure_generated_columns_supported(unsupported_columns: &[String]) -> DeltaResult<()> {
+    if unsupported_columns.is_empty() {
+        return Ok(());
+    }
+    Err(Error::unsupported(format!(
+        "Cannot validate the generated columns {} of the table, whose generation expressions are not supported",
+        unsupported_columns.iter().join(", ")
+    )))
+}
+
+/// Checks that for every row of `data`, which must conform to the logical table `schema`, the
+/// values of all `generated_columns` match their generation expressions. Returns an error
+/// describing the first mismatch otherwise, or if there are any `unsupported_columns`.
+pub(crate) fn check_generated_columns(
+    engine: &dyn Engine,
+    schema: &SchemaRef,
+    generated_columns: &[GeneratedColumn],
+    unsupported_columns: &[String],
+    data: &dyn EngineData,
+) -> DeltaResult<()> {
+    ensure_generated_columns_supported(unsupported_columns)?;
+    let evaluation_handler = engine.evaluation_handler();
+    for column in generated_columns {
+        let evaluator =
+            evaluation_handler.new_predicate_evaluator(schema.clone(), column.mismatch.clone());
+        let mismatches = evaluator.evaluate(data)?;
+        let mut visitor = SelectionVectorVisitor::default();
+        visitor.visit_rows_of(mismatches.as_ref())?;
+        if let Some(row) = visitor
+            .selection_vector
+            .iter()
+            .position(|mismatch| *mismatch)
+        {
+            return Err(Error::generic(format!(
+                "Value of generated column {} in row {row} does not match its generation expression `{}`",
+                column.name, column.sql
+            ))
+            .with_column(ColumnName::new([column.name.as_str()])));
+        }
+    }
+    Ok(())
+}
+
+/// Computes the values of all `generated_columns` for `data`, which must conform to the logical
+/// table `schema`, replacing whatever values the generated columns had in `data`. Returns an error
+/// if there are any `unsupported_columns`.
+pub(crate) fn compute_generated_columns(
+    engine: &dyn Engine,
+    schema: &SchemaRef,
+    generated_columns: &[GeneratedColumn],
+    unsupported_columns: &[String],
+    data: &dyn EngineData,
+) -> DeltaResult<Box<dyn EngineData>> {
+    ensure_generated_columns_supported(unsupported_columns)?;
+    let transform =
+        generated_columns
+            .iter()
+            .fold(Transform::new_top_level(), |transform, column| {
+                transform.with_replaced_field(column.name.clone(), column.expression.clone())
+            });
+    let evaluator = engine.evaluation_handler().new_expression_evaluator(
+        schema.clone(),
+        Arc::new(Expression::transform(transform)),
+        schema.clone().into(),
+    );
+    evaluator.evaluate(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{column_expr, BinaryExpressionOp, Scalar};
+    use crate::schema::StructField;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    fn generated_field(name: &str, data_type: DataType, sql: &str) -> StructField {
+        StructField::nullable(name, data_type).with_metadata([(
+            ColumnMetadataKey::GenerationExpression.as_ref(),
+            MetadataValue::String(sql.to_string()),
+        )])
+    }
+
+    #[test]
+    fn test_get_generated_columns() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable("x", DataType::LONG),
+            generated_field("y", DataType::LONG, "x * 2"),
+        ]);
+        let (generated_columns, unsupported_columns) = get_generated_columns(&schema).unwrap();
+        assert_eq!(generated_columns.len(), 1);
+        assert!(unsupported_columns.is_empty());
+        let column = &generated_columns[0];
+        assert_eq!(column.name, "y");
+        assert_eq!(column.sql, "x * 2");
+        let expected = Expression::binary(
+            BinaryExpressionOp::Multiply,
+            column_expr!("x"),
+            Scalar::Long(2),
+        );
+        assert_eq!(*column.expression, expected);
+        assert_eq!(
+            *column.mismatch,
+            Predicate::distinct(column_expr!("y"), expected)
+        );
+    }
+
+    #[test]
+    fn test_cast_generated_column() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable("ts", DataType::TIMESTAMP),
+            generated_field("d", DataType::DATE, "CAST(ts AS DATE)"),
+        ]);
+        let (generated_columns, _) = get_generated_columns(&schema).unwrap();
+        assert_eq!(generated_columns.len(), 1);
+        assert_eq!(
+            *generated_columns[0].expression,
+            Expression::cast(column_expr!("ts"), DataType::DATE)
+        );
+    }
+
+    #[test]
+    fn test_invalid_generated_columns() {
+        let schema = StructType::new_unchecked([
+            StructField::nullable("ts", DataType::TIMESTAMP),
+            generated_field("d", DataType::DATE, "to_date(ts)"),
+            generated_field("y", DataType::INTEGER, "year(ts)"),
+        ]);
+        assert_result_error_with_message(
+            GeneratedColumn::try_new("d".into(), &DataType::DATE, "to_date(ts)".into(), &schema),
+            "Cannot parse generation expression `to_date(ts)` of column d",
+        );
+        // columns with unsupported expressions don't fail the whole table, but are reported
+        let (generated_columns, unsupported_columns) = get_generated_columns(&schema).unwrap();
+        assert_eq!(generated_columns, vec![]);
+        assert_eq!(unsupported_columns, vec!["d", "y"]);
+        assert_result_error_with_message(
+            ensure_generated_columns_supported(&unsupported_columns),
+            "Cannot validate the generated columns d, y of the table",
+        );
+
+        let schema = StructType::new_unchecked([
+            StructField::nullable("x", DataType::LONG),
+            generated_field("y", DataType::INTEGER, "x + 1"),
+            generated_field("z", DataType::LONG, "x + 1"),
+        ]);
+        assert_result_error_with_message(
+            GeneratedColumn::try_new("y".into(), &DataType::INTEGER, "x + 1".into(), &schema),
+            "Generation expression `x + 1` of column y produces long, but the column has type integer",
+        );
+        let (generated_columns, unsupported_columns) = get_generated_columns(&schema).unwrap();
+        assert_eq!(generated_columns.len(), 1);
+        assert_eq!(generated_columns[0].name, "z");
+        assert_eq!(unsupported_columns, vec!["y"]);
+    }
+}
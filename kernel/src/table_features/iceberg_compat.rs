@@ -0,0 +1,254 @@
+//! Validation for the IcebergCompatV2 (`icebergCompatV2`) writer feature.
+//!
+//! Tables with `delta.enableIcebergCompatV2` enabled must stay convertible to Iceberg (e.g. for
+//! UniForm), which imposes extra constraints on the table metadata and on every write. See
+//! <https://github.com/delta-io/delta/blob/master/PROTOCOL.md#iceberg-compatibility-v2>.
+
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+use crate::engine_data::{GetData, RowVisitor, TypedGetData as _};
+use crate::schema::{
+    ColumnMetadataKey, ColumnName, ColumnNamesAndTypes, DataType, SchemaTransform, StructField,
+    StructType,
+};
+use crate::table_configuration::TableConfiguration;
+use crate::table_features::ColumnMappingMode;
+use crate::utils::require;
+use crate::{DeltaResult, EngineData, Error};
+
+/// Validates that the table metadata is compliant with IcebergCompatV2, given the (possibly
+/// updated) table `schema` and `column_mapping_mode` of a transaction. Returns an error describing
+/// the first violation otherwise.
+pub(crate) fn validate_iceberg_compat_v2(
+    table_configuration: &TableConfiguration,
+    schema: &StructType,
+    column_mapping_mode: ColumnMappingMode,
+) -> DeltaResult<()> {
+    let table_properties = table_configuration.table_properties();
+    require!(
+        column_mapping_mode != ColumnMappingMode::None,
+        iceberg_compat_violation("column mapping must be enabled ('name' or 'id' mode)")
+    );
+    require!(
+        !table_properties.enable_iceberg_compat_v1.unwrap_or(false),
+        iceberg_compat_violation("IcebergCompatV1 must not be enabled at the same time")
+    );
+    require!(
+        !table_properties.enable_deletion_vectors.unwrap_or(false),
+        iceberg_compat_violation("deletion vectors must not be enabled")
+    );
+
+    let mut validator = ValidateIcebergCompatSchema {
+        path: vec![],
+        err: None,
+    };
+    let _ = validator.transform_struct(schema);
+    match validator.err {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Validates that every file in `add_files_metadata` (which must conform to
+/// [`add_files_schema`]) reports its number of records, which IcebergCompatV2 requires.
+///
+/// [`add_files_schema`]: crate::transaction::add_files_schema
+pub(crate) fn validate_iceberg_compat_v2_adds<'a>(
+    add_files_metadata: impl IntoIterator<Item = &'a dyn EngineData>,
+) -> DeltaResult<()> {
+    let mut visitor = NumRecordsVisitor;
+    for add_files_batch in add_files_metadata {
+        visitor.visit_rows_of(add_files_batch)?;
+    }
+    Ok(())
+}
+
+fn iceberg_compat_violation(reason: impl std::fmt::Display) -> Error {
+    Error::generic(format!("Table is not IcebergCompatV2 compliant: {reason}"))
+}
+
+/// Checks that the schema only uses types supported by IcebergCompatV2, and that every field
+/// containing arrays or maps carries the field IDs of their nested elements.
+struct ValidateIcebergCompatSchema<'a> {
+    path: Vec<&'a str>,
+    err: Option<Error>,
+}
+
+impl<'a> SchemaTransform<'a> for ValidateIcebergCompatSchema<'a> {
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        if self.err.is_none() {
+            self.path.push(&field.name);
+            let has_nested_collection = match field.data_type() {
+                DataType::Array(_) | DataType::Map(_) => true,
+                DataType::Primitive(_) | DataType::Struct(_) | DataType::Variant(_) => false,
+            };
+            if has_nested_collection
+                && field
+                    .get_config_value(&ColumnMetadataKey::ColumnMappingNestedIds)
+                    .is_none()
+            {
+                self.err = Some(iceberg_compat_violation(format!(
+                    "field '{}' lacks the {} annotation required for arrays and maps",
+                    ColumnName::new(self.path.iter().copied()),
+                    ColumnMetadataKey::ColumnMappingNestedIds.as_ref()
+                )));
+            }
+            let _ = self.recurse_into_struct_field(field);
+            self.path.pop();
+        }
+        None
+    }
+    fn transform_variant(&mut self, _: &'a StructType) -> Option<Cow<'a, StructType>> {
+        if self.err.is_none() {
+            self.err = Some(iceberg_compat_violation(format!(
+                "field '{}' has unsupported type variant",
+                ColumnName::new(self.path.iter().copied())
+            )));
+        }
+        None
+    }
+}
+
+/// A visitor over the engine-provided add files metadata that fails on the first file without a
+/// `numRecords` statistic.
+struct NumRecordsVisitor;
+
+impl RowVisitor for NumRecordsVisitor {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            (
+                vec![
+                    ColumnName::new(["path"]),
+                    ColumnName::new(["stats", "numRecords"]),
+                ],
+                vec![DataType::STRING, DataType::LONG],
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 2,
+            Error::InternalError(format!(
+                "Wrong number of NumRecordsVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            let num_records: Option<i64> = getters[1].get_opt(i, "stats.numRecords")?;
+            if num_records.is_none() {
+                let path: String = getters[0].get(i, "path")?;
+                return Err(iceberg_compat_violation(format!(
+                    "file {path} lacks the numRecords statistic"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::{Metadata, Protocol};
+    use crate::schema::{ArrayType, MetadataValue};
+    use crate::table_features::{ReaderFeature, WriterFeature};
+    use crate::utils::test_utils::assert_result_error_with_message;
+    use std::collections::HashMap;
+    use url::Url;
+
+    fn field(name: &str, data_type: impl Into<DataType>, id: i64) -> StructField {
+        StructField::nullable(name, data_type).with_metadata([
+            (
+                ColumnMetadataKey::ColumnMappingId.as_ref(),
+                MetadataValue::Number(id),
+            ),
+            (
+                ColumnMetadataKey::ColumnMappingPhysicalName.as_ref(),
+                MetadataValue::String(format!("col-{name}")),
+            ),
+        ])
+    }
+
+    fn table_configuration(schema: &StructType, properties: &[(&str, &str)]) -> TableConfiguration {
+        let configuration: HashMap<_, _> = properties
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let metadata =
+            Metadata::try_new(None, None, schema.clone(), vec![], 0, configuration).unwrap();
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::ColumnMapping]),
+            Some([WriterFeature::ColumnMapping, WriterFeature::IcebergCompatV2]),
+        )
+        .unwrap();
+        let table_root = Url::try_from("file:///").unwrap();
+        TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap()
+    }
+
+    #[test]
+    fn test_validate_iceberg_compat_v2() {
+        let schema = StructType::new_unchecked([field("x", DataType::LONG, 1)]);
+        let properties = [
+            ("delta.columnMapping.mode", "name"),
+            ("delta.enableIcebergCompatV2", "true"),
+        ];
+        let config = table_configuration(&schema, &properties);
+        validate_iceberg_compat_v2(&config, &schema, ColumnMappingMode::Name).unwrap();
+
+        assert_result_error_with_message(
+            validate_iceberg_compat_v2(&config, &schema, ColumnMappingMode::None),
+            "Table is not IcebergCompatV2 compliant: column mapping must be enabled",
+        );
+
+        let config = table_configuration(
+            &schema,
+            &[
+                ("delta.columnMapping.mode", "name"),
+                ("delta.enableIcebergCompatV2", "true"),
+                ("delta.enableDeletionVectors", "true"),
+            ],
+        );
+        assert_result_error_with_message(
+            validate_iceberg_compat_v2(&config, &schema, ColumnMappingMode::Name),
+            "deletion vectors must not be enabled",
+        );
+    }
+
+    #[test]
+    fn test_validate_iceberg_compat_v2_schema() {
+        let properties = [
+            ("delta.columnMapping.mode", "name"),
+            ("delta.enableIcebergCompatV2", "true"),
+        ];
+
+        let schema =
+            StructType::new_unchecked([field("arr", ArrayType::new(DataType::LONG, true), 1)]);
+        let config = table_configuration(&schema, &properties);
+        assert_result_error_with_message(
+            validate_iceberg_compat_v2(&config, &schema, ColumnMappingMode::Name),
+            "field 'arr' lacks the delta.columnMapping.nested.ids annotation",
+        );
+
+        let mut arr = field("arr", ArrayType::new(DataType::LONG, true), 1);
+        arr.metadata.insert(
+            ColumnMetadataKey::ColumnMappingNestedIds
+                .as_ref()
+                .to_string(),
+            MetadataValue::Other(serde_json::json!({"col-arr.element": 2})),
+        );
+        let schema = StructType::new_unchecked([arr]);
+        validate_iceberg_compat_v2(&config, &schema, ColumnMappingMode::Name).unwrap();
+
+        let schema = StructType::new_unchecked([field("v", DataType::unshredded_variant(), 1)]);
+        assert_result_error_with_message(
+            validate_iceberg_compat_v2(&config, &schema, ColumnMappingMode::Name),
+            "field 'v' has unsupported type variant",
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Support for the identity columns (`identityColumns`) writer feature.
+//!
+//! An identity column is a `LONG` column whose values are generated by the writer, starting at
+//! `delta.identity.start` in increments of `delta.identity.step`. The last value generated so far
+//! (i.e. the highest one for positive steps and the lowest one for negative steps) is tracked in
+//! the `delta.identity.highWaterMark` column metadata, which every commit that generates identity
+//! values must update with a new Metadata action.
+
+use crate::schema::{ColumnMetadataKey, DataType, MetadataValue, StructField, StructType};
+use crate::transaction::IdentityValueRange;
+use crate::{DeltaResult, Error};
+
+/// A single (top-level) identity column along with its current high water mark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IdentityColumn {
+    /// The name of the identity column
+    pub(crate) name: String,
+    /// The first value of the identity column
+    pub(crate) start: i64,
+    /// The (non-zero) increment between consecutive values of the identity column
+    pub(crate) step: i64,
+    /// The last value generated so far, if any
+    pub(crate) high_water_mark: Option<i64>,
+    /// Whether values were reserved (and hence the high water mark moved) in this transaction
+    updated: bool,
+}
+
+impl IdentityColumn {
+    fn try_new(field: &StructField) -> DeltaResult<Self> {
+        let name = field.name();
+        let get_long = |key: ColumnMetadataKey| match field.get_config_value(&key) {
+            None => Ok(None),
+            Some(MetadataValue::Number(value)) => Ok(Some(*value)),
+            Some(other) => Err(Error::generic(format!(
+                "Invalid {} for identity column {name}: {other}",
+                key.as_ref()
+            ))),
+        };
+        let missing = |key: ColumnMetadataKey| {
+            Error::generic(format!(
+                "Identity column {name} is missing {}",
+                key.as_ref()
+            ))
+        };
+
+        if field.data_type() != &DataType::LONG {
+            return Err(Error::generic(format!(
+                "Identity column {name} must have type long, but has type {}",
+                field.data_type()
+            )));
+        }
+        let start = get_long(ColumnMetadataKey::IdentityStart)?
+            .ok_or_else(|| missing(ColumnMetadataKey::IdentityStart))?;
+        let step = get_long(ColumnMetadataKey::IdentityStep)?
+            .ok_or_else(|| missing(ColumnMetadataKey::IdentityStep))?;
+        if step == 0 {
+            return Err(Error::generic(format!(
+                "Identity column {name} must have a non-zero step"
+            )));
+        }
+        let high_water_mark = get_long(ColumnMetadataKey::IdentityHighWaterMark)?;
+        Ok(Self {
+            name: name.clone(),
+            start,
+            step,
+            high_water_mark,
+            updated: false,
+        })
+    }
+
+    /// Reserves the next `num_values` values of this identity column and advances the high water
+    /// mark past them.
+    pub(crate) fn reserve(&mut self, num_values: u64) -> DeltaResult<IdentityValueRange> {
+        let overflow = || {
+            Error::generic(format!(
+                "Cannot reserve {num_values} values for identity column {}: value overflow",
+                self.name
+            ))
+        };
+        let first = match self.high_water_mark {
+            Some(high_water_mark) => high_water_mark
+                .checked_add(self.step)
+                .ok_or_else(overflow)?,
+            None => self.start,
+        };
+        if num_values > 0 {
+            // compute in i128 so that the multiplication can't overflow before the range check
+            let last = first as i128 + (num_values - 1) as i128 * self.step as i128;
+            let last = i64::try_from(last).map_err(|_| overflow())?;
+            self.high_water_mark = Some(last);
+            self.updated = true;
+        }
+        Ok(IdentityValueRange::new(first, self.step, num_values))
+    }
+}
+
+/// Extracts all identity columns of `schema`. Per the Delta protocol, only top-level columns can be
+/// identity columns.
+pub(crate) fn get_identity_columns(schema: &StructType) -> DeltaResult<Vec<IdentityColumn>> {
+    schema
+        .fields()
+        .filter(|field| {
+            field
+                .get_config_value(&ColumnMetadataKey::IdentityStart)
+                .is_some()
+        })
+        .map(IdentityColumn::try_new)
+        .collect()
+}
+
+/// Returns a copy of `schema` in which the `delta.identity.highWaterMark` metadata of every
+/// identity column updated in this transaction is set to its new high water mark, or `None` if no
+/// identity column was updated.
+pub(crate) fn update_high_water_marks(
+    schema: &StructType,
+    identity_columns: &[IdentityColumn],
+) -> Option<StructType> {
+    if !identity_columns.iter().any(|column| column.updated) {
+        return None;
+    }
+    let fields = schema.fields().map(|field| {
+        let mut field = field.clone();
+        let updated_column = identity_columns
+            .iter()
+            .find(|column| column.updated && column.name == field.name);
+        if let Some(high_water_mark) = updated_column.and_then(|column| column.high_water_mark) {
+            field.metadata.insert(
+                ColumnMetadataKey::IdentityHighWaterMark
+                    .as_ref()
+                    .to_string(),
+                MetadataValue::Number(high_water_mark),
+            );
+        }
+        field
+    });
+    Some(StructType::new_unchecked(fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    fn identity_field(
+        name: &str,
+        start: i64,
+        step: i64,
+        high_water_mark: Option<i64>,
+    ) -> StructField {
+        let mut metadata = vec![
+            (
+                ColumnMetadataKey::IdentityStart.as_ref(),
+                MetadataValue::Number(start),
+            ),
+            (
+                ColumnMetadataKey::IdentityStep.as_ref(),
+                MetadataValue::Number(step),
+            ),
+        ];
+        if let Some(high_water_mark) = high_water_mark {
+            metadata.push((
+                ColumnMetadataKey::IdentityHighWaterMark.as_ref(),
+                MetadataValue::Number(high_water_mark),
+            ));
+        }
+        StructField::not_null(name, DataType::LONG).with_metadata(metadata)
+    }
+
+    #[test]
+    fn test_get_identity_columns() {
+        let schema = StructType::new_unchecked([
+            identity_field("id", 1, 1, None),
+            StructField::nullable("value", DataType::STRING),
+            identity_field("down", -1, -2, Some(-7)),
+        ]);
+        let columns = get_identity_columns(&schema).unwrap();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].name, "id");
+        assert_eq!((columns[0].start, columns[0].step), (1, 1));
+        assert_eq!(columns[0].high_water_mark, None);
+        assert_eq!(columns[1].name, "down");
+        assert_eq!(columns[1].high_water_mark, Some(-7));
+    }
+
+    #[test]
+    fn test_invalid_identity_columns() {
+        let schema = StructType::new_unchecked([identity_field("id", 1, 0, None)]);
+        assert_result_error_with_message(
+            get_identity_columns(&schema),
+            "Identity column id must have a non-zero step",
+        );
+
+        let schema = StructType::new_unchecked([StructField::nullable("id", DataType::LONG)
+            .with_metadata([(
+                ColumnMetadataKey::IdentityStart.as_ref(),
+                MetadataValue::Number(1),
+            )])]);
+        assert_result_error_with_message(
+            get_identity_columns(&schema),
+            "Identity column id is missing delta.identity.step",
+        );
+
+        let mut field = identity_field("id", 1, 1, None);
+        field.data_type = DataType::INTEGER;
+        assert_result_error_with_message(
+            get_identity_columns(&StructType::new_unchecked([field])),
+            "Identity column id must have type long, but has type integer",
+        );
+    }
+
+    #[test]
+    fn test_reserve_identity_values() {
+        let schema = StructType::new_unchecked([
+            identity_field("id", 1, 1, None),
+            identity_field("down", -1, -2, Some(-7)),
+        ]);
+        let mut columns = get_identity_columns(&schema).unwrap();
+        assert!(update_high_water_marks(&schema, &columns).is_none());
+
+        // the first reservation starts at `start`, later ones continue after the high water mark
+        let range = columns[0].reserve(3).unwrap();
+        assert_eq!(range.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+        let range = columns[0].reserve(2).unwrap();
+        assert_eq!(range.iter().collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(columns[0].high_water_mark, Some(5));
+
+        // reserving no values leaves the column untouched
+        let range = columns[1].reserve(0).unwrap();
+        assert!(range.is_empty());
+        assert_eq!(columns[1].high_water_mark, Some(-7));
+
+        let updated = update_high_water_marks(&schema, &columns).unwrap();
+        assert_eq!(
+            updated
+                .field("id")
+                .unwrap()
+                .get_config_value(&ColumnMetadataKey::IdentityHighWaterMark),
+            Some(&MetadataValue::Number(5))
+        );
+        assert_eq!(updated.field("down"), schema.field("down"));
+
+        let range = columns[1].reserve(2).unwrap();
+        assert_eq!(range.iter().collect::<Vec<_>>(), vec![-9, -11]);
+        assert_eq!(range.last(), Some(-11));
+    }
+
+    #[test]
+    fn test_reserve_identity_values_overflow() {
+        let schema = StructType::new_unchecked([identity_field("id", 1, 10, Some(i64::MAX - 20))]);
+        let mut columns = get_identity_columns(&schema).unwrap();
+        assert_result_error_with_message(
+            columns[0].reserve(3),
+            "Cannot reserve 3 values for identity column id: value overflow",
+        );
+        assert_eq!(columns[0].high_water_mark, Some(i64::MAX - 20));
+        let range = columns[0].reserve(2).unwrap();
+        assert_eq!(range.last(), Some(i64::MAX));
+
+        // a range spanning all i64 values
+        let schema = StructType::new_unchecked([identity_field("id", i64::MIN, 1, None)]);
+        let mut columns = get_identity_columns(&schema).unwrap();
+        let range = columns[0].reserve(u64::MAX).unwrap();
+        assert_eq!(range.start(), i64::MIN);
+        assert_eq!(range.last(), Some(i64::MAX - 1));
+
+        // a range whose intermediate offsets overflow, although all of its values fit
+        let schema = StructType::new_unchecked([identity_field("id", i64::MIN, 1 << 62, None)]);
+        let mut columns = get_identity_columns(&schema).unwrap();
+        let range = columns[0].reserve(4).unwrap();
+        assert_eq!(
+            range.iter().collect::<Vec<_>>(),
+            vec![i64::MIN, -(1 << 62), 0, 1 << 62]
+        );
+        assert_eq!(range.last(), Some(1 << 62));
+    }
+}
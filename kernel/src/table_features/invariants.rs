@@ -0,0 +1,217 @@
+//! Support for legacy column invariants (writer version 2 / the `invariants` writer feature).
+//!
+//! Invariants are SQL boolean expressions stored in the `delta.invariants` metadata of a column,
+//! e.g. `{"expression": {"expression": "x > 3"}}`. Every row written to the table must satisfy
+//! every invariant, i.e. each expression must evaluate to `true` (and not `false` or `NULL`).
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::actions::visitors::SelectionVectorVisitor;
+use crate::expressions::sql_parser::parse_predicate;
+use crate::expressions::{ColumnName, Expression, Predicate, PredicateRef};
+use crate::schema::{
+    ColumnMetadataKey, DataType, InvariantChecker, MetadataValue, SchemaRef, StructType,
+};
+use crate::{DeltaResult, Engine, EngineData, Error, RowVisitor as _};
+
+/// A single parsed column invariant.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ColumnInvariant {
+    /// The (possibly nested) column the invariant is defined on
+    pub(crate) column: ColumnName,
+    /// The invariant's SQL expression, as stored in the column metadata
+    pub(crate) sql: String,
+    /// A predicate that evaluates to `true` for every row that violates the invariant
+    violation: PredicateRef,
+}
+
+impl ColumnInvariant {
+    fn try_new(column: ColumnName, sql: String, schema: &StructType) -> DeltaResult<Self> {
+        let predicate = parse_predicate(&sql, schema).map_err(|e| {
+            Error::unsupported(format!(
+                "Cannot parse invariant `{sql}` of column {column}: {e}"
+            ))
+        })?;
+        // A row violates the invariant if the predicate is FALSE or NULL, i.e. DISTINCT FROM TRUE
+        let violation =
+            Predicate::distinct(Expression::from_pred(predicate), Expression::literal(true));
+        Ok(Self {
+            column,
+            sql,
+            violation: Arc::new(violation),
+        })
+    }
+}
+
+/// The JSON layout of the `delta.invariants` column metadata value.
+#[derive(Deserialize)]
+struct InvariantMetadata {
+    expression: InvariantExpression,
+}
+
+#[derive(Deserialize)]
+struct InvariantExpression {
+    expression: String,
+}
+
+fn parse_invariant_metadata(column: &ColumnName, value: &MetadataValue) -> DeltaResult<String> {
+    let metadata: InvariantMetadata = match value {
+        MetadataValue::String(json) => serde_json::from_str(json)?,
+        MetadataValue::Other(json) => serde_json::from_value(json.clone())?,
+        other => {
+            return Err(Error::generic(format!(
+                "Invalid invariant metadata for column {column}: {other}"
+            )))
+        }
+    };
+    Ok(metadata.expression.expression)
+}
+
+/// Extracts and parses all column invariants defined in `schema`, including those of nested struct
+/// fields. Invariants on fields nested inside arrays or maps are not supported.
+pub(crate) fn get_column_invariants(schema: &StructType) -> DeltaResult<Vec<ColumnInvariant>> {
+    if !InvariantChecker::has_invariants(schema) {
+        return Ok(vec![]);
+    }
+    let mut invariants = vec![];
+    collect_invariants(schema, schema, &mut vec![], &mut invariants)?;
+    Ok(invariants)
+}
+
+fn collect_invariants(
+    schema: &StructType,
+    struct_type: &StructType,
+    path: &mut Vec<String>,
+    invariants: &mut Vec<ColumnInvariant>,
+) -> DeltaResult<()> {
+    for field in struct_type.fields() {
+        path.push(field.name().clone());
+        let column = ColumnName::new(path.iter());
+        if let Some(value) = field.get_config_value(&ColumnMetadataKey::Invariants) {
+            let sql = parse_invariant_metadata(&column, value)?;
+            invariants.push(ColumnInvariant::try_new(column.clone(), sql, schema)?);
+        }
+        match field.data_type() {
+            DataType::Struct(inner) => collect_invariants(schema, inner, path, invariants)?,
+            data_type @ (DataType::Array(_) | DataType::Map(_))
+                if InvariantChecker::data_type_has_invariants(data_type) =>
+            {
+                return Err(Error::unsupported(format!(
+                    "Invariants on fields nested in arrays or maps are not supported (column {column})"
+                )));
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+/// Checks that every row of `data`, which must conform to the logical table `schema`, satisfies
+/// all of the given `invariants`. Returns an error describing the first violation otherwise.
+pub(crate) fn check_invariants(
+    engine: &dyn Engine,
+    schema: &SchemaRef,
+    invariants: &[ColumnInvariant],
+    data: &dyn EngineData,
+) -> DeltaResult<()> {
+    let evaluation_handler = engine.evaluation_handler();
+    for invariant in invariants {
+        let evaluator =
+            evaluation_handler.new_predicate_evaluator(schema.clone(), invariant.violation.clone());
+        let violations = evaluator.evaluate(data)?;
+        let mut visitor = SelectionVectorVisitor::default();
+        visitor.visit_rows_of(violations.as_ref())?;
+        if let Some(row) = visitor
+            .selection_vector
+            .iter()
+            .position(|violated| *violated)
+        {
+            return Err(Error::invariant_violation(format!(
+                "Invariant `{}` on column {} violated by row {row}",
+                invariant.sql, invariant.column
+            ))
+            .with_column(invariant.column.clone()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{column_expr, column_name, Scalar};
+    use crate::schema::StructField;
+    use crate::utils::test_utils::assert_result_error_with_message;
+
+    fn field_with_invariant(name: &str, data_type: impl Into<DataType>, sql: &str) -> StructField {
+        StructField::nullable(name, data_type).with_metadata([(
+            ColumnMetadataKey::Invariants.as_ref(),
+            MetadataValue::String(format!(r#"{{"expression":{{"expression":"{sql}"}}}}"#)),
+        )])
+    }
+
+    #[test]
+    fn test_get_column_invariants() {
+        let schema = StructType::new_unchecked([
+            field_with_invariant("x", DataType::LONG, "x > 3"),
+            StructField::nullable("y", DataType::STRING),
+            StructField::nullable(
+                "s",
+                StructType::new_unchecked([field_with_invariant(
+                    "z",
+                    DataType::INTEGER,
+                    "s.z IS NOT NULL",
+                )]),
+            ),
+        ]);
+        let invariants = get_column_invariants(&schema).unwrap();
+        assert_eq!(invariants.len(), 2);
+
+        assert_eq!(invariants[0].column, column_name!("x"));
+        assert_eq!(invariants[0].sql, "x > 3");
+        assert_eq!(
+            *invariants[0].violation,
+            Predicate::distinct(
+                Expression::from_pred(Predicate::gt(column_expr!("x"), Scalar::Long(3))),
+                Expression::literal(true)
+            )
+        );
+
+        assert_eq!(invariants[1].column, column_name!("s.z"));
+        assert_eq!(invariants[1].sql, "s.z IS NOT NULL");
+    }
+
+    #[test]
+    fn test_no_invariants() {
+        let schema = StructType::new_unchecked([StructField::nullable("x", DataType::LONG)]);
+        assert!(get_column_invariants(&schema).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_invariants() {
+        // invariant referencing a missing column
+        let schema =
+            StructType::new_unchecked([field_with_invariant("x", DataType::LONG, "missing > 3")]);
+        assert_result_error_with_message(
+            get_column_invariants(&schema),
+            "Cannot parse invariant `missing > 3` of column x",
+        );
+
+        // invariant nested inside an array
+        let schema = StructType::new_unchecked([StructField::nullable(
+            "arr",
+            crate::schema::ArrayType::new(
+                StructType::new_unchecked([field_with_invariant("x", DataType::LONG, "x > 3")])
+                    .into(),
+                true,
+            ),
+        )]);
+        assert_result_error_with_message(
+            get_column_invariants(&schema),
+            "Invariants on fields nested in arrays or maps are not supported (column arr)",
+        );
+    }
+}
@@ -8,11 +8,27 @@ use crate::schema::derive_macro_utils::ToDataType;
 use crate::schema::DataType;
 use delta_kernel_derive::internal_api;
 
-pub(crate) use column_mapping::column_mapping_mode;
+pub(crate) use column_defaults::{fill_missing_columns, get_column_defaults, ColumnDefault};
+pub(crate) use column_mapping::{assign_column_mapping_metadata, column_mapping_mode};
 pub use column_mapping::{validate_schema_column_mapping, ColumnMappingMode};
+pub(crate) use generated_columns::{
+    check_generated_columns, compute_generated_columns, get_generated_columns, GeneratedColumn,
+};
+pub(crate) use iceberg_compat::{validate_iceberg_compat_v2, validate_iceberg_compat_v2_adds};
+pub(crate) use identity_columns::{get_identity_columns, update_high_water_marks, IdentityColumn};
+pub(crate) use invariants::{check_invariants, get_column_invariants, ColumnInvariant};
 pub(crate) use timestamp_ntz::validate_timestamp_ntz_feature_support;
+pub(crate) use type_widening::has_widened_fields;
+pub(crate) use universal_format::validate_universal_format;
+mod column_defaults;
 mod column_mapping;
+mod generated_columns;
+mod iceberg_compat;
+mod identity_columns;
+mod invariants;
 mod timestamp_ntz;
+mod type_widening;
+mod universal_format;
 
 /// Reader features communicate capabilities that must be implemented in order to correctly read a
 /// given table. That is, readers must implement and respect all features listed in a table's
@@ -43,6 +59,10 @@ pub(crate) enum ReaderFeature {
     #[strum(serialize = "catalogOwned-preview")]
     #[serde(rename = "catalogOwned-preview")]
     CatalogOwnedPreview,
+    /// Collation-aware comparisons of string columns
+    #[strum(serialize = "collations-preview")]
+    #[serde(rename = "collations-preview")]
+    CollationsPreview,
     /// Mapping of one column to another
     ColumnMapping,
     /// Deletion vectors for merge, update, delete
@@ -104,12 +124,18 @@ pub(crate) enum WriterFeature {
     CatalogOwnedPreview,
     /// Append Only Tables
     AppendOnly,
+    /// Columns with default values
+    AllowColumnDefaults,
     /// Table invariants
     Invariants,
     /// Check constraints on columns
     CheckConstraints,
     /// CDF on a table
     ChangeDataFeed,
+    /// Collation-aware comparisons of string columns
+    #[strum(serialize = "collations-preview")]
+    #[serde(rename = "collations-preview")]
+    CollationsPreview,
     /// Columns with generated values
     GeneratedColumns,
     /// Mapping of one column to another
@@ -184,6 +210,68 @@ impl From<WriterFeature> for Scalar {
     }
 }
 
+/// A table feature, identified by its [`WriterFeature`] (since every table feature is a writer
+/// feature). Reader-writer features must additionally be listed as reader features, see
+/// [`TableFeature::reader_feature`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[internal_api]
+pub(crate) struct TableFeature(WriterFeature);
+
+impl TableFeature {
+    /// The writer feature of this table feature.
+    pub(crate) fn writer_feature(&self) -> &WriterFeature {
+        &self.0
+    }
+
+    /// The reader feature of this table feature if it is a reader-writer feature, or `None` if it
+    /// is a writer-only feature.
+    pub(crate) fn reader_feature(&self) -> Option<ReaderFeature> {
+        match self.0 {
+            WriterFeature::CatalogManaged => Some(ReaderFeature::CatalogManaged),
+            WriterFeature::CatalogOwnedPreview => Some(ReaderFeature::CatalogOwnedPreview),
+            WriterFeature::CollationsPreview => Some(ReaderFeature::CollationsPreview),
+            WriterFeature::ColumnMapping => Some(ReaderFeature::ColumnMapping),
+            WriterFeature::DeletionVectors => Some(ReaderFeature::DeletionVectors),
+            WriterFeature::TimestampWithoutTimezone => {
+                Some(ReaderFeature::TimestampWithoutTimezone)
+            }
+            WriterFeature::TypeWidening => Some(ReaderFeature::TypeWidening),
+            WriterFeature::TypeWideningPreview => Some(ReaderFeature::TypeWideningPreview),
+            WriterFeature::V2Checkpoint => Some(ReaderFeature::V2Checkpoint),
+            WriterFeature::VacuumProtocolCheck => Some(ReaderFeature::VacuumProtocolCheck),
+            WriterFeature::VariantType => Some(ReaderFeature::VariantType),
+            WriterFeature::VariantTypePreview => Some(ReaderFeature::VariantTypePreview),
+            WriterFeature::VariantShreddingPreview => Some(ReaderFeature::VariantShreddingPreview),
+            _ => None,
+        }
+    }
+
+    /// The other table features that a table must support in order to support this feature.
+    pub(crate) fn required_features(&self) -> Vec<TableFeature> {
+        match self.0 {
+            WriterFeature::RowTracking | WriterFeature::ClusteredTable => {
+                vec![WriterFeature::DomainMetadata.into()]
+            }
+            WriterFeature::IcebergCompatV1 | WriterFeature::IcebergCompatV2 => {
+                vec![WriterFeature::ColumnMapping.into()]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl From<WriterFeature> for TableFeature {
+    fn from(feature: WriterFeature) -> Self {
+        TableFeature(feature)
+    }
+}
+
+impl std::fmt::Display for TableFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[cfg(test)] // currently only used in tests
 impl ReaderFeature {
     pub(crate) fn unknown(s: impl ToString) -> Self {
@@ -204,6 +292,9 @@ pub(crate) static SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = Lazy
         ReaderFeature::CatalogManaged,
         #[cfg(feature = "catalog-managed")]
         ReaderFeature::CatalogOwnedPreview,
+        // Kernel doesn't use the min/max stats of collated string columns for data skipping, since
+        // they aren't ordered by the column's collation.
+        ReaderFeature::CollationsPreview,
         ReaderFeature::ColumnMapping,
         ReaderFeature::DeletionVectors,
         ReaderFeature::TimestampWithoutTimezone,
@@ -213,29 +304,74 @@ pub(crate) static SUPPORTED_READER_FEATURES: LazyLock<Vec<ReaderFeature>> = Lazy
         ReaderFeature::V2Checkpoint,
         ReaderFeature::VariantType,
         ReaderFeature::VariantTypePreview,
-        // The default engine currently DOES NOT support shredded Variant reads and the parquet
-        // reader will reject the read if it sees a shredded schema in the parquet file. That being
-        // said, kernel does permit reconstructing shredded variants into the
-        // `STRUCT<metadata: BINARY, value: BINARY>` representation if parquet readers of
-        // third-party engines support it.
+        // Kernel reads shredded Variants in their unshredded `STRUCT<metadata: BINARY, value:
+        // BINARY>` representation. The default parquet reader reconstructs this representation
+        // from the shredded columns, and parquet readers of third-party engines must do the same.
         ReaderFeature::VariantShreddingPreview,
     ]
 });
 
 /// The writer features have the following limitations:
-/// - We 'support' Invariants only insofar as we check that they are not present.
-/// - We support writing to tables that have Invariants enabled but not used.
-/// - We only support DeletionVectors in that we never write them (no DML).
-/// - We support writing to existing tables with row tracking, but we don't support creating
-///   tables with row tracking yet.
+/// - We support AllowColumnDefaults by filling in the default values of columns missing from
+///   written data (see [`WriteContext::fill_missing_columns`]).
+/// - We support Invariants by validating written data against them (see
+///   [`WriteContext::check_invariants`]). The kernel never sees written data, so engines that
+///   write data files themselves must call this check; the default engine does so automatically.
+/// - We support GeneratedColumns by validating (or computing) generated column values of written
+///   data (see [`WriteContext::check_generated_columns`]). Validating generated columns whose
+///   expressions the kernel cannot parse fails, so writing to such tables requires the engine to
+///   validate them itself (see [`WriteContext::unsupported_generated_columns`]).
+/// - We support IdentityColumns by letting engines reserve identity values and updating the high
+///   water marks in the commit (see [`Transaction::reserve_identity_values`]). Explicitly inserted
+///   identity values are not tracked.
+/// - We support ColumnMapping by writing data files with physical column names (and field IDs in
+///   `id` mode), see [`WriteContext::physical_schema`]. Column mapping can be enabled on a table
+///   with [`Transaction::with_column_mapping_enabled`].
+/// - We support IcebergCompatV2 by validating the table metadata and the written files against its
+///   constraints on commit.
+/// - We support DeletionVectors by letting engines delete rows of existing files: the engine
+///   serializes a deletion vector with [`DeletionVectorWriter`] (stored inline in the log if small,
+///   in a file otherwise), then removes the file and adds it back with the new deletion vector
+///   (see [`Transaction::remove_files`]). Deletion vectors can be enabled on a table with
+///   [`Transaction::with_deletion_vectors_enabled`].
+/// - We support writing to existing tables with row tracking, and adding row tracking support to a
+///   table (see [`Transaction::with_row_tracking_supported`]), but we don't enable row tracking
+///   on tables, which would require assigning row IDs to their existing files.
+/// - We support InCommitTimestamp by writing a monotonically increasing in-commit timestamp in the
+///   commit info of every commit to tables with in-commit timestamps enabled.
+/// - We support VacuumProtocolCheck by validating both the reader and writer protocol before
+///   VACUUM (see [`crate::vacuum`]). Other writes are unaffected by it.
+/// - We support VariantType by validating that written Variant values use the well-formed
+///   unshredded `STRUCT<metadata: BINARY, value: BINARY>` representation. We never write shredded
+///   Variants, which is permitted on tables with VariantShreddingPreview.
+///
+/// [`WriteContext::fill_missing_columns`]: crate::transaction::WriteContext::fill_missing_columns
+/// [`WriteContext::check_invariants`]: crate::transaction::WriteContext::check_invariants
+/// [`WriteContext::check_generated_columns`]: crate::transaction::WriteContext::check_generated_columns
+/// [`WriteContext::unsupported_generated_columns`]: crate::transaction::WriteContext::unsupported_generated_columns
+/// [`Transaction::reserve_identity_values`]: crate::transaction::Transaction::reserve_identity_values
+/// [`WriteContext::physical_schema`]: crate::transaction::WriteContext::physical_schema
+/// [`Transaction::with_column_mapping_enabled`]: crate::transaction::Transaction::with_column_mapping_enabled
+/// [`DeletionVectorWriter`]: crate::actions::deletion_vector::DeletionVectorWriter
+/// [`Transaction::remove_files`]: crate::transaction::Transaction::remove_files
+/// [`Transaction::with_deletion_vectors_enabled`]: crate::transaction::Transaction::with_deletion_vectors_enabled
+/// [`Transaction::with_row_tracking_supported`]: crate::transaction::Transaction::with_row_tracking_supported
 pub(crate) static SUPPORTED_WRITER_FEATURES: LazyLock<Vec<WriterFeature>> = LazyLock::new(|| {
     vec![
+        WriterFeature::AllowColumnDefaults,
         WriterFeature::AppendOnly,
+        WriterFeature::ChangeDataFeed,
+        WriterFeature::ColumnMapping,
         WriterFeature::DeletionVectors,
         WriterFeature::DomainMetadata,
+        WriterFeature::GeneratedColumns,
+        WriterFeature::IcebergCompatV2,
+        WriterFeature::IdentityColumns,
+        WriterFeature::InCommitTimestamp,
         WriterFeature::Invariants,
         WriterFeature::RowTracking,
         WriterFeature::TimestampWithoutTimezone,
+        WriterFeature::VacuumProtocolCheck,
         WriterFeature::VariantType,
         WriterFeature::VariantTypePreview,
         WriterFeature::VariantShreddingPreview,
@@ -285,6 +421,7 @@ mod tests {
         let cases = [
             (ReaderFeature::CatalogManaged, "catalogManaged"),
             (ReaderFeature::CatalogOwnedPreview, "catalogOwned-preview"),
+            (ReaderFeature::CollationsPreview, "collations-preview"),
             (ReaderFeature::ColumnMapping, "columnMapping"),
             (ReaderFeature::DeletionVectors, "deletionVectors"),
             (ReaderFeature::TimestampWithoutTimezone, "timestampNtz"),
@@ -320,11 +457,13 @@ mod tests {
     fn test_roundtrip_writer_features() {
         let cases = [
             (WriterFeature::AppendOnly, "appendOnly"),
+            (WriterFeature::AllowColumnDefaults, "allowColumnDefaults"),
             (WriterFeature::CatalogManaged, "catalogManaged"),
             (WriterFeature::CatalogOwnedPreview, "catalogOwned-preview"),
             (WriterFeature::Invariants, "invariants"),
             (WriterFeature::CheckConstraints, "checkConstraints"),
             (WriterFeature::ChangeDataFeed, "changeDataFeed"),
+            (WriterFeature::CollationsPreview, "collations-preview"),
             (WriterFeature::GeneratedColumns, "generatedColumns"),
             (WriterFeature::ColumnMapping, "columnMapping"),
             (WriterFeature::IdentityColumns, "identityColumns"),
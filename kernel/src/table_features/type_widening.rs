@@ -0,0 +1,65 @@
+//! Support for reading tables with the `typeWidening` feature.
+//!
+//! Type widening changes the type of a column to a wider type (e.g. `int` to `long`) without
+//! rewriting existing data files, so older files store the column with its narrower type. Each
+//! widened field records its history of type changes in the `delta.typeChanges` field metadata.
+//! Readers upcast the narrower types of older files to the current type of the column.
+
+use std::borrow::Cow;
+
+use crate::schema::{ColumnMetadataKey, Schema, SchemaTransform, StructField};
+
+/// Returns true if the type of any field in `schema` (including nested fields) was widened, i.e.
+/// if data files may store some columns of the schema with narrower types.
+pub(crate) fn has_widened_fields(schema: &Schema) -> bool {
+    let mut has_type_changes = HasTypeChanges(false);
+    let _ = has_type_changes.transform_struct(schema);
+    has_type_changes.0
+}
+
+/// Schema visitor that checks if any field in the schema has a history of type changes
+struct HasTypeChanges(bool);
+
+impl<'a> SchemaTransform<'a> for HasTypeChanges {
+    fn transform_struct_field(&mut self, field: &'a StructField) -> Option<Cow<'a, StructField>> {
+        if field
+            .get_config_value(&ColumnMetadataKey::TypeChanges)
+            .is_some()
+        {
+            self.0 = true;
+        }
+        self.recurse_into_struct_field(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{ArrayType, DataType, MetadataValue, StructType};
+
+    #[test]
+    fn test_has_widened_fields() {
+        let type_changes = MetadataValue::Other(serde_json::json!([
+            {"fromType": "integer", "toType": "long", "tableVersion": 2}
+        ]));
+        let widened = StructField::nullable("widened", DataType::LONG)
+            .with_metadata([(ColumnMetadataKey::TypeChanges.as_ref(), type_changes)]);
+
+        let schema = StructType::new_unchecked([StructField::nullable("id", DataType::LONG)]);
+        assert!(!has_widened_fields(&schema));
+
+        let schema = StructType::new_unchecked([
+            StructField::nullable("id", DataType::LONG),
+            widened.clone(),
+        ]);
+        assert!(has_widened_fields(&schema));
+
+        // widened fields nested in arrays of structs are found too
+        let nested = StructType::new_unchecked([widened]);
+        let schema = StructType::new_unchecked([StructField::nullable(
+            "nested",
+            ArrayType::new(nested.into(), true),
+        )]);
+        assert!(has_widened_fields(&schema));
+    }
+}
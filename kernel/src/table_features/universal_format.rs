@@ -0,0 +1,105 @@
+//! Validation for UniForm (universal format) tables.
+//!
+//! The metadata of tables with `delta.universalFormat.enabledFormats` set is also made available
+//! in other formats (e.g. Iceberg), by converting the Delta log after each commit. Writers don't
+//! perform this conversion, but must keep the table convertible to the enabled formats.
+
+use crate::table_configuration::TableConfiguration;
+use crate::table_properties::UniversalFormat;
+use crate::utils::require;
+use crate::{DeltaResult, Error};
+
+/// Validates that the table satisfies the requirements of every UniForm format enabled on it.
+/// Returns an error explaining the first unsatisfied requirement otherwise.
+pub(crate) fn validate_universal_format(
+    table_configuration: &TableConfiguration,
+) -> DeltaResult<()> {
+    for format in table_configuration.universal_formats() {
+        match format {
+            UniversalFormat::Iceberg => require!(
+                table_configuration.is_iceberg_compat_v2_enabled(),
+                universal_format_violation(
+                    *format,
+                    "IcebergCompatV2 must be enabled (i.e. the 'icebergCompatV2' writer feature \
+                     and the 'delta.enableIcebergCompatV2' table property). Writing to \
+                     IcebergCompatV1 tables is not supported"
+                )
+            ),
+            UniversalFormat::Hudi => require!(
+                !table_configuration
+                    .table_properties()
+                    .enable_deletion_vectors
+                    .unwrap_or(false),
+                universal_format_violation(*format, "deletion vectors must not be enabled")
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn universal_format_violation(format: UniversalFormat, reason: impl std::fmt::Display) -> Error {
+    Error::generic(format!(
+        "Table has UniForm {format} enabled ('delta.universalFormat.enabledFormats'), but is not compatible with it: {reason}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::{Metadata, Protocol};
+    use crate::schema::{DataType, StructField, StructType};
+    use crate::table_features::{ReaderFeature, WriterFeature};
+    use crate::utils::test_utils::assert_result_error_with_message;
+    use std::collections::HashMap;
+    use url::Url;
+
+    fn table_configuration(properties: &[(&str, &str)]) -> TableConfiguration {
+        let schema = StructType::new_unchecked([StructField::nullable("x", DataType::LONG)]);
+        let configuration: HashMap<_, _> = properties
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let metadata = Metadata::try_new(None, None, schema, vec![], 0, configuration).unwrap();
+        let protocol = Protocol::try_new(
+            3,
+            7,
+            Some([ReaderFeature::ColumnMapping]),
+            Some([WriterFeature::ColumnMapping, WriterFeature::IcebergCompatV2]),
+        )
+        .unwrap();
+        let table_root = Url::try_from("file:///").unwrap();
+        TableConfiguration::try_new(metadata, protocol, table_root, 0).unwrap()
+    }
+
+    #[test]
+    fn test_validate_universal_format() {
+        // no UniForm formats enabled
+        validate_universal_format(&table_configuration(&[])).unwrap();
+        validate_universal_format(&table_configuration(&[(
+            "delta.universalFormat.enabledFormats",
+            "",
+        )]))
+        .unwrap();
+
+        let config = table_configuration(&[
+            ("delta.universalFormat.enabledFormats", "iceberg"),
+            ("delta.enableIcebergCompatV2", "true"),
+        ]);
+        validate_universal_format(&config).unwrap();
+
+        let config = table_configuration(&[("delta.universalFormat.enabledFormats", "iceberg")]);
+        assert_result_error_with_message(
+            validate_universal_format(&config),
+            "Table has UniForm iceberg enabled ('delta.universalFormat.enabledFormats'), but is not compatible with it: IcebergCompatV2 must be enabled",
+        );
+
+        let config = table_configuration(&[
+            ("delta.universalFormat.enabledFormats", "hudi"),
+            ("delta.enableDeletionVectors", "true"),
+        ]);
+        assert_result_error_with_message(
+            validate_universal_format(&config),
+            "Table has UniForm hudi enabled ('delta.universalFormat.enabledFormats'), but is not compatible with it: deletion vectors must not be enabled",
+        );
+    }
+}
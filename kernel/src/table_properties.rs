@@ -18,7 +18,7 @@ use crate::expressions::ColumnName;
 use crate::table_features::ColumnMappingMode;
 use crate::{Error, Version};
 
-use strum::EnumString;
+use strum::{Display, EnumString};
 
 mod deserialize;
 pub use deserialize::ParseIntervalError;
@@ -57,6 +57,10 @@ pub struct TableProperties {
     /// Parquet columns that use different names.
     pub column_mapping_mode: Option<ColumnMappingMode>,
 
+    /// The highest column ID assigned to any (possibly nested) field of the table schema so far,
+    /// when column mapping is enabled. New fields must be assigned IDs above this value.
+    pub column_mapping_max_column_id: Option<u64>,
+
     /// The number of columns for Delta Lake to collect statistics about for data skipping.
     /// A value of -1 means to collect statistics for all columns. Updating this property does
     /// not automatically collect statistics again; instead, it redefines the statistics schema
@@ -89,6 +93,24 @@ pub struct TableProperties {
     /// true to enable deletion vectors and predictive I/O for updates.
     pub enable_deletion_vectors: Option<bool>,
 
+    /// true to keep the table compatible with Iceberg (IcebergCompatV1). Kernel doesn't support
+    /// writing to tables with the IcebergCompatV1 writer feature.
+    pub enable_iceberg_compat_v1: Option<bool>,
+
+    /// true to keep the table compatible with Iceberg (IcebergCompatV2), e.g. for UniForm.
+    /// Writers must then satisfy the constraints of [IcebergCompatV2].
+    ///
+    /// [IcebergCompatV2]: https://github.com/delta-io/delta/blob/master/PROTOCOL.md#iceberg-compatibility-v2
+    pub enable_iceberg_compat_v2: Option<bool>,
+
+    /// The formats (besides Delta) in which the table metadata is made available with [UniForm],
+    /// parsed from the comma-separated `delta.universalFormat.enabledFormats` property. Writers
+    /// must keep the table compatible with these formats, e.g. Iceberg requires IcebergCompatV2
+    /// (see [`Self::enable_iceberg_compat_v2`]).
+    ///
+    /// [UniForm]: https://docs.delta.io/latest/delta-uniform.html
+    pub universal_format_enabled_formats: Option<Vec<UniversalFormat>>,
+
     /// The degree to which a transaction must be isolated from modifications made by concurrent
     /// transactions.
     ///
@@ -123,8 +145,8 @@ pub struct TableProperties {
     /// ignores it. The SetTransaction identifier is used when making the writes idempotent.
     pub set_transaction_retention_duration: Option<Duration>,
 
-    /// The target file size in bytes or higher units for file tuning. For example, 104857600
-    /// (bytes) or 100mb.
+    /// The target file size in bytes for file tuning. The property may be given in bytes or higher
+    /// units, e.g. 104857600 (bytes) or 100mb.
     pub target_file_size: Option<NonZero<u64>>,
 
     /// The target file size in bytes or higher units for file tuning. For example, 104857600
@@ -167,6 +189,55 @@ pub struct TableProperties {
     pub unknown_properties: HashMap<String, String>,
 }
 
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The default [`TableProperties::checkpoint_interval`].
+const DEFAULT_CHECKPOINT_INTERVAL: u64 = 10;
+
+/// The default [`TableProperties::data_skipping_num_indexed_cols`].
+const DEFAULT_NUM_INDEXED_COLS: u64 = 32;
+
+/// The default [`TableProperties::deleted_file_retention_duration`] (1 week), as in delta-spark.
+pub(crate) const DEFAULT_DELETED_FILE_RETENTION_DURATION: Duration =
+    Duration::from_secs(7 * SECONDS_PER_DAY);
+
+/// The default [`TableProperties::log_retention_duration`] (30 days), as in delta-spark.
+const DEFAULT_LOG_RETENTION_DURATION: Duration = Duration::from_secs(30 * SECONDS_PER_DAY);
+
+impl TableProperties {
+    /// The number of commits after which a new checkpoint should be created: the
+    /// [`checkpoint_interval`](Self::checkpoint_interval) if set, or else 10.
+    pub fn checkpoint_interval_or_default(&self) -> u64 {
+        self.checkpoint_interval
+            .map_or(DEFAULT_CHECKPOINT_INTERVAL, NonZero::get)
+    }
+
+    /// The columns to collect statistics for: the
+    /// [`data_skipping_num_indexed_cols`](Self::data_skipping_num_indexed_cols) if set, or else
+    /// the first 32 columns. Note that `delta.dataSkippingStatsColumns` takes precedence.
+    pub fn data_skipping_num_indexed_cols_or_default(&self) -> DataSkippingNumIndexedCols {
+        self.data_skipping_num_indexed_cols
+            .unwrap_or(DataSkippingNumIndexedCols::NumColumns(
+                DEFAULT_NUM_INDEXED_COLS,
+            ))
+    }
+
+    /// How long logically deleted data files are kept: the
+    /// [`deleted_file_retention_duration`](Self::deleted_file_retention_duration) if set, or else
+    /// 1 week.
+    pub fn deleted_file_retention_duration_or_default(&self) -> Duration {
+        self.deleted_file_retention_duration
+            .unwrap_or(DEFAULT_DELETED_FILE_RETENTION_DURATION)
+    }
+
+    /// How long the history of the table is kept: the
+    /// [`log_retention_duration`](Self::log_retention_duration) if set, or else 30 days.
+    pub fn log_retention_duration_or_default(&self) -> Duration {
+        self.log_retention_duration
+            .unwrap_or(DEFAULT_LOG_RETENTION_DURATION)
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum DataSkippingNumIndexedCols {
     AllColumns,
@@ -191,6 +262,18 @@ impl TryFrom<&str> for DataSkippingNumIndexedCols {
     }
 }
 
+/// A format in which the table metadata is made available with UniForm, see
+/// [`TableProperties::universal_format_enabled_formats`].
+#[derive(Debug, EnumString, Display, Copy, Clone, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum UniversalFormat {
+    /// Apache Iceberg metadata, which requires the table to be IcebergCompatV1 or IcebergCompatV2
+    /// compliant
+    Iceberg,
+    /// Apache Hudi metadata
+    Hudi,
+}
+
 /// The isolation level applied during transaction
 #[derive(Debug, EnumString, Default, Copy, Clone, PartialEq, Eq)]
 #[strum(serialize_all = "camelCase")]
@@ -266,6 +349,53 @@ mod tests {
         assert_eq!(actual, default_table_properties);
     }
 
+    #[test]
+    fn test_defaults() {
+        let properties = TableProperties::default();
+        assert_eq!(properties.checkpoint_interval_or_default(), 10);
+        assert_eq!(
+            properties.data_skipping_num_indexed_cols_or_default(),
+            DataSkippingNumIndexedCols::NumColumns(32)
+        );
+        assert_eq!(
+            properties.deleted_file_retention_duration_or_default(),
+            Duration::from_secs(7 * 24 * 60 * 60)
+        );
+        assert_eq!(
+            properties.log_retention_duration_or_default(),
+            Duration::from_secs(30 * 24 * 60 * 60)
+        );
+
+        let properties = TableProperties::from([
+            ("delta.checkpointInterval", "5"),
+            ("delta.dataSkippingNumIndexedCols", "-1"),
+            ("delta.deletedFileRetentionDuration", "interval 2 days"),
+            ("delta.logRetentionDuration", "interval 3 hours"),
+        ]);
+        assert_eq!(properties.checkpoint_interval_or_default(), 5);
+        assert_eq!(
+            properties.data_skipping_num_indexed_cols_or_default(),
+            DataSkippingNumIndexedCols::AllColumns
+        );
+        assert_eq!(
+            properties.deleted_file_retention_duration_or_default(),
+            Duration::from_secs(2 * 24 * 60 * 60)
+        );
+        assert_eq!(
+            properties.log_retention_duration_or_default(),
+            Duration::from_secs(3 * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn test_target_file_size_units() {
+        let properties = TableProperties::from([("delta.targetFileSize", "100mb")]);
+        assert_eq!(
+            properties.target_file_size,
+            Some(NonZero::new(100 * 1024 * 1024).unwrap())
+        );
+    }
+
     #[test]
     fn test_parse_table_properties() {
         let properties = [
@@ -276,11 +406,15 @@ mod tests {
             ("delta.checkpoint.writeStatsAsJson", "true"),
             ("delta.checkpoint.writeStatsAsStruct", "true"),
             ("delta.columnMapping.mode", "id"),
+            ("delta.columnMapping.maxColumnId", "7"),
             ("delta.dataSkippingNumIndexedCols", "-1"),
             ("delta.dataSkippingStatsColumns", "col1,col2"),
             ("delta.deletedFileRetentionDuration", "interval 1 second"),
             ("delta.enableChangeDataFeed", "true"),
             ("delta.enableDeletionVectors", "true"),
+            ("delta.enableIcebergCompatV1", "false"),
+            ("delta.enableIcebergCompatV2", "true"),
+            ("delta.universalFormat.enabledFormats", "iceberg,hudi"),
             ("delta.isolationLevel", "snapshotIsolation"),
             ("delta.logRetentionDuration", "interval 2 seconds"),
             ("delta.enableExpiredLogCleanup", "true"),
@@ -316,11 +450,18 @@ mod tests {
             checkpoint_write_stats_as_json: Some(true),
             checkpoint_write_stats_as_struct: Some(true),
             column_mapping_mode: Some(ColumnMappingMode::Id),
+            column_mapping_max_column_id: Some(7),
             data_skipping_num_indexed_cols: Some(DataSkippingNumIndexedCols::AllColumns),
             data_skipping_stats_columns: Some(vec![column_name!("col1"), column_name!("col2")]),
             deleted_file_retention_duration: Some(Duration::new(1, 0)),
             enable_change_data_feed: Some(true),
             enable_deletion_vectors: Some(true),
+            enable_iceberg_compat_v1: Some(false),
+            enable_iceberg_compat_v2: Some(true),
+            universal_format_enabled_formats: Some(vec![
+                UniversalFormat::Iceberg,
+                UniversalFormat::Hudi,
+            ]),
             isolation_level: Some(IsolationLevel::SnapshotIsolation),
             log_retention_duration: Some(Duration::new(2, 0)),
             enable_expired_log_cleanup: Some(true),
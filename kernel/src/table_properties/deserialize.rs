@@ -54,6 +54,9 @@ fn try_parse(props: &mut TableProperties, k: &str, v: &str) -> Option<()> {
         "delta.columnMapping.mode" => {
             props.column_mapping_mode = ColumnMappingMode::try_from(v).ok()
         }
+        "delta.columnMapping.maxColumnId" => {
+            props.column_mapping_max_column_id = Some(parse_non_negative(v)?)
+        }
         "delta.dataSkippingNumIndexedCols" => {
             props.data_skipping_num_indexed_cols = DataSkippingNumIndexedCols::try_from(v).ok()
         }
@@ -65,6 +68,11 @@ fn try_parse(props: &mut TableProperties, k: &str, v: &str) -> Option<()> {
         }
         "delta.enableChangeDataFeed" => props.enable_change_data_feed = Some(parse_bool(v)?),
         "delta.enableDeletionVectors" => props.enable_deletion_vectors = Some(parse_bool(v)?),
+        "delta.enableIcebergCompatV1" => props.enable_iceberg_compat_v1 = Some(parse_bool(v)?),
+        "delta.enableIcebergCompatV2" => props.enable_iceberg_compat_v2 = Some(parse_bool(v)?),
+        "delta.universalFormat.enabledFormats" => {
+            props.universal_format_enabled_formats = Some(parse_universal_formats(v)?)
+        }
         "delta.isolationLevel" => props.isolation_level = IsolationLevel::try_from(v).ok(),
         "delta.logRetentionDuration" => props.log_retention_duration = Some(parse_interval(v)?),
         "delta.enableExpiredLogCleanup" => props.enable_expired_log_cleanup = Some(parse_bool(v)?),
@@ -73,7 +81,7 @@ fn try_parse(props: &mut TableProperties, k: &str, v: &str) -> Option<()> {
         "delta.setTransactionRetentionDuration" => {
             props.set_transaction_retention_duration = Some(parse_interval(v)?)
         }
-        "delta.targetFileSize" => props.target_file_size = Some(parse_positive_int(v)?),
+        "delta.targetFileSize" => props.target_file_size = Some(parse_byte_size(v)?),
         "delta.tuneFileSizesForRewrites" => {
             props.tune_file_sizes_for_rewrites = Some(parse_bool(v)?)
         }
@@ -107,6 +115,28 @@ pub(crate) fn parse_positive_int(s: &str) -> Option<NonZero<u64>> {
     NonZero::new(parse_non_negative(s)?)
 }
 
+/// Deserialize a string representing a positive (> 0) number of bytes, optionally followed by a
+/// binary unit as accepted by spark (e.g. "104857600", "100m" or "100mb"), into an
+/// `Option<NonZero<u64>>`. Returns `Some` if successfully parses, and `None` otherwise.
+pub(crate) fn parse_byte_size(s: &str) -> Option<NonZero<u64>> {
+    let s = s.trim().to_ascii_lowercase();
+    let unit_start = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(unit_start);
+    let exponent = match unit {
+        "" | "b" => 0,
+        "k" | "kb" => 1,
+        "m" | "mb" => 2,
+        "g" | "gb" => 3,
+        "t" | "tb" => 4,
+        "p" | "pb" => 5,
+        _ => return None,
+    };
+    let bytes = parse_positive_int(number)?.checked_mul(NonZero::new(1024u64.pow(exponent))?)?;
+    // parse_positive_int caps the number at i64::MAX, and so must the size since java doesn't
+    // allow u64 either
+    i64::try_from(bytes.get()).is_ok().then_some(bytes)
+}
+
 /// Deserialize a string representing a non-negative integer into an `Option<u64>`. Returns `Some` if
 /// successfully parses, and `None` otherwise.
 pub(crate) fn parse_non_negative<T>(s: &str) -> Option<T>
@@ -136,6 +166,17 @@ pub(crate) fn parse_column_names(s: &str) -> Option<Vec<ColumnName>> {
         .ok()
 }
 
+/// Deserialize a comma-separated list of UniForm formats (e.g. "iceberg,hudi") into an
+/// `Option<Vec<UniversalFormat>>`. Returns `Some` if successfully parses (an empty string being an
+/// empty list), and `None` otherwise.
+pub(crate) fn parse_universal_formats(s: &str) -> Option<Vec<UniversalFormat>> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|format| !format.is_empty())
+        .map(|format| UniversalFormat::try_from(format).ok())
+        .collect()
+}
+
 /// Deserialize an interval string of the form "interval 5 days" into an `Option<Duration>`.
 /// Returns `Some` if successfully parses, and `None` otherwise.
 pub(crate) fn parse_interval(s: &str) -> Option<Duration> {
@@ -240,6 +281,36 @@ mod tests {
         assert_eq!(parse_non_negative::<i64>("-12"), None);
     }
 
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("104857600").unwrap().get(), 104_857_600);
+        assert_eq!(parse_byte_size("100b").unwrap().get(), 100);
+        assert_eq!(parse_byte_size("2k").unwrap().get(), 2 * 1024);
+        assert_eq!(parse_byte_size("100mb").unwrap().get(), 100 * 1024 * 1024);
+        assert_eq!(parse_byte_size("1G").unwrap().get(), 1024 * 1024 * 1024);
+        assert_eq!(parse_byte_size("3tb").unwrap().get(), 3 << 40);
+        assert_eq!(parse_byte_size("0mb"), None);
+        assert_eq!(parse_byte_size("-1mb"), None);
+        assert_eq!(parse_byte_size("mb"), None);
+        assert_eq!(parse_byte_size("1.5mb"), None);
+        assert_eq!(parse_byte_size("10 parsecs"), None);
+        assert_eq!(parse_byte_size("8192pb"), None);
+    }
+
+    #[test]
+    fn test_parse_universal_formats() {
+        assert_eq!(
+            parse_universal_formats("iceberg").unwrap(),
+            vec![UniversalFormat::Iceberg]
+        );
+        assert_eq!(
+            parse_universal_formats("iceberg, Hudi").unwrap(),
+            vec![UniversalFormat::Iceberg, UniversalFormat::Hudi]
+        );
+        assert_eq!(parse_universal_formats("").unwrap(), vec![]);
+        assert_eq!(parse_universal_formats("iceberg,paimon"), None);
+    }
+
     #[test]
     fn test_parse_interval() {
         assert_eq!(
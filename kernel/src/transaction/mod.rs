@@ -1,24 +1,43 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::ops::Deref;
 use std::sync::{Arc, LazyLock};
 
+use delta_kernel_derive::internal_api;
 use url::Url;
 
+use crate::actions::deletion_vector::DeletionVectorDescriptor;
+use crate::actions::domain_metadata::domain_metadata_configuration;
 use crate::actions::{
-    as_log_add_schema, get_log_commit_info_schema, get_log_domain_metadata_schema,
-    get_log_txn_schema, CommitInfo, DomainMetadata, SetTransaction,
+    as_log_add_schema, get_log_add_schema, get_log_commit_info_schema,
+    get_log_domain_metadata_schema, get_log_metadata_schema, get_log_protocol_schema,
+    get_log_txn_schema, Add, CommitInfo, DomainMetadata, Protocol, SetTransaction, CDC_NAME,
+    REMOVE_NAME,
 };
 use crate::error::Error;
 use crate::expressions::{ArrayData, Transform, UnaryExpressionOp::ToJson};
+use crate::history_manager::snapshot_in_commit_timestamp;
 use crate::path::ParsedLogPath;
+use crate::restore::RestoreActions;
 use crate::row_tracking::{RowTrackingDomainMetadata, RowTrackingVisitor};
-use crate::schema::{ArrayType, MapType, SchemaRef, StructField, StructType};
+use crate::schema::variant_utils::UsesVariant;
+use crate::schema::{
+    ArrayType, MapType, SchemaRef, SchemaTransform as _, StructField, StructType, ToSchema as _,
+};
 use crate::snapshot::SnapshotRef;
-use crate::utils::current_time_ms;
+use crate::table_changes::CHANGE_TYPE_COL_NAME;
+use crate::table_features::{
+    assign_column_mapping_metadata, check_generated_columns, check_invariants,
+    compute_generated_columns, fill_missing_columns, get_column_defaults, get_column_invariants,
+    get_generated_columns, get_identity_columns, update_high_water_marks,
+    validate_iceberg_compat_v2, validate_iceberg_compat_v2_adds, validate_universal_format,
+    ColumnDefault, ColumnInvariant, ColumnMappingMode, GeneratedColumn, IdentityColumn,
+    TableFeature, WriterFeature,
+};
+use crate::utils::{current_time_ms, enter_span, require};
 use crate::{
-    DataType, DeltaResult, Engine, EngineData, Expression, ExpressionRef, IntoEngineData,
-    RowVisitor, Version,
+    should_compact, DataType, DeltaResult, Engine, EngineData, Expression, ExpressionRef,
+    IntoEngineData, RowVisitor, Version,
 };
 
 /// Type alias for an iterator of [`EngineData`] results.
@@ -64,8 +83,9 @@ pub(crate) static ADD_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
 /// file to be added to the table. Kernel takes this information and extends it to the full add_file
 /// action schema, adding additional fields (e.g., baseRowID) as necessary.
 ///
-/// For now, Kernel only supports the number of records as a file statistic.
-/// This will change in a future release.
+/// The `stats` struct must contain the number of records of the file, and may also contain the
+/// `nullCount`, `minValues` and `maxValues` statistics of the columns given by
+/// [`WriteContext::stats_columns_schema`], keyed by physical column name.
 ///
 /// [`add_files`]: crate::transaction::Transaction::add_files
 /// [`ParquetHandler`]: crate::ParquetHandler
@@ -73,6 +93,70 @@ pub fn add_files_schema() -> &'static SchemaRef {
     &ADD_FILES_SCHEMA
 }
 
+/// The static instance referenced by [`remove_files_schema`].
+pub(crate) static REMOVE_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(StructType::new_unchecked(vec![
+        StructField::not_null("path", DataType::STRING),
+        StructField::not_null(
+            "partitionValues",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        ),
+        StructField::not_null("size", DataType::LONG),
+        StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+    ]))
+});
+
+/// The expected schema for the [`EngineData`] describing the files removed by
+/// [`rewrite_files`]. Each row represents a file currently in the table (as e.g. returned by a
+/// scan), including its deletion vector, if any. Kernel extends this information to the remove
+/// action schema.
+///
+/// [`rewrite_files`]: crate::transaction::Transaction::rewrite_files
+pub fn remove_files_schema() -> &'static SchemaRef {
+    &REMOVE_FILES_SCHEMA
+}
+
+/// The schema of the remove actions written by [`Transaction::rewrite_files`], nested in a
+/// top-level `remove` struct.
+static LOG_REMOVE_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let remove = StructType::new_unchecked(vec![
+        StructField::not_null("path", DataType::STRING),
+        StructField::nullable("deletionTimestamp", DataType::LONG),
+        StructField::not_null("dataChange", DataType::BOOLEAN),
+        StructField::nullable("extendedFileMetadata", DataType::BOOLEAN),
+        StructField::nullable(
+            "partitionValues",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        ),
+        StructField::nullable("size", DataType::LONG),
+        StructField::nullable("deletionVector", DeletionVectorDescriptor::to_schema()),
+    ]);
+    Arc::new(StructType::new_unchecked([StructField::nullable(
+        REMOVE_NAME,
+        remove,
+    )]))
+});
+
+/// The schema of the cdc actions written for the change data files added with
+/// [`Transaction::add_cdc_files`], nested in a top-level `cdc` struct.
+static LOG_CDC_FILES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    let cdc = StructType::new_unchecked(vec![
+        StructField::not_null("path", DataType::STRING),
+        StructField::not_null(
+            "partitionValues",
+            MapType::new(DataType::STRING, DataType::STRING, true),
+        ),
+        StructField::not_null("size", DataType::LONG),
+        StructField::not_null("dataChange", DataType::BOOLEAN),
+    ]);
+    Arc::new(StructType::new_unchecked([StructField::nullable(
+        CDC_NAME, cdc,
+    )]))
+});
+
+/// The directory (relative to the table root) that change data files are written to.
+const CHANGE_DATA_DIR: &str = "_change_data/";
+
 // NOTE: The following two methods are a workaround for the fact that we do not have a proper SchemaBuilder yet.
 // See https://github.com/delta-io/delta-kernel-rs/issues/1284
 /// Extend a schema with a statistics column and return a new SchemaRef.
@@ -118,6 +202,16 @@ pub struct Transaction {
     operation: Option<String>,
     engine_info: Option<String>,
     add_files_metadata: Vec<Box<dyn EngineData>>,
+    // files removed by file rewrites (see `rewrite_files`) or deletes (see `remove_files`),
+    // conforming to `remove_files_schema`
+    remove_files_metadata: Vec<Box<dyn EngineData>>,
+    // change data files written by this transaction, conforming to `add_files_schema`
+    cdc_files_metadata: Vec<Box<dyn EngineData>>,
+    // whether files were rewritten and whether data was added or removed in this transaction.
+    // rewrites are mutually exclusive with the others: their actions don't change the table data
+    has_rewrites: bool,
+    has_appends: bool,
+    has_deletes: bool,
     // NB: hashmap would require either duplicating the appid or splitting SetTransaction
     // key/payload. HashSet requires Borrow<&str> with matching Eq, Ord, and Hash. Plus,
     // HashSet::insert drops the to-be-inserted value without returning the existing one, which
@@ -128,6 +222,37 @@ pub struct Transaction {
     // keep all timestamps within the same commit consistent.
     commit_timestamp: i64,
     domain_metadatas: Vec<DomainMetadata>,
+    // column invariants of the table, which all written data must satisfy
+    invariants: Vec<ColumnInvariant>,
+    // generated columns of the table, whose written values must match their generation expressions
+    generated_columns: Vec<GeneratedColumn>,
+    // generated columns of the table whose generation expressions the kernel cannot handle
+    unsupported_generated_columns: Vec<String>,
+    // columns of the table with default values, used for columns missing from written data
+    column_defaults: Vec<ColumnDefault>,
+    // identity columns of the table, along with their high water marks as advanced by this
+    // transaction
+    identity_columns: Vec<IdentityColumn>,
+    // the logical table schema as of this transaction, including any column mapping annotations
+    // assigned by it
+    schema: SchemaRef,
+    column_mapping_mode: ColumnMappingMode,
+    // table properties set by this transaction, to be committed in a new metadata action
+    configuration_updates: HashMap<String, String>,
+    // whether this transaction adds support for row tracking to the table
+    adds_row_tracking: bool,
+    // whether the commit may upgrade the table protocol to support the table features required by
+    // this transaction
+    allow_protocol_upgrade: bool,
+    // the protocol set by this transaction (see `update_protocol`), if any
+    protocol_update: Option<Protocol>,
+    // the actions restoring the table to a previous version, if this transaction is a RESTORE
+    restore: Option<RestoreActions>,
+    // the add actions of files whose stats this transaction replaces, committed with
+    // `dataChange = false`
+    stats_updates: Vec<Add>,
+    // the interval (in commits) at which the engine wants to compact the log, if any
+    log_compaction_interval: Option<u64>,
 }
 
 impl std::fmt::Debug for Transaction {
@@ -151,26 +276,117 @@ impl Transaction {
         let read_snapshot = snapshot.into();
 
         // important! before a read/write to the table we must check it is supported
-        read_snapshot
-            .table_configuration()
-            .ensure_write_supported()?;
+        let table_configuration = read_snapshot.table_configuration();
+        table_configuration.ensure_write_supported()?;
+
+        // extract (and validate) column invariants and generation expressions up front so
+        // unsupported ones fail early
+        let invariants = if table_configuration.is_invariants_supported() {
+            get_column_invariants(table_configuration.schema().as_ref())?
+        } else {
+            vec![]
+        };
+        let (generated_columns, unsupported_generated_columns) =
+            if table_configuration.is_generated_columns_supported() {
+                get_generated_columns(table_configuration.schema().as_ref())?
+            } else {
+                (vec![], vec![])
+            };
+        let column_defaults = if table_configuration.is_column_defaults_supported() {
+            get_column_defaults(table_configuration.schema().as_ref())?
+        } else {
+            vec![]
+        };
+        let identity_columns = if table_configuration.is_identity_columns_supported() {
+            get_identity_columns(table_configuration.schema().as_ref())?
+        } else {
+            vec![]
+        };
 
         let commit_timestamp = current_time_ms()?;
+        let schema = table_configuration.schema();
+        let column_mapping_mode = table_configuration.column_mapping_mode();
 
         Ok(Transaction {
             read_snapshot,
             operation: None,
             engine_info: None,
             add_files_metadata: vec![],
+            remove_files_metadata: vec![],
+            cdc_files_metadata: vec![],
+            has_rewrites: false,
+            has_appends: false,
+            has_deletes: false,
             set_transactions: vec![],
             commit_timestamp,
             domain_metadatas: vec![],
+            invariants,
+            generated_columns,
+            unsupported_generated_columns,
+            column_defaults,
+            identity_columns,
+            schema,
+            column_mapping_mode,
+            configuration_updates: HashMap::new(),
+            adds_row_tracking: false,
+            allow_protocol_upgrade: false,
+            protocol_update: None,
+            restore: None,
+            stats_updates: Vec::new(),
+            log_compaction_interval: None,
         })
     }
 
     /// Consume the transaction and commit it to the table. The result is a [CommitResult] which
     /// will include the failed transaction in case of a conflict so the user can retry.
     pub fn commit(self, engine: &dyn Engine) -> DeltaResult<CommitResult> {
+        enter_span!(
+            INFO,
+            "commit",
+            table = %self.read_snapshot.table_root(),
+            version = self.read_snapshot.version() + 1
+        );
+        // Step 0: Reject writes that would break UniForm or Iceberg compatibility of the table,
+        // including through the table properties this transaction updates (e.g. enabling deletion
+        // vectors)
+        let table_configuration = self.read_snapshot.table_configuration();
+        let committed_configuration =
+            table_configuration.with_configuration_updates(&self.configuration_updates);
+        validate_universal_format(&committed_configuration)?;
+        if committed_configuration.is_iceberg_compat_v2_enabled() {
+            validate_iceberg_compat_v2(
+                &committed_configuration,
+                &self.schema,
+                self.column_mapping_mode,
+            )?;
+            validate_iceberg_compat_v2_adds(self.add_files_metadata.iter().map(|a| a.deref()))?;
+        }
+        if self.has_rewrites {
+            require!(
+                !self.has_appends && !self.has_deletes,
+                Error::generic(
+                    "Cannot both add or remove data and rewrite files in a single transaction"
+                )
+            );
+            require!(
+                !table_configuration.is_row_tracking_enabled(),
+                Error::unsupported(
+                    "Rewriting files is not supported on tables with row tracking enabled, since kernel does not preserve row IDs yet"
+                )
+            );
+        }
+
+        require!(
+            !(self.restore.is_some() && self.protocol_update.is_some()),
+            Error::generic("Cannot update the protocol in a RESTORE transaction")
+        );
+        require!(
+            !(self.has_deletes && table_configuration.is_append_only_enabled()),
+            Error::generic("Cannot remove files from an append-only table")
+        );
+        self.validate_change_data()?;
+        self.validate_column_mapping_id_mode(engine)?;
+
         // Step 1: Check for duplicate app_ids and generate set transactions (`txn`)
         // Note: The commit info must always be the first action in the commit but we generate it in
         // step 2 to fail early on duplicate transaction appIds
@@ -194,11 +410,12 @@ impl Transaction {
             .map(|txn| txn.into_engine_data(get_log_txn_schema().clone(), engine));
 
         // Step 2: Construct commit info and initialize the action iterator
-        let commit_info = CommitInfo::new(
+        let mut commit_info = CommitInfo::new(
             self.commit_timestamp,
             self.operation.clone(),
             self.engine_info.clone(),
         );
+        commit_info.in_commit_timestamp = self.in_commit_timestamp(engine)?;
         let commit_info_action =
             commit_info.into_engine_data(get_log_commit_info_schema().clone(), engine);
 
@@ -207,23 +424,56 @@ impl Transaction {
         let (add_actions, row_tracking_domain_metadata) =
             self.generate_adds(engine, commit_version)?;
 
+        // Step 3b: Generate remove actions for files rewritten or removed by this transaction, and
+        // cdc actions for the change data files it wrote
+        let remove_actions = self.generate_removes(engine);
+        let cdc_actions = self.generate_cdc_actions(engine);
+
+        // Step 3c: Generate the protocol, metadata and file actions restoring a previous version
+        let restore_actions = self
+            .restore
+            .clone()
+            .map(|restore| restore.into_engine_data(self.commit_timestamp, engine))
+            .unwrap_or_default();
+
+        // Step 3d: Generate the add actions replacing the stats of existing files
+        let stats_update_actions = self.stats_updates.iter().map(|add| {
+            let add = Add {
+                data_change: false,
+                ..add.clone()
+            };
+            add.into_engine_data(get_log_add_schema().clone(), engine)
+        });
+
         // Step 4: Generate all domain metadata actions (user and system domains)
         let domain_metadata_actions =
             self.generate_domain_metadata_actions(engine, row_tracking_domain_metadata)?;
 
-        // Step 5: Commit the actions as a JSON file to the Delta log
+        // Step 5: Generate a protocol action if this transaction updated the protocol or the table
+        // features it requires aren't supported yet (and upgrading the protocol is allowed), and a
+        // metadata action if the table metadata changed (e.g. column mapping was enabled or
+        // identity column high water marks moved)
+        let protocol_action = self.generate_protocol_action(engine)?;
+        let metadata_action = self.generate_metadata_action(engine)?;
+
+        // Step 6: Commit the actions as a JSON file to the Delta log
         let commit_path =
             ParsedLogPath::new_commit(self.read_snapshot.table_root(), commit_version)?;
         let actions = iter::once(commit_info_action)
+            .chain(protocol_action)
+            .chain(metadata_action)
             .chain(add_actions)
+            .chain(remove_actions)
+            .chain(cdc_actions)
+            .chain(restore_actions)
+            .chain(stats_update_actions)
             .chain(set_transaction_actions)
             .chain(domain_metadata_actions);
 
-        let json_handler = engine.json_handler();
-        match json_handler.write_json_file(&commit_path.location, Box::new(actions), false) {
-            Ok(()) => Ok(CommitResult::Committed {
-                version: commit_version,
-                post_commit_stats: PostCommitStats {
+        let log_store = engine.log_store();
+        match log_store.write_commit(&commit_path.location, Box::new(actions)) {
+            Ok(()) => {
+                let post_commit_stats = PostCommitStats {
                     commits_since_checkpoint: self
                         .read_snapshot
                         .log_segment()
@@ -234,9 +484,17 @@ impl Transaction {
                         .log_segment()
                         .commits_since_log_compaction_or_checkpoint()
                         + 1,
-                },
-            }),
-            Err(Error::FileAlreadyExists(_)) => Ok(CommitResult::Conflict(self, commit_version)),
+                };
+                Ok(CommitResult::Committed {
+                    version: commit_version,
+                    post_commit_actions: self
+                        .post_commit_actions(commit_version, &post_commit_stats),
+                    post_commit_stats,
+                })
+            }
+            Err(e) if matches!(e.inner(), Error::FileAlreadyExists(_)) => {
+                Ok(CommitResult::Conflict(self, commit_version))
+            }
             Err(e) => Err(e),
         }
     }
@@ -277,26 +535,29 @@ impl Transaction {
         self
     }
 
+    /// Remove the domain metadata of `domain` from the Delta log, by writing a tombstone for it.
+    /// The same rules as for [`Self::with_domain_metadata`] apply, i.e. a domain can't be both set
+    /// and removed in a single transaction. Removing a domain that doesn't exist in the read
+    /// snapshot is a no-op.
+    ///
+    /// Note that concurrent transactions that set or remove the same domain conflict with each
+    /// other, like any other concurrent commits to the table (see [`CommitResult::Conflict`]).
+    pub fn remove_domain_metadata(mut self, domain: String) -> Self {
+        // the configuration of the tombstone is filled in from the read snapshot on commit
+        self.domain_metadatas
+            .push(DomainMetadata::remove(domain, String::new()));
+        self
+    }
+
     /// Generate domain metadata actions with validation. Handle both user and system domains.
     fn generate_domain_metadata_actions<'a>(
         &'a self,
         engine: &'a dyn Engine,
         row_tracking_high_watermark: Option<RowTrackingDomainMetadata>,
     ) -> DeltaResult<impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + 'a> {
-        // if there are domain metadata actions, the table must support it
-        if !self.domain_metadatas.is_empty()
-            && !self
-                .read_snapshot
-                .table_configuration()
-                .is_domain_metadata_supported()
-        {
-            return Err(Error::unsupported(
-                "Domain metadata operations require writer version 7 and the 'domainMetadata' writer feature"
-            ));
-        }
-
         // validate domain metadata
         let mut domains = HashSet::new();
+        let mut user_domains = Vec::with_capacity(self.domain_metadatas.len());
         for domain_metadata in &self.domain_metadatas {
             if domain_metadata.is_internal() {
                 return Err(Error::Generic(
@@ -310,6 +571,19 @@ impl Transaction {
                     domain_metadata.domain()
                 )));
             }
+            if !domain_metadata.is_removed() {
+                user_domains.push(domain_metadata.clone());
+            } else if let Some(configuration) = domain_metadata_configuration(
+                self.read_snapshot.log_segment(),
+                domain_metadata.domain(),
+                engine,
+            )? {
+                // tombstones carry the latest configuration of the removed domain
+                user_domains.push(DomainMetadata::remove(
+                    domain_metadata.domain().to_string(),
+                    configuration,
+                ));
+            }
         }
 
         let system_domains = row_tracking_high_watermark
@@ -317,26 +591,429 @@ impl Transaction {
             .transpose()?
             .into_iter();
 
-        Ok(self
-            .domain_metadatas
-            .iter()
-            .cloned()
+        Ok(user_domains
+            .into_iter()
             .chain(system_domains)
             .map(|dm| dm.into_engine_data(get_log_domain_metadata_schema().clone(), engine)))
     }
 
+    /// Reserve `num_values` consecutive values of the identity column `column` for data written in
+    /// this transaction. The returned [`IdentityValueRange`] continues after all values previously
+    /// generated for the column (including those reserved earlier in this transaction), and the
+    /// column's `delta.identity.highWaterMark` is updated accordingly in a Metadata action when the
+    /// transaction commits.
+    ///
+    /// Engines must use exactly the reserved values for the identity column of the data they write.
+    /// Reserved values that end up unused leave gaps in the identity column, which is allowed.
+    /// Note that kernel does not track explicitly inserted identity values, so writing values
+    /// other than reserved ones is not supported.
+    pub fn reserve_identity_values(
+        &mut self,
+        column: &str,
+        num_values: u64,
+    ) -> DeltaResult<IdentityValueRange> {
+        let identity_column = self
+            .identity_columns
+            .iter_mut()
+            .find(|identity_column| identity_column.name == column)
+            .ok_or_else(|| Error::generic(format!("Column {column} is not an identity column")))?;
+        identity_column.reserve(num_values)
+    }
+
+    /// Returns the names of the identity columns of the table, for which values must be reserved
+    /// with [`Self::reserve_identity_values`].
+    pub fn identity_columns(&self) -> impl Iterator<Item = &str> {
+        self.identity_columns
+            .iter()
+            .map(|identity_column| identity_column.name.as_str())
+    }
+
+    /// Enable column mapping on the table in the given `mode` (which must not be
+    /// [`ColumnMappingMode::None`]). Every field of the table schema is assigned a column ID, and
+    /// keeps its current name as physical name, since the existing data files of the table use
+    /// the logical names. The annotated schema, along with the `delta.columnMapping.mode` and
+    /// `delta.columnMapping.maxColumnId` table properties, is committed in a new Metadata action.
+    ///
+    /// Existing data files have no field IDs, so readers resolving columns by ID would not find
+    /// their data. [`ColumnMappingMode::Id`] can thus only be enabled on tables without data files,
+    /// which is checked on commit.
+    ///
+    /// Column mapping must not be enabled yet, and the table protocol must support the column
+    /// mapping table feature unless protocol upgrades are allowed (see
+    /// [`Self::allow_protocol_upgrade`]). Data written in this transaction must use a write context
+    /// obtained after this call (see [`Self::get_write_context`]).
+    pub fn with_column_mapping_enabled(mut self, mode: ColumnMappingMode) -> DeltaResult<Self> {
+        let table_configuration = self.read_snapshot.table_configuration();
+        require!(
+            mode != ColumnMappingMode::None,
+            Error::invalid_column_mapping_mode("Cannot enable column mapping in mode 'none'")
+        );
+        require!(
+            self.column_mapping_mode == ColumnMappingMode::None,
+            Error::invalid_column_mapping_mode("Column mapping is already enabled on this table")
+        );
+
+        let mut max_column_id = table_configuration
+            .table_properties()
+            .column_mapping_max_column_id
+            .and_then(|id| i64::try_from(id).ok())
+            .unwrap_or(0);
+        let schema = assign_column_mapping_metadata(&self.schema, &mut max_column_id, true);
+        let mode_name = match mode {
+            ColumnMappingMode::Id => "id",
+            ColumnMappingMode::Name | ColumnMappingMode::None => "name",
+        };
+        self.configuration_updates.insert(
+            "delta.columnMapping.mode".to_string(),
+            mode_name.to_string(),
+        );
+        self.configuration_updates.insert(
+            "delta.columnMapping.maxColumnId".to_string(),
+            max_column_id.to_string(),
+        );
+        self.schema = Arc::new(schema);
+        self.column_mapping_mode = mode;
+        Ok(self)
+    }
+
+    // Column mapping `id` mode can only be enabled on tables without data files, since existing
+    // files have no field IDs (see `with_column_mapping_enabled`)
+    fn validate_column_mapping_id_mode(&self, engine: &dyn Engine) -> DeltaResult<()> {
+        let table_configuration = self.read_snapshot.table_configuration();
+        if self.column_mapping_mode != ColumnMappingMode::Id
+            || table_configuration.column_mapping_mode() != ColumnMappingMode::None
+        {
+            return Ok(());
+        }
+        let scan = self.read_snapshot.clone().scan_builder().build()?;
+        for scan_metadata in scan.scan_metadata(engine)? {
+            let has_files = scan_metadata?
+                .visit_scan_files(false, |has_files: &mut bool, _, _, _, _, _, _| {
+                    *has_files = true
+                })?;
+            require!(
+                !has_files,
+                Error::invalid_column_mapping_mode(
+                    "Column mapping 'id' mode can only be enabled on tables without data files"
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Enable deletion vectors on the table, i.e. set the `delta.enableDeletionVectors` table
+    /// property, which lets writers delete rows of existing files by marking them in deletion
+    /// vectors instead of rewriting the files.
+    ///
+    /// The table protocol must support the deletion vectors table feature unless protocol upgrades
+    /// are allowed (see [`Self::allow_protocol_upgrade`]). Committing fails on tables with
+    /// IcebergCompatV2 or UniForm Hudi enabled, which don't allow deletion vectors.
+    pub fn with_deletion_vectors_enabled(mut self) -> Self {
+        self.configuration_updates.insert(
+            "delta.enableDeletionVectors".to_string(),
+            "true".to_string(),
+        );
+        self
+    }
+
+    /// Add support for row tracking to the table: the files added by this transaction and all
+    /// later ones are assigned row IDs and row commit versions. The names of the columns that
+    /// materialize them in data files are set, unless the table already has them. Row tracking
+    /// isn't *enabled* (`delta.enableRowTracking`), since that requires all existing files of the
+    /// table to have row IDs, which kernel doesn't assign to existing files.
+    ///
+    /// The table protocol must support the row tracking table feature unless protocol upgrades
+    /// are allowed (see [`Self::allow_protocol_upgrade`]).
+    pub fn with_row_tracking_supported(mut self) -> Self {
+        let table_properties = self.read_snapshot.table_properties();
+        let column_names = [
+            (
+                "delta.rowTracking.materializedRowIdColumnName",
+                "_row-id-col-",
+                &table_properties.materialized_row_id_column_name,
+            ),
+            (
+                "delta.rowTracking.materializedRowCommitVersionColumnName",
+                "_row-commit-version-col-",
+                &table_properties.materialized_row_commit_version_column_name,
+            ),
+        ];
+        for (property, prefix, current) in column_names {
+            if current.is_none() {
+                let name = format!("{prefix}{}", uuid::Uuid::new_v4());
+                self.configuration_updates
+                    .insert(property.to_string(), name);
+            }
+        }
+        self.adds_row_tracking = true;
+        self
+    }
+
+    /// Enable in-commit timestamps on the table, i.e. set the `delta.enableInCommitTimestamps`
+    /// table property along with the version and timestamp of this commit as its enablement
+    /// version and timestamp. From this commit on, every commit records a monotonically increasing
+    /// in-commit timestamp, which readers use instead of the modification time of commit files.
+    /// This does nothing if in-commit timestamps are already enabled.
+    ///
+    /// The table protocol must support the in-commit timestamps table feature unless protocol
+    /// upgrades are allowed (see [`Self::allow_protocol_upgrade`]).
+    pub fn with_in_commit_timestamps_enabled(mut self) -> Self {
+        let table_configuration = self.read_snapshot.table_configuration();
+        if table_configuration.is_in_commit_timestamps_enabled() {
+            return self;
+        }
+        let enablement_timestamp = self.enablement_in_commit_timestamp();
+        self.configuration_updates.extend([
+            (
+                "delta.enableInCommitTimestamps".to_string(),
+                "true".to_string(),
+            ),
+            (
+                "delta.inCommitTimestampEnablementVersion".to_string(),
+                (self.read_snapshot.version() + 1).to_string(),
+            ),
+            (
+                "delta.inCommitTimestampEnablementTimestamp".to_string(),
+                enablement_timestamp.to_string(),
+            ),
+        ]);
+        self
+    }
+
+    /// Allow this transaction to upgrade the table protocol when committing, if the operations of
+    /// the transaction require table features the table doesn't support yet (e.g. adding domain
+    /// metadata or enabling column mapping). The new protocol uses table features (i.e. writer
+    /// version 7, and reader version 3 if a reader-writer feature is added), which may prevent
+    /// older clients from reading or writing the table.
+    ///
+    /// Without this opt-in, committing such a transaction fails instead.
+    pub fn allow_protocol_upgrade(mut self) -> Self {
+        self.allow_protocol_upgrade = true;
+        self
+    }
+
+    /// Replace the table protocol with `protocol` in this transaction, e.g. to add table features
+    /// (see [`Protocol::upgrade_with_features`]) or to move to legacy protocol versions (see
+    /// [`Protocol::try_downgrade`]). The new protocol must support all table features of the
+    /// current protocol (table features cannot be dropped this way) and be supported for writes by
+    /// kernel.
+    ///
+    /// If the operations of this transaction require further table features, they are added to
+    /// the new protocol on commit (if protocol upgrades are allowed, see
+    /// [`Self::allow_protocol_upgrade`]).
+    #[internal_api]
+    pub(crate) fn update_protocol(mut self, protocol: Protocol) -> DeltaResult<Self> {
+        let current = self.read_snapshot.table_configuration().protocol();
+        let reader_features = protocol.effective_reader_features();
+        let writer_features = protocol.effective_writer_features();
+        if let Some(feature) = current
+            .effective_reader_features()
+            .into_iter()
+            .find(|feature| !reader_features.contains(feature))
+        {
+            return Err(Error::generic(format!(
+                "Cannot update protocol: the new protocol doesn't support the '{feature}' reader feature of the table"
+            )));
+        }
+        if let Some(feature) = current
+            .effective_writer_features()
+            .into_iter()
+            .find(|feature| !writer_features.contains(feature))
+        {
+            return Err(Error::generic(format!(
+                "Cannot update protocol: the new protocol doesn't support the '{feature}' writer feature of the table"
+            )));
+        }
+        protocol.ensure_write_supported()?;
+        self.protocol_update = Some(protocol);
+        Ok(self)
+    }
+
+    /// The writer features required by the operations of this transaction.
+    fn required_writer_features(&self) -> Vec<WriterFeature> {
+        let mut features = vec![];
+        if !self.domain_metadatas.is_empty() {
+            features.push(WriterFeature::DomainMetadata);
+        }
+        if self.sets_property("delta.enableDeletionVectors") {
+            features.push(WriterFeature::DeletionVectors);
+        }
+        if self.adds_row_tracking {
+            features.push(WriterFeature::RowTracking);
+        }
+        if self.sets_property("delta.enableInCommitTimestamps") {
+            features.push(WriterFeature::InCommitTimestamp);
+        }
+        if self.column_mapping_mode
+            != self
+                .read_snapshot
+                .table_configuration()
+                .column_mapping_mode()
+        {
+            features.push(WriterFeature::ColumnMapping);
+        }
+        let protocol = self.read_snapshot.table_configuration().protocol();
+        if !protocol.has_writer_feature(&WriterFeature::VariantTypePreview) {
+            let mut uses_variant = UsesVariant::default();
+            let _ = uses_variant.transform_struct(&self.schema);
+            if uses_variant.0 {
+                features.push(WriterFeature::VariantType);
+            }
+        }
+        features
+    }
+
+    /// Whether this transaction sets the boolean table property `key` to `true`.
+    fn sets_property(&self, key: &str) -> bool {
+        self.configuration_updates
+            .get(key)
+            .is_some_and(|value| value == "true")
+    }
+
+    /// The in-commit timestamp of this commit, if in-commit timestamps are enabled on the table
+    /// or by this transaction. In-commit timestamps must increase with every commit, so this is
+    /// the commit timestamp, unless that is not after the in-commit timestamp of the previous
+    /// commit.
+    fn in_commit_timestamp(&self, engine: &dyn Engine) -> DeltaResult<Option<i64>> {
+        if let Some(previous) = snapshot_in_commit_timestamp(&self.read_snapshot, engine)? {
+            return Ok(Some(self.commit_timestamp.max(previous.saturating_add(1))));
+        }
+        Ok(self
+            .sets_property("delta.enableInCommitTimestamps")
+            .then(|| self.enablement_in_commit_timestamp()))
+    }
+
+    /// The in-commit timestamp of a commit enabling in-commit timestamps: the commit timestamp,
+    /// unless that is not after the modification time of the previous commit.
+    fn enablement_in_commit_timestamp(&self) -> i64 {
+        let log_segment = self.read_snapshot.log_segment();
+        match log_segment.ascending_commit_files.last() {
+            Some(commit) if commit.version == self.read_snapshot.version() => self
+                .commit_timestamp
+                .max(commit.location.last_modified.saturating_add(1)),
+            _ => self.commit_timestamp,
+        }
+    }
+
+    /// Generate a protocol action if this transaction updated the protocol (see
+    /// [`Self::update_protocol`]) or requires table features the table doesn't support yet, or fail
+    /// if upgrading the protocol isn't allowed.
+    fn generate_protocol_action(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<DeltaResult<Box<dyn EngineData>>>> {
+        let table_configuration = self.read_snapshot.table_configuration();
+        let missing_features: Vec<_> = self
+            .required_writer_features()
+            .into_iter()
+            .filter(|feature| match &self.protocol_update {
+                Some(protocol) => !protocol.effective_writer_features().contains(feature),
+                None => match feature {
+                    WriterFeature::DomainMetadata => {
+                        !table_configuration.is_domain_metadata_supported()
+                    }
+                    WriterFeature::ColumnMapping => {
+                        !table_configuration.is_column_mapping_supported()
+                    }
+                    feature => !table_configuration.protocol().has_writer_feature(feature),
+                },
+            })
+            .collect();
+        let protocol = match (missing_features.first(), &self.protocol_update) {
+            (None, None) => return Ok(None),
+            (None, Some(protocol)) => protocol.clone(),
+            (Some(first_missing), _) if !self.allow_protocol_upgrade => {
+                return Err(match first_missing {
+                    WriterFeature::DomainMetadata => Error::unsupported(
+                        "Domain metadata operations require writer version 7 and the 'domainMetadata' writer feature"
+                    ),
+                    WriterFeature::ColumnMapping => Error::unsupported(
+                        "Enabling column mapping requires the table protocol to support the 'columnMapping' table feature"
+                    ),
+                    feature => Error::unsupported(format!(
+                        "This transaction requires the '{feature}' writer feature"
+                    )),
+                });
+            }
+            (Some(_), protocol_update) => {
+                let features: Vec<TableFeature> =
+                    missing_features.into_iter().map(Into::into).collect();
+                protocol_update
+                    .as_ref()
+                    .unwrap_or(table_configuration.protocol())
+                    .upgrade_with_features(&features)?
+            }
+        };
+        protocol.ensure_write_supported()?;
+        Ok(Some(protocol.into_engine_data(
+            get_log_protocol_schema().clone(),
+            engine,
+        )))
+    }
+
+    /// Generate a metadata action if this transaction changed the table metadata, i.e. updated
+    /// table properties (such as when enabling column mapping) or reserved identity column values
+    /// (whose high water marks then need to be updated).
+    fn generate_metadata_action(
+        &self,
+        engine: &dyn Engine,
+    ) -> DeltaResult<Option<DeltaResult<Box<dyn EngineData>>>> {
+        let updated_schema = update_high_water_marks(&self.schema, &self.identity_columns);
+        if updated_schema.is_none() && self.configuration_updates.is_empty() {
+            return Ok(None);
+        }
+        let schema = match updated_schema {
+            Some(schema) => schema,
+            None => self.schema.as_ref().clone(),
+        };
+        let mut metadata = self.read_snapshot.metadata().clone();
+        metadata.schema_string = serde_json::to_string(&schema)?;
+        metadata
+            .configuration
+            .extend(self.configuration_updates.clone());
+        Ok(Some(metadata.into_engine_data(
+            get_log_metadata_schema().clone(),
+            engine,
+        )))
+    }
+
     // Generate the logical-to-physical transform expression which must be evaluated on every data
-    // chunk before writing. At the moment, this is a transaction-wide expression.
+    // chunk before writing. At the moment, this is a transaction-wide expression. Its result is
+    // expected to have the physical write schema (see `generate_physical_schema`), which also
+    // renames the columns to their physical names.
     fn generate_logical_to_physical(&self) -> Expression {
+        Expression::struct_from(self.physical_columns())
+    }
+
+    // Generate the logical to physical transform of change data, which additionally passes through
+    // the `_change_type` column.
+    fn generate_logical_to_change_data(&self) -> Expression {
+        Expression::struct_from(
+            self.physical_columns()
+                .chain([Expression::column([CHANGE_TYPE_COL_NAME])]),
+        )
+    }
+
+    fn physical_columns(&self) -> impl Iterator<Item = Expression> + '_ {
         // for now, we just pass through all the columns except partition columns.
         // note this is _incorrect_ if table config deems we need partition columns.
         let partition_columns = &self.read_snapshot.metadata().partition_columns;
-        let schema = self.read_snapshot.schema();
-        let fields = schema
+        self.schema
+            .fields()
+            .filter(move |f| !partition_columns.contains(f.name()))
+            .map(|f| Expression::column([f.name()]))
+    }
+
+    // Generate the physical schema of the data files written in this transaction: the (non-partition)
+    // columns of the table, with physical names and field IDs according to the column mapping mode.
+    fn generate_physical_schema(&self) -> StructType {
+        let partition_columns = &self.read_snapshot.metadata().partition_columns;
+        let fields = self
+            .schema
             .fields()
             .filter(|f| !partition_columns.contains(f.name()))
-            .map(|f| Expression::column([f.name()]));
-        Expression::struct_from(fields)
+            .map(|f| f.make_physical(self.column_mapping_mode));
+        StructType::new_unchecked(fields)
     }
 
     /// Get the write context for this transaction. At the moment, this is constant for the whole
@@ -346,12 +1023,24 @@ impl Transaction {
     // have invalid metadata.
     pub fn get_write_context(&self) -> WriteContext {
         let target_dir = self.read_snapshot.table_root();
-        let snapshot_schema = self.read_snapshot.schema();
         let logical_to_physical = self.generate_logical_to_physical();
         WriteContext::new(
             target_dir.clone(),
-            snapshot_schema,
+            self.schema.clone(),
+            Arc::new(self.generate_physical_schema()),
             Arc::new(logical_to_physical),
+            self.read_snapshot
+                .table_configuration()
+                .stats_columns_schema(),
+            self.invariants.clone(),
+            self.generated_columns.clone(),
+            self.unsupported_generated_columns.clone(),
+            self.column_defaults.clone(),
+            self.read_snapshot
+                .table_properties()
+                .enable_change_data_feed
+                .unwrap_or(false),
+            Arc::new(self.generate_logical_to_change_data()),
         )
     }
 
@@ -360,8 +1049,194 @@ impl Transaction {
     /// to add multiple batches.
     ///
     /// The expected schema for `add_metadata` is given by [`add_files_schema`].
+    ///
+    /// The kernel does not validate the data of the added files: the engine must have checked it
+    /// against the table's column invariants and generated columns before writing it (see
+    /// [`WriteContext::check_invariants`] and [`WriteContext::check_generated_columns`]).
     pub fn add_files(&mut self, add_metadata: Box<dyn EngineData>) {
         self.add_files_metadata.push(add_metadata);
+        self.has_appends = true;
+    }
+
+    /// Rewrite files of the table without changing its data, e.g. to compact small files (as done
+    /// by `OPTIMIZE`). The files described by `removed` (whose expected schema is given by
+    /// [`remove_files_schema`]) are replaced by the files described by `added` (whose expected
+    /// schema is given by [`add_files_schema`]), which must contain exactly the same rows. The
+    /// rows deleted by the deletion vectors of removed files must not be contained in the added
+    /// files, i.e. rewritten files never have deletion vectors. Note that this API can be called
+    /// multiple times to rewrite multiple batches of files.
+    ///
+    /// All add and remove actions of the rewrite are committed with `dataChange = false`, so
+    /// streaming readers and change data feed consumers don't process the rewritten rows again.
+    /// A transaction that rewrites files can't also add new data with [`Self::add_files`]. If no
+    /// operation is set on the transaction, it is committed as an `OPTIMIZE` operation.
+    pub fn rewrite_files(&mut self, removed: Box<dyn EngineData>, added: Box<dyn EngineData>) {
+        self.remove_files_metadata.push(removed);
+        self.add_files_metadata.push(added);
+        self.has_rewrites = true;
+        self.operation.get_or_insert_with(|| "OPTIMIZE".to_string());
+    }
+
+    /// Remove files from the table, deleting all of their rows, e.g. to delete whole files or
+    /// partitions (as done by `DELETE`). The expected schema for `removed` is given by
+    /// [`remove_files_schema`]: each row describes a file currently in the table (as e.g. returned
+    /// by a scan). Note that this API can be called multiple times to remove multiple batches of
+    /// files.
+    ///
+    /// Combined with [`Self::add_files`], this allows rewriting data, e.g. to delete or update
+    /// some rows of files by replacing them with new files. On tables with the change data feed
+    /// enabled (`delta.enableChangeDataFeed`), such commits must also record the changed rows in
+    /// change data files (see [`Self::add_cdc_files`]). Commits that only add files or only remove
+    /// whole files don't need change data files, since readers derive their changes from the add
+    /// and remove actions. If no operation is set on the transaction, it is committed as a
+    /// `DELETE` operation.
+    pub fn remove_files(&mut self, removed: Box<dyn EngineData>) {
+        self.remove_files_metadata.push(removed);
+        self.has_deletes = true;
+        self.operation.get_or_insert_with(|| "DELETE".to_string());
+    }
+
+    /// Add change data files to include in this transaction, which record the rows changed by it
+    /// for the change data feed. Change data files are written to
+    /// [`WriteContext::change_data_dir`] and contain the columns of the table, along with a
+    /// `_change_type` column holding one of `insert`, `delete`, `update_preimage` or
+    /// `update_postimage` (see [`WriteContext::logical_to_change_data`]). Note that this API can be
+    /// called multiple times to add multiple batches.
+    ///
+    /// The expected schema for `cdc_metadata` is given by [`add_files_schema`]. Change data files
+    /// can only be added to tables with the change data feed enabled.
+    pub fn add_cdc_files(&mut self, cdc_metadata: Box<dyn EngineData>) {
+        self.cdc_files_metadata.push(cdc_metadata);
+    }
+
+    /// Validate the change data files of this transaction: tables with the change data feed
+    /// enabled require change data files for commits that both add and remove data, since readers
+    /// can't tell which of their rows changed. Other commits can't have change data files.
+    fn validate_change_data(&self) -> DeltaResult<()> {
+        let cdf_enabled = self
+            .read_snapshot
+            .table_properties()
+            .enable_change_data_feed
+            .unwrap_or(false);
+        let has_cdc_files = !self.cdc_files_metadata.is_empty();
+        if !cdf_enabled {
+            require!(
+                !has_cdc_files,
+                Error::generic(
+                    "Cannot add change data files to a table without the change data feed enabled"
+                )
+            );
+        } else if self.has_rewrites {
+            require!(
+                !has_cdc_files,
+                Error::generic("Cannot add change data files when rewriting files")
+            );
+        } else if self.has_appends && self.has_deletes {
+            require!(
+                has_cdc_files,
+                Error::generic(
+                    "The change data feed is enabled on this table: transactions that both add and remove files must add change data files for the changed rows"
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Compact the log every `interval` commits: if committing this transaction completes an
+    /// interval (see [`should_compact`]), the [`PostCommitActions`] of the commit request a log
+    /// compaction of the last `interval` commits.
+    ///
+    /// [`should_compact`]: crate::should_compact
+    pub fn with_log_compaction_interval(mut self, interval: u64) -> Self {
+        self.log_compaction_interval = Some(interval);
+        self
+    }
+
+    /// The maintenance work to perform after committing this transaction as `commit_version`.
+    fn post_commit_actions(
+        &self,
+        commit_version: Version,
+        post_commit_stats: &PostCommitStats,
+    ) -> PostCommitActions {
+        let checkpoint_interval = self
+            .read_snapshot
+            .table_properties()
+            .checkpoint_interval_or_default();
+        let log_compaction = self
+            .log_compaction_interval
+            .filter(|&interval| should_compact(commit_version, interval))
+            .map(|interval| (commit_version + 1 - interval, commit_version));
+        PostCommitActions {
+            checkpoint: post_commit_stats.commits_since_checkpoint >= checkpoint_interval,
+            log_compaction,
+        }
+    }
+
+    /// Restore the table to a previous version, as computed by [`Restore`]. Files removed by the
+    /// restore count as deletes, so restoring an append-only table fails if it removes any files.
+    ///
+    /// [`Restore`]: crate::restore::Restore
+    pub(crate) fn with_restore(mut self, restore: RestoreActions) -> Self {
+        self.has_deletes |= restore.removes_files();
+        self.restore = Some(restore);
+        self
+    }
+
+    /// Replace the stats of existing files of the table, as computed by [`Analyze`]. Each of
+    /// `files` is the add action of a live file with its new stats.
+    ///
+    /// [`Analyze`]: crate::analyze::Analyze
+    #[cfg(any(feature = "default-engine-base", feature = "sync-engine"))]
+    pub(crate) fn with_stats_updates(mut self, files: Vec<Add>) -> Self {
+        self.stats_updates = files;
+        self
+    }
+
+    /// Generate the remove actions of files rewritten or removed by this transaction
+    fn generate_removes<'a>(&'a self, engine: &dyn Engine) -> EngineDataResultIterator<'a> {
+        let evaluation_handler = engine.evaluation_handler();
+        // the remove actions of file rewrites don't change the table data
+        let removes_expr = Arc::new(Expression::struct_from([Expression::struct_from([
+            Expression::column(["path"]),
+            Expression::literal(self.commit_timestamp),
+            Expression::literal(!self.has_rewrites),
+            Expression::literal(true),
+            Expression::column(["partitionValues"]),
+            Expression::column(["size"]),
+            Expression::column(["deletionVector"]),
+        ])]));
+        Box::new(
+            self.remove_files_metadata
+                .iter()
+                .map(move |remove_files_batch| {
+                    let removes_evaluator = evaluation_handler.new_expression_evaluator(
+                        remove_files_schema().clone(),
+                        removes_expr.clone(),
+                        LOG_REMOVE_FILES_SCHEMA.clone().into(),
+                    );
+                    removes_evaluator.evaluate(remove_files_batch.deref())
+                }),
+        )
+    }
+
+    /// Generate the cdc actions of the change data files written by this transaction
+    fn generate_cdc_actions<'a>(&'a self, engine: &dyn Engine) -> EngineDataResultIterator<'a> {
+        let evaluation_handler = engine.evaluation_handler();
+        // cdc actions never change the table data
+        let cdc_expr = Arc::new(Expression::struct_from([Expression::struct_from([
+            Expression::column(["path"]),
+            Expression::column(["partitionValues"]),
+            Expression::column(["size"]),
+            Expression::literal(false),
+        ])]));
+        Box::new(self.cdc_files_metadata.iter().map(move |cdc_files_batch| {
+            let cdc_evaluator = evaluation_handler.new_expression_evaluator(
+                add_files_schema().clone(),
+                cdc_expr.clone(),
+                LOG_CDC_FILES_SCHEMA.clone().into(),
+            );
+            cdc_evaluator.evaluate(cdc_files_batch.deref())
+        }))
     }
 
     /// Generate add actions, handling row tracking internally if needed
@@ -378,6 +1253,7 @@ impl Transaction {
             add_files_metadata: I,
             input_schema: SchemaRef,
             output_schema: SchemaRef,
+            data_change: Option<bool>,
         ) -> impl Iterator<Item = DeltaResult<Box<dyn EngineData>>> + 'a
         where
             I: Iterator<Item = DeltaResult<T>> + Send + 'a,
@@ -387,12 +1263,15 @@ impl Transaction {
 
             add_files_metadata.map(move |add_files_batch| {
                 // Convert stats to a JSON string and nest the add action in a top-level struct
-                let adds_expr = Expression::struct_from([Expression::transform(
-                    Transform::new_top_level().with_replaced_field(
-                        "stats",
-                        Expression::unary(ToJson, Expression::column(["stats"])).into(),
-                    ),
-                )]);
+                let mut transform = Transform::new_top_level().with_replaced_field(
+                    "stats",
+                    Expression::unary(ToJson, Expression::column(["stats"])).into(),
+                );
+                if let Some(data_change) = data_change {
+                    transform = transform
+                        .with_replaced_field("dataChange", Expression::literal(data_change).into());
+                }
+                let adds_expr = Expression::struct_from([Expression::transform(transform)]);
                 let adds_evaluator = evaluation_handler.new_expression_evaluator(
                     input_schema.clone(),
                     Arc::new(adds_expr),
@@ -409,10 +1288,14 @@ impl Transaction {
         let commit_version = i64::try_from(commit_version)
             .map_err(|_| Error::generic("Commit version too large to fit in i64"))?;
 
-        let needs_row_tracking = self
-            .read_snapshot
-            .table_configuration()
-            .should_write_row_tracking();
+        // the add actions of file rewrites don't change the table data
+        let data_change = self.has_rewrites.then_some(false);
+
+        let needs_row_tracking = self.adds_row_tracking
+            || self
+                .read_snapshot
+                .table_configuration()
+                .should_write_row_tracking();
 
         if needs_row_tracking {
             // Read the current rowIdHighWaterMark from the snapshot's row tracking domain metadata
@@ -460,6 +1343,7 @@ impl Transaction {
                 as_log_add_schema(with_row_tracking_cols(&with_stats_col(
                     mandatory_add_file_schema(),
                 ))),
+                data_change,
             );
 
             // Generate a row tracking domain metadata based on the final high water mark
@@ -474,6 +1358,7 @@ impl Transaction {
                 self.add_files_metadata.iter().map(|a| Ok(a.deref())),
                 add_files_schema().clone(),
                 as_log_add_schema(with_stats_col(mandatory_add_file_schema())),
+                data_change,
             );
 
             Ok((Box::new(add_actions), None))
@@ -481,22 +1366,117 @@ impl Transaction {
     }
 }
 
+/// A contiguous range of identity column values reserved with
+/// [`Transaction::reserve_identity_values`]: `num_values` values starting at `start`, in increments
+/// of `step`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdentityValueRange {
+    start: i64,
+    step: i64,
+    num_values: u64,
+}
+
+impl IdentityValueRange {
+    pub(crate) fn new(start: i64, step: i64, num_values: u64) -> Self {
+        Self {
+            start,
+            step,
+            num_values,
+        }
+    }
+
+    /// The first value of the range.
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// The increment between consecutive values of the range.
+    pub fn step(&self) -> i64 {
+        self.step
+    }
+
+    /// The number of values in the range.
+    pub fn len(&self) -> u64 {
+        self.num_values
+    }
+
+    /// Returns `true` if the range contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.num_values == 0
+    }
+
+    /// The last value of the range, if the range is not empty.
+    pub fn last(&self) -> Option<i64> {
+        Some(self.value_at(self.num_values.checked_sub(1)?))
+    }
+
+    /// Iterate over all values of the range, in order.
+    pub fn iter(&self) -> impl Iterator<Item = i64> {
+        let range = *self;
+        (0..self.num_values).map(move |i| range.value_at(i))
+    }
+
+    /// The `i`-th value of the range. `reserve_identity_values` checked that all values of the
+    /// range fit an i64, so the wrapping two's complement arithmetic yields them even if
+    /// intermediate results don't fit.
+    fn value_at(&self, i: u64) -> i64 {
+        let offset = i.wrapping_mul(self.step as u64);
+        self.start.wrapping_add(offset as i64)
+    }
+}
+
 /// WriteContext is data derived from a [`Transaction`] that can be provided to writers in order to
 /// write table data.
 ///
+/// The kernel never sees the data an engine writes, only the metadata of the written files passed
+/// to [`Transaction::add_files`], so it cannot enforce data constraints itself. Engines that write
+/// data files themselves must therefore call [`Self::check_invariants`] and
+/// [`Self::check_generated_columns`] on all (logical) data before writing it, and must not add
+/// files whose data failed either check. The default engine's `write_parquet` does this
+/// automatically.
+///
 /// [`Transaction`]: struct.Transaction.html
 pub struct WriteContext {
     target_dir: Url,
     schema: SchemaRef,
+    physical_schema: SchemaRef,
     logical_to_physical: ExpressionRef,
+    stats_columns_schema: Option<SchemaRef>,
+    invariants: Vec<ColumnInvariant>,
+    generated_columns: Vec<GeneratedColumn>,
+    unsupported_generated_columns: Vec<String>,
+    column_defaults: Vec<ColumnDefault>,
+    change_data_feed_enabled: bool,
+    logical_to_change_data: ExpressionRef,
 }
 
 impl WriteContext {
-    fn new(target_dir: Url, schema: SchemaRef, logical_to_physical: ExpressionRef) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        target_dir: Url,
+        schema: SchemaRef,
+        physical_schema: SchemaRef,
+        logical_to_physical: ExpressionRef,
+        stats_columns_schema: Option<SchemaRef>,
+        invariants: Vec<ColumnInvariant>,
+        generated_columns: Vec<GeneratedColumn>,
+        unsupported_generated_columns: Vec<String>,
+        column_defaults: Vec<ColumnDefault>,
+        change_data_feed_enabled: bool,
+        logical_to_change_data: ExpressionRef,
+    ) -> Self {
         WriteContext {
             target_dir,
             schema,
+            physical_schema,
             logical_to_physical,
+            stats_columns_schema,
+            invariants,
+            generated_columns,
+            unsupported_generated_columns,
+            column_defaults,
+            change_data_feed_enabled,
+            logical_to_change_data,
         }
     }
 
@@ -508,9 +1488,171 @@ impl WriteContext {
         &self.schema
     }
 
+    /// The physical schema of the data files to write, i.e. the result schema of
+    /// [`Self::logical_to_physical`]. Partition columns are excluded, and when column mapping is
+    /// enabled, fields have their physical names. In column mapping `id` mode, fields also carry
+    /// their field IDs in the [`ColumnMetadataKey::ParquetFieldId`] metadata, which Parquet writers
+    /// must emit as the Parquet field IDs.
+    ///
+    /// [`ColumnMetadataKey::ParquetFieldId`]: crate::schema::ColumnMetadataKey::ParquetFieldId
+    pub fn physical_schema(&self) -> &SchemaRef {
+        &self.physical_schema
+    }
+
     pub fn logical_to_physical(&self) -> ExpressionRef {
         self.logical_to_physical.clone()
     }
+
+    /// The physical schema of the columns of [`Self::physical_schema`] that writers should collect
+    /// file statistics (null counts, and min and max values) for, or `None` if the table collects
+    /// no column statistics. These are the first `delta.dataSkippingNumIndexedCols` (by default
+    /// 32) leaf columns of the table, or the columns named by `delta.dataSkippingStatsColumns` if
+    /// set. Statistics of other columns are not used for data skipping, so collecting them only
+    /// bloats the log.
+    pub fn stats_columns_schema(&self) -> Option<&SchemaRef> {
+        self.stats_columns_schema.as_ref()
+    }
+
+    /// Returns `true` if the table has the change data feed enabled (`delta.enableChangeDataFeed`),
+    /// in which case transactions that both add and remove files must add change data files (see
+    /// [`Transaction::add_cdc_files`]).
+    pub fn is_change_data_feed_enabled(&self) -> bool {
+        self.change_data_feed_enabled
+    }
+
+    /// The directory to write change data files to.
+    pub fn change_data_dir(&self) -> DeltaResult<Url> {
+        Ok(self.target_dir.join(CHANGE_DATA_DIR)?)
+    }
+
+    /// The physical schema of change data files, i.e. the result schema of
+    /// [`Self::logical_to_change_data`]: the [`Self::physical_schema`] followed by the
+    /// `_change_type` column.
+    pub fn change_data_schema(&self) -> SchemaRef {
+        Arc::new(StructType::new_unchecked(
+            self.physical_schema
+                .fields()
+                .cloned()
+                .chain([StructField::not_null(
+                    CHANGE_TYPE_COL_NAME,
+                    DataType::STRING,
+                )]),
+        ))
+    }
+
+    /// The expression transforming logical change data into physical change data. Logical change
+    /// data conforms to [`Self::schema`], followed by the `_change_type` column.
+    pub fn logical_to_change_data(&self) -> ExpressionRef {
+        self.logical_to_change_data.clone()
+    }
+
+    /// Returns `true` if the table has column invariants (`delta.invariants` column metadata),
+    /// in which case writers must call [`Self::check_invariants`] on all data before writing it.
+    pub fn has_invariants(&self) -> bool {
+        !self.invariants.is_empty()
+    }
+
+    /// Validates that every row of `data` satisfies the table's column invariants. `data` is
+    /// expected to be logical data (i.e. conform to [`Self::schema`]), before the
+    /// [`Self::logical_to_physical`] transform is applied.
+    ///
+    /// Writers must not write (and hence not commit) data for which this check fails: tables with
+    /// writer version 2 and above require that all written data satisfies the invariants. Returns
+    /// an [`Error::InvariantViolation`] describing the first violated invariant, if any.
+    pub fn check_invariants(&self, engine: &dyn Engine, data: &dyn EngineData) -> DeltaResult<()> {
+        check_invariants(engine, &self.schema, &self.invariants, data)
+    }
+
+    /// Returns `true` if the table has generated columns (`delta.generationExpression` column
+    /// metadata), in which case writers must ensure that written values of those columns match
+    /// their generation expressions, e.g. via [`Self::check_generated_columns`].
+    pub fn has_generated_columns(&self) -> bool {
+        !self.generated_columns.is_empty() || !self.unsupported_generated_columns.is_empty()
+    }
+
+    /// The names of the generated columns whose generation expressions the kernel cannot parse,
+    /// and hence cannot validate (or compute). [`Self::check_generated_columns`] and
+    /// [`Self::compute_generated_columns`] fail if there are any, so writers that want to write to
+    /// such tables must validate the values of these columns themselves.
+    pub fn unsupported_generated_columns(&self) -> &[String] {
+        &self.unsupported_generated_columns
+    }
+
+    /// Validates that for every row of `data`, the values of all generated columns match their
+    /// generation expressions (NULL matches NULL). `data` is expected to be logical data (i.e.
+    /// conform to [`Self::schema`]), before the [`Self::logical_to_physical`] transform is applied.
+    ///
+    /// Writers must not write (and hence not commit) data for which this check fails. Returns an
+    /// [`Error::Unsupported`] if the table has [unsupported generated columns].
+    ///
+    /// [unsupported generated columns]: Self::unsupported_generated_columns
+    pub fn check_generated_columns(
+        &self,
+        engine: &dyn Engine,
+        data: &dyn EngineData,
+    ) -> DeltaResult<()> {
+        check_generated_columns(
+            engine,
+            &self.schema,
+            &self.generated_columns,
+            &self.unsupported_generated_columns,
+            data,
+        )
+    }
+
+    /// Computes the values of all generated columns of `data` from their generation expressions,
+    /// using the engine's [`EvaluationHandler`]. Any values the generated columns already had in
+    /// `data` are replaced. `data` is expected to be logical data (i.e. conform to
+    /// [`Self::schema`]), and so is the result. Returns an [`Error::Unsupported`] if the table has
+    /// [unsupported generated columns].
+    ///
+    /// [unsupported generated columns]: Self::unsupported_generated_columns
+    /// [`EvaluationHandler`]: crate::EvaluationHandler
+    pub fn compute_generated_columns(
+        &self,
+        engine: &dyn Engine,
+        data: &dyn EngineData,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        compute_generated_columns(
+            engine,
+            &self.schema,
+            &self.generated_columns,
+            &self.unsupported_generated_columns,
+            data,
+        )
+    }
+
+    /// Returns the default value expression of the top-level column `name`, parsed from its
+    /// `CURRENT_DEFAULT` column metadata, if the table supports the allowColumnDefaults feature and
+    /// the column has a default.
+    pub fn column_default(&self, name: &str) -> Option<ExpressionRef> {
+        self.column_defaults
+            .iter()
+            .find(|column| column.name == name)
+            .map(|column| column.expression.clone())
+    }
+
+    /// Fills in the top-level columns of [`Self::schema`] that are missing from `data` (whose
+    /// schema is `data_schema`) using the engine's [`EvaluationHandler`]. Missing columns take
+    /// their default values (see [`Self::column_default`]), or NULL if they have none. Returns an
+    /// error if a missing column is non-nullable and has no default. The result is logical data
+    /// conforming to [`Self::schema`].
+    ///
+    /// [`EvaluationHandler`]: crate::EvaluationHandler
+    pub fn fill_missing_columns(
+        &self,
+        engine: &dyn Engine,
+        data_schema: &SchemaRef,
+        data: &dyn EngineData,
+    ) -> DeltaResult<Box<dyn EngineData>> {
+        fill_missing_columns(
+            engine,
+            &self.schema,
+            &self.column_defaults,
+            data_schema,
+            data,
+        )
+    }
 }
 
 /// Kernel exposes information about the state of the table that engines might want to use to
@@ -526,8 +1668,31 @@ pub struct PostCommitStats {
     pub commits_since_log_compaction: u64,
 }
 
+/// The maintenance work that should be performed after a commit, as determined by the table
+/// properties (e.g. `delta.checkpointInterval`) and the transaction (e.g.
+/// [`Transaction::with_log_compaction_interval`]).
+///
+/// Kernel doesn't perform this work itself, since writing checkpoints requires the engine to write
+/// Parquet files. Instead, engines can perform it inline after the commit, or schedule it in the
+/// background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PostCommitActions {
+    /// Whether the table should be checkpointed at the committed version, using
+    /// [`Snapshot::checkpoint`] on a snapshot of that version. The checkpoint writer picks the
+    /// checkpoint type (V1 or V2) according to the table's checkpoint policy.
+    ///
+    /// [`Snapshot::checkpoint`]: crate::Snapshot::checkpoint
+    pub checkpoint: bool,
+    /// The (inclusive) range of commit versions whose log should be compacted, using
+    /// [`Snapshot::log_compaction_writer`] on a snapshot of the committed version.
+    ///
+    /// [`Snapshot::log_compaction_writer`]: crate::Snapshot::log_compaction_writer
+    pub log_compaction: Option<(Version, Version)>,
+}
+
 /// Result of committing a transaction.
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum CommitResult {
     /// The transaction was successfully committed.
     Committed {
@@ -535,10 +1700,14 @@ pub enum CommitResult {
         version: Version,
         /// The [`PostCommitStats`] for this transaction
         post_commit_stats: PostCommitStats,
+        /// The [`PostCommitActions`] the engine should perform after this commit
+        post_commit_actions: PostCommitActions,
     },
     /// This transaction conflicted with an existing version (at the version given). The transaction
     /// is returned so the caller can resolve the conflict (along with the version which
-    /// conflicted).
+    /// conflicted). Callers surfacing the conflict as an error should use
+    /// [`Error::CommitConflict`], which is [retryable](Error::is_retryable), unlike the
+    /// [`Error::FileAlreadyExists`] the [`LogStore`](crate::LogStore) reports it with.
     // TODO(zach): in order to make the returning of a transaction useful, we need to add APIs to
     // update the transaction to a new version etc.
     Conflict(Transaction, Version),
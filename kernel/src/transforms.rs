@@ -9,20 +9,34 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 
-use crate::expressions::{Expression, ExpressionRef};
+use crate::expressions::{Expression, ExpressionRef, VariadicExpressionOp};
 use crate::schema::{DataType, SchemaRef};
 use crate::{DeltaResult, Error};
 
 /// Scan uses this to set up what kinds of top-level columns it is scanning. For `Selected` we just
 /// store the name of the column, as that's all that's needed during the actual query. For
 /// `Partition` we store an index into the logical schema for this query since later we need the
-/// data type as well to materialize the partition column.
+/// data type as well to materialize the partition column. Row tracking columns store the names of
+/// the physical columns they are computed from.
 #[derive(PartialEq, Debug)]
 pub(crate) enum ColumnType {
     // A column, selected from the data, as is
     Selected(String),
     // A partition column that needs to be added back in
     Partition(usize),
+    // A row ID column, read from the named materialized row ID column where present, and otherwise
+    // computed from the base row ID of the file and the named row index column
+    RowId {
+        materialized_column: String,
+        row_index_column: String,
+    },
+    // A row commit version column, read from the named materialized row commit version column
+    // where present, and otherwise taken from the default row commit version of the file
+    RowCommitVersion {
+        materialized_column: String,
+    },
+    // A column that is read from the data only to compute other columns, and must be dropped
+    Internal(String),
 }
 
 /// A list of field transforms that describes a transform expression to be created at scan time.
@@ -42,17 +56,12 @@ pub(crate) enum FieldTransformSpec {
         expr: ExpressionRef,
     },
     /// Replace the named input column with an expression
-    // NOTE: Row tracking will eventually need to replace the physical rowid column with a COALESCE
-    // to compute non-materialized row ids and row commit versions.
     #[allow(unused)]
     StaticReplace {
         field_name: String,
         expr: ExpressionRef,
     },
     /// Drops the named input column
-    // NOTE: Row tracking will need to drop metadata columns that were used to compute rowids, since
-    // they should not appear in the query's output.
-    #[allow(unused)]
     StaticDrop { field_name: String },
     /// Inserts a partition column after the named input column. The partition column is identified
     /// by its field index in the logical table schema (the column is not present in the physical
@@ -61,41 +70,56 @@ pub(crate) enum FieldTransformSpec {
         field_index: usize,
         insert_after: Option<String>,
     },
+    /// Replaces the named materialized row ID column with the row IDs of the file, i.e.
+    /// `COALESCE(materialized_row_id, baseRowId + row_index)`. The base row ID varies from file to
+    /// file and is obtained from file metadata.
+    GenerateRowId {
+        field_name: String,
+        row_index_field_name: String,
+    },
+    /// Replaces the named materialized row commit version column with the row commit versions of
+    /// the file, i.e. `COALESCE(materialized_row_commit_version, defaultRowCommitVersion)`. The
+    /// default row commit version varies from file to file and is obtained from file metadata.
+    GenerateRowCommitVersion { field_name: String },
 }
 
-/// Parse a single partition value from the raw string representation
-pub(crate) fn parse_partition_value(
+/// Parse a single partition value from the raw string representation. `partition_values` looks up
+/// the raw value of a partition column by its physical name, e.g. in an add action's
+/// `partitionValues` map, so that the map need not be materialized.
+pub(crate) fn parse_partition_value<'s, 'v>(
     field_idx: usize,
-    logical_schema: &SchemaRef,
-    partition_values: &HashMap<String, String>,
-) -> DeltaResult<(usize, (String, crate::expressions::Scalar))> {
+    logical_schema: &'s SchemaRef,
+    partition_values: impl Fn(&str) -> Option<&'v str>,
+) -> DeltaResult<(usize, (&'s str, crate::expressions::Scalar))> {
     let Some(field) = logical_schema.field_at_index(field_idx) else {
         return Err(Error::InternalError(format!(
             "out of bounds partition column field index {field_idx}"
         )));
     };
     let name = field.physical_name();
-    let partition_value = parse_partition_value_raw(partition_values.get(name), field.data_type())?;
-    Ok((field_idx, (name.to_string(), partition_value)))
+    let partition_value = parse_partition_value_raw(partition_values(name), field.data_type())?;
+    Ok((field_idx, (name, partition_value)))
 }
 
 /// Parse all partition values from a transform spec
-pub(crate) fn parse_partition_values(
-    logical_schema: &SchemaRef,
+pub(crate) fn parse_partition_values<'s, 'v>(
+    logical_schema: &'s SchemaRef,
     transform_spec: &TransformSpec,
-    partition_values: &HashMap<String, String>,
-) -> DeltaResult<HashMap<usize, (String, crate::expressions::Scalar)>> {
+    partition_values: impl Fn(&str) -> Option<&'v str>,
+) -> DeltaResult<HashMap<usize, (&'s str, crate::expressions::Scalar)>> {
     transform_spec
         .iter()
         .filter_map(|field_transform| match field_transform {
             FieldTransformSpec::PartitionColumn { field_index, .. } => Some(parse_partition_value(
                 *field_index,
                 logical_schema,
-                partition_values,
+                &partition_values,
             )),
             FieldTransformSpec::StaticInsert { .. }
             | FieldTransformSpec::StaticReplace { .. }
-            | FieldTransformSpec::StaticDrop { .. } => None,
+            | FieldTransformSpec::StaticDrop { .. }
+            | FieldTransformSpec::GenerateRowId { .. }
+            | FieldTransformSpec::GenerateRowCommitVersion { .. } => None,
         })
         .try_collect()
 }
@@ -105,9 +129,14 @@ pub(crate) fn parse_partition_values(
 /// An empty `transform_spec` is valid and represents the case where only column mapping is needed
 /// (e.g., no partition columns to inject). The resulting empty `Expression::Transform` will
 /// pass all input fields through unchanged while applying the output schema for name mapping.
+///
+/// `base_row_id` and `default_row_commit_version` are the row tracking fields of the Add action,
+/// which are required only if the transform spec generates row IDs or row commit versions.
 pub(crate) fn get_transform_expr(
     transform_spec: &TransformSpec,
-    mut partition_values: HashMap<usize, (String, crate::expressions::Scalar)>,
+    mut partition_values: HashMap<usize, (&str, crate::expressions::Scalar)>,
+    base_row_id: Option<i64>,
+    default_row_commit_version: Option<i64>,
 ) -> DeltaResult<ExpressionRef> {
     let mut transform = crate::expressions::Transform::new_top_level();
 
@@ -134,6 +163,40 @@ pub(crate) fn get_transform_expr(
                 let partition_value = Arc::new(partition_value.into());
                 transform.with_inserted_field(insert_after.clone(), partition_value)
             }
+            GenerateRowId {
+                field_name,
+                row_index_field_name,
+            } => {
+                let Some(base_row_id) = base_row_id else {
+                    return Err(Error::generic(
+                        "Row IDs were requested, but the file has no base row ID",
+                    ));
+                };
+                let row_id = Expression::variadic(
+                    VariadicExpressionOp::Coalesce,
+                    [
+                        Expression::column([field_name]),
+                        Expression::literal(base_row_id)
+                            + Expression::column([row_index_field_name]),
+                    ],
+                );
+                transform.with_replaced_field(field_name.clone(), Arc::new(row_id))
+            }
+            GenerateRowCommitVersion { field_name } => {
+                let Some(default_row_commit_version) = default_row_commit_version else {
+                    return Err(Error::generic(
+                        "Row commit versions were requested, but the file has no default row commit version",
+                    ));
+                };
+                let row_commit_version = Expression::variadic(
+                    VariadicExpressionOp::Coalesce,
+                    [
+                        Expression::column([field_name]),
+                        Expression::literal(default_row_commit_version),
+                    ],
+                );
+                transform.with_replaced_field(field_name.clone(), Arc::new(row_commit_version))
+            }
         }
     }
 
@@ -141,8 +204,8 @@ pub(crate) fn get_transform_expr(
 }
 
 /// Computes the transform spec for this scan. Static (query-level) transforms can already be
-/// turned into expressions now, but file-level transforms like partition values can only be
-/// described now; they are converted to expressions during the scan, using file metadata.
+/// turned into expressions now, but file-level transforms like partition values and row IDs can
+/// only be described now; they are converted to expressions during the scan, using file metadata.
 ///
 /// NOTE: Transforms are "sparse" in the sense that they only mention fields which actually
 /// change (added, replaced, dropped); the transform implicitly captures all fields that pass
@@ -163,6 +226,30 @@ pub(crate) fn get_transform_spec(all_fields: &[ColumnType]) -> TransformSpec {
                     field_index: *logical_idx,
                 });
             }
+            ColumnType::RowId {
+                materialized_column,
+                row_index_column,
+            } => {
+                transform_spec.push(FieldTransformSpec::GenerateRowId {
+                    field_name: materialized_column.clone(),
+                    row_index_field_name: row_index_column.clone(),
+                });
+                last_physical_field = Some(materialized_column);
+            }
+            ColumnType::RowCommitVersion {
+                materialized_column,
+            } => {
+                transform_spec.push(FieldTransformSpec::GenerateRowCommitVersion {
+                    field_name: materialized_column.clone(),
+                });
+                last_physical_field = Some(materialized_column);
+            }
+            ColumnType::Internal(physical_name) => {
+                transform_spec.push(FieldTransformSpec::StaticDrop {
+                    field_name: physical_name.clone(),
+                });
+                last_physical_field = Some(physical_name);
+            }
         }
     }
 
@@ -172,16 +259,13 @@ pub(crate) fn get_transform_spec(all_fields: &[ColumnType]) -> TransformSpec {
 /// Parse a partition value from the raw string representation
 /// This was originally `parse_partition_value` in scan/mod.rs
 pub(crate) fn parse_partition_value_raw(
-    raw: Option<&String>,
+    raw: Option<&str>,
     data_type: &DataType,
 ) -> DeltaResult<crate::expressions::Scalar> {
     use crate::expressions::Scalar;
-    match (raw, data_type.as_primitive_opt()) {
-        (Some(v), Some(primitive)) => primitive.parse_scalar(v),
-        (Some(_), None) => Err(Error::generic(format!(
-            "Unexpected partition column type: {data_type:?}"
-        ))),
-        _ => Ok(Scalar::Null(data_type.clone())),
+    match raw {
+        Some(v) => Scalar::from_partition_value(v, data_type),
+        None => Ok(Scalar::Null(data_type.clone())),
     }
 }
 
@@ -198,9 +282,7 @@ mod tests {
             "col1",
             DataType::STRING,
         )]));
-        let partition_values = HashMap::new();
-
-        let result = parse_partition_value(5, &schema, &partition_values);
+        let result = parse_partition_value(5, &schema, |_| None);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("out of bounds"));
     }
@@ -228,19 +310,20 @@ mod tests {
         partition_values.insert("age".to_string(), "30".to_string());
         partition_values.insert("id".to_string(), "test".to_string());
 
-        let result = parse_partition_values(&schema, &transform_spec, &partition_values).unwrap();
+        let result = parse_partition_values(&schema, &transform_spec, |name| {
+            partition_values.get(name).map(String::as_str)
+        })
+        .unwrap();
         assert_eq!(result.len(), 2);
-        assert!(result.contains_key(&0));
-        assert!(result.contains_key(&1));
+        assert_eq!(result[&0], ("id", Scalar::from("test")));
+        assert_eq!(result[&1], ("age", Scalar::Long(30)));
     }
 
     #[test]
     fn test_parse_partition_values_empty_spec() {
         let schema = Arc::new(StructType::new_unchecked(vec![]));
         let transform_spec = vec![];
-        let partition_values = HashMap::new();
-
-        let result = parse_partition_values(&schema, &transform_spec, &partition_values).unwrap();
+        let result = parse_partition_values(&schema, &transform_spec, |_| None).unwrap();
         assert!(result.is_empty());
     }
 
@@ -252,7 +335,7 @@ mod tests {
         }];
         let partition_values = HashMap::new(); // Missing required partition value
 
-        let result = get_transform_expr(&transform_spec, partition_values);
+        let result = get_transform_expr(&transform_spec, partition_values, None, None);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
@@ -278,10 +361,100 @@ mod tests {
         ];
         let partition_values = HashMap::new();
 
-        let result = get_transform_expr(&transform_spec, partition_values).unwrap();
+        let result = get_transform_expr(&transform_spec, partition_values, None, None).unwrap();
         assert!(matches!(result.as_ref(), Expression::Transform(_)));
     }
 
+    #[test]
+    fn test_get_transform_expr_row_tracking() {
+        let transform_spec = vec![
+            FieldTransformSpec::GenerateRowId {
+                field_name: "row_id_col".to_string(),
+                row_index_field_name: "row_index_col".to_string(),
+            },
+            FieldTransformSpec::GenerateRowCommitVersion {
+                field_name: "row_commit_version_col".to_string(),
+            },
+        ];
+
+        let result =
+            get_transform_expr(&transform_spec, HashMap::new(), Some(10), Some(3)).unwrap();
+        let Expression::Transform(transform) = result.as_ref() else {
+            panic!("Expected Transform expression");
+        };
+        let row_id = &transform.field_transforms["row_id_col"];
+        assert!(row_id.is_replace);
+        assert_eq!(
+            row_id.exprs[0].as_ref(),
+            &Expression::variadic(
+                VariadicExpressionOp::Coalesce,
+                [
+                    Expression::column(["row_id_col"]),
+                    Expression::literal(10i64) + Expression::column(["row_index_col"]),
+                ],
+            )
+        );
+        let row_commit_version = &transform.field_transforms["row_commit_version_col"];
+        assert!(row_commit_version.is_replace);
+        assert_eq!(
+            row_commit_version.exprs[0].as_ref(),
+            &Expression::variadic(
+                VariadicExpressionOp::Coalesce,
+                [
+                    Expression::column(["row_commit_version_col"]),
+                    Expression::literal(3i64),
+                ],
+            )
+        );
+
+        let result = get_transform_expr(&transform_spec, HashMap::new(), None, Some(3));
+        assert!(result.unwrap_err().to_string().contains("no base row ID"));
+        let result = get_transform_expr(&transform_spec, HashMap::new(), Some(10), None);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("no default row commit version"));
+    }
+
+    #[test]
+    fn test_get_transform_spec_row_tracking() {
+        let all_fields = vec![
+            ColumnType::Selected("col1".to_string()),
+            ColumnType::RowId {
+                materialized_column: "row_id_col".to_string(),
+                row_index_column: "row_index_col".to_string(),
+            },
+            ColumnType::Partition(2),
+            ColumnType::RowCommitVersion {
+                materialized_column: "row_commit_version_col".to_string(),
+            },
+            ColumnType::Internal("row_index_col".to_string()),
+        ];
+
+        let result = get_transform_spec(&all_fields);
+        assert_eq!(result.len(), 4);
+        assert!(matches!(
+            &result[0],
+            FieldTransformSpec::GenerateRowId { field_name, row_index_field_name }
+                if field_name == "row_id_col" && row_index_field_name == "row_index_col"
+        ));
+        // partition columns are inserted after the materialized row ID column it replaces
+        assert!(matches!(
+            &result[1],
+            FieldTransformSpec::PartitionColumn { field_index: 2, insert_after: Some(after) }
+                if after == "row_id_col"
+        ));
+        assert!(matches!(
+            &result[2],
+            FieldTransformSpec::GenerateRowCommitVersion { field_name }
+                if field_name == "row_commit_version_col"
+        ));
+        assert!(matches!(
+            &result[3],
+            FieldTransformSpec::StaticDrop { field_name } if field_name == "row_index_col"
+        ));
+    }
+
     #[test]
     fn test_get_transform_spec_selected_only() {
         let all_fields = vec![
@@ -332,18 +505,15 @@ mod tests {
 
     #[test]
     fn test_parse_partition_value_raw_string() {
-        let result =
-            parse_partition_value_raw(Some(&"test_string".to_string()), &DataType::STRING).unwrap();
+        let result = parse_partition_value_raw(Some("test_string"), &DataType::STRING).unwrap();
         assert_eq!(result, Scalar::String("test_string".to_string()));
     }
 
     #[test]
     fn test_parse_partition_value_raw_integer() {
-        let result = parse_partition_value_raw(
-            Some(&"42".to_string()),
-            &DataType::Primitive(PrimitiveType::Integer),
-        )
-        .unwrap();
+        let result =
+            parse_partition_value_raw(Some("42"), &DataType::Primitive(PrimitiveType::Integer))
+                .unwrap();
         assert_eq!(result, Scalar::Integer(42));
     }
 
@@ -356,7 +526,7 @@ mod tests {
     #[test]
     fn test_parse_partition_value_raw_invalid_type() {
         let result = parse_partition_value_raw(
-            Some(&"value".to_string()),
+            Some("value"),
             &DataType::struct_type_unchecked(vec![]), // Non-primitive type
         );
         assert!(result.is_err());
@@ -369,7 +539,7 @@ mod tests {
     #[test]
     fn test_parse_partition_value_raw_invalid_parse() {
         let result = parse_partition_value_raw(
-            Some(&"not_a_number".to_string()),
+            Some("not_a_number"),
             &DataType::Primitive(PrimitiveType::Integer),
         );
         assert!(result.is_err());
@@ -1,7 +1,9 @@
 //! Various utility functions/macros used throughout the kernel
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::ops::Deref;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use url::Url;
@@ -20,8 +22,38 @@ macro_rules! require {
 
 pub(crate) use require;
 
+/// Create a `tracing` span at the given level (e.g. `INFO`) with the given name and fields, which
+/// take the same form as those of [`tracing::span!`]. Without the `tracing-spans` feature, this
+/// returns a disabled span instead, and doesn't evaluate the fields.
+macro_rules! new_span {
+    ( $level:ident, $($args:tt)* ) => {{
+        #[cfg(feature = "tracing-spans")]
+        let span = tracing::span!(tracing::Level::$level, $($args)*);
+        #[cfg(not(feature = "tracing-spans"))]
+        let span = tracing::Span::none();
+        span
+    }};
+}
+
+/// Enter a span created by [`new_span!`] for the rest of the enclosing scope.
+macro_rules! enter_span {
+    ( $($args:tt)* ) => {
+        let _span = $crate::utils::new_span!($($args)*).entered();
+    };
+}
+
+pub(crate) use {enter_span, new_span};
+
+/// Fails to compile unless `T` is `Send + Sync`, for compile-time assertions of the form
+/// `const _: () = assert_send_sync::<T>();`.
+pub(crate) const fn assert_send_sync<T: Send + Sync>() {}
+
 /// Try to parse string uri into a URL for a table path. This will do it's best to handle things
 /// like `/local/paths`, and even `../relative/paths`.
+///
+/// The URL is normalized so that the same table location always yields the same URL: its path ends
+/// with a slash, it is percent-encoded, and aliased schemes are replaced by their canonical scheme
+/// (e.g. `s3a://` by `s3://`).
 #[allow(unused)]
 #[internal_api]
 pub(crate) fn try_parse_uri(uri: impl AsRef<str>) -> DeltaResult<Url> {
@@ -52,11 +84,38 @@ pub(crate) fn try_parse_uri(uri: impl AsRef<str>) -> DeltaResult<Url> {
                 Error::InvalidTableLocation(msg)
             })?
         }
-        UriType::Url(url) => url,
+        UriType::Url(url) => normalize_scheme(url),
     };
     Ok(url)
 }
 
+/// Schemes that name the same objects as another, canonical scheme, e.g. Hadoop's `s3a://` for
+/// `s3://`. Other schemes Hadoop uses, such as `abfss://` and `gs://`, are already canonical.
+const SCHEME_ALIASES: [(&str, &str); 2] = [("s3a", "s3"), ("s3n", "s3")];
+
+/// Replace an aliased scheme of `url` with its canonical scheme, so that the same table is always
+/// identified by the same URL.
+fn normalize_scheme(mut url: Url) -> Url {
+    let canonical = SCHEME_ALIASES
+        .iter()
+        .find_map(|&(alias, canonical)| (url.scheme() == alias).then_some(canonical));
+    if let Some(canonical) = canonical {
+        // both schemes are "non-special" (see the docs of `Url::set_scheme`), so this can't fail
+        let _ = url.set_scheme(canonical);
+    }
+    url
+}
+
+/// Ensure the path of `url` ends with a slash, so that it identifies a directory and paths can be
+/// joined onto it (`Url::join` replaces the last segment of a path without a trailing slash).
+pub(crate) fn ensure_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
+}
+
 #[allow(unused)]
 #[derive(Debug)]
 enum UriType {
@@ -109,6 +168,36 @@ pub(crate) fn current_time_ms() -> DeltaResult<i64> {
         .map_err(|_| Error::generic("Current timestamp exceeds i64 millisecond range"))
 }
 
+/// Interns strings that repeat across the rows of the log (e.g. the paths of files that are added
+/// and later removed), so that each distinct string is allocated once and shared as an `Arc<str>`.
+#[derive(Debug, Default)]
+pub(crate) struct StringInterner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl StringInterner {
+    /// Get the interned copy of `s`, interning it first if needed.
+    pub(crate) fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    /// Get the interned copy of `s`, if it was interned. This never allocates.
+    pub(crate) fn get(&self, s: &str) -> Option<&Arc<str>> {
+        self.strings.get(s)
+    }
+
+    /// The number of distinct strings interned.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.strings.len()
+    }
+}
+
 // Extension trait for Cow<'_, T>
 pub(crate) trait CowExt<T: ToOwned + ?Sized> {
     /// The owned type that corresopnds to Self
@@ -328,6 +417,55 @@ mod tests {
         resolve_uri_type("file://foo/bar").expect_err("file://foo/bar should not have parsed");
     }
 
+    #[test]
+    fn test_try_parse_uri_normalization() {
+        let parse = |uri| try_parse_uri(uri).unwrap().to_string();
+        assert_eq!(parse("s3a://bucket/table"), "s3://bucket/table/");
+        assert_eq!(parse("S3N://bucket/table/"), "s3://bucket/table/");
+        assert_eq!(parse("s3://bucket/table"), "s3://bucket/table/");
+        assert_eq!(
+            parse("abfss://container@account.dfs.core.windows.net/table"),
+            "abfss://container@account.dfs.core.windows.net/table/"
+        );
+        assert_eq!(parse("gs://bucket/table"), "gs://bucket/table/");
+        // spaces are percent-encoded, and existing escapes are kept as they are
+        assert_eq!(parse("s3://bucket/my table"), "s3://bucket/my%20table/");
+        assert_eq!(parse("s3://bucket/my%20table"), "s3://bucket/my%20table/");
+
+        // local paths are percent-encoded, however they are written
+        let dir = tempfile::tempdir().unwrap();
+        let table = dir.path().join("my table");
+        std::fs::create_dir(&table).unwrap();
+        let expected = Url::from_directory_path(std::fs::canonicalize(&table).unwrap()).unwrap();
+        assert!(expected.path().ends_with("/my%20table/"));
+        assert_eq!(try_parse_uri(table.to_str().unwrap()).unwrap(), expected);
+        assert_eq!(try_parse_uri(expected.as_str()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_ensure_trailing_slash() {
+        for (url, expected) in [
+            ("memory:///table", "memory:///table/"),
+            ("memory:///table/", "memory:///table/"),
+            ("s3://bucket", "s3://bucket/"),
+        ] {
+            let url = Url::parse(url).unwrap();
+            assert_eq!(ensure_trailing_slash(url).as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn test_string_interner() {
+        let mut interner = StringInterner::default();
+        assert!(interner.get("a").is_none());
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+        assert!(Arc::ptr_eq(&a, &interner.intern("a")));
+        assert!(Arc::ptr_eq(&b, interner.get("b").unwrap()));
+        assert_eq!(&*a, "a");
+        assert_eq!(interner.len(), 2);
+    }
+
     #[test]
     fn try_from_uri_without_trailing_slash() {
         let location = "s3://foo/__unitystorage/catalogs/cid/tables/tid";
@@ -0,0 +1,347 @@
+//! This module implements VACUUM, i.e. deleting files in the table directory that are no longer
+//! referenced by the table.
+//!
+//! The entry point for this API is [`Snapshot::vacuum`]. A file is eligible for deletion if
+//! 1. it is not referenced by the latest version of the table, i.e. neither by a live `add`
+//!    action nor by a `remove` action whose tombstone hasn't expired yet (nor by the deletion
+//!    vectors of such actions),
+//! 2. it is older than the retention period (`delta.deletedFileRetentionDuration`, 7 days by
+//!    default), so that files being written by in-flight transactions are never deleted, and
+//! 3. it is not hidden, i.e. no component of its path relative to the table root starts with `.` or
+//!    `_` (except for partition directories such as `_col=value`, and the `_change_data` directory
+//!    of change data files). This excludes the Delta log.
+//!
+//! VACUUM is only supported if kernel supports writing to the table, and for tables with the
+//! `vacuumProtocolCheck` feature, also reading from it.
+//!
+//! VACUUM always plans against the latest version of the table, even if it was created from an
+//! older snapshot: files added after the snapshot's version are not referenced by it, and deleting
+//! them would delete live table data.
+//!
+//! Expired tombstones are those whose `deletionTimestamp` is older than the retention period, so
+//! readers of table versions within the retention period can still read all their files.
+//!
+//! ```no_run
+//! # use delta_kernel::{DeltaResult, Engine, Snapshot};
+//! # fn vacuum(engine: &dyn Engine) -> DeltaResult<()> {
+//! let url = delta_kernel::try_parse_uri("./tests/data/table-with-dv-small")?;
+//! let snapshot = Snapshot::builder_for(url).build(engine)?;
+//!
+//! // plan the vacuum (a "dry run") and inspect the files that would be deleted
+//! let plan = snapshot.vacuum().plan(engine)?;
+//! println!("Deleting {} files ({} bytes)", plan.files().len(), plan.size_in_bytes());
+//!
+//! // delete the files
+//! plan.execute(engine)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Note that the files are listed with [`StorageHandler::list_from`] on the table root, which must
+//! return all files under the table root (including those in partition directories), as
+//! object-store based implementations do. Listings of a single directory level, such as that of
+//! the [sync engine], return subdirectories instead of the files in them: planning a VACUUM fails
+//! if the listing returns a directory holding files of the table (or change data files).
+//!
+//! [sync engine]: crate::engine::sync
+//!
+//! [`Snapshot::vacuum`]: crate::Snapshot::vacuum
+//! [`StorageHandler::list_from`]: crate::StorageHandler::list_from
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use url::Url;
+
+use crate::action_reconciliation::deleted_file_retention_timestamp_with_time;
+use crate::action_reconciliation::log_replay::ActionReconciliationProcessor;
+use crate::actions::visitors::visit_deletion_vector_at;
+use crate::checkpoint::CHECKPOINT_ACTIONS_SCHEMA;
+use crate::engine_data::{GetData, RowVisitor, SelectionVector, TypedGetData as _};
+use crate::log_replay::LogReplayProcessor as _;
+use crate::schema::{ColumnName, ColumnNamesAndTypes, DataType};
+use crate::snapshot::SnapshotRef;
+use crate::utils::require;
+use crate::{DeltaResult, Engine, Error, FileMeta, Snapshot};
+
+/// Plans the deletion of files that are no longer referenced by a table. Created with
+/// [`Snapshot::vacuum`].
+///
+/// [`Snapshot::vacuum`]: crate::Snapshot::vacuum
+#[derive(Debug)]
+pub struct Vacuum {
+    snapshot: SnapshotRef,
+    retention_duration: Option<Duration>,
+}
+
+impl Vacuum {
+    pub(crate) fn new(snapshot: SnapshotRef) -> Self {
+        Self {
+            snapshot,
+            retention_duration: None,
+        }
+    }
+
+    /// Override the retention period of the table (`delta.deletedFileRetentionDuration`).
+    ///
+    /// Using a retention period shorter than the longest running transaction or the oldest table
+    /// version still being read can delete files that are still needed, which corrupts those
+    /// transactions and reads.
+    pub fn with_retention_duration(mut self, retention_duration: Duration) -> Self {
+        self.retention_duration = Some(retention_duration);
+        self
+    }
+
+    /// Compute the files to delete, without deleting them. Note that this lists all files in the
+    /// table directory and replays the whole log.
+    ///
+    /// The files to delete are computed for the latest version of the table, which is loaded
+    /// starting from the snapshot this [`Vacuum`] was created with.
+    pub fn plan(&self, engine: &dyn Engine) -> DeltaResult<VacuumPlan> {
+        // Files added after an older snapshot's version look unreferenced to it
+        let snapshot = Snapshot::builder_from(self.snapshot.clone()).build(engine)?;
+        snapshot.table_configuration().ensure_vacuum_supported()?;
+        let retention_duration = self
+            .retention_duration
+            .or(snapshot.table_properties().deleted_file_retention_duration);
+        let retention_timestamp = deleted_file_retention_timestamp_with_time(
+            retention_duration,
+            crate::utils::current_time_duration()?,
+        )?;
+        let table_root = snapshot.table_root();
+
+        // Collect all files referenced by live add actions and unexpired tombstones
+        let actions = snapshot.log_segment().read_actions(
+            engine,
+            CHECKPOINT_ACTIONS_SCHEMA.clone(),
+            CHECKPOINT_ACTIONS_SCHEMA.clone(),
+            None,
+        )?;
+        let mut visitor = ReferencedFilesVisitor {
+            table_root,
+            selection_vector: Default::default(),
+            referenced_files: HashSet::new(),
+        };
+        for batch in ActionReconciliationProcessor::new(retention_timestamp, None)
+            .process_actions_iter(actions)
+        {
+            let filtered_data = batch?.filtered_data;
+            visitor.selection_vector = filtered_data.selection_vector;
+            visitor.visit_rows_of(filtered_data.data.as_ref())?;
+        }
+        let referenced_files = visitor.referenced_files;
+        let directories = table_directories(table_root, &referenced_files);
+
+        let mut files = vec![];
+        for file in engine.storage_handler().list_from(table_root)? {
+            let file = file?;
+            let Some(relative_path) = file.location.as_str().strip_prefix(table_root.as_str())
+            else {
+                continue;
+            };
+            require!(
+                !directories.contains(relative_path),
+                Error::unsupported(format!(
+                    "VACUUM requires listing all files under the table root, but the storage handler listed the directory {}",
+                    file.location
+                ))
+            );
+            if !relative_path.is_empty()
+                && !is_hidden(relative_path)
+                && file.last_modified < retention_timestamp
+                && !referenced_files.contains(file.location.as_str())
+            {
+                files.push(file);
+            }
+        }
+        Ok(VacuumPlan { files })
+    }
+}
+
+/// The files to be deleted by a VACUUM, as computed by [`Vacuum::plan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VacuumPlan {
+    files: Vec<FileMeta>,
+}
+
+impl VacuumPlan {
+    /// The files that will be deleted.
+    pub fn files(&self) -> &[FileMeta] {
+        &self.files
+    }
+
+    /// The total size of the files that will be deleted, in bytes.
+    pub fn size_in_bytes(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// Delete the files of this plan using the engine's [`StorageHandler`].
+    ///
+    /// [`StorageHandler`]: crate::StorageHandler
+    pub fn execute(self, engine: &dyn Engine) -> DeltaResult<()> {
+        if self.files.is_empty() {
+            return Ok(());
+        }
+        let files = self.files.into_iter().map(|file| file.location).collect();
+        engine.storage_handler().delete(files)
+    }
+}
+
+/// The directory of change data files, relative to the table root.
+const CHANGE_DATA_DIR: &str = "_change_data";
+
+/// Returns `true` if any component of the (table root relative) `path` is hidden, i.e. starts with
+/// `.` or `_`, which excludes e.g. the `_delta_log` directory. Partition directories of partition
+/// columns whose name starts with `_` (e.g. `_col=value`) are not hidden, and neither is the
+/// `_change_data` directory, whose change data files are vacuumed like data files.
+fn is_hidden(path: &str) -> bool {
+    let mut components = path.split('/').peekable();
+    while let Some(component) = components.next() {
+        let is_directory = components.peek().is_some();
+        let is_visible_directory =
+            is_directory && (component.contains('=') || component == CHANGE_DATA_DIR);
+        if component.starts_with('.') || (component.starts_with('_') && !is_visible_directory) {
+            return true;
+        }
+    }
+    false
+}
+
+/// The (table root relative) directories known to hold files of the table: the directories of the
+/// `referenced_files`, and the change data directory. A listing of all files under the table root
+/// never returns these.
+fn table_directories<'a>(
+    table_root: &Url,
+    referenced_files: &'a HashSet<String>,
+) -> HashSet<&'a str> {
+    let mut directories = HashSet::from([CHANGE_DATA_DIR]);
+    for file in referenced_files {
+        let Some(mut path) = file.strip_prefix(table_root.as_str()) else {
+            continue;
+        };
+        while let Some((directory, _)) = path.rsplit_once('/') {
+            if !directories.insert(directory) {
+                break;
+            }
+            path = directory;
+        }
+    }
+    directories
+}
+
+/// Collects the absolute paths of all data files and deletion vector files referenced by the
+/// selected add and remove actions.
+struct ReferencedFilesVisitor<'a> {
+    table_root: &'a Url,
+    selection_vector: SelectionVector,
+    referenced_files: HashSet<String>,
+}
+
+impl ReferencedFilesVisitor<'_> {
+    fn add_file(&mut self, path: &str) -> DeltaResult<()> {
+        let url = self
+            .table_root
+            .join(path)
+            .map_err(|_| Error::generic(format!("Invalid file path in the log: {path}")))?;
+        self.referenced_files.insert(url.to_string());
+        Ok(())
+    }
+}
+
+impl RowVisitor for ReferencedFilesVisitor<'_> {
+    fn selected_column_names_and_types(&self) -> (&'static [ColumnName], &'static [DataType]) {
+        static NAMES_AND_TYPES: LazyLock<ColumnNamesAndTypes> = LazyLock::new(|| {
+            let file_action_columns = |action: &str| {
+                [
+                    ColumnName::new([action, "path"]),
+                    ColumnName::new([action, "deletionVector", "storageType"]),
+                    ColumnName::new([action, "deletionVector", "pathOrInlineDv"]),
+                    ColumnName::new([action, "deletionVector", "offset"]),
+                    ColumnName::new([action, "deletionVector", "sizeInBytes"]),
+                    ColumnName::new([action, "deletionVector", "cardinality"]),
+                ]
+            };
+            let file_action_types = [
+                DataType::STRING,
+                DataType::STRING,
+                DataType::STRING,
+                DataType::INTEGER,
+                DataType::INTEGER,
+                DataType::LONG,
+            ];
+            (
+                [file_action_columns("add"), file_action_columns("remove")].concat(),
+                [file_action_types.clone(), file_action_types].concat(),
+            )
+                .into()
+        });
+        NAMES_AND_TYPES.as_ref()
+    }
+
+    fn visit<'a>(&mut self, row_count: usize, getters: &[&'a dyn GetData<'a>]) -> DeltaResult<()> {
+        require!(
+            getters.len() == 12,
+            Error::InternalError(format!(
+                "Wrong number of ReferencedFilesVisitor getters: {}",
+                getters.len()
+            ))
+        );
+        for i in 0..row_count {
+            if !self.selection_vector.get(i).unwrap_or(true) {
+                continue;
+            }
+            // an action is either an add (getters 0..6) or a remove (getters 6..12)
+            for getters in getters.chunks(6) {
+                let Some(path): Option<String> = getters[0].get_opt(i, "path")? else {
+                    continue;
+                };
+                self.add_file(&path)?;
+                if let Some(dv) = visit_deletion_vector_at(i, &getters[1..])? {
+                    if let Some(dv_path) = dv.absolute_path(self.table_root)? {
+                        self.referenced_files.insert(dv_path.to_string());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_hidden() {
+        assert!(!is_hidden("part-00000.parquet"));
+        assert!(!is_hidden("deletion_vector_1234.bin"));
+        assert!(!is_hidden("a=1/part-00000.parquet"));
+        assert!(!is_hidden("_a=1/part-00000.parquet"));
+        assert!(is_hidden("_delta_log/00000000000000000000.json"));
+        assert!(is_hidden("_delta_log"));
+        assert!(is_hidden(".part-00000.parquet.crc"));
+        assert!(is_hidden("a=1/.part-00000.parquet.crc"));
+        assert!(is_hidden("_tmp/part-00000.parquet"));
+        assert!(is_hidden("a=1/_committed_123"));
+        assert!(!is_hidden("_change_data/cdc-00000.parquet"));
+        assert!(!is_hidden("_change_data/a=1/cdc-00000.parquet"));
+        assert!(is_hidden("_change_data"));
+        assert!(is_hidden("a=1/_change_data_file"));
+    }
+
+    #[test]
+    fn test_table_directories() {
+        let table_root = Url::parse("memory:///table/").unwrap();
+        let referenced_files = HashSet::from([
+            "memory:///table/part-00000.parquet".to_string(),
+            "memory:///table/a=1/b=2/part-00000.parquet".to_string(),
+            "memory:///table/a=1/b=3/part-00000.parquet".to_string(),
+            "memory:///table/ab/deletion_vector_1234.bin".to_string(),
+        ]);
+        let directories = table_directories(&table_root, &referenced_files);
+        assert_eq!(
+            directories,
+            HashSet::from(["_change_data", "a=1", "a=1/b=2", "a=1/b=3", "ab"])
+        );
+    }
+}
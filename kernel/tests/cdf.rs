@@ -1,19 +1,28 @@
+use std::collections::HashMap;
 use std::error;
+use std::sync::Arc;
+use std::time::Duration;
 
-use delta_kernel::arrow::array::RecordBatch;
+use delta_kernel::arrow::array::{Int32Array, RecordBatch, StringArray};
 use delta_kernel::arrow::compute::filter_record_batch;
-use delta_kernel::arrow::datatypes::Schema as ArrowSchema;
+use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
 use itertools::Itertools;
 
-use delta_kernel::engine::arrow_conversion::TryFromKernel as _;
+use delta_kernel::engine::arrow_conversion::{TryFromKernel as _, TryIntoArrow as _};
+use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::schema::{DataType, StructField, StructType};
 use delta_kernel::table_changes::TableChanges;
-use delta_kernel::{DeltaResult, Error, PredicateRef, Version};
+use delta_kernel::{DeltaResult, Engine, Error, PredicateRef, Snapshot, Version};
 
 mod common;
 
+use object_store::path::Path;
 use test_utils::DefaultEngineExtension;
-use test_utils::{load_test_data, to_arrow};
+use test_utils::{
+    added_files_to_remove, create_table, engine_store_setup, load_test_data, to_arrow,
+};
+use url::Url;
 
 fn read_cdf_for_table(
     test_name: impl AsRef<str>,
@@ -25,8 +34,18 @@ fn read_cdf_for_table(
     let test_path = test_dir.path().join(test_name.as_ref());
     let test_path = delta_kernel::try_parse_uri(test_path.to_str().expect("table path to string"))?;
     let engine = DefaultEngine::new_local();
+    read_cdf(test_path, engine, start_version, end_version, predicate)
+}
+
+fn read_cdf(
+    table_root: Url,
+    engine: Arc<dyn Engine>,
+    start_version: Version,
+    end_version: impl Into<Option<Version>>,
+    predicate: impl Into<Option<PredicateRef>>,
+) -> DeltaResult<Vec<RecordBatch>> {
     let table_changes = TableChanges::try_new(
-        test_path,
+        table_root,
         engine.as_ref(),
         start_version,
         end_version.into(),
@@ -553,3 +572,205 @@ fn conditional_delete_two_rows() -> DeltaResult<()> {
     assert_batches_sorted_eq!(expected, &batches);
     Ok(())
 }
+
+#[tokio::test]
+async fn cdf_of_kernel_writes() -> Result<(), Box<dyn error::Error>> {
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "value",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "cdf_of_kernel_writes";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["changeDataFeed"],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+    let arrow_schema: Arc<ArrowSchema> = Arc::new(schema.as_ref().try_into_arrow()?);
+
+    // 1. Insert 1..=3
+    // 2. Insert 4..=6
+    // 3. Delete the file of 1..=3
+    // 4. Update 6 to 60, with change data files
+    for values in [vec![1, 2, 3], vec![4, 5, 6]] {
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![Arc::new(Int32Array::from(values))],
+        )?;
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+        let mut txn = snapshot.transaction()?;
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(batch),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+        txn.commit(engine.as_ref())?;
+    }
+
+    let removed = added_files_to_remove(store.as_ref(), table_name, 1).await?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let mut txn = snapshot.transaction()?;
+    txn.remove_files(Box::new(ArrowEngineData::new(removed)));
+    txn.commit(engine.as_ref())?;
+
+    let removed = added_files_to_remove(store.as_ref(), table_name, 2).await?;
+    let updated = RecordBatch::try_new(
+        arrow_schema.clone(),
+        vec![Arc::new(Int32Array::from(vec![4, 5, 60]))],
+    )?;
+    let change_data = RecordBatch::try_new(
+        Arc::new(ArrowSchema::new(vec![
+            Field::new("value", ArrowDataType::Int32, true),
+            Field::new("_change_type", ArrowDataType::Utf8, false),
+        ])),
+        vec![
+            Arc::new(Int32Array::from(vec![6, 60])),
+            Arc::new(StringArray::from(vec![
+                "update_preimage",
+                "update_postimage",
+            ])),
+        ],
+    )?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let mut txn = snapshot.transaction()?.with_operation("UPDATE".to_string());
+    let write_context = txn.get_write_context();
+    let added = engine
+        .write_parquet(
+            &ArrowEngineData::new(updated),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    let cdc_files_metadata = engine
+        .write_change_data(
+            &ArrowEngineData::new(change_data),
+            &write_context,
+            HashMap::new(),
+        )
+        .await?;
+    txn.remove_files(Box::new(ArrowEngineData::new(removed)));
+    txn.add_files(added);
+    txn.add_cdc_files(cdc_files_metadata);
+    txn.commit(engine.as_ref())?;
+
+    // the changes of commits without change data files are derived from their add and remove
+    // actions, the changes of commit 4 are read from its change data files only
+    let batches = read_cdf(table_url, engine, 1, None, None)?;
+    let mut expected = vec![
+        "+-------+------------------+-----------------+",
+        "| value | _change_type     | _commit_version |",
+        "+-------+------------------+-----------------+",
+        "| 1     | insert           | 1               |",
+        "| 2     | insert           | 1               |",
+        "| 3     | insert           | 1               |",
+        "| 4     | insert           | 2               |",
+        "| 5     | insert           | 2               |",
+        "| 6     | insert           | 2               |",
+        "| 1     | delete           | 3               |",
+        "| 2     | delete           | 3               |",
+        "| 3     | delete           | 3               |",
+        "| 6     | update_preimage  | 4               |",
+        "| 60    | update_postimage | 4               |",
+        "+-------+------------------+-----------------+",
+    ];
+    sort_lines!(expected);
+    assert_batches_sorted_eq!(expected, &batches);
+    Ok(())
+}
+
+#[tokio::test]
+async fn cdf_with_timestamp_bounds() -> Result<(), Box<dyn error::Error>> {
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "value",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "cdf_with_timestamp_bounds";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["changeDataFeed"],
+    )
+    .await?;
+    let arrow_schema: Arc<ArrowSchema> = Arc::new(schema.as_ref().try_into_arrow()?);
+
+    // insert a row in each of versions 1 and 2, with some time between the commits so that their
+    // timestamps (the modification times of the commit files) differ
+    let mut commit_timestamps = vec![];
+    for version in 0..=2 {
+        if version > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let batch = RecordBatch::try_new(
+                arrow_schema.clone(),
+                vec![Arc::new(Int32Array::from(vec![version]))],
+            )?;
+            let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+            let mut txn = snapshot.transaction()?;
+            let add_files_metadata = engine
+                .write_parquet(
+                    &ArrowEngineData::new(batch),
+                    &txn.get_write_context(),
+                    HashMap::new(),
+                    true,
+                )
+                .await?;
+            txn.add_files(add_files_metadata);
+            txn.commit(&engine)?;
+        }
+        let commit = store
+            .head(&Path::from(format!(
+                "/{table_name}/_delta_log/{version:020}.json"
+            )))
+            .await?;
+        commit_timestamps.push(commit.last_modified.timestamp_millis());
+    }
+    let [_, t1, t2] = commit_timestamps[..] else {
+        unreachable!()
+    };
+
+    let versions = |start_timestamp, end_timestamp| {
+        TableChanges::try_new_with_timestamps(
+            table_url.clone(),
+            &engine,
+            start_timestamp,
+            end_timestamp,
+        )
+        .map(|table_changes| (table_changes.start_version(), table_changes.end_version()))
+    };
+    // the start timestamp resolves to the first version at or after it, and the end timestamp to
+    // the last version at or before it
+    assert_eq!(versions(t1, None)?, (1, 2));
+    assert_eq!(versions(t1 - 1, Some(t1))?, (1, 1));
+    assert_eq!(versions(t1 + 1, Some(t2 + 1))?, (2, 2));
+    // no version was committed between the timestamps
+    assert!(versions(t1 + 1, Some(t2 - 1)).is_err());
+    // the start timestamp is after the latest commit
+    assert!(versions(t2 + 1, None).is_err());
+    // the start timestamp is after the end timestamp
+    assert!(versions(t2, Some(t1)).is_err());
+
+    // following the table from version 1 picks up version 2, and then nothing until a new
+    // version is committed
+    let table_changes = TableChanges::try_new(table_url.clone(), &engine, 0, Some(1))?;
+    let next = table_changes
+        .try_next(&engine)?
+        .expect("version 2 was committed");
+    assert_eq!((next.start_version(), next.end_version()), (2, 2));
+    assert!(next.try_next(&engine)?.is_none());
+    Ok(())
+}
@@ -11,6 +11,8 @@ use test_utils::DefaultEngineExtension;
 use itertools::Itertools;
 use test_log::test;
 
+mod common;
+
 fn count_total_scan_rows(
     scan_result_iter: impl Iterator<Item = DeltaResult<ScanResult>>,
 ) -> DeltaResult<usize> {
@@ -55,3 +57,31 @@ fn non_dv_table() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(total_rows, 10);
     Ok(())
 }
+
+#[test]
+fn dv_table_scan_arrow() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::fs::canonicalize(PathBuf::from("./tests/data/table-with-dv-small/"))?;
+    let url = url::Url::from_directory_path(path).unwrap();
+    let engine = DefaultEngine::new_local();
+
+    let snapshot = Snapshot::builder_for(url).build(engine.as_ref())?;
+    let batches: Vec<_> = snapshot
+        .scan_arrow(engine, Some(&["value"]), None)?
+        .try_collect()?;
+    let expected = vec![
+        "+-------+",
+        "| value |",
+        "+-------+",
+        "| 1     |",
+        "| 2     |",
+        "| 3     |",
+        "| 4     |",
+        "| 5     |",
+        "| 6     |",
+        "| 7     |",
+        "| 8     |",
+        "+-------+",
+    ];
+    assert_batches_sorted_eq!(expected, &batches);
+    Ok(())
+}
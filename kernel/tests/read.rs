@@ -16,7 +16,7 @@ use delta_kernel::parquet::file::properties::{EnabledStatistics, WriterPropertie
 use delta_kernel::scan::state::{transform_to_logical, DvInfo, Stats};
 use delta_kernel::scan::Scan;
 use delta_kernel::schema::{DataType, MetadataColumnSpec, Schema, StructField, StructType};
-use delta_kernel::{Engine, FileMeta, Snapshot};
+use delta_kernel::{Engine, Error, FileMeta, Snapshot};
 
 use itertools::Itertools;
 use object_store::{memory::InMemory, path::Path, ObjectStore};
@@ -1487,46 +1487,22 @@ async fn test_unsupported_metadata_columns() -> Result<(), Box<dyn std::error::E
         Arc::new(TokioBackgroundExecutor::new()),
     ));
 
-    // Test that unsupported metadata columns fail with appropriate errors
+    // Row tracking metadata columns can't be read from a table without row tracking
     let test_cases = [
-        ("row_id", MetadataColumnSpec::RowId, "RowId"),
-        (
-            "row_commit_version",
-            MetadataColumnSpec::RowCommitVersion,
-            "RowCommitVersion",
-        ),
+        ("row_id", MetadataColumnSpec::RowId),
+        ("row_commit_version", MetadataColumnSpec::RowCommitVersion),
     ];
-    for (column_name, metadata_spec, error_text) in test_cases {
+    for (column_name, metadata_spec) in test_cases {
         let snapshot = Snapshot::builder_for(location.clone()).build(engine.as_ref())?;
         let schema = Arc::new(StructType::try_new([
             StructField::nullable("id", DataType::INTEGER),
             StructField::create_metadata_column(column_name, metadata_spec),
         ])?);
-        let scan = snapshot.scan_builder().with_schema(schema).build()?;
-        let stream = scan.execute(engine.clone())?;
-
-        let mut found_error = false;
-        for scan_result in stream {
-            match scan_result {
-                Err(e) => {
-                    let error_msg = e.to_string();
-                    if error_msg.contains(error_text) && error_msg.contains("not supported") {
-                        found_error = true;
-                        break;
-                    }
-                }
-                Ok(_) => {
-                    panic!(
-                        "Expected error for {} metadata column, but scan succeeded",
-                        error_text
-                    );
-                }
-            }
-        }
+        let result = snapshot.scan_builder().with_schema(schema).build();
         assert!(
-            found_error,
-            "Expected error about {} not being supported",
-            error_text
+            matches!(result.as_ref().map_err(Error::inner), Err(Error::Unsupported(msg)) if msg.contains("row tracking")),
+            "Expected an unsupported error for {column_name}, got {:?}",
+            result.err()
         );
     }
 
@@ -14,7 +14,7 @@ use delta_kernel::engine::arrow_conversion::TryIntoArrow;
 use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::DefaultEngine;
-use delta_kernel::schema::{DataType, SchemaRef, StructField, StructType};
+use delta_kernel::schema::{DataType, MetadataColumnSpec, SchemaRef, StructField, StructType};
 use delta_kernel::transaction::CommitResult;
 use delta_kernel::{DeltaResult, Error, Snapshot};
 
@@ -50,6 +50,28 @@ async fn create_row_tracking_table(
     Ok((table_url, Arc::new(engine), store))
 }
 
+/// Helper function to set the `delta.enableRowTracking` table property of a table that was just
+/// created by [`create_row_tracking_table`].
+async fn enable_row_tracking(store: &Arc<dyn ObjectStore>, table_url: &Url) -> DeltaResult<()> {
+    let commit_url = table_url.join("_delta_log/00000000000000000000.json")?;
+    let commit_path = Path::from_url_path(commit_url.path())?;
+    let commit = store.get(&commit_path).await?.bytes().await?;
+    let actions: Vec<Value> = Deserializer::from_slice(&commit)
+        .into_iter::<Value>()
+        .try_collect()?;
+    let actions = actions
+        .into_iter()
+        .map(|mut action| {
+            if let Some(configuration) = action.pointer_mut("/metaData/configuration") {
+                configuration["delta.enableRowTracking"] = Value::from("true");
+            }
+            action.to_string()
+        })
+        .join("\n");
+    store.put(&commit_path, actions.into()).await?;
+    Ok(())
+}
+
 /// Helper function to write data and return the number of records written.
 async fn write_data_to_table(
     table_url: &Url,
@@ -797,3 +819,98 @@ async fn test_no_row_tracking_fields_without_feature() -> DeltaResult<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_read_row_ids_and_row_commit_versions() -> DeltaResult<()> {
+    // Setup
+    let _ = tracing_subscriber::fmt::try_init();
+    let tmp_test_dir = tempdir()?;
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    let (table_url, engine, store) =
+        create_row_tracking_table(&tmp_test_dir, "test_read_row_ids", schema.clone()).await?;
+    enable_row_tracking(&store, &table_url).await?;
+
+    // Commit 1 has base row IDs 0 and 3, commit 2 has base row ID 6
+    let data_1 = generate_data(
+        schema.clone(),
+        [
+            vec![int32_array(vec![1, 2, 3])],
+            vec![int32_array(vec![4, 5, 6])],
+        ],
+    )?;
+    write_data_to_table(&table_url, engine.clone(), data_1).await?;
+    let data_2 = generate_data(schema.clone(), [vec![int32_array(vec![7, 8])]])?;
+    write_data_to_table(&table_url, engine.clone(), data_2).await?;
+
+    // None of the files materialize row IDs or row commit versions, so they are computed from the
+    // base row IDs and default row commit versions of the files
+    let read_schema = Arc::new(
+        schema
+            .add_metadata_column("row_id", MetadataColumnSpec::RowId)?
+            .add_metadata_column("row_commit_version", MetadataColumnSpec::RowCommitVersion)?,
+    );
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let scan = snapshot.scan_builder().with_schema(read_schema).build()?;
+    let batches = read_scan(&scan, engine)?;
+
+    let mut rows = vec![];
+    for batch in batches {
+        let column = |i: usize| batch.column(i).as_any();
+        let numbers = column(0).downcast_ref::<Int32Array>().unwrap();
+        let row_ids = column(1).downcast_ref::<Int64Array>().unwrap();
+        let row_commit_versions = column(2).downcast_ref::<Int64Array>().unwrap();
+        for i in 0..batch.num_rows() {
+            rows.push((
+                numbers.value(i),
+                row_ids.value(i),
+                row_commit_versions.value(i),
+            ));
+        }
+    }
+    rows.sort();
+    assert_eq!(
+        rows,
+        vec![
+            (1, 0, 1),
+            (2, 1, 1),
+            (3, 2, 1),
+            (4, 3, 1),
+            (5, 4, 1),
+            (6, 5, 1),
+            (7, 6, 2),
+            (8, 7, 2),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_row_ids_requires_row_tracking_enabled() -> DeltaResult<()> {
+    // Setup
+    let _ = tracing_subscriber::fmt::try_init();
+    let tmp_test_dir = tempdir()?;
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    // Row tracking is supported, but not enabled
+    let (table_url, engine, _store) =
+        create_row_tracking_table(&tmp_test_dir, "test_read_row_ids_disabled", schema.clone())
+            .await?;
+
+    let read_schema = Arc::new(schema.add_metadata_column("row_id", MetadataColumnSpec::RowId)?);
+    let snapshot = Snapshot::builder_for(table_url).build(engine.as_ref())?;
+    let result = snapshot.scan_builder().with_schema(read_schema).build();
+    assert!(matches!(
+        result.as_ref().map_err(Error::inner),
+        Err(Error::Unsupported(_))
+    ));
+
+    Ok(())
+}
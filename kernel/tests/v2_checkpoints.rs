@@ -1,12 +1,18 @@
+use std::collections::HashMap;
+
 use delta_kernel::arrow::array::RecordBatch;
+use delta_kernel::async_engine::SyncAdapter;
 use delta_kernel::engine::default::DefaultEngine;
+use delta_kernel::scan::state::{DvInfo, Stats};
+use delta_kernel::scan::ScanMetadata;
 
-use delta_kernel::{DeltaResult, Snapshot};
+use delta_kernel::{DeltaResult, ExpressionRef, Snapshot};
 
 mod common;
 
 use test_utils::{load_test_data, DefaultEngineExtension};
 
+use futures::StreamExt as _;
 use itertools::Itertools;
 use test_utils::read_scan;
 
@@ -223,3 +229,54 @@ fn v2_checkpoints_parquet_with_last_checkpoint() -> DeltaResult<()> {
         get_simple_id_table(),
     )
 }
+
+/// The sorted paths of the files selected by `scan_metadata`.
+fn scan_file_paths(
+    scan_metadata: impl IntoIterator<Item = DeltaResult<ScanMetadata>>,
+) -> DeltaResult<Vec<String>> {
+    fn callback(
+        paths: &mut Vec<String>,
+        path: &str,
+        _: i64,
+        _: Option<Stats>,
+        _: DvInfo,
+        _: Option<ExpressionRef>,
+        _: HashMap<String, String>,
+    ) {
+        paths.push(path.to_string());
+    }
+    let mut paths = vec![];
+    for scan_metadata in scan_metadata {
+        paths = scan_metadata?.visit_scan_files(paths, callback)?;
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[tokio::test]
+async fn v2_checkpoints_scan_metadata_async() -> DeltaResult<()> {
+    for table_name in [
+        "v2-checkpoints-json-with-sidecars",
+        "v2-checkpoints-parquet-with-sidecars",
+        "v2-classic-checkpoint-parquet",
+    ] {
+        let test_dir = load_test_data("tests/data", table_name).unwrap();
+        let test_path = test_dir.path().join(table_name);
+        let url = delta_kernel::try_parse_uri(test_path.to_str().expect("table path to string"))?;
+        let engine = DefaultEngine::new_local();
+        let snapshot = Snapshot::builder_for(url).build(engine.as_ref())?;
+        let scan = snapshot.scan_builder().build()?;
+        let expected = scan_file_paths(scan.scan_metadata(engine.as_ref())?)?;
+        assert!(!expected.is_empty());
+
+        // the default engine reads the log files without blocking
+        let scan_metadata: Vec<_> = scan.scan_metadata_async(engine.as_ref())?.collect().await;
+        assert_eq!(scan_file_paths(scan_metadata)?, expected);
+
+        // a sync engine reads them through its iterators
+        let adapter = SyncAdapter::new(engine.clone());
+        let scan_metadata: Vec<_> = scan.scan_metadata_async(&adapter)?.collect().await;
+        assert_eq!(scan_file_paths(scan_metadata)?, expected);
+    }
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use delta_kernel::engine::sync::SyncEngine;
+use delta_kernel::schema::{DataType, StructField, StructType};
+use delta_kernel::Snapshot;
+use test_utils::{create_table, engine_store_setup};
+
+use futures::TryStreamExt;
+use itertools::Itertools;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use serde_json::json;
+
+#[tokio::test]
+async fn vacuum_deletes_unreferenced_files() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "id",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_vacuum_table";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema,
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+
+    // commit 1 adds `live.parquet` and removes `removed-recent.parquet` (whose tombstone is not
+    // expired yet) and `removed-old.parquet` (whose tombstone is expired)
+    let far_future = 4_102_444_800_000i64; // 2100-01-01
+    let actions = [
+        json!({"add": {"path": "live.parquet", "partitionValues": {}, "size": 4, "modificationTime": 0, "dataChange": true}}),
+        json!({"remove": {"path": "removed-recent.parquet", "deletionTimestamp": far_future, "dataChange": true}}),
+        json!({"remove": {"path": "removed-old.parquet", "deletionTimestamp": 0, "dataChange": true}}),
+    ];
+    let commit = actions.iter().map(|action| action.to_string()).join("\n");
+    store
+        .put(
+            &Path::from(format!("{table_name}/_delta_log/00000000000000000001.json")),
+            commit.into(),
+        )
+        .await?;
+    let files = [
+        "live.parquet",
+        "removed-recent.parquet",
+        "removed-old.parquet",
+        "unreferenced.parquet",
+        "_hidden/file.parquet",
+        ".unreferenced.parquet.crc",
+    ];
+    for file in files {
+        store
+            .put(&Path::from(format!("{table_name}/{file}")), "data".into())
+            .await?;
+    }
+    // make sure all files are strictly older than the retention cutoff
+    std::thread::sleep(Duration::from_millis(10));
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+
+    // with the default retention period of 7 days, no file is old enough to be deleted
+    let plan = snapshot.clone().vacuum().plan(&engine)?;
+    assert!(plan.files().is_empty());
+
+    let plan = snapshot
+        .clone()
+        .vacuum()
+        .with_retention_duration(Duration::ZERO)
+        .plan(&engine)?;
+    let planned_files = plan
+        .files()
+        .iter()
+        .map(|file| file.location.path().rsplit('/').next().unwrap().to_string())
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        planned_files,
+        vec!["removed-old.parquet", "unreferenced.parquet"]
+    );
+    assert_eq!(plan.size_in_bytes(), 8);
+    plan.execute(&engine)?;
+
+    let remaining_files: Vec<_> = store
+        .list(Some(&Path::from(table_name)))
+        .map_ok(|meta| meta.location.to_string())
+        .try_collect::<Vec<_>>()
+        .await?
+        .into_iter()
+        .filter(|location| !location.contains("_delta_log"))
+        .sorted()
+        .collect();
+    assert_eq!(
+        remaining_files,
+        vec![
+            format!("{table_name}/.unreferenced.parquet.crc"),
+            format!("{table_name}/_hidden/file.parquet"),
+            format!("{table_name}/live.parquet"),
+            format!("{table_name}/removed-recent.parquet"),
+        ]
+    );
+
+    // vacuum is idempotent
+    let plan = snapshot
+        .vacuum()
+        .with_retention_duration(Duration::ZERO)
+        .plan(&engine)?;
+    assert!(plan.files().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn vacuum_of_stale_snapshot_keeps_newer_files() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "id",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_vacuum_stale_snapshot";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema,
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+    let stale_snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    assert_eq!(stale_snapshot.version(), 0);
+
+    // commit 1 adds `live.parquet`, which the snapshot at version 0 doesn't reference
+    let add = json!({"add": {"path": "live.parquet", "partitionValues": {}, "size": 4, "modificationTime": 0, "dataChange": true}});
+    store
+        .put(
+            &Path::from(format!("{table_name}/_delta_log/00000000000000000001.json")),
+            add.to_string().into(),
+        )
+        .await?;
+    for file in ["live.parquet", "unreferenced.parquet"] {
+        store
+            .put(&Path::from(format!("{table_name}/{file}")), "data".into())
+            .await?;
+    }
+    std::thread::sleep(Duration::from_millis(10));
+
+    // VACUUM plans against the latest version, so only the unreferenced file is deleted
+    let plan = stale_snapshot
+        .vacuum()
+        .with_retention_duration(Duration::ZERO)
+        .plan(&engine)?;
+    let planned_files = plan
+        .files()
+        .iter()
+        .map(|file| file.location.path())
+        .collect_vec();
+    assert_eq!(
+        planned_files,
+        vec![format!("/{table_name}/unreferenced.parquet")]
+    );
+    plan.execute(&engine)?;
+    store
+        .head(&Path::from(format!("{table_name}/live.parquet")))
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn vacuum_checks_protocol() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "id",
+        DataType::INTEGER,
+    )])?);
+
+    // vacuumProtocolCheck tables can be vacuumed if kernel supports both the reader and writer
+    // protocol
+    let table_name = "test_vacuum_protocol_check";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store,
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec!["vacuumProtocolCheck"],
+        vec!["vacuumProtocolCheck"],
+    )
+    .await?;
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    let plan = snapshot.vacuum().plan(&engine)?;
+    assert!(plan.files().is_empty());
+
+    // VACUUM refuses to run if kernel doesn't support the writer protocol
+    let table_name = "test_vacuum_unsupported_writer_feature";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store,
+        table_location,
+        schema,
+        &[],
+        true,
+        vec!["vacuumProtocolCheck"],
+        vec!["vacuumProtocolCheck", "checkConstraints"],
+    )
+    .await?;
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    let err = snapshot.vacuum().plan(&engine).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unknown WriterFeatures: \"checkConstraints\""));
+    Ok(())
+}
+
+#[tokio::test]
+async fn vacuum_deletes_expired_change_data_files() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "id",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_vacuum_change_data";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema,
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+
+    // change data files are only referenced by the cdc actions of their commit, so they are deleted
+    // once they are older than the retention period
+    let cdc_file = "_change_data/cdc-00000.parquet";
+    let cdc =
+        json!({"cdc": {"path": cdc_file, "partitionValues": {}, "size": 4, "dataChange": false}});
+    store
+        .put(
+            &Path::from(format!("{table_name}/_delta_log/00000000000000000001.json")),
+            cdc.to_string().into(),
+        )
+        .await?;
+    store
+        .put(
+            &Path::from(format!("{table_name}/{cdc_file}")),
+            "data".into(),
+        )
+        .await?;
+    std::thread::sleep(Duration::from_millis(10));
+
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    let plan = snapshot
+        .vacuum()
+        .with_retention_duration(Duration::ZERO)
+        .plan(&engine)?;
+    let planned_files = plan
+        .files()
+        .iter()
+        .map(|file| file.location.path())
+        .collect_vec();
+    assert_eq!(planned_files, vec![format!("/{table_name}/{cdc_file}")]);
+    plan.execute(&engine)?;
+    let cdc_path = Path::from(format!("{table_name}/{cdc_file}"));
+    assert!(matches!(
+        store.head(&cdc_path).await,
+        Err(object_store::Error::NotFound { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn vacuum_requires_recursive_listing() -> Result<(), Box<dyn std::error::Error>> {
+    // the sync engine only lists the files directly in a directory, and returns partition
+    // directories instead of the files in them
+    let path = std::fs::canonicalize("./tests/data/basic_partitioned/")?;
+    let table_url = url::Url::from_directory_path(path).unwrap();
+    let engine = SyncEngine::new();
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    let err = snapshot.vacuum().plan(&engine).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("but the storage handler listed the directory"),
+        "{err}"
+    );
+    Ok(())
+}
@@ -2,14 +2,16 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use delta_kernel::Error as KernelError;
-use delta_kernel::{DeltaResult, Engine, Snapshot, Version};
+use delta_kernel::{DeltaResult, Engine, Expression, Snapshot, Version};
 use uuid::Uuid;
 
 use delta_kernel::arrow::array::{ArrayRef, BinaryArray, StructArray};
-use delta_kernel::arrow::array::{Int32Array, StringArray, TimestampMicrosecondArray};
+use delta_kernel::arrow::array::{Int32Array, Int64Array, StringArray, TimestampMicrosecondArray};
 use delta_kernel::arrow::buffer::NullBuffer;
-use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field};
+use delta_kernel::arrow::compute::concat_batches;
+use delta_kernel::arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
 use delta_kernel::arrow::error::ArrowError;
+use delta_kernel::arrow::json::ReaderBuilder;
 use delta_kernel::arrow::record_batch::RecordBatch;
 
 use delta_kernel::engine::arrow_conversion::{TryFromKernel, TryIntoArrow as _};
@@ -18,6 +20,8 @@ use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::parquet::DefaultParquetHandler;
 use delta_kernel::engine::default::DefaultEngine;
 
+use delta_kernel::restore::RestoreTarget;
+use delta_kernel::table_features::{ColumnMappingMode, WriterFeature};
 use delta_kernel::transaction::CommitResult;
 
 use test_utils::set_json_value;
@@ -29,11 +33,13 @@ use serde_json::json;
 use serde_json::Deserializer;
 use tempfile::tempdir;
 
-use delta_kernel::schema::{DataType, SchemaRef, StructField, StructType};
+use delta_kernel::schema::{
+    ColumnMetadataKey, DataType, MetadataValue, SchemaRef, StructField, StructType,
+};
 
 use test_utils::{
-    assert_result_error_with_message, create_table, engine_store_setup, setup_test_tables,
-    test_read,
+    added_files_to_remove, assert_result_error_with_message, create_table, engine_store_setup,
+    setup_test_tables, test_read,
 };
 
 mod common;
@@ -186,6 +192,7 @@ async fn write_data_and_check_result_and_stats(
         CommitResult::Committed {
             version,
             post_commit_stats,
+            ..
         } => {
             assert_eq!(version, expected_since_commit as Version);
             assert_eq!(
@@ -317,7 +324,7 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
                     "size": size,
                     "modificationTime": 0,
                     "dataChange": true,
-                    "stats": "{\"numRecords\":3}"
+                    "stats": "{\"numRecords\":3,\"nullCount\":{\"number\":0},\"minValues\":{\"number\":1},\"maxValues\":{\"number\":3}}"
                 }
             }),
             json!({
@@ -327,7 +334,7 @@ async fn test_append() -> Result<(), Box<dyn std::error::Error>> {
                     "size": size,
                     "modificationTime": 0,
                     "dataChange": true,
-                    "stats": "{\"numRecords\":3}"
+                    "stats": "{\"numRecords\":3,\"nullCount\":{\"number\":0},\"minValues\":{\"number\":4},\"maxValues\":{\"number\":6}}"
                 }
             }),
         ];
@@ -527,7 +534,7 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
                     "size": size,
                     "modificationTime": 0,
                     "dataChange": true,
-                    "stats": "{\"numRecords\":3}"
+                    "stats": "{\"numRecords\":3,\"nullCount\":{\"number\":0},\"minValues\":{\"number\":1},\"maxValues\":{\"number\":3}}"
                 }
             }),
             json!({
@@ -539,7 +546,7 @@ async fn test_append_partitioned() -> Result<(), Box<dyn std::error::Error>> {
                     "size": size,
                     "modificationTime": 0,
                     "dataChange": true,
-                    "stats": "{\"numRecords\":3}"
+                    "stats": "{\"numRecords\":3,\"nullCount\":{\"number\":0},\"minValues\":{\"number\":4},\"maxValues\":{\"number\":6}}"
                 }
             }),
         ];
@@ -900,8 +907,7 @@ async fn test_append_variant() -> Result<(), Box<dyn std::error::Error>> {
         engine_store_setup("test_table_variant", Some(&tmp_test_dir_url));
 
     // We can add shredding features as well as we are allowed to write unshredded variants
-    // into shredded tables and shredded reads are explicitly blocked in the default
-    // engine's parquet reader.
+    // into shredded tables.
     // TODO: (#1124) we don't actually support column mapping writes yet, but have some
     // tests that do column mapping on writes. For now omit the writer feature to let tests
     // run, but after actual support this should be enabled.
@@ -1088,8 +1094,89 @@ async fn test_append_variant() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 #[tokio::test]
-async fn test_shredded_variant_read_rejection() -> Result<(), Box<dyn std::error::Error>> {
-    // Ensure that shredded variants are rejected by the default engine's parquet reader
+async fn test_append_invalid_variant() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "v",
+        DataType::unshredded_variant(),
+    )])?);
+    let (store, engine, table_location) = engine_store_setup("test_table_invalid_variant", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec!["variantType"],
+        vec!["variantType"],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let mut txn = snapshot.transaction()?.with_engine_info("default engine");
+    let write_context = txn.get_write_context();
+    let ArrowDataType::Struct(fields) =
+        ArrowDataType::try_from_kernel(&DataType::unshredded_variant())?
+    else {
+        panic!("Variant arrow data type is not struct.");
+    };
+    let variant_data = |metadata: Vec<&[u8]>, value: Vec<&[u8]>| {
+        let variant = StructArray::try_new(
+            fields.clone(),
+            vec![
+                Arc::new(BinaryArray::from(metadata)),
+                Arc::new(BinaryArray::from(value)),
+            ],
+            None,
+        )?;
+        RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(variant)],
+        )
+    };
+
+    // the second variant has an unsupported metadata version and must be rejected before writing
+    let data = variant_data(
+        vec![&[0x01, 0x00, 0x00][..], &[0x02, 0x00, 0x00][..]],
+        vec![&[0x0C, 0x01][..], &[0x0C, 0x02][..]],
+    )?;
+    let result = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await;
+    assert_result_error_with_message(result, "Invalid Variant value in row 1");
+
+    // well-formed variants are written as usual
+    let data = variant_data(
+        vec![&[0x01, 0x00, 0x00][..], &[0x01, 0x00, 0x00][..]],
+        vec![&[0x0C, 0x01][..], &[0x0C, 0x02][..]],
+    )?;
+    let add_files_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data.clone()),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    assert!(matches!(
+        txn.commit(&engine)?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+    test_read(&ArrowEngineData::new(data), &table_url, Arc::new(engine))?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shredded_variant_read() -> Result<(), Box<dyn std::error::Error>> {
+    // Ensure that the default engine's parquet reader reconstructs shredded variants
 
     // setup tracing
     let _ = tracing_subscriber::fmt::try_init();
@@ -1098,9 +1185,8 @@ async fn test_shredded_variant_read_rejection() -> Result<(), Box<dyn std::error
         DataType::unshredded_variant(),
     )])?);
 
-    // The table will be attempted to be written in this form but be read into
-    // STRUCT<metadata: BINARY, value: BINARY>. The read should fail because the default engine
-    // currently does not support shredded reads.
+    // The table will be written in this form but be read into
+    // STRUCT<metadata: BINARY, value: BINARY>.
     let shredded_write_schema = Arc::new(StructType::try_new(vec![StructField::nullable(
         "v",
         DataType::try_struct_type([
@@ -1129,19 +1215,16 @@ async fn test_shredded_variant_read_rejection() -> Result<(), Box<dyn std::error
     let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
     let mut txn = snapshot.transaction()?;
 
-    // First value corresponds to the variant value "1". Third value corresponds to the variant
-    // representing the JSON Object {"a":2}.
+    // The first value is the int 21, shredded into `typed_value`. The second value is the
+    // variant representing the JSON Object {"a":2}, which is not shredded.
     let metadata_v = vec![
         Some(&[0x01, 0x00, 0x00][..]),
         Some(&[0x01, 0x01, 0x00, 0x01, 0x61][..]),
     ];
-    let value_v = vec![
-        Some(&[0x0C, 0x01][..]),
-        Some(&[0x02, 0x01, 0x00, 0x00, 0x01, 0x02][..]),
-    ];
-    let typed_value_v = vec![Some(21), Some(3)];
+    let value_v = vec![None, Some(&[0x02, 0x01, 0x00, 0x00, 0x01, 0x02][..])];
+    let typed_value_v = vec![Some(21), None];
 
-    let metadata_v_array = Arc::new(BinaryArray::from(metadata_v)) as ArrayRef;
+    let metadata_v_array = Arc::new(BinaryArray::from(metadata_v.clone())) as ArrayRef;
     let value_v_array = Arc::new(BinaryArray::from(value_v)) as ArrayRef;
     let typed_value_v_array = Arc::new(Int32Array::from(typed_value_v)) as ArrayRef;
 
@@ -1215,9 +1298,27 @@ async fn test_shredded_variant_read_rejection() -> Result<(), Box<dyn std::error
     // Check that the add action exists
     assert!(parsed_commits[1].get("add").is_some());
 
-    let res = test_read(&ArrowEngineData::new(data), &table_url, engine);
-    assert!(matches!(res,
-        Err(e) if e.to_string().contains("The default engine does not support shredded reads")));
+    // The shredded int is read as the variant-encoded int 21
+    let expected_value_v = vec![
+        Some(&[0x14, 0x15, 0x00, 0x00, 0x00][..]),
+        Some(&[0x02, 0x01, 0x00, 0x00, 0x01, 0x02][..]),
+    ];
+    let variant_arrow_type = ArrowDataType::try_from_kernel(&DataType::unshredded_variant())?;
+    let ArrowDataType::Struct(expected_fields) = variant_arrow_type else {
+        panic!("Variant arrow data type is not struct.");
+    };
+    let expected_data = RecordBatch::try_new(
+        Arc::new(table_schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(StructArray::try_new(
+            expected_fields,
+            vec![
+                Arc::new(BinaryArray::from(metadata_v)),
+                Arc::new(BinaryArray::from(expected_value_v)),
+            ],
+            None,
+        )?)],
+    )?;
+    test_read(&ArrowEngineData::new(expected_data), &table_url, engine)?;
 
     Ok(())
 }
@@ -1344,6 +1445,109 @@ async fn test_set_domain_metadata_errors() -> Result<(), Box<dyn std::error::Err
         "Metadata for domain app.config already specified in this transaction",
     );
 
+    // Setting and removing the same domain is rejected as well
+    let txn3 = snapshot.clone().transaction()?;
+    let res = txn3
+        .with_domain_metadata("app.config".to_string(), "v1".to_string())
+        .remove_domain_metadata("app.config".to_string())
+        .commit(&engine);
+    assert_result_error_with_message(
+        res,
+        "Metadata for domain app.config already specified in this transaction",
+    );
+
+    // System domains can't be removed either
+    let txn4 = snapshot.clone().transaction()?;
+    let res = txn4
+        .remove_domain_metadata("delta.rowTracking".to_string())
+        .commit(&engine);
+    assert_result_error_with_message(
+        res,
+        "Cannot modify domains that start with 'delta.' as those are system controlled",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remove_domain_metadata() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    let table_name = "test_domain_metadata_remove";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["domainMetadata"],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    snapshot
+        .transaction()?
+        .with_domain_metadata("app.config".to_string(), "v1".to_string())
+        .with_domain_metadata("app.other".to_string(), "other".to_string())
+        .commit(&engine)?;
+
+    // removing an existing domain writes a tombstone with its latest configuration, removing a
+    // missing domain is a no-op
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    snapshot
+        .transaction()?
+        .remove_domain_metadata("app.config".to_string())
+        .remove_domain_metadata("app.missing".to_string())
+        .commit(&engine)?;
+
+    let commit_data = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/00000000000000000002.json"
+        )))
+        .await?
+        .bytes()
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit_data)
+        .into_iter()
+        .try_collect()?;
+    let domain_actions: Vec<_> = actions
+        .iter()
+        .filter_map(|v| v.get("domainMetadata"))
+        .collect();
+    assert_eq!(
+        domain_actions,
+        vec![&json!({
+            "domain": "app.config",
+            "configuration": "v1",
+            "removed": true,
+        })]
+    );
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert_eq!(snapshot.get_domain_metadata("app.config", &engine)?, None);
+    assert_eq!(
+        snapshot.get_domain_metadata("app.other", &engine)?,
+        Some("other".to_string())
+    );
+
+    // a removed domain can be set again
+    snapshot
+        .transaction()?
+        .with_domain_metadata("app.config".to_string(), "v2".to_string())
+        .commit(&engine)?;
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    assert_eq!(
+        snapshot.get_domain_metadata("app.config", &engine)?,
+        Some("v2".to_string())
+    );
+
     Ok(())
 }
 
@@ -1382,3 +1586,1666 @@ async fn test_set_domain_metadata_unsupported_writer_feature(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_set_domain_metadata_with_protocol_upgrade() -> Result<(), Box<dyn std::error::Error>>
+{
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    let table_name = "test_domain_metadata_protocol_upgrade";
+
+    // Create a (1, 1) table, which doesn't support domain metadata
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let res = snapshot
+        .transaction()?
+        .with_domain_metadata("app.config".to_string(), "test_config".to_string())
+        .allow_protocol_upgrade()
+        .commit(&engine)?;
+    assert!(matches!(res, CommitResult::Committed { version: 1, .. }));
+
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/00000000000000000001.json"
+        )))
+        .await?;
+    let parsed_commits: Vec<serde_json::Value> = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .try_collect()?;
+    let protocol = parsed_commits
+        .iter()
+        .find_map(|action| action.get("protocol"))
+        .expect("commit should contain a protocol action");
+    assert_eq!(protocol["minReaderVersion"], json!(1));
+    assert_eq!(protocol["minWriterVersion"], json!(7));
+    assert_eq!(protocol["writerFeatures"], json!(["domainMetadata"]));
+
+    // the upgraded table supports domain metadata without further upgrades
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    assert_eq!(
+        snapshot.get_domain_metadata("app.config", &engine)?,
+        Some("test_config".to_string())
+    );
+    let res = snapshot
+        .transaction()?
+        .with_domain_metadata("app.config".to_string(), "new_config".to_string())
+        .commit(&engine)?;
+    assert!(matches!(res, CommitResult::Committed { version: 2, .. }));
+
+    Ok(())
+}
+
+/// Read the actions of the commit of `version` of the table `table_name` in `store`.
+async fn read_commit_actions(
+    store: &Arc<dyn ObjectStore>,
+    table_name: &str,
+    version: Version,
+) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/{version:020}.json"
+        )))
+        .await?;
+    Ok(Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .try_collect()?)
+}
+
+fn find_action<'a>(actions: &'a [serde_json::Value], name: &str) -> &'a serde_json::Value {
+    actions
+        .iter()
+        .find_map(|action| action.get(name))
+        .unwrap_or_else(|| panic!("commit should contain a {name} action"))
+}
+
+#[tokio::test]
+async fn test_enable_deletion_vectors_with_protocol_upgrade(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_enable_deletion_vectors";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+
+    // the (1, 1) table doesn't support deletion vectors
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert_result_error_with_message(
+        snapshot
+            .clone()
+            .transaction()?
+            .with_deletion_vectors_enabled()
+            .commit(&engine),
+        "This transaction requires the 'deletionVectors' writer feature",
+    );
+
+    let res = snapshot
+        .transaction()?
+        .with_deletion_vectors_enabled()
+        .allow_protocol_upgrade()
+        .commit(&engine)?;
+    assert!(matches!(res, CommitResult::Committed { version: 1, .. }));
+    let actions = read_commit_actions(&store, table_name, 1).await?;
+    let protocol = find_action(&actions, "protocol");
+    assert_eq!(protocol["minReaderVersion"], json!(3));
+    assert_eq!(protocol["minWriterVersion"], json!(7));
+    assert_eq!(protocol["readerFeatures"], json!(["deletionVectors"]));
+    assert_eq!(protocol["writerFeatures"], json!(["deletionVectors"]));
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    assert_eq!(
+        snapshot.table_properties().enable_deletion_vectors,
+        Some(true)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_row_tracking_with_protocol_upgrade() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_add_row_tracking";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+
+    let append = |txn: &delta_kernel::transaction::Transaction, values: Vec<i32>| {
+        let batch = RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow().unwrap()),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+        .unwrap();
+        let write_context = txn.get_write_context();
+        let engine = &engine;
+        async move {
+            engine
+                .write_parquet(
+                    &ArrowEngineData::new(batch),
+                    &write_context,
+                    HashMap::new(),
+                    true,
+                )
+                .await
+        }
+    };
+
+    // the files added in the commit adding row tracking are assigned row IDs
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let mut txn = snapshot
+        .transaction()?
+        .with_row_tracking_supported()
+        .allow_protocol_upgrade();
+    let add_files_metadata = append(&txn, vec![1, 2, 3]).await?;
+    txn.add_files(add_files_metadata);
+    assert!(matches!(
+        txn.commit(&engine)?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+    let actions = read_commit_actions(&store, table_name, 1).await?;
+    let protocol = find_action(&actions, "protocol");
+    assert_eq!(protocol["minWriterVersion"], json!(7));
+    assert_eq!(
+        protocol["writerFeatures"],
+        json!(["rowTracking", "domainMetadata"])
+    );
+    let add = find_action(&actions, "add");
+    assert_eq!(add["baseRowId"], json!(0));
+    assert_eq!(add["defaultRowCommitVersion"], json!(1));
+    let configuration = &find_action(&actions, "metaData")["configuration"];
+    assert!(
+        configuration["delta.rowTracking.materializedRowIdColumnName"]
+            .as_str()
+            .unwrap()
+            .starts_with("_row-id-col-")
+    );
+    assert!(
+        configuration["delta.rowTracking.materializedRowCommitVersionColumnName"]
+            .as_str()
+            .unwrap()
+            .starts_with("_row-commit-version-col-")
+    );
+    assert_eq!(configuration.get("delta.enableRowTracking"), None);
+
+    // later commits continue after the assigned row IDs
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    let mut txn = snapshot.transaction()?;
+    let add_files_metadata = append(&txn, vec![4, 5]).await?;
+    txn.add_files(add_files_metadata);
+    txn.commit(&engine)?;
+    let actions = read_commit_actions(&store, table_name, 2).await?;
+    assert_eq!(find_action(&actions, "add")["baseRowId"], json!(3));
+    assert!(actions
+        .iter()
+        .all(|action| action.get("protocol").is_none()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_enable_in_commit_timestamps_with_protocol_upgrade(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_enable_in_commit_timestamps";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let res = snapshot
+        .transaction()?
+        .with_in_commit_timestamps_enabled()
+        .allow_protocol_upgrade()
+        .commit(&engine)?;
+    assert!(matches!(res, CommitResult::Committed { version: 1, .. }));
+    let actions = read_commit_actions(&store, table_name, 1).await?;
+    let protocol = find_action(&actions, "protocol");
+    assert_eq!(protocol["minWriterVersion"], json!(7));
+    assert_eq!(protocol["writerFeatures"], json!(["inCommitTimestamp"]));
+    // the commit info is the first action, and holds the in-commit timestamp of the enablement
+    let enablement_timestamp = actions[0]["commitInfo"]["inCommitTimestamp"]
+        .as_i64()
+        .unwrap();
+    let configuration = &find_action(&actions, "metaData")["configuration"];
+    assert_eq!(
+        configuration["delta.enableInCommitTimestamps"],
+        json!("true")
+    );
+    assert_eq!(
+        configuration["delta.inCommitTimestampEnablementVersion"],
+        json!("1")
+    );
+    assert_eq!(
+        configuration["delta.inCommitTimestampEnablementTimestamp"],
+        json!(enablement_timestamp.to_string())
+    );
+
+    // later commits have increasing in-commit timestamps
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    snapshot.transaction()?.commit(&engine)?;
+    let actions = read_commit_actions(&store, table_name, 2).await?;
+    let in_commit_timestamp = actions[0]["commitInfo"]["inCommitTimestamp"]
+        .as_i64()
+        .unwrap();
+    assert!(in_commit_timestamp > enablement_timestamp);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_protocol() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+
+    // Create a (1, 1) table
+    let (store, engine, table_location) = engine_store_setup("test_update_protocol", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let initial_protocol = snapshot.protocol().clone();
+
+    // upgrade to table features
+    let protocol = initial_protocol.upgrade_with_features(&[
+        WriterFeature::Invariants.into(),
+        WriterFeature::AppendOnly.into(),
+    ])?;
+    let res = snapshot
+        .transaction()?
+        .update_protocol(protocol.clone())?
+        .commit(&engine)?;
+    assert!(matches!(res, CommitResult::Committed { version: 1, .. }));
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert_eq!(snapshot.protocol(), &protocol);
+    assert_eq!(snapshot.protocol().min_writer_version(), 7);
+
+    // the features are those of writer version 2, so the protocol can be downgraded
+    let protocol = snapshot.protocol().try_downgrade()?;
+    let res = snapshot
+        .transaction()?
+        .update_protocol(protocol)?
+        .commit(&engine)?;
+    assert!(matches!(res, CommitResult::Committed { version: 2, .. }));
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    assert_eq!(snapshot.protocol().min_reader_version(), 1);
+    assert_eq!(snapshot.protocol().min_writer_version(), 2);
+    assert_eq!(snapshot.protocol().writer_features(), None);
+
+    // table features cannot be dropped
+    assert_result_error_with_message(
+        snapshot.transaction()?.update_protocol(initial_protocol),
+        "Cannot update protocol: the new protocol doesn't support the 'appendOnly' writer feature of the table",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_invariants() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a table with a column invariant `number > 0`
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )
+    .with_metadata([(
+        "delta.invariants",
+        r#"{"expression":{"expression":"number > 0"}}"#,
+    )])])?);
+
+    let (store, engine, table_location) = engine_store_setup("test_table_invariants", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["invariants"],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let mut txn = snapshot.transaction()?.with_engine_info("default engine");
+    let engine = Arc::new(engine);
+    let write_context = txn.get_write_context();
+    assert!(write_context.has_invariants());
+
+    // data that violates the invariant (including a null) must be rejected before writing
+    for values in [vec![Some(1), Some(0)], vec![Some(1), None]] {
+        let data = RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![Arc::new(Int32Array::from(values))],
+        )?;
+        let result = engine
+            .write_parquet(
+                &ArrowEngineData::new(data),
+                &write_context,
+                HashMap::new(),
+                true,
+            )
+            .await;
+        assert!(matches!(
+            result.as_ref().map_err(KernelError::inner),
+            Err(KernelError::InvariantViolation(_))
+        ));
+    }
+
+    // data that satisfies the invariant is written and committed as usual
+    let data = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let add_files_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data.clone()),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+
+    test_read(&ArrowEngineData::new(data), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_generated_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a table with a generated column `doubled` = `number * 2`
+    let schema = Arc::new(StructType::try_new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("doubled", DataType::INTEGER)
+            .with_metadata([("delta.generationExpression", "number * 2")]),
+    ])?);
+
+    let (store, engine, table_location) = engine_store_setup("test_table_generated", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["generatedColumns"],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let mut txn = snapshot.transaction()?.with_engine_info("default engine");
+    let engine = Arc::new(engine);
+    let write_context = txn.get_write_context();
+    assert!(write_context.has_generated_columns());
+
+    // data whose generated column doesn't match the generation expression must be rejected
+    let data = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2])),
+            Arc::new(Int32Array::from(vec![2, 5])),
+        ],
+    )?;
+    let result = engine
+        .write_parquet(
+            &ArrowEngineData::new(data.clone()),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await;
+    assert!(result.is_err());
+
+    // computing the generated column fixes up the data, which is then written as usual
+    let computed =
+        write_context.compute_generated_columns(engine.as_ref(), &ArrowEngineData::new(data))?;
+    let add_files_metadata = engine
+        .write_parquet(
+            ArrowEngineData::try_from_engine_data(computed)?.as_ref(),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+
+    let expected = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2])),
+            Arc::new(Int32Array::from(vec![2, 4])),
+        ],
+    )?;
+    test_read(&ArrowEngineData::new(expected), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_unsupported_generation_expression(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // the kernel cannot parse `concat(...)`, so it must not write unvalidated data to the table
+    let schema = Arc::new(StructType::try_new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("label", DataType::STRING)
+            .with_metadata([("delta.generationExpression", "concat('n', number)")]),
+    ])?);
+
+    let (store, engine, table_location) =
+        engine_store_setup("test_table_unsupported_generated", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["generatedColumns"],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let txn = snapshot.transaction()?.with_engine_info("default engine");
+    let write_context = txn.get_write_context();
+    assert!(write_context.has_generated_columns());
+    assert_eq!(write_context.unsupported_generated_columns(), ["label"]);
+
+    let data = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec!["n1", "n2"])),
+        ],
+    )?;
+    let result = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(KernelError::Unsupported(e)) if e.contains("generated columns label")
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_column_defaults() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a table whose `status` column defaults to 'new'
+    let schema = Arc::new(StructType::try_new(vec![
+        StructField::nullable("number", DataType::INTEGER),
+        StructField::nullable("status", DataType::STRING)
+            .with_metadata([("CURRENT_DEFAULT", "'new'")]),
+    ])?);
+
+    let (store, engine, table_location) = engine_store_setup("test_table_defaults", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["allowColumnDefaults"],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let mut txn = snapshot.transaction()?.with_engine_info("default engine");
+    let engine = Arc::new(engine);
+    let write_context = txn.get_write_context();
+    assert_eq!(
+        write_context.column_default("status").as_deref(),
+        Some(&Expression::literal("new"))
+    );
+    assert!(write_context.column_default("number").is_none());
+
+    // write data without the `status` column, which takes its default value
+    let data_schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let data = RecordBatch::try_new(
+        Arc::new(data_schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2]))],
+    )?;
+    let add_files_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+
+    let expected = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec!["new", "new"])),
+        ],
+    )?;
+    test_read(&ArrowEngineData::new(expected), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_identity_column() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a table with an identity column `id` starting at 1 with step 1
+    let schema = Arc::new(StructType::try_new(vec![
+        StructField::not_null("id", DataType::LONG).with_metadata([
+            ("delta.identity.start", MetadataValue::Number(1)),
+            ("delta.identity.step", MetadataValue::Number(1)),
+        ]),
+        StructField::nullable("number", DataType::INTEGER),
+    ])?);
+
+    let (store, engine, table_location) = engine_store_setup("test_table_identity", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["identityColumns"],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+
+    for (version, expected_ids) in [(1, vec![1, 2, 3]), (2, vec![4, 5, 6])] {
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+        let mut txn = snapshot.transaction()?.with_engine_info("default engine");
+        assert_eq!(txn.identity_columns().collect_vec(), vec!["id"]);
+        assert!(txn.reserve_identity_values("number", 3).is_err());
+
+        let ids = txn.reserve_identity_values("id", 3)?;
+        assert_eq!(ids.iter().collect_vec(), expected_ids);
+        let data = RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow()?),
+            vec![
+                Arc::new(Int64Array::from_iter_values(ids.iter())),
+                Arc::new(Int32Array::from(vec![10, 20, 30])),
+            ],
+        )?;
+        let write_context = txn.get_write_context();
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(data),
+                &write_context,
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+        assert!(matches!(
+            txn.commit(engine.as_ref())?,
+            CommitResult::Committed { version: v, .. } if v == version
+        ));
+
+        // the commit moved the high water mark to the last reserved value
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+        let schema = snapshot.schema();
+        let id_field = schema.field("id").unwrap();
+        assert_eq!(
+            id_field.get_config_value(&ColumnMetadataKey::IdentityHighWaterMark),
+            Some(&MetadataValue::Number(ids.last().unwrap()))
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_with_column_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    // create a table in column mapping `name` mode, whose columns have physical names that differ
+    // from their logical names
+    let column_mapping_field = |name: &str, data_type: DataType, id: i64| {
+        StructField::nullable(name, data_type).with_metadata([
+            ("delta.columnMapping.id", MetadataValue::Number(id)),
+            (
+                "delta.columnMapping.physicalName",
+                MetadataValue::String(format!("col-{name}")),
+            ),
+        ])
+    };
+    let schema = Arc::new(StructType::try_new(vec![
+        column_mapping_field("number", DataType::INTEGER, 1),
+        column_mapping_field("string", DataType::STRING, 2),
+    ])?);
+
+    let (store, engine, table_location) = engine_store_setup("test_table_column_mapping", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec!["columnMapping"],
+        vec!["columnMapping"],
+    )
+    .await?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    let mut txn = snapshot.transaction()?.with_engine_info("default engine");
+    let engine = Arc::new(engine);
+    let write_context = txn.get_write_context();
+    let physical_names = write_context
+        .physical_schema()
+        .fields()
+        .map(|field| field.name().as_str())
+        .collect_vec();
+    assert_eq!(physical_names, vec!["col-number", "col-string"]);
+
+    let data = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["a", "b", "c"])),
+        ],
+    )?;
+    let add_files_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data.clone()),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+
+    // reading maps the physical columns of the written file back to the logical schema
+    test_read(&ArrowEngineData::new(data), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_enable_column_mapping_id_mode() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let (store, engine, table_location) = engine_store_setup("test_table_enable_id_mode", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec![],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+
+    // `id` mode can be enabled while the table has no data files
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let txn = snapshot
+        .transaction()?
+        .allow_protocol_upgrade()
+        .with_column_mapping_enabled(ColumnMappingMode::Id)?;
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 1, .. }
+    ));
+
+    // ...but not once it has some, whose data readers resolving columns by ID would not find
+    let (store, engine, table_location) = engine_store_setup("test_table_enable_id_mode", None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec![],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let mut txn = snapshot.transaction()?;
+    let data = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let add_files_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(data),
+            &txn.get_write_context(),
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    txn.commit(engine.as_ref())?;
+
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let txn = snapshot
+        .clone()
+        .transaction()?
+        .allow_protocol_upgrade()
+        .with_column_mapping_enabled(ColumnMappingMode::Id)?;
+    assert_result_error_with_message(
+        txn.commit(engine.as_ref()),
+        "Column mapping 'id' mode can only be enabled on tables without data files",
+    );
+
+    // `name` mode keeps the logical names as physical names, which existing files use
+    let txn = snapshot
+        .transaction()?
+        .allow_protocol_upgrade()
+        .with_column_mapping_enabled(ColumnMappingMode::Name)?;
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 2, .. }
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_enable_deletion_vectors_on_iceberg_compat_v2_table(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )
+    .with_metadata([
+        ("delta.columnMapping.id", MetadataValue::Number(1)),
+        (
+            "delta.columnMapping.physicalName",
+            MetadataValue::String("col-number".to_string()),
+        ),
+    ])])?);
+    let table_name = "test_table_iceberg_compat_v2_deletion_vectors";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema,
+        &[],
+        true,
+        vec!["columnMapping", "deletionVectors"],
+        vec!["columnMapping", "icebergCompatV2", "deletionVectors"],
+    )
+    .await?;
+
+    // version 1 enables IcebergCompatV2
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/{:020}.json",
+            0
+        )))
+        .await?;
+    let mut metadata = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .map(|action| action.unwrap())
+        .find(|action| action.get("metaData").is_some())
+        .unwrap();
+    metadata["metaData"]["configuration"]["delta.enableIcebergCompatV2"] = json!("true");
+    store
+        .put(
+            &Path::from(format!("/{table_name}/_delta_log/{:020}.json", 1)),
+            metadata.to_string().into(),
+        )
+        .await?;
+
+    // IcebergCompatV2 forbids deletion vectors, including ones enabled by the transaction itself
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+    assert_result_error_with_message(
+        snapshot
+            .clone()
+            .transaction()?
+            .with_deletion_vectors_enabled()
+            .commit(&engine),
+        "Table is not IcebergCompatV2 compliant: deletion vectors must not be enabled",
+    );
+    assert!(matches!(
+        snapshot.transaction()?.commit(&engine)?,
+        CommitResult::Committed { version: 2, .. }
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rewrite_files() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_table_rewrite_files";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+
+    // append two small files
+    let batches = [vec![1, 2, 3], vec![4, 5, 6]].map(|values| {
+        RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow().unwrap()),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+        .unwrap()
+    });
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let mut txn = snapshot.transaction()?;
+    let write_context = txn.get_write_context();
+    for batch in &batches {
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(batch.clone()),
+                &write_context,
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+    }
+    txn.commit(engine.as_ref())?;
+
+    // compact them into a single file
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/00000000000000000001.json"
+        )))
+        .await?;
+    let removed: String = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .filter_map_ok(|action| action.get("add").cloned())
+        .map_ok(|add| {
+            json!({
+                "path": add["path"],
+                "partitionValues": add["partitionValues"],
+                "size": add["size"],
+            })
+            .to_string()
+        })
+        .try_collect::<_, Vec<_>, _>()?
+        .join("\n");
+    let removed = ReaderBuilder::new(Arc::new(
+        delta_kernel::transaction::remove_files_schema()
+            .as_ref()
+            .try_into_arrow()?,
+    ))
+    .build(removed.as_bytes())?
+    .next()
+    .unwrap()?;
+    assert_eq!(removed.num_rows(), 2);
+
+    let data = concat_batches(&batches[0].schema(), &batches)?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let mut txn = snapshot.transaction()?;
+    let write_context = txn.get_write_context();
+    let added = engine
+        .write_parquet(
+            &ArrowEngineData::new(data.clone()),
+            &write_context,
+            HashMap::new(),
+            false,
+        )
+        .await?;
+    txn.rewrite_files(Box::new(ArrowEngineData::new(removed)), added);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 2, .. }
+    ));
+
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/00000000000000000002.json"
+        )))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    assert_eq!(actions[0]["commitInfo"]["operation"], json!("OPTIMIZE"));
+    let adds = actions.iter().filter_map(|a| a.get("add")).collect_vec();
+    let removes = actions.iter().filter_map(|a| a.get("remove")).collect_vec();
+    assert_eq!(adds.len(), 1);
+    assert_eq!(adds[0]["dataChange"], json!(false));
+    assert_eq!(removes.len(), 2);
+    for remove in removes {
+        assert_eq!(remove["dataChange"], json!(false));
+        assert!(remove["deletionTimestamp"].is_i64());
+    }
+
+    // the table contents are unchanged
+    test_read(&ArrowEngineData::new(data), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restore() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_table_restore";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+
+    // versions 1 and 2 each append a file
+    let batches = [vec![1, 2, 3], vec![4, 5, 6]].map(|values| {
+        RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow().unwrap()),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+        .unwrap()
+    });
+    for batch in &batches {
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+        let mut txn = snapshot.transaction()?;
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(batch.clone()),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+        txn.commit(engine.as_ref())?;
+    }
+
+    let read_commit = |version: u64| {
+        let store = store.clone();
+        async move {
+            let commit = store
+                .get(&Path::from(format!(
+                    "/{table_name}/_delta_log/{version:020}.json"
+                )))
+                .await?;
+            let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit.bytes().await?)
+                .into_iter()
+                .try_collect()?;
+            Ok::<_, Box<dyn std::error::Error>>(actions)
+        }
+    };
+
+    // restoring version 1 removes the file appended by version 2
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    assert!(matches!(
+        snapshot
+            .restore(RestoreTarget::Version(1))
+            .commit(engine.as_ref())?,
+        CommitResult::Committed { version: 3, .. }
+    ));
+    let actions = read_commit(3).await?;
+    assert_eq!(actions[0]["commitInfo"]["operation"], json!("RESTORE"));
+    let adds = actions.iter().filter_map(|a| a.get("add")).collect_vec();
+    let removes = actions.iter().filter_map(|a| a.get("remove")).collect_vec();
+    assert!(adds.is_empty());
+    assert_eq!(removes.len(), 1);
+    assert_eq!(removes[0]["dataChange"], json!(true));
+    assert!(removes[0]["deletionTimestamp"].is_i64());
+    test_read(
+        &ArrowEngineData::new(batches[0].clone()),
+        &table_url,
+        engine.clone(),
+    )?;
+
+    // restoring version 2 adds the removed file back
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    assert!(matches!(
+        snapshot
+            .restore(RestoreTarget::Version(2))
+            .commit(engine.as_ref())?,
+        CommitResult::Committed { version: 4, .. }
+    ));
+    let actions = read_commit(4).await?;
+    let adds = actions.iter().filter_map(|a| a.get("add")).collect_vec();
+    assert_eq!(adds.len(), 1);
+    assert_eq!(adds[0]["path"], removes[0]["path"]);
+    assert_eq!(adds[0]["dataChange"], json!(true));
+    assert!(actions.iter().all(|a| a.get("remove").is_none()));
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let scan = snapshot.clone().scan_builder().build()?;
+    let num_rows: usize = test_utils::read_scan(&scan, engine.clone())?
+        .iter()
+        .map(|batch| batch.num_rows())
+        .sum();
+    assert_eq!(num_rows, 6);
+
+    // restoring a version whose files were deleted fails
+    let path = Url::parse(removes[0]["path"].as_str().unwrap())?;
+    store.delete(&Path::from_url_path(path.path())?).await?;
+    snapshot
+        .restore(RestoreTarget::Version(1))
+        .commit(engine.as_ref())?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    assert_result_error_with_message(
+        snapshot
+            .clone()
+            .restore(RestoreTarget::Version(2))
+            .commit(engine.as_ref()),
+        "no longer exists",
+    );
+
+    // versions newer than the current version can't be restored
+    assert_result_error_with_message(
+        snapshot
+            .restore(RestoreTarget::Version(6))
+            .commit(engine.as_ref()),
+        "newer than the current version",
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_restore_append_only() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_table_restore_append_only";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["appendOnly"],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+
+    // version 1 appends a file
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let mut txn = snapshot.transaction()?;
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.as_ref().try_into_arrow()?),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+    )?;
+    let add_files_metadata = engine
+        .write_parquet(
+            &ArrowEngineData::new(batch),
+            &txn.get_write_context(),
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    txn.add_files(add_files_metadata);
+    txn.commit(engine.as_ref())?;
+
+    // version 2 makes the table append-only
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/{:020}.json",
+            0
+        )))
+        .await?;
+    let mut metadata = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .map(|action| action.unwrap())
+        .find(|action| action.get("metaData").is_some())
+        .unwrap();
+    metadata["metaData"]["configuration"] = json!({"delta.appendOnly": "true"});
+    store
+        .put(
+            &Path::from(format!("/{table_name}/_delta_log/{:020}.json", 2)),
+            metadata.to_string().into(),
+        )
+        .await?;
+
+    // restoring version 0 would remove the file appended by version 1
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    assert_eq!(snapshot.table_properties().append_only, Some(true));
+    assert_result_error_with_message(
+        snapshot
+            .clone()
+            .restore(RestoreTarget::Version(0))
+            .commit(engine.as_ref()),
+        "Cannot remove files from an append-only table",
+    );
+
+    // restoring version 1 only restores the metadata, which removes no files
+    assert!(matches!(
+        snapshot
+            .restore(RestoreTarget::Version(1))
+            .commit(engine.as_ref())?,
+        CommitResult::Committed { version: 3, .. }
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shallow_clone() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_table_shallow_clone_source";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+
+    // versions 1 and 2 each append a file
+    let batches = [vec![1, 2, 3], vec![4, 5, 6]].map(|values| {
+        RecordBatch::try_new(
+            Arc::new(schema.as_ref().try_into_arrow().unwrap()),
+            vec![Arc::new(Int32Array::from(values))],
+        )
+        .unwrap()
+    });
+    for batch in &batches {
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+        let mut txn = snapshot.transaction()?;
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(batch.clone()),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+        txn.commit(engine.as_ref())?;
+    }
+
+    // clone version 1 of the source table
+    let clone_url = Url::parse("memory:///test_table_shallow_clone_target/")?;
+    let snapshot = Snapshot::builder_for(table_url.clone())
+        .at_version(1)
+        .build(engine.as_ref())?;
+    snapshot
+        .clone()
+        .shallow_clone(clone_url.clone())
+        .commit(engine.as_ref())?;
+
+    let commit = store
+        .get(&Path::from(
+            "/test_table_shallow_clone_target/_delta_log/00000000000000000000.json",
+        ))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    assert_eq!(actions[0]["commitInfo"]["operation"], json!("CLONE"));
+    assert_eq!(
+        actions[0]["commitInfo"]["operationParameters"]["sourceVersion"],
+        json!("1")
+    );
+    let source_schema = snapshot.schema();
+    let clone_snapshot = Snapshot::builder_for(clone_url.clone()).build(engine.as_ref())?;
+    assert_eq!(clone_snapshot.version(), 0);
+    assert_eq!(clone_snapshot.schema(), source_schema);
+    let adds = actions.iter().filter_map(|a| a.get("add")).collect_vec();
+    assert_eq!(adds.len(), 1);
+    assert!(adds[0]["path"]
+        .as_str()
+        .unwrap()
+        .starts_with(table_url.as_str()));
+
+    // the clone reads the data files of the source table
+    test_read(
+        &ArrowEngineData::new(batches[0].clone()),
+        &clone_url,
+        engine.clone(),
+    )?;
+
+    // cloning into an existing table fails
+    assert_result_error_with_message(
+        snapshot
+            .shallow_clone(clone_url.clone())
+            .commit(engine.as_ref()),
+        "a table already exists",
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_post_commit_actions() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let (store, engine, table_location) = engine_store_setup("test_post_commit_actions", None);
+    let table_url = create_table(store, table_location, schema, &[], false, vec![], vec![]).await?;
+
+    // the table doesn't set `delta.checkpointInterval`, so it should be checkpointed every 10
+    // commits. the log is compacted every 3 commits.
+    for expected_version in 1..=10 {
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+        let txn = snapshot.transaction()?.with_log_compaction_interval(3);
+        let CommitResult::Committed {
+            version,
+            post_commit_actions,
+            ..
+        } = txn.commit(&engine)?
+        else {
+            panic!("Commit should have succeeded");
+        };
+        assert_eq!(version, expected_version);
+        assert_eq!(post_commit_actions.checkpoint, version == 10);
+        let expected_compaction = [2, 5, 8].contains(&version).then(|| (version - 2, version));
+        assert_eq!(post_commit_actions.log_compaction, expected_compaction);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_change_data_feed_writes() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let table_name = "test_table_change_data_feed_writes";
+    let (store, engine, table_location) = engine_store_setup(table_name, None);
+    let table_url = create_table(
+        store.clone(),
+        table_location,
+        schema.clone(),
+        &[],
+        true,
+        vec![],
+        vec!["changeDataFeed"],
+    )
+    .await?;
+    let engine = Arc::new(engine);
+    let arrow_schema: Arc<ArrowSchema> = Arc::new(schema.as_ref().try_into_arrow()?);
+
+    // versions 1 and 2 each append a file, which doesn't need change data files
+    for values in [vec![1, 2, 3], vec![4, 5, 6]] {
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![Arc::new(Int32Array::from(values))],
+        )?;
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+        let mut txn = snapshot.transaction()?;
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(batch),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+        txn.commit(engine.as_ref())?;
+    }
+
+    // version 3 updates 3 to 30 by replacing the first file, which requires change data files
+    let updated = RecordBatch::try_new(
+        arrow_schema.clone(),
+        vec![Arc::new(Int32Array::from(vec![1, 2, 30]))],
+    )?;
+    let change_data = RecordBatch::try_new(
+        Arc::new(delta_kernel::arrow::datatypes::Schema::new(vec![
+            Field::new("number", ArrowDataType::Int32, true),
+            Field::new("_change_type", ArrowDataType::Utf8, false),
+        ])),
+        vec![
+            Arc::new(Int32Array::from(vec![3, 30])),
+            Arc::new(StringArray::from(vec![
+                "update_preimage",
+                "update_postimage",
+            ])),
+        ],
+    )?;
+    let removed = added_files_to_remove(store.as_ref(), table_name, 1).await?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let write_context = snapshot.clone().transaction()?.get_write_context();
+    assert!(write_context.is_change_data_feed_enabled());
+    let added = engine
+        .write_parquet(
+            &ArrowEngineData::new(updated.clone()),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    let cdc_files_metadata = engine
+        .write_change_data(
+            &ArrowEngineData::new(change_data),
+            &write_context,
+            HashMap::new(),
+        )
+        .await?;
+
+    let mut txn = snapshot.clone().transaction()?;
+    txn.remove_files(Box::new(ArrowEngineData::new(removed.clone())));
+    txn.add_files(added);
+    assert_result_error_with_message(
+        txn.commit(engine.as_ref()),
+        "must add change data files for the changed rows",
+    );
+
+    let added = engine
+        .write_parquet(
+            &ArrowEngineData::new(updated.clone()),
+            &write_context,
+            HashMap::new(),
+            true,
+        )
+        .await?;
+    let mut txn = snapshot.transaction()?.with_operation("UPDATE".to_string());
+    txn.remove_files(Box::new(ArrowEngineData::new(removed)));
+    txn.add_files(added);
+    txn.add_cdc_files(cdc_files_metadata);
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 3, .. }
+    ));
+
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/00000000000000000003.json"
+        )))
+        .await?;
+    let actions: Vec<serde_json::Value> = Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter()
+        .try_collect()?;
+    assert_eq!(actions[0]["commitInfo"]["operation"], json!("UPDATE"));
+    let removes = actions.iter().filter_map(|a| a.get("remove")).collect_vec();
+    assert_eq!(removes.len(), 1);
+    assert_eq!(removes[0]["dataChange"], json!(true));
+    let cdcs = actions.iter().filter_map(|a| a.get("cdc")).collect_vec();
+    assert_eq!(cdcs.len(), 1);
+    assert_eq!(cdcs[0]["dataChange"], json!(false));
+    assert!(cdcs[0]["path"].as_str().unwrap().contains("/_change_data/"));
+
+    // version 4 deletes the second file, which doesn't need change data files
+    let removed = added_files_to_remove(store.as_ref(), table_name, 2).await?;
+    let snapshot = Snapshot::builder_for(table_url.clone()).build(engine.as_ref())?;
+    let mut txn = snapshot.transaction()?;
+    txn.remove_files(Box::new(ArrowEngineData::new(removed)));
+    assert!(matches!(
+        txn.commit(engine.as_ref())?,
+        CommitResult::Committed { version: 4, .. }
+    ));
+
+    test_read(&ArrowEngineData::new(updated), &table_url, engine)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_change_data_files_require_change_data_feed() -> Result<(), Box<dyn std::error::Error>>
+{
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let schema = Arc::new(StructType::try_new(vec![StructField::nullable(
+        "number",
+        DataType::INTEGER,
+    )])?);
+    let (store, engine, table_location) =
+        engine_store_setup("test_change_data_files_require_change_data_feed", None);
+    let table_url = create_table(
+        store,
+        table_location,
+        schema.clone(),
+        &[],
+        false,
+        vec![],
+        vec![],
+    )
+    .await?;
+
+    let change_data = RecordBatch::try_new(
+        Arc::new(delta_kernel::arrow::datatypes::Schema::new(vec![
+            Field::new("number", ArrowDataType::Int32, true),
+            Field::new("_change_type", ArrowDataType::Utf8, false),
+        ])),
+        vec![
+            Arc::new(Int32Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["insert"])),
+        ],
+    )?;
+    let snapshot = Snapshot::builder_for(table_url).build(&engine)?;
+    let mut txn = snapshot.transaction()?;
+    let write_context = txn.get_write_context();
+    assert!(!write_context.is_change_data_feed_enabled());
+    let cdc_files_metadata = engine
+        .write_change_data(
+            &ArrowEngineData::new(change_data),
+            &write_context,
+            HashMap::new(),
+        )
+        .await?;
+    txn.add_cdc_files(cdc_files_metadata);
+    assert_result_error_with_message(txn.commit(&engine), "without the change data feed enabled");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_append_stats_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = tracing_subscriber::fmt::try_init();
+
+    let nested_fields = vec![
+        StructField::nullable("x", DataType::LONG),
+        StructField::nullable("y", DataType::STRING),
+    ];
+    let schema = Arc::new(StructType::try_new(vec![
+        StructField::nullable("a", DataType::INTEGER),
+        StructField::nullable("b", DataType::STRING),
+        StructField::nullable("s", StructType::try_new(nested_fields)?),
+    ])?);
+    let arrow_schema: Arc<ArrowSchema> = Arc::new(schema.as_ref().try_into_arrow()?);
+    let ArrowDataType::Struct(nested_arrow_fields) = arrow_schema.field(2).data_type().clone()
+    else {
+        panic!("s must be a struct");
+    };
+    let data = RecordBatch::try_new(
+        arrow_schema,
+        vec![
+            Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])),
+            Arc::new(StringArray::from(vec!["b", "a", "c"])),
+            Arc::new(StructArray::try_new(
+                nested_arrow_fields,
+                vec![
+                    Arc::new(Int64Array::from(vec![10, 20, 30])),
+                    Arc::new(StringArray::from(vec![Some("z"), None, Some("y")])),
+                ],
+                None,
+            )?),
+        ],
+    )?;
+
+    // each case configures the stats columns of the table and expects the stats of the file
+    let cases = [
+        (
+            ("delta.dataSkippingNumIndexedCols", "2"),
+            json!({
+                "numRecords": 3,
+                "nullCount": {"a": 1, "b": 0},
+                "minValues": {"a": 1, "b": "a"},
+                "maxValues": {"a": 3, "b": "c"}
+            }),
+        ),
+        (
+            ("delta.dataSkippingStatsColumns", "s.y,a"),
+            json!({
+                "numRecords": 3,
+                "nullCount": {"a": 1, "s": {"y": 1}},
+                "minValues": {"a": 1, "s": {"y": "y"}},
+                "maxValues": {"a": 3, "s": {"y": "z"}}
+            }),
+        ),
+        (
+            ("delta.dataSkippingNumIndexedCols", "0"),
+            json!({"numRecords": 3}),
+        ),
+    ];
+    for ((key, value), expected_stats) in cases {
+        let (store, engine, table_location) = engine_store_setup("test_table_stats_columns", None);
+        let table_url = create_table(
+            store.clone(),
+            table_location,
+            schema.clone(),
+            &[],
+            true,
+            vec![],
+            vec![],
+        )
+        .await?;
+        // add the stats columns property to the metadata of the table
+        let commit0 = Path::from("test_table_stats_columns/_delta_log/00000000000000000000.json");
+        let actions: Vec<serde_json::Value> =
+            Deserializer::from_slice(&store.get(&commit0).await?.bytes().await?)
+                .into_iter()
+                .try_collect()?;
+        let actions = actions.into_iter().map(|mut action| {
+            if let Some(configuration) = action.pointer_mut("/metaData/configuration") {
+                configuration[key] = json!(value);
+            }
+            action.to_string()
+        });
+        store
+            .put(&commit0, actions.collect_vec().join("\n").into())
+            .await?;
+
+        let snapshot = Snapshot::builder_for(table_url.clone()).build(&engine)?;
+        let mut txn = snapshot.transaction()?;
+        let add_files_metadata = engine
+            .write_parquet(
+                &ArrowEngineData::new(data.clone()),
+                &txn.get_write_context(),
+                HashMap::new(),
+                true,
+            )
+            .await?;
+        txn.add_files(add_files_metadata);
+        txn.commit(&engine)?;
+
+        let commit1 = Path::from("test_table_stats_columns/_delta_log/00000000000000000001.json");
+        let actions: Vec<serde_json::Value> =
+            Deserializer::from_slice(&store.get(&commit1).await?.bytes().await?)
+                .into_iter()
+                .try_collect()?;
+        let stats = actions[1]["add"]["stats"].as_str().unwrap();
+        let stats: serde_json::Value = serde_json::from_str(stats)?;
+        assert_eq!(stats, expected_stats, "{key}={value}");
+    }
+    Ok(())
+}
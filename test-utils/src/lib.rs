@@ -8,7 +8,9 @@ use delta_kernel::arrow::array::{
 
 use delta_kernel::arrow::compute::filter_record_batch;
 use delta_kernel::arrow::error::ArrowError;
+use delta_kernel::arrow::json::ReaderBuilder;
 use delta_kernel::arrow::util::pretty::pretty_format_batches;
+use delta_kernel::engine::arrow_conversion::TryIntoArrow as _;
 use delta_kernel::engine::arrow_data::ArrowEngineData;
 use delta_kernel::engine::default::executor::tokio::TokioBackgroundExecutor;
 use delta_kernel::engine::default::executor::TaskExecutor;
@@ -17,7 +19,7 @@ use delta_kernel::parquet::arrow::arrow_writer::ArrowWriter;
 use delta_kernel::parquet::file::properties::WriterProperties;
 use delta_kernel::scan::Scan;
 use delta_kernel::schema::SchemaRef;
-use delta_kernel::{DeltaResult, Engine, EngineData, Snapshot};
+use delta_kernel::{DeltaResult, Engine, EngineData, Snapshot, Version};
 
 use itertools::Itertools;
 use object_store::local::LocalFileSystem;
@@ -189,9 +191,7 @@ pub async fn add_staged_commit(
 /// Try to convert an `EngineData` into a `RecordBatch`. Panics if not using `ArrowEngineData` from
 /// the default module
 pub fn into_record_batch(engine_data: Box<dyn EngineData>) -> RecordBatch {
-    ArrowEngineData::try_from_engine_data(engine_data)
-        .unwrap()
-        .into()
+    ArrowEngineData::try_into_record_batch(engine_data).unwrap()
 }
 
 /// Simple extension trait with helpful methods (just constuctor for now) for creating/using
@@ -281,13 +281,16 @@ pub async fn create_table(
         if reader_features.contains(&"columnMapping") {
             config.insert("delta.columnMapping.mode".to_string(), json!("name"));
         }
+        if writer_features.contains(&"changeDataFeed") {
+            config.insert("delta.enableChangeDataFeed".to_string(), json!("true"));
+        }
         if writer_features.contains(&"rowTracking") {
             config.insert(
-                "delta.materializedRowIdColumnName".to_string(),
+                "delta.rowTracking.materializedRowIdColumnName".to_string(),
                 json!("some_dummy_column_name"),
             );
             config.insert(
-                "delta.materializedRowCommitVersionColumnName".to_string(),
+                "delta.rowTracking.materializedRowCommitVersionColumnName".to_string(),
                 json!("another_dummy_column_name"),
             );
         }
@@ -443,6 +446,41 @@ pub fn set_json_value(
     Ok(())
 }
 
+/// The files added by the given commit of the table `table_name` in `store` (as set up by
+/// [`engine_store_setup`]), in the form expected by `Transaction::remove_files`.
+pub async fn added_files_to_remove(
+    store: &dyn ObjectStore,
+    table_name: &str,
+    version: Version,
+) -> Result<RecordBatch, Box<dyn std::error::Error>> {
+    let commit = store
+        .get(&Path::from(format!(
+            "/{table_name}/_delta_log/{version:020}.json"
+        )))
+        .await?;
+    let removed: String = serde_json::Deserializer::from_slice(&commit.bytes().await?)
+        .into_iter::<serde_json::Value>()
+        .filter_map_ok(|action| action.get("add").cloned())
+        .map_ok(|add| {
+            json!({
+                "path": add["path"],
+                "partitionValues": add["partitionValues"],
+                "size": add["size"],
+            })
+            .to_string()
+        })
+        .try_collect::<_, Vec<_>, _>()?
+        .join("\n");
+    Ok(ReaderBuilder::new(Arc::new(
+        delta_kernel::transaction::remove_files_schema()
+            .as_ref()
+            .try_into_arrow()?,
+    ))
+    .build(removed.as_bytes())?
+    .next()
+    .ok_or("no files were added")??)
+}
+
 pub fn assert_result_error_with_message<T, E: ToString>(res: Result<T, E>, message: &str) {
     match res {
         Ok(_) => panic!("Expected error, but got Ok result"),